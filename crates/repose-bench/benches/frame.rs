@@ -0,0 +1,51 @@
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use repose_bench::scenarios;
+use repose_core::{RetainedStateStore, Scheduler};
+use repose_ui::{Interactions, layout_and_paint};
+
+const SIZE: (u32, u32) = (1280, 800);
+
+fn build(c: &mut Criterion) {
+    let mut g = c.benchmark_group("build");
+    for s in scenarios() {
+        g.bench_function(s.name, |b| b.iter(|| black_box((s.build)())));
+    }
+    g.finish();
+}
+
+fn layout(c: &mut Criterion) {
+    let mut g = c.benchmark_group("layout_paint");
+    g.sample_size(20);
+    for s in scenarios() {
+        let view = (s.build)();
+        g.bench_function(s.name, |b| {
+            b.iter(|| {
+                black_box(layout_and_paint(
+                    &view,
+                    SIZE,
+                    &RetainedStateStore::new(),
+                    &Interactions::default(),
+                    None,
+                ))
+            })
+        });
+    }
+    g.finish();
+}
+
+fn frame(c: &mut Criterion) {
+    let mut g = c.benchmark_group("frame");
+    g.sample_size(20);
+    for s in scenarios() {
+        let mut sched = Scheduler::new();
+        g.bench_function(s.name, |b| {
+            b.iter(|| black_box(repose_bench::run_frame(&mut sched, s.build, SIZE)))
+        });
+    }
+    g.finish();
+}
+
+criterion_group!(benches, build, layout, frame);
+criterion_main!(benches);
@@ -0,0 +1,89 @@
+//! Runs every scenario headlessly and reports median timings.
+//!
+//! ```text
+//! cargo run --release -p repose-bench --bin repose_bench -- [--iters N] [--out bench.csv]
+//! ```
+//!
+//! With `--out`, one CSV row per scenario is appended (`commit,scenario,build_ms,
+//! layout_paint_ms,scene_nodes`), keyed by `git rev-parse --short HEAD` so runs can be
+//! compared across commits.
+
+use std::io::Write;
+use std::process::Command;
+use std::time::Duration;
+
+use repose_bench::{FrameTimings, run_frame, scenarios};
+use repose_core::Scheduler;
+
+fn median(mut v: Vec<Duration>) -> Duration {
+    v.sort();
+    v.get(v.len() / 2).copied().unwrap_or_default()
+}
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+fn main() -> std::io::Result<()> {
+    let mut iters = 10usize;
+    let mut out: Option<String> = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(a) = args.next() {
+        match a.as_str() {
+            "--iters" => iters = args.next().and_then(|s| s.parse().ok()).unwrap_or(iters),
+            "--out" => out = args.next(),
+            _ => eprintln!("ignoring unknown argument {a:?}"),
+        }
+    }
+
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".into());
+
+    let mut rows = Vec::new();
+    println!(
+        "{:<20} {:>10} {:>16} {:>12}",
+        "scenario", "build ms", "layout+paint ms", "scene nodes"
+    );
+    for s in scenarios() {
+        let mut sched = Scheduler::new();
+        // Warm-up: fills text/shaping caches and remembered state.
+        run_frame(&mut sched, s.build, (1280, 800));
+
+        let runs: Vec<FrameTimings> = (0..iters.max(1))
+            .map(|_| run_frame(&mut sched, s.build, (1280, 800)))
+            .collect();
+        let build = median(runs.iter().map(|r| r.build).collect());
+        let layout = median(runs.iter().map(|r| r.layout_paint).collect());
+        let nodes = runs.last().map(|r| r.scene_nodes).unwrap_or(0);
+
+        println!(
+            "{:<20} {:>10.2} {:>16.2} {:>12}",
+            s.name,
+            ms(build),
+            ms(layout),
+            nodes
+        );
+        rows.push(format!(
+            "{commit},{},{:.3},{:.3},{nodes}",
+            s.name,
+            ms(build),
+            ms(layout)
+        ));
+    }
+
+    if let Some(path) = out {
+        let mut f = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        for r in rows {
+            writeln!(f, "{r}")?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,132 @@
+//! Headless benchmarks for frame build and layout.
+//!
+//! Each [`Scenario`] composes a synthetic tree and runs it through
+//! `repose_ui::layout_and_paint` without a window or GPU. The criterion benches in
+//! `benches/frame.rs` use these for statistically sound comparisons; the `repose_bench`
+//! binary prints (and optionally appends) per-commit timings so regressions show up
+//! in a plain text log.
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use repose_core::*;
+use repose_ui::lazy::{LazyColumn, LazyColumnState};
+use repose_ui::*;
+
+/// A named synthetic tree.
+#[derive(Clone, Copy)]
+pub struct Scenario {
+    pub name: &'static str,
+    pub build: fn() -> View,
+}
+
+/// `n` short text nodes in a wrapping grid.
+pub fn text_nodes(n: usize) -> View {
+    Column(Modifier::new().fill_max_size().grid(20, 2.0, 2.0)).with_children(
+        (0..n)
+            .map(|i| Text(format!("Item {i}")).size(12.0))
+            .collect(),
+    )
+}
+
+/// A chain of `depth` nested padded boxes with a text leaf.
+pub fn deep_nesting(depth: usize) -> View {
+    let mut v = Text("leaf");
+    for i in 0..depth {
+        let m = Modifier::new().key(i as u64).padding(1.0);
+        v = if i % 2 == 0 { Row(m) } else { Column(m) }.child(v);
+    }
+    v
+}
+
+/// A `LazyColumn` over `n` items; only the visible window is materialised.
+pub fn lazy_list(n: usize) -> View {
+    let state = remember_with_key("bench:lazy", LazyColumnState::new);
+    LazyColumn(
+        (0..n).collect::<Vec<_>>(),
+        24.0,
+        state,
+        Modifier::new().fill_max_size(),
+        |i, _| Text(format!("Row {i}")),
+    )
+}
+
+/// Mixed widget rows (button, checkbox, slider, text) repeated `n` times.
+pub fn widget_rows(n: usize) -> View {
+    Column(Modifier::new().fill_max_size()).with_children(
+        (0..n)
+            .map(|i| {
+                Row(Modifier::new().key(i as u64)).child((
+                    Button(Text("Go"), || {}),
+                    Checkbox(i % 2 == 0, |_| {}),
+                    Slider(0.5, (0.0, 1.0), None, |_| {}),
+                    Text(format!("Row {i}")),
+                ))
+            })
+            .collect(),
+    )
+}
+
+/// The standard scenario set used by both the criterion benches and `repose_bench`.
+pub fn scenarios() -> Vec<Scenario> {
+    vec![
+        Scenario {
+            name: "text_10k",
+            build: || text_nodes(10_000),
+        },
+        Scenario {
+            name: "deep_nesting_200",
+            build: || deep_nesting(200),
+        },
+        Scenario {
+            name: "lazy_list_1m",
+            build: || lazy_list(1_000_000),
+        },
+        Scenario {
+            name: "widget_rows_500",
+            build: || widget_rows(500),
+        },
+    ]
+}
+
+/// Wall-clock time spent in each phase of one headless frame.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameTimings {
+    pub build: Duration,
+    pub layout_paint: Duration,
+    pub scene_nodes: usize,
+}
+
+/// Composes `build` and lays it out at `size` px, returning per-phase timings.
+pub fn run_frame(sched: &mut Scheduler, build: fn() -> View, size: (u32, u32)) -> FrameTimings {
+    let build_t = Cell::new(Duration::ZERO);
+    let layout_t = Cell::new(Duration::ZERO);
+    sched.size = size;
+
+    let frame = sched.repose(
+        |_s| {
+            let t0 = Instant::now();
+            let v = build();
+            build_t.set(t0.elapsed());
+            v
+        },
+        |view, size| {
+            let t0 = Instant::now();
+            let out = layout_and_paint(
+                view,
+                size,
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            );
+            layout_t.set(t0.elapsed());
+            out
+        },
+    );
+
+    FrameTimings {
+        build: build_t.get(),
+        layout_paint: layout_t.get(),
+        scene_nodes: frame.scene.nodes.len(),
+    }
+}
@@ -28,9 +28,32 @@ pub enum DrawCommand {
         color: Color,
         size: f32,
     },
+    Line {
+        from: Vec2,
+        to: Vec2,
+        width: f32,
+        color: Color,
+    },
+    Arc {
+        center: Vec2,
+        radius: f32,
+        inner_radius: f32,
+        start: f32,
+        sweep: f32,
+        color: Color,
+    },
 }
 
 impl DrawScope {
+    /// Records into an empty scope of the given size; pair with [`DrawScope::paint`] to
+    /// draw from a custom painter.
+    pub fn new(size: Size) -> Self {
+        Self {
+            commands: Vec::new(),
+            size,
+        }
+    }
+
     pub fn draw_rect(&mut self, rect: Rect, color: Color, radius: f32) {
         self.commands.push(DrawCommand::Rect {
             rect,
@@ -78,6 +101,41 @@ impl DrawScope {
     pub fn draw_circle_stroke(&mut self, center: Vec2, radius: f32, color: Color, width: f32) {
         self.draw_ellipse_stroke(center, radius, radius, color, width);
     }
+    /// A straight stroke with round caps.
+    pub fn draw_line(&mut self, from: Vec2, to: Vec2, color: Color, width: f32) {
+        self.commands.push(DrawCommand::Line {
+            from,
+            to,
+            width: width.max(0.0),
+            color,
+        });
+    }
+    /// Joins consecutive points with [`DrawScope::draw_line`]; the round caps fill the joins.
+    pub fn draw_polyline(&mut self, points: &[Vec2], color: Color, width: f32) {
+        for w in points.windows(2) {
+            self.draw_line(w[0], w[1], color, width);
+        }
+    }
+    /// A pie slice, or a ring segment when `inner_radius > 0`. Angles are in radians,
+    /// clockwise from the +x axis; `sweep` may be negative.
+    pub fn draw_arc(
+        &mut self,
+        center: Vec2,
+        radius: f32,
+        inner_radius: f32,
+        start: f32,
+        sweep: f32,
+        color: Color,
+    ) {
+        self.commands.push(DrawCommand::Arc {
+            center,
+            radius: radius.max(0.0),
+            inner_radius: inner_radius.clamp(0.0, radius.max(0.0)),
+            start,
+            sweep,
+            color,
+        });
+    }
     pub fn draw_text(&mut self, text: impl Into<String>, pos: Vec2, color: Color, size: f32) {
         self.commands.push(DrawCommand::Text {
             text: text.into(),
@@ -86,20 +144,9 @@ impl DrawScope {
             size,
         });
     }
-}
-
-pub fn Canvas(modifier: Modifier, on_draw: impl Fn(&mut DrawScope) + 'static) -> View {
-    // Painter replays drawing each frame, so Canvas can react to signals/animation.
-    let painter = move |scene: &mut Scene, rect: Rect| {
-        let mut scope = DrawScope {
-            commands: Vec::new(),
-            size: Size {
-                width: rect.w.max(0.0),
-                height: rect.h.max(0.0),
-            },
-        };
-        on_draw(&mut scope);
 
+    /// Emits the recorded commands as scene nodes, offset to `rect`'s origin.
+    pub fn paint(&self, scene: &mut Scene, rect: Rect) {
         // local->global helper
         let to_global = |r: Rect| Rect {
             x: rect.x + r.x,
@@ -108,7 +155,7 @@ pub fn Canvas(modifier: Modifier, on_draw: impl Fn(&mut DrawScope) + 'static) ->
             h: r.h,
         };
 
-        for cmd in &scope.commands {
+        for cmd in &self.commands {
             match cmd {
                 DrawCommand::Rect {
                     rect: r,
@@ -168,13 +215,66 @@ pub fn Canvas(modifier: Modifier, on_draw: impl Fn(&mut DrawScope) + 'static) ->
                             w: 0.0,
                             h: *size,
                         },
-                        text: text.clone(),
+                        text: text.as_str().into(),
                         color: *color,
                         size: *size,
+                        letter_spacing: 0.0,
+                        weight: FontWeight::NORMAL,
+                    });
+                }
+                DrawCommand::Line {
+                    from,
+                    to,
+                    width,
+                    color,
+                } => {
+                    scene.nodes.push(SceneNode::Line {
+                        from: Vec2 {
+                            x: rect.x + from.x,
+                            y: rect.y + from.y,
+                        },
+                        to: Vec2 {
+                            x: rect.x + to.x,
+                            y: rect.y + to.y,
+                        },
+                        width: *width,
+                        color: *color,
+                    });
+                }
+                DrawCommand::Arc {
+                    center,
+                    radius,
+                    inner_radius,
+                    start,
+                    sweep,
+                    color,
+                } => {
+                    scene.nodes.push(SceneNode::Arc {
+                        center: Vec2 {
+                            x: rect.x + center.x,
+                            y: rect.y + center.y,
+                        },
+                        radius: *radius,
+                        inner_radius: *inner_radius,
+                        start: *start,
+                        sweep: *sweep,
+                        color: *color,
                     });
                 }
             }
         }
+    }
+}
+
+pub fn Canvas(modifier: Modifier, on_draw: impl Fn(&mut DrawScope) + 'static) -> View {
+    // Painter replays drawing each frame, so Canvas can react to signals/animation.
+    let painter = move |scene: &mut Scene, rect: Rect| {
+        let mut scope = DrawScope::new(Size {
+            width: rect.w.max(0.0),
+            height: rect.h.max(0.0),
+        });
+        on_draw(&mut scope);
+        scope.paint(scene, rect);
     };
 
     // Respect caller sizing. Only apply a default if they didn't specify any size behavior.
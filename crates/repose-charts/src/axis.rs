@@ -0,0 +1,97 @@
+//! Value-axis ticks.
+
+/// Evenly spaced tick values on round numbers, from `min` to `max` inclusive.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ticks {
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+}
+
+impl Ticks {
+    pub fn values(&self) -> impl Iterator<Item = f32> + use<> {
+        let Ticks { min, max, step } = *self;
+        let n = ((max - min) / step).round() as usize;
+        (0..=n).map(move |i| min + i as f32 * step)
+    }
+
+    /// Where `v` falls on the axis, 0.0 at `min` and 1.0 at `max`.
+    pub fn fraction(&self, v: f32) -> f32 {
+        if self.max > self.min {
+            (v - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        }
+    }
+
+    /// `v` with as many decimals as the step needs.
+    pub fn label(&self, v: f32) -> String {
+        let decimals = (-self.step.log10().floor()).max(0.0) as usize;
+        // `+ 0.0` turns -0.0 into 0.0, so the zero tick doesn't print as "-0".
+        format!("{:.*}", decimals, v + 0.0)
+    }
+}
+
+/// Ticks covering `min..=max` with about `count` intervals, each 1, 2 or 5 times a power
+/// of ten.
+pub fn nice_ticks(min: f32, max: f32, count: usize) -> Ticks {
+    if !min.is_finite() || !max.is_finite() {
+        return Ticks {
+            min: 0.0,
+            max: 1.0,
+            step: 1.0,
+        };
+    }
+    let (mut lo, mut hi) = (min.min(max), min.max(max));
+    if hi - lo <= f32::EPSILON * hi.abs().max(1.0) {
+        let pad = if lo == 0.0 { 1.0 } else { lo.abs() * 0.5 };
+        lo -= pad;
+        hi += pad;
+    }
+
+    let raw = (hi - lo) / count.max(1) as f32;
+    let exp = 10f32.powi(raw.log10().floor() as i32);
+    let step = match raw / exp {
+        f if f <= 1.0 => 1.0,
+        f if f <= 2.0 => 2.0,
+        f if f <= 5.0 => 5.0,
+        _ => 10.0,
+    } * exp;
+
+    Ticks {
+        min: (lo / step).floor() * step,
+        max: (hi / step).ceil() * step,
+        step,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(t: Ticks) -> Vec<String> {
+        t.values().map(|v| t.label(v)).collect()
+    }
+
+    #[test]
+    fn ticks_land_on_round_numbers_covering_the_data() {
+        let t = nice_ticks(0.0, 87.0, 5);
+        assert_eq!(t.step, 20.0);
+        assert_eq!(labels(t), ["0", "20", "40", "60", "80", "100"]);
+
+        let t = nice_ticks(-3.2, 4.9, 4);
+        assert_eq!(labels(t), ["-5", "0", "5"]);
+
+        let t = nice_ticks(0.0, 0.9, 4);
+        assert_eq!(labels(t), ["0.0", "0.5", "1.0"]);
+        assert!((t.fraction(0.25) - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn flat_data_still_gets_a_range() {
+        let t = nice_ticks(0.0, 0.0, 5);
+        assert!(t.min <= -1.0 && t.max >= 1.0);
+        let t = nice_ticks(40.0, 40.0, 4);
+        assert!(t.min < 40.0 && t.max > 40.0);
+    }
+}
@@ -0,0 +1,295 @@
+//! Line and bar charts: one slot per label along x, values up y.
+
+use std::cell::Cell;
+
+use repose_canvas::DrawScope;
+use repose_core::*;
+use repose_ui::Box;
+
+use crate::{
+    CHART_STYLE, Series, animated_values, draw_tooltip, format_value, nice_ticks, text_width,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Line,
+    Bar,
+}
+
+/// For `n` points spread edge to edge across `width` (as in a line chart), the one
+/// nearest `x`.
+pub fn point_index_at(x: f32, width: f32, n: usize) -> Option<usize> {
+    if n == 0 || !(0.0..=width).contains(&x) {
+        return None;
+    }
+    if n == 1 {
+        return Some(0);
+    }
+    let step = width / (n - 1) as f32;
+    Some(((x / step).round() as usize).min(n - 1))
+}
+
+/// For `n` equal slots across `width` (as in a bar chart), the one containing `x`.
+pub fn slot_index_at(x: f32, width: f32, n: usize) -> Option<usize> {
+    if n == 0 || !(0.0..width).contains(&x) {
+        return None;
+    }
+    Some(((x * n as f32 / width) as usize).min(n - 1))
+}
+
+/// Each series as a line through its values, one point per label.
+pub fn LineChart(modifier: Modifier, labels: Vec<String>, series: Vec<Series>) -> View {
+    chart(Kind::Line, modifier, labels, series)
+}
+
+/// Each series as a bar per label, the series side by side within a label's slot.
+pub fn BarChart(modifier: Modifier, labels: Vec<String>, series: Vec<Series>) -> View {
+    chart(Kind::Bar, modifier, labels, series)
+}
+
+fn chart(kind: Kind, modifier: Modifier, labels: Vec<String>, series: Vec<Series>) -> View {
+    let style = CHART_STYLE.current();
+    let n = series
+        .iter()
+        .map(|s| s.values.len())
+        .max()
+        .unwrap_or(0)
+        .max(labels.len());
+    let target: Vec<f32> = series
+        .iter()
+        .flat_map(|s| (0..n).map(|i| s.values.get(i).copied().unwrap_or(0.0)))
+        .collect();
+    let shown = animated_values(&target, style.animation);
+
+    // The value axis always includes zero, so bars have a baseline to grow from.
+    let (lo, hi) = target
+        .iter()
+        .fold((0.0f32, 0.0f32), |(lo, hi), v| (lo.min(*v), hi.max(*v)));
+    let ticks = nice_ticks(lo, hi, style.tick_count);
+
+    let hovered = remember(|| Cell::new(None::<usize>));
+    // The plot area as last painted, in px, for hit testing.
+    let plot = remember(|| Cell::new(Rect::default()));
+
+    let dp = locals::dp_to_px(1.0);
+    let font_px = locals::dp_to_px(style.font_dp) * locals::text_scale().0;
+    let line_w = style.line_width_dp * dp;
+    let colors: Vec<Color> = series
+        .iter()
+        .enumerate()
+        .map(|(i, s)| s.color.unwrap_or_else(|| style.color(i)))
+        .collect();
+
+    let hover = hovered.clone();
+    let plot_out = plot.clone();
+    let painter = move |scene: &mut Scene, rect: Rect| {
+        let mut ds = DrawScope::new(Size {
+            width: rect.w.max(0.0),
+            height: rect.h.max(0.0),
+        });
+        let gap = 8.0 * dp;
+
+        let tick_labels: Vec<(f32, String)> = ticks.values().map(|v| (v, ticks.label(v))).collect();
+        let y_label_w = tick_labels
+            .iter()
+            .map(|(_, s)| text_width(s, font_px))
+            .fold(0.0, f32::max);
+        // A line chart's last point sits on the right edge, with its label centred on it.
+        let right = match (kind, labels.last()) {
+            (Kind::Line, Some(s)) => (text_width(s, font_px) / 2.0).max(gap),
+            _ => gap,
+        };
+        let p = Rect {
+            x: y_label_w + gap,
+            y: font_px / 2.0,
+            w: (rect.w - y_label_w - gap - right).max(0.0),
+            h: (rect.h - font_px * 1.5 - gap).max(0.0),
+        };
+        plot_out.set(Rect {
+            x: rect.x + p.x,
+            y: rect.y + p.y,
+            ..p
+        });
+        let y_of = |v: f32| p.y + p.h * (1.0 - ticks.fraction(v));
+
+        for (v, s) in &tick_labels {
+            let y = y_of(*v);
+            let c = if *v == 0.0 { style.axis } else { style.grid };
+            ds.draw_line(Vec2 { x: p.x, y }, Vec2 { x: p.x + p.w, y }, c, 1.0);
+            ds.draw_text(
+                s.clone(),
+                Vec2 {
+                    x: p.x - gap - text_width(s, font_px),
+                    y: y - font_px / 2.0,
+                },
+                style.label,
+                font_px,
+            );
+        }
+
+        let slot = if n > 0 { p.w / n as f32 } else { 0.0 };
+        let x_of = |i: usize| match kind {
+            Kind::Line if n > 1 => p.x + p.w * i as f32 / (n - 1) as f32,
+            Kind::Line => p.x + p.w / 2.0,
+            Kind::Bar => p.x + slot * (i as f32 + 0.5),
+        };
+
+        // Label every `stride`th category so the labels don't overlap.
+        let spacing = match kind {
+            Kind::Line if n > 1 => p.w / (n - 1) as f32,
+            _ => slot,
+        };
+        let widest = labels
+            .iter()
+            .map(|s| text_width(s, font_px))
+            .fold(0.0, f32::max);
+        let stride = if spacing > 0.0 {
+            ((widest + gap) / spacing).ceil().max(1.0) as usize
+        } else {
+            1
+        };
+        for (i, s) in labels.iter().enumerate().step_by(stride) {
+            ds.draw_text(
+                s.clone(),
+                Vec2 {
+                    x: x_of(i) - text_width(s, font_px) / 2.0,
+                    y: p.y + p.h + gap / 2.0,
+                },
+                style.label,
+                font_px,
+            );
+        }
+
+        let hover = hover.get().filter(|i| *i < n);
+        match (kind, hover) {
+            (Kind::Line, Some(i)) => {
+                let x = x_of(i);
+                ds.draw_line(
+                    Vec2 { x, y: p.y },
+                    Vec2 { x, y: p.y + p.h },
+                    style.axis,
+                    1.0,
+                );
+            }
+            (Kind::Bar, Some(i)) => ds.draw_rect(
+                Rect {
+                    x: p.x + slot * i as f32,
+                    y: p.y,
+                    w: slot,
+                    h: p.h,
+                },
+                style.grid,
+                0.0,
+            ),
+            _ => {}
+        }
+
+        let baseline = y_of(0.0);
+        let group = slot * 0.7;
+        let bar_w = group / colors.len().max(1) as f32;
+        for (si, color) in colors.iter().enumerate() {
+            let values = &shown[si * n..(si + 1) * n];
+            match kind {
+                Kind::Line => {
+                    let points: Vec<Vec2> = values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| Vec2 {
+                            x: x_of(i),
+                            y: y_of(*v),
+                        })
+                        .collect();
+                    ds.draw_polyline(&points, *color, line_w);
+                    if n == 1 {
+                        ds.draw_circle(points[0], line_w, *color);
+                    }
+                    if let Some(i) = hover {
+                        ds.draw_circle(points[i], line_w * 2.0, *color);
+                    }
+                }
+                Kind::Bar => {
+                    for (i, v) in values.iter().enumerate() {
+                        let y = y_of(*v);
+                        ds.draw_rect(
+                            Rect {
+                                x: p.x + slot * i as f32 + (slot - group) / 2.0 + bar_w * si as f32,
+                                y: y.min(baseline),
+                                w: bar_w,
+                                h: (y - baseline).abs(),
+                            },
+                            *color,
+                            0.0,
+                        );
+                    }
+                }
+            }
+        }
+
+        if let Some(i) = hover {
+            let rows: Vec<(Color, String)> = series
+                .iter()
+                .zip(&colors)
+                .map(|(s, c)| {
+                    let v = s.values.get(i).copied().unwrap_or(0.0);
+                    (*c, format!("{}: {}", s.label, format_value(v)))
+                })
+                .collect();
+            let top = (0..series.len())
+                .map(|si| target[si * n + i])
+                .fold(ticks.min, f32::max);
+            let anchor = Vec2 {
+                x: x_of(i) + if kind == Kind::Bar { group / 2.0 } else { 0.0 },
+                y: y_of(top),
+            };
+            let title = labels.get(i).map(String::as_str).unwrap_or("");
+            draw_tooltip(&mut ds, anchor, title, &rows, &style, font_px, dp);
+        }
+
+        ds.paint(scene, rect);
+    };
+
+    let on_move = {
+        let hovered = hovered.clone();
+        move |e: input::PointerEvent| {
+            let p = plot.get();
+            let x = e.position.x - p.x;
+            hovered.set(match kind {
+                Kind::Line => point_index_at(x, p.w, n),
+                Kind::Bar => slot_index_at(x, p.w, n),
+            });
+        }
+    };
+
+    let mut m = modifier
+        .painter(painter)
+        .on_pointer_move(on_move)
+        .on_pointer_leave(move |_| hovered.set(None));
+    if m.size.is_none() && m.height.is_none() && !m.fill_max && !m.fill_max_h {
+        m = m.height(200.0);
+    }
+    if m.size.is_none() && m.width.is_none() && !m.fill_max && !m.fill_max_w {
+        m = m.fill_max_width();
+    }
+    Box(m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hover_picks_the_nearest_point_or_the_slot_under_the_pointer() {
+        // Points at 0, 50, 100.
+        assert_eq!(point_index_at(20.0, 100.0, 3), Some(0));
+        assert_eq!(point_index_at(30.0, 100.0, 3), Some(1));
+        assert_eq!(point_index_at(100.0, 100.0, 3), Some(2));
+        assert_eq!(point_index_at(-1.0, 100.0, 3), None);
+        assert_eq!(point_index_at(70.0, 100.0, 1), Some(0));
+
+        // Slots [0, 25), [25, 50), ...
+        assert_eq!(slot_index_at(24.9, 100.0, 4), Some(0));
+        assert_eq!(slot_index_at(25.0, 100.0, 4), Some(1));
+        assert_eq!(slot_index_at(100.0, 100.0, 4), None);
+        assert_eq!(slot_index_at(10.0, 100.0, 0), None);
+    }
+}
@@ -0,0 +1,331 @@
+#![allow(non_snake_case)]
+//! # Charts
+//!
+//! [`LineChart`], [`BarChart`] and [`PieChart`] draw with `repose-canvas`, so they scale
+//! with the view and need no images. Each takes a modifier for its size (a default one is
+//! applied otherwise) and the data to show:
+//!
+//! ```rust,no_run
+//! use repose_charts::{LineChart, Series};
+//! use repose_core::Modifier;
+//!
+//! let chart = LineChart(
+//!     Modifier::new().fill_max_width().height(200.0),
+//!     vec!["Mon".into(), "Tue".into(), "Wed".into()],
+//!     vec![Series::new("Visits", vec![12.0, 30.0, 21.0])],
+//! );
+//! ```
+//!
+//! When the data changes, values move from where they were to the new ones (and grow from
+//! zero the first time). Hovering a category or slice shows its values in a tooltip.
+//!
+//! Colours, fonts and the transition come from [`ChartStyle`], read from
+//! [`CHART_STYLE`]; its default follows the current theme.
+
+pub mod axis;
+mod cartesian;
+mod pie;
+
+use std::cell::RefCell;
+
+use repose_canvas::DrawScope;
+use repose_core::animation::{AnimatedValue, AnimationSpec};
+use repose_core::*;
+
+pub use axis::{Ticks, nice_ticks};
+pub use cartesian::{BarChart, LineChart, point_index_at, slot_index_at};
+pub use pie::{PieChart, slice_at};
+
+/// A named row of values, one per category.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Series {
+    pub label: String,
+    pub values: Vec<f32>,
+    /// Falls back to the style's palette.
+    pub color: Option<Color>,
+}
+
+impl Series {
+    pub fn new(label: impl Into<String>, values: Vec<f32>) -> Self {
+        Self {
+            label: label.into(),
+            values,
+            color: None,
+        }
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+/// One wedge of a [`PieChart`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Slice {
+    pub label: String,
+    pub value: f32,
+    /// Falls back to the style's palette.
+    pub color: Option<Color>,
+}
+
+impl Slice {
+    pub fn new(label: impl Into<String>, value: f32) -> Self {
+        Self {
+            label: label.into(),
+            value,
+            color: None,
+        }
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ChartStyle {
+    /// Colours for series and slices without their own, used in order and then repeated.
+    pub palette: Vec<Color>,
+    pub axis: Color,
+    pub grid: Color,
+    pub label: Color,
+    pub tooltip_background: Color,
+    pub tooltip_text: Color,
+    pub font_dp: f32,
+    pub line_width_dp: f32,
+    /// About how many intervals the value axis is split into.
+    pub tick_count: usize,
+    /// Inner radius of a [`PieChart`] as a fraction of its radius; above 0 makes a donut.
+    pub hole: f32,
+    /// How values move when the data changes.
+    pub animation: AnimationSpec,
+}
+
+impl Default for ChartStyle {
+    fn default() -> Self {
+        let th = locals::theme();
+        Self {
+            palette: vec![
+                th.primary,
+                Color::from_hex("#F59E0B"),
+                Color::from_hex("#10B981"),
+                Color::from_hex("#EF4444"),
+                Color::from_hex("#8B5CF6"),
+                Color::from_hex("#06B6D4"),
+            ],
+            axis: th.outline,
+            grid: th.outline.with_alpha(60),
+            label: th.on_surface,
+            tooltip_background: th.surface,
+            tooltip_text: th.on_surface,
+            font_dp: 12.0,
+            line_width_dp: 2.0,
+            tick_count: 5,
+            hole: 0.0,
+            animation: th.motion.long,
+        }
+    }
+}
+
+impl ChartStyle {
+    /// The palette colour for the `i`th series or slice.
+    pub fn color(&self, i: usize) -> Color {
+        if self.palette.is_empty() {
+            self.axis
+        } else {
+            self.palette[i % self.palette.len()]
+        }
+    }
+}
+
+/// The style charts in a subtree use. Provide one to restyle them:
+/// `CHART_STYLE.provide(style, || ...)`.
+pub static CHART_STYLE: CompositionLocal<ChartStyle> = composition_local_of(ChartStyle::default);
+
+struct Transition {
+    from: Vec<f32>,
+    to: Vec<f32>,
+    progress: AnimatedValue<f32>,
+}
+
+impl Transition {
+    fn current(&self) -> Vec<f32> {
+        let p = *self.progress.get();
+        self.to
+            .iter()
+            .enumerate()
+            .map(|(i, to)| {
+                let from = self.from.get(i).copied().unwrap_or(0.0);
+                from + (to - from) * p
+            })
+            .collect()
+    }
+}
+
+/// `target` as it should be drawn this frame: on its way from the values shown before the
+/// last change, with frames requested until it lands. Call once per chart, in the same
+/// place every frame.
+pub(crate) fn animated_values(target: &[f32], spec: AnimationSpec) -> Vec<f32> {
+    let tr = remember(|| {
+        RefCell::new(Transition {
+            from: Vec::new(),
+            to: Vec::new(),
+            progress: AnimatedValue::new(1.0, spec),
+        })
+    });
+    let mut t = tr.borrow_mut();
+    let mut running = t.progress.update();
+    if t.to != target {
+        t.from = t.current();
+        t.to = target.to_vec();
+        t.progress = AnimatedValue::new(0.0, spec);
+        t.progress.set_target(1.0);
+        running = true;
+    }
+    if running {
+        with_frame_nanos(|_| {});
+    }
+    t.current()
+}
+
+pub(crate) fn text_width(s: &str, font_px: f32) -> f32 {
+    repose_text::metrics_for_textfield(s, font_px)
+        .positions
+        .last()
+        .copied()
+        .unwrap_or(0.0)
+}
+
+/// A data value for a tooltip: whole numbers without decimals, others with up to two.
+pub(crate) fn format_value(v: f32) -> String {
+    if v.fract() == 0.0 {
+        format!("{v:.0}")
+    } else {
+        let s = format!("{v:.2}");
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+/// Draws a tooltip beside `anchor`, flipped to its left when it would run off the canvas.
+/// Each row gets a colour swatch. `dp` is the px size of a dp, captured while composing.
+pub(crate) fn draw_tooltip(
+    ds: &mut DrawScope,
+    anchor: Vec2,
+    title: &str,
+    rows: &[(Color, String)],
+    style: &ChartStyle,
+    font_px: f32,
+    dp: f32,
+) {
+    let pad = 6.0 * dp;
+    let gap = 8.0 * dp;
+    let line_h = font_px * 1.4;
+    let swatch = font_px * 0.7;
+
+    let text_w = rows
+        .iter()
+        .map(|(_, s)| swatch + pad + text_width(s, font_px))
+        .fold(text_width(title, font_px), f32::max);
+    let w = text_w + 2.0 * pad;
+    let h = line_h * (rows.len() + 1) as f32 + 2.0 * pad;
+
+    let mut x = anchor.x + gap;
+    if x + w > ds.size.width {
+        x = anchor.x - gap - w;
+    }
+    let x = x.max(0.0);
+    let y = (anchor.y - h / 2.0).clamp(0.0, (ds.size.height - h).max(0.0));
+
+    // Outline as a slightly larger rect underneath; `draw_rect_stroke` also fills.
+    let radius = 4.0 * dp;
+    ds.draw_rect(
+        Rect {
+            x: x - 1.0,
+            y: y - 1.0,
+            w: w + 2.0,
+            h: h + 2.0,
+        },
+        style.axis,
+        radius + 1.0,
+    );
+    ds.draw_rect(Rect { x, y, w, h }, style.tooltip_background, radius);
+
+    let text_y = |row: usize| y + pad + line_h * row as f32 + (line_h - font_px) / 2.0;
+    ds.draw_text(
+        title,
+        Vec2 {
+            x: x + pad,
+            y: text_y(0),
+        },
+        style.tooltip_text,
+        font_px,
+    );
+    for (i, (color, s)) in rows.iter().enumerate() {
+        let ty = text_y(i + 1);
+        ds.draw_rect(
+            Rect {
+                x: x + pad,
+                y: ty + (font_px - swatch) / 2.0,
+                w: swatch,
+                h: swatch,
+            },
+            *color,
+            swatch / 4.0,
+        );
+        ds.draw_text(
+            s.clone(),
+            Vec2 {
+                x: x + pad + swatch + pad,
+                y: ty,
+            },
+            style.tooltip_text,
+            font_px,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tooltip_values_drop_needless_decimals() {
+        assert_eq!(format_value(12.0), "12");
+        assert_eq!(format_value(-3.5), "-3.5");
+        assert_eq!(format_value(0.1254), "0.13");
+        assert_eq!(format_value(1.001), "1");
+    }
+
+    #[test]
+    fn new_values_animate_with_frames_requested_until_they_land() {
+        use repose_core::animation::{Easing, TestClock, set_clock, swap_clock};
+        use std::time::{Duration, Instant};
+
+        let t0 = Instant::now();
+        let _clock = swap_clock(Box::new(TestClock { t: t0 }));
+        let spec = AnimationSpec::tween(Duration::from_millis(100), Easing::Linear);
+        let mut sched = Scheduler::new();
+        let mut frame = |ms: u64, target: f32| {
+            set_clock(Box::new(TestClock {
+                t: t0 + Duration::from_millis(ms),
+            }));
+            let mut shown = Vec::new();
+            sched.repose(
+                |_| {
+                    shown = animated_values(&[target], spec);
+                    View::new(0, ViewKind::Box)
+                },
+                |_, _| (Scene::default(), vec![], vec![]),
+            );
+            (shown[0], frame_requested())
+        };
+
+        frame(0, 10.0);
+        frame(1000, 10.0);
+        assert_eq!(frame(1000, 20.0), (10.0, true));
+        assert_eq!(frame(1050, 20.0), (15.0, true));
+        assert_eq!(frame(1100, 20.0), (20.0, false));
+    }
+}
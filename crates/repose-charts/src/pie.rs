@@ -0,0 +1,168 @@
+//! Pie and donut charts.
+
+use std::cell::Cell;
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use repose_canvas::DrawScope;
+use repose_core::*;
+use repose_ui::Box;
+
+use crate::{CHART_STYLE, Slice, animated_values, draw_tooltip, format_value};
+
+/// The slice under `offset` from the centre, for slices given as fractions of the whole
+/// laid out clockwise from 12 o'clock. `None` outside the ring from `inner` to `radius`.
+pub fn slice_at(offset: Vec2, radius: f32, inner: f32, fractions: &[f32]) -> Option<usize> {
+    let d = offset.x.hypot(offset.y);
+    if d > radius || d < inner {
+        return None;
+    }
+    // Turns clockwise from 12 o'clock (y points down).
+    let turn = (offset.x.atan2(-offset.y) / TAU).rem_euclid(1.0);
+    let mut end = 0.0;
+    for (i, f) in fractions.iter().enumerate() {
+        end += f;
+        if turn < end {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// Slices sized by their share of the total; negative values count as zero. The hovered
+/// slice is pulled out a little. Set [`ChartStyle::hole`](crate::ChartStyle::hole) for a
+/// donut.
+pub fn PieChart(modifier: Modifier, slices: Vec<Slice>) -> View {
+    let style = CHART_STYLE.current();
+    let target: Vec<f32> = slices.iter().map(|s| s.value.max(0.0)).collect();
+    let shown = animated_values(&target, style.animation);
+    let fractions = shares(&shown);
+    let percents: Vec<f32> = shares(&target).iter().map(|f| f * 100.0).collect();
+
+    let hovered = remember(|| Cell::new(None::<usize>));
+    // Centre, radius and inner radius as last painted, in px, for hit testing.
+    let geometry = remember(|| Cell::new((Vec2::default(), 0.0f32, 0.0f32)));
+
+    let dp = locals::dp_to_px(1.0);
+    let font_px = locals::dp_to_px(style.font_dp) * locals::text_scale().0;
+    let colors: Vec<Color> = slices
+        .iter()
+        .enumerate()
+        .map(|(i, s)| s.color.unwrap_or_else(|| style.color(i)))
+        .collect();
+
+    let hover = hovered.clone();
+    let geometry_out = geometry.clone();
+    let hit_fractions = fractions.clone();
+    let painter = move |scene: &mut Scene, rect: Rect| {
+        let mut ds = DrawScope::new(Size {
+            width: rect.w.max(0.0),
+            height: rect.h.max(0.0),
+        });
+        let pop = 6.0 * dp;
+        let r = (rect.w.min(rect.h) / 2.0 - pop).max(0.0);
+        let inner = r * style.hole.clamp(0.0, 0.95);
+        let c = Vec2 {
+            x: rect.w / 2.0,
+            y: rect.h / 2.0,
+        };
+        geometry_out.set((
+            Vec2 {
+                x: rect.x + c.x,
+                y: rect.y + c.y,
+            },
+            r,
+            inner,
+        ));
+
+        let hover = hover.get().filter(|i| *i < slices.len());
+        let mut anchor = None;
+        let mut start = -FRAC_PI_2;
+        for (i, f) in fractions.iter().enumerate() {
+            let sweep = f * TAU;
+            let mid = start + sweep / 2.0;
+            let off = if hover == Some(i) { pop } else { 0.0 };
+            let center = Vec2 {
+                x: c.x + mid.cos() * off,
+                y: c.y + mid.sin() * off,
+            };
+            ds.draw_arc(center, r, inner, start, sweep, colors[i]);
+            if hover == Some(i) {
+                let at = (r + inner) / 2.0;
+                anchor = Some(Vec2 {
+                    x: center.x + mid.cos() * at,
+                    y: center.y + mid.sin() * at,
+                });
+            }
+            start += sweep;
+        }
+
+        if let (Some(i), Some(anchor)) = (hover, anchor) {
+            let row = format!("{} ({:.0}%)", format_value(slices[i].value), percents[i]);
+            draw_tooltip(
+                &mut ds,
+                anchor,
+                &slices[i].label,
+                &[(colors[i], row)],
+                &style,
+                font_px,
+                dp,
+            );
+        }
+
+        ds.paint(scene, rect);
+    };
+
+    let on_move = {
+        let hovered = hovered.clone();
+        move |e: input::PointerEvent| {
+            let (c, r, inner) = geometry.get();
+            let offset = Vec2 {
+                x: e.position.x - c.x,
+                y: e.position.y - c.y,
+            };
+            hovered.set(slice_at(offset, r, inner, &hit_fractions));
+        }
+    };
+
+    let mut m = modifier
+        .painter(painter)
+        .on_pointer_move(on_move)
+        .on_pointer_leave(move |_| hovered.set(None));
+    let has_size = m.size.is_some()
+        || m.width.is_some()
+        || m.height.is_some()
+        || m.fill_max
+        || m.fill_max_w
+        || m.fill_max_h;
+    if !has_size {
+        m = m.size(200.0, 200.0);
+    }
+    Box(m)
+}
+
+fn shares(values: &[f32]) -> Vec<f32> {
+    let total: f32 = values.iter().sum();
+    values
+        .iter()
+        .map(|v| if total > 0.0 { v / total } else { 0.0 })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slices_run_clockwise_from_twelve_o_clock() {
+        let fractions = [0.25, 0.75];
+        let at = |x, y| slice_at(Vec2 { x, y }, 10.0, 2.0, &fractions);
+        // Upper right quarter is the first slice, everything else the second.
+        assert_eq!(at(3.0, -3.0), Some(0));
+        assert_eq!(at(3.0, 3.0), Some(1));
+        assert_eq!(at(-5.0, 0.0), Some(1));
+        assert_eq!(at(-1.0, -5.0), Some(1));
+        // Outside the ring, or in the hole.
+        assert_eq!(at(9.0, -9.0), None);
+        assert_eq!(at(1.0, -1.0), None);
+    }
+}
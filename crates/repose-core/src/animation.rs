@@ -1,5 +1,7 @@
 use parking_lot::RwLock;
-use std::sync::OnceLock;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::{Arc, OnceLock};
 use web_time::{Duration, Instant};
 
 pub(crate) fn now() -> Instant {
@@ -7,7 +9,7 @@ pub(crate) fn now() -> Instant {
     lock.read().now()
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Easing {
     Linear,
     EaseIn,
@@ -62,7 +64,7 @@ fn spring_underdamped_normalized(t: f32, zeta: f32, omega: f32) -> f32 {
     y.clamp(0.0, 1.0)
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct AnimationSpec {
     pub duration: Duration,
     pub easing: Easing,
@@ -139,6 +141,15 @@ impl Interpolate for f32 {
     }
 }
 
+impl Interpolate for crate::Vec2 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        crate::Vec2 {
+            x: self.x.interpolate(&other.x, t),
+            y: self.y.interpolate(&other.y, t),
+        }
+    }
+}
+
 impl Interpolate for crate::Color {
     fn interpolate(&self, other: &Self, t: f32) -> Self {
         crate::Color(
@@ -169,6 +180,37 @@ pub fn set_clock(clock: Box<dyn Clock>) {
     let lock = CLOCK.get_or_init(|| RwLock::new(Box::new(SystemClock) as Box<dyn Clock>));
     *lock.write() = clock;
 }
+/// Installs `clock` until the returned guard is dropped, which puts back the clock it
+/// replaced. Only one guard is alive at a time (another `swap_clock` waits for it), so tests
+/// that drive a [`TestClock`] don't move each other's time when they run in parallel;
+/// within a test, [`set_clock`] moves it along.
+pub fn swap_clock(clock: Box<dyn Clock>) -> ClockGuard {
+    let exclusive = SWAPPED.lock();
+    let lock = CLOCK.get_or_init(|| RwLock::new(Box::new(SystemClock) as Box<dyn Clock>));
+    let previous = std::mem::replace(&mut *lock.write(), clock);
+    ClockGuard {
+        previous: Some(previous),
+        _exclusive: exclusive,
+    }
+}
+
+static SWAPPED: parking_lot::Mutex<()> = parking_lot::Mutex::new(());
+
+/// Restores the animation clock from before [`swap_clock`] when dropped.
+#[must_use = "dropping the guard puts the previous clock back right away"]
+pub struct ClockGuard {
+    previous: Option<Box<dyn Clock>>,
+    _exclusive: parking_lot::MutexGuard<'static, ()>,
+}
+
+impl Drop for ClockGuard {
+    fn drop(&mut self) {
+        if let (Some(previous), Some(lock)) = (self.previous.take(), CLOCK.get()) {
+            *lock.write() = previous;
+        }
+    }
+}
+
 /// Install default system clock if none present (idempotent).
 pub fn ensure_system_clock() {
     let _ = CLOCK.get_or_init(|| RwLock::new(Box::new(SystemClock) as Box<dyn Clock>));
@@ -185,6 +227,127 @@ impl Clock for TestClock {
     }
 }
 
+/// A clock with playback controls: it follows real time at an adjustable speed and can be
+/// paused, stepped and scrubbed while the app keeps running. Clones control the same clock.
+///
+/// Devtools use the process-wide one from [`playback_clock`] to slow every animation down
+/// or step through it frame by frame.
+#[derive(Clone)]
+pub struct PlaybackClock {
+    inner: Arc<parking_lot::Mutex<Playback>>,
+}
+
+struct Playback {
+    /// Real time and clock time at the last change of speed or position.
+    real: Instant,
+    virt: Instant,
+    speed: f32,
+    paused: bool,
+}
+
+impl Playback {
+    fn now(&self) -> Instant {
+        if self.paused {
+            return self.virt;
+        }
+        self.virt + self.real.elapsed().mul_f32(self.speed)
+    }
+
+    /// Re-anchors at the current time, so later changes only affect time from here on.
+    fn rebase(&mut self) {
+        self.virt = self.now();
+        self.real = Instant::now();
+    }
+}
+
+impl Default for PlaybackClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlaybackClock {
+    /// A clock at the current time, running at normal speed.
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            inner: Arc::new(parking_lot::Mutex::new(Playback {
+                real: now,
+                virt: now,
+                speed: 1.0,
+                paused: false,
+            })),
+        }
+    }
+
+    /// Makes this the animation clock.
+    pub fn install(&self) {
+        set_clock(Box::new(self.clone()));
+    }
+
+    /// How fast the clock runs relative to real time (`0.1` is ten times slower).
+    pub fn speed(&self) -> f32 {
+        self.inner.lock().speed
+    }
+
+    pub fn set_speed(&self, speed: f32) {
+        let mut p = self.inner.lock();
+        p.rebase();
+        p.speed = speed.max(0.0);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.inner.lock().paused
+    }
+
+    /// Stops the clock; animations hold their current frame.
+    pub fn pause(&self) {
+        let mut p = self.inner.lock();
+        p.rebase();
+        p.paused = true;
+    }
+
+    pub fn resume(&self) {
+        let mut p = self.inner.lock();
+        p.rebase();
+        p.paused = false;
+    }
+
+    /// Moves the clock `by` forward, paused or not.
+    pub fn step(&self, by: Duration) {
+        let mut p = self.inner.lock();
+        p.rebase();
+        p.virt += by;
+    }
+
+    /// Moves the clock to `t`, backwards or forwards.
+    pub fn seek(&self, t: Instant) {
+        let mut p = self.inner.lock();
+        p.rebase();
+        p.virt = t;
+    }
+}
+
+impl Clock for PlaybackClock {
+    fn now(&self) -> Instant {
+        self.inner.lock().now()
+    }
+}
+
+static PLAYBACK: OnceLock<PlaybackClock> = OnceLock::new();
+
+/// The process-wide [`PlaybackClock`], (re)installed as the animation clock.
+pub fn playback_clock() -> PlaybackClock {
+    let clock = PLAYBACK.get_or_init(PlaybackClock::new).clone();
+    clock.install();
+    clock
+}
+
+/// The process-wide [`PlaybackClock`] if something has started using it.
+pub fn active_playback_clock() -> Option<PlaybackClock> {
+    PLAYBACK.get().cloned()
+}
+
 /// Animated value that transitions smoothly
 pub struct AnimatedValue<T: Interpolate + Clone> {
     current: T,
@@ -251,7 +414,60 @@ impl<T: Interpolate + Clone> AnimatedValue<T> {
         &self.current
     }
 
+    pub fn target(&self) -> &T {
+        &self.target
+    }
+
     pub fn is_animating(&self) -> bool {
         self.start_time.is_some()
     }
 }
+
+/// A dp offset for [`Modifier::offset_animated`](crate::Modifier::offset_animated).
+///
+/// Layout only sees the *settled* offset, which changes once when an animation finishes;
+/// the in-flight difference is painted as a translation.
+#[derive(Clone)]
+pub struct AnimatedOffset {
+    anim: Rc<RefCell<AnimatedValue<crate::Vec2>>>,
+    settled: Rc<Cell<crate::Vec2>>,
+}
+
+impl AnimatedOffset {
+    pub fn new(initial: crate::Vec2, spec: AnimationSpec) -> Self {
+        Self {
+            anim: Rc::new(RefCell::new(AnimatedValue::new(initial, spec))),
+            settled: Rc::new(Cell::new(initial)),
+        }
+    }
+
+    /// Starts animating toward `target`; a no-op if it's already the target.
+    pub fn animate_to(&self, target: crate::Vec2) {
+        let mut a = self.anim.borrow_mut();
+        if *a.target() != target {
+            a.set_target(target);
+        }
+    }
+
+    pub fn target(&self) -> crate::Vec2 {
+        *self.anim.borrow().target()
+    }
+
+    /// Advances the animation and returns the current offset.
+    pub fn value(&self) -> crate::Vec2 {
+        let mut a = self.anim.borrow_mut();
+        if !a.update() {
+            self.settled.set(*a.get());
+        }
+        *a.get()
+    }
+
+    /// The offset layout uses: the value at the end of the last finished animation.
+    pub fn settled(&self) -> crate::Vec2 {
+        self.settled.get()
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.anim.borrow().is_animating()
+    }
+}
@@ -0,0 +1,177 @@
+//! # Annotated text
+//!
+//! An [`AnnotatedText`] is a string with styled spans: runs in another color, size or
+//! weight, underlined runs, and links. `repose_ui::RichText` lays it out as one paragraph,
+//! wrapping across spans, and makes link spans clickable.
+//!
+//! ```rust
+//! use repose_core::*;
+//!
+//! let t = AnnotatedText::new()
+//!     .push("By continuing you accept the ")
+//!     .push_link("terms", || println!("open terms"))
+//!     .push(" and ")
+//!     .push_styled("privacy policy", SpanStyle::new().bold());
+//!
+//! assert_eq!(t.text, "By continuing you accept the terms and privacy policy");
+//! assert_eq!(t.spans[0].range, 29..34);
+//! assert!(t.spans[0].on_click.is_some());
+//! ```
+
+use std::ops::Range;
+use std::rc::Rc;
+
+use crate::{Callback, Color};
+
+/// CSS-style font weight, 100 (thin) to 900 (black).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontWeight(pub u16);
+
+impl FontWeight {
+    pub const NORMAL: Self = Self(400);
+    pub const MEDIUM: Self = Self(500);
+    pub const SEMIBOLD: Self = Self(600);
+    pub const BOLD: Self = Self(700);
+}
+
+impl Default for FontWeight {
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+/// How a span differs from the text around it; unset fields keep the `Text`'s own.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SpanStyle {
+    pub color: Option<Color>,
+    /// In dp.
+    pub font_size: Option<f32>,
+    pub weight: Option<FontWeight>,
+    pub underline: bool,
+}
+
+impl SpanStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn color(mut self, c: Color) -> Self {
+        self.color = Some(c);
+        self
+    }
+
+    pub fn size(mut self, dp: f32) -> Self {
+        self.font_size = Some(dp);
+        self
+    }
+
+    pub fn weight(mut self, w: FontWeight) -> Self {
+        self.weight = Some(w);
+        self
+    }
+
+    pub fn bold(self) -> Self {
+        self.weight(FontWeight::BOLD)
+    }
+
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+}
+
+/// A styled byte range of an [`AnnotatedText`].
+#[derive(Clone)]
+pub struct TextSpan {
+    pub range: Range<usize>,
+    pub style: SpanStyle,
+    /// Makes the span a link.
+    pub on_click: Option<Callback>,
+}
+
+impl std::fmt::Debug for TextSpan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TextSpan")
+            .field("range", &self.range)
+            .field("style", &self.style)
+            .field("link", &self.on_click.is_some())
+            .finish()
+    }
+}
+
+/// Text with styled spans, built by appending runs in order. Spans don't overlap.
+#[derive(Clone, Debug, Default)]
+pub struct AnnotatedText {
+    pub text: String,
+    pub spans: Vec<TextSpan>,
+}
+
+impl AnnotatedText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends text in the `Text`'s own style.
+    pub fn push(mut self, text: &str) -> Self {
+        self.text.push_str(text);
+        self
+    }
+
+    pub fn push_styled(self, text: &str, style: SpanStyle) -> Self {
+        self.push_span(text, style, None)
+    }
+
+    /// Appends a link: underlined and in the theme's primary color, calling `on_click` when
+    /// clicked. [`push_span`](Self::push_span) makes links in other styles.
+    pub fn push_link(self, text: &str, on_click: impl Fn() + 'static) -> Self {
+        self.push_span(text, SpanStyle::new().underline(), Some(Rc::new(on_click)))
+    }
+
+    pub fn push_span(mut self, text: &str, style: SpanStyle, on_click: Option<Callback>) -> Self {
+        let start = self.text.len();
+        self.text.push_str(text);
+        self.spans.push(TextSpan {
+            range: start..self.text.len(),
+            style,
+            on_click,
+        });
+        self
+    }
+
+    /// Splits the text into runs, see [`span_runs`].
+    pub fn runs(&self) -> impl Iterator<Item = (Range<usize>, Option<&TextSpan>)> + '_ {
+        span_runs(&self.text, &self.spans)
+    }
+}
+
+/// Splits `text` into consecutive runs covering all of it, each with its span from `spans`
+/// (in order, not overlapping) if it has one.
+pub fn span_runs<'a>(
+    text: &'a str,
+    spans: &'a [TextSpan],
+) -> impl Iterator<Item = (Range<usize>, Option<&'a TextSpan>)> + 'a {
+    let mut at = 0;
+    let mut spans = spans.iter().filter(|s| !s.range.is_empty()).peekable();
+    std::iter::from_fn(move || {
+        if at >= text.len() {
+            return None;
+        }
+        let run = match spans.peek() {
+            Some(s) if s.range.start == at => {
+                let s = spans.next()?;
+                (s.range.clone(), Some(s))
+            }
+            Some(s) => (at..s.range.start, None),
+            None => (at..text.len(), None),
+        };
+        at = run.0.end;
+        Some(run)
+    })
+}
+
+impl From<&str> for AnnotatedText {
+    fn from(text: &str) -> Self {
+        Self::new().push(text)
+    }
+}
@@ -0,0 +1,148 @@
+//! # Event bus
+//!
+//! Panels that don't share a parent (a status bar, a devtools pane, the document they
+//! describe) can talk through the [`EventBus`] instead of threading callbacks through the
+//! whole tree. Topics are types: anyone can [`emit`] a value, and whoever subscribed to its
+//! type with [`use_subscription`] receives it.
+//!
+//! ```rust
+//! use repose_core::*;
+//!
+//! #[derive(Clone)]
+//! struct Saved(String);
+//!
+//! fn status_bar(last: Signal<String>) {
+//!     use_subscription(move |e: &Saved| last.set(format!("Saved {}", e.0)));
+//! }
+//!
+//! fn on_save_clicked() {
+//!     emit(Saved("notes.txt".into()));
+//! }
+//! ```
+//!
+//! Events are queued and delivered on the UI thread at the start of the next frame, before
+//! composition, so a subscriber's state changes show up in the frame that follows the
+//! emit. Subscriptions are declarative like the rest of the tree: each composition declares
+//! them again, and one that isn't declared any more stops receiving.
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+/// Rounds of delivery per frame; events emitted by subscribers beyond that wait a frame.
+const MAX_ROUNDS: usize = 16;
+
+type Handler = Rc<dyn Fn(&dyn Any)>;
+
+struct Subscriber {
+    topic: TypeId,
+    deliver: Handler,
+}
+
+#[derive(Default)]
+struct Inner {
+    queue: RefCell<VecDeque<Box<dyn Any>>>,
+    subscribers: RefCell<Vec<Subscriber>>,
+}
+
+/// A typed publish/subscribe queue, owned by the `Scheduler`. Clones share the same bus.
+#[derive(Clone, Default)]
+pub struct EventBus {
+    inner: Rc<Inner>,
+}
+
+thread_local! {
+    static BUS: EventBus = EventBus::default();
+}
+
+impl EventBus {
+    /// This thread's bus, which every `Scheduler` on the thread shares.
+    pub fn current() -> Self {
+        BUS.with(Clone::clone)
+    }
+
+    /// Queues `event` for the subscribers of its type.
+    pub fn emit<E: 'static>(&self, event: E) {
+        self.inner.queue.borrow_mut().push_back(Box::new(event));
+    }
+
+    /// Calls `f` with every `E` delivered until the next composition.
+    pub fn subscribe<E: 'static>(&self, f: impl Fn(&E) + 'static) {
+        self.add(
+            TypeId::of::<E>(),
+            Rc::new(move |e| {
+                if let Some(e) = e.downcast_ref::<E>() {
+                    f(e)
+                }
+            }),
+        );
+    }
+
+    fn add(&self, topic: TypeId, deliver: Handler) {
+        crate::runtime::record_declaration(|| {
+            let (bus, deliver) = (self.clone(), deliver.clone());
+            move || bus.add(topic, deliver.clone())
+        });
+        self.inner
+            .subscribers
+            .borrow_mut()
+            .push(Subscriber { topic, deliver });
+    }
+
+    /// Whether events are waiting to be delivered.
+    pub fn has_pending(&self) -> bool {
+        !self.inner.queue.borrow().is_empty()
+    }
+
+    /// Hands queued events to their subscribers in the order they were emitted, including
+    /// events the subscribers emit in turn. Returns how many were delivered.
+    pub fn deliver(&self) -> usize {
+        let mut delivered = 0;
+        for _ in 0..MAX_ROUNDS {
+            let batch = std::mem::take(&mut *self.inner.queue.borrow_mut());
+            if batch.is_empty() {
+                break;
+            }
+            for event in batch {
+                let topic = (*event).type_id();
+                // Subscribers may subscribe or emit while running; call them outside the borrow.
+                let targets: Vec<_> = self
+                    .inner
+                    .subscribers
+                    .borrow()
+                    .iter()
+                    .filter(|s| s.topic == topic)
+                    .map(|s| s.deliver.clone())
+                    .collect();
+                for f in targets {
+                    f(event.as_ref());
+                }
+                delivered += 1;
+            }
+        }
+        delivered
+    }
+
+    /// Drops the subscriptions of the last composition; the one starting declares its own.
+    pub(crate) fn begin_composition(&self) {
+        self.inner.subscribers.borrow_mut().clear();
+    }
+}
+
+/// Queues `event` on this thread's bus; see [`EventBus::emit`].
+pub fn emit<E: 'static>(event: E) {
+    EventBus::current().emit(event)
+}
+
+/// Receives every `E` emitted on this thread's bus, for as long as the calling composable
+/// keeps calling this each composition.
+pub fn use_subscription<E: 'static>(f: impl Fn(&E) + 'static) {
+    EventBus::current().subscribe(f)
+}
+
+/// Whether events are waiting for the next frame; runners that don't redraw continuously
+/// should schedule one.
+pub fn events_pending() -> bool {
+    EventBus::current().has_pending()
+}
@@ -1,6 +1,7 @@
 use crate::Vec2;
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Color(pub u8, pub u8, pub u8, pub u8);
 
 impl Color {
@@ -59,6 +60,7 @@ impl Color {
 /// widgets) should talk in terms of `Brush` rather than raw `Color` so that
 /// gradients and future brush types (radial, image) can share the same path.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Brush {
     /// Solid color fill
     Solid(Color),
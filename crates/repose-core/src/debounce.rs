@@ -0,0 +1,94 @@
+//! # Debounce and throttle
+//!
+//! Values that change on every keystroke or every frame (a search query, a window width)
+//! often drive work that shouldn't run that often. [`use_debounced`] follows a value only
+//! once it has stopped changing for a while; [`use_throttled`] follows it at most once per
+//! interval. Both are composables: call them every composition with the current value, and
+//! they return the value to act on. Their timers are tasks on a [`task_scope`], so they stop
+//! when the caller leaves the tree.
+//!
+//! ```rust
+//! use repose_core::*;
+//! use std::time::Duration;
+//!
+//! fn results(query: &Signal<String>) -> View {
+//!     let q = use_debounced(query.get(), Duration::from_millis(300));
+//!     // `q` only changes once typing pauses, so this rebuilds once per pause.
+//!     memo(q, || View::new(0, ViewKind::Column))
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use web_time::Duration;
+
+use crate::{TaskHandle, delay, remember, task_scope};
+
+struct Limited<T> {
+    input: T,
+    output: T,
+    timer: Option<TaskHandle>,
+}
+
+fn limited<T: Clone + 'static>(value: &T) -> Rc<RefCell<Limited<T>>> {
+    (*remember(|| {
+        Rc::new(RefCell::new(Limited {
+            input: value.clone(),
+            output: value.clone(),
+            timer: None,
+        }))
+    }))
+    .clone()
+}
+
+/// `value` once it has gone `quiet` without changing; until then, the value it last settled
+/// on. The first composition returns `value` as is.
+pub fn use_debounced<T: Clone + PartialEq + 'static>(value: T, quiet: Duration) -> T {
+    let tasks = task_scope();
+    let st = limited(&value);
+    let mut s = st.borrow_mut();
+    if s.input != value {
+        s.input = value;
+        if let Some(t) = s.timer.take() {
+            t.cancel();
+        }
+        let (st, wait) = (st.clone(), delay(quiet));
+        s.timer = Some(tasks.spawn(async move {
+            wait.await;
+            let mut s = st.borrow_mut();
+            s.output = s.input.clone();
+        }));
+    }
+    s.output.clone()
+}
+
+/// `value`, changing at most once per `interval`: a change after a quiet interval comes
+/// through right away, and changes within an interval are held back until it ends, when the
+/// latest one comes through.
+pub fn use_throttled<T: Clone + PartialEq + 'static>(value: T, interval: Duration) -> T {
+    let tasks = task_scope();
+    let st = limited(&value);
+    let mut s = st.borrow_mut();
+    if s.input != value {
+        s.input = value.clone();
+        if !s.timer.as_ref().is_some_and(TaskHandle::is_active) {
+            s.output = value;
+            let (st, mut wait) = (st.clone(), delay(interval));
+            s.timer = Some(tasks.spawn(async move {
+                loop {
+                    wait.await;
+                    {
+                        let mut s = st.borrow_mut();
+                        if s.output == s.input {
+                            break;
+                        }
+                        s.output = s.input.clone();
+                    }
+                    wait = delay(interval);
+                }
+            }));
+        }
+    }
+    s.output.clone()
+}
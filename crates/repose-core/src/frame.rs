@@ -0,0 +1,508 @@
+//! # Frame API
+//!
+//! The contract between composition and everything downstream of it: renderers, test
+//! harnesses, devtools and screenshot tools. Each tick `Scheduler::repose` produces a
+//! [`Frame`]:
+//!
+//! - [`Scene`] — a flat list of [`SceneNode`] draw commands in physical pixels, in paint
+//!   order. Clip and transform nodes come in matched push/pop pairs ([`check_stacks`]).
+//! - [`HitRegion`]s — input targets, later ones on top (ties broken by `z_index`).
+//! - [`SemNode`]s — the flattened accessibility tree.
+//! - `focus_chain` — ids of focusable regions in tab order.
+//!
+//! Everything re-exported here is covered by semver: fields and variants are only added or
+//! changed in a breaking release, which also bumps [`FRAME_API_VERSION`]. Tooling built
+//! out-of-tree should depend on these names rather than on other `repose_core` internals.
+//!
+//! With the `serde` feature, frames (minus the callbacks on hit regions) serialize, so
+//! they can be recorded, diffed in tests or streamed to a remote inspector. The semantics
+//! tree has a dependency-free JSON dump, [`Frame::semantics_to_json`], and a structural diff,
+//! [`diff_semantics`], for a11y debugging and test assertions.
+//!
+//! ```rust
+//! use repose_core::frame::*;
+//!
+//! let r = Rect { x: 0.0, y: 0.0, w: 100.0, h: 40.0 };
+//! let scene = Scene::new(Color::BLACK).with_node(SceneNode::Rect {
+//!     rect: r,
+//!     brush: Brush::Solid(Color::WHITE),
+//!     radius: 4.0,
+//! });
+//! let frame = Frame::new(scene)
+//!     .with_hit_regions(vec![HitRegion::new(1, r).on_click(|| {}).focusable(true)])
+//!     .with_semantics(vec![SemNode::new(1, Role::Button, r).label("OK")]);
+//! assert_eq!(frame.scene.nodes.len(), 1);
+//! ```
+
+use std::fmt::{self, Write as _};
+use std::rc::Rc;
+
+pub use crate::input::{KeyEvent, PointerEvent};
+pub use crate::runtime::{Frame, HitRegion, SemNode};
+pub use crate::semantics::{Role, ToggleableState};
+pub use crate::stats::FrameStats;
+pub use crate::view::{ImageFit, ImageHandle, Scene, SceneNode};
+pub use crate::{Brush, Color, InternedStr, Rect, Transform, Vec2};
+
+/// Version of the frame contract. Bumped with every breaking change to the types above:
+///
+/// - 2: `HitRegion::on_key_event`.
+/// - 3: `SceneNode::Text` carries an [`InternedStr`].
+/// - 4: `SceneNode::Line` and `SceneNode::Arc`.
+/// - 5: `SemNode::toggle`. Serialized frames from earlier versions still load, with no
+///   toggle state.
+pub const FRAME_API_VERSION: u32 = 5;
+
+impl Scene {
+    pub fn new(clear_color: Color) -> Self {
+        Self {
+            clear_color,
+            nodes: Vec::new(),
+        }
+    }
+
+    pub fn with_node(mut self, node: SceneNode) -> Self {
+        self.nodes.push(node);
+        self
+    }
+
+    pub fn with_nodes(mut self, nodes: impl IntoIterator<Item = SceneNode>) -> Self {
+        self.nodes.extend(nodes);
+        self
+    }
+
+    /// Whether every clip and transform push is popped, and nothing else is.
+    pub fn check_stacks(&self) -> Result<(), StackError> {
+        check_stacks(&self.nodes)
+    }
+
+    /// Drops drawing nodes that land entirely outside `viewport` or their enclosing clips,
+    /// so scrolled-out content costs the renderer nothing. Clip and transform nodes are
+    /// kept, keeping the stacks balanced. Returns how many nodes were removed.
+    ///
+    /// Bounds go through the transform stack with [`Transform::apply_to_rect`], as the
+    /// renderer maps them.
+    pub fn cull(&mut self, viewport: Rect) -> usize {
+        let before = self.nodes.len();
+        let mut transforms = vec![Transform::identity()];
+        let mut clips = vec![viewport];
+        let mut out: Vec<SceneNode> = Vec::with_capacity(before);
+        for node in self.nodes.drain(..) {
+            let t = *transforms.last().unwrap_or(&Transform::identity());
+            let clip = *clips.last().unwrap_or(&viewport);
+            match &node {
+                SceneNode::PushClip { rect, .. } => {
+                    clips.push(intersection(clip, normalized(t.apply_to_rect(*rect))));
+                }
+                SceneNode::PushTransform { transform } => transforms.push(t.combine(transform)),
+                SceneNode::PopClip => {
+                    if clips.len() > 1 {
+                        clips.pop();
+                    }
+                }
+                SceneNode::PopTransform => {
+                    if transforms.len() > 1 {
+                        transforms.pop();
+                    }
+                }
+                _ => {
+                    if let Some(b) = node.bounds()
+                        && !overlaps(clip, normalized(t.apply_to_rect(b)))
+                    {
+                        continue;
+                    }
+                }
+            }
+            out.push(node);
+        }
+        self.nodes = out;
+        before - self.nodes.len()
+    }
+}
+
+/// `r` with non-negative width and height (a negative scale flips it).
+fn normalized(r: Rect) -> Rect {
+    Rect {
+        x: r.x.min(r.x + r.w),
+        y: r.y.min(r.y + r.h),
+        w: r.w.abs(),
+        h: r.h.abs(),
+    }
+}
+
+fn intersection(a: Rect, b: Rect) -> Rect {
+    let x = a.x.max(b.x);
+    let y = a.y.max(b.y);
+    Rect {
+        x,
+        y,
+        w: ((a.x + a.w).min(b.x + b.w) - x).max(0.0),
+        h: ((a.y + a.h).min(b.y + b.h) - y).max(0.0),
+    }
+}
+
+fn overlaps(a: Rect, b: Rect) -> bool {
+    b.x < a.x + a.w && b.x + b.w > a.x && b.y < a.y + a.h && b.y + b.h > a.y
+}
+
+/// Unbalanced clip or transform nodes, found by [`check_stacks`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StackError {
+    /// The node at `index` pops a clip (or transform) that was never pushed.
+    UnmatchedPop { index: usize, clip: bool },
+    /// Pushes still open after the last node.
+    Unclosed { clips: usize, transforms: usize },
+}
+
+impl fmt::Display for StackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            StackError::UnmatchedPop { index, clip } => {
+                let what = if clip { "PopClip" } else { "PopTransform" };
+                write!(f, "{what} at node {index} has no matching push")
+            }
+            StackError::Unclosed { clips, transforms } => write!(
+                f,
+                "{clips} PushClip and {transforms} PushTransform left without a pop"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StackError {}
+
+/// Checks that `nodes` opens and closes its clips and transforms in pairs. Clips and
+/// transforms are counted separately, as renderers keep a stack for each.
+pub fn check_stacks(nodes: &[SceneNode]) -> Result<(), StackError> {
+    let (mut clips, mut transforms) = (0usize, 0usize);
+    for (index, node) in nodes.iter().enumerate() {
+        let (depth, clip) = match node {
+            SceneNode::PushClip { .. } => {
+                clips += 1;
+                continue;
+            }
+            SceneNode::PushTransform { .. } => {
+                transforms += 1;
+                continue;
+            }
+            SceneNode::PopClip => (&mut clips, true),
+            SceneNode::PopTransform => (&mut transforms, false),
+            _ => continue,
+        };
+        *depth = depth
+            .checked_sub(1)
+            .ok_or(StackError::UnmatchedPop { index, clip })?;
+    }
+    if clips > 0 || transforms > 0 {
+        return Err(StackError::Unclosed { clips, transforms });
+    }
+    Ok(())
+}
+
+impl Frame {
+    /// A frame with `scene` and no input targets or semantics.
+    pub fn new(scene: Scene) -> Self {
+        Self {
+            scene,
+            hit_regions: Vec::new(),
+            semantics_nodes: Vec::new(),
+            focus_chain: Vec::new(),
+            stats: FrameStats::default(),
+        }
+    }
+
+    pub fn with_hit_regions(mut self, hit_regions: Vec<HitRegion>) -> Self {
+        self.hit_regions = hit_regions;
+        self
+    }
+
+    pub fn with_semantics(mut self, nodes: Vec<SemNode>) -> Self {
+        self.semantics_nodes = nodes;
+        self
+    }
+
+    pub fn with_focus_chain(mut self, ids: Vec<u64>) -> Self {
+        self.focus_chain = ids;
+        self
+    }
+
+    /// The semantics nodes as a JSON array, one object per node in tree order.
+    pub fn semantics_to_json(&self) -> String {
+        semantics_to_json(&self.semantics_nodes)
+    }
+
+    /// How this frame's semantics differ from `previous`'s.
+    pub fn semantics_diff(&self, previous: &Frame) -> Vec<SemChange> {
+        diff_semantics(&previous.semantics_nodes, &self.semantics_nodes)
+    }
+}
+
+/// `nodes` as a JSON array. Fields are always present and in declaration order, so dumps
+/// also diff well as text; `rect` is `[x, y, w, h]` and a missing label is `null`. The one
+/// exception is `toggle`, written last and only for toggles, e.g. `"toggle":"On"`.
+pub fn semantics_to_json(nodes: &[SemNode]) -> String {
+    fn num(out: &mut String, v: f32) {
+        if v.is_finite() {
+            let _ = write!(out, "{v}");
+        } else {
+            out.push_str("null");
+        }
+    }
+    fn string(out: &mut String, s: &str) {
+        out.push('"');
+        for c in s.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                c if (c as u32) < 0x20 => {
+                    let _ = write!(out, "\\u{:04x}", c as u32);
+                }
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+    }
+
+    let mut out = String::from("[");
+    for (i, n) in nodes.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let _ = write!(
+            out,
+            "{{\"id\":{},\"role\":\"{:?}\",\"label\":",
+            n.id, n.role
+        );
+        match &n.label {
+            Some(l) => string(&mut out, l),
+            None => out.push_str("null"),
+        }
+        out.push_str(",\"rect\":[");
+        for (j, v) in [n.rect.x, n.rect.y, n.rect.w, n.rect.h]
+            .into_iter()
+            .enumerate()
+        {
+            if j > 0 {
+                out.push(',');
+            }
+            num(&mut out, v);
+        }
+        let _ = write!(
+            out,
+            "],\"focused\":{},\"enabled\":{},\"truncated\":{}",
+            n.focused, n.enabled, n.truncated
+        );
+        if let Some(t) = n.toggle {
+            let _ = write!(out, ",\"toggle\":\"{t:?}\"");
+        }
+        out.push('}');
+    }
+    out.push(']');
+    out
+}
+
+/// One difference between two semantics trees, matched by node id.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SemChange {
+    Added(SemNode),
+    Removed(SemNode),
+    Changed { before: SemNode, after: SemNode },
+}
+
+impl fmt::Display for SemChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let node = |n: &SemNode| {
+            format!(
+                "{:?} #{:x} {:?}",
+                n.role,
+                n.id,
+                n.label.as_deref().unwrap_or("")
+            )
+        };
+        match self {
+            SemChange::Added(n) => write!(f, "+ {}", node(n)),
+            SemChange::Removed(n) => write!(f, "- {}", node(n)),
+            SemChange::Changed { before, after } => {
+                write!(f, "~ {}", node(after))?;
+                if before.role != after.role {
+                    write!(f, " role {:?} -> {:?}", before.role, after.role)?;
+                }
+                if before.label != after.label {
+                    write!(f, " label {:?} -> {:?}", before.label, after.label)?;
+                }
+                if before.rect != after.rect {
+                    let r = |r: Rect| format!("[{} {} {} {}]", r.x, r.y, r.w, r.h);
+                    write!(f, " rect {} -> {}", r(before.rect), r(after.rect))?;
+                }
+                if before.toggle != after.toggle {
+                    write!(f, " toggle {:?} -> {:?}", before.toggle, after.toggle)?;
+                }
+                for (name, a, b) in [
+                    ("focused", before.focused, after.focused),
+                    ("enabled", before.enabled, after.enabled),
+                    ("truncated", before.truncated, after.truncated),
+                ] {
+                    if a != b {
+                        write!(f, " {name} {a} -> {b}")?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Nodes removed from `before`, then nodes changed or added in `after`, in tree order.
+pub fn diff_semantics(before: &[SemNode], after: &[SemNode]) -> Vec<SemChange> {
+    let find = |nodes: &[SemNode], id| nodes.iter().find(|n| n.id == id).cloned();
+    let mut changes: Vec<SemChange> = before
+        .iter()
+        .filter(|b| find(after, b.id).is_none())
+        .map(|b| SemChange::Removed(b.clone()))
+        .collect();
+    for a in after {
+        match find(before, a.id) {
+            None => changes.push(SemChange::Added(a.clone())),
+            Some(b) if b != *a => changes.push(SemChange::Changed {
+                before: b,
+                after: a.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    changes
+}
+
+impl HitRegion {
+    /// A region at `rect` that reacts to nothing until handlers are added.
+    pub fn new(id: u64, rect: Rect) -> Self {
+        Self {
+            id,
+            rect,
+            on_click: None,
+            on_scroll: None,
+            focusable: false,
+            on_pointer_down: None,
+            on_pointer_move: None,
+            on_pointer_up: None,
+            on_pointer_enter: None,
+            on_pointer_leave: None,
+            on_key_event: None,
+            z_index: 0.0,
+            on_text_change: None,
+            on_text_submit: None,
+            tf_state_key: None,
+        }
+    }
+
+    pub fn on_click(mut self, f: impl Fn() + 'static) -> Self {
+        self.on_click = Some(Rc::new(f));
+        self
+    }
+
+    /// `f` receives the scroll delta and returns the part it didn't consume.
+    pub fn on_scroll(mut self, f: impl Fn(Vec2) -> Vec2 + 'static) -> Self {
+        self.on_scroll = Some(Rc::new(f));
+        self
+    }
+
+    pub fn focusable(mut self, focusable: bool) -> Self {
+        self.focusable = focusable;
+        self
+    }
+
+    pub fn on_pointer_down(mut self, f: impl Fn(PointerEvent) + 'static) -> Self {
+        self.on_pointer_down = Some(Rc::new(f));
+        self
+    }
+
+    pub fn on_pointer_move(mut self, f: impl Fn(PointerEvent) + 'static) -> Self {
+        self.on_pointer_move = Some(Rc::new(f));
+        self
+    }
+
+    pub fn on_pointer_up(mut self, f: impl Fn(PointerEvent) + 'static) -> Self {
+        self.on_pointer_up = Some(Rc::new(f));
+        self
+    }
+
+    pub fn on_pointer_enter(mut self, f: impl Fn(PointerEvent) + 'static) -> Self {
+        self.on_pointer_enter = Some(Rc::new(f));
+        self
+    }
+
+    pub fn on_pointer_leave(mut self, f: impl Fn(PointerEvent) + 'static) -> Self {
+        self.on_pointer_leave = Some(Rc::new(f));
+        self
+    }
+
+    /// `f` returns whether it consumed the event.
+    pub fn on_key_event(mut self, f: impl Fn(KeyEvent) -> bool + 'static) -> Self {
+        self.on_key_event = Some(Rc::new(f));
+        self
+    }
+
+    pub fn z_index(mut self, z: f32) -> Self {
+        self.z_index = z;
+        self
+    }
+
+    pub fn on_text_change(mut self, f: impl Fn(String) + 'static) -> Self {
+        self.on_text_change = Some(Rc::new(f));
+        self
+    }
+
+    pub fn on_text_submit(mut self, f: impl Fn(String) + 'static) -> Self {
+        self.on_text_submit = Some(Rc::new(f));
+        self
+    }
+
+    pub fn tf_state_key(mut self, key: u64) -> Self {
+        self.tf_state_key = Some(key);
+        self
+    }
+}
+
+impl SemNode {
+    /// An enabled, unfocused node with no label.
+    pub fn new(id: u64, role: Role, rect: Rect) -> Self {
+        Self {
+            id,
+            role,
+            label: None,
+            rect,
+            focused: false,
+            enabled: true,
+            truncated: false,
+            toggle: None,
+        }
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn truncated(mut self, truncated: bool) -> Self {
+        self.truncated = truncated;
+        self
+    }
+
+    pub fn toggle(mut self, state: ToggleableState) -> Self {
+        self.toggle = Some(state);
+        self
+    }
+}
@@ -0,0 +1,128 @@
+//! # Frame clock
+//!
+//! Animations and gesture physics that advance with time should step once per produced
+//! frame, not whenever something happens to repaint. [`with_frame_nanos`] registers a
+//! callback for the next frame; `Scheduler::repose` runs it once, before composition, with
+//! that frame's timestamp. Every callback in a frame sees the same timestamp, taken from the
+//! animation clock (so `TestClock` drives it too).
+//!
+//! Coroutine-style code can `await` [`next_frame`] instead:
+//!
+//! ```rust
+//! use repose_core::*;
+//!
+//! async fn drift(offset: Signal<f32>) {
+//!     let start = next_frame().await;
+//!     loop {
+//!         let t = next_frame().await;
+//!         offset.set((t - start) as f32 * 40e-9); // 40 px/s
+//!     }
+//! }
+//! ```
+//!
+//! Idle runners check [`frame_requested`] and keep producing frames while anyone is waiting.
+
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+use web_time::Instant;
+
+type FrameCallback = Box<dyn FnOnce(u64)>;
+
+thread_local! {
+    static CALLBACKS: RefCell<Vec<FrameCallback>> = const { RefCell::new(Vec::new()) };
+    static ORIGIN: Cell<Option<Instant>> = const { Cell::new(None) };
+    static FRAME_NANOS: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Calls `cb` once, at the start of the next frame, with its timestamp in nanoseconds.
+///
+/// Callbacks registered while frame callbacks are running go to the frame after, so a
+/// callback that re-registers itself runs exactly once per frame.
+pub fn with_frame_nanos(cb: impl FnOnce(u64) + 'static) {
+    CALLBACKS.with(|c| c.borrow_mut().push(Box::new(cb)));
+}
+
+/// [`with_frame_nanos`] in milliseconds.
+pub fn with_frame_millis(cb: impl FnOnce(u64) + 'static) {
+    with_frame_nanos(move |ns| cb(ns / 1_000_000));
+}
+
+/// Whether something is waiting for a frame; runners that don't redraw continuously should
+/// schedule one.
+pub fn frame_requested() -> bool {
+    CALLBACKS.with(|c| !c.borrow().is_empty())
+}
+
+/// Timestamp of the current (or most recent) frame in nanoseconds.
+pub fn frame_time_nanos() -> u64 {
+    FRAME_NANOS.get()
+}
+
+/// Stamps a new frame and runs the callbacks registered for it.
+pub(crate) fn begin_frame() {
+    let now = crate::animation::now();
+    let origin = ORIGIN.get().unwrap_or_else(|| {
+        ORIGIN.set(Some(now));
+        now
+    });
+    let nanos = now.saturating_duration_since(origin).as_nanos() as u64;
+    // Keep timestamps monotonic even if a test clock is moved backwards.
+    let nanos = nanos.max(FRAME_NANOS.get());
+    FRAME_NANOS.set(nanos);
+
+    let due = CALLBACKS.with(|c| std::mem::take(&mut *c.borrow_mut()));
+    for cb in due {
+        cb(nanos);
+    }
+}
+
+/// A future resolving to the next frame's timestamp in nanoseconds; see [`next_frame`].
+pub struct NextFrame {
+    slot: Rc<RefCell<FrameWait>>,
+}
+
+#[derive(Default)]
+struct FrameWait {
+    nanos: Option<u64>,
+    waker: Option<Waker>,
+    registered: bool,
+}
+
+/// Waits for the next frame and resolves with its timestamp (ns). Needs a local executor
+/// (the UI thread's); the waker is called from `Scheduler::repose`.
+pub fn next_frame() -> NextFrame {
+    NextFrame {
+        slot: Rc::new(RefCell::new(FrameWait::default())),
+    }
+}
+
+impl Future for NextFrame {
+    type Output = u64;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u64> {
+        let mut wait = self.slot.borrow_mut();
+        if let Some(nanos) = wait.nanos {
+            return Poll::Ready(nanos);
+        }
+        wait.waker = Some(cx.waker().clone());
+        if !wait.registered {
+            wait.registered = true;
+            let slot = self.slot.clone();
+            with_frame_nanos(move |nanos| {
+                let waker = {
+                    let mut wait = slot.borrow_mut();
+                    wait.nanos = Some(nanos);
+                    wait.waker.take()
+                };
+                if let Some(w) = waker {
+                    w.wake();
+                }
+            });
+        }
+        Poll::Pending
+    }
+}
@@ -0,0 +1,511 @@
+//! # Localization
+//!
+//! The active [`Locale`] is a composition local (`locale()` / `with_locale`); platform
+//! runners seed the default from the OS. Apps register string tables per locale with
+//! [`register_strings`] and look entries up with [`tr!`](crate::tr), which formats a subset
+//! of ICU MessageFormat:
+//!
+//! ```rust
+//! use repose_core::*;
+//!
+//! register_strings("en", [(
+//!     "inbox",
+//!     "{name} has {count, plural, =0 {no messages} one {# message} other {# messages}}",
+//! )]);
+//! with_locale(Locale::parse("en-US").unwrap(), || {
+//!     assert_eq!(tr!("inbox", name = "Ada", count = 3), "Ada has 3 messages");
+//! });
+//! ```
+//!
+//! Patterns support `{arg}`, `{arg, number}`, `{arg, plural, ...}` (with `offset:N`, `=N`
+//! exact matches, CLDR categories and `#`) and `{arg, select, ...}`, each falling back to
+//! `other`. Apostrophe quoting isn't supported. Plural rules cover the common language
+//! families; unknown languages use English rules.
+//!
+//! [`format_number`], [`format_percent`] and [`format_date`] render values with the
+//! current locale's separators and field order; built-in widgets use them for labels.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use parking_lot::RwLock;
+
+use crate::locals::locale;
+
+/// A language with an optional region, e.g. `pt-BR`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Locale {
+    /// Lowercase ISO 639 code (`"en"`).
+    pub language: String,
+    /// Uppercase ISO 3166 code or UN M.49 number (`"US"`, `"419"`).
+    pub region: Option<String>,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self {
+            language: "en".into(),
+            region: Some("US".into()),
+        }
+    }
+}
+
+impl Locale {
+    pub fn new(language: &str, region: Option<&str>) -> Self {
+        Self {
+            language: language.to_ascii_lowercase(),
+            region: region.map(|r| r.to_ascii_uppercase()),
+        }
+    }
+
+    /// Parses BCP 47 (`"zh-Hant-TW"`) and POSIX (`"de_DE.UTF-8@euro"`) tags. Scripts and
+    /// variants are dropped; `"C"` and `"POSIX"` have no language and return `None`.
+    pub fn parse(tag: &str) -> Option<Self> {
+        let tag = tag.split(['.', '@']).next().unwrap_or("");
+        let mut parts = tag.split(['-', '_']);
+        let language = parts.next()?;
+        if !(2..=3).contains(&language.len()) || !language.chars().all(|c| c.is_ascii_alphabetic())
+        {
+            return None;
+        }
+        let region = parts.find(|p| {
+            (p.len() == 2 && p.chars().all(|c| c.is_ascii_alphabetic()))
+                || (p.len() == 3 && p.chars().all(|c| c.is_ascii_digit()))
+        });
+        Some(Self::new(language, region))
+    }
+
+    /// BCP 47 form, e.g. `"en-US"`.
+    pub fn tag(&self) -> String {
+        self.to_string()
+    }
+
+    fn is(&self, languages: &[&str]) -> bool {
+        languages.contains(&self.language.as_str())
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.region {
+            Some(r) => write!(f, "{}-{}", self.language, r),
+            None => f.write_str(&self.language),
+        }
+    }
+}
+
+// ---- Plurals ----
+
+/// CLDR plural categories.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            Self::Zero => "zero",
+            Self::One => "one",
+            Self::Two => "two",
+            Self::Few => "few",
+            Self::Many => "many",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// Cardinal plural category of `n` in `locale`.
+pub fn plural_category(locale: &Locale, n: f64) -> PluralCategory {
+    use PluralCategory::*;
+
+    let n = n.abs();
+    let integer = n.fract() == 0.0;
+    let i = n.trunc() as u64;
+    let (i10, i100) = (i % 10, i % 100);
+
+    if locale.is(&[
+        "ja", "zh", "ko", "vi", "th", "id", "ms", "lo", "my", "km", "yue",
+    ]) {
+        Other
+    } else if locale.is(&["fr", "pt", "hi", "bn", "fa", "gu", "kn", "zu", "am"]) {
+        if i <= 1 { One } else { Other }
+    } else if locale.is(&["ru", "uk", "be"]) {
+        if !integer {
+            Other
+        } else if i10 == 1 && i100 != 11 {
+            One
+        } else if (2..=4).contains(&i10) && !(12..=14).contains(&i100) {
+            Few
+        } else {
+            Many
+        }
+    } else if locale.is(&["pl"]) {
+        if !integer {
+            Other
+        } else if i == 1 {
+            One
+        } else if (2..=4).contains(&i10) && !(12..=14).contains(&i100) {
+            Few
+        } else {
+            Many
+        }
+    } else if locale.is(&["cs", "sk"]) {
+        match (integer, i) {
+            (false, _) => Many,
+            (true, 1) => One,
+            (true, 2..=4) => Few,
+            _ => Other,
+        }
+    } else if locale.is(&["ar"]) {
+        match (integer, i, i100) {
+            (false, ..) => Other,
+            (true, 0, _) => Zero,
+            (true, 1, _) => One,
+            (true, 2, _) => Two,
+            (true, _, 3..=10) => Few,
+            (true, _, 11..=99) => Many,
+            _ => Other,
+        }
+    } else if locale.is(&["he", "iw"]) {
+        match (integer, i) {
+            (true, 1) => One,
+            (true, 2) => Two,
+            _ => Other,
+        }
+    } else if integer && i == 1 {
+        One
+    } else {
+        Other
+    }
+}
+
+// ---- Messages ----
+
+/// A value substituted into a message pattern.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MessageArg {
+    Num(f64),
+    Str(String),
+}
+
+macro_rules! message_arg_from_num {
+    ($($t:ty),*) => {$(
+        impl From<$t> for MessageArg {
+            fn from(v: $t) -> Self {
+                MessageArg::Num(v as f64)
+            }
+        }
+    )*};
+}
+message_arg_from_num!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+impl From<&str> for MessageArg {
+    fn from(v: &str) -> Self {
+        MessageArg::Str(v.to_string())
+    }
+}
+impl From<String> for MessageArg {
+    fn from(v: String) -> Self {
+        MessageArg::Str(v)
+    }
+}
+impl From<&String> for MessageArg {
+    fn from(v: &String) -> Self {
+        MessageArg::Str(v.clone())
+    }
+}
+
+impl MessageArg {
+    fn display(&self, locale: &Locale) -> String {
+        match self {
+            MessageArg::Num(n) => format_number_in(locale, *n, shortest_decimals(*n)),
+            MessageArg::Str(s) => s.clone(),
+        }
+    }
+}
+
+/// Formats `pattern` with `args` for `locale`. Unknown arguments are left as written.
+pub fn format_message(locale: &Locale, pattern: &str, args: &[(&str, MessageArg)]) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    format_into(&mut out, locale, pattern, args, None);
+    out
+}
+
+fn format_into(
+    out: &mut String,
+    locale: &Locale,
+    pattern: &str,
+    args: &[(&str, MessageArg)],
+    hash: Option<f64>,
+) {
+    let mut rest = pattern;
+    while let Some(at) = rest.find(['{', '#']) {
+        out.push_str(&rest[..at]);
+        let tail = &rest[at..];
+        if let Some(after) = tail.strip_prefix('#') {
+            match hash {
+                Some(n) => out.push_str(&format_number_in(locale, n, shortest_decimals(n))),
+                None => out.push('#'),
+            }
+            rest = after;
+            continue;
+        }
+        let Some(end) = matching_brace(tail) else {
+            // Unbalanced: emit the remainder verbatim.
+            out.push_str(tail);
+            return;
+        };
+        format_placeholder(out, locale, &tail[1..end], args);
+        rest = &tail[end + 1..];
+    }
+    out.push_str(rest);
+}
+
+/// Index of the `}` closing the `{` at the start of `s`.
+fn matching_brace(s: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn format_placeholder(out: &mut String, locale: &Locale, body: &str, args: &[(&str, MessageArg)]) {
+    let mut parts = body.splitn(3, ',');
+    let name = parts.next().unwrap_or("").trim();
+    let kind = parts.next().map(str::trim);
+    let style = parts.next().unwrap_or("");
+
+    let Some(arg) = args.iter().find(|(k, _)| *k == name).map(|(_, v)| v) else {
+        out.push('{');
+        out.push_str(body);
+        out.push('}');
+        return;
+    };
+
+    match (kind, arg) {
+        (Some("plural"), MessageArg::Num(n)) => {
+            let (offset, cases) = split_offset(style);
+            let cases = parse_cases(cases);
+            let exact = cases.iter().find(|(sel, _)| {
+                sel.strip_prefix('=')
+                    .and_then(|v| v.parse::<f64>().ok())
+                    .is_some_and(|v| v == *n)
+            });
+            let category = plural_category(locale, n - offset).keyword();
+            let chosen = exact
+                .or_else(|| cases.iter().find(|(sel, _)| *sel == category))
+                .or_else(|| cases.iter().find(|(sel, _)| *sel == "other"));
+            if let Some((_, msg)) = chosen {
+                format_into(out, locale, msg, args, Some(n - offset));
+            }
+        }
+        (Some("select"), _) => {
+            let value = arg.display(locale);
+            let cases = parse_cases(style);
+            let chosen = cases
+                .iter()
+                .find(|(sel, _)| *sel == value)
+                .or_else(|| cases.iter().find(|(sel, _)| *sel == "other"));
+            if let Some((_, msg)) = chosen {
+                format_into(out, locale, msg, args, None);
+            }
+        }
+        _ => out.push_str(&arg.display(locale)),
+    }
+}
+
+fn split_offset(style: &str) -> (f64, &str) {
+    let s = style.trim_start();
+    if let Some(rest) = s.strip_prefix("offset:") {
+        let rest = rest.trim_start();
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        (rest[..end].parse().unwrap_or(0.0), &rest[end..])
+    } else {
+        (0.0, s)
+    }
+}
+
+/// Parses `sel {message} sel {message} ...`.
+fn parse_cases(s: &str) -> Vec<(&str, &str)> {
+    let mut cases = Vec::new();
+    let mut rest = s;
+    loop {
+        rest = rest.trim_start();
+        let Some(open) = rest.find('{') else { break };
+        let selector = rest[..open].trim();
+        let Some(close) = matching_brace(&rest[open..]) else {
+            break;
+        };
+        cases.push((selector, &rest[open + 1..open + close]));
+        rest = &rest[open + close + 1..];
+    }
+    cases
+}
+
+// ---- String tables ----
+
+type Tables = HashMap<String, HashMap<String, String>>;
+
+static STRINGS: OnceLock<RwLock<Tables>> = OnceLock::new();
+
+fn strings() -> &'static RwLock<Tables> {
+    STRINGS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Adds (or replaces) message patterns for a locale tag. A bare language (`"de"`) serves
+/// every region that has no table of its own.
+pub fn register_strings<K: Into<String>, V: Into<String>>(
+    locale_tag: &str,
+    entries: impl IntoIterator<Item = (K, V)>,
+) {
+    let tag = Locale::parse(locale_tag)
+        .map(|l| l.tag())
+        .unwrap_or_else(|| locale_tag.to_string());
+    let mut tables = strings().write();
+    let table = tables.entry(tag).or_default();
+    for (k, v) in entries {
+        table.insert(k.into(), v.into());
+    }
+}
+
+/// Looks up `key` for the current locale (exact tag, then language) and formats it. A
+/// missing key formats the key itself, so untranslated UI stays readable.
+pub fn translate(key: &str, args: &[(&str, MessageArg)]) -> String {
+    let loc = locale();
+    let pattern = {
+        let tables = strings().read();
+        [loc.tag(), loc.language.clone()]
+            .iter()
+            .find_map(|t| tables.get(t).and_then(|tbl| tbl.get(key)).cloned())
+    };
+    format_message(&loc, pattern.as_deref().unwrap_or(key), args)
+}
+
+/// Looks up and formats a localized string: `tr!("key")` or `tr!("key", count = n, ...)`.
+#[macro_export]
+macro_rules! tr {
+    ($key:expr $(,)?) => {
+        $crate::i18n::translate($key, &[])
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::translate(
+            $key,
+            &[$((stringify!($name), $crate::i18n::MessageArg::from($value))),+],
+        )
+    };
+}
+
+// ---- Numbers and dates ----
+
+const NBSP: char = '\u{a0}';
+const NARROW_NBSP: char = '\u{202f}';
+
+/// Decimal separator and digit-group separator.
+fn number_symbols(locale: &Locale) -> (char, char) {
+    if locale.is(&[
+        "de", "es", "it", "nl", "pt", "id", "tr", "da", "el", "ro", "hr", "sl", "sr",
+    ]) {
+        (',', '.')
+    } else if locale.is(&["fr"]) {
+        (',', NARROW_NBSP)
+    } else if locale.is(&[
+        "ru", "uk", "be", "pl", "cs", "sk", "sv", "nb", "no", "fi", "hu", "bg", "lt", "lv", "et",
+    ]) {
+        (',', NBSP)
+    } else {
+        ('.', ',')
+    }
+}
+
+fn shortest_decimals(n: f64) -> usize {
+    (0..=6)
+        .find(|&d| {
+            let scale = 10f64.powi(d as i32);
+            ((n * scale).round() / scale - n).abs() < 1e-9
+        })
+        .unwrap_or(6)
+}
+
+fn format_number_in(locale: &Locale, value: f64, decimals: usize) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+    let (decimal, group) = number_symbols(locale);
+    let fixed = format!("{:.*}", decimals, value.abs());
+    let (int_part, frac_part) = fixed.split_once('.').unwrap_or((&fixed, ""));
+
+    let mut out = String::with_capacity(fixed.len() + 4);
+    if value.is_sign_negative() && fixed.bytes().any(|b| (b'1'..=b'9').contains(&b)) {
+        out.push('-');
+    }
+    for (i, c) in int_part.chars().enumerate() {
+        if i > 0 && (int_part.len() - i).is_multiple_of(3) {
+            out.push(group);
+        }
+        out.push(c);
+    }
+    if !frac_part.is_empty() {
+        out.push(decimal);
+        out.push_str(frac_part);
+    }
+    out
+}
+
+/// Formats `value` with `decimals` fraction digits and the current locale's separators.
+pub fn format_number(value: f64, decimals: usize) -> String {
+    format_number_in(&locale(), value, decimals)
+}
+
+/// Formats a 0..=1 `fraction` as a percentage, e.g. `"42%"` or `"42 %"`.
+pub fn format_percent(fraction: f64, decimals: usize) -> String {
+    let loc = locale();
+    let n = format_number_in(&loc, fraction * 100.0, decimals);
+    if loc.is(&[
+        "de", "fr", "es", "sv", "nb", "no", "da", "fi", "cs", "sk", "ru", "uk", "pl", "nl",
+    ]) {
+        format!("{n}{NBSP}%")
+    } else {
+        format!("{n}%")
+    }
+}
+
+/// Formats a calendar date in the current locale's numeric short form (`3/7/2025`,
+/// `07.03.2025`, `2025/03/07`, ...). `month` and `day` are 1-based.
+pub fn format_date(year: i32, month: u32, day: u32) -> String {
+    let loc = locale();
+    let region = loc.region.as_deref();
+    if loc.is(&["en"]) && matches!(region, None | Some("US")) {
+        format!("{month}/{day}/{year}")
+    } else if loc.is(&["ja", "zh"]) {
+        format!("{year}/{month:02}/{day:02}")
+    } else if loc.is(&["ko", "hu"]) {
+        format!("{year}. {month:02}. {day:02}.")
+    } else if loc.is(&["sv", "lt"]) || (loc.is(&["en"]) && region == Some("CA")) {
+        format!("{year}-{month:02}-{day:02}")
+    } else if loc.is(&["nl"]) {
+        format!("{day:02}-{month:02}-{year}")
+    } else if loc.is(&[
+        "de", "ru", "uk", "be", "pl", "cs", "sk", "fi", "nb", "no", "da", "tr", "ro", "bg",
+    ]) {
+        format!("{day:02}.{month:02}.{year}")
+    } else {
+        format!("{day:02}/{month:02}/{year}")
+    }
+}
@@ -1,3 +1,6 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+
 use crate::Vec2;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -22,6 +25,8 @@ pub enum PointerEventKind {
     Down(PointerButton),
     Up(PointerButton),
     Move,
+    /// The gesture was taken away (e.g. by a scroll container) or aborted. Delivered to
+    /// `on_pointer_up`; no click follows.
     Cancel,
     Enter,
     Leave,
@@ -45,13 +50,37 @@ pub struct Modifiers {
     pub meta: bool, // Cmd on Mac, Win key on Windows
 }
 
-#[derive(Clone, Debug)]
+impl Modifiers {
+    /// The shortcut modifier: Cmd on macOS (Cmd+C, Cmd+V, Cmd+A…), Ctrl elsewhere.
+    pub fn command(&self) -> bool {
+        if cfg!(target_os = "macos") {
+            self.meta
+        } else {
+            self.ctrl
+        }
+    }
+
+    /// The modifier that makes caret moves and deletes go by word: Option on macOS, Ctrl
+    /// elsewhere.
+    pub fn word(&self) -> bool {
+        if cfg!(target_os = "macos") {
+            self.alt
+        } else {
+            self.ctrl
+        }
+    }
+}
+
+/// Logical key: what the key means under the current layout, not where it is on the board.
+/// Digits on the numeric keypad are `Character`s with [`KeyLocation::Numpad`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Key {
     Character(char),
     Enter,
     Tab,
     Backspace,
     Delete,
+    Insert,
     Escape,
     ArrowLeft,
     ArrowRight,
@@ -62,14 +91,69 @@ pub enum Key {
     PageUp,
     PageDown,
     Space,
-    F(u8), // F1-F12
+    F(u8), // F1-F35
+    Shift,
+    Control,
+    Alt,
+    Meta,
+    CapsLock,
+    NumLock,
+    ScrollLock,
+    PrintScreen,
+    Pause,
+    ContextMenu,
+    MediaPlayPause,
+    MediaPlay,
+    MediaPause,
+    MediaStop,
+    MediaTrackNext,
+    MediaTrackPrevious,
+    AudioVolumeUp,
+    AudioVolumeDown,
+    AudioVolumeMute,
+    BrowserBack,
+    BrowserForward,
+    /// A dead key of a compose sequence, with the accent it adds when known.
+    Dead(Option<char>),
+    /// Any other named key, by its platform name (e.g. `"LaunchMail"`), or text that is
+    /// more than one character.
+    Other(String),
+    Unidentified,
+}
+
+/// Which of several keys with the same meaning was used.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum KeyLocation {
+    #[default]
+    Standard,
+    Left,
+    Right,
+    Numpad,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyState {
+    #[default]
+    Pressed,
+    Released,
 }
 
 #[derive(Clone, Debug)]
 pub struct KeyEvent {
     pub key: Key,
+    pub location: KeyLocation,
+    pub state: KeyState,
     pub modifiers: Modifiers,
+    /// Auto-repeat of a held key; the runner times these with `PlatformSettings`.
     pub is_repeat: bool,
+    /// Text the key produces, if any (`None` for releases and non-text keys).
+    pub text: Option<String>,
+}
+
+impl KeyEvent {
+    pub fn is_pressed(&self) -> bool {
+        self.state == KeyState::Pressed
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -99,3 +183,28 @@ pub enum InputEvent {
     Text(TextInputEvent),
     Ime(ImeEvent),
 }
+
+thread_local! {
+    static KEEP_GESTURE: RefCell<HashSet<PointerId>> = RefCell::new(HashSet::new());
+}
+
+/// Asks scroll containers not to take `pointer`'s gesture from the view handling it.
+///
+/// A touch that lands on a view is undecided until it moves past the touch slop; then a
+/// scroll container under it takes over (and the view gets a `Cancel`) if it can scroll in
+/// the direction the finger set out in. A view that must keep the gesture whatever the
+/// direction, like a drawing surface, calls this from `on_pointer_down` or
+/// `on_pointer_move`. The request lasts until the pointer is lifted.
+pub fn request_disallow_intercept(pointer: PointerId) {
+    KEEP_GESTURE.with(|k| k.borrow_mut().insert(pointer));
+}
+
+/// Whether the view handling `pointer` asked to keep its gesture.
+pub fn intercept_disallowed(pointer: PointerId) -> bool {
+    KEEP_GESTURE.with(|k| k.borrow().contains(&pointer))
+}
+
+/// Forgets [`request_disallow_intercept`] for `pointer`; runners call this when it's lifted.
+pub fn end_gesture(pointer: PointerId) {
+    KEEP_GESTURE.with(|k| k.borrow_mut().remove(&pointer));
+}
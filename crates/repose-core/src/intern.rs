@@ -0,0 +1,184 @@
+//! # Interned strings
+//!
+//! Scene text is mostly the same from frame to frame. Painting it through [`intern`] hands
+//! out one shared allocation per distinct string instead of a fresh `String` per node, and
+//! gives it an [`InternedStr::id`] that stays the same for as long as the string is in use,
+//! so renderers can key shaping and glyph-run caches on the id rather than hashing the text
+//! again every frame.
+//!
+//! The table is per thread, but ids are unique across threads. At the start of each frame
+//! the scheduler drops strings that no scene refers to and that the last frame didn't paint;
+//! a string that comes back after that gets a new id. [`take_swept_text`] hands the dropped
+//! strings to whoever caches work per string, such as the text shaper, so it can let go of
+//! them too.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// An immutable string shared through the thread's intern table.
+#[derive(Clone)]
+pub struct InternedStr {
+    id: u64,
+    text: Arc<str>,
+}
+
+struct Entry {
+    id: u64,
+    /// Interned since the last sweep.
+    used: bool,
+}
+
+// Shared by every thread's table, so an id never names two different strings.
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static TABLE: RefCell<HashMap<Arc<str>, Entry>> = RefCell::new(HashMap::new());
+    /// What the latest sweep dropped.
+    static SWEPT: RefCell<Vec<Arc<str>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The interned copy of `s`.
+pub fn intern(s: &str) -> InternedStr {
+    TABLE.with(|t| {
+        let mut t = t.borrow_mut();
+        if let Some((text, e)) = t.get_key_value(s) {
+            let out = InternedStr {
+                id: e.id,
+                text: text.clone(),
+            };
+            if let Some(e) = t.get_mut(s) {
+                e.used = true;
+            }
+            return out;
+        }
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let text: Arc<str> = Arc::from(s);
+        t.insert(text.clone(), Entry { id, used: true });
+        InternedStr { id, text }
+    })
+}
+
+/// Drops the strings nothing holds any more and that weren't interned since the last
+/// sweep, so text a runner drops along with its frame keeps its id into the next one.
+/// Returns how many were dropped.
+pub(crate) fn sweep_interned() -> usize {
+    let mut swept = Vec::new();
+    TABLE.with(|t| {
+        t.borrow_mut().retain(|text, e| {
+            let keep = std::mem::take(&mut e.used) || Arc::strong_count(text) > 1;
+            if !keep {
+                swept.push(text.clone());
+            }
+            keep
+        })
+    });
+    let n = swept.len();
+    SWEPT.with(|s| *s.borrow_mut() = swept);
+    n
+}
+
+/// The strings the latest sweep dropped: text that left the UI a frame ago. Each sweep
+/// replaces the list, so callers take it every frame.
+pub fn take_swept_text() -> Vec<Arc<str>> {
+    SWEPT.with(|s| std::mem::take(&mut *s.borrow_mut()))
+}
+
+impl InternedStr {
+    /// Identifies the string's contents while it stays interned.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Deref for InternedStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl AsRef<str> for InternedStr {
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl From<&str> for InternedStr {
+    fn from(s: &str) -> Self {
+        intern(s)
+    }
+}
+
+impl From<String> for InternedStr {
+    fn from(s: String) -> Self {
+        intern(&s)
+    }
+}
+
+impl From<&String> for InternedStr {
+    fn from(s: &String) -> Self {
+        intern(s)
+    }
+}
+
+impl PartialEq for InternedStr {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id || self.text == other.text
+    }
+}
+
+impl Eq for InternedStr {}
+
+impl PartialEq<str> for InternedStr {
+    fn eq(&self, other: &str) -> bool {
+        &*self.text == other
+    }
+}
+
+impl PartialEq<&str> for InternedStr {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.text == *other
+    }
+}
+
+impl PartialEq<String> for InternedStr {
+    fn eq(&self, other: &String) -> bool {
+        *self.text == **other
+    }
+}
+
+impl fmt::Debug for InternedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.text, f)
+    }
+}
+
+impl fmt::Display for InternedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.text, f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for InternedStr {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&self.text)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for InternedStr {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(d)?;
+        Ok(intern(&s))
+    }
+}
@@ -0,0 +1,283 @@
+//! # Window layers
+//!
+//! Floating UI that must sit above the whole window (toasts, drag ghosts, suggestion
+//! strips) can't be expressed as a child of whatever composable wants it: the parent's clip,
+//! z-order and hit-testing would all apply. Instead, composables declare it with
+//! [`show_layer`] during composition, and `Scheduler::repose` lays out each layer as its own
+//! window-sized root after the main tree. Layer output is appended to the frame, so layers
+//! paint above everything and are hit-tested first.
+//!
+//! Layers are declarative like the rest of the tree: one that isn't shown in a frame is gone.
+//! They can also be shown while a tree is being painted, for overlays that depend on where
+//! things landed (tooltips, text selection handles). Such a layer is painted above the layers
+//! painted before it, and a modal layer painted later drops it along with the input it blocks.
+//!
+//! While a modal layer with something focusable is shown, focus stays inside it: the
+//! scheduler moves focus into the layer when it opens and gives it back to the view that had
+//! it once the layer is gone.
+//! Runners send Escape (and Back) to the topmost layer's `on_dismiss` first, looking no lower
+//! than the topmost modal layer.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::{Brush, Color, HitRegion, Rect, Scene, SceneNode, SemNode, Vec2, View};
+
+/// How a layer stacks and whether it contains input.
+#[derive(Clone, Default)]
+pub struct LayerOptions {
+    /// Stacking order among layers; higher is on top. Ties keep declaration order.
+    pub z: i32,
+    /// Blocks pointer input to everything underneath and limits the focus chain to this
+    /// layer (the topmost modal layer wins).
+    pub modal: bool,
+    /// Called when a modal layer's backdrop (anything outside its content) is clicked, and
+    /// on Escape or Back while this is the topmost layer with an `on_dismiss` and no modal
+    /// layer is above it. A non-modal popup uses it to close on Back.
+    pub on_dismiss: Option<Rc<dyn Fn()>>,
+    /// Painted over the whole window underneath the layer's content, to dim what's behind.
+    pub scrim: Option<Color>,
+}
+
+impl LayerOptions {
+    pub fn z(mut self, z: i32) -> Self {
+        self.z = z;
+        self
+    }
+    pub fn modal(mut self) -> Self {
+        self.modal = true;
+        self
+    }
+    pub fn on_dismiss(mut self, f: impl Fn() + 'static) -> Self {
+        self.on_dismiss = Some(Rc::new(f));
+        self
+    }
+    pub fn scrim(mut self, color: Color) -> Self {
+        self.scrim = Some(color);
+        self
+    }
+}
+
+#[derive(Clone)]
+pub struct Layer {
+    pub key: String,
+    pub options: LayerOptions,
+    pub content: View,
+}
+
+impl Layer {
+    /// Stable id for this layer's root, so its subtree keeps ViewIds distinct from the main
+    /// tree (and from other layers) across frames.
+    pub fn root_key(&self) -> u64 {
+        layer_root_key(&self.key)
+    }
+}
+
+/// [`Layer::root_key`] for a layer shown with `key`, so a painter can tell that it's laying
+/// out that layer (`View::modifier.key` of the root it gets).
+pub fn layer_root_key(key: &str) -> u64 {
+    // FNV-1a
+    key.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The layers declared for the current frame, owned by the `Scheduler`.
+#[derive(Default)]
+pub struct LayerHost {
+    layers: Vec<Layer>,
+    /// Keys of layers shown while painting this frame.
+    late: Vec<String>,
+    /// Focus from before a modal layer took it; `Some(None)` when nothing was focused.
+    return_focus: Option<Option<u64>>,
+}
+
+thread_local! {
+    static PENDING: RefCell<Vec<Layer>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Declares a window-level layer for this frame. Showing the same `key` twice keeps the
+/// last declaration, unless the first one has already been painted.
+///
+/// `content` is laid out as a window-sized root; place it with alignment or
+/// `Modifier::absolute().offset(..)`. Only the content's own hit regions take input, so a
+/// non-modal layer doesn't block the UI around it.
+pub fn show_layer(key: impl Into<String>, options: LayerOptions, content: View) {
+    show(Layer {
+        key: key.into(),
+        options,
+        content,
+    });
+}
+
+fn show(layer: Layer) {
+    crate::runtime::record_declaration(|| {
+        let layer = layer.clone();
+        move || show(layer.clone())
+    });
+    PENDING.with(|p| {
+        let mut p = p.borrow_mut();
+        p.retain(|l| l.key != layer.key);
+        p.push(layer);
+    });
+}
+
+impl LayerHost {
+    /// Layers in paint order (bottom first).
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Calls `on_dismiss` of the topmost layer that has one, unless a modal layer without
+    /// one is above it. Returns whether there was one to call.
+    pub fn dismiss_modal(&self) -> bool {
+        let top = self
+            .layers
+            .iter()
+            .rev()
+            .find(|l| l.options.modal || l.options.on_dismiss.is_some());
+        match top.and_then(|l| l.options.on_dismiss.as_ref()) {
+            Some(f) => {
+                f();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Keeps `focused` inside a modal layer (`modal`, whose focusables are `chain`): moves
+    /// it to the layer's first focusable when it is outside, and back to where it was
+    /// once no modal layer is shown. A modal layer with nothing focusable leaves focus be.
+    pub(crate) fn trap_focus(&mut self, focused: &mut Option<u64>, modal: bool, chain: &[u64]) {
+        if modal {
+            if chain.is_empty() {
+                return;
+            }
+            if focused.is_none_or(|id| !chain.contains(&id)) {
+                self.return_focus.get_or_insert(*focused);
+                *focused = chain.first().copied();
+            }
+        } else if let Some(before) = self.return_focus.take() {
+            *focused = before.filter(|id| chain.contains(id));
+        }
+    }
+
+    pub(crate) fn begin_frame(&mut self) {
+        self.layers.clear();
+        self.late.clear();
+        PENDING.with(|p| p.borrow_mut().clear());
+    }
+
+    pub(crate) fn end_composition(&mut self) {
+        self.layers = PENDING.with(|p| std::mem::take(&mut *p.borrow_mut()));
+        self.layers.sort_by_key(|l| l.options.z);
+    }
+
+    /// Lays out every layer with `layout_paint` and appends it on top of the main frame,
+    /// along with the layers shown while painting. Returns the focus chain, restricted to
+    /// the topmost modal layer if there is one.
+    pub(crate) fn paint_over(
+        &mut self,
+        layout_paint: &impl Fn(&View, (u32, u32)) -> (Scene, Vec<HitRegion>, Vec<SemNode>),
+        size: (u32, u32),
+        scene: &mut Scene,
+        hits: &mut Vec<HitRegion>,
+        sems: &mut Vec<SemNode>,
+    ) -> Option<Vec<u64>> {
+        let mut modal_focus = None;
+        self.adopt_late(0);
+        let mut painted = 0;
+        while painted < self.layers.len() {
+            if self.layers[painted].options.modal {
+                // Overlays of what's underneath go with its input.
+                let late = std::mem::take(&mut self.late);
+                let mut i = painted + 1;
+                while i < self.layers.len() {
+                    if late.contains(&self.layers[i].key) {
+                        self.layers.remove(i);
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+            let layer = &self.layers[painted];
+            let mut root = layer.content.clone();
+            root.modifier.key = Some(layer.root_key());
+            let (s, h, sem) = layout_paint(&root, size);
+
+            if let Some(color) = layer.options.scrim {
+                scene.nodes.push(SceneNode::Rect {
+                    rect: window_rect(size),
+                    brush: Brush::Solid(color),
+                    radius: 0.0,
+                });
+            }
+            if layer.options.modal {
+                hits.push(backdrop(layer, size));
+                modal_focus = Some(h.iter().filter(|h| h.focusable).map(|h| h.id).collect());
+            }
+            scene.nodes.extend(s.nodes);
+            hits.extend(h);
+            sems.extend(sem);
+
+            painted += 1;
+            self.adopt_late(painted);
+        }
+        modal_focus
+    }
+
+    /// Moves layers shown since composition ended into the unpainted part of the stack
+    /// (`self.layers[painted..]`), by z. A key that's already painted keeps that paint.
+    fn adopt_late(&mut self, painted: usize) {
+        let shown = PENDING.with(|p| std::mem::take(&mut *p.borrow_mut()));
+        for layer in shown {
+            if self.layers[..painted].iter().any(|l| l.key == layer.key) {
+                continue;
+            }
+            self.layers.retain(|l| l.key != layer.key);
+            let above = self.layers[painted..]
+                .iter()
+                .take_while(|l| l.options.z <= layer.options.z)
+                .count();
+            if !self.late.contains(&layer.key) {
+                self.late.push(layer.key.clone());
+            }
+            self.layers.insert(painted + above, layer);
+        }
+    }
+}
+
+/// A window-sized hit region under a modal layer's content that swallows clicks and wheel.
+fn backdrop(layer: &Layer, size: (u32, u32)) -> HitRegion {
+    let on_dismiss = layer.options.on_dismiss.clone();
+    HitRegion {
+        id: layer.root_key() ^ 0x6c61_7965_725f_6264,
+        rect: window_rect(size),
+        on_click: on_dismiss,
+        on_scroll: Some(Rc::new(|_| Vec2::default())),
+        focusable: false,
+        on_pointer_down: None,
+        on_pointer_move: None,
+        on_pointer_up: None,
+        on_pointer_enter: None,
+        on_pointer_leave: None,
+        on_key_event: None,
+        z_index: 0.0,
+        on_text_change: None,
+        on_text_submit: None,
+        tf_state_key: None,
+    }
+}
+
+fn window_rect(size: (u32, u32)) -> Rect {
+    Rect {
+        x: 0.0,
+        y: 0.0,
+        w: size.0 as f32,
+        h: size.1 as f32,
+    }
+}
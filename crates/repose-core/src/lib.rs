@@ -92,44 +92,80 @@
 //! - `scoped_effect` is wired to the current `Scope` and is cleaned up on
 //!   scope disposal (e.g. when a navigation entry is popped).
 //!
-//! For long‑running tasks (network, timers), prefer building small helpers on
-//! top of `scoped_effect` so everything cleans up correctly when the UI that
-//! owns it disappears.
+//! For long‑running async work (network, timers, debounces), spawn futures on
+//! a `task_scope()`: they are cancelled when the UI that owns them disappears.
 
 pub mod animation;
+pub mod annotated_text;
+pub mod bus;
 pub mod color;
+pub mod debounce;
 pub mod effects;
 pub mod effects_ext;
 pub mod error;
+pub mod frame;
+pub mod frame_clock;
 pub mod geometry;
+pub mod i18n;
 pub mod input;
+pub mod intern;
+pub mod layers;
+pub mod live_edit;
 pub mod locals;
 pub mod modifier;
+pub mod pixmap;
+pub mod power;
 pub mod prelude;
 pub mod reactive;
 pub mod render_api;
+pub mod retained;
 pub mod runtime;
+pub mod saved;
 pub mod scope;
 pub mod semantics;
 pub mod signal;
+pub mod snapshot;
 pub mod state;
+pub mod stats;
+pub mod tasks;
 pub mod tests;
+pub mod text_diagnostics;
 pub mod view;
+pub mod visual_transformation;
+pub mod widget;
 
+pub use annotated_text::*;
+pub use bus::*;
 pub use color::*;
+pub use debounce::*;
 pub use effects::*;
 pub use effects_ext::*;
+pub use frame_clock::*;
 pub use geometry::*;
+pub use i18n::*;
+pub use intern::*;
+pub use layers::*;
+pub use live_edit::*;
 pub use locals::*;
 pub use modifier::*;
+pub use pixmap::*;
+pub use power::*;
 pub use prelude::*;
 pub use reactive::*;
 pub use render_api::*;
+pub use retained::*;
 pub use runtime::*;
+pub use saved::*;
 pub use semantics::*;
 pub use signal::*;
+pub use snapshot::*;
 pub use state::*;
+pub use stats::*;
+pub use tasks::*;
+pub use text_diagnostics::*;
 pub use view::*;
+pub use visual_transformation::*;
+pub use widget::*;
 
 // Ensure a clock is installed even if platform didn't (tests, benches).
 #[doc(hidden)]
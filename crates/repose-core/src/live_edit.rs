@@ -0,0 +1,154 @@
+//! # Live editing
+//!
+//! Lets the devtools inspector tweak a running app without recompiling. An edit is a
+//! [`ModifierOverride`] registered for a view id; the layout backend applies it right after
+//! ids are assigned each frame, so the change shows up on the next frame and nothing in app
+//! code has to cooperate. The backend also reports the effective values of the view picked
+//! with [`set_inspected`], which is what the inspector panel shows. Until something is
+//! edited or inspected, that per-view step is a single flag check.
+//!
+//! Theme tokens are edited through [`edit_theme_default`]. Subtrees under an explicit
+//! `with_theme` keep the theme they were given.
+//!
+//! ```rust
+//! use repose_core::*;
+//!
+//! set_modifier_override(42, ModifierOverride { padding: Some(24.0), ..Default::default() });
+//! let m = apply_live_edits(42, Modifier::new().padding(8.0));
+//! assert_eq!(m.total_padding().unwrap().left, 24.0);
+//! clear_modifier_overrides();
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use crate::{Brush, Color, Modifier, Theme, ViewId, locals};
+
+/// Modifier values the inspector can replace on one view, in dp. `None` keeps the view's own.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ModifierOverride {
+    /// Uniform padding, replacing the view's own.
+    pub padding: Option<f32>,
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+    /// Solid background, replacing the view's own.
+    pub background: Option<Color>,
+}
+
+impl ModifierOverride {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The values `m` has for the fields this type covers. Padding is reported only when
+    /// it is the same on all sides.
+    pub fn of(m: &Modifier) -> Self {
+        let padding = m
+            .total_padding()
+            .filter(|p| p.left == p.right && p.left == p.top && p.left == p.bottom)
+            .map(|p| p.left);
+        let background = match m.background {
+            Some(Brush::Solid(c)) => Some(c),
+            _ => None,
+        };
+        Self {
+            padding,
+            width: m.width.or(m.size.map(|s| s.width)),
+            height: m.height.or(m.size.map(|s| s.height)),
+            background,
+        }
+    }
+
+    /// `m` with the set fields in place of its own. Padding and background keep their place
+    /// in the chain.
+    pub fn apply(&self, mut m: Modifier) -> Modifier {
+        if let Some(p) = self.padding {
+            m.padding = Some(p);
+            m.padding_values = None;
+        }
+        if let Some(w) = self.width {
+            m = m.width(w);
+        }
+        if let Some(h) = self.height {
+            m = m.height(h);
+        }
+        if let Some(c) = self.background {
+            m.background = Some(Brush::Solid(c));
+        }
+        m
+    }
+}
+
+thread_local! {
+    static OVERRIDES: RefCell<HashMap<ViewId, ModifierOverride>> = RefCell::new(HashMap::new());
+    static INSPECTED: Cell<Option<ViewId>> = const { Cell::new(None) };
+    static INSPECTED_VALUES: Cell<Option<ModifierOverride>> = const { Cell::new(None) };
+    /// Whether there is any edit or inspected view, so frames without devtools skip the lookups.
+    static ACTIVE: Cell<bool> = const { Cell::new(false) };
+}
+
+fn update_active() {
+    let active =
+        INSPECTED.with(|c| c.get()).is_some() || OVERRIDES.with(|m| !m.borrow().is_empty());
+    ACTIVE.with(|c| c.set(active));
+}
+
+/// Replaces view `id`'s modifier values from the next frame on. An empty override removes
+/// the edit.
+pub fn set_modifier_override(id: ViewId, o: ModifierOverride) {
+    OVERRIDES.with(|m| {
+        let mut m = m.borrow_mut();
+        if o.is_empty() {
+            m.remove(&id);
+        } else {
+            m.insert(id, o);
+        }
+    });
+    update_active();
+}
+
+pub fn modifier_override(id: ViewId) -> Option<ModifierOverride> {
+    OVERRIDES.with(|m| m.borrow().get(&id).copied())
+}
+
+/// Drops every modifier edit.
+pub fn clear_modifier_overrides() {
+    OVERRIDES.with(|m| m.borrow_mut().clear());
+    update_active();
+}
+
+/// Picks the view whose effective values [`inspected`] reports, or none.
+pub fn set_inspected(id: Option<ViewId>) {
+    INSPECTED.with(|c| c.set(id));
+    INSPECTED_VALUES.with(|c| c.set(None));
+    update_active();
+}
+
+/// The inspected view and its values as of the last frame that laid it out.
+pub fn inspected() -> Option<(ViewId, ModifierOverride)> {
+    let id = INSPECTED.with(|c| c.get())?;
+    INSPECTED_VALUES.with(|c| c.get()).map(|v| (id, v))
+}
+
+/// Applies view `id`'s edit to `m` and records the result if `id` is being inspected.
+/// Called by the layout backend for every view.
+pub fn apply_live_edits(id: ViewId, m: Modifier) -> Modifier {
+    if !ACTIVE.with(|c| c.get()) {
+        return m;
+    }
+    let m = match modifier_override(id) {
+        Some(o) => o.apply(m),
+        None => m,
+    };
+    if INSPECTED.with(|c| c.get()) == Some(id) {
+        INSPECTED_VALUES.with(|c| c.set(Some(ModifierOverride::of(&m))));
+    }
+    m
+}
+
+/// Edits the app-wide theme in place; views pick it up on the next frame.
+pub fn edit_theme_default(f: impl FnOnce(&mut Theme)) {
+    let mut t = locals::theme_default();
+    f(&mut t);
+    locals::set_theme_default(t);
+}
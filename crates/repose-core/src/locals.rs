@@ -7,18 +7,44 @@
 //! - `UiScale` — app-controlled UI scale multiplier (defaults to 1.0).
 //! - `TextScale` — user text scaling (defaults to 1.0).
 //! - `TextDirection` — LTR or RTL (defaults to LTR).
+//! - `Locale` — language/region for strings and number formatting (platform sets this
+//!   from the OS; see [`crate::i18n`]).
+//! - `MinTouchTarget` — smallest hit area (dp) for built-in controls (defaults to 48).
+//! - `PlatformSettings` — double-click interval, key repeat timing and wheel scroll
+//!   distance. These are Repose's own defaults, not read from the OS; apps override them.
 //!
 //! Locals can be overridden for a subtree with `with_*`. If no local is set,
 //! getters fall back to global defaults (which an app can set each frame).
+//!
+//! Libraries can declare their own with [`composition_local_of`]:
+//!
+//! ```rust
+//! use repose_core::*;
+//!
+//! #[derive(Clone)]
+//! struct Router { base: &'static str }
+//!
+//! static ROUTER: CompositionLocal<Router> = composition_local_of(|| Router { base: "/" });
+//!
+//! assert_eq!(ROUTER.current().base, "/");
+//! provide(&ROUTER, Router { base: "/admin" }, || {
+//!     assert_eq!(ROUTER.current().base, "/admin");
+//! });
+//! ```
 
 use std::any::{Any, TypeId};
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::marker::PhantomData;
 use std::sync::OnceLock;
+use std::time::Duration;
 
 use parking_lot::RwLock;
 
 use crate::Color;
+use crate::animation::{AnimationSpec, Easing};
+use crate::i18n::Locale;
+use crate::modifier::PaddingValues;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub enum TextDirection {
@@ -27,17 +53,34 @@ pub enum TextDirection {
     Rtl,
 }
 
+/// Built-in locals are keyed by type; user locals by the address of their static.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum LocalKey {
+    Builtin(TypeId),
+    User(usize),
+}
+
+impl LocalKey {
+    fn of<T: 'static>() -> Self {
+        LocalKey::Builtin(TypeId::of::<T>())
+    }
+}
+
 thread_local! {
-    static LOCALS_STACK: RefCell<Vec<HashMap<TypeId, Box<dyn Any>>>> = RefCell::new(Vec::new());
+    static LOCALS_STACK: RefCell<Vec<HashMap<LocalKey, Box<dyn Any>>>> = RefCell::new(Vec::new());
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 struct Defaults {
     theme: Theme,
     text_direction: TextDirection,
     ui_scale: UiScale,
     text_scale: TextScale,
     density: Density,
+    locale: Locale,
+    min_touch_target: MinTouchTarget,
+    platform_settings: PlatformSettings,
+    window_insets: WindowInsets,
 }
 
 impl Default for Defaults {
@@ -48,6 +91,10 @@ impl Default for Defaults {
             ui_scale: UiScale::default(),
             text_scale: TextScale::default(),
             density: Density::default(),
+            locale: Locale::default(),
+            min_touch_target: MinTouchTarget::default(),
+            platform_settings: PlatformSettings::default(),
+            window_insets: WindowInsets::default(),
         }
     }
 }
@@ -86,6 +133,29 @@ pub fn set_density_default(d: Density) {
     };
 }
 
+/// Set the global default locale used when no local Locale is active.
+/// Platform runners call this at startup with the OS locale.
+pub fn set_locale_default(l: Locale) {
+    defaults().write().locale = l;
+}
+
+/// Set the global default minimum touch target used when no local MinTouchTarget is active.
+pub fn set_min_touch_target_default(t: MinTouchTarget) {
+    defaults().write().min_touch_target = MinTouchTarget(t.0.max(0.0));
+}
+
+/// Set the global default input settings used when no local PlatformSettings is active.
+/// Runners don't read these from the OS, so this is how an app applies system values.
+pub fn set_platform_settings_default(s: PlatformSettings) {
+    defaults().write().platform_settings = s;
+}
+
+/// Set the global default window insets used when no local WindowInsets is active.
+/// Platform runners call this before each frame as system bars and the keyboard come and go.
+pub fn set_window_insets_default(i: WindowInsets) {
+    defaults().write().window_insets = i;
+}
+
 // ---- Units ----
 
 /// density‑independent pixels (dp)
@@ -118,7 +188,7 @@ fn with_locals_frame<R>(f: impl FnOnce() -> R) -> R {
     f()
 }
 
-fn set_local_boxed(t: TypeId, v: Box<dyn Any>) {
+fn set_local_boxed(t: LocalKey, v: Box<dyn Any>) {
     LOCALS_STACK.with(|st| {
         if let Some(top) = st.borrow_mut().last_mut() {
             top.insert(t, v);
@@ -131,20 +201,24 @@ fn set_local_boxed(t: TypeId, v: Box<dyn Any>) {
     });
 }
 
-fn get_local<T: 'static + Copy>() -> Option<T> {
+fn get_local<T: 'static + Clone>() -> Option<T> {
+    lookup(LocalKey::of::<T>())
+}
+
+fn lookup<T: 'static + Clone>(key: LocalKey) -> Option<T> {
     LOCALS_STACK.with(|st| {
         for frame in st.borrow().iter().rev() {
-            if let Some(v) = frame.get(&TypeId::of::<T>())
+            if let Some(v) = frame.get(&key)
                 && let Some(t) = v.downcast_ref::<T>()
             {
-                return Some(*t);
+                return Some(t.clone());
             }
         }
         None
     })
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Theme {
     pub background: Color,
     pub surface: Color,
@@ -164,6 +238,35 @@ pub struct Theme {
     pub scrollbar_thumb: Color,
 
     pub error: Color,
+
+    /// Opacity of the hover halo around checkboxes, radios, switches and slider knobs;
+    /// pressed uses twice this.
+    pub state_layer_opacity: f32,
+
+    pub motion: Motion,
+}
+
+/// Durations and easings for built-in transitions, from small state changes to larger
+/// movements.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Motion {
+    /// Check marks and radio dots.
+    pub short: AnimationSpec,
+    /// Switch knobs and similar travel across a control.
+    pub medium: AnimationSpec,
+    /// Panels and content entering or leaving.
+    pub long: AnimationSpec,
+}
+
+impl Default for Motion {
+    fn default() -> Self {
+        use std::time::Duration;
+        Self {
+            short: AnimationSpec::tween(Duration::from_millis(120), Easing::EaseOut),
+            medium: AnimationSpec::tween(Duration::from_millis(200), Easing::EaseInOut),
+            long: AnimationSpec::tween(Duration::from_millis(350), Easing::EaseInOut),
+        }
+    }
 }
 
 impl Default for Theme {
@@ -182,6 +285,8 @@ impl Default for Theme {
             scrollbar_track: Color(0xDD, 0xDD, 0xDD, 32),
             scrollbar_thumb: Color(0xDD, 0xDD, 0xDD, 140),
             error: Color::from_hex("#ae3636"),
+            state_layer_opacity: 0.08,
+            motion: Motion::default(),
         }
     }
 }
@@ -214,41 +319,167 @@ impl Default for TextScale {
     }
 }
 
+/// Smallest hit area (dp, each side) for buttons, checkboxes, switches and sliders. Smaller
+/// controls keep their visual size; only their hit region grows, around its centre.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MinTouchTarget(pub f32);
+impl Default for MinTouchTarget {
+    fn default() -> Self {
+        Self(48.0)
+    }
+}
+
+/// Input timings and distances the runners and gestures use.
+///
+/// The defaults are fixed values, not the user's OS configuration; an app that wants those
+/// reads them itself and passes them to [`set_platform_settings_default`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlatformSettings {
+    /// Longest gap between two clicks or taps that still makes a double click.
+    pub double_click_interval: Duration,
+    /// How long a key is held before it starts repeating.
+    pub key_repeat_delay: Duration,
+    /// Gap between repeats of a held key.
+    pub key_repeat_interval: Duration,
+    /// Distance one wheel notch scrolls, in dp.
+    pub scroll_line_dp: f32,
+    /// How wheel and touchpad deltas turn into scrolling.
+    pub scroll: ScrollBehavior,
+}
+impl Default for PlatformSettings {
+    fn default() -> Self {
+        Self {
+            double_click_interval: Duration::from_millis(300),
+            key_repeat_delay: Duration::from_millis(500),
+            key_repeat_interval: Duration::from_millis(33),
+            scroll_line_dp: 60.0,
+            scroll: ScrollBehavior::default(),
+        }
+    }
+}
+
+/// How the runners turn wheel and touchpad input into scroll distances.
+///
+/// Platforms report wheel motion either in lines (most mouse wheels) or in pixels
+/// (touchpads, high-resolution wheels), and disagree on how big either is: a macOS line is
+/// an accelerated fraction of a notch, while Windows and X11 send one line per notch. The
+/// defaults scale both kinds per platform so the same gesture scrolls about as far
+/// everywhere.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScrollBehavior {
+    /// Multiplies line deltas, which are `scroll_line_dp` each before this.
+    pub line_multiplier: f32,
+    /// Multiplies pixel deltas.
+    pub pixel_multiplier: f32,
+    /// Eases line scrolls over `smooth_duration` instead of jumping a notch at a time.
+    /// Pixel deltas are already fine-grained and always apply at once.
+    pub smooth: bool,
+    pub smooth_duration: Duration,
+}
+impl Default for ScrollBehavior {
+    fn default() -> Self {
+        let (line_multiplier, pixel_multiplier) = if cfg!(target_os = "macos") {
+            // AppKit lines are ~10 px and come accelerated.
+            (1.0 / 6.0, 1.0)
+        } else if cfg!(target_arch = "wasm32") {
+            // Browsers report most wheels in CSS px, ~100 per notch.
+            (1.0, 0.6)
+        } else {
+            (1.0, 1.0)
+        };
+        Self {
+            line_multiplier,
+            pixel_multiplier,
+            smooth: false,
+            smooth_duration: Duration::from_millis(120),
+        }
+    }
+}
+
+/// Edges of the window covered by system UI, in dp.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WindowInsets {
+    /// Status and navigation bars, display cutouts.
+    pub system_bars: PaddingValues,
+    /// The on-screen keyboard, measured from the window edge; zero while it is hidden.
+    pub ime: PaddingValues,
+}
+impl WindowInsets {
+    /// What content pinned to the bottom edge must stay above: the keyboard when it is
+    /// open, the navigation bar otherwise.
+    pub fn bottom(&self) -> f32 {
+        self.system_bars.bottom.max(self.ime.bottom)
+    }
+}
+
 pub fn with_theme<R>(theme: Theme, f: impl FnOnce() -> R) -> R {
     with_locals_frame(|| {
-        set_local_boxed(TypeId::of::<Theme>(), Box::new(theme));
+        set_local_boxed(LocalKey::of::<Theme>(), Box::new(theme));
         f()
     })
 }
 
 pub fn with_density<R>(density: Density, f: impl FnOnce() -> R) -> R {
     with_locals_frame(|| {
-        set_local_boxed(TypeId::of::<Density>(), Box::new(density));
+        set_local_boxed(LocalKey::of::<Density>(), Box::new(density));
         f()
     })
 }
 
 pub fn with_ui_scale<R>(s: UiScale, f: impl FnOnce() -> R) -> R {
     with_locals_frame(|| {
-        set_local_boxed(TypeId::of::<UiScale>(), Box::new(s));
+        set_local_boxed(LocalKey::of::<UiScale>(), Box::new(s));
         f()
     })
 }
 
 pub fn with_text_scale<R>(ts: TextScale, f: impl FnOnce() -> R) -> R {
     with_locals_frame(|| {
-        set_local_boxed(TypeId::of::<TextScale>(), Box::new(ts));
+        set_local_boxed(LocalKey::of::<TextScale>(), Box::new(ts));
         f()
     })
 }
 
 pub fn with_text_direction<R>(dir: TextDirection, f: impl FnOnce() -> R) -> R {
     with_locals_frame(|| {
-        set_local_boxed(TypeId::of::<TextDirection>(), Box::new(dir));
+        set_local_boxed(LocalKey::of::<TextDirection>(), Box::new(dir));
+        f()
+    })
+}
+
+pub fn with_locale<R>(l: Locale, f: impl FnOnce() -> R) -> R {
+    with_locals_frame(|| {
+        set_local_boxed(LocalKey::of::<Locale>(), Box::new(l));
+        f()
+    })
+}
+
+pub fn with_min_touch_target<R>(t: MinTouchTarget, f: impl FnOnce() -> R) -> R {
+    with_locals_frame(|| {
+        set_local_boxed(LocalKey::of::<MinTouchTarget>(), Box::new(t));
+        f()
+    })
+}
+
+pub fn with_platform_settings<R>(s: PlatformSettings, f: impl FnOnce() -> R) -> R {
+    with_locals_frame(|| {
+        set_local_boxed(LocalKey::of::<PlatformSettings>(), Box::new(s));
+        f()
+    })
+}
+
+pub fn with_window_insets<R>(i: WindowInsets, f: impl FnOnce() -> R) -> R {
+    with_locals_frame(|| {
+        set_local_boxed(LocalKey::of::<WindowInsets>(), Box::new(i));
         f()
     })
 }
 
+/// The global default theme, ignoring any local one.
+pub(crate) fn theme_default() -> Theme {
+    defaults().read().theme
+}
+
 pub fn theme() -> Theme {
     get_local::<Theme>().unwrap_or_else(|| defaults().read().theme)
 }
@@ -268,3 +499,66 @@ pub fn text_scale() -> TextScale {
 pub fn text_direction() -> TextDirection {
     get_local::<TextDirection>().unwrap_or_else(|| defaults().read().text_direction)
 }
+
+pub fn locale() -> Locale {
+    get_local::<Locale>().unwrap_or_else(|| defaults().read().locale.clone())
+}
+
+pub fn min_touch_target() -> MinTouchTarget {
+    get_local::<MinTouchTarget>().unwrap_or_else(|| defaults().read().min_touch_target)
+}
+
+pub fn platform_settings() -> PlatformSettings {
+    get_local::<PlatformSettings>().unwrap_or_else(|| defaults().read().platform_settings)
+}
+
+pub fn window_insets() -> WindowInsets {
+    get_local::<WindowInsets>().unwrap_or_else(|| defaults().read().window_insets)
+}
+
+// ---- User-defined locals ----
+
+/// An ambient value libraries can thread down the tree (an image loader, a router, ...)
+/// without passing it through every function. Declare one as a `static` with
+/// [`composition_local_of`]; a local is identified by its address, so don't use `const`.
+pub struct CompositionLocal<T: 'static> {
+    default: fn() -> T,
+    _t: PhantomData<fn() -> T>,
+}
+
+/// Declares a [`CompositionLocal`] whose value is `default()` where nothing is provided.
+pub const fn composition_local_of<T: Clone + 'static>(default: fn() -> T) -> CompositionLocal<T> {
+    CompositionLocal {
+        default,
+        _t: PhantomData,
+    }
+}
+
+impl<T: Clone + 'static> CompositionLocal<T> {
+    fn key(&'static self) -> LocalKey {
+        LocalKey::User(self as *const Self as usize)
+    }
+
+    /// The innermost provided value, or the default. Like the built-in locals this is only
+    /// scoped while composing; capture it if a callback needs it later.
+    pub fn current(&'static self) -> T {
+        lookup(self.key()).unwrap_or_else(self.default)
+    }
+
+    /// See [`provide`].
+    pub fn provide<R>(&'static self, value: T, f: impl FnOnce() -> R) -> R {
+        with_locals_frame(|| {
+            set_local_boxed(self.key(), Box::new(value));
+            f()
+        })
+    }
+}
+
+/// Runs `f` (typically building a subtree) with `local` set to `value`.
+pub fn provide<T: Clone + 'static, R>(
+    local: &'static CompositionLocal<T>,
+    value: T,
+    f: impl FnOnce() -> R,
+) -> R {
+    local.provide(value, f)
+}
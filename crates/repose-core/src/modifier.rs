@@ -1,8 +1,10 @@
+use std::borrow::Cow;
 use std::rc::Rc;
 
 use taffy::{AlignContent, AlignItems, AlignSelf, FlexDirection, FlexWrap, JustifyContent};
 
-use crate::{Brush, Color, PointerEvent, Size, Transform, Vec2};
+use crate::animation::{AnimatedOffset, AnimationSpec};
+use crate::{Brush, Color, Density, KeyEvent, PointerEvent, Size, TextScale, Transform, Vec2};
 
 #[derive(Clone, Debug)]
 pub struct Border {
@@ -11,7 +13,7 @@ pub struct Border {
     pub radius: f32,
 }
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct PaddingValues {
     pub left: f32,
     pub right: f32,
@@ -19,6 +21,17 @@ pub struct PaddingValues {
     pub bottom: f32,
 }
 
+impl PaddingValues {
+    pub fn all(v: f32) -> Self {
+        Self {
+            left: v,
+            right: v,
+            top: v,
+            bottom: v,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct GridConfig {
     pub columns: usize,
@@ -32,6 +45,132 @@ pub enum PositionType {
     Absolute,
 }
 
+/// Pivot for a [`GraphicsLayer`]'s scale and rotation, as fractions of the node's box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransformOrigin {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl TransformOrigin {
+    pub const CENTER: Self = Self { x: 0.5, y: 0.5 };
+    pub const TOP_LEFT: Self = Self { x: 0.0, y: 0.0 };
+}
+
+impl Default for TransformOrigin {
+    fn default() -> Self {
+        Self::CENTER
+    }
+}
+
+/// A drop shadow painted under a [`GraphicsLayer`]; offset and blur are in dp.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Shadow {
+    pub color: Color,
+    pub offset: Vec2,
+    pub blur: f32,
+}
+
+/// Paint-only transform, fade and shadow for a node and its subtree.
+///
+/// Nothing here is read by layout, so animating these values (e.g. a press scale or a
+/// slide-in translation) never changes the Taffy style; the node keeps its laid-out box
+/// and only the scene transform moves. Hit regions stay at the laid-out box as well.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GraphicsLayer {
+    pub scale_x: f32,
+    pub scale_y: f32,
+    /// Radians, clockwise around `transform_origin`.
+    pub rotation: f32,
+    /// Offset in dp, applied after scale and rotation.
+    pub translation: Vec2,
+    pub transform_origin: TransformOrigin,
+    pub alpha: f32,
+    pub shadow: Option<Shadow>,
+}
+
+impl Default for GraphicsLayer {
+    fn default() -> Self {
+        Self {
+            scale_x: 1.0,
+            scale_y: 1.0,
+            rotation: 0.0,
+            translation: Vec2::default(),
+            transform_origin: TransformOrigin::CENTER,
+            alpha: 1.0,
+            shadow: None,
+        }
+    }
+}
+
+impl GraphicsLayer {
+    /// The scene transform for this layer over `bounds` (px), pivoting around
+    /// `transform_origin` and converting `translation` from dp.
+    pub fn transform(&self, bounds: crate::Rect) -> Transform {
+        let origin = Vec2 {
+            x: bounds.x + bounds.w * self.transform_origin.x,
+            y: bounds.y + bounds.h * self.transform_origin.y,
+        };
+        let linear = Transform {
+            scale_x: self.scale_x,
+            scale_y: self.scale_y,
+            rotate: self.rotation,
+            ..Transform::identity()
+        };
+        // p' = RS(p - o) + o + d  =>  translate by o + d - RS(o)
+        let moved = linear.apply_to_point(origin);
+        Transform {
+            translate_x: origin.x + crate::dp_to_px(self.translation.x) - moved.x,
+            translate_y: origin.y + crate::dp_to_px(self.translation.y) - moved.y,
+            ..linear
+        }
+    }
+}
+
+/// One visual step of a modifier chain, kept in the order the builders were called.
+///
+/// Paint interprets the chain front to back: a `Padding` insets everything drawn after it,
+/// so `padding(8.0).background(c)` fills the inner box while `background(c).padding(8.0)`
+/// fills the whole node. `Clip` and `Transform` wrap every later element plus the node's
+/// content and children; `Alpha` fades everything after it.
+///
+/// Each kind appears once: calling its builder again replaces the value and moves it to
+/// the later position. Graphics layers are the exception and stack.
+#[derive(Clone, Debug)]
+pub enum ModifierElement {
+    Padding(PaddingValues),
+    Background(Brush),
+    Border(Border),
+    /// Clips to the current (inset) box with the given corner radius in dp.
+    Clip(f32),
+    Alpha(f32),
+    Transform(Transform),
+    GraphicsLayer(GraphicsLayer),
+    /// Clips to an animated copy of the node's laid-out size; see
+    /// [`Modifier::animate_content_size`].
+    AnimateContentSize(AnimationSpec),
+    /// Moves in from where another node with the same key was painted; see
+    /// [`Modifier::shared_element`].
+    SharedElement(u64, AnimationSpec),
+}
+
+/// Makes a node an on/off control; see [`Modifier::toggleable`].
+#[derive(Clone)]
+pub struct Toggleable {
+    pub role: crate::Role,
+    pub state: crate::ToggleableState,
+    pub on_toggle: crate::Callback,
+}
+
+impl std::fmt::Debug for Toggleable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Toggleable")
+            .field("role", &self.role)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct Modifier {
     /// Optional stable identity key for this view node.
@@ -65,15 +204,31 @@ pub struct Modifier {
     pub align_items_container: Option<AlignItems>,
     pub align_content: Option<AlignContent>,
     pub clip_rounded: Option<f32>,
-    /// Works for hit-testing only, draw order is not changed.
+    /// Orders hit-testing globally and painting among siblings (higher draws on top;
+    /// equal values keep tree order).
     pub z_index: f32,
     pub click: bool,
+    /// This node and its subtree get no hit regions; see [`Modifier::hit_test_disabled`].
+    pub hit_test_disabled: bool,
+    /// Clicks, Space and Enter call `on_toggle`; see [`Modifier::toggleable`].
+    pub toggleable: Option<Toggleable>,
     pub on_scroll: Option<Rc<dyn Fn(Vec2) -> Vec2>>,
     pub on_pointer_down: Option<Rc<dyn Fn(PointerEvent)>>,
     pub on_pointer_move: Option<Rc<dyn Fn(PointerEvent)>>,
     pub on_pointer_up: Option<Rc<dyn Fn(PointerEvent)>>,
     pub on_pointer_enter: Option<Rc<dyn Fn(PointerEvent)>>,
     pub on_pointer_leave: Option<Rc<dyn Fn(PointerEvent)>>,
+    pub on_key_event: Option<Rc<dyn Fn(KeyEvent) -> bool>>,
+    pub on_size: Option<Rc<dyn Fn(Size)>>,
+    /// Receives this node's laid-out rect in window px (before paint-time transforms).
+    pub on_placed: Option<Rc<dyn Fn(crate::Rect)>>,
+    /// Pans and zooms the children: a child point `p` (px, relative to this node's
+    /// top-left) is drawn at `translate + p * scale`; rotation is ignored. Hit regions move
+    /// with the children and report pointer positions as if nothing were zoomed.
+    pub content_transform: Option<Transform>,
+    /// Receives the bounds of what the children painted, before `content_transform`, in px
+    /// relative to this node's top-left.
+    pub on_content_bounds: Option<Rc<dyn Fn(crate::Rect)>>,
     pub semantics: Option<crate::Semantics>,
     pub alpha: Option<f32>,
     pub transform: Option<Transform>,
@@ -91,6 +246,57 @@ pub struct Modifier {
     pub margin_bottom: Option<f32>,
     pub aspect_ratio: Option<f32>,
     pub painter: Option<Rc<dyn Fn(&mut crate::Scene, crate::Rect)>>,
+    /// Overrides the dp→px `Density` for this node and its subtree during layout/paint.
+    pub density: Option<Density>,
+    /// Overrides the `TextScale` for this node and its subtree during layout/paint.
+    pub text_scale: Option<TextScale>,
+    /// Turns off the full-text hover tooltip that ellipsized `Text` shows by default.
+    pub no_overflow_tooltip: bool,
+    /// Shown in a tooltip while the pointer rests on this node; see [`Modifier::tooltip`].
+    pub tooltip: Option<String>,
+    /// On a scroll container, reuses the scene walk of its content while this key stays the
+    /// same; see [`Modifier::walk_cache`].
+    pub walk_cache: Option<u64>,
+    /// Width (dp) of the overview gutter on a two-axis scroll container; see
+    /// [`Modifier::minimap`].
+    pub minimap: Option<f32>,
+    /// Accessible name announced for this node in place of its visible text.
+    pub content_description: Option<String>,
+    /// Id other nodes refer to with [`Modifier::labelled_by`]; see [`Modifier::label_id`].
+    pub label_id: Option<u64>,
+    /// `label_id` of the view whose text names this node for accessibility.
+    pub labelled_by: Option<u64>,
+    /// Whether this node is open, and how it animates between open and closed; see
+    /// [`Modifier::animate_expand`].
+    pub animate_expand: Option<(bool, AnimationSpec)>,
+    /// Where each visual builder was last called; see [`Modifier::elements`].
+    chain: Vec<Step>,
+}
+
+/// An entry of [`Modifier`]'s chain. Elements with a flat field only record their
+/// position there, so the field stays the one place their value lives.
+#[derive(Clone, Debug)]
+enum Step {
+    Padding,
+    Background,
+    Border,
+    Clip,
+    Alpha,
+    Transform,
+    Other(ModifierElement),
+}
+
+impl Step {
+    fn same_kind(&self, other: &Step) -> bool {
+        match (self, other) {
+            // Graphics layers stack.
+            (Step::Other(ModifierElement::GraphicsLayer(_)), _) => false,
+            (Step::Other(a), Step::Other(b)) => {
+                std::mem::discriminant(a) == std::mem::discriminant(b)
+            }
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
 }
 
 impl std::fmt::Debug for Modifier {
@@ -121,6 +327,16 @@ impl std::fmt::Debug for Modifier {
             .field("clip_rounded", &self.clip_rounded)
             .field("z_index", &self.z_index)
             .field("click", &self.click)
+            .field("hit_test_disabled", &self.hit_test_disabled)
+            .field("toggleable", &self.toggleable)
+            .field("no_overflow_tooltip", &self.no_overflow_tooltip)
+            .field("tooltip", &self.tooltip)
+            .field("walk_cache", &self.walk_cache)
+            .field("minimap", &self.minimap)
+            .field("content_description", &self.content_description)
+            .field("label_id", &self.label_id)
+            .field("labelled_by", &self.labelled_by)
+            .field("animate_expand", &self.animate_expand)
             .field("on_scroll", &self.on_scroll.as_ref().map(|_| "..."))
             .field(
                 "on_pointer_down",
@@ -139,6 +355,14 @@ impl std::fmt::Debug for Modifier {
                 "on_pointer_leave",
                 &self.on_pointer_leave.as_ref().map(|_| "..."),
             )
+            .field("on_key_event", &self.on_key_event.as_ref().map(|_| "..."))
+            .field("on_size", &self.on_size.as_ref().map(|_| "..."))
+            .field("on_placed", &self.on_placed.as_ref().map(|_| "..."))
+            .field("content_transform", &self.content_transform)
+            .field(
+                "on_content_bounds",
+                &self.on_content_bounds.as_ref().map(|_| "..."),
+            )
             .field("semantics", &self.semantics)
             .field("alpha", &self.alpha)
             .field("transform", &self.transform)
@@ -152,6 +376,9 @@ impl std::fmt::Debug for Modifier {
             .field("offset_bottom", &self.offset_bottom)
             .field("aspect_ratio", &self.aspect_ratio)
             .field("painter", &self.painter.as_ref().map(|_| "..."))
+            .field("density", &self.density)
+            .field("text_scale", &self.text_scale)
+            .field("chain", &self.elements())
             .finish()
     }
 }
@@ -195,12 +422,34 @@ impl Modifier {
         self.fill_max_h = true;
         self
     }
+    /// Insets the content, and whatever is chained after this call, by `v` dp on every
+    /// side. Replaces an earlier padding.
     pub fn padding(mut self, v: f32) -> Self {
         self.padding = Some(v);
-        self
+        self.padding_values = None;
+        self.place(Step::Padding)
     }
     pub fn padding_values(mut self, padding: PaddingValues) -> Self {
         self.padding_values = Some(padding);
+        self.padding = None;
+        self.place(Step::Padding)
+    }
+    /// Adds `element` to the chain through its builder; see [`ModifierElement`].
+    pub fn then(self, element: ModifierElement) -> Self {
+        match element {
+            ModifierElement::Padding(p) => self.padding_values(p),
+            ModifierElement::Background(b) => self.background_brush(b),
+            ModifierElement::Border(b) => self.border(b.width, b.color, b.radius),
+            ModifierElement::Clip(r) => self.clip_rounded(r),
+            ModifierElement::Alpha(a) => self.alpha(a),
+            ModifierElement::Transform(t) => self.transform(t),
+            other => self.place(Step::Other(other)),
+        }
+    }
+    /// Records `step` as the latest call of its kind.
+    fn place(mut self, step: Step) -> Self {
+        self.chain.retain(|s| !step.same_kind(s));
+        self.chain.push(step);
         self
     }
     pub fn min_size(mut self, w: f32, h: f32) -> Self {
@@ -230,22 +479,22 @@ impl Modifier {
         self
     }
     /// Set a solid color background.
-    pub fn background(mut self, color: Color) -> Self {
-        self.background = Some(Brush::Solid(color));
-        self
+    pub fn background(self, color: Color) -> Self {
+        self.background_brush(Brush::Solid(color))
     }
     /// Set a brush (solid, gradient, etc.) background.
     pub fn background_brush(mut self, brush: Brush) -> Self {
         self.background = Some(brush);
-        self
+        self.place(Step::Background)
     }
     pub fn border(mut self, width: f32, color: Color, radius: f32) -> Self {
-        self.border = Some(Border {
+        let border = Border {
             width,
             color,
             radius,
-        });
-        self
+        };
+        self.border = Some(border);
+        self.place(Step::Border)
     }
     pub fn flex_grow(mut self, v: f32) -> Self {
         self.flex_grow = Some(v);
@@ -287,9 +536,11 @@ impl Modifier {
         self.align_content = Some(a);
         self
     }
+    /// Clips everything after this point (and the content) to a rounded box. The radius
+    /// also shapes this node's background and border, wherever they sit in the chain.
     pub fn clip_rounded(mut self, radius: f32) -> Self {
         self.clip_rounded = Some(radius);
-        self
+        self.place(Step::Clip)
     }
     pub fn z_index(mut self, z: f32) -> Self {
         self.z_index = z;
@@ -299,6 +550,80 @@ impl Modifier {
         self.click = true;
         self
     }
+    /// Makes this node a focusable toggle, the way `Checkbox` and `Switch` are: clicking
+    /// it, or pressing Space or Enter while it has focus, calls `on_toggle`, which should
+    /// flip `state` for the next frame. Screen readers get `role` and `state`, and the node
+    /// shows the usual hover, pressed and focus visuals. For toggles drawn with `Canvas`.
+    pub fn toggleable(
+        mut self,
+        role: crate::Role,
+        state: impl Into<crate::ToggleableState>,
+        on_toggle: impl Fn() + 'static,
+    ) -> Self {
+        self.toggleable = Some(Toggleable {
+            role,
+            state: state.into(),
+            on_toggle: Rc::new(on_toggle),
+        });
+        self
+    }
+    /// Makes this node and everything in it invisible to the pointer, so clicks reach
+    /// whatever is underneath: for scrims, decorations and other visual-only overlays. Nodes
+    /// painted fully transparent (alpha 0) pass clicks through the same way on their own.
+    pub fn hit_test_disabled(mut self) -> Self {
+        self.hit_test_disabled = true;
+        self
+    }
+    /// Don't show the full string in a tooltip when this `Text` is ellipsized.
+    pub fn no_overflow_tooltip(mut self) -> Self {
+        self.no_overflow_tooltip = true;
+        self
+    }
+    /// Shows `text` in a tooltip once the pointer has rested on this node for a moment.
+    /// It sits below the node, or above it near the bottom of the window, and stays inside
+    /// the window horizontally. Doesn't make the node hit-testable.
+    pub fn tooltip(mut self, text: impl Into<String>) -> Self {
+        self.tooltip = Some(text.into());
+        self
+    }
+    /// On a scroll container (`ScrollArea` and friends): promises the content only changes
+    /// when `key` does, so frames that merely scroll replay the scene nodes and hit regions
+    /// the last walk produced at the new offset instead of walking every child again. The
+    /// GPU still draws every visible node. Hovering, pressing or focusing something inside
+    /// walks it again. Content that animates, nests other scroll containers or reports
+    /// `on_placed` should fold that state into `key`.
+    pub fn walk_cache(mut self, key: u64) -> Self {
+        self.walk_cache = Some(key);
+        self
+    }
+    /// On a `ScrollArea(ScrollDirection::Both, ..)`: shows a `width_dp` wide overview of
+    /// the content along the right edge, in place of the vertical scrollbar. See
+    /// `repose_ui::minimap`.
+    pub fn minimap(mut self, width_dp: f32) -> Self {
+        self.minimap = Some(width_dp.max(0.0));
+        self
+    }
+    /// Names this node for screen readers, e.g. an icon-only button. Takes precedence over
+    /// visible text and [`labelled_by`](Self::labelled_by). Views that have no accessibility
+    /// node of their own (images, clickable boxes) get one.
+    pub fn content_description(mut self, text: impl Into<String>) -> Self {
+        self.content_description = Some(text.into());
+        self
+    }
+    /// Gives this node an id that [`labelled_by`](Self::labelled_by) can point at. Unlike
+    /// [`key`](Self::key), which only has to be unique among siblings, it should be unique in
+    /// the window; if it isn't, the first node painted with it wins.
+    pub fn label_id(mut self, id: u64) -> Self {
+        self.label_id = Some(id);
+        self
+    }
+    /// Names this node after another view, e.g. a `TextField` with a `Text` label next to it.
+    /// `id` is that view's [`label_id`](Self::label_id); the text inside it becomes this
+    /// node's label.
+    pub fn labelled_by(mut self, id: u64) -> Self {
+        self.labelled_by = Some(id);
+        self
+    }
     pub fn on_scroll(mut self, f: impl Fn(Vec2) -> Vec2 + 'static) -> Self {
         self.on_scroll = Some(Rc::new(f));
         self
@@ -323,17 +648,107 @@ impl Modifier {
         self.on_pointer_leave = Some(Rc::new(f));
         self
     }
+    /// Handles keys while this view is focused (or, when nothing is focused, before the
+    /// runner's own shortcuts). `f` returns `true` to consume the event; otherwise it goes
+    /// on to the built-in handling, e.g. `TextField` editing. Makes the view focusable.
+    pub fn on_key_event(mut self, f: impl Fn(KeyEvent) -> bool + 'static) -> Self {
+        self.on_key_event = Some(Rc::new(f));
+        self
+    }
+    /// Receives this node's laid-out size in px after every layout, e.g. to pick a compact
+    /// variant when the next frame is built. The first frame is built before it is known.
+    pub fn on_size(mut self, f: impl Fn(Size) + 'static) -> Self {
+        self.on_size = Some(Rc::new(f));
+        self
+    }
+    /// Receives where this node was laid out, in window px, after every layout; e.g. to
+    /// anchor a popup shown in a window layer. The first frame is built before it is known.
+    pub fn on_placed(mut self, f: impl Fn(crate::Rect) + 'static) -> Self {
+        self.on_placed = Some(Rc::new(f));
+        self
+    }
+    /// Pans and zooms this node's children at paint time, hit testing included; see
+    /// the field of the same name. Layout is unaffected.
+    pub fn content_transform(mut self, t: Transform) -> Self {
+        self.content_transform = Some(t);
+        self
+    }
+    /// Reports the painted bounds of this node's children after every paint; the first
+    /// frame is built before they are known.
+    pub fn on_content_bounds(mut self, f: impl Fn(crate::Rect) + 'static) -> Self {
+        self.on_content_bounds = Some(Rc::new(f));
+        self
+    }
     pub fn semantics(mut self, s: crate::Semantics) -> Self {
         self.semantics = Some(s);
         self
     }
     pub fn alpha(mut self, a: f32) -> Self {
         self.alpha = Some(a);
-        self
+        self.place(Step::Alpha)
     }
-    pub fn transform(mut self, t: Transform) -> Self {
-        self.transform = Some(t);
-        self
+    /// Scales, rotates, translates and fades this node at paint time; see [`GraphicsLayer`].
+    /// Chain it before `background` so a shadow lands underneath.
+    pub fn graphics_layer(self, layer: GraphicsLayer) -> Self {
+        self.place(Step::Other(ModifierElement::GraphicsLayer(layer)))
+    }
+    /// Offsets this node by an animated amount without relaying out every frame.
+    ///
+    /// Layout places the node at `state.settled()`; while an animation runs, the remaining
+    /// distance is painted as a [`GraphicsLayer`] translation.
+    pub fn offset_animated(self, state: &AnimatedOffset) -> Self {
+        let current = state.value();
+        let settled = state.settled();
+        let m = self.offset_left(settled.x).offset_top(settled.y);
+        if current == settled {
+            return m;
+        }
+        m.graphics_layer(GraphicsLayer {
+            translation: Vec2 {
+                x: current.x - settled.x,
+                y: current.y - settled.y,
+            },
+            ..GraphicsLayer::default()
+        })
+    }
+    /// Animates size changes of this node's content at paint time.
+    ///
+    /// Growth is laid out immediately and revealed by an animated clip. When the content
+    /// shrinks, layout holds the old size until the animation settles, so Taffy reruns at
+    /// the start and the end of an animation rather than on every frame. Put it first in
+    /// the chain so backgrounds and borders are clipped with the content.
+    pub fn animate_content_size(self) -> Self {
+        self.animate_content_size_with(AnimationSpec::default())
+    }
+    pub fn animate_content_size_with(self, spec: AnimationSpec) -> Self {
+        self.place(Step::Other(ModifierElement::AnimateContentSize(spec)))
+    }
+    /// Marks this node as the shared element `key`: when a node with that key was painted
+    /// elsewhere in a previous frame (e.g. a thumbnail on the screen that was just navigated
+    /// away from), this one starts at that box and animates into its own, as a
+    /// [`GraphicsLayer`] scale and translation. Layout and hit testing use the node's own box
+    /// throughout.
+    pub fn shared_element(self, key: u64) -> Self {
+        self.shared_element_with(key, AnimationSpec::default())
+    }
+    pub fn shared_element_with(self, key: u64, spec: AnimationSpec) -> Self {
+        self.place(Step::Other(ModifierElement::SharedElement(key, spec)))
+    }
+    /// Animates this node's height between nothing and its natural height as `expanded`
+    /// changes, clipping its children (and their input) to the open part. Unlike
+    /// [`animate_content_size`](Self::animate_content_size), layout follows every frame of
+    /// the animation, so siblings and scroll containers move with it. Uses the theme's
+    /// medium motion.
+    pub fn animate_expand(self, expanded: bool) -> Self {
+        self.animate_expand_with(expanded, crate::locals::theme().motion.medium)
+    }
+    pub fn animate_expand_with(mut self, expanded: bool, spec: AnimationSpec) -> Self {
+        self.animate_expand = Some((expanded, spec));
+        self
+    }
+    /// Sets the transform, replacing one set by an immediately preceding transform builder.
+    pub fn transform(self, t: Transform) -> Self {
+        self.map_transform(|_| t)
     }
     pub fn grid(mut self, columns: usize, row_gap: f32, column_gap: f32) -> Self {
         self.grid = Some(GridConfig {
@@ -412,22 +827,38 @@ impl Modifier {
     pub fn scale(self, s: f32) -> Self {
         self.scale2(s, s)
     }
-    pub fn scale2(mut self, sx: f32, sy: f32) -> Self {
-        let mut t = self.transform.unwrap_or_else(Transform::identity);
-        t.scale_x *= sx;
-        t.scale_y *= sy;
-        self.transform = Some(t);
-        self
-    }
-    pub fn translate(mut self, x: f32, y: f32) -> Self {
-        let t = self.transform.unwrap_or_else(Transform::identity);
-        self.transform = Some(t.combine(&Transform::translate(x, y)));
+    pub fn scale2(self, sx: f32, sy: f32) -> Self {
+        self.map_transform(|mut t| {
+            t.scale_x *= sx;
+            t.scale_y *= sy;
+            t
+        })
+    }
+    pub fn translate(self, x: f32, y: f32) -> Self {
+        self.map_transform(|t| t.combine(&Transform::translate(x, y)))
+    }
+    pub fn rotate(self, radians: f32) -> Self {
+        self.map_transform(|mut t| {
+            t.rotate += radians;
+            t
+        })
+    }
+    /// Transform builders build up one transform, as they did before the chain; it sits
+    /// where the last of them was called.
+    fn map_transform(mut self, f: impl Fn(Transform) -> Transform) -> Self {
+        self.transform = Some(f(self.transform.unwrap_or_else(Transform::identity)));
+        self.place(Step::Transform)
+    }
+    /// Lays out and paints this subtree at `scale` dp→px instead of the host density.
+    pub fn density(mut self, scale: f32) -> Self {
+        self.density = Some(Density {
+            scale: scale.max(0.0),
+        });
         self
     }
-    pub fn rotate(mut self, radians: f32) -> Self {
-        let mut t = self.transform.unwrap_or_else(Transform::identity);
-        t.rotate += radians;
-        self.transform = Some(t);
+    /// Lays out and paints this subtree with a different user text scale.
+    pub fn text_scale(mut self, scale: f32) -> Self {
+        self.text_scale = Some(TextScale(scale.max(0.0)));
         self
     }
     pub fn weight(mut self, w: f32) -> Self {
@@ -438,4 +869,53 @@ impl Modifier {
         self.flex_basis = Some(0.0);
         self
     }
+
+    /// Padding in dp: what layout reserves around the content box.
+    pub fn total_padding(&self) -> Option<PaddingValues> {
+        self.padding_values.or(self.padding.map(PaddingValues::all))
+    }
+
+    /// The visual chain to paint, in builder order, with values read from the flat fields.
+    /// A field written by hand keeps its builder's position, or when no builder placed it,
+    /// the position it had before the chain existed: background and border on the outer
+    /// box, then padding, clip, alpha and transform around the content. A field cleared by
+    /// hand drops out.
+    pub fn elements(&self) -> Cow<'_, [ModifierElement]> {
+        let padding = self.total_padding().map(ModifierElement::Padding);
+        let background = self.background.map(ModifierElement::Background);
+        let border = self.border.clone().map(ModifierElement::Border);
+        let clip = self.clip_rounded.map(ModifierElement::Clip);
+        let alpha = self.alpha.map(ModifierElement::Alpha);
+        let transform = self.transform.map(ModifierElement::Transform);
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        let unplaced = |step: Step, el: Option<ModifierElement>, to: &mut Vec<_>| {
+            if !self.chain.iter().any(|s| s.same_kind(&step)) {
+                to.extend(el);
+            }
+        };
+        unplaced(Step::Background, background.clone(), &mut front);
+        unplaced(Step::Border, border.clone(), &mut front);
+        unplaced(Step::Padding, padding.clone(), &mut back);
+        unplaced(Step::Clip, clip.clone(), &mut back);
+        unplaced(Step::Alpha, alpha.clone(), &mut back);
+        unplaced(Step::Transform, transform.clone(), &mut back);
+        if front.is_empty() && back.is_empty() && self.chain.is_empty() {
+            return Cow::Borrowed(&[]);
+        }
+
+        for step in &self.chain {
+            front.extend(match step {
+                Step::Padding => padding.clone(),
+                Step::Background => background.clone(),
+                Step::Border => border.clone(),
+                Step::Clip => clip.clone(),
+                Step::Alpha => alpha.clone(),
+                Step::Transform => transform.clone(),
+                Step::Other(el) => Some(el.clone()),
+            });
+        }
+        front.extend(back);
+        Cow::Owned(front)
+    }
 }
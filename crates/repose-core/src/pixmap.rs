@@ -0,0 +1,126 @@
+//! # Pixmaps
+//!
+//! Images the UI draws on the CPU itself, such as a minimap's overview of its content.
+//! [`set_pixmap`] queues the pixels under a handle from [`pixmap_handle`]; the renderer
+//! uploads whatever is queued before it draws the next frame, and `SceneNode::Image` shows
+//! the result like any other image. Redrawing a pixmap only costs an upload when it's set
+//! again, so callers keep them for as long as what they show doesn't change.
+//!
+//! ```rust
+//! use repose_core::*;
+//!
+//! let mut p = Pixmap::new(4, 4);
+//! p.fill_rect(Rect { x: 1.0, y: 1.0, w: 2.0, h: 2.0 }, Color::WHITE, None);
+//! assert_eq!(p.pixel(1, 1), Color::WHITE);
+//!
+//! let handle = pixmap_handle(7);
+//! set_pixmap(handle, p);
+//! assert_eq!(take_pixmap_updates().len(), 1);
+//! ```
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::{Color, ImageHandle, Rect};
+
+/// Handles at or above this are pixmaps; the renderer numbers decoded images from 1.
+const PIXMAP_HANDLES: ImageHandle = 1 << 63;
+
+/// An RGBA image with straight (not premultiplied) alpha, in sRGB.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pixmap {
+    pub width: u32,
+    pub height: u32,
+    /// Rows top to bottom, four bytes per pixel.
+    pub rgba: Vec<u8>,
+}
+
+impl Pixmap {
+    /// A transparent `width` x `height` pixmap.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            rgba: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> Color {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        let p = &self.rgba[i..i + 4];
+        Color(p[0], p[1], p[2], p[3])
+    }
+
+    /// Blends `color` over the pixels `rect` covers (in pixels, edges rounded to the
+    /// nearest pixel), staying inside `clip` when given.
+    pub fn fill_rect(&mut self, rect: Rect, color: Color, clip: Option<Rect>) {
+        if color.3 == 0 {
+            return;
+        }
+        let (mut x0, mut y0) = (rect.x, rect.y);
+        let (mut x1, mut y1) = (rect.x + rect.w, rect.y + rect.h);
+        if let Some(c) = clip {
+            x0 = x0.max(c.x);
+            y0 = y0.max(c.y);
+            x1 = x1.min(c.x + c.w);
+            y1 = y1.min(c.y + c.h);
+        }
+        let span = |a: f32, b: f32, max: u32| {
+            let a = a.round().clamp(0.0, max as f32) as u32;
+            let b = b.round().clamp(0.0, max as f32) as u32;
+            a..b.max(a)
+        };
+        let (xs, ys) = (span(x0, x1, self.width), span(y0, y1, self.height));
+        for y in ys {
+            for x in xs.clone() {
+                let i = (y as usize * self.width as usize + x as usize) * 4;
+                blend(&mut self.rgba[i..i + 4], color);
+            }
+        }
+    }
+}
+
+/// Source-over for straight alpha.
+fn blend(dst: &mut [u8], src: Color) {
+    let sa = src.3 as f32 / 255.0;
+    let da = dst[3] as f32 / 255.0;
+    let a = sa + da * (1.0 - sa);
+    if a <= 0.0 {
+        return;
+    }
+    let mix = |s: u8, d: u8| {
+        ((s as f32 * sa + d as f32 * da * (1.0 - sa)) / a)
+            .round()
+            .clamp(0.0, 255.0) as u8
+    };
+    dst[0] = mix(src.0, dst[0]);
+    dst[1] = mix(src.1, dst[1]);
+    dst[2] = mix(src.2, dst[2]);
+    dst[3] = (a * 255.0).round() as u8;
+}
+
+thread_local! {
+    // Latest change per handle: `Some` to upload, `None` to free.
+    static PENDING: RefCell<HashMap<ImageHandle, Option<Pixmap>>> = RefCell::new(HashMap::new());
+}
+
+/// The image handle for pixmap `key`. Keys only need to be unique among pixmaps.
+pub fn pixmap_handle(key: u64) -> ImageHandle {
+    key | PIXMAP_HANDLES
+}
+
+/// Shows `pixmap` wherever `handle` is drawn, from the next frame the renderer draws.
+pub fn set_pixmap(handle: ImageHandle, pixmap: Pixmap) {
+    PENDING.with(|p| p.borrow_mut().insert(handle, Some(pixmap)));
+}
+
+/// Frees the pixmap under `handle`.
+pub fn remove_pixmap(handle: ImageHandle) {
+    PENDING.with(|p| p.borrow_mut().insert(handle, None));
+}
+
+/// Pixmaps set or removed since the last call, for the renderer to upload or free. Only
+/// the latest change to each handle is kept.
+pub fn take_pixmap_updates() -> Vec<(ImageHandle, Option<Pixmap>)> {
+    PENDING.with(|p| p.borrow_mut().drain().collect())
+}
@@ -0,0 +1,101 @@
+//! # Power mode
+//!
+//! On battery a UI that animates at the display's full rate drains it for little gain. The
+//! runners therefore follow a [`PowerMode`]: by default ([`PowerMode::Auto`]) they save
+//! power while the device reports running on battery, and an app can force either
+//! behaviour with [`set_power_mode`] (a "battery saver" toggle in its settings, say).
+//!
+//! While saving power, runners cap frames that only serve animations and frame callbacks at
+//! [`POWER_SAVER_FRAME_INTERVAL`] (input is still answered right away) and the GPU backend
+//! presents with vsync (`Fifo`). Widgets that animate on their own can check
+//! [`power_saving`] and pause what isn't essential.
+//!
+//! Battery detection is best effort: runners report it with [`set_on_battery`] where the
+//! platform exposes it, and `Auto` means "performance" elsewhere.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+
+/// Shortest time between animation frames while saving power (30 fps).
+pub const POWER_SAVER_FRAME_INTERVAL: Duration = Duration::from_micros(33_333);
+
+/// How eagerly the runners produce frames.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PowerMode {
+    /// Save power while on battery, run at full rate otherwise.
+    #[default]
+    Auto,
+    /// Always run at full rate.
+    Performance,
+    /// Always save power.
+    PowerSaver,
+}
+
+impl PowerMode {
+    fn to_u8(self) -> u8 {
+        match self {
+            PowerMode::Auto => 0,
+            PowerMode::Performance => 1,
+            PowerMode::PowerSaver => 2,
+        }
+    }
+
+    fn from_u8(v: u8) -> Self {
+        match v {
+            1 => PowerMode::Performance,
+            2 => PowerMode::PowerSaver,
+            _ => PowerMode::Auto,
+        }
+    }
+}
+
+static MODE: AtomicU8 = AtomicU8::new(0);
+
+// 0 = unknown, 1 = on external power, 2 = on battery.
+static BATTERY: AtomicU8 = AtomicU8::new(0);
+
+/// Sets the app's power mode hint; takes effect from the next frame.
+pub fn set_power_mode(mode: PowerMode) {
+    MODE.store(mode.to_u8(), Ordering::Relaxed);
+}
+
+/// The power mode set with [`set_power_mode`] (`Auto` unless the app chose one).
+pub fn power_mode() -> PowerMode {
+    PowerMode::from_u8(MODE.load(Ordering::Relaxed))
+}
+
+/// Reports whether the device runs on battery; `None` when the platform can't tell.
+/// Called by the runners.
+pub fn set_on_battery(on_battery: Option<bool>) {
+    let v = match on_battery {
+        None => 0,
+        Some(false) => 1,
+        Some(true) => 2,
+    };
+    BATTERY.store(v, Ordering::Relaxed);
+}
+
+/// Whether the device runs on battery, as last reported by the runner.
+pub fn on_battery() -> Option<bool> {
+    match BATTERY.load(Ordering::Relaxed) {
+        1 => Some(false),
+        2 => Some(true),
+        _ => None,
+    }
+}
+
+/// Whether the UI should currently save power: the app asked for it, or left the mode on
+/// `Auto` and the device is on battery.
+pub fn power_saving() -> bool {
+    match power_mode() {
+        PowerMode::Auto => on_battery() == Some(true),
+        PowerMode::Performance => false,
+        PowerMode::PowerSaver => true,
+    }
+}
+
+/// Shortest time between animation frames right now; `None` means as fast as the display
+/// refreshes.
+pub fn frame_interval() -> Option<Duration> {
+    power_saving().then_some(POWER_SAVER_FRAME_INTERVAL)
+}
@@ -5,14 +5,14 @@ pub use crate::error::*;
 pub use crate::geometry::{Rect, Size, Vec2};
 pub use crate::input::*;
 pub use crate::locals::{
-    Density, Dp, TextDirection, TextScale, Theme, UiScale, density, dp_to_px, text_direction,
-    text_scale, theme, ui_scale, with_density, with_text_direction, with_text_scale, with_theme,
-    with_ui_scale,
+    CompositionLocal, Density, Dp, TextDirection, TextScale, Theme, UiScale, composition_local_of,
+    density, dp_to_px, provide, text_direction, text_scale, theme, ui_scale, with_density,
+    with_text_direction, with_text_scale, with_theme, with_ui_scale,
 };
 pub use crate::modifier::Modifier;
 pub use crate::render_api::{GlyphRasterConfig, RenderBackend};
 pub use crate::runtime::{
-    ComposeGuard, Frame, Scheduler, remember, remember_state, remember_state_with_key,
+    ComposeGuard, Frame, Scheduler, memo, remember, remember_state, remember_state_with_key,
     remember_with_key,
 };
 pub use crate::scope::{Scope, current_scope, scoped_effect};
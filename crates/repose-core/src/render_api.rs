@@ -2,7 +2,9 @@ use crate::view::Scene;
 
 #[derive(Clone, Copy)]
 pub struct GlyphRasterConfig {
-    pub px: f32,
+    /// Device pixels per dp. Scene text sizes are already in device pixels, so glyphs are
+    /// rasterized at the size they're shown; this scales the largest size rasterized.
+    pub scale: f32,
 }
 
 pub trait RenderBackend {
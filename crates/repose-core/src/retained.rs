@@ -0,0 +1,119 @@
+//! # Retained widget state
+//!
+//! Some widget state lives outside composition: it is created by the platform runner in
+//! response to input (a text field's buffer on first focus) or by the painter (a drag in
+//! progress), and has to survive the tree being rebuilt every frame. Such state is kept in
+//! a [`RetainedStateStore`], keyed by the view's stable id — the id layout derives from the
+//! view's position and `Modifier::key` — and typed, so different widgets can share one store.
+//!
+//! Entries are evicted once their view leaves composition: layout marks the id of every
+//! view in the tree as live, and [`RetainedStateStore::evict_unseen`], called by the runner
+//! after each frame, drops the rest.
+
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+struct Entry {
+    /// Generation the view was last seen in.
+    seen: u64,
+    values: Vec<(TypeId, Rc<dyn Any>)>,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: RefCell<HashMap<u64, Entry>>,
+    generation: Cell<u64>,
+}
+
+/// Per-view state that outlives the frame, owned by the platform runner. Clones share the
+/// same entries.
+#[derive(Clone, Default)]
+pub struct RetainedStateStore {
+    inner: Rc<Inner>,
+}
+
+impl RetainedStateStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `T` kept for `key`, if any.
+    pub fn get<T: 'static>(&self, key: u64) -> Option<Rc<RefCell<T>>> {
+        let entries = self.inner.entries.borrow();
+        entries
+            .get(&key)?
+            .values
+            .iter()
+            .find(|(t, _)| *t == TypeId::of::<T>())
+            .and_then(|(_, v)| v.clone().downcast::<RefCell<T>>().ok())
+    }
+
+    /// The `T` kept for `key`, created with `init` the first time.
+    pub fn get_or_insert_with<T: 'static>(
+        &self,
+        key: u64,
+        init: impl FnOnce() -> T,
+    ) -> Rc<RefCell<T>> {
+        if let Some(v) = self.get::<T>(key) {
+            return v;
+        }
+        self.insert(key, init())
+    }
+
+    /// Keeps `value` for `key`, replacing any `T` kept before.
+    pub fn insert<T: 'static>(&self, key: u64, value: T) -> Rc<RefCell<T>> {
+        let rc = Rc::new(RefCell::new(value));
+        let generation = self.inner.generation.get();
+        let mut entries = self.inner.entries.borrow_mut();
+        let entry = entries.entry(key).or_insert_with(|| Entry {
+            seen: generation,
+            values: Vec::new(),
+        });
+        entry.seen = generation;
+        entry.values.retain(|(t, _)| *t != TypeId::of::<T>());
+        entry.values.push((TypeId::of::<T>(), rc.clone()));
+        rc
+    }
+
+    /// Drops the `T` kept for `key`.
+    pub fn remove<T: 'static>(&self, key: u64) {
+        let mut entries = self.inner.entries.borrow_mut();
+        if let Some(entry) = entries.get_mut(&key) {
+            entry.values.retain(|(t, _)| *t != TypeId::of::<T>());
+            if entry.values.is_empty() {
+                entries.remove(&key);
+            }
+        }
+    }
+
+    /// Marks the view `key` as still in composition, keeping its state through the next
+    /// [`evict_unseen`](Self::evict_unseen). Cheap for views without state.
+    pub fn mark_live(&self, key: u64) {
+        if let Some(entry) = self.inner.entries.borrow_mut().get_mut(&key) {
+            entry.seen = self.inner.generation.get();
+        }
+    }
+
+    /// Drops the state of every view that hasn't been marked live or given new state since
+    /// the last call, and returns how many views lost theirs. State inserted between frames
+    /// (by an input handler) so survives until its view has had a chance to be laid out.
+    pub fn evict_unseen(&self) -> usize {
+        let generation = self.inner.generation.get();
+        let mut entries = self.inner.entries.borrow_mut();
+        let before = entries.len();
+        entries.retain(|_, e| e.seen == generation);
+        self.inner.generation.set(generation + 1);
+        before - entries.len()
+    }
+
+    /// Number of views with state.
+    pub fn len(&self) -> usize {
+        self.inner.entries.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -3,7 +3,12 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
+use web_time::{Duration, Instant};
+
+use crate::bus::EventBus;
+use crate::layers::LayerHost;
 use crate::scope::Scope;
+use crate::stats::{FrameStats, JankTracker, clear_paint_costs, take_recorded_phases};
 use crate::{Rect, Scene, View, semantics::Role};
 
 thread_local! {
@@ -16,6 +21,8 @@ pub struct Composer {
     pub slots: Vec<Box<dyn Any>>,
     pub cursor: usize,
     pub keyed_slots: HashMap<String, Box<dyn Any>>,
+    /// `remember` slots of each [`keyed`] group.
+    pub groups: HashMap<u64, Vec<Box<dyn Any>>>,
 }
 
 pub struct ComposeGuard {
@@ -111,6 +118,32 @@ pub fn remember_with_key<T: 'static>(key: impl Into<String>, init: impl FnOnce()
     })
 }
 
+/// Runs `f` with its own `remember` slots, found by `key` rather than by position: state
+/// remembered inside follows the key when the call moves (list items reordering, or
+/// scrolling in and out of a lazy list). Groups are kept until [`forget_keyed`].
+pub fn keyed<R>(key: u64, f: impl FnOnce() -> R) -> R {
+    let outer = COMPOSER.with(|c| {
+        let mut c = c.borrow_mut();
+        let group = c.groups.remove(&key).unwrap_or_default();
+        let slots = std::mem::replace(&mut c.slots, group);
+        (slots, std::mem::replace(&mut c.cursor, 0))
+    });
+    let r = f();
+    COMPOSER.with(|c| {
+        let mut c = c.borrow_mut();
+        let group = std::mem::replace(&mut c.slots, outer.0);
+        c.cursor = outer.1;
+        c.groups.insert(key, group);
+    });
+    r
+}
+
+/// Drops the state remembered in a [`keyed`] group.
+pub fn forget_keyed(key: u64) {
+    let group = COMPOSER.with(|c| c.borrow_mut().groups.remove(&key));
+    drop(group);
+}
+
 pub fn remember_state<T: 'static>(init: impl FnOnce() -> T) -> Rc<RefCell<T>> {
     remember(|| RefCell::new(init()))
 }
@@ -122,28 +155,124 @@ pub fn remember_state_with_key<T: 'static>(
     remember_with_key(key, || RefCell::new(init()))
 }
 
+struct MemoEntry<K> {
+    key: K,
+    view: View,
+    /// `remember` slots the subtree took when it was built.
+    slots: usize,
+    /// Per-composition declarations the subtree made, repeated whenever it's reused.
+    declarations: Vec<Declaration>,
+}
+
+type Declaration = Rc<dyn Fn()>;
+
+thread_local! {
+    // One list per `memo` build in progress, innermost last.
+    static DECLARING: RefCell<Vec<Vec<Declaration>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Notes how to repeat a declaration that only lasts one composition (a subscription, a
+/// layer, a task scope), so a `memo` that reuses the subtree making it can declare it again.
+/// `repeat` is only made while a memo is being built, and should record itself the same way.
+pub(crate) fn record_declaration<F: Fn() + 'static>(repeat: impl FnOnce() -> F) {
+    DECLARING.with(|d| {
+        if let Some(building) = d.borrow_mut().last_mut() {
+            building.push(Rc::new(repeat()));
+        }
+    });
+}
+
+/// Returns the subtree `build` made the last time this call site saw an equal `key`, and
+/// only runs `build` when the key changed, so static parts of a screen (a sidebar, a
+/// toolbar) aren't rebuilt every time something else changes.
+///
+/// `key` has to cover everything the subtree reads: a reused tree keeps the values and
+/// callbacks it was built with. The subtree's `remember` slots are kept while it's reused,
+/// and what it declared for the composition (`use_subscription`, layers, `task_scope`) is
+/// declared again. Layout still runs over a reused subtree every frame.
+///
+/// ```rust
+/// use repose_core::*;
+///
+/// fn sidebar(items: &[String]) -> View {
+///     memo(items.to_vec(), || {
+///         // built again only when `items` changes
+///         View::new(0, ViewKind::Column)
+///     })
+/// }
+/// ```
+pub fn memo<K: PartialEq + 'static>(key: K, build: impl FnOnce() -> View) -> View {
+    let entry = remember(|| RefCell::new(None::<MemoEntry<K>>));
+    let reused = entry.borrow().as_ref().filter(|e| e.key == key).map(|e| {
+        COMPOSER.with(|c| c.borrow_mut().cursor += e.slots);
+        (e.view.clone(), e.declarations.clone())
+    });
+    if let Some((view, declarations)) = reused {
+        for declare in &declarations {
+            declare();
+        }
+        return view;
+    }
+    let start = COMPOSER.with(|c| c.borrow().cursor);
+    DECLARING.with(|d| d.borrow_mut().push(Vec::new()));
+    let view = build();
+    let declarations = DECLARING.with(|d| {
+        let mut d = d.borrow_mut();
+        let made = d.pop().unwrap_or_default();
+        // An enclosing memo repeats these too.
+        if let Some(outer) = d.last_mut() {
+            outer.extend(made.iter().cloned());
+        }
+        made
+    });
+    let slots = COMPOSER.with(|c| c.borrow().cursor) - start;
+    *entry.borrow_mut() = Some(MemoEntry {
+        key,
+        view: view.clone(),
+        slots,
+        declarations,
+    });
+    view
+}
+
 /// Frame — output of composition for a tick: scene + input/semantics.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Frame {
     pub scene: Scene,
     pub hit_regions: Vec<HitRegion>,
     pub semantics_nodes: Vec<SemNode>,
     pub focus_chain: Vec<u64>,
+    /// Per-phase timings; `gpu_ms` is left at 0 for the platform runner to fill in.
+    pub stats: FrameStats,
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HitRegion {
     pub id: u64,
     pub rect: Rect,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub on_click: Option<Rc<dyn Fn()>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub on_scroll: Option<Rc<dyn Fn(crate::Vec2) -> crate::Vec2>>,
     pub focusable: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub on_pointer_down: Option<Rc<dyn Fn(crate::input::PointerEvent)>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub on_pointer_move: Option<Rc<dyn Fn(crate::input::PointerEvent)>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub on_pointer_up: Option<Rc<dyn Fn(crate::input::PointerEvent)>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub on_pointer_enter: Option<Rc<dyn Fn(crate::input::PointerEvent)>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub on_pointer_leave: Option<Rc<dyn Fn(crate::input::PointerEvent)>>,
+    /// Key handler; returns whether it consumed the event. See `Modifier::on_key_event`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub on_key_event: Option<Rc<dyn Fn(crate::input::KeyEvent) -> bool>>,
     pub z_index: f32,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub on_text_change: Option<Rc<dyn Fn(String)>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub on_text_submit: Option<Rc<dyn Fn(String)>>,
     /// If this hit region belongs to a TextField, this persistent key is used
     /// for looking up platform-managed TextFieldState. Falls back to `id` if None.
@@ -157,7 +286,8 @@ pub struct HitRegion {
 ///
 /// The platform runner should convert this into OS‑specific accessibility trees (when implemented)
 /// (AT‑SPI on Linux, TalkBack on Android, etc.).
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SemNode {
     /// Stable id, shared with the associated `HitRegion` / `ViewId`.
     pub id: u64,
@@ -166,12 +296,25 @@ pub struct SemNode {
     pub rect: Rect,
     pub focused: bool,
     pub enabled: bool,
+    /// Only part of `label` is on screen (e.g. ellipsized text); `label` is the full string.
+    pub truncated: bool,
+    /// Checked state of a toggle.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub toggle: Option<crate::ToggleableState>,
 }
 
 pub struct Scheduler {
     next_id: u64,
     pub focused: Option<u64>,
     pub size: (u32, u32),
+    /// Rolling frame timings; platform runners record each frame once it's submitted.
+    pub jank: JankTracker,
+    /// Window-level layers declared during the last composition.
+    pub layers: LayerHost,
+    /// Events emitted with [`crate::emit`] wait here until the start of the next frame.
+    pub bus: EventBus,
+    // The animation clock `advance` took over, put back when the scheduler is dropped.
+    test_clock: Option<crate::animation::ClockGuard>,
 }
 
 impl Default for Scheduler {
@@ -186,6 +329,10 @@ impl Scheduler {
             next_id: 1,
             focused: None,
             size: (1280, 800),
+            jank: JankTracker::default(),
+            layers: LayerHost::default(),
+            bus: EventBus::current(),
+            test_clock: None,
         }
     }
 
@@ -197,23 +344,92 @@ impl Scheduler {
 
     pub fn repose<F>(
         &mut self,
-        mut build_root: F,
+        build_root: F,
         layout_paint: impl Fn(&View, (u32, u32)) -> (Scene, Vec<HitRegion>, Vec<SemNode>),
     ) -> Frame
     where
         F: FnMut(&mut Scheduler) -> View,
     {
-        let guard = ComposeGuard::begin();
-        let root = guard.scope.run(|| build_root(self));
-        let (scene, hits, sem) = layout_paint(&root, self.size);
+        self.begin_frame();
+        let t0 = Instant::now();
+        let root = self.compose(build_root);
+        let compose_ms = t0.elapsed().as_secs_f32() * 1000.0;
 
-        let focus_chain: Vec<u64> = hits.iter().filter(|h| h.focusable).map(|h| h.id).collect();
+        take_recorded_phases();
+        clear_paint_costs();
+        let t1 = Instant::now();
+        let (mut scene, mut hits, mut sem) = layout_paint(&root, self.size);
+        let modal_focus =
+            self.layers
+                .paint_over(&layout_paint, self.size, &mut scene, &mut hits, &mut sem);
+        let layout_paint_ms = t1.elapsed().as_secs_f32() * 1000.0;
+        let (measure_ms, paint_ms) = take_recorded_phases().unwrap_or((layout_paint_ms, 0.0));
+
+        let modal = modal_focus.is_some();
+        let focus_chain: Vec<u64> = modal_focus
+            .unwrap_or_else(|| hits.iter().filter(|h| h.focusable).map(|h| h.id).collect());
+        self.layers
+            .trap_focus(&mut self.focused, modal, &focus_chain);
 
         Frame {
             scene,
             hit_regions: hits,
             semantics_nodes: sem,
             focus_chain,
+            stats: FrameStats {
+                compose_ms,
+                measure_ms,
+                paint_ms,
+                gpu_ms: 0.0,
+            },
         }
     }
+
+    /// Moves the animation clock `frame_time` forward and runs a frame without layout or
+    /// paint: queued snapshots and events, frame callbacks, then composition and its effects.
+    /// Returns the composed tree.
+    ///
+    /// Meant for unit tests of state and animation timing: no backend is involved, and the
+    /// clock becomes a `TestClock`, so results don't depend on how long the test takes. The
+    /// first call takes the clock over with [`swap_clock`](crate::animation::swap_clock), so
+    /// it waits for other tests driving the clock, and the scheduler puts the previous clock
+    /// back when it's dropped.
+    pub fn advance<F>(&mut self, frame_time: Duration, build_root: F) -> View
+    where
+        F: FnMut(&mut Scheduler) -> View,
+    {
+        use crate::animation::{TestClock, now, set_clock, swap_clock};
+
+        let t = now();
+        self.test_clock
+            .get_or_insert_with(|| swap_clock(Box::new(TestClock { t })));
+        set_clock(Box::new(TestClock { t: t + frame_time }));
+        self.begin_frame();
+        self.compose(build_root)
+    }
+
+    /// Work due before composition: cross-thread snapshots, bus events, frame callbacks,
+    /// woken tasks.
+    fn begin_frame(&mut self) {
+        crate::snapshot::apply_snapshots();
+        self.bus.deliver();
+        self.bus.begin_composition();
+        crate::frame_clock::begin_frame();
+        crate::tasks::run_ready_tasks();
+        crate::intern::sweep_interned();
+    }
+
+    fn compose<F>(&mut self, mut build_root: F) -> View
+    where
+        F: FnMut(&mut Scheduler) -> View,
+    {
+        profiling::scope!("compose");
+        let _span = tracing::info_span!("compose").entered();
+        let guard = ComposeGuard::begin();
+        self.layers.begin_frame();
+        let root = guard.scope.run(|| build_root(self));
+        self.layers.end_composition();
+        crate::tasks::end_composition();
+        root
+    }
 }
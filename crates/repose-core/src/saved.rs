@@ -0,0 +1,53 @@
+//! # Saved values
+//!
+//! A registry of small string values by key, for state that should outlive the views that
+//! use it: recent searches, the last selected tab, an unsent draft. Widgets write through
+//! [`save_value`] whenever the value changes and read it back with [`saved_value`] when they
+//! are created again.
+//!
+//! The registry lives for the process. To keep it across restarts (or an Android process
+//! being killed in the background), the app or runner stores [`saved_values`] somewhere
+//! durable and hands them back with [`restore_saved_values`] before the first frame.
+//!
+//! ```rust
+//! use repose_core::*;
+//!
+//! save_value("tabs.selected", "2");
+//! let stored = saved_values();
+//!
+//! // ...next launch:
+//! restore_saved_values(stored);
+//! assert_eq!(saved_value("tabs.selected").as_deref(), Some("2"));
+//! ```
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+thread_local! {
+    static SAVED: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+/// Stores `value` under `key`, replacing what was there.
+pub fn save_value(key: impl Into<String>, value: impl Into<String>) {
+    SAVED.with(|s| s.borrow_mut().insert(key.into(), value.into()));
+}
+
+/// The value last stored under `key`.
+pub fn saved_value(key: &str) -> Option<String> {
+    SAVED.with(|s| s.borrow().get(key).cloned())
+}
+
+/// Drops the value under `key`.
+pub fn forget_saved_value(key: &str) {
+    SAVED.with(|s| s.borrow_mut().remove(key));
+}
+
+/// Every stored value, for the app to persist.
+pub fn saved_values() -> HashMap<String, String> {
+    SAVED.with(|s| s.borrow().clone())
+}
+
+/// Adds previously persisted values to the registry, replacing any under the same keys.
+pub fn restore_saved_values(values: impl IntoIterator<Item = (String, String)>) {
+    SAVED.with(|s| s.borrow_mut().extend(values));
+}
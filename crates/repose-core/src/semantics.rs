@@ -1,5 +1,6 @@
 /// High‑level semantic role of a view, similar to ARIA roles.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Role {
     Text,
     Button,
@@ -12,6 +13,22 @@ pub enum Role {
     ProgressBar,
 }
 
+/// On/off state of a checkbox, switch or other toggle, reported to screen readers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ToggleableState {
+    Off,
+    On,
+    /// Neither, e.g. a "select all" checkbox over a partly selected list.
+    Indeterminate,
+}
+
+impl From<bool> for ToggleableState {
+    fn from(on: bool) -> Self {
+        if on { Self::On } else { Self::Off }
+    }
+}
+
 /// Semantics attached to a `View`, used to build the accessibility tree.
 #[derive(Clone, Debug)]
 pub struct Semantics {
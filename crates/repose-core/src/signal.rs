@@ -1,5 +1,5 @@
 use std::cell::RefCell;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::reactive;
@@ -8,6 +8,41 @@ pub type SubId = usize;
 
 static NEXT_SIGNAL_ID: AtomicUsize = AtomicUsize::new(1);
 
+type Notify = (usize, Box<dyn FnOnce()>);
+
+thread_local! {
+    // Signals written inside `batch`, notified once when it ends.
+    static BATCH: RefCell<Option<Vec<Notify>>> = const { RefCell::new(None) };
+}
+
+/// Runs `f`, deferring signal notifications until it returns. Each signal written inside
+/// notifies its subscribers and observers once, with its final value. Nested batches join
+/// the outer one.
+pub fn batch<R>(f: impl FnOnce() -> R) -> R {
+    if BATCH.with(|b| b.borrow().is_some()) {
+        return f();
+    }
+    BATCH.with(|b| *b.borrow_mut() = Some(Vec::new()));
+    let guard = EndBatch;
+    let out = f();
+    let pending = BATCH.with(|b| b.borrow_mut().take()).unwrap_or_default();
+    drop(guard);
+    for (_, notify) in pending {
+        notify();
+    }
+    out
+}
+
+/// Ends the batch even if `f` panics, dropping its notifications, so later writes don't
+/// queue up behind a batch that never finishes.
+struct EndBatch;
+
+impl Drop for EndBatch {
+    fn drop(&mut self) {
+        BATCH.with(|b| b.borrow_mut().take());
+    }
+}
+
 pub struct Signal<T: 'static>(Rc<RefCell<Inner<T>>>);
 
 impl<T> Clone for Signal<T> {
@@ -52,35 +87,36 @@ impl<T> Signal<T> {
     /// without panicking. (Mutating the same signal inside its own subscriber is still
     /// considered invalid and may panic, which is a reasonable constraint for a small core.)
     pub fn set(&self, v: T) {
-        let id = {
-            let mut inner = self.0.borrow_mut();
-            inner.value = v;
-            inner.id
-        };
+        self.0.borrow_mut().value = v;
+        self.notify();
+    }
 
-        // Call subscribers under an immutable borrow (safe for reads).
-        {
-            let inner = self.0.borrow();
-            let vref = &inner.value;
-            for s in &inner.subs {
-                if let Some(cb) = s.as_ref() {
-                    cb(vref);
+    pub fn update<F: FnOnce(&mut T)>(&self, f: F) {
+        f(&mut self.0.borrow_mut().value);
+        self.notify();
+    }
+
+    /// Notifies now, or at the end of the enclosing [`batch`].
+    fn notify(&self) {
+        let id = self.0.borrow().id;
+        let deferred = BATCH.with(|b| match b.borrow_mut().as_mut() {
+            Some(pending) => {
+                if !pending.iter().any(|(p, _)| *p == id) {
+                    let sig = self.clone();
+                    pending.push((id, Box::new(move || sig.notify_now())));
                 }
+                true
             }
+            None => false,
+        });
+        if !deferred {
+            self.notify_now();
         }
-
-        // Notify reactive graph after all borrows are dropped.
-        reactive::signal_changed(id);
     }
 
-    pub fn update<F: FnOnce(&mut T)>(&self, f: F) {
+    fn notify_now(&self) {
+        // Call subscribers under an immutable borrow (safe for reads).
         let id = {
-            let mut inner = self.0.borrow_mut();
-            f(&mut inner.value);
-            inner.id
-        };
-
-        {
             let inner = self.0.borrow();
             let vref = &inner.value;
             for s in &inner.subs {
@@ -88,11 +124,17 @@ impl<T> Signal<T> {
                     cb(vref);
                 }
             }
-        }
+            inner.id
+        };
 
+        // Notify reactive graph after all borrows are dropped.
         reactive::signal_changed(id);
     }
 
+    pub(crate) fn downgrade(&self) -> WeakSignal<T> {
+        WeakSignal(Rc::downgrade(&self.0))
+    }
+
     pub fn subscribe(&self, f: impl Fn(&T) + 'static) -> SubId {
         self.0.borrow_mut().subs.push(Some(Box::new(f)));
         self.0.borrow().subs.len() - 1
@@ -121,6 +163,14 @@ impl<T> Signal<T> {
     }
 }
 
+pub(crate) struct WeakSignal<T: 'static>(Weak<RefCell<Inner<T>>>);
+
+impl<T> WeakSignal<T> {
+    pub(crate) fn upgrade(&self) -> Option<Signal<T>> {
+        self.0.upgrade().map(Signal)
+    }
+}
+
 pub fn signal<T>(t: T) -> Signal<T> {
     Signal::new(t)
 }
@@ -0,0 +1,239 @@
+//! # Snapshots
+//!
+//! `Signal`s live on the UI thread. Background work changes them through a [`Snapshot`]: it
+//! records writes against [`SignalWriter`]s (which are `Send`), and [`Snapshot::apply`]
+//! hands the whole set to the UI thread that made the writers. At the start of the next frame `Scheduler::repose`
+//! applies every queued snapshot in order, inside one [`batch`](crate::batch), so no
+//! observer or subscriber sees a half-applied snapshot and each signal notifies once per
+//! frame however many writes it received.
+//!
+//! ```rust
+//! use repose_core::*;
+//!
+//! let progress = signal(0.0f32);
+//! let status = signal(String::new());
+//! let (p, s) = (progress.writer(), status.writer());
+//!
+//! std::thread::spawn(move || {
+//!     let mut snap = Snapshot::new();
+//!     snap.set(&p, 1.0);
+//!     snap.update(&s, |s| s.push_str("done"));
+//!     snap.apply();
+//! })
+//! .join()
+//! .unwrap();
+//!
+//! apply_snapshots(); // what the scheduler does each frame
+//! assert_eq!((progress.get(), status.get()), (1.0, "done".to_string()));
+//! ```
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::OnceLock;
+use std::thread::{self, ThreadId};
+
+use parking_lot::{Mutex, RwLock};
+
+use crate::{Signal, batch};
+
+type Op<T> = Box<dyn FnOnce(&mut T) + Send>;
+
+/// What a snapshot does to one signal.
+enum Entry<T> {
+    /// A value was set (later updates in the same snapshot were folded into it).
+    Value(T),
+    /// Updates against whatever the signal holds when the snapshot is applied.
+    Ops(Vec<Op<T>>),
+}
+
+type Applier = Rc<dyn Fn(Box<dyn Any + Send>) -> bool>;
+type Wake = Box<dyn Fn() + Send + Sync>;
+
+thread_local! {
+    // signal id -> applies an `Entry<T>` to it; false once the signal is gone
+    static APPLIERS: RefCell<HashMap<usize, Applier>> = RefCell::new(HashMap::new());
+}
+
+// Queued snapshots by the UI thread that applies them (the one owning their signals).
+static PENDING: OnceLock<Mutex<HashMap<ThreadId, Vec<Snapshot>>>> = OnceLock::new();
+static WAKER: OnceLock<RwLock<Option<Wake>>> = OnceLock::new();
+
+fn pending() -> &'static Mutex<HashMap<ThreadId, Vec<Snapshot>>> {
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn waker() -> &'static RwLock<Option<Wake>> {
+    WAKER.get_or_init(|| RwLock::new(None))
+}
+
+/// A `Send` handle for writing one `Signal` from a [`Snapshot`]. Get it from
+/// [`Signal::writer`] on the UI thread.
+pub struct SignalWriter<T> {
+    id: usize,
+    owner: ThreadId,
+    _t: PhantomData<fn(T)>,
+}
+
+impl<T> Clone for SignalWriter<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for SignalWriter<T> {}
+
+impl<T: Send + 'static> Signal<T> {
+    /// A handle that lets snapshots on other threads write this signal. Snapshots are
+    /// applied on the thread that called this (the UI thread).
+    pub fn writer(&self) -> SignalWriter<T> {
+        let weak = self.downgrade();
+        let apply: Applier = Rc::new(move |entry| {
+            let Some(sig) = weak.upgrade() else {
+                return false;
+            };
+            if let Ok(entry) = entry.downcast::<Entry<T>>() {
+                match *entry {
+                    Entry::Value(v) => sig.set(v),
+                    Entry::Ops(ops) => sig.update(|v| ops.into_iter().for_each(|op| op(v))),
+                }
+            }
+            true
+        });
+        APPLIERS.with(|a| a.borrow_mut().insert(self.id(), apply));
+        SignalWriter {
+            id: self.id(),
+            owner: thread::current().id(),
+            _t: PhantomData,
+        }
+    }
+}
+
+/// A set of signal writes applied together on the UI thread.
+#[derive(Default)]
+pub struct Snapshot {
+    entries: Vec<(usize, ThreadId, Box<dyn Any + Send>)>,
+}
+
+impl Snapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn entry<T: Send + 'static>(&mut self, w: &SignalWriter<T>) -> Option<&mut Entry<T>> {
+        self.entries
+            .iter_mut()
+            .find(|(id, _, _)| *id == w.id)
+            .and_then(|(_, _, e)| e.downcast_mut::<Entry<T>>())
+    }
+
+    /// Sets the signal, replacing anything written to it earlier in this snapshot.
+    pub fn set<T: Send + 'static>(&mut self, w: &SignalWriter<T>, value: T) {
+        match self.entry(w) {
+            Some(e) => *e = Entry::Value(value),
+            None => self
+                .entries
+                .push((w.id, w.owner, Box::new(Entry::Value(value)))),
+        }
+    }
+
+    /// Modifies the signal. After a [`set`](Self::set) in this snapshot `f` runs right away
+    /// on that value; otherwise it runs on the UI thread against the signal's value then.
+    pub fn update<T: Send + 'static>(
+        &mut self,
+        w: &SignalWriter<T>,
+        f: impl FnOnce(&mut T) + Send + 'static,
+    ) {
+        match self.entry(w) {
+            Some(Entry::Value(v)) => f(v),
+            Some(Entry::Ops(ops)) => ops.push(Box::new(f)),
+            None => {
+                self.entries
+                    .push((w.id, w.owner, Box::new(Entry::<T>::Ops(vec![Box::new(f)]))))
+            }
+        }
+    }
+
+    /// The value set for this signal in this snapshot, if any.
+    pub fn get<T: Send + 'static>(&mut self, w: &SignalWriter<T>) -> Option<&T> {
+        match self.entry(w) {
+            Some(Entry::Value(v)) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Queues the snapshot for the UI thread and wakes the runner to produce a frame. Writes
+    /// to signals of different UI threads are queued for each of them separately.
+    pub fn apply(self) {
+        if self.is_empty() {
+            return;
+        }
+        let mut by_owner: Vec<Snapshot> = Vec::new();
+        for entry in self.entries {
+            match by_owner.iter_mut().find(|s| s.entries[0].1 == entry.1) {
+                Some(s) => s.entries.push(entry),
+                None => by_owner.push(Snapshot {
+                    entries: vec![entry],
+                }),
+            }
+        }
+        let mut pending = pending().lock();
+        for snap in by_owner {
+            pending.entry(snap.entries[0].1).or_default().push(snap);
+        }
+        drop(pending);
+        wake_runner();
+    }
+}
+
+/// Asks the runner for a frame, from any thread.
+pub(crate) fn wake_runner() {
+    if let Some(wake) = waker().read().as_ref() {
+        wake();
+    }
+}
+
+/// Whether snapshots are waiting to be applied on this thread.
+pub fn snapshots_pending() -> bool {
+    pending()
+        .lock()
+        .get(&thread::current().id())
+        .is_some_and(|q| !q.is_empty())
+}
+
+/// Applies the snapshots queued for this thread's signals, in the order they were applied,
+/// as one batch. Returns how many were applied. `Scheduler::repose` calls this at the start
+/// of every frame.
+pub fn apply_snapshots() -> usize {
+    let snaps = pending()
+        .lock()
+        .remove(&thread::current().id())
+        .unwrap_or_default();
+    if snaps.is_empty() {
+        return 0;
+    }
+    let n = snaps.len();
+    batch(|| {
+        for snap in snaps {
+            for (id, _, entry) in snap.entries {
+                let apply = APPLIERS.with(|a| a.borrow().get(&id).cloned());
+                if !apply.is_some_and(|apply| apply(entry)) {
+                    APPLIERS.with(|a| a.borrow_mut().remove(&id));
+                    log::warn!("snapshot: signal {id} is gone; discarding its write");
+                }
+            }
+        }
+    });
+    n
+}
+
+/// Installs the runner's wake-up, called (from any thread) when a snapshot is queued or a
+/// task is woken.
+pub fn set_snapshot_waker(wake: impl Fn() + Send + Sync + 'static) {
+    *waker().write() = Some(Box::new(wake));
+}
@@ -0,0 +1,226 @@
+//! # Frame statistics
+//!
+//! `Scheduler::repose` times composition itself; layout backends report the measure and
+//! paint split through [`record_phase`], and platform runners add the GPU submit time
+//! before handing the finished [`FrameStats`] to the scheduler's [`JankTracker`].
+//!
+//! The same phases are `tracing` spans — `frame` (opened by the runner) around `compose`,
+//! `layout`, `paint` and `render` — so a `tracing-chrome` or Tracy subscriber shows where
+//! frame time goes over a whole session. Enable `repose-ui`'s `trace-widgets` feature for
+//! a `view` span per widget painted.
+//!
+//! With [`set_paint_cost_tracking`] on, backends also attribute the scene they paint to the
+//! views that emitted it ([`PaintCost`]), for the devtools paint cost overlay.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+use crate::{Rect, ViewId};
+
+/// Wall-clock milliseconds spent in each phase of one frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameStats {
+    /// Running the root composable (building the `View` tree).
+    pub compose_ms: f32,
+    /// Building the layout tree and running Taffy (including text measurement).
+    pub measure_ms: f32,
+    /// Walking the laid-out tree into scene nodes, hit regions and semantics.
+    pub paint_ms: f32,
+    /// Encoding and submitting the scene to the GPU (filled in by the platform runner).
+    pub gpu_ms: f32,
+}
+
+impl FrameStats {
+    pub fn total_ms(&self) -> f32 {
+        self.compose_ms + self.measure_ms + self.paint_ms + self.gpu_ms
+    }
+}
+
+/// A phase reported by a layout backend via [`record_phase`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FramePhase {
+    Measure,
+    Paint,
+}
+
+thread_local! {
+    static PENDING: Cell<Option<(f32, f32)>> = const { Cell::new(None) };
+}
+
+/// Reports time spent in a layout phase for the frame currently being built.
+///
+/// Backends that don't call this get their whole `layout_paint` time booked as measure.
+pub fn record_phase(phase: FramePhase, ms: f32) {
+    PENDING.with(|p| {
+        let (mut measure, mut paint) = p.get().unwrap_or((0.0, 0.0));
+        match phase {
+            FramePhase::Measure => measure += ms,
+            FramePhase::Paint => paint += ms,
+        }
+        p.set(Some((measure, paint)));
+    });
+}
+
+pub(crate) fn take_recorded_phases() -> Option<(f32, f32)> {
+    PENDING.with(|p| p.take())
+}
+
+/// Scene output attributed to one view in the last frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PaintCost {
+    pub id: ViewId,
+    /// Depth in the view tree (the root is 0).
+    pub depth: u32,
+    pub rect: Rect,
+    /// Scene nodes the view emitted itself.
+    pub nodes: u32,
+    /// Pixels those nodes cover (see `SceneNode::coverage_px`); overlapping nodes count twice.
+    pub fill_px: f32,
+    /// Both of the above including every descendant.
+    pub subtree_nodes: u32,
+    pub subtree_fill_px: f32,
+}
+
+thread_local! {
+    static TRACK_PAINT_COST: Cell<bool> = const { Cell::new(false) };
+    static PAINT_COSTS: RefCell<Vec<PaintCost>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Turns per-view paint cost attribution on or off. It costs a little per view, so it's
+/// off unless a tool asks for it.
+pub fn set_paint_cost_tracking(on: bool) {
+    TRACK_PAINT_COST.with(|t| t.set(on));
+    if !on {
+        PAINT_COSTS.with(|c| c.borrow_mut().clear());
+    }
+}
+
+pub fn paint_cost_tracking() -> bool {
+    TRACK_PAINT_COST.with(|t| t.get())
+}
+
+/// Adds costs for the frame being built. Backends call this once per paint pass.
+pub fn record_paint_costs(costs: impl IntoIterator<Item = PaintCost>) {
+    PAINT_COSTS.with(|c| c.borrow_mut().extend(costs));
+}
+
+pub(crate) fn clear_paint_costs() {
+    PAINT_COSTS.with(|c| c.borrow_mut().clear());
+}
+
+/// Per-view costs of the last frame, children before their parents.
+pub fn paint_costs() -> Vec<PaintCost> {
+    PAINT_COSTS.with(|c| c.borrow().clone())
+}
+
+/// Rolling window of recent frames with over-budget ("janky") frame detection.
+#[derive(Clone, Debug)]
+pub struct JankTracker {
+    /// Frame budget in ms; frames whose `total_ms` exceed it count as jank.
+    pub budget_ms: f32,
+    capacity: usize,
+    history: VecDeque<FrameStats>,
+    total_frames: u64,
+    total_janky: u64,
+}
+
+impl Default for JankTracker {
+    fn default() -> Self {
+        // 60 Hz, two seconds of history.
+        Self::new(1000.0 / 60.0, 120)
+    }
+}
+
+impl JankTracker {
+    pub fn new(budget_ms: f32, capacity: usize) -> Self {
+        Self {
+            budget_ms,
+            capacity: capacity.max(1),
+            history: VecDeque::with_capacity(capacity.max(1)),
+            total_frames: 0,
+            total_janky: 0,
+        }
+    }
+
+    /// Records a finished frame; returns `true` if it was over budget.
+    pub fn record(&mut self, stats: FrameStats) -> bool {
+        if self.history.len() == self.capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back(stats);
+        self.total_frames += 1;
+        let janky = self.is_janky(&stats);
+        if janky {
+            self.total_janky += 1;
+            tracing::debug!(
+                total_ms = stats.total_ms(),
+                budget_ms = self.budget_ms,
+                compose_ms = stats.compose_ms,
+                measure_ms = stats.measure_ms,
+                paint_ms = stats.paint_ms,
+                gpu_ms = stats.gpu_ms,
+                "janky frame"
+            );
+        }
+        janky
+    }
+
+    pub fn is_janky(&self, stats: &FrameStats) -> bool {
+        stats.total_ms() > self.budget_ms
+    }
+
+    /// Frames in the rolling window, oldest first.
+    pub fn recent(&self) -> impl Iterator<Item = &FrameStats> {
+        self.history.iter()
+    }
+
+    pub fn last(&self) -> Option<FrameStats> {
+        self.history.back().copied()
+    }
+
+    /// Number of over-budget frames in the rolling window.
+    pub fn janky_frames(&self) -> usize {
+        self.history.iter().filter(|s| self.is_janky(s)).count()
+    }
+
+    /// Fraction of over-budget frames in the rolling window (0..=1).
+    pub fn jank_ratio(&self) -> f32 {
+        if self.history.is_empty() {
+            0.0
+        } else {
+            self.janky_frames() as f32 / self.history.len() as f32
+        }
+    }
+
+    /// Slowest frame in the rolling window.
+    pub fn worst(&self) -> Option<FrameStats> {
+        self.history
+            .iter()
+            .copied()
+            .max_by(|a, b| a.total_ms().total_cmp(&b.total_ms()))
+    }
+
+    /// Per-phase mean over the rolling window.
+    pub fn average(&self) -> FrameStats {
+        let n = self.history.len().max(1) as f32;
+        let mut sum = FrameStats::default();
+        for s in &self.history {
+            sum.compose_ms += s.compose_ms;
+            sum.measure_ms += s.measure_ms;
+            sum.paint_ms += s.paint_ms;
+            sum.gpu_ms += s.gpu_ms;
+        }
+        FrameStats {
+            compose_ms: sum.compose_ms / n,
+            measure_ms: sum.measure_ms / n,
+            paint_ms: sum.paint_ms / n,
+            gpu_ms: sum.gpu_ms / n,
+        }
+    }
+
+    /// Lifetime counters: (frames recorded, janky frames).
+    pub fn totals(&self) -> (u64, u64) {
+        (self.total_frames, self.total_janky)
+    }
+}
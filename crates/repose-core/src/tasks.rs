@@ -0,0 +1,248 @@
+//! # Tasks
+//!
+//! [`task_scope`] gives a composable somewhere to run async work. Futures spawned on the
+//! returned [`TaskScope`] run on the UI thread, so they can touch `Signal`s and other
+//! `!Send` state directly; `Scheduler::repose` polls the ones that were woken at the start
+//! of each frame. They are dropped, which cancels them at their next `.await`, once the
+//! composable stops calling `task_scope` (it left the tree), or on [`TaskScope::cancel`].
+//!
+//! ```rust
+//! use repose_core::*;
+//! use std::time::Duration;
+//!
+//! fn search_results(query: &str, results: Signal<Vec<String>>) {
+//!     let tasks = task_scope();
+//!     let last = remember(|| std::cell::RefCell::new((String::new(), None::<TaskHandle>)));
+//!     let mut last = last.borrow_mut();
+//!     if last.0 != query {
+//!         // Search once typing pauses: each edit replaces the waiting task.
+//!         if let Some(old) = last.1.take() {
+//!             old.cancel();
+//!         }
+//!         let q = query.to_string();
+//!         last.0 = q.clone();
+//!         last.1 = Some(tasks.spawn(async move {
+//!             delay(Duration::from_millis(300)).await;
+//!             results.set(vec![format!("results for {q}")]);
+//!         }));
+//!     }
+//! }
+//! ```
+//!
+//! Wakers are `Send`: a future may wait on work finishing on another thread, and waking it
+//! wakes the runner (see [`set_snapshot_waker`](crate::set_snapshot_waker)) to produce the
+//! frame that polls it.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::{Rc, Weak};
+use std::sync::Arc;
+use std::task::{Context, Wake, Waker};
+
+use parking_lot::Mutex;
+use web_time::Duration;
+
+type LocalFuture = Pin<Box<dyn Future<Output = ()>>>;
+
+thread_local! {
+    static NEXT_TASK: Cell<u64> = const { Cell::new(1) };
+    // task id -> the scope holding its future
+    static OWNERS: RefCell<HashMap<u64, Weak<ScopeInner>>> = RefCell::new(HashMap::new());
+    // Woken task ids; shared with the wakers, which may be called from any thread.
+    static READY: Arc<Mutex<Vec<u64>>> = Arc::new(Mutex::new(Vec::new()));
+    // Scopes declared with `task_scope` in the previous and the current composition.
+    static DECLARED: RefCell<(Vec<TaskScope>, Vec<TaskScope>)> =
+        const { RefCell::new((Vec::new(), Vec::new())) };
+}
+
+#[derive(Default)]
+struct ScopeInner {
+    // `None` while the task is being polled.
+    tasks: RefCell<HashMap<u64, Option<LocalFuture>>>,
+}
+
+/// Runs futures on the UI thread until they finish or the scope is cancelled; see the
+/// [module docs](self). Clones share the same tasks.
+#[derive(Clone, Default)]
+pub struct TaskScope {
+    inner: Rc<ScopeInner>,
+}
+
+impl TaskScope {
+    /// A scope that isn't tied to any composable: its tasks run until they finish, until
+    /// [`cancel`](Self::cancel), or until the last clone is dropped.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts `fut`; it is first polled at the start of the next frame.
+    pub fn spawn(&self, fut: impl Future<Output = ()> + 'static) -> TaskHandle {
+        let id = NEXT_TASK.with(|n| n.replace(n.get() + 1));
+        self.inner
+            .tasks
+            .borrow_mut()
+            .insert(id, Some(Box::pin(fut)));
+        OWNERS.with(|o| o.borrow_mut().insert(id, Rc::downgrade(&self.inner)));
+        wake_task(id);
+        TaskHandle {
+            scope: Rc::downgrade(&self.inner),
+            id,
+        }
+    }
+
+    /// Drops every task still running.
+    pub fn cancel(&self) {
+        let tasks = std::mem::take(&mut *self.inner.tasks.borrow_mut());
+        OWNERS.with(|o| {
+            let mut o = o.borrow_mut();
+            for id in tasks.keys() {
+                o.remove(id);
+            }
+        });
+    }
+
+    /// How many tasks haven't finished yet.
+    pub fn active_tasks(&self) -> usize {
+        self.inner.tasks.borrow().len()
+    }
+}
+
+/// One task spawned on a [`TaskScope`].
+#[derive(Clone, Debug)]
+pub struct TaskHandle {
+    scope: Weak<ScopeInner>,
+    id: u64,
+}
+
+impl TaskHandle {
+    /// Drops the task if it is still running.
+    pub fn cancel(&self) {
+        if let Some(scope) = self.scope.upgrade() {
+            // Dropped after the borrow ends, in case dropping it touches the scope.
+            let _task = scope.tasks.borrow_mut().remove(&self.id);
+        }
+        OWNERS.with(|o| o.borrow_mut().remove(&self.id));
+    }
+
+    /// Whether the task has neither finished nor been cancelled.
+    pub fn is_active(&self) -> bool {
+        self.scope
+            .upgrade()
+            .is_some_and(|s| s.tasks.borrow().contains_key(&self.id))
+    }
+}
+
+/// A [`TaskScope`] that lives as long as the calling composable: call it every
+/// composition, and its tasks are cancelled after the first composition that doesn't.
+/// Like `remember`, it is matched to the call by position.
+pub fn task_scope() -> TaskScope {
+    let scope = (*crate::remember(TaskScope::new)).clone();
+    declare(scope.clone());
+    scope
+}
+
+fn declare(scope: TaskScope) {
+    DECLARED.with(|d| d.borrow_mut().1.push(scope.clone()));
+    crate::runtime::record_declaration(|| move || declare(scope.clone()));
+}
+
+/// Whether woken tasks are waiting for a frame; runners that don't redraw continuously
+/// should schedule one.
+pub fn tasks_pending() -> bool {
+    READY.with(|r| !r.lock().is_empty())
+}
+
+/// Resolves once `d` has passed on the animation clock since the call, checking once per
+/// frame (so a `TestClock` drives it too).
+pub fn delay(d: Duration) -> impl Future<Output = ()> {
+    let until = crate::animation::now() + d;
+    async move {
+        while crate::animation::now() < until {
+            crate::next_frame().await;
+        }
+    }
+}
+
+struct TaskWaker {
+    id: u64,
+    ready: Arc<Mutex<Vec<u64>>>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.ready.lock().push(self.id);
+        crate::snapshot::wake_runner();
+    }
+}
+
+fn wake_task(id: u64) {
+    READY.with(|r| r.lock().push(id));
+    crate::snapshot::wake_runner();
+}
+
+/// Polls every task woken since the last call, once each. Tasks woken while this runs
+/// (including by themselves) wait for the next frame.
+pub(crate) fn run_ready_tasks() {
+    let (ready, queue) = READY.with(|r| (std::mem::take(&mut *r.lock()), r.clone()));
+    let mut seen = HashSet::new();
+    for id in ready {
+        if !seen.insert(id) {
+            continue;
+        }
+        let Some(scope) = OWNERS.with(|o| o.borrow().get(&id).and_then(Weak::upgrade)) else {
+            continue;
+        };
+        let Some(mut fut) = scope.tasks.borrow_mut().get_mut(&id).and_then(Option::take) else {
+            continue;
+        };
+        let waker = Waker::from(Arc::new(TaskWaker {
+            id,
+            ready: queue.clone(),
+        }));
+        let done = fut
+            .as_mut()
+            .poll(&mut Context::from_waker(&waker))
+            .is_ready();
+        let mut tasks = scope.tasks.borrow_mut();
+        // Gone if it was cancelled while running.
+        if let Some(slot) = tasks.get_mut(&id) {
+            if done {
+                tasks.remove(&id);
+                OWNERS.with(|o| o.borrow_mut().remove(&id));
+            } else {
+                *slot = Some(fut);
+            }
+        }
+    }
+}
+
+/// Cancels the scopes `task_scope` handed out last composition that this one didn't ask
+/// for again.
+pub(crate) fn end_composition() {
+    let gone: Vec<TaskScope> = DECLARED.with(|d| {
+        let (prev, cur) = &mut *d.borrow_mut();
+        let gone = prev
+            .drain(..)
+            .filter(|p| !cur.iter().any(|c| Rc::ptr_eq(&p.inner, &c.inner)))
+            .collect();
+        std::mem::swap(prev, cur);
+        gone
+    });
+    for scope in gone {
+        scope.cancel();
+    }
+}
+
+impl std::fmt::Debug for TaskScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TaskScope")
+            .field("active_tasks", &self.active_tasks())
+            .finish()
+    }
+}
@@ -5,9 +5,14 @@ mod tests {
     use crate::Rect;
     use crate::Vec2;
     use crate::animation::*;
+    use crate::i18n::*;
+    use crate::layers::*;
+    use crate::locals::with_locale;
+    use crate::modifier::*;
     use crate::remember_with_key;
     use crate::scope::*;
     use crate::signal::*;
+    use crate::stats::*;
     use web_time::{Duration, Instant};
 
     #[test]
@@ -107,7 +112,7 @@ mod tests {
     #[test]
     fn test_animation_deterministic() {
         let t0 = Instant::now();
-        set_clock(Box::new(TestClock { t: t0 }));
+        let _clock = swap_clock(Box::new(TestClock { t: t0 }));
 
         let mut a = AnimatedValue::new(
             0.0f32,
@@ -128,4 +133,974 @@ mod tests {
         assert!(!cont);
         assert!((*a.get() - 10.0).abs() < 0.001);
     }
+
+    #[test]
+    fn test_playback_clock_pauses_steps_and_seeks() {
+        let clock = PlaybackClock::new();
+        clock.pause();
+        let t0 = clock.now();
+        std::thread::sleep(Duration::from_millis(2));
+        assert_eq!(clock.now(), t0);
+
+        clock.step(Duration::from_millis(16));
+        assert_eq!(clock.now(), t0 + Duration::from_millis(16));
+        // Speed only applies while running.
+        clock.set_speed(0.1);
+        assert_eq!(clock.now(), t0 + Duration::from_millis(16));
+        clock.seek(t0);
+        assert_eq!(clock.now(), t0);
+
+        clock.resume();
+        std::thread::sleep(Duration::from_millis(20));
+        let ran = clock.now() - t0;
+        assert!(ran > Duration::ZERO && ran < Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_jank_tracker_window() {
+        let mut j = JankTracker::new(16.0, 3);
+        let frame = |ms: f32| FrameStats {
+            compose_ms: ms,
+            ..Default::default()
+        };
+        assert!(!j.record(frame(5.0)));
+        assert!(j.record(frame(20.0)));
+        assert!(!j.record(frame(8.0)));
+        assert_eq!(j.janky_frames(), 1);
+        assert_eq!(j.worst().map(|s| s.compose_ms), Some(20.0));
+
+        // Oldest frames fall out of the rolling window; lifetime totals keep them.
+        j.record(frame(1.0));
+        j.record(frame(1.0));
+        assert_eq!(j.janky_frames(), 0);
+        assert_eq!(j.totals(), (5, 1));
+        assert!((j.average().compose_ms - 10.0 / 3.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_graphics_layer_pivots_on_origin() {
+        let bounds = Rect {
+            x: 10.0,
+            y: 10.0,
+            w: 100.0,
+            h: 50.0,
+        };
+        let layer = GraphicsLayer {
+            scale_x: 2.0,
+            scale_y: 2.0,
+            ..Default::default()
+        };
+        // Scaling around the center keeps the center fixed.
+        let center = Vec2 { x: 60.0, y: 35.0 };
+        assert_eq!(layer.transform(bounds).apply_to_point(center), center);
+
+        let top_left = GraphicsLayer {
+            transform_origin: TransformOrigin::TOP_LEFT,
+            ..layer
+        };
+        let corner = Vec2 { x: 10.0, y: 10.0 };
+        assert_eq!(top_left.transform(bounds).apply_to_point(corner), corner);
+    }
+
+    #[test]
+    fn test_modal_layer_paints_last_and_traps_focus() {
+        use crate::{HitRegion, Scene, Scheduler, View, ViewKind};
+
+        // One focusable hit per laid-out root, tagged with the root's key.
+        let layout = |v: &View, _size: (u32, u32)| {
+            let hit = HitRegion {
+                id: v.modifier.key.unwrap_or(0),
+                rect: Rect::default(),
+                on_click: None,
+                on_scroll: None,
+                focusable: true,
+                on_pointer_down: None,
+                on_pointer_move: None,
+                on_pointer_up: None,
+                on_pointer_enter: None,
+                on_pointer_leave: None,
+                on_key_event: None,
+                z_index: 0.0,
+                on_text_change: None,
+                on_text_submit: None,
+                tf_state_key: None,
+            };
+            (Scene::default(), vec![hit], vec![])
+        };
+
+        let mut sched = Scheduler::new();
+        let frame = sched.repose(
+            |_| {
+                show_layer(
+                    "dialog",
+                    LayerOptions::default().modal(),
+                    View::new(0, ViewKind::Box),
+                );
+                View::new(0, ViewKind::Box)
+            },
+            layout,
+        );
+        let key = sched.layers.layers()[0].root_key();
+        assert_eq!(frame.hit_regions.last().map(|h| h.id), Some(key));
+        assert_eq!(frame.focus_chain, vec![key]);
+
+        // Not declared again, so it's gone.
+        let frame = sched.repose(|_| View::new(0, ViewKind::Box), layout);
+        assert!(sched.layers.is_empty());
+        assert_eq!(frame.focus_chain, vec![0]);
+    }
+
+    #[test]
+    fn test_layers_shown_while_painting_join_the_frame() {
+        use crate::{Scene, Scheduler, View, ViewKind};
+
+        // Each root paints one text node named after its key; the main tree's paint shows
+        // a "tip" layer, as a tooltip does once it knows where its anchor landed.
+        let layout = |v: &View, _size: (u32, u32)| {
+            let key = v.modifier.key.unwrap_or(0);
+            if key == 0 {
+                show_layer(
+                    "tip",
+                    LayerOptions::default().z(10),
+                    View::new(0, ViewKind::Box),
+                );
+            }
+            let mut scene = Scene::default();
+            scene.nodes.push(crate::SceneNode::Text {
+                rect: Rect::default(),
+                text: key.to_string().into(),
+                color: crate::Color::WHITE,
+                size: 1.0,
+                letter_spacing: 0.0,
+                weight: crate::FontWeight::NORMAL,
+            });
+            (scene, vec![], vec![])
+        };
+        let painted = |frame: &crate::Frame| -> Vec<String> {
+            frame
+                .scene
+                .nodes
+                .iter()
+                .filter_map(|n| match n {
+                    crate::SceneNode::Text { text, .. } => Some(text.to_string()),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        let mut sched = Scheduler::new();
+        let frame = sched.repose(
+            |_| {
+                show_layer(
+                    "popup",
+                    LayerOptions::default().z(5),
+                    View::new(0, ViewKind::Box),
+                );
+                View::new(0, ViewKind::Box)
+            },
+            layout,
+        );
+        let keys: Vec<String> = sched
+            .layers
+            .layers()
+            .iter()
+            .map(|l| l.root_key().to_string())
+            .collect();
+        assert_eq!(keys.len(), 2, "the tip joins the declared popup");
+        assert_eq!(painted(&frame), ["0", keys[0].as_str(), keys[1].as_str()]);
+        assert_eq!(sched.layers.layers()[1].key, "tip");
+
+        // A modal layer covers what's underneath, tip included.
+        sched.repose(
+            |_| {
+                show_layer(
+                    "dialog",
+                    LayerOptions::default().modal(),
+                    View::new(0, ViewKind::Box),
+                );
+                View::new(0, ViewKind::Box)
+            },
+            layout,
+        );
+        let keys: Vec<&str> = sched
+            .layers
+            .layers()
+            .iter()
+            .map(|l| l.key.as_str())
+            .collect();
+        assert_eq!(keys, ["dialog"]);
+    }
+
+    #[test]
+    fn test_bus_delivers_typed_events_between_frames() {
+        use crate::bus::*;
+        use crate::{Scene, Scheduler, View, ViewKind};
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        struct Saved(&'static str);
+        struct Closed;
+
+        let layout = |_: &View, _: (u32, u32)| (Scene::default(), vec![], vec![]);
+        let log: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+        let mut sched = Scheduler::new();
+        let mut frame = |subscribed: bool| {
+            let log = log.clone();
+            sched.repose(
+                move |_| {
+                    if subscribed {
+                        let l = log.clone();
+                        use_subscription(move |e: &Saved| {
+                            l.borrow_mut().push(e.0.to_string());
+                            // Emitted from a subscriber: delivered in the same frame.
+                            emit(Closed);
+                        });
+                        let l = log.clone();
+                        use_subscription(move |_: &Closed| l.borrow_mut().push("closed".into()));
+                    }
+                    View::new(0, ViewKind::Box)
+                },
+                layout,
+            );
+        };
+
+        frame(true);
+        emit(Saved("a.txt"));
+        assert!(events_pending());
+        assert!(log.borrow().is_empty());
+        frame(true);
+        assert_eq!(*log.borrow(), ["a.txt", "closed"]);
+        assert!(!events_pending());
+
+        // A subscription not declared again stops receiving.
+        frame(false);
+        emit(Saved("b.txt"));
+        frame(false);
+        assert_eq!(log.borrow().len(), 2);
+        assert!(!events_pending());
+    }
+
+    #[test]
+    fn test_power_mode_follows_battery_unless_hinted() {
+        use crate::power::*;
+
+        assert_eq!(power_mode(), PowerMode::Auto);
+        set_on_battery(None);
+        assert!(!power_saving());
+        set_on_battery(Some(true));
+        assert!(power_saving());
+        assert_eq!(frame_interval(), Some(POWER_SAVER_FRAME_INTERVAL));
+
+        set_power_mode(PowerMode::Performance);
+        assert!(!power_saving());
+        set_on_battery(Some(false));
+        set_power_mode(PowerMode::PowerSaver);
+        assert!(power_saving());
+
+        set_power_mode(PowerMode::Auto);
+        assert!(!power_saving());
+        assert_eq!(frame_interval(), None);
+        set_on_battery(None);
+    }
+
+    #[test]
+    fn test_frame_callbacks_run_once_per_frame() {
+        use crate::frame_clock::*;
+        use crate::{Scene, Scheduler, View, ViewKind};
+        use std::cell::RefCell;
+        use std::future::Future;
+        use std::rc::Rc;
+        use std::task::{Context, Poll, Waker};
+
+        let layout = |_: &View, _: (u32, u32)| (Scene::default(), vec![], vec![]);
+        let frame = |sched: &mut Scheduler| {
+            sched.repose(|_| View::new(0, ViewKind::Box), layout);
+        };
+        let mut sched = Scheduler::new();
+
+        // A callback that re-registers itself ticks exactly once per frame.
+        let ticks: Rc<RefCell<Vec<u64>>> = Rc::new(RefCell::new(Vec::new()));
+        fn tick(ticks: Rc<RefCell<Vec<u64>>>) {
+            with_frame_nanos(move |t| {
+                ticks.borrow_mut().push(t);
+                if ticks.borrow().len() < 3 {
+                    tick(ticks.clone());
+                }
+            });
+        }
+        tick(ticks.clone());
+        assert!(frame_requested());
+        for _ in 0..4 {
+            frame(&mut sched);
+        }
+        let ticks = ticks.borrow();
+        assert_eq!(ticks.len(), 3);
+        assert!(ticks.windows(2).all(|w| w[0] <= w[1]));
+        assert!(!frame_requested());
+
+        // The awaitable form resolves with the next frame's timestamp.
+        let mut next = std::pin::pin!(next_frame());
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(next.as_mut().poll(&mut cx).is_pending());
+        frame(&mut sched);
+        assert_eq!(next.as_mut().poll(&mut cx), Poll::Ready(frame_time_nanos()));
+    }
+
+    #[test]
+    fn test_advance_composes_on_a_stepped_clock() {
+        use crate::frame_clock::*;
+        use crate::{Scheduler, View, ViewKind};
+        use std::cell::{Cell, RefCell};
+        use std::rc::Rc;
+
+        let composed = Rc::new(Cell::new(0));
+        let ticks: Rc<RefCell<Vec<u64>>> = Rc::new(RefCell::new(Vec::new()));
+        let mut sched = Scheduler::new();
+        for _ in 0..3 {
+            let (composed, ticks) = (composed.clone(), ticks.clone());
+            let root = sched.advance(Duration::from_millis(16), move |_| {
+                composed.set(composed.get() + 1);
+                let ticks = ticks.clone();
+                with_frame_nanos(move |t| ticks.borrow_mut().push(t));
+                View::new(7, ViewKind::Box)
+            });
+            assert_eq!(root.id, 7);
+        }
+        assert_eq!(composed.get(), 3);
+        // Callbacks registered by one frame see the next one's time, 16 ms later.
+        let ticks = ticks.borrow();
+        assert_eq!(ticks.len(), 2);
+        assert_eq!(ticks[1] - ticks[0], 16_000_000);
+    }
+
+    #[test]
+    fn test_advance_gives_the_clock_back_when_the_scheduler_drops() {
+        use crate::{Scheduler, View, ViewKind};
+
+        let mut sched = Scheduler::new();
+        sched.advance(Duration::from_secs(3600), |_| View::new(0, ViewKind::Box));
+        assert!(now() >= Instant::now() + Duration::from_secs(3599));
+        drop(sched);
+        assert!(now() < Instant::now() + Duration::from_secs(1800));
+        // Nothing holds the clock any more.
+        drop(swap_clock(Box::new(SystemClock)));
+    }
+
+    #[test]
+    fn test_task_scope_runs_tasks_until_its_composable_leaves() {
+        use crate::frame_clock::next_frame;
+        use crate::{Scheduler, View, ViewKind, delay, remember, task_scope};
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        struct DropFlag(Rc<Cell<bool>>);
+        impl Drop for DropFlag {
+            fn drop(&mut self) {
+                self.0.set(true);
+            }
+        }
+
+        let shown = Rc::new(Cell::new(true));
+        let ticks = Rc::new(Cell::new(0));
+        let dropped = Rc::new(Cell::new(false));
+        let waited = Rc::new(Cell::new(false));
+        let mut sched = Scheduler::new();
+        let mut frame = || {
+            let (shown, ticks, dropped, waited) = (
+                shown.clone(),
+                ticks.clone(),
+                dropped.clone(),
+                waited.clone(),
+            );
+            sched.advance(Duration::from_millis(16), move |_| {
+                if shown.get() {
+                    let tasks = task_scope();
+                    if !remember(|| Cell::new(false)).replace(true) {
+                        let (flag, ticks, waited) =
+                            (DropFlag(dropped.clone()), ticks.clone(), waited.clone());
+                        tasks.spawn(async move {
+                            let _flag = flag;
+                            loop {
+                                next_frame().await;
+                                ticks.set(ticks.get() + 1);
+                            }
+                        });
+                        tasks.spawn(async move {
+                            delay(Duration::from_millis(40)).await;
+                            waited.set(true);
+                        });
+                    }
+                }
+                View::new(0, ViewKind::Box)
+            });
+        };
+
+        frame(); // spawned
+        frame(); // first poll
+        assert!(!waited.get());
+        for _ in 0..4 {
+            frame();
+        }
+        assert_eq!(ticks.get(), 4);
+        assert!(waited.get());
+        assert!(!dropped.get());
+
+        shown.set(false);
+        frame();
+        assert!(dropped.get());
+        let at_removal = ticks.get();
+        frame();
+        frame();
+        assert_eq!(ticks.get(), at_removal);
+    }
+
+    #[test]
+    fn test_debounced_waits_for_quiet_and_throttled_samples() {
+        use crate::{Scheduler, View, ViewKind, use_debounced, use_throttled};
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        // The input changes every 16ms frame for 6 frames, then holds at 6.
+        let out: Rc<RefCell<Vec<(u32, u32)>>> = Rc::new(RefCell::new(Vec::new()));
+        let mut sched = Scheduler::new();
+        for frame in 0..16u32 {
+            let out = out.clone();
+            sched.advance(Duration::from_millis(16), move |_| {
+                let v = frame.min(6);
+                let d = use_debounced(v, Duration::from_millis(50));
+                let t = use_throttled(v, Duration::from_millis(50));
+                out.borrow_mut().push((d, t));
+                View::new(0, ViewKind::Box)
+            });
+        }
+        let (deb, thr): (Vec<u32>, Vec<u32>) = out.borrow().iter().copied().unzip();
+
+        // Nothing gets through while typing, nor for 50ms after the last change.
+        assert!(deb[..=8].iter().all(|v| *v == 0));
+        assert_eq!(deb.last(), Some(&6));
+        assert!(deb.iter().all(|v| *v == 0 || *v == 6));
+
+        // The first change goes straight through; later ones are sampled.
+        assert_eq!(thr[1], 1);
+        assert!(thr.windows(2).all(|w| w[0] <= w[1]));
+        let changes = thr.windows(2).filter(|w| w[0] != w[1]).count();
+        assert!((2..6).contains(&changes), "{thr:?}");
+        assert_eq!(thr.last(), Some(&6));
+    }
+
+    #[test]
+    fn test_memo_reuses_the_subtree_until_its_key_changes() {
+        use crate::{Scene, Scheduler, View, ViewKind, memo, remember};
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let layout = |_: &View, _: (u32, u32)| (Scene::default(), vec![], vec![]);
+        let builds = Rc::new(Cell::new(0));
+        let after: Rc<Cell<Option<Rc<Cell<u32>>>>> = Rc::new(Cell::new(None));
+        let mut sched = Scheduler::new();
+        let mut frame = |key: u32| {
+            let (builds, after) = (builds.clone(), after.clone());
+            sched.repose(
+                move |_| {
+                    let sidebar = memo(key, || {
+                        builds.set(builds.get() + 1);
+                        let _ = remember(|| "inside");
+                        View::new(0, ViewKind::Column)
+                    });
+                    // A slot after the memo stays the same whether the memo rebuilt or not.
+                    after.set(Some(remember(|| Cell::new(0u32))));
+                    sidebar
+                },
+                layout,
+            );
+        };
+
+        frame(1);
+        let slot = after.take().unwrap();
+        slot.set(5);
+        frame(1);
+        assert_eq!(builds.get(), 1);
+        assert_eq!(after.take().unwrap().get(), 5);
+        frame(2);
+        assert_eq!(builds.get(), 2);
+        assert_eq!(after.take().unwrap().get(), 5);
+    }
+
+    #[test]
+    fn test_memo_declares_its_tasks_layers_and_subscriptions_again_when_reused() {
+        use crate::frame_clock::next_frame;
+        use crate::{
+            LayerOptions, Scheduler, View, ViewKind, emit, memo, show_layer, task_scope,
+            use_subscription,
+        };
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        struct Ping;
+        let ticks = Rc::new(Cell::new(0));
+        let pings = Rc::new(Cell::new(0));
+        let builds = Rc::new(Cell::new(0));
+        let mut sched = Scheduler::new();
+        let mut frame = || {
+            let (ticks, pings, builds) = (ticks.clone(), pings.clone(), builds.clone());
+            sched.advance(Duration::from_millis(16), move |_| {
+                // Nested, so the outer memo repeats what the inner one declared.
+                memo(1u32, || {
+                    memo(1u32, || {
+                        builds.set(builds.get() + 1);
+                        let ticks = ticks.clone();
+                        task_scope().spawn(async move {
+                            loop {
+                                next_frame().await;
+                                ticks.set(ticks.get() + 1);
+                            }
+                        });
+                        let pings = pings.clone();
+                        use_subscription(move |_: &Ping| pings.set(pings.get() + 1));
+                        show_layer("tip", LayerOptions::default(), View::new(0, ViewKind::Box));
+                        View::new(0, ViewKind::Box)
+                    })
+                })
+            });
+            emit(Ping);
+            sched.layers.layers().len()
+        };
+
+        for _ in 0..6 {
+            assert_eq!(frame(), 1);
+        }
+        assert_eq!(builds.get(), 1);
+        assert_eq!(ticks.get(), 4);
+        assert_eq!(pings.get(), 5);
+    }
+
+    #[test]
+    fn test_composition_locals_nest_and_stay_distinct() {
+        use crate::locals::{CompositionLocal, composition_local_of, provide};
+
+        static A: CompositionLocal<u32> = composition_local_of(|| 1);
+        static B: CompositionLocal<u32> = composition_local_of(|| 2);
+
+        assert_eq!((A.current(), B.current()), (1, 2));
+        provide(&A, 10, || {
+            // Same value type, different local.
+            assert_eq!((A.current(), B.current()), (10, 2));
+            A.provide(20, || assert_eq!(A.current(), 20));
+            assert_eq!(A.current(), 10);
+        });
+        assert_eq!(A.current(), 1);
+    }
+
+    #[test]
+    fn test_platform_settings_override_defaults() {
+        use crate::locals::{PlatformSettings, platform_settings, with_platform_settings};
+        use std::time::Duration;
+
+        let slow = PlatformSettings {
+            double_click_interval: Duration::from_millis(900),
+            scroll_line_dp: 120.0,
+            ..Default::default()
+        };
+        with_platform_settings(slow, || {
+            assert_eq!(platform_settings(), slow);
+        });
+        assert_eq!(platform_settings(), PlatformSettings::default());
+    }
+
+    #[test]
+    fn test_batch_notifies_once_with_final_value() {
+        use crate::{batch, signal};
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let s = signal(0);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen2 = seen.clone();
+        let _sub = s.subscribe_guard(move |v| seen2.borrow_mut().push(*v));
+
+        batch(|| {
+            s.set(1);
+            s.update(|v| *v += 1);
+            // Writes are visible inside the batch; only notifications wait.
+            assert_eq!(s.get(), 2);
+            assert!(seen.borrow().is_empty());
+        });
+        assert_eq!(*seen.borrow(), vec![2]);
+    }
+
+    #[test]
+    fn test_snapshot_applies_atomically_on_ui_thread() {
+        use crate::signal;
+        use crate::snapshot::*;
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let count = signal(0);
+        let name = signal(String::from("a"));
+        let notified = Rc::new(Cell::new(0));
+        let n2 = notified.clone();
+        let _sub = count.subscribe_guard(move |_| n2.set(n2.get() + 1));
+        let (c, n) = (count.writer(), name.writer());
+
+        std::thread::spawn(move || {
+            let mut snap = Snapshot::new();
+            snap.update(&c, |v| *v += 5);
+            snap.update(&c, |v| *v *= 2);
+            snap.set(&n, "b".to_string());
+            snap.update(&n, |s| s.push('c'));
+            assert_eq!(snap.get(&n).map(String::as_str), Some("bc"));
+            snap.apply();
+        })
+        .join()
+        .unwrap();
+
+        // Nothing changes until the UI thread applies it.
+        assert!(snapshots_pending());
+        assert_eq!((count.get(), name.get()), (0, "a".to_string()));
+        assert_eq!(apply_snapshots(), 1);
+        assert_eq!((count.get(), name.get()), (10, "bc".to_string()));
+        assert_eq!(notified.get(), 1);
+        assert!(!snapshots_pending());
+    }
+
+    #[test]
+    fn test_snapshots_wait_for_the_thread_that_owns_their_signals() {
+        use crate::signal;
+        use crate::snapshot::*;
+
+        let count = signal(0);
+        let c = count.writer();
+        let mut snap = Snapshot::new();
+        snap.set(&c, 3);
+        snap.apply();
+
+        // Another UI thread's frame leaves it alone.
+        let other = std::thread::spawn(|| (snapshots_pending(), apply_snapshots()));
+        assert_eq!(other.join().unwrap(), (false, 0));
+        assert!(snapshots_pending());
+        assert_eq!(apply_snapshots(), 1);
+        assert_eq!(count.get(), 3);
+    }
+
+    #[test]
+    fn test_batch_ends_when_its_closure_panics() {
+        use crate::{batch, signal};
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let s = signal(0);
+        let seen = Rc::new(Cell::new(0));
+        let seen2 = seen.clone();
+        let _sub = s.subscribe_guard(move |v| seen2.set(*v));
+
+        let caught = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            batch(|| {
+                s.set(1);
+                panic!("boom");
+            })
+        }));
+        assert!(caught.is_err());
+        // Writes after the failed batch notify right away again.
+        s.set(2);
+        assert_eq!(seen.get(), 2);
+    }
+
+    #[test]
+    fn test_plural_message_formatting() {
+        let pattern =
+            "{n, plural, =0 {none} one {# file} few {# pliki} many {# plików} other {# files}}";
+        let en = Locale::parse("en_US.UTF-8").unwrap();
+        let pl = Locale::parse("pl-PL").unwrap();
+        let fmt = |l: &Locale, n: i32| format_message(l, pattern, &[("n", n.into())]);
+
+        assert_eq!(en.tag(), "en-US");
+        assert_eq!(fmt(&en, 0), "none");
+        assert_eq!(fmt(&en, 1), "1 file");
+        assert_eq!(fmt(&en, 1200), "1,200 files");
+        assert_eq!(fmt(&pl, 3), "3 pliki");
+        assert_eq!(fmt(&pl, 12), "12 plików");
+        assert_eq!(fmt(&pl, 22), "22 pliki");
+
+        let select = "{who, select, me {You} other {{who}}} left";
+        assert_eq!(
+            format_message(&en, select, &[("who", "Ada".into())]),
+            "Ada left"
+        );
+
+        register_strings("de", [("greet", "Hallo {name}")]);
+        with_locale(Locale::parse("de-AT").unwrap(), || {
+            assert_eq!(crate::tr!("greet", name = "Welt"), "Hallo Welt");
+            assert_eq!(crate::tr!("missing.key"), "missing.key");
+            assert_eq!(format_number(1234.5, 2), "1.234,50");
+            assert_eq!(format_date(2025, 3, 7), "07.03.2025");
+        });
+    }
+
+    #[test]
+    fn test_semantics_json_and_diff() {
+        use crate::frame::*;
+
+        let r = Rect {
+            x: 0.0,
+            y: 0.0,
+            w: 10.0,
+            h: 5.0,
+        };
+        let before = Frame::new(Scene::new(Color::BLACK)).with_semantics(vec![
+            SemNode::new(1, Role::Button, r).label("Say \"hi\""),
+            SemNode::new(2, Role::Text, r).label("gone"),
+        ]);
+        assert_eq!(
+            before.semantics_to_json(),
+            r#"[{"id":1,"role":"Button","label":"Say \"hi\"","rect":[0,0,10,5],"focused":false,"enabled":true,"truncated":false},{"id":2,"role":"Text","label":"gone","rect":[0,0,10,5],"focused":false,"enabled":true,"truncated":false}]"#
+        );
+
+        let after = Frame::new(Scene::new(Color::BLACK)).with_semantics(vec![
+            SemNode::new(1, Role::Button, r)
+                .label("Say \"hi\"")
+                .focused(true),
+            SemNode::new(3, Role::Checkbox, r),
+        ]);
+        let changes: Vec<String> = after
+            .semantics_diff(&before)
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                "- Text #2 \"gone\"",
+                "~ Button #1 \"Say \\\"hi\\\"\" focused false -> true",
+                "+ Checkbox #3 \"\"",
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_frame_round_trips_through_serde() {
+        use crate::frame::*;
+
+        let r = Rect {
+            x: 1.0,
+            y: 2.0,
+            w: 30.0,
+            h: 40.0,
+        };
+        let scene = Scene::new(Color::BLACK).with_nodes([
+            SceneNode::PushClip {
+                rect: r,
+                radius: 2.0,
+            },
+            SceneNode::Text {
+                rect: r,
+                text: "hello".into(),
+                color: Color::WHITE,
+                size: 14.0,
+                letter_spacing: 0.0,
+                weight: Default::default(),
+            },
+            SceneNode::PopClip,
+        ]);
+        let mut frame = Frame::new(scene)
+            .with_hit_regions(vec![HitRegion::new(1, r).on_click(|| {}).focusable(true)])
+            .with_semantics(vec![
+                SemNode::new(1, Role::Checkbox, r)
+                    .label("Agree")
+                    .toggle(ToggleableState::On),
+            ])
+            .with_focus_chain(vec![1]);
+        frame.stats.paint_ms = 1.5;
+
+        let json = serde_json::to_string(&frame).unwrap();
+        let back: Frame = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&back).unwrap(), json);
+        assert_eq!(format!("{:?}", back.scene), format!("{:?}", frame.scene));
+        assert_eq!(back.semantics_nodes, frame.semantics_nodes);
+        assert_eq!(back.focus_chain, [1]);
+        assert_eq!(back.stats.paint_ms, 1.5);
+        // Callbacks don't serialize; the rest of the region does.
+        let hit = &back.hit_regions[0];
+        assert!(hit.on_click.is_none() && hit.focusable && hit.rect == r);
+        assert!(back.scene.check_stacks().is_ok());
+    }
+
+    #[test]
+    fn test_check_stacks_finds_stray_pops_and_open_pushes() {
+        use crate::frame::*;
+
+        let clip = || SceneNode::PushClip {
+            rect: Rect::default(),
+            radius: 0.0,
+        };
+        let push_t = || SceneNode::PushTransform {
+            transform: Transform::identity(),
+        };
+        let scene = Scene::new(Color::BLACK).with_nodes([
+            clip(),
+            push_t(),
+            SceneNode::PopClip,
+            SceneNode::PopTransform,
+        ]);
+        assert_eq!(scene.check_stacks(), Ok(()));
+        assert_eq!(
+            check_stacks(&[clip(), SceneNode::PopClip, SceneNode::PopTransform]),
+            Err(StackError::UnmatchedPop {
+                index: 2,
+                clip: false
+            })
+        );
+        assert_eq!(
+            check_stacks(&[clip(), push_t(), clip(), SceneNode::PopClip]),
+            Err(StackError::Unclosed {
+                clips: 1,
+                transforms: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_cull_drops_nodes_outside_the_viewport_and_clips() {
+        use crate::frame::*;
+
+        let rect = |x: f32, y: f32| SceneNode::Rect {
+            rect: Rect {
+                x,
+                y,
+                w: 10.0,
+                h: 10.0,
+            },
+            brush: Brush::Solid(Color::WHITE),
+            radius: 0.0,
+        };
+        let mut scene = Scene::new(Color::BLACK).with_nodes([
+            rect(5.0, 5.0),
+            rect(5.0, 150.0),
+            SceneNode::PushClip {
+                rect: Rect {
+                    x: 0.0,
+                    y: 0.0,
+                    w: 100.0,
+                    h: 20.0,
+                },
+                radius: 0.0,
+            },
+            rect(5.0, 15.0),
+            rect(5.0, 40.0),
+            // Text runs on past its rect, so it's kept when starting left of the clip.
+            SceneNode::Text {
+                rect: Rect {
+                    x: -50.0,
+                    y: 0.0,
+                    w: 10.0,
+                    h: 12.0,
+                },
+                text: "long".into(),
+                color: Color::WHITE,
+                size: 10.0,
+                letter_spacing: 0.0,
+                weight: crate::FontWeight::NORMAL,
+            },
+            SceneNode::PushTransform {
+                transform: Transform::translate(0.0, 50.0),
+            },
+            rect(5.0, 0.0),
+            SceneNode::PopTransform,
+            SceneNode::PopClip,
+        ]);
+        assert_eq!(
+            scene.cull(Rect {
+                x: 0.0,
+                y: 0.0,
+                w: 100.0,
+                h: 100.0
+            }),
+            3
+        );
+        assert_eq!(scene.check_stacks(), Ok(()));
+        let kept: Vec<_> = scene
+            .nodes
+            .iter()
+            .filter_map(|n| match n {
+                SceneNode::Rect { rect, .. } => Some(rect.y),
+                SceneNode::Text { .. } => Some(-1.0),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(kept, vec![5.0, 15.0, -1.0]);
+    }
+
+    #[test]
+    fn test_interned_text_keeps_its_id_while_painted() {
+        use crate::intern::*;
+
+        let a = intern("Save");
+        let b: InternedStr = String::from("Save").into();
+        assert_eq!(a.id(), b.id());
+        assert_eq!(a, "Save");
+        assert_ne!(a.id(), intern("Cancel").id());
+
+        // Dropped by every scene, but painted since the last sweep: kept.
+        let id = a.id();
+        drop((a, b));
+        sweep_interned();
+        assert_eq!(intern("Save").id(), id);
+
+        // Not painted for a whole frame: dropped, and a later copy gets a new id.
+        sweep_interned();
+        sweep_interned();
+        assert_ne!(intern("Save").id(), id);
+
+        // The sweep that drops a string reports it, once.
+        sweep_interned();
+        sweep_interned();
+        let swept = take_swept_text();
+        assert_eq!(swept.iter().map(|s| &**s).collect::<Vec<_>>(), ["Save"]);
+        assert!(take_swept_text().is_empty());
+    }
+
+    #[test]
+    fn test_interned_ids_differ_across_threads() {
+        use crate::intern::*;
+
+        let here = intern("Open");
+        let there = std::thread::spawn(|| intern("Close").id()).join().unwrap();
+        assert_ne!(here.id(), there);
+    }
+
+    #[test]
+    fn test_shortcut_modifiers_follow_the_platform() {
+        use crate::input::Modifiers;
+
+        let ctrl = Modifiers {
+            ctrl: true,
+            ..Default::default()
+        };
+        let cmd = Modifiers {
+            meta: true,
+            ..Default::default()
+        };
+        let option = Modifiers {
+            alt: true,
+            ..Default::default()
+        };
+        let mac = cfg!(target_os = "macos");
+        assert_eq!(ctrl.command(), !mac);
+        assert_eq!(cmd.command(), mac);
+        assert_eq!(ctrl.word(), !mac);
+        assert_eq!(option.word(), mac);
+    }
+
+    #[test]
+    fn test_diagnostics_published_from_another_thread_ask_for_a_frame() {
+        use crate::text_diagnostics::*;
+
+        let slot = DiagnosticsSlot::default();
+        let sink = slot.sink(1);
+        std::thread::spawn(move || sink.publish(Vec::new()))
+            .join()
+            .unwrap();
+        assert!(diagnostics_pending());
+        assert_eq!(slot.take(), Some((1, Vec::new())));
+    }
 }
@@ -0,0 +1,130 @@
+//! # Text diagnostics
+//!
+//! A [`TextDiagnostics`] provider (a spellchecker, grammar checker or LSP-style linter)
+//! attached to a `TextField` is handed the range of text that changed; it reports
+//! [`TextIssue`]s through a [`DiagnosticsSink`], immediately or later from any thread. The
+//! field paints a squiggly underline under each issue and offers its suggestions in a
+//! right-click menu.
+//!
+//! ```rust
+//! use repose_core::*;
+//!
+//! struct NoTeh;
+//! impl TextDiagnostics for NoTeh {
+//!     fn check(&self, text: &str, range: std::ops::Range<usize>, sink: DiagnosticsSink) {
+//!         let issues = text[range.clone()]
+//!             .match_indices("teh")
+//!             .map(|(i, w)| TextIssue {
+//!                 range: range.start + i..range.start + i + w.len(),
+//!                 severity: Severity::Error,
+//!                 message: "Unknown word".into(),
+//!                 suggestions: vec!["the".into()],
+//!             })
+//!             .collect();
+//!         sink.publish(issues);
+//!     }
+//! }
+//! ```
+
+use std::ops::Range;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use parking_lot::Mutex;
+
+use crate::Color;
+
+/// How an issue is underlined.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Severity {
+    /// Misspellings and hard errors (red).
+    #[default]
+    Error,
+    /// Grammar and style (amber).
+    Warning,
+    /// Suggestions (blue).
+    Hint,
+}
+
+impl Severity {
+    pub fn color(self) -> Color {
+        match self {
+            Severity::Error => Color::from_hex("#E5484D"),
+            Severity::Warning => Color::from_hex("#F5A524"),
+            Severity::Hint => Color::from_hex("#3B7BFF"),
+        }
+    }
+}
+
+/// One problem in a field's text. `range` is in bytes of the text that was checked.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextIssue {
+    pub range: Range<usize>,
+    pub severity: Severity,
+    pub message: String,
+    /// Replacements offered in the right-click menu, best first.
+    pub suggestions: Vec<String>,
+}
+
+/// Checks text for a `TextField`.
+pub trait TextDiagnostics {
+    /// Checks `range` (bytes, widened to whole words) of `text` and reports the issues found
+    /// inside it through `sink`. Issues outside `range` are kept from earlier checks.
+    ///
+    /// `sink` is `Send`; slow checkers can move it to a worker thread and publish later. If
+    /// the text changes before then, the stale result is dropped and a new check covers it.
+    fn check(&self, text: &str, range: Range<usize>, sink: DiagnosticsSink);
+}
+
+type Pending = Option<(u64, Vec<TextIssue>)>;
+
+// Set when a result is published, until a runner asks for the frame that picks it up.
+static PUBLISHED: AtomicBool = AtomicBool::new(false);
+
+/// Whether results were published since the last call; runners that don't redraw
+/// continuously should schedule a frame so the fields pick them up.
+pub fn diagnostics_pending() -> bool {
+    PUBLISHED.swap(false, Ordering::AcqRel)
+}
+
+/// Where published results wait for the field to pick them up on its next frame.
+#[derive(Clone, Debug, Default)]
+pub struct DiagnosticsSlot(Arc<Mutex<Pending>>);
+
+impl DiagnosticsSlot {
+    /// A sink answering check number `revision`.
+    pub fn sink(&self, revision: u64) -> DiagnosticsSink {
+        DiagnosticsSink {
+            revision,
+            slot: self.0.clone(),
+        }
+    }
+
+    /// Takes the most recently published `(revision, issues)`, if any.
+    pub fn take(&self) -> Pending {
+        self.0.lock().take()
+    }
+}
+
+/// Delivers the result of one [`TextDiagnostics::check`] call.
+pub struct DiagnosticsSink {
+    revision: u64,
+    slot: Arc<Mutex<Pending>>,
+}
+
+impl DiagnosticsSink {
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Reports the issues for the checked range (empty if it's clean).
+    pub fn publish(self, issues: Vec<TextIssue>) {
+        let mut slot = self.slot.lock();
+        if slot.as_ref().is_none_or(|(rev, _)| *rev <= self.revision) {
+            *slot = Some((self.revision, issues));
+            drop(slot);
+            PUBLISHED.store(true, Ordering::Release);
+            crate::snapshot::wake_runner();
+        }
+    }
+}
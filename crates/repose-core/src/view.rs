@@ -1,10 +1,11 @@
-use crate::{Brush, Color, Modifier, Rect, Transform};
+use crate::{Brush, Color, InternedStr, Modifier, Rect, Transform, Vec2};
 use std::rc::Rc;
 
 pub type ViewId = u64;
 
 pub type ImageHandle = u64;
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ImageFit {
     Contain,
     Cover,
@@ -14,6 +15,8 @@ pub enum ImageFit {
 
 pub type Callback = Rc<dyn Fn()>;
 pub type ScrollCallback = Rc<dyn Fn(crate::Vec2) -> crate::Vec2>;
+/// Accepts or rejects the text an input would leave in a field.
+pub type TextFilter = Rc<dyn Fn(&str) -> bool>;
 
 #[derive(Clone)]
 pub enum ViewKind {
@@ -22,6 +25,9 @@ pub enum ViewKind {
     Row,
     Column,
     Stack,
+    /// Low-level form of a vertical scroll container; build it with
+    /// `repose_ui::scroll::ScrollArea` rather than wiring these callbacks by hand.
+    #[deprecated = "build scroll containers with repose_ui::scroll::ScrollArea"]
     ScrollV {
         on_scroll: Option<ScrollCallback>,
         set_viewport_height: Option<Rc<dyn Fn(f32)>>,
@@ -29,6 +35,8 @@ pub enum ViewKind {
         get_scroll_offset: Option<Rc<dyn Fn() -> f32>>,
         set_scroll_offset: Option<Rc<dyn Fn(f32)>>,
     },
+    /// Low-level form of a horizontal or 2D scroll container; see `ScrollV`.
+    #[deprecated = "build scroll containers with repose_ui::scroll::ScrollArea"]
     ScrollXY {
         on_scroll: Option<ScrollCallback>,
         set_viewport_width: Option<Rc<dyn Fn(f32)>>,
@@ -42,9 +50,18 @@ pub enum ViewKind {
         text: String,
         color: Color,
         font_size: f32,
+        /// Baseline-to-baseline distance in dp; `None` is `1.3 * font_size`.
+        line_height: Option<f32>,
+        /// Extra dp after every grapheme.
+        letter_spacing: f32,
+        /// Extra dp between paragraphs (`\n`-separated runs).
+        paragraph_spacing: f32,
         soft_wrap: bool,
         max_lines: Option<usize>,
         overflow: TextOverflow,
+        align: TextAlign,
+        /// Styled runs and links within `text`, from an `AnnotatedText`.
+        spans: Vec<crate::TextSpan>,
     },
     Button {
         on_click: Option<Callback>,
@@ -54,6 +71,24 @@ pub enum ViewKind {
         hint: String,
         on_change: Option<Rc<dyn Fn(String)>>,
         on_submit: Option<Rc<dyn Fn(String)>>,
+        /// Spellchecker/linter whose issues are underlined.
+        diagnostics: Option<Rc<dyn crate::TextDiagnostics>>,
+        /// Changes what's painted in place of the text, e.g. a password mask.
+        visual_transformation: Option<Rc<dyn crate::VisualTransformation>>,
+        /// Accepts or rejects the text typing, pasting or an IME commit would produce.
+        input_filter: Option<TextFilter>,
+        /// Most characters input may bring the text to.
+        max_length: Option<usize>,
+    },
+    /// Multi-line form of `TextField`: wraps, scrolls vertically, and Enter inserts a line
+    /// break (there is no submit).
+    TextArea {
+        state_key: ViewId,
+        hint: String,
+        on_change: Option<Rc<dyn Fn(String)>>,
+        diagnostics: Option<Rc<dyn crate::TextDiagnostics>>,
+        input_filter: Option<TextFilter>,
+        max_length: Option<usize>,
     },
     Checkbox {
         checked: bool,
@@ -102,9 +137,12 @@ pub enum ViewKind {
         color: Color,
         width: f32, // screen-space width (px)
     },
+    /// A third-party primitive; see [`crate::widget`].
+    Custom(Rc<dyn crate::Widget>),
 }
 
 impl std::fmt::Debug for ViewKind {
+    #[allow(deprecated)]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ViewKind::Checkbox { checked, .. } => f
@@ -129,17 +167,27 @@ impl std::fmt::Debug for ViewKind {
                 text,
                 color,
                 font_size,
+                line_height,
+                letter_spacing,
+                paragraph_spacing,
                 soft_wrap,
                 max_lines,
                 overflow,
+                align,
+                spans,
             } => f
                 .debug_struct("Text")
                 .field("text", text)
                 .field("color", color)
                 .field("font_size", font_size)
+                .field("line_height", line_height)
+                .field("letter_spacing", letter_spacing)
+                .field("paragraph_spacing", paragraph_spacing)
                 .field("soft_wrap", soft_wrap)
                 .field("max_lines", max_lines)
                 .field("overflow", overflow)
+                .field("align", align)
+                .field("spans", spans)
                 .finish(),
             ViewKind::Image { handle, tint, fit } => f
                 .debug_struct("Image")
@@ -156,10 +204,33 @@ impl std::fmt::Debug for ViewKind {
                 hint,
                 on_change,
                 on_submit,
+                diagnostics,
+                visual_transformation,
+                input_filter,
+                max_length,
             } => f
                 .debug_struct("TextField")
                 .field("state_key", state_key)
                 .field("hint", hint)
+                .field("diagnostics", &diagnostics.as_ref().map(|_| "<provider>"))
+                .field("visual_transformation", visual_transformation)
+                .field("input_filter", &input_filter.as_ref().map(|_| "<filter>"))
+                .field("max_length", max_length)
+                .finish(),
+            ViewKind::TextArea {
+                state_key,
+                hint,
+                diagnostics,
+                input_filter,
+                max_length,
+                ..
+            } => f
+                .debug_struct("TextArea")
+                .field("state_key", state_key)
+                .field("hint", hint)
+                .field("diagnostics", &diagnostics.as_ref().map(|_| "<provider>"))
+                .field("input_filter", &input_filter.as_ref().map(|_| "<filter>"))
+                .field("max_length", max_length)
                 .finish(),
             ViewKind::Slider {
                 value,
@@ -212,6 +283,7 @@ impl std::fmt::Debug for ViewKind {
                 .field("color", color)
                 .field("width", width)
                 .finish(),
+            ViewKind::Custom(w) => f.debug_tuple("Custom").field(&w.name()).finish(),
         }
     }
 }
@@ -251,12 +323,14 @@ impl View {
 
 /// Renderable scene
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Scene {
     pub clear_color: Color,
     pub nodes: Vec<SceneNode>,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SceneNode {
     Rect {
         rect: Rect,
@@ -272,9 +346,14 @@ pub enum SceneNode {
 
     Text {
         rect: Rect,
-        text: String,
+        /// Interned, so renderers can key shaping caches on `text.id()`.
+        text: InternedStr,
         color: Color,
         size: f32,
+        /// Extra px after every grapheme.
+        letter_spacing: f32,
+        #[cfg_attr(feature = "serde", serde(default))]
+        weight: crate::FontWeight,
     },
     Ellipse {
         rect: Rect,
@@ -285,6 +364,23 @@ pub enum SceneNode {
         color: Color,
         width: f32, // screen-space width (px)
     },
+    /// A straight stroke with round caps.
+    Line {
+        from: Vec2,
+        to: Vec2,
+        width: f32,
+        color: Color,
+    },
+    /// A filled circular sector, or a ring segment when `inner_radius > 0`. Angles are in
+    /// radians clockwise from the positive x axis (y points down).
+    Arc {
+        center: Vec2,
+        radius: f32,
+        inner_radius: f32,
+        start: f32,
+        sweep: f32,
+        color: Color,
+    },
     PushClip {
         rect: Rect,
         radius: f32,
@@ -302,9 +398,98 @@ pub enum SceneNode {
     },
 }
 
+impl SceneNode {
+    /// Approximate pixels this node writes: its rect's area for fills, text and images, the
+    /// stroke for borders, nothing for clip and transform markers.
+    pub fn coverage_px(&self) -> f32 {
+        match self {
+            SceneNode::Rect { rect, .. }
+            | SceneNode::Text { rect, .. }
+            | SceneNode::Image { rect, .. } => rect.w * rect.h,
+            SceneNode::Ellipse { rect, .. } => std::f32::consts::FRAC_PI_4 * rect.w * rect.h,
+            SceneNode::Border { rect, width, .. }
+            | SceneNode::EllipseBorder { rect, width, .. } => {
+                ((rect.w + rect.h) * 2.0 * width).min(rect.w * rect.h)
+            }
+            SceneNode::Line {
+                from, to, width, ..
+            } => ((to.x - from.x).hypot(to.y - from.y) + width) * width,
+            SceneNode::Arc {
+                radius,
+                inner_radius,
+                sweep,
+                ..
+            } => {
+                0.5 * sweep.abs().min(std::f32::consts::TAU)
+                    * (radius * radius - inner_radius * inner_radius).max(0.0)
+            }
+            SceneNode::PushClip { .. }
+            | SceneNode::PopClip
+            | SceneNode::PushTransform { .. }
+            | SceneNode::PopTransform => 0.0,
+        }
+    }
+
+    /// Area this node can draw into, before clips and transforms; `None` for clip and
+    /// transform markers. Text may run past its rect, so it's taken to reach a million px to
+    /// the right, with half a line of slack above and below.
+    pub fn bounds(&self) -> Option<Rect> {
+        let outset = |r: &Rect, dx: f32, dy: f32| Rect {
+            x: r.x - dx,
+            y: r.y - dy,
+            w: r.w + 2.0 * dx,
+            h: r.h + 2.0 * dy,
+        };
+        Some(match self {
+            SceneNode::Rect { rect, .. }
+            | SceneNode::Ellipse { rect, .. }
+            | SceneNode::Image { rect, .. } => *rect,
+            SceneNode::Border { rect, width, .. }
+            | SceneNode::EllipseBorder { rect, width, .. } => outset(rect, *width, *width),
+            SceneNode::Text { rect, size, .. } => Rect {
+                w: rect.w.max(1.0e6),
+                ..outset(rect, 0.0, size * 0.5)
+            },
+            SceneNode::Line {
+                from, to, width, ..
+            } => {
+                let r = Rect {
+                    x: from.x.min(to.x),
+                    y: from.y.min(to.y),
+                    w: (to.x - from.x).abs(),
+                    h: (to.y - from.y).abs(),
+                };
+                outset(&r, *width, *width)
+            }
+            SceneNode::Arc { center, radius, .. } => Rect {
+                x: center.x - radius,
+                y: center.y - radius,
+                w: 2.0 * radius,
+                h: 2.0 * radius,
+            },
+            SceneNode::PushClip { .. }
+            | SceneNode::PopClip
+            | SceneNode::PushTransform { .. }
+            | SceneNode::PopTransform => return None,
+        })
+    }
+}
+
 pub type CallbackF32 = Rc<dyn Fn(f32)>;
 pub type CallbackRange = Rc<dyn Fn(f32, f32)>;
 
+/// Where each line of a `Text` sits across its box. `Start` and `End` follow the
+/// `TextDirection`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextAlign {
+    #[default]
+    Start,
+    Center,
+    End,
+    /// Widens the spaces of each line but a paragraph's last so it fills the box.
+    Justify,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TextOverflow {
     Visible,
@@ -0,0 +1,136 @@
+//! # Visual transformations
+//!
+//! A [`VisualTransformation`] attached to a `TextField` changes what the field paints
+//! without changing its text: a password field shows a mask, a card number field groups
+//! digits. It returns the painted text with an [`OffsetMapping`] between the two, which
+//! the field uses to place the caret, selection and pointer hits.
+//!
+//! ```rust
+//! use repose_core::*;
+//!
+//! /// Groups digits in fours: "12345678" paints as "1234 5678".
+//! struct CardNumber;
+//! impl VisualTransformation for CardNumber {
+//!     fn filter(&self, text: &str) -> TransformedText {
+//!         let mut out = String::new();
+//!         let mut anchors = Vec::new();
+//!         for (i, c) in text.char_indices() {
+//!             if i > 0 && i % 4 == 0 {
+//!                 // Both sides of the space are the same place in the text.
+//!                 anchors.push((i, out.len()));
+//!                 out.push(' ');
+//!             }
+//!             anchors.push((i, out.len()));
+//!             out.push(c);
+//!         }
+//!         anchors.push((text.len(), out.len()));
+//!         TransformedText {
+//!             text: out,
+//!             mapping: OffsetMapping::from_anchors(anchors),
+//!         }
+//!     }
+//! }
+//!
+//! let t = CardNumber.filter("12345678");
+//! assert_eq!(t.text, "1234 5678");
+//! assert_eq!(t.mapping.to_transformed(6), 7);
+//! assert_eq!(t.mapping.to_original(4), 4);
+//! assert_eq!(t.mapping.to_original(5), 4);
+//! ```
+
+/// Maps byte offsets between a field's text and the text it paints.
+///
+/// It's a list of anchors, pairs of matching `(original, transformed)` offsets, usually
+/// one per character boundary. An offset between two anchors maps as the earlier one does,
+/// and of anchors at the same offset the last wins. No anchors means both texts are the same.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OffsetMapping {
+    anchors: Vec<(usize, usize)>,
+}
+
+impl OffsetMapping {
+    pub fn identity() -> Self {
+        Self::default()
+    }
+
+    /// `anchors` must not decrease on either side.
+    pub fn from_anchors(anchors: Vec<(usize, usize)>) -> Self {
+        Self { anchors }
+    }
+
+    pub fn to_transformed(&self, original: usize) -> usize {
+        through(self.anchors.iter().copied(), original)
+    }
+
+    pub fn to_original(&self, transformed: usize) -> usize {
+        through(self.anchors.iter().map(|&(o, t)| (t, o)), transformed)
+    }
+}
+
+/// Maps `at` through `(from, to)` anchors.
+fn through(mut anchors: impl Iterator<Item = (usize, usize)>, at: usize) -> usize {
+    let Some(first) = anchors.next() else {
+        return at;
+    };
+    anchors
+        .take_while(|&(from, _)| from <= at)
+        .last()
+        .unwrap_or(first)
+        .1
+}
+
+/// What a [`VisualTransformation`] paints in place of a field's text.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransformedText {
+    pub text: String,
+    pub mapping: OffsetMapping,
+}
+
+impl TransformedText {
+    /// `text` painted as it is.
+    pub fn identity(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            mapping: OffsetMapping::identity(),
+        }
+    }
+}
+
+/// Changes how a `TextField`'s text is painted.
+pub trait VisualTransformation {
+    fn filter(&self, text: &str) -> TransformedText;
+}
+
+impl std::fmt::Debug for dyn VisualTransformation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("<visual transformation>")
+    }
+}
+
+/// Paints every character as `mask`, for passwords and PINs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PasswordVisualTransformation {
+    pub mask: char,
+}
+
+impl Default for PasswordVisualTransformation {
+    fn default() -> Self {
+        Self { mask: '•' }
+    }
+}
+
+impl VisualTransformation for PasswordVisualTransformation {
+    fn filter(&self, text: &str) -> TransformedText {
+        let step = self.mask.len_utf8();
+        let anchors = text
+            .char_indices()
+            .enumerate()
+            .map(|(n, (i, _))| (i, n * step))
+            .chain(std::iter::once((text.len(), text.chars().count() * step)))
+            .collect();
+        TransformedText {
+            text: std::iter::repeat_n(self.mask, text.chars().count()).collect(),
+            mapping: OffsetMapping::from_anchors(anchors),
+        }
+    }
+}
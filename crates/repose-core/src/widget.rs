@@ -0,0 +1,80 @@
+//! # Custom widgets
+//!
+//! `ViewKind` is closed, so primitives that need their own measurement and drawing (a
+//! chart, a map tile, a code editor surface) implement [`Widget`] and go in a
+//! `ViewKind::Custom`. The layout backend calls [`Widget::measure`] when Taffy sizes the
+//! node and the paint methods once it has a rect. Everything is in physical pixels; the
+//! view's density and text scale locals are active, so `dp_to_px` works inside.
+//!
+//! Modifiers apply as usual (background, padding, clickable, ...); a custom view with
+//! children lays them out like a `Box` and paints them on top of the widget.
+//!
+//! ```rust
+//! use repose_core::*;
+//!
+//! struct Swatch(Color);
+//!
+//! impl Widget for Swatch {
+//!     fn measure(&self, c: Constraints) -> Size {
+//!         let side = dp_to_px(24.0);
+//!         Size { width: c.known_width.unwrap_or(side), height: c.known_height.unwrap_or(side) }
+//!     }
+//!     fn paint(&self, cx: &PaintCtx, scene: &mut Scene) {
+//!         scene.nodes.push(SceneNode::Rect {
+//!             rect: cx.rect,
+//!             brush: Brush::Solid(self.0.with_alpha((cx.alpha * 255.0) as u8)),
+//!             radius: 0.0,
+//!         });
+//!     }
+//! }
+//!
+//! let v = View::new(0, ViewKind::Custom(std::rc::Rc::new(Swatch(Color::WHITE))));
+//! ```
+
+use crate::{HitRegion, Rect, Scene, SemNode, Size, ViewId};
+
+/// Sizes Taffy offers a custom leaf (px). `known_*` are sizes the parent has already fixed;
+/// `max_*` is the space available, `None` when the widget is asked for its natural size.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Constraints {
+    pub known_width: Option<f32>,
+    pub known_height: Option<f32>,
+    pub max_width: Option<f32>,
+    pub max_height: Option<f32>,
+}
+
+/// Where and how a custom widget is being painted.
+#[derive(Clone, Copy, Debug)]
+pub struct PaintCtx {
+    pub id: ViewId,
+    /// Border box in window px.
+    pub rect: Rect,
+    /// Accumulated opacity of the widget and its ancestors; multiply colors by it.
+    pub alpha: f32,
+    pub focused: bool,
+    pub enabled: bool,
+}
+
+/// A primitive with its own measurement and drawing; see the module docs.
+pub trait Widget {
+    /// Name shown in `Debug` output and the inspector.
+    fn name(&self) -> &str {
+        "Custom"
+    }
+
+    /// The widget's size for `c`. Only called for views without children.
+    fn measure(&self, c: Constraints) -> Size;
+
+    /// Appends the widget's scene nodes, after its modifier backgrounds and before its children.
+    fn paint(&self, cx: &PaintCtx, scene: &mut Scene);
+
+    /// Input targets for the widget, on top of any a `clickable` modifier adds.
+    fn hit_regions(&self, _cx: &PaintCtx) -> Vec<HitRegion> {
+        Vec::new()
+    }
+
+    /// The widget's accessibility node. `View::semantics`, if set, takes precedence.
+    fn semantics(&self, _cx: &PaintCtx) -> Option<SemNode> {
+        None
+    }
+}
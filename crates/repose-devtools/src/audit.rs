@@ -0,0 +1,217 @@
+//! Accessibility audit over a finished [`Frame`]: unlabeled controls, low-contrast text,
+//! small touch targets and controls keyboard focus can't reach.
+
+use repose_core::{Brush, Color, Frame, Rect, Role, SceneNode, Vec2, locals};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum IssueKind {
+    /// A control with no accessible name.
+    MissingLabel(Role),
+    /// Text against the background under it, with the WCAG ratio and the required one.
+    LowContrast { ratio: f32, required: f32 },
+    /// A focusable target smaller than the minimum touch target (dp).
+    SmallTarget { w_dp: f32, h_dp: f32 },
+    /// A control that isn't in the focus chain.
+    NotFocusable(Role),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Issue {
+    /// Node the issue belongs to, when it has one (scene text doesn't carry ids).
+    pub id: Option<u64>,
+    pub rect: Rect,
+    pub kind: IssueKind,
+}
+
+impl Issue {
+    pub fn describe(&self) -> String {
+        match &self.kind {
+            IssueKind::MissingLabel(role) => format!("{role:?} has no label"),
+            IssueKind::LowContrast { ratio, required } => {
+                format!("text contrast {ratio:.2}:1, needs {required:.1}:1")
+            }
+            IssueKind::SmallTarget { w_dp, h_dp } => {
+                format!("touch target {w_dp:.0}x{h_dp:.0}dp is too small")
+            }
+            IssueKind::NotFocusable(role) => format!("{role:?} is not reachable by keyboard"),
+        }
+    }
+}
+
+fn is_control(role: Role) -> bool {
+    matches!(
+        role,
+        Role::Button
+            | Role::TextField
+            | Role::Checkbox
+            | Role::RadioButton
+            | Role::Switch
+            | Role::Slider
+    )
+}
+
+/// WCAG 2 contrast ratio between two opaque colors, from 1 to 21.
+pub fn contrast_ratio(a: Color, b: Color) -> f32 {
+    fn luminance(c: Color) -> f32 {
+        let [r, g, b, _] = c.to_linear();
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+    let (la, lb) = (luminance(a), luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+/// Composites `fg` over opaque `bg`.
+fn over(fg: Color, bg: Color) -> Color {
+    let a = fg.3 as f32 / 255.0;
+    let mix = |f: u8, b: u8| (f as f32 * a + b as f32 * (1.0 - a)).round() as u8;
+    Color(mix(fg.0, bg.0), mix(fg.1, bg.1), mix(fg.2, bg.2), 255)
+}
+
+/// Runs every check on `frame`. Sizes use the current density and minimum touch target.
+pub fn audit(frame: &Frame) -> Vec<Issue> {
+    let scale = locals::density().scale.max(f32::EPSILON);
+    let min_target = locals::min_touch_target().0 * scale;
+    let mut issues = Vec::new();
+
+    for n in &frame.semantics_nodes {
+        if !is_control(n.role) || !n.enabled {
+            continue;
+        }
+        if n.label.as_deref().is_none_or(|l| l.trim().is_empty()) {
+            issues.push(Issue {
+                id: Some(n.id),
+                rect: n.rect,
+                kind: IssueKind::MissingLabel(n.role),
+            });
+        }
+        // Focusables behind a modal are left out of the chain on purpose; only flag controls
+        // that have no focusable region at all.
+        let focusable = frame
+            .hit_regions
+            .iter()
+            .any(|h| h.id == n.id && h.focusable);
+        if !focusable && !frame.focus_chain.contains(&n.id) {
+            issues.push(Issue {
+                id: Some(n.id),
+                rect: n.rect,
+                kind: IssueKind::NotFocusable(n.role),
+            });
+        }
+    }
+
+    for h in frame.hit_regions.iter().filter(|h| h.focusable) {
+        if h.rect.w + 0.5 < min_target || h.rect.h + 0.5 < min_target {
+            issues.push(Issue {
+                id: Some(h.id),
+                rect: h.rect,
+                kind: IssueKind::SmallTarget {
+                    w_dp: h.rect.w / scale,
+                    h_dp: h.rect.h / scale,
+                },
+            });
+        }
+    }
+
+    // Text against the topmost solid fill painted before it under its centre.
+    let nodes = &frame.scene.nodes;
+    for (i, node) in nodes.iter().enumerate() {
+        let SceneNode::Text {
+            rect,
+            text,
+            color,
+            size,
+            ..
+        } = node
+        else {
+            continue;
+        };
+        if text.trim().is_empty() || color.3 == 0 {
+            continue;
+        }
+        let centre = Vec2 {
+            x: rect.x + rect.w * 0.5,
+            y: rect.y + rect.h * 0.5,
+        };
+        let bg = nodes[..i]
+            .iter()
+            .rev()
+            .find_map(|n| match n {
+                SceneNode::Rect {
+                    rect,
+                    brush: Brush::Solid(c),
+                    ..
+                } if c.3 == 255 && rect.contains(centre) => Some(*c),
+                _ => None,
+            })
+            .unwrap_or(frame.scene.clear_color);
+        let ratio = contrast_ratio(over(*color, bg), bg);
+        // Large text (18pt, i.e. 24 px at 1x) only needs 3:1.
+        let required = if *size / scale >= 24.0 { 3.0 } else { 4.5 };
+        if ratio < required {
+            let id = frame
+                .semantics_nodes
+                .iter()
+                .find(|n| n.role == Role::Text && n.rect.contains(centre))
+                .map(|n| n.id);
+            issues.push(Issue {
+                id,
+                rect: *rect,
+                kind: IssueKind::LowContrast { ratio, required },
+            });
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use repose_core::{FontWeight, HitRegion, Scene, SemNode};
+
+    #[test]
+    fn audit_flags_each_kind() {
+        let r = Rect {
+            x: 0.0,
+            y: 0.0,
+            w: 100.0,
+            h: 60.0,
+        };
+        let small = Rect {
+            w: 20.0,
+            h: 20.0,
+            ..r
+        };
+        let scene = Scene::new(Color::WHITE).with_node(SceneNode::Text {
+            rect: r,
+            text: "faint".into(),
+            color: Color::from_hex("#DDDDDD"),
+            size: 14.0,
+            letter_spacing: 0.0,
+            weight: FontWeight::NORMAL,
+        });
+        let frame = Frame::new(scene)
+            .with_hit_regions(vec![HitRegion::new(2, small).focusable(true)])
+            .with_semantics(vec![
+                SemNode::new(1, Role::Button, r),
+                SemNode::new(2, Role::Checkbox, small).label("ok"),
+            ])
+            .with_focus_chain(vec![2]);
+
+        let kinds: Vec<_> = audit(&frame).into_iter().map(|i| i.kind).collect();
+        assert!(kinds.contains(&IssueKind::MissingLabel(Role::Button)));
+        assert!(kinds.contains(&IssueKind::NotFocusable(Role::Button)));
+        assert!(
+            kinds
+                .iter()
+                .any(|k| matches!(k, IssueKind::SmallTarget { .. }))
+        );
+        assert!(
+            kinds
+                .iter()
+                .any(|k| matches!(k, IssueKind::LowContrast { .. }))
+        );
+        assert_eq!(kinds.len(), 4);
+        assert!((contrast_ratio(Color::BLACK, Color::WHITE) - 21.0).abs() < 0.01);
+    }
+}
@@ -1,6 +1,12 @@
-use web_time::Instant;
+use web_time::{Duration, Instant};
 
-use repose_core::{Color, Rect, Scene, SceneNode};
+pub mod audit;
+pub use audit::{Issue, IssueKind, audit};
+
+use repose_core::{
+    Color, FontWeight, Frame, FrameStats, ModifierOverride, PaintCost, Rect, Scene, SceneNode,
+    SemNode, Theme, Vec2, locals,
+};
 
 pub struct Hud {
     pub inspector_enabled: bool,
@@ -60,6 +66,12 @@ impl Hud {
             lines.push(format!("build+layout: {:.2} ms", m.build_layout_ms));
             lines.push(format!("nodes: {}", m.scene_nodes));
         }
+        if let Some(clock) = repose_core::animation::active_playback_clock()
+            && (clock.speed() != 1.0 || clock.is_paused())
+        {
+            let paused = if clock.is_paused() { " (paused)" } else { "" };
+            lines.push(format!("animations: {}x{paused}", clock.speed()));
+        }
         let text = lines.join("  |  ");
         scene.nodes.push(SceneNode::Text {
             rect: Rect {
@@ -68,11 +80,46 @@ impl Hud {
                 w: 200.0,
                 h: 16.0,
             },
-            text,
+            text: text.into(),
             color: Color::from_hex("#AAAAAA"),
             size: 14.0,
+            letter_spacing: 0.0,
+            weight: FontWeight::NORMAL,
         });
 
+        // Frame budget panel: per-phase split plus jank over the rolling window.
+        if let Some(m) = &self.metrics {
+            let s = m.stats;
+            let over = s.total_ms() > m.budget_ms;
+            scene.nodes.push(SceneNode::Text {
+                rect: Rect {
+                    x: 8.0,
+                    y: 26.0,
+                    w: 200.0,
+                    h: 16.0,
+                },
+                text: format!(
+                    "compose {:.2}  measure {:.2}  paint {:.2}  gpu {:.2} ms  |  jank {}/{} (budget {:.1} ms)",
+                    s.compose_ms,
+                    s.measure_ms,
+                    s.paint_ms,
+                    s.gpu_ms,
+                    m.jank_frames,
+                    m.jank_window,
+                    m.budget_ms
+                )
+                .into(),
+                color: if over {
+                    Color::from_hex("#FF7766")
+                } else {
+                    Color::from_hex("#AAAAAA")
+                },
+                size: 14.0,
+                letter_spacing: 0.0,
+                weight: FontWeight::NORMAL,
+            });
+        }
+
         if let Some(r) = self.hovered {
             scene.nodes.push(SceneNode::Border {
                 rect: r,
@@ -88,10 +135,55 @@ impl Hud {
 pub struct Metrics {
     pub build_layout_ms: f32,
     pub scene_nodes: usize,
+    /// Per-phase timings of the last submitted frame.
+    pub stats: FrameStats,
+    /// Over-budget frames in the scheduler's rolling window, and the window size.
+    pub jank_frames: usize,
+    pub jank_window: usize,
+    pub budget_ms: f32,
 }
 
+/// One keyboard step of a live edit, in dp.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Nudge {
+    Padding(f32),
+    Width(f32),
+    Height(f32),
+}
+
+/// Animation speeds [`Inspector::cycle_animation_speed`] steps through.
+const ANIMATION_SPEEDS: [f32; 3] = [1.0, 0.5, 0.1];
+
+/// Picks one color out of a theme.
+type ThemeColor = fn(&mut Theme) -> &mut Color;
+
+/// Theme colors [`Inspector::next_theme_token`] steps through, by name.
+const THEME_TOKENS: [(&str, ThemeColor); 8] = [
+    ("primary", |t| &mut t.primary),
+    ("on_primary", |t| &mut t.on_primary),
+    ("background", |t| &mut t.background),
+    ("surface", |t| &mut t.surface),
+    ("on_surface", |t| &mut t.on_surface),
+    ("outline", |t| &mut t.outline),
+    ("focus", |t| &mut t.focus),
+    ("error", |t| &mut t.error),
+];
+
 pub struct Inspector {
     pub hud: Hud,
+    /// Node picked for live editing, with its rect in the last frame.
+    selected: Option<(u64, Rect)>,
+    pub audit_enabled: bool,
+    /// Findings of the last audited frame, and the one last jumped to.
+    issues: Vec<Issue>,
+    issue_cursor: Option<usize>,
+    pub paint_cost_enabled: bool,
+    /// Asks the renderer to tint pixels by how often they're written; runners forward it.
+    pub overdraw_enabled: bool,
+    /// Semantics of the last dump, for diffing the next one against.
+    last_semantics: Option<Vec<SemNode>>,
+    /// Index into [`THEME_TOKENS`] of the color the theme keys edit.
+    theme_token: usize,
 }
 impl Default for Inspector {
     fn default() -> Self {
@@ -101,11 +193,395 @@ impl Default for Inspector {
 
 impl Inspector {
     pub fn new() -> Self {
-        Self { hud: Hud::new() }
+        Self {
+            hud: Hud::new(),
+            selected: None,
+            audit_enabled: false,
+            issues: Vec::new(),
+            issue_cursor: None,
+            paint_cost_enabled: false,
+            overdraw_enabled: false,
+            last_semantics: None,
+            theme_token: 0,
+        }
+    }
+
+    /// `frame`'s semantics tree as JSON, followed by what changed since the previous dump.
+    pub fn dump_semantics(&mut self, frame: &Frame) -> String {
+        let mut out = frame.semantics_to_json();
+        if let Some(prev) = &self.last_semantics {
+            let changes = repose_core::frame::diff_semantics(prev, &frame.semantics_nodes);
+            out.push_str(&format!("\n{} change(s) since last dump", changes.len()));
+            for c in changes {
+                out.push_str(&format!("\n{c}"));
+            }
+        }
+        self.last_semantics = Some(frame.semantics_nodes.clone());
+        out
+    }
+
+    pub fn toggle_overdraw(&mut self) {
+        self.overdraw_enabled = !self.overdraw_enabled;
+    }
+
+    /// Toggles the paint cost overlay, along with the per-view attribution it needs.
+    pub fn toggle_paint_cost(&mut self) {
+        self.paint_cost_enabled = !self.paint_cost_enabled;
+        repose_core::set_paint_cost_tracking(self.paint_cost_enabled);
+    }
+
+    /// Slows every animation down: normal speed, half, a tenth, then back. Returns the speed.
+    pub fn cycle_animation_speed(&mut self) -> f32 {
+        let clock = repose_core::animation::playback_clock();
+        let i = ANIMATION_SPEEDS
+            .iter()
+            .position(|s| *s == clock.speed())
+            .map_or(0, |i| (i + 1) % ANIMATION_SPEEDS.len());
+        clock.set_speed(ANIMATION_SPEEDS[i]);
+        ANIMATION_SPEEDS[i]
+    }
+
+    /// Freezes or resumes every animation; returns whether they're now paused.
+    pub fn toggle_animation_pause(&mut self) -> bool {
+        let clock = repose_core::animation::playback_clock();
+        if clock.is_paused() {
+            clock.resume();
+        } else {
+            clock.pause();
+        }
+        clock.is_paused()
+    }
+
+    /// Advances animations by one 60 Hz frame, for stepping through them while paused.
+    pub fn step_animations(&mut self) {
+        repose_core::animation::playback_clock().step(Duration::from_micros(16_667));
+    }
+
+    pub fn toggle_audit(&mut self) {
+        self.audit_enabled = !self.audit_enabled;
+        self.issue_cursor = None;
+    }
+
+    /// Accessibility issues found in the last frame while the audit is on.
+    pub fn issues(&self) -> &[Issue] {
+        &self.issues
+    }
+
+    /// Selects issue `i`'s node (or just highlights its rect when it has none).
+    pub fn jump_to_issue(&mut self, i: usize) -> bool {
+        let Some(issue) = self.issues.get(i) else {
+            return false;
+        };
+        self.issue_cursor = Some(i);
+        self.selected = issue.id.map(|id| (id, issue.rect));
+        repose_core::set_inspected(self.selected());
+        true
+    }
+
+    /// Jumps to the issue after the current one, wrapping around.
+    pub fn next_issue(&mut self) -> bool {
+        if self.issues.is_empty() {
+            return false;
+        }
+        let i = self.issue_cursor.map_or(0, |i| (i + 1) % self.issues.len());
+        self.jump_to_issue(i)
+    }
+
+    pub fn selected(&self) -> Option<u64> {
+        self.selected.map(|(id, _)| id)
+    }
+
+    /// Selects the innermost node of `frame` under `pos`, or clears the selection.
+    pub fn pick(&mut self, frame: &Frame, pos: Vec2) {
+        let area = |r: &Rect| r.w * r.h;
+        let hits = frame.hit_regions.iter().map(|h| (h.id, h.rect));
+        let sems = frame.semantics_nodes.iter().map(|n| (n.id, n.rect));
+        self.selected = hits
+            .chain(sems)
+            .filter(|(_, r)| r.contains(pos))
+            .min_by(|a, b| area(&a.1).total_cmp(&area(&b.1)));
+        repose_core::set_inspected(self.selected());
+    }
+
+    pub fn deselect(&mut self) {
+        self.selected = None;
+        repose_core::set_inspected(None);
+    }
+
+    /// Changes the selected node's edit; false when nothing is selected.
+    pub fn edit_selected(&mut self, f: impl FnOnce(&mut ModifierOverride)) -> bool {
+        let Some(id) = self.selected() else {
+            return false;
+        };
+        let mut o = repose_core::modifier_override(id).unwrap_or_default();
+        f(&mut o);
+        repose_core::set_modifier_override(id, o);
+        true
+    }
+
+    /// Steps a value of the selected node, starting from what it currently has (its laid-out
+    /// size for width and height).
+    pub fn nudge(&mut self, n: Nudge) -> bool {
+        let Some((_, rect)) = self.selected else {
+            return false;
+        };
+        let current = repose_core::inspected().map(|(_, v)| v).unwrap_or_default();
+        let scale = locals::density().scale.max(f32::EPSILON);
+        self.edit_selected(|o| match n {
+            Nudge::Padding(d) => {
+                o.padding = Some((o.padding.or(current.padding).unwrap_or(0.0) + d).max(0.0))
+            }
+            Nudge::Width(d) => {
+                let w = o.width.or(current.width).unwrap_or(rect.w / scale);
+                o.width = Some((w + d).max(0.0));
+            }
+            Nudge::Height(d) => {
+                let h = o.height.or(current.height).unwrap_or(rect.h / scale);
+                o.height = Some((h + d).max(0.0));
+            }
+        })
+    }
+
+    /// Edits the app-wide theme tokens.
+    pub fn edit_theme(&mut self, f: impl FnOnce(&mut Theme)) {
+        repose_core::edit_theme_default(f);
+    }
+
+    /// Name of the theme color [`nudge_theme`](Self::nudge_theme) edits.
+    pub fn theme_token(&self) -> &'static str {
+        THEME_TOKENS[self.theme_token].0
+    }
+
+    /// Moves the theme keys on to the next color, wrapping around.
+    pub fn next_theme_token(&mut self) -> &'static str {
+        self.theme_token = (self.theme_token + 1) % THEME_TOKENS.len();
+        self.theme_token()
+    }
+
+    /// Lightens (positive `d`) or darkens the current theme color by `d` per channel.
+    pub fn nudge_theme(&mut self, d: i16) {
+        let token = THEME_TOKENS[self.theme_token].1;
+        self.edit_theme(|t| {
+            let c = token(t);
+            let step = |v: u8| (v as i16 + d).clamp(0, 255) as u8;
+            *c = Color(step(c.0), step(c.1), step(c.2), c.3);
+        });
+    }
+
+    /// Drops every modifier edit made so far.
+    pub fn reset_edits(&mut self) {
+        repose_core::clear_modifier_overrides();
+    }
+
+    pub fn frame(&mut self, scene: &mut Scene, frame: &Frame) {
+        if self.paint_cost_enabled {
+            paint_cost_overlay(scene, &repose_core::paint_costs());
+        }
+        if self.audit_enabled {
+            self.issues = audit(frame);
+            self.audit_panel(scene);
+        }
+        if !self.hud.inspector_enabled {
+            return;
+        }
+        self.hud.overlay(scene);
+
+        // Follow the selection as edits move and resize it.
+        if let Some((id, rect)) = &mut self.selected
+            && let Some(r) = frame
+                .hit_regions
+                .iter()
+                .find(|h| h.id == *id)
+                .map(|h| h.rect)
+                .or_else(|| {
+                    frame
+                        .semantics_nodes
+                        .iter()
+                        .find(|n| n.id == *id)
+                        .map(|n| n.rect)
+                })
+        {
+            *rect = r;
+        }
+        let hex = |c: Color| format!("#{:02X}{:02X}{:02X}", c.0, c.1, c.2);
+        let mut theme = locals::theme();
+        let mut lines = vec![format!(
+            "theme {} {}  |  T next color  - = darker/lighter",
+            self.theme_token(),
+            hex(*THEME_TOKENS[self.theme_token].1(&mut theme))
+        )];
+        if let Some((id, rect)) = self.selected {
+            scene.nodes.push(SceneNode::Border {
+                rect,
+                color: Color::from_hex("#FFAA33"),
+                width: 2.0,
+                radius: 0.0,
+            });
+
+            let dp = |v: Option<f32>| v.map_or("auto".to_string(), |v| format!("{v:.0}"));
+            let v = repose_core::inspected().map(|(_, v)| v).unwrap_or_default();
+            lines.insert(
+                0,
+                format!(
+                    "#{id:x}  padding {}  width {}  height {}  background {}",
+                    dp(v.padding),
+                    dp(v.width),
+                    dp(v.height),
+                    v.background.map_or("none".to_string(), hex)
+                ),
+            );
+            lines.push(
+                "[ ] padding  \u{2190}\u{2192} width  \u{2191}\u{2193} height  Backspace reset  Esc deselect"
+                    .to_string(),
+            );
+        }
+        for (i, text) in lines.into_iter().enumerate() {
+            scene.nodes.push(SceneNode::Text {
+                rect: Rect {
+                    x: 8.0,
+                    y: 44.0 + i as f32 * 18.0,
+                    w: 200.0,
+                    h: 16.0,
+                },
+                text: text.into(),
+                color: Color::from_hex("#FFAA33"),
+                size: 14.0,
+                letter_spacing: 0.0,
+                weight: FontWeight::NORMAL,
+            });
+        }
     }
-    pub fn frame(&mut self, scene: &mut Scene) {
-        if self.hud.inspector_enabled {
-            self.hud.overlay(scene);
+
+    fn audit_panel(&self, scene: &mut Scene) {
+        const SHOWN: usize = 12;
+        let accent = Color::from_hex("#FF5577");
+        for (i, issue) in self.issues.iter().enumerate() {
+            scene.nodes.push(SceneNode::Border {
+                rect: issue.rect,
+                color: accent,
+                width: if self.issue_cursor == Some(i) {
+                    3.0
+                } else {
+                    1.0
+                },
+                radius: 0.0,
+            });
         }
+
+        let mut lines = vec![format!(
+            "a11y audit: {} issue(s)  |  F8 next",
+            self.issues.len()
+        )];
+        // Keep the current issue in view when the list is longer than the panel.
+        let first = self.issue_cursor.map_or(0, |c| c.saturating_sub(SHOWN - 1));
+        for (i, issue) in self.issues.iter().enumerate().skip(first).take(SHOWN) {
+            let mark = if self.issue_cursor == Some(i) {
+                ">"
+            } else {
+                " "
+            };
+            lines.push(format!("{mark} {}. {}", i + 1, issue.describe()));
+        }
+        let top = 86.0;
+        scene.nodes.push(SceneNode::Rect {
+            rect: Rect {
+                x: 4.0,
+                y: top - 4.0,
+                w: 360.0,
+                h: lines.len() as f32 * 18.0 + 8.0,
+            },
+            brush: repose_core::Brush::Solid(Color(0, 0, 0, 200)),
+            radius: 4.0,
+        });
+        for (i, text) in lines.into_iter().enumerate() {
+            scene.nodes.push(SceneNode::Text {
+                rect: Rect {
+                    x: 8.0,
+                    y: top + i as f32 * 18.0,
+                    w: 352.0,
+                    h: 16.0,
+                },
+                text: text.into(),
+                color: if i == 0 {
+                    accent
+                } else {
+                    Color::from_hex("#DDDDDD")
+                },
+                size: 14.0,
+                letter_spacing: 0.0,
+                weight: FontWeight::NORMAL,
+            });
+        }
+    }
+}
+
+/// Tints every view by the pixels it fills itself, relative to the costliest one (green to
+/// red), and lists the heaviest subtrees.
+fn paint_cost_overlay(scene: &mut Scene, costs: &[PaintCost]) {
+    const SHOWN: usize = 8;
+    let Some(root) = costs.iter().find(|c| c.depth == 0) else {
+        return;
+    };
+    let max_fill = costs.iter().map(|c| c.fill_px).fold(0.0, f32::max);
+    if max_fill <= 0.0 {
+        return;
+    }
+    for c in costs.iter().filter(|c| c.nodes > 0) {
+        let heat = (c.fill_px / max_fill).clamp(0.0, 1.0);
+        scene.nodes.push(SceneNode::Rect {
+            rect: c.rect,
+            brush: repose_core::Brush::Solid(Color(
+                (255.0 * heat) as u8,
+                (255.0 * (1.0 - heat)) as u8,
+                0,
+                (40.0 + 100.0 * heat) as u8,
+            )),
+            radius: 0.0,
+        });
+    }
+
+    let mut heaviest: Vec<&PaintCost> = costs.iter().filter(|c| c.depth > 0).collect();
+    heaviest.sort_by(|a, b| b.subtree_fill_px.total_cmp(&a.subtree_fill_px));
+    let screen = (root.rect.w * root.rect.h).max(1.0);
+    let mut lines = vec![format!(
+        "paint cost: {} nodes, {:.2} screens filled",
+        root.subtree_nodes,
+        root.subtree_fill_px / screen
+    )];
+    lines.extend(heaviest.iter().take(SHOWN).map(|c| {
+        format!(
+            "#{:x} (depth {})  {} nodes  {:.2} screens",
+            c.id,
+            c.depth,
+            c.subtree_nodes,
+            c.subtree_fill_px / screen
+        )
+    }));
+
+    let (w, x) = (360.0, (root.rect.x + root.rect.w - 364.0).max(4.0));
+    scene.nodes.push(SceneNode::Rect {
+        rect: Rect {
+            x,
+            y: 4.0,
+            w,
+            h: lines.len() as f32 * 18.0 + 8.0,
+        },
+        brush: repose_core::Brush::Solid(Color(0, 0, 0, 200)),
+        radius: 4.0,
+    });
+    for (i, text) in lines.into_iter().enumerate() {
+        scene.nodes.push(SceneNode::Text {
+            rect: Rect {
+                x: x + 4.0,
+                y: 8.0 + i as f32 * 18.0,
+                w: w - 8.0,
+                h: 16.0,
+            },
+            text: text.into(),
+            color: Color::from_hex("#DDDDDD"),
+            size: 14.0,
+            letter_spacing: 0.0,
+            weight: FontWeight::NORMAL,
+        });
     }
 }
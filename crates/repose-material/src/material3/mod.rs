@@ -138,6 +138,7 @@ pub fn OutlinedCard(modifier: Modifier, content: View) -> View {
     )
 }
 
+/// A chip that stays selected once clicked, announced as a checkbox.
 pub fn FilterChip(
     selected: bool,
     on_click: impl Fn() + 'static,
@@ -161,25 +162,30 @@ pub fn FilterChip(
             .border(1.0, Color::from_hex("#444444"), 8.0)
             .clip_rounded(8.0)
             .padding(12.0)
-            .clickable()
-            .on_pointer_down(move |_| on_click()),
+            .toggleable(Role::Checkbox, selected, on_click),
         Row(Modifier::new()).child((leading_icon.unwrap_or(Box(Modifier::new())), label)),
     )
 }
 
+/// Top bar, content, bottom bar and FAB, laid out around the window insets: the top bar
+/// sits below the status bar, and the bottom bar and FAB ride above the navigation bar or,
+/// while it is open, the on-screen keyboard. The content area shrinks to fit between them.
 pub fn Scaffold(
     top_bar: Option<View>,
     bottom_bar: Option<View>,
     floating_action_button: Option<View>,
     content: impl Fn(PaddingValues) -> View,
 ) -> View {
+    let insets = window_insets();
+    let top = insets.system_bars.top;
+    let bottom = insets.bottom();
     Stack(Modifier::new().fill_max_size()).child((
         // Main content with padding
         Box(Modifier::new()
             .fill_max_size()
             .padding_values(PaddingValues {
-                top: if top_bar.is_some() { 64.0 } else { 0.0 },
-                bottom: if bottom_bar.is_some() { 80.0 } else { 0.0 },
+                top: top + if top_bar.is_some() { 64.0 } else { 0.0 },
+                bottom: bottom + if bottom_bar.is_some() { 80.0 } else { 0.0 },
                 ..Default::default()
             }))
         .child(content(PaddingValues::default())),
@@ -187,7 +193,7 @@ pub fn Scaffold(
         if let Some(bar) = top_bar {
             Box(Modifier::new()
                 .absolute()
-                .offset(Some(0.0), Some(0.0), Some(0.0), None))
+                .offset(Some(0.0), Some(top), Some(0.0), None))
             .child(bar)
         } else {
             Box(Modifier::new())
@@ -196,7 +202,7 @@ pub fn Scaffold(
         if let Some(bar) = bottom_bar {
             Box(Modifier::new()
                 .absolute()
-                .offset(Some(0.0), None, Some(0.0), Some(0.0)))
+                .offset(Some(0.0), None, Some(0.0), Some(bottom)))
             .child(bar)
         } else {
             Box(Modifier::new())
@@ -205,7 +211,7 @@ pub fn Scaffold(
         if let Some(fab) = floating_action_button {
             Box(Modifier::new()
                 .absolute()
-                .offset(None, None, Some(16.0), Some(16.0)))
+                .offset(None, None, Some(16.0), Some(bottom + 16.0)))
             .child(fab)
         } else {
             Box(Modifier::new())
@@ -5,6 +5,8 @@ use repose_core::*;
 use repose_ui::{
     Box as VBox, Stack, ViewExt,
     anim::{animate_f32, animate_f32_from},
+    dialog::{Dialog, Sheet},
+    lazy::LazyColumnState,
 };
 use serde::{Deserialize, Serialize};
 
@@ -263,6 +265,14 @@ impl<K: NavKey> EntryScope<K> {
     ) -> Rc<RefCell<T>> {
         self.saved.remember(slot, init)
     }
+    /// Scroll state for a `LazyColumn` on this entry. It lives as long as the entry, so the
+    /// list is where it was left when the user navigates back to it.
+    pub fn remember_lazy_list_state(&self, slot: &'static str) -> Rc<LazyColumnState> {
+        self.saved
+            .remember(slot, || Rc::new(LazyColumnState::new()))
+            .borrow()
+            .clone()
+    }
     pub fn set_result<T: 'static>(&self, slot: &'static str, v: T) {
         self.saved.set_result(slot, v)
     }
@@ -292,32 +302,122 @@ impl Default for NavTransition {
     }
 }
 
+/// How [`NavDisplayWith`] shows an entry.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Destination {
+    /// Replaces whatever was showing, with the display's [`NavTransition`].
+    #[default]
+    Screen,
+    /// A modal [`Dialog`] over the screen beneath it.
+    Dialog,
+    /// A modal bottom [`Sheet`] over the screen beneath it.
+    Sheet,
+}
+
 pub fn NavDisplay<K: NavKey>(
     stack: Rc<NavBackStack<K>>,
     make_view: EntryRenderer<K>,
     on_back: Option<Rc<dyn Fn()>>,
     transition: NavTransition,
+) -> View {
+    NavDisplayWith(
+        stack,
+        make_view,
+        |_| Destination::Screen,
+        on_back,
+        transition,
+    )
+}
+
+/// [`NavDisplay`] for a graph with dialog and sheet destinations, as `destination_of`
+/// classifies each key. While such entries are on top of the stack, the nearest screen
+/// beneath them stays composed and each of them is shown over it as a modal layer, in stack
+/// order. Back, a click on the scrim, or Escape pops the top one; each keeps its own
+/// [`SavedState`] like any other entry.
+pub fn NavDisplayWith<K: NavKey>(
+    stack: Rc<NavBackStack<K>>,
+    make_view: EntryRenderer<K>,
+    destination_of: impl Fn(&K) -> Destination,
+    on_back: Option<Rc<dyn Fn()>>,
+    transition: NavTransition,
 ) -> View {
     let _v = stack.version.get(); // join reactive graph
-    let (id, key, saved, entry_scope) = match stack.top() {
-        Some(t) => t,
-        None => return VBox(Modifier::new()),
+    let nav = Navigator {
+        stack: (*stack).clone(),
+    };
+    let entries: Vec<_> = {
+        let s = stack.inner.borrow();
+        let base = s
+            .entries
+            .iter()
+            .rposition(|e| destination_of(&e.key) == Destination::Screen)
+            .unwrap_or(0);
+        s.entries[base..]
+            .iter()
+            .map(|e| {
+                let scope = EntryScope {
+                    id: e.id,
+                    key: e.key.clone(),
+                    saved: e.saved.clone(),
+                    nav: nav.clone(),
+                };
+                (scope, e.scope.clone())
+            })
+            .collect()
     };
-    let scope = EntryScope {
-        id,
-        key,
-        saved,
-        nav: Navigator {
-            stack: (*stack).clone(),
-        },
+    let Some(((screen, screen_scope), overlays)) = entries.split_first() else {
+        return VBox(Modifier::new());
     };
 
-    let dir = stack.last_dir();
-    if dir == TransitionDir::None {
-        let v = entry_scope.run(|| (make_view)(&scope));
-        return maybe_intercept_back(v, on_back);
+    // Showing or dismissing an overlay leaves the screen beneath where it is, so the screen
+    // only transitions when it is a different entry from the last one shown.
+    let shown = remember_with_key("nav3:shown", || {
+        std::cell::Cell::new((screen.id, stack.last_dir()))
+    });
+    if shown.get().0 != screen.id {
+        shown.set((screen.id, stack.last_dir()));
     }
+    let dir = shown.get().1;
 
+    let v = screen_scope.run(|| (make_view)(screen));
+    let mut v = if dir == TransitionDir::None {
+        v
+    } else {
+        transitioned(v, screen.id, dir, transition)
+    };
+
+    let live: Vec<u64> = overlays.iter().map(|(e, _)| overlay_group(e.id)).collect();
+    let was_live = remember_with_key("nav3:overlays", || RefCell::new(Vec::new()));
+    for g in was_live.replace(live.clone()) {
+        if !live.contains(&g) {
+            forget_keyed(g);
+        }
+    }
+    for ((entry, scope), group) in overlays.iter().zip(live) {
+        let nav = nav.clone();
+        let dismiss = move || {
+            nav.pop();
+        };
+        let layer = keyed(group, || {
+            let content = scope.run(|| (make_view)(entry));
+            match destination_of(&entry.key) {
+                Destination::Sheet => Sheet(content, dismiss),
+                _ => Dialog(content, dismiss),
+            }
+        });
+        v = Stack(Modifier::new().fill_max_size()).child((v, layer));
+    }
+    maybe_intercept_back(v, on_back)
+}
+
+fn overlay_group(id: u64) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut h = std::collections::hash_map::DefaultHasher::new();
+    ("nav3:overlay", id).hash(&mut h);
+    h.finish()
+}
+
+fn transitioned(v: View, id: u64, dir: TransitionDir, transition: NavTransition) -> View {
     let (initial, target) = if dir == TransitionDir::Push {
         (0.0, 1.0)
     } else {
@@ -343,10 +443,8 @@ pub fn NavDisplay<K: NavKey>(
         1.0
     };
 
-    let v = entry_scope.run(|| (make_view)(&scope));
-    let framed = Stack(Modifier::new().fill_max_size())
-        .child(VBox(Modifier::new().translate(dx, 0.0).alpha(alpha)).child(v));
-    maybe_intercept_back(framed, on_back)
+    Stack(Modifier::new().fill_max_size())
+        .child(VBox(Modifier::new().translate(dx, 0.0).alpha(alpha)).child(v))
 }
 
 fn maybe_intercept_back(v: View, _on_back: Option<Rc<dyn Fn()>>) -> View {
@@ -389,3 +487,60 @@ pub fn InstallBackHandler<K: NavKey>(stack: NavBackStack<K>) -> Dispose {
     back::set(Some(Rc::new(move || nav.pop())));
     on_unmount(|| back::set(None))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nav_guards_vet_navigation_and_results_survive_a_restore() {
+        use repose_ui::navigation::{GuardDecision, NavController, NavResultKey, Transition};
+        use std::cell::Cell;
+        use std::time::Duration;
+
+        const PICKED: NavResultKey<u32> = NavResultKey::new("picked");
+        let mut sched = Scheduler::new();
+        let mut frame = || {
+            sched.advance(Duration::from_millis(16), |_| View::new(0, ViewKind::Box));
+        };
+
+        // `admin` needs a login; `nowhere` is off limits.
+        let logged_in = Rc::new(Cell::new(false));
+        let nav = NavController::new("home");
+        let ok = logged_in.clone();
+        nav.add_guard(move |t| {
+            let (Transition::Push { to, .. }
+            | Transition::Replace { to, .. }
+            | Transition::Pop { to, .. }) = t;
+            let (to, ok) = (to.clone(), ok.get());
+            async move {
+                match to.as_str() {
+                    "admin" if !ok => GuardDecision::Redirect("login".into()),
+                    "nowhere" => GuardDecision::Deny,
+                    _ => GuardDecision::Allow,
+                }
+            }
+        });
+        nav.navigate("admin");
+        assert!(nav.is_navigating());
+        assert_eq!(nav.current.get(), "home");
+        frame();
+        assert_eq!(nav.current.get(), "login");
+        nav.navigate("nowhere");
+        frame();
+        assert_eq!(nav.current.get(), "login");
+
+        // A picker hands a value back to the screen that opened it, across restores.
+        nav.navigate_for_result("picker", PICKED);
+        frame();
+        assert_eq!(nav.current.get(), "picker");
+        let nav = NavController::restore(nav.snapshot());
+        assert!(nav.set_result(PICKED, 7));
+        let nav = NavController::restore(nav.snapshot());
+        assert!(nav.pop());
+        assert_eq!(nav.take_result(PICKED), Some(7));
+        assert_eq!(nav.take_result(PICKED), None);
+        // Screens not opened for the result can't send one.
+        assert!(!nav.set_result(PICKED, 1));
+    }
+}
@@ -1,11 +1,14 @@
 use crate::common as rc;
 use crate::*;
 
+use repose_core::input::PointerId;
 use repose_ui::TextFieldState;
-use repose_ui::textfield::{TF_FONT_DP, TF_PADDING_X_DP, index_for_x_bytes, measure_text};
+use repose_ui::text_selection;
+use repose_ui::textfield::{LONG_PRESS, TF_FONT_DP, TF_PADDING_X_DP, TouchSelection};
 
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+use std::ops::Range;
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -51,14 +54,19 @@ pub fn run_android_app_with_options(
     options: AndroidOptions,
 ) -> anyhow::Result<()> {
     repose_core::animation::set_clock(Box::new(repose_core::animation::SystemClock));
+    rc::init_locale_from_os();
 
     let event_loop = winit::event_loop::EventLoopBuilder::new()
-        .with_android_app(app)
+        .with_android_app(app.clone())
         .build()?;
+    rc::install_snapshot_waker(&event_loop);
+    crate::crash::install();
+    rc::start_profiler();
 
     struct AppState {
         root: Box<dyn FnMut(&mut Scheduler) -> View>,
         options: AndroidOptions,
+        app: AndroidApp,
 
         window: Option<Arc<Window>>,
         backend: Option<repose_render_wgpu::WgpuBackend>,
@@ -68,30 +76,41 @@ pub fn run_android_app_with_options(
         // input state
         last_pos_px: (f32, f32),
         modifiers: Modifiers,
-        capture_id: Option<u64>,
-        pressed_ids: HashSet<u64>,
+        key_repeat: rc::KeyRepeat,
+        captures: rc::PointerCaptures,
 
-        // touch scroll cancel-click
-        touch_scrolled: bool,
-        touch_scroll_accum_y_px: f32,
-        prev_touch_px: Option<(f32, f32)>,
+        /// The gesture of each finger that's down.
+        touches: HashMap<u64, rc::TouchGesture>,
 
         // TextFields
-        textfield_states: HashMap<u64, Rc<RefCell<TextFieldState>>>,
+        retained: RetainedStateStore,
         ime_preedit: bool,
+        // touch down on a TextField: when, and its selection before the caret moved
+        tf_press: Option<(web_time::Instant, Range<usize>)>,
+        // winit has no Android clipboard; the selection toolbar copies and pastes in-app
+        clipboard: Option<String>,
 
         // auto root scroll state
         root_scroll: Rc<RefCell<rc::RootScrollState>>,
 
         // redraw control
         dirty: bool,
+        caret_blink: rc::CaretBlinkTimer,
+        pacer: rc::FramePacer,
+        battery: rc::BatteryWatch,
+        insets: rc::InsetsTracker,
     }
 
     impl AppState {
-        fn new(root: Box<dyn FnMut(&mut Scheduler) -> View>, options: AndroidOptions) -> Self {
+        fn new(
+            app: AndroidApp,
+            root: Box<dyn FnMut(&mut Scheduler) -> View>,
+            options: AndroidOptions,
+        ) -> Self {
             Self {
                 root,
                 options,
+                app,
                 window: None,
                 backend: None,
                 sched: Scheduler::new(),
@@ -99,18 +118,22 @@ pub fn run_android_app_with_options(
 
                 last_pos_px: (0.0, 0.0),
                 modifiers: Modifiers::default(),
-                capture_id: None,
-                pressed_ids: HashSet::new(),
+                key_repeat: rc::KeyRepeat::default(),
+                captures: rc::PointerCaptures::default(),
 
-                touch_scrolled: false,
-                touch_scroll_accum_y_px: 0.0,
-                prev_touch_px: None,
+                touches: HashMap::new(),
 
-                textfield_states: HashMap::new(),
+                retained: RetainedStateStore::new(),
                 ime_preedit: false,
+                tf_press: None,
+                clipboard: None,
 
                 root_scroll: Rc::new(RefCell::new(rc::RootScrollState::default())),
                 dirty: true,
+                caret_blink: rc::CaretBlinkTimer::default(),
+                pacer: rc::FramePacer::default(),
+                battery: rc::BatteryWatch::default(),
+                insets: rc::InsetsTracker::default(),
             }
         }
 
@@ -139,6 +162,32 @@ pub fn run_android_app_with_options(
             self.dp_px(6.0)
         }
 
+        /// Takes `finger`'s gesture from the view it pressed, which gets a `Cancel`.
+        fn cancel_touch(&mut self, finger: u64, pos: Vec2) {
+            let Some(cid) = self.captures.release(PointerId(finger)) else {
+                return;
+            };
+            if let Some(f) = &self.frame_cache
+                && let Some(i) = rc::hit_index_by_id(f, cid)
+                && let Some(cb) = &f.hit_regions[i].on_pointer_up
+            {
+                cb(rc::pe_finger(
+                    rc::pe_touch(
+                        repose_core::input::PointerEventKind::Cancel,
+                        pos,
+                        self.modifiers,
+                    ),
+                    finger,
+                ));
+            }
+            if self.is_textfield(cid)
+                && let Some(st) = self.retained.get::<TextFieldState>(self.tf_key_of(cid))
+            {
+                st.borrow_mut().end_drag();
+                self.tf_press = None;
+            }
+        }
+
         fn tf_key_of(&self, visual_id: u64) -> u64 {
             if let Some(f) = &self.frame_cache
                 && let Some(i) = rc::hit_index_by_id(f, visual_id)
@@ -171,8 +220,11 @@ pub fn run_android_app_with_options(
 
         fn ensure_caret_visible_in_hit(&self, st: &mut TextFieldState, hit_rect: Rect) {
             let font_px = dp_to_px(TF_FONT_DP) * repose_core::locals::text_scale().0;
-            let m = measure_text(&st.text, font_px);
-            let caret_x_px = m.positions.get(st.caret_index()).copied().unwrap_or(0.0);
+            if st.multiline {
+                st.ensure_caret_visible_y(font_px);
+                return;
+            }
+            let caret_x_px = st.x_for_index(st.caret_index(), font_px);
             st.ensure_caret_visible(
                 caret_x_px,
                 hit_rect.w - 2.0 * self.padding_px(),
@@ -180,6 +232,28 @@ pub fn run_android_app_with_options(
             );
         }
 
+        /// Applies taps on the touch selection toolbar (Cut/Copy/Paste/Select all).
+        fn apply_text_edit_actions(&mut self) {
+            for (id, action) in text_selection::take_edit_actions() {
+                let Some(state_rc) = self.retained.get::<TextFieldState>(self.tf_key_of(id)) else {
+                    continue;
+                };
+                let mut st = state_rc.borrow_mut();
+                let before = st.text.clone();
+                if let Some(copied) = st.apply_edit(action, self.clipboard.as_deref()) {
+                    self.clipboard = Some(copied);
+                }
+                if let Some(f) = &self.frame_cache
+                    && let Some(i) = rc::hit_index_by_id(f, id)
+                {
+                    self.ensure_caret_visible_in_hit(&mut st, f.hit_regions[i].rect);
+                }
+                if st.text != before {
+                    self.notify_text_change(id, st.text.clone());
+                }
+            }
+        }
+
         fn sync_window_size(&mut self, size: PhysicalSize<u32>) {
             self.sched.size = (size.width, size.height);
             if let Some(b) = &mut self.backend {
@@ -226,6 +300,7 @@ pub fn run_android_app_with_options(
             _id: winit::window::WindowId,
             event: WindowEvent,
         ) {
+            crate::crash::record_window_event(&event);
             match event {
                 WindowEvent::CloseRequested => el.exit(),
 
@@ -246,23 +321,34 @@ pub fn run_android_app_with_options(
 
                     match t.phase {
                         winit::event::TouchPhase::Started => {
-                            self.touch_scrolled = false;
-                            self.touch_scroll_accum_y_px = 0.0;
-
                             if let Some(f) = &self.frame_cache {
-                                if let Some(i) = rc::top_hit_index(f, pos) {
+                                let top = rc::top_hit_index(f, pos);
+
+                                // Touching anything but the focused field or its selection
+                                // handles/toolbar dismisses the touch selection UI.
+                                if let Some(fid) = self.sched.focused {
+                                    let keep = top.is_some_and(|i| {
+                                        let id = f.hit_regions[i].id;
+                                        id == fid || text_selection::is_overlay_of(id, fid)
+                                    });
+                                    if !keep
+                                        && let Some(st) =
+                                            self.retained.get::<TextFieldState>(self.tf_key_of(fid))
+                                    {
+                                        st.borrow_mut().touch = TouchSelection::default();
+                                    }
+                                }
+
+                                if let Some(i) = top {
                                     let hit = &f.hit_regions[i];
 
-                                    self.capture_id = Some(hit.id);
-                                    self.pressed_ids.insert(hit.id);
+                                    self.captures.capture(PointerId(t.id), hit.id);
 
                                     // focus + IME for textfields
                                     if self.is_textfield(hit.id) {
                                         self.sched.focused = Some(hit.id);
                                         let key = self.tf_key_of(hit.id);
-                                        self.textfield_states.entry(key).or_insert_with(|| {
-                                            Rc::new(RefCell::new(TextFieldState::new()))
-                                        });
+                                        self.retained.get_or_insert_with(key, TextFieldState::new);
 
                                         if let Some(win) = &self.window {
                                             let sf = win.scale_factor() as f32;
@@ -282,110 +368,141 @@ pub fn run_android_app_with_options(
 
                                         // caret placement on touch down
                                         let key = self.tf_key_of(hit.id);
-                                        if let Some(state_rc) = self.textfield_states.get(&key) {
+                                        if let Some(state_rc) =
+                                            self.retained.get::<TextFieldState>(key)
+                                        {
                                             let mut st = state_rc.borrow_mut();
-                                            let inner_x_px = hit.rect.x + self.padding_px();
-                                            let content_x_px =
-                                                pos_px.0 - inner_x_px + st.scroll_offset;
-                                            let font_px = dp_to_px(TF_FONT_DP)
-                                                * repose_core::locals::text_scale().0;
-                                            let idx = index_for_x_bytes(
-                                                &st.text,
-                                                font_px,
-                                                content_x_px.max(0.0),
-                                            );
+                                            let (idx, affinity) = if st.multiline {
+                                                rc::text_area_index(&st, hit.rect, pos)
+                                            } else {
+                                                let inner_x_px = hit.rect.x + self.padding_px();
+                                                let content_x_px =
+                                                    pos_px.0 - inner_x_px + st.scroll_offset;
+                                                let font_px = dp_to_px(TF_FONT_DP)
+                                                    * repose_core::locals::text_scale().0;
+                                                let idx =
+                                                    st.index_for_x(font_px, content_x_px.max(0.0));
+                                                (idx, st.affinity)
+                                            };
+                                            self.tf_press = Some((
+                                                web_time::Instant::now(),
+                                                st.selection.clone(),
+                                            ));
                                             st.begin_drag(idx, self.modifiers.shift);
+                                            st.affinity = affinity;
                                             self.ensure_caret_visible_in_hit(&mut st, hit.rect);
                                         }
                                     }
 
                                     // pointer down callback
                                     if let Some(cb) = &hit.on_pointer_down {
-                                        cb(rc::pe_down_primary(
-                                            repose_core::input::PointerKind::Touch,
-                                            pos,
-                                            self.modifiers,
+                                        cb(rc::pe_finger(
+                                            rc::pe_down_primary(
+                                                repose_core::input::PointerKind::Touch,
+                                                pos,
+                                                self.modifiers,
+                                            ),
+                                            t.id,
                                         ));
                                     }
                                 }
                             }
 
-                            self.prev_touch_px = Some(pos_px);
+                            let captured = self.captures.get(PointerId(t.id)).is_some();
+                            self.touches
+                                .insert(t.id, rc::TouchGesture::new(pos, captured));
                             self.dirty = true;
                             self.request_redraw();
                         }
 
                         winit::event::TouchPhase::Moved => {
-                            if let (Some(prev), Some(f)) = (self.prev_touch_px, &self.frame_cache) {
-                                let dy_px = pos_px.1 - prev.1;
-
-                                // Always attempt to scroll the best consumer under the finger.
-                                if dy_px.abs() > 0.0 {
-                                    self.touch_scroll_accum_y_px += dy_px;
-
-                                    let consumed =
-                                        rc::dispatch_scroll(f, pos, Vec2 { x: 0.0, y: -dy_px });
-
-                                    if consumed
-                                        && self.touch_scroll_accum_y_px.abs() > self.touch_slop_px()
-                                    {
-                                        self.touch_scrolled = true;
-                                    }
-                                }
+                            let capture = self.captures.get(PointerId(t.id));
+                            // A dragged selection handle keeps the gesture, as does a view
+                            // that asked to.
+                            let keep = repose_core::intercept_disallowed(PointerId(t.id))
+                                || self.sched.focused.zip(capture).is_some_and(|(fid, cid)| {
+                                    text_selection::is_overlay_of(cid, fid)
+                                });
+                            let (fingers, slop) = (self.touches.len(), self.touch_slop_px());
+                            let stolen = match (self.touches.get_mut(&t.id), &self.frame_cache) {
+                                (Some(g), Some(f)) => g.move_to(f, pos, fingers, slop, keep),
+                                _ => false,
+                            };
+                            if stolen {
+                                self.cancel_touch(t.id, pos);
+                            }
 
-                                // still deliver pointer_move to captured widget if present
-                                if let Some(cid) = self.capture_id
+                            if let Some(f) = &self.frame_cache {
+                                // still deliver pointer_move to the widget this finger captured
+                                if let Some(cid) = self.captures.get(PointerId(t.id))
                                     && let Some(i) = rc::hit_index_by_id(f, cid)
                                     && let Some(cb) = &f.hit_regions[i].on_pointer_move
                                 {
-                                    cb(rc::pe_touch(
-                                        repose_core::input::PointerEventKind::Move,
-                                        pos,
-                                        self.modifiers,
+                                    cb(rc::pe_finger(
+                                        rc::pe_touch(
+                                            repose_core::input::PointerEventKind::Move,
+                                            pos,
+                                            self.modifiers,
+                                        ),
+                                        t.id,
                                     ));
                                 }
                             }
 
-                            self.prev_touch_px = Some(pos_px);
                             self.dirty = true;
                             self.request_redraw();
                         }
 
                         winit::event::TouchPhase::Ended | winit::event::TouchPhase::Cancelled => {
-                            if let (Some(f), Some(cid)) = (&self.frame_cache, self.capture_id) {
+                            let capture = self.captures.release(PointerId(t.id));
+                            if let (Some(f), Some(cid)) = (&self.frame_cache, capture) {
                                 if let Some(i) = rc::hit_index_by_id(f, cid) {
                                     let hit = &f.hit_regions[i];
 
                                     if let Some(cb) = &hit.on_pointer_up {
-                                        cb(rc::pe_up_primary(
-                                            repose_core::input::PointerKind::Touch,
-                                            pos,
-                                            self.modifiers,
+                                        cb(rc::pe_finger(
+                                            rc::pe_up_primary(
+                                                repose_core::input::PointerKind::Touch,
+                                                pos,
+                                                self.modifiers,
+                                            ),
+                                            t.id,
                                         ));
                                     }
 
-                                    // click only if we didn't scroll-drag
+                                    // A scroll that took the gesture released the capture, so
+                                    // there's no click after a scroll-drag.
                                     if t.phase == winit::event::TouchPhase::Ended
-                                        && !self.touch_scrolled
                                         && hit.rect.contains(pos)
                                         && let Some(cb) = &hit.on_click
                                     {
                                         cb();
                                     }
 
-                                    // end drag selection for textfields
+                                    // end drag selection for textfields; a tap or long
+                                    // press brings up the touch selection UI
                                     if self.is_textfield(cid) {
                                         let key = self.tf_key_of(cid);
-                                        if let Some(st) = self.textfield_states.get(&key) {
-                                            st.borrow_mut().end_drag();
+                                        if let Some(st) = self.retained.get::<TextFieldState>(key) {
+                                            let mut st = st.borrow_mut();
+                                            st.end_drag();
+                                            if t.phase == winit::event::TouchPhase::Ended
+                                                && let Some((at, before)) = self.tf_press.take()
+                                            {
+                                                st.touch_release(
+                                                    before,
+                                                    at.elapsed() >= LONG_PRESS,
+                                                );
+                                            }
                                         }
                                     }
                                 }
                             }
+                            self.tf_press = None;
+                            self.apply_text_edit_actions();
 
-                            self.capture_id = None;
-                            self.prev_touch_px = None;
-                            self.pressed_ids.clear();
+                            self.touches.remove(&t.id);
+                            repose_core::end_gesture(PointerId(t.id));
                             self.dirty = true;
                             self.request_redraw();
                         }
@@ -393,14 +510,34 @@ pub fn run_android_app_with_options(
                 }
 
                 // Basic keyboard support (hardware keyboards / Tab focus)
+                WindowEvent::Focused(false) => {
+                    // The release of a held key goes to whichever window has focus now.
+                    self.key_repeat.cancel();
+                }
                 WindowEvent::KeyboardInput {
-                    event: key_event, ..
+                    device_id,
+                    event: key_event,
+                    ..
                 } => {
+                    if !self.key_repeat.accept(device_id, &key_event) {
+                        return;
+                    }
+                    let app_event = rc::key_event_from_winit(&key_event, self.modifiers);
+                    if let Some(f) = &self.frame_cache
+                        && rc::dispatch_key(f, self.sched.focused, &app_event)
+                    {
+                        self.request_redraw();
+                        return;
+                    }
                     // Back key / Escape handling (optional)
                     if key_event.state == ElementState::Pressed && !key_event.repeat {
                         match key_event.physical_key {
                             PhysicalKey::Code(KeyCode::Escape)
                             | PhysicalKey::Code(KeyCode::BrowserBack) => {
+                                if self.sched.layers.dismiss_modal() {
+                                    self.request_redraw();
+                                    return;
+                                }
                                 // If you use repose_navigation::back on Android too, call it here.
                                 // use repose_navigation::back;
                                 // if !back::handle() { el.exit(); }
@@ -463,7 +600,7 @@ pub fn run_android_app_with_options(
                                 && let Some(on_submit) = &f.hit_regions[i].on_text_submit
                             {
                                 let key = self.tf_key_of(focused_id);
-                                if let Some(state) = self.textfield_states.get(&key) {
+                                if let Some(state) = self.retained.get::<TextFieldState>(key) {
                                     on_submit(state.borrow().text.clone());
                                     self.dirty = true;
                                     self.request_redraw();
@@ -472,13 +609,32 @@ pub fn run_android_app_with_options(
                             }
                         }
                     }
+
+                    // Line breaks and line navigation in a focused TextArea
+                    if key_event.state == ElementState::Pressed
+                        && let Some(fid) = self.sched.focused
+                        && let Some(state_rc) =
+                            self.retained.get::<TextFieldState>(self.tf_key_of(fid))
+                    {
+                        let mut st = state_rc.borrow_mut();
+                        if let Some(changed) =
+                            rc::text_area_key(&mut st, &key_event.logical_key, self.modifiers.shift)
+                        {
+                            if changed {
+                                self.notify_text_change(fid, st.text.clone());
+                            }
+                            self.dirty = true;
+                            self.request_redraw();
+                            return;
+                        }
+                    }
                 }
 
                 // IME (Preedit/Commit)
                 WindowEvent::Ime(ime) => {
                     if let Some(focused_id) = self.sched.focused {
                         let key = self.tf_key_of(focused_id);
-                        if let Some(state_rc) = self.textfield_states.get(&key) {
+                        if let Some(state_rc) = self.retained.get::<TextFieldState>(key) {
                             let mut state = state_rc.borrow_mut();
                             match ime {
                                 winit::event::Ime::Enabled => self.ime_preedit = false,
@@ -551,10 +707,25 @@ pub fn run_android_app_with_options(
                         return;
                     };
 
+                    let _span = tracing::info_span!("frame").entered();
+                    self.pacer.frame_at(web_time::Instant::now());
                     let scale = win.scale_factor() as f32;
                     let size_px_u32 = self.sched.size;
                     let focused = self.sched.focused;
 
+                    // The content rect shrinks around the status bar, navigation bar and
+                    // soft keyboard.
+                    let cr = self.app.content_rect();
+                    let visible = Rect {
+                        x: cr.left as f32,
+                        y: cr.top as f32,
+                        w: (cr.right - cr.left) as f32,
+                        h: (cr.bottom - cr.top) as f32,
+                    };
+                    if visible.w > 0.0 && visible.h > 0.0 {
+                        set_window_insets_default(self.insets.update(size_px_u32, visible, scale));
+                    }
+
                     let auto_root_scroll = self.options.auto_root_scroll;
                     let root_scroll = self.root_scroll.clone();
                     let root_fn = &mut self.root;
@@ -574,17 +745,26 @@ pub fn run_android_app_with_options(
                         scale,
                         size_px_u32,
                         None, // hover_id (no mouse on Android usually)
-                        &self.pressed_ids,
-                        &self.textfield_states,
+                        None, // pointer_px
+                        &self.captures.pressed(),
+                        &self.retained,
                         focused,
                     );
-
-                    backend.frame(&frame.scene, GlyphRasterConfig { px: 18.0 * scale });
+                    crate::crash::record_frame(&frame, &self.sched);
+
+                    let t_gpu = Instant::now();
+                    backend.set_power_saving(repose_core::power_saving());
+                    backend.frame(&frame.scene, GlyphRasterConfig { scale });
+                    let mut stats = frame.stats;
+                    stats.gpu_ms = t_gpu.elapsed().as_secs_f32() * 1000.0;
+                    self.sched.jank.record(stats);
+                    profiling::finish_frame!();
                     self.frame_cache = Some(frame);
 
                     self.dirty = false;
 
-                    if self.options.continuous_redraw {
+                    // While saving power `about_to_wait` paces the next one.
+                    if self.options.continuous_redraw && repose_core::frame_interval().is_none() {
                         win.request_redraw();
                     }
                 }
@@ -593,15 +773,79 @@ pub fn run_android_app_with_options(
             }
         }
 
-        fn about_to_wait(&mut self, _el: &winit::event_loop::ActiveEventLoop) {
-            // Only redraw if needed (unless continuous_redraw is enabled).
-            if self.options.continuous_redraw || self.dirty {
+        fn about_to_wait(&mut self, el: &winit::event_loop::ActiveEventLoop) {
+            use winit::event_loop::ControlFlow;
+
+            if let Some((device_id, event)) = self.key_repeat.due(web_time::Instant::now())
+                && let Some(id) = self.window.as_ref().map(|w| w.id())
+            {
+                self.window_event(
+                    el,
+                    id,
+                    WindowEvent::KeyboardInput {
+                        device_id,
+                        event,
+                        is_synthetic: false,
+                    },
+                );
+            }
+
+            let now = web_time::Instant::now();
+            self.battery.poll(now);
+
+            // Only redraw if needed (unless continuous_redraw is enabled); queued snapshots,
+            // bus events, published diagnostics and woken tasks need one too.
+            if self.dirty
+                || repose_core::snapshots_pending()
+                || repose_core::diagnostics_pending()
+                || repose_core::events_pending()
+                || repose_core::tasks_pending()
+            {
                 self.request_redraw();
+                return;
+            }
+            // Animation frames (continuous redraw, `with_frame_nanos` callbacks) are spaced out
+            // while saving power.
+            if self.options.continuous_redraw || repose_core::frame_requested() {
+                match self.pacer.wait_until(now, repose_core::frame_interval()) {
+                    None => {
+                        el.set_control_flow(ControlFlow::Wait);
+                        self.request_redraw();
+                    }
+                    Some(at) => {
+                        let at = self.key_repeat.deadline().map_or(at, |r| r.min(at));
+                        el.set_control_flow(ControlFlow::WaitUntil(at));
+                    }
+                }
+                return;
+            }
+            // Otherwise sleep, waking only to blink the focused TextField's caret or repeat a
+            // held key.
+            let focused = self
+                .sched
+                .focused
+                .and_then(|id| self.retained.get::<TextFieldState>(self.tf_key_of(id)));
+            let repeat_at = self.key_repeat.deadline();
+            let wake = self.caret_blink.poll(
+                focused.as_ref().map(|s| s.borrow()).as_deref(),
+                web_time::Instant::now(),
+            );
+            match wake {
+                rc::BlinkWake::Redraw => {
+                    el.set_control_flow(ControlFlow::Wait);
+                    self.request_redraw();
+                }
+                rc::BlinkWake::WaitUntil(at) => {
+                    el.set_control_flow(ControlFlow::WaitUntil(repeat_at.map_or(at, |r| r.min(at))))
+                }
+                rc::BlinkWake::Idle => {
+                    el.set_control_flow(repeat_at.map_or(ControlFlow::Wait, ControlFlow::WaitUntil))
+                }
             }
         }
     }
 
-    let mut app_state = AppState::new(Box::new(root), options);
+    let mut app_state = AppState::new(app, Box::new(root), options);
     event_loop.run_app(&mut app_state)?;
     Ok(())
 }
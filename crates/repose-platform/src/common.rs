@@ -1,5 +1,7 @@
 use crate::*;
-use repose_core::input::{PointerButton, PointerEvent, PointerEventKind, PointerId, PointerKind};
+use repose_core::input::{
+    KeyEvent, PointerButton, PointerEvent, PointerEventKind, PointerId, PointerKind,
+};
 
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -19,9 +21,13 @@ pub(crate) fn hit_index_by_id(frame: &Frame, id: u64) -> Option<usize> {
     frame.hit_regions.iter().position(|h| h.id == id)
 }
 
+/// The id mouse events carry; touch points use their finger id.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub(crate) const MOUSE_POINTER: PointerId = PointerId(0);
+
 pub(crate) fn pe_mouse(event: PointerEventKind, pos: Vec2, mods: Modifiers) -> PointerEvent {
     PointerEvent {
-        id: PointerId(0),
+        id: MOUSE_POINTER,
         kind: PointerKind::Mouse,
         event,
         position: pos,
@@ -63,6 +69,251 @@ pub(crate) fn pe_up_primary(kind: PointerKind, pos: Vec2, mods: Modifiers) -> Po
     }
 }
 
+#[cfg(any(target_os = "android", target_arch = "wasm32", test))]
+pub(crate) use android_web::*;
+
+/// Pieces only the Android and web runners use: multi-touch pointers, and the timers
+/// and insets a runner needs when it doesn't redraw every frame.
+// Built for the host's tests too, which only exercise part of it.
+#[cfg(any(target_os = "android", target_arch = "wasm32", test))]
+#[cfg_attr(test, allow(dead_code))]
+mod android_web {
+    use super::*;
+    use std::collections::{HashMap, HashSet};
+
+    /// `pe` as coming from touch point `finger`, so multi-touch handlers can tell fingers apart.
+    pub(crate) fn pe_finger(pe: PointerEvent, finger: u64) -> PointerEvent {
+        PointerEvent {
+            id: PointerId(finger),
+            ..pe
+        }
+    }
+
+    /// The view each pointer pressed, which gets that pointer's moves and release. Keeping it
+    /// per pointer lets two fingers hold two different views (or drag two sliders) at once.
+    #[derive(Default)]
+    pub(crate) struct PointerCaptures {
+        capture: HashMap<PointerId, u64>,
+    }
+
+    impl PointerCaptures {
+        pub(crate) fn capture(&mut self, pointer: PointerId, view: u64) {
+            self.capture.insert(pointer, view);
+        }
+
+        pub(crate) fn get(&self, pointer: PointerId) -> Option<u64> {
+            self.capture.get(&pointer).copied()
+        }
+
+        /// Ends `pointer`'s capture, returning the view it held.
+        pub(crate) fn release(&mut self, pointer: PointerId) -> Option<u64> {
+            self.capture.remove(&pointer)
+        }
+
+        /// Views held down by at least one pointer, for their pressed visuals.
+        pub(crate) fn pressed(&self) -> HashSet<u64> {
+            self.capture.values().copied().collect()
+        }
+    }
+
+    /// Who a touch gesture belongs to.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub(crate) enum GestureOwner {
+        /// Hasn't moved past the touch slop; moves go to the captured view.
+        Undecided,
+        /// A scroll container under the finger took it over.
+        Scroll,
+        /// The captured view keeps it and nothing scrolls.
+        Captured,
+    }
+
+    /// One finger's gesture, arbitrated between the view it pressed and the scroll containers
+    /// under it.
+    pub(crate) struct TouchGesture {
+        start: Vec2,
+        last: Vec2,
+        pub(crate) owner: GestureOwner,
+    }
+
+    impl TouchGesture {
+        /// A finger down at `at`. With no view captured, scroll containers have it from the start.
+        pub(crate) fn new(at: Vec2, captured: bool) -> Self {
+            Self {
+                start: at,
+                last: at,
+                owner: if captured {
+                    GestureOwner::Undecided
+                } else {
+                    GestureOwner::Scroll
+                },
+            }
+        }
+
+        /// Moves the finger to `pos`, scrolling if the gesture is a scroll. With `fingers` down,
+        /// each scrolls by its share, so fingers moving together scroll like one and a pinch
+        /// doesn't scroll at all.
+        ///
+        /// Once the finger is more than `slop` from where it landed, the gesture goes to a
+        /// scroll container that can scroll the way the finger set out (catching up on the
+        /// movement so far), unless the captured view asked to `keep` it. Returns true when the
+        /// gesture was just taken that way; the captured view should then get a `Cancel`.
+        pub(crate) fn move_to(
+            &mut self,
+            frame: &Frame,
+            pos: Vec2,
+            fingers: usize,
+            slop: f32,
+            keep: bool,
+        ) -> bool {
+            let share = 1.0 / fingers.max(1) as f32;
+            let delta = Vec2 {
+                x: (pos.x - self.last.x) * share,
+                y: (pos.y - self.last.y) * share,
+            };
+            self.last = pos;
+
+            match self.owner {
+                GestureOwner::Scroll => {
+                    dispatch_scroll(
+                        frame,
+                        pos,
+                        Vec2 {
+                            x: -delta.x,
+                            y: -delta.y,
+                        },
+                    );
+                    false
+                }
+                GestureOwner::Captured => false,
+                GestureOwner::Undecided => {
+                    let moved = Vec2 {
+                        x: pos.x - self.start.x,
+                        y: pos.y - self.start.y,
+                    };
+                    if moved.x.hypot(moved.y) <= slop {
+                        return false;
+                    }
+                    if keep {
+                        self.owner = GestureOwner::Captured;
+                        return false;
+                    }
+                    let along = if moved.y.abs() >= moved.x.abs() {
+                        Vec2 {
+                            x: 0.0,
+                            y: -moved.y * share,
+                        }
+                    } else {
+                        Vec2 {
+                            x: -moved.x * share,
+                            y: 0.0,
+                        }
+                    };
+                    if dispatch_scroll(frame, self.start, along) {
+                        self.owner = GestureOwner::Scroll;
+                        true
+                    } else {
+                        self.owner = GestureOwner::Captured;
+                        false
+                    }
+                }
+            }
+        }
+    }
+
+    /// Derives `WindowInsets` from the part of the window the platform says is visible.
+    ///
+    /// Android and mobile browsers only report one visible rect, so the smallest bottom cover
+    /// seen at the current window size is taken to be the navigation bar, and anything taller
+    /// is the keyboard.
+    #[derive(Default)]
+    pub(crate) struct InsetsTracker {
+        size: (u32, u32),
+        bars_bottom_px: Option<f32>,
+    }
+
+    impl InsetsTracker {
+        /// `visible` is in window px; the result is in dp.
+        pub(crate) fn update(
+            &mut self,
+            size: (u32, u32),
+            visible: Rect,
+            scale: f32,
+        ) -> WindowInsets {
+            if size != self.size {
+                self.size = size;
+                self.bars_bottom_px = None;
+            }
+            let (w, h) = (size.0 as f32, size.1 as f32);
+            let bottom = (h - (visible.y + visible.h)).max(0.0);
+            let bars = self.bars_bottom_px.map_or(bottom, |b| b.min(bottom));
+            self.bars_bottom_px = Some(bars);
+
+            let dp = |px: f32| px.max(0.0) / scale.max(f32::EPSILON);
+            WindowInsets {
+                system_bars: PaddingValues {
+                    left: dp(visible.x),
+                    right: dp(w - (visible.x + visible.w)),
+                    top: dp(visible.y),
+                    bottom: dp(bars),
+                },
+                ime: PaddingValues {
+                    // A pixel of slack for rounding in the reported rect.
+                    bottom: if bottom > bars + 1.0 { dp(bottom) } else { 0.0 },
+                    ..Default::default()
+                },
+            }
+        }
+    }
+}
+
+/// What a runner that doesn't redraw continuously should do about the caret blink.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum BlinkWake {
+    /// The caret flipped since the last frame; repaint it.
+    Redraw,
+    /// Sleep until the next flip.
+    WaitUntil(web_time::Instant),
+    /// No focused text field, nothing to schedule.
+    Idle,
+}
+
+/// Schedules caret blink repaints for the focused `TextField`, so idle runners only wake up
+/// twice a second while something is being edited. Every edit or caret move restarts the
+/// phase (`TextFieldState::reset_caret_blink`), which moves the deadline with it.
+#[derive(Default)]
+pub(crate) struct CaretBlinkTimer {
+    // (blink_start the deadline was computed from, deadline)
+    deadline: Option<(web_time::Instant, web_time::Instant)>,
+}
+
+impl CaretBlinkTimer {
+    pub(crate) fn poll(
+        &mut self,
+        focused: Option<&repose_ui::TextFieldState>,
+        now: web_time::Instant,
+    ) -> BlinkWake {
+        let Some(st) = focused else {
+            self.deadline = None;
+            return BlinkWake::Idle;
+        };
+        match self.deadline {
+            Some((start, at)) if start == st.blink_start => {
+                if now >= at {
+                    self.deadline = None;
+                    BlinkWake::Redraw
+                } else {
+                    BlinkWake::WaitUntil(at)
+                }
+            }
+            _ => {
+                let at = st.next_caret_toggle(now);
+                self.deadline = Some((st.blink_start, at));
+                BlinkWake::WaitUntil(at)
+            }
+        }
+    }
+}
+
 /// Dispatch wheel/touch-scroll to the top-most scroll consumer under `pos`.
 /// Returns `true` if something consumed the scroll.
 pub(crate) fn dispatch_scroll(frame: &Frame, pos: Vec2, delta: Vec2) -> bool {
@@ -85,6 +336,183 @@ pub(crate) fn dispatch_scroll(frame: &Frame, pos: Vec2, delta: Vec2) -> bool {
     false
 }
 
+/// Wheel scrolling eased over a few frames, for `ScrollBehavior::smooth`.
+#[derive(Default)]
+pub(crate) struct SmoothWheel {
+    /// Distance still to scroll, in px.
+    pending: Vec2,
+    /// Where the wheel was turned; what's under it scrolls.
+    at: Vec2,
+    last: Option<web_time::Instant>,
+}
+
+impl SmoothWheel {
+    /// Adds a notch's worth of scrolling to what's still pending.
+    pub(crate) fn add(&mut self, delta: Vec2, at: Vec2, now: web_time::Instant) {
+        if !self.is_active() {
+            self.last = Some(now);
+        }
+        self.pending.x += delta.x;
+        self.pending.y += delta.y;
+        self.at = at;
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        self.pending.x != 0.0 || self.pending.y != 0.0
+    }
+
+    /// The part of the pending distance to scroll this frame, and where. Most of it is gone
+    /// after `duration`, fast at first and slowing down.
+    pub(crate) fn step(
+        &mut self,
+        now: web_time::Instant,
+        duration: std::time::Duration,
+    ) -> Option<(Vec2, Vec2)> {
+        if !self.is_active() {
+            return None;
+        }
+        let dt = self.last.map_or(0.0, |t| (now - t).as_secs_f32());
+        self.last = Some(now);
+        let k = if duration.is_zero() {
+            1.0
+        } else {
+            1.0 - (-4.0 * dt / duration.as_secs_f32()).exp()
+        };
+        let mut step = Vec2 {
+            x: self.pending.x * k,
+            y: self.pending.y * k,
+        };
+        // Finish rather than crawl through the last fraction of a pixel.
+        if (self.pending.x - step.x).abs() < 0.5 && (self.pending.y - step.y).abs() < 0.5 {
+            step = self.pending;
+        }
+        self.pending.x -= step.x;
+        self.pending.y -= step.y;
+        Some((step, self.at))
+    }
+}
+
+/// Counts quick successive presses at about the same spot, for double and triple click.
+#[derive(Default)]
+pub(crate) struct ClickCounter {
+    last: Option<(web_time::Instant, Vec2, u64)>,
+    count: u32,
+}
+
+impl ClickCounter {
+    /// Longest gap between presses that still counts as a repeat.
+    const INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+    /// How far (px) the pointer may move between repeated presses.
+    const SLOP_PX: f32 = 4.0;
+
+    /// Records a press on `id` and returns which click of a run it is: 1, 2, 3, then back
+    /// to 1.
+    pub(crate) fn press(&mut self, id: u64, at: Vec2, now: web_time::Instant) -> u32 {
+        let repeat = self.last.is_some_and(|(t, p, last_id)| {
+            last_id == id
+                && now.saturating_duration_since(t) <= Self::INTERVAL
+                && (at.x - p.x).abs() <= Self::SLOP_PX
+                && (at.y - p.y).abs() <= Self::SLOP_PX
+        });
+        self.count = if repeat { self.count % 3 + 1 } else { 1 };
+        self.last = Some((now, at, id));
+        self.count
+    }
+}
+
+/// Spaces out frames that nothing but animations asked for, per `repose_core::frame_interval`.
+#[derive(Default)]
+pub(crate) struct FramePacer {
+    last: Option<web_time::Instant>,
+}
+
+impl FramePacer {
+    /// Records that a frame was produced at `now`.
+    pub(crate) fn frame_at(&mut self, now: web_time::Instant) {
+        self.last = Some(now);
+    }
+
+    /// When the next animation frame may be produced, or `None` if it may be right away.
+    pub(crate) fn wait_until(
+        &self,
+        now: web_time::Instant,
+        interval: Option<std::time::Duration>,
+    ) -> Option<web_time::Instant> {
+        let at = self.last? + interval?;
+        (at > now).then_some(at)
+    }
+}
+
+/// Polls the OS for whether the device runs on battery and reports it to
+/// `repose_core::set_on_battery`. Browsers don't say; web apps rely on `set_power_mode`.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+#[derive(Default)]
+pub(crate) struct BatteryWatch {
+    next: Option<web_time::Instant>,
+}
+
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+impl BatteryWatch {
+    const PERIOD: std::time::Duration = std::time::Duration::from_secs(30);
+
+    pub(crate) fn poll(&mut self, now: web_time::Instant) {
+        if self.next.is_some_and(|at| now < at) {
+            return;
+        }
+        self.next = Some(now + Self::PERIOD);
+        repose_core::set_on_battery(on_battery());
+    }
+}
+
+/// Whether the device runs on battery, read from `/sys/class/power_supply`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn on_battery() -> Option<bool> {
+    let supplies = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    let read = |dir: &std::path::Path, name: &str| {
+        std::fs::read_to_string(dir.join(name))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    };
+    let supplies: Vec<(String, String)> = supplies
+        .flatten()
+        .map(|e| {
+            let dir = e.path();
+            let kind = read(&dir, "type");
+            let state = if kind == "Battery" {
+                read(&dir, "status")
+            } else {
+                read(&dir, "online")
+            };
+            (kind, state)
+        })
+        .collect();
+    on_battery_from(&supplies)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+fn on_battery() -> Option<bool> {
+    None
+}
+
+/// `supplies` are `(type, status)` for batteries and `(type, online)` for everything else.
+/// Any supply reporting external power wins; a machine without a battery is on mains.
+#[cfg_attr(not(any(target_os = "linux", target_os = "android")), allow(dead_code))]
+fn on_battery_from(supplies: &[(String, String)]) -> Option<bool> {
+    let external = supplies
+        .iter()
+        .any(|(kind, state)| kind != "Battery" && state == "1");
+    let discharging = supplies
+        .iter()
+        .any(|(kind, state)| kind == "Battery" && state == "Discharging");
+    match (external, discharging) {
+        (true, _) => Some(false),
+        (false, true) => Some(true),
+        _ if supplies.is_empty() => None,
+        _ => Some(false),
+    }
+}
+
 /// Shared state for runner-provided "auto root scroll".
 #[derive(Default)]
 pub(crate) struct RootScrollState {
@@ -102,6 +530,7 @@ impl RootScrollState {
 
 /// Wrap any `child` view in a vertical scroll container backed by `RootScrollState`.
 /// This is how the runner can provide "overflow-y: auto" semantics for the whole app.
+#[allow(deprecated)]
 pub(crate) fn wrap_root_scroll(child: View, st: Rc<RefCell<RootScrollState>>) -> View {
     let st_get = st.clone();
     let get_scroll_offset = Some(Rc::new(move || st_get.borrow().offset_y) as Rc<dyn Fn() -> f32>);
@@ -158,3 +587,551 @@ pub(crate) fn wrap_root_scroll(child: View, st: Rc<RefCell<RootScrollState>>) ->
     .modifier(Modifier::new().fill_max_size())
     .with_children(vec![child])
 }
+
+/// Offers `event` to the app's `on_key_event` handlers before the runner's own handling:
+/// the focused view's, or with nothing focused, every handler from the top down until one
+/// consumes it. Returns whether one did.
+pub(crate) fn dispatch_key(frame: &Frame, focused: Option<u64>, event: &KeyEvent) -> bool {
+    let mut handlers = frame
+        .hit_regions
+        .iter()
+        .rev()
+        .filter(|h| focused.is_none_or(|id| h.id == id))
+        .filter_map(|h| h.on_key_event.as_ref());
+    handlers.any(|f| f(event.clone()))
+}
+
+/// Px size runners measure field text at; matches what `layout_and_paint` draws.
+pub(crate) fn tf_font_px() -> f32 {
+    dp_to_px(TF_FONT_DP) * repose_core::locals::text_scale().0
+}
+
+/// Where in a `TextArea` whose hit rect is `field` the window point `pos` lands.
+pub(crate) fn text_area_index(
+    state: &repose_ui::TextFieldState,
+    field: Rect,
+    pos: Vec2,
+) -> (usize, repose_ui::textfield::CaretAffinity) {
+    state.index_for_point(
+        pos.x - field.x - dp_to_px(TF_PADDING_X_DP),
+        pos.y - field.y - dp_to_px(repose_ui::textfield::TF_PADDING_Y_DP),
+        tf_font_px(),
+    )
+}
+
+/// Seeds the default `Locale` from the OS (`LANG`, user settings, `navigator.language`).
+pub(crate) fn init_locale_from_os() {
+    if let Some(l) = sys_locale::get_locale().as_deref().and_then(Locale::parse) {
+        log::debug!("OS locale: {l}");
+        set_locale_default(l);
+    }
+}
+
+/// Connects the profiler picked with the `profile-with-*` features. Tracy is started here;
+/// puffin only records, and the app serves it (`puffin_http`) to a viewer.
+pub(crate) fn start_profiler() {
+    #[cfg(feature = "profile-with-tracy")]
+    profiling::tracy_client::Client::start();
+    #[cfg(feature = "profile-with-puffin")]
+    profiling::puffin::set_scopes_on(true);
+    profiling::register_thread!("ui");
+}
+
+// The helpers that take winit types; winit is only linked in with a runner.
+#[cfg(any(feature = "desktop", feature = "android", target_arch = "wasm32"))]
+pub(crate) use winit_input::*;
+
+#[cfg(any(feature = "desktop", feature = "android", target_arch = "wasm32"))]
+mod winit_input {
+    use super::*;
+    use repose_core::input::{Key, KeyLocation, KeyState};
+
+    /// Runs `key` through the multi-line handling of a focused `TextArea` (see
+    /// `TextFieldState::multiline_key`). `None` when it's not one of the keys it takes, or the
+    /// field is single-line.
+    pub(crate) fn text_area_key(
+        state: &mut repose_ui::TextFieldState,
+        key: &winit::keyboard::Key,
+        shift: bool,
+    ) -> Option<bool> {
+        state.multiline_key(&key_from_winit(key), shift, tf_font_px())
+    }
+
+    /// How far a wheel or touchpad `delta` scrolls the content, in px, per the platform's
+    /// `ScrollBehavior`. `scale` is the window's scale factor.
+    pub(crate) fn wheel_delta_px(delta: winit::event::MouseScrollDelta, scale: f32) -> Vec2 {
+        let settings = repose_core::locals::platform_settings();
+        let (x, y) = match delta {
+            winit::event::MouseScrollDelta::LineDelta(x, y) => {
+                let line_px = settings.scroll_line_dp * scale * settings.scroll.line_multiplier;
+                (x * line_px, y * line_px)
+            }
+            winit::event::MouseScrollDelta::PixelDelta(p) => {
+                let m = settings.scroll.pixel_multiplier;
+                (p.x as f32 * m, p.y as f32 * m)
+            }
+        };
+        Vec2 { x: -x, y: -y }
+    }
+
+    /// The app-facing logical key for a winit key.
+    pub(crate) fn key_from_winit(key: &winit::keyboard::Key) -> Key {
+        use winit::keyboard::{Key as WKey, NamedKey as N};
+        match key {
+            WKey::Character(s) => {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Key::Character(c),
+                    _ => Key::Other(s.to_string()),
+                }
+            }
+            WKey::Dead(c) => Key::Dead(*c),
+            WKey::Unidentified(_) => Key::Unidentified,
+            WKey::Named(named) => match named {
+                N::Enter => Key::Enter,
+                N::Tab => Key::Tab,
+                N::Space => Key::Space,
+                N::Backspace => Key::Backspace,
+                N::Delete => Key::Delete,
+                N::Insert => Key::Insert,
+                N::Escape => Key::Escape,
+                N::ArrowLeft => Key::ArrowLeft,
+                N::ArrowRight => Key::ArrowRight,
+                N::ArrowUp => Key::ArrowUp,
+                N::ArrowDown => Key::ArrowDown,
+                N::Home => Key::Home,
+                N::End => Key::End,
+                N::PageUp => Key::PageUp,
+                N::PageDown => Key::PageDown,
+                N::Shift => Key::Shift,
+                N::Control => Key::Control,
+                N::Alt | N::AltGraph => Key::Alt,
+                N::Super | N::Meta => Key::Meta,
+                N::CapsLock => Key::CapsLock,
+                N::NumLock => Key::NumLock,
+                N::ScrollLock => Key::ScrollLock,
+                N::PrintScreen => Key::PrintScreen,
+                N::Pause => Key::Pause,
+                N::ContextMenu => Key::ContextMenu,
+                N::MediaPlayPause => Key::MediaPlayPause,
+                N::MediaPlay => Key::MediaPlay,
+                N::MediaPause => Key::MediaPause,
+                N::MediaStop => Key::MediaStop,
+                N::MediaTrackNext => Key::MediaTrackNext,
+                N::MediaTrackPrevious => Key::MediaTrackPrevious,
+                N::AudioVolumeUp => Key::AudioVolumeUp,
+                N::AudioVolumeDown => Key::AudioVolumeDown,
+                N::AudioVolumeMute => Key::AudioVolumeMute,
+                N::BrowserBack => Key::BrowserBack,
+                N::BrowserForward => Key::BrowserForward,
+                other => {
+                    // F1..F35 and everything else go by their winit name.
+                    let name = format!("{other:?}");
+                    match name.strip_prefix('F').and_then(|n| n.parse().ok()) {
+                        Some(n) => Key::F(n),
+                        None => Key::Other(name),
+                    }
+                }
+            },
+        }
+    }
+
+    pub(crate) fn key_event_from_winit(
+        e: &winit::event::KeyEvent,
+        modifiers: Modifiers,
+    ) -> KeyEvent {
+        use winit::keyboard::KeyLocation as L;
+        KeyEvent {
+            key: key_from_winit(&e.logical_key),
+            location: match e.location {
+                L::Standard => KeyLocation::Standard,
+                L::Left => KeyLocation::Left,
+                L::Right => KeyLocation::Right,
+                L::Numpad => KeyLocation::Numpad,
+            },
+            state: match e.state {
+                winit::event::ElementState::Pressed => KeyState::Pressed,
+                winit::event::ElementState::Released => KeyState::Released,
+            },
+            modifiers,
+            is_repeat: e.repeat,
+            text: e.text.as_ref().map(|t| t.to_string()),
+        }
+    }
+
+    /// Key auto-repeat driven by the runner, timed by `PlatformSettings` rather than the OS.
+    ///
+    /// OS repeats are dropped: some platforms only repeat keys that produce text, or stop
+    /// repeating navigation keys. Instead the last non-modifier key pressed is replayed as a
+    /// `repeat` event after `key_repeat_delay`, then every `key_repeat_interval`, until it is
+    /// released or the window loses focus.
+    ///
+    /// `T` is what gets replayed; runners use the winit event and its device.
+    pub(crate) struct KeyRepeat<T = (winit::event::DeviceId, winit::event::KeyEvent)> {
+        held: Option<HeldKey<T>>,
+        // Set by `due` so the replayed event gets through `accept`.
+        replaying: bool,
+    }
+
+    impl<T> Default for KeyRepeat<T> {
+        fn default() -> Self {
+            Self {
+                held: None,
+                replaying: false,
+            }
+        }
+    }
+
+    struct HeldKey<T> {
+        key: winit::keyboard::PhysicalKey,
+        replay: T,
+        next: web_time::Instant,
+    }
+
+    fn is_modifier(key: winit::keyboard::PhysicalKey) -> bool {
+        use winit::keyboard::{KeyCode, PhysicalKey};
+        matches!(
+            key,
+            PhysicalKey::Code(
+                KeyCode::ShiftLeft
+                    | KeyCode::ShiftRight
+                    | KeyCode::ControlLeft
+                    | KeyCode::ControlRight
+                    | KeyCode::AltLeft
+                    | KeyCode::AltRight
+                    | KeyCode::SuperLeft
+                    | KeyCode::SuperRight
+                    | KeyCode::Meta
+                    | KeyCode::Hyper
+                    | KeyCode::Fn
+                    | KeyCode::FnLock
+                    | KeyCode::CapsLock
+                    | KeyCode::NumLock
+                    | KeyCode::ScrollLock
+            )
+        )
+    }
+
+    impl KeyRepeat {
+        /// Whether the runner should handle `event`; tracks the held key as a side effect.
+        pub(crate) fn accept(
+            &mut self,
+            device_id: winit::event::DeviceId,
+            event: &winit::event::KeyEvent,
+        ) -> bool {
+            let now = web_time::Instant::now();
+            self.track(event.physical_key, event.state, event.repeat, now, || {
+                let mut replay = event.clone();
+                replay.repeat = true;
+                (device_id, replay)
+            })
+        }
+    }
+
+    impl<T: Clone> KeyRepeat<T> {
+        /// [`accept`](KeyRepeat::accept) for a key event at `now`; `replay` is what [`due`](Self::due)
+        /// hands back while the key is held.
+        pub(crate) fn track(
+            &mut self,
+            key: winit::keyboard::PhysicalKey,
+            state: winit::event::ElementState,
+            repeat: bool,
+            now: web_time::Instant,
+            replay: impl FnOnce() -> T,
+        ) -> bool {
+            if repeat {
+                return std::mem::take(&mut self.replaying);
+            }
+            match state {
+                winit::event::ElementState::Pressed if !is_modifier(key) => {
+                    let delay = repose_core::locals::platform_settings().key_repeat_delay;
+                    self.held = Some(HeldKey {
+                        key,
+                        replay: replay(),
+                        next: now + delay,
+                    });
+                }
+                winit::event::ElementState::Released
+                    if self.held.as_ref().is_some_and(|h| h.key == key) =>
+                {
+                    self.held = None;
+                }
+                _ => {}
+            }
+            true
+        }
+
+        /// Stops repeating, e.g. when the window loses focus and the release won't arrive.
+        pub(crate) fn cancel(&mut self) {
+            self.held = None;
+        }
+
+        /// The repeat event to dispatch now, if one is due. A stalled loop catches up with a
+        /// single repeat, not a burst.
+        pub(crate) fn due(&mut self, now: web_time::Instant) -> Option<T> {
+            let held = self.held.as_mut().filter(|h| now >= h.next)?;
+            held.next = now + repose_core::locals::platform_settings().key_repeat_interval;
+            self.replaying = true;
+            Some(held.replay.clone())
+        }
+
+        /// When the next repeat is due, for runners that sleep between events.
+        pub(crate) fn deadline(&self) -> Option<web_time::Instant> {
+            self.held.as_ref().map(|h| h.next)
+        }
+    }
+
+    /// Wakes the event loop when a background `Snapshot` is queued, so idle runners produce the
+    /// frame that applies it.
+    pub(crate) fn install_snapshot_waker(event_loop: &winit::event_loop::EventLoop<()>) {
+        let proxy = std::sync::Mutex::new(event_loop.create_proxy());
+        repose_core::set_snapshot_waker(move || {
+            if let Ok(p) = proxy.lock() {
+                let _ = p.send_event(());
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use repose_core::input::{Key, KeyLocation, KeyState};
+    use std::cell::Cell;
+    use std::collections::HashSet;
+
+    #[test]
+    #[cfg(any(feature = "desktop", feature = "android", target_arch = "wasm32"))]
+    fn winit_keys_map_to_logical_keys() {
+        use winit::keyboard::{Key as WKey, NamedKey};
+
+        assert_eq!(key_from_winit(&WKey::Named(NamedKey::F11)), Key::F(11));
+        assert_eq!(
+            key_from_winit(&WKey::Named(NamedKey::MediaPlayPause)),
+            Key::MediaPlayPause
+        );
+        assert_eq!(
+            key_from_winit(&WKey::Character("7".into())),
+            Key::Character('7')
+        );
+        assert_eq!(
+            key_from_winit(&WKey::Named(NamedKey::LaunchMail)),
+            Key::Other("LaunchMail".into())
+        );
+    }
+
+    #[test]
+    fn keys_reach_the_focused_handler() {
+        let got = Rc::new(Cell::new(0u64));
+        let handler = |id: u64, consume: bool| {
+            let got = got.clone();
+            HitRegion::new(id, Rect::default()).on_key_event(move |_| {
+                got.set(id);
+                consume
+            })
+        };
+        let frame = Frame::new(Scene::default())
+            .with_hit_regions(vec![handler(1, true), handler(2, false)]);
+        let ev = KeyEvent {
+            key: Key::F(5),
+            location: KeyLocation::Standard,
+            state: KeyState::Pressed,
+            modifiers: Modifiers::default(),
+            is_repeat: false,
+            text: None,
+        };
+
+        assert!(!dispatch_key(&frame, Some(2), &ev));
+        assert_eq!(got.get(), 2);
+        // Nothing focused: topmost first, falling through until one consumes it.
+        assert!(dispatch_key(&frame, None, &ev));
+        assert_eq!(got.get(), 1);
+    }
+
+    #[test]
+    #[cfg(any(feature = "desktop", feature = "android", target_arch = "wasm32"))]
+    fn held_keys_repeat_on_the_settings_timing_not_the_os() {
+        use winit::event::ElementState::{Pressed, Released};
+        use winit::keyboard::{KeyCode, PhysicalKey};
+
+        let s = repose_core::locals::platform_settings();
+        let ms = |n| web_time::Duration::from_millis(n);
+        let t0 = web_time::Instant::now();
+        let a = PhysicalKey::Code(KeyCode::KeyA);
+        let mut r = KeyRepeat::<&str>::default();
+
+        assert!(r.track(a, Pressed, false, t0, || "a"));
+        assert_eq!(r.deadline(), Some(t0 + s.key_repeat_delay));
+        // The OS's own repeats never get through.
+        assert!(!r.track(a, Pressed, true, t0 + ms(40), || "a"));
+        assert_eq!(r.due(t0 + s.key_repeat_delay - ms(1)), None);
+
+        let t1 = t0 + s.key_repeat_delay;
+        assert_eq!(r.due(t1), Some("a"));
+        // The replayed event does, once.
+        assert!(r.track(a, Pressed, true, t1, || "a"));
+        assert!(!r.track(a, Pressed, true, t1, || "a"));
+        assert_eq!(r.due(t1 + s.key_repeat_interval - ms(1)), None);
+        // A stalled loop gets one repeat, then waits a full interval again.
+        let late = t1 + s.key_repeat_interval * 10;
+        assert_eq!(r.due(late), Some("a"));
+        assert_eq!(r.due(late), None);
+
+        // Modifiers don't take over the repeat; releasing the key ends it.
+        let shift = PhysicalKey::Code(KeyCode::ShiftLeft);
+        assert!(r.track(shift, Pressed, false, late, || "shift"));
+        assert_eq!(r.due(late + s.key_repeat_interval), Some("a"));
+        assert!(r.track(a, Released, false, late, || "a"));
+        assert_eq!(r.deadline(), None);
+        assert_eq!(r.due(late + s.key_repeat_delay * 2), None);
+    }
+
+    #[test]
+    fn each_pointer_keeps_its_own_capture() {
+        let mut c = PointerCaptures::default();
+        c.capture(PointerId(1), 10);
+        c.capture(PointerId(2), 20);
+        assert_eq!(c.pressed(), HashSet::from([10, 20]));
+
+        // Lifting one finger leaves the other's view held.
+        assert_eq!(c.release(PointerId(1)), Some(10));
+        assert_eq!(c.get(PointerId(2)), Some(20));
+        assert_eq!(c.pressed(), HashSet::from([20]));
+        assert_eq!(c.release(PointerId(1)), None);
+    }
+
+    #[test]
+    fn scroll_takes_a_touch_only_along_its_axis_and_past_the_slop() {
+        // A vertical scroller under a draggable child.
+        let scrolled = Rc::new(Cell::new(0.0f32));
+        let s = scrolled.clone();
+        let frame = Frame::new(Scene::default()).with_hit_regions(vec![
+            HitRegion::new(
+                1,
+                Rect {
+                    x: 0.0,
+                    y: 0.0,
+                    w: 400.0,
+                    h: 400.0,
+                },
+            )
+            .on_scroll(move |d| {
+                s.set(s.get() + d.y);
+                Vec2 { x: d.x, y: 0.0 }
+            }),
+        ]);
+        let at = |x, y| Vec2 { x, y };
+
+        let mut g = TouchGesture::new(at(100.0, 100.0), true);
+        assert!(!g.move_to(&frame, at(100.0, 104.0), 1, 8.0, false));
+        assert_eq!((g.owner, scrolled.get()), (GestureOwner::Undecided, 0.0));
+        // Past the slop, vertically: taken, with the movement so far scrolled.
+        assert!(g.move_to(&frame, at(101.0, 120.0), 1, 8.0, false));
+        assert_eq!((g.owner, scrolled.get()), (GestureOwner::Scroll, -20.0));
+        assert!(!g.move_to(&frame, at(101.0, 130.0), 1, 8.0, false));
+        assert_eq!(scrolled.get(), -30.0);
+
+        // Sideways, the scroller can't use it: the child keeps it.
+        let mut g = TouchGesture::new(at(100.0, 100.0), true);
+        assert!(!g.move_to(&frame, at(120.0, 103.0), 1, 8.0, false));
+        assert_eq!(g.owner, GestureOwner::Captured);
+        g.move_to(&frame, at(120.0, 200.0), 1, 8.0, false);
+        assert_eq!(scrolled.get(), -30.0);
+
+        // A child that asked to keep the gesture keeps it in any direction.
+        let mut g = TouchGesture::new(at(100.0, 100.0), true);
+        assert!(!g.move_to(&frame, at(100.0, 150.0), 1, 8.0, true));
+        assert_eq!(g.owner, GestureOwner::Captured);
+    }
+
+    #[test]
+    fn smooth_wheel_eases_out_and_lands_on_the_full_distance() {
+        use std::time::Duration;
+
+        let t0 = web_time::Instant::now();
+        let ms = |n| t0 + Duration::from_millis(n);
+        let mut w = SmoothWheel::default();
+        w.add(Vec2 { x: 0.0, y: 120.0 }, Vec2::default(), t0);
+
+        let mut steps = Vec::new();
+        for n in (16..=320).step_by(16) {
+            if let Some((d, _)) = w.step(ms(n), Duration::from_millis(120)) {
+                steps.push(d.y);
+            }
+        }
+        assert!(!w.is_active());
+        assert!((steps.iter().sum::<f32>() - 120.0).abs() < 1e-3);
+        // Each step shorter than the one before, up to the last bit that finishes it.
+        let eased = &steps[..steps.len() - 1];
+        assert!(eased.windows(2).all(|p| p[0] >= p[1]));
+        assert!(steps.len() > 3);
+    }
+
+    #[test]
+    fn clicks_count_up_to_three_when_quick_and_in_place() {
+        let t0 = web_time::Instant::now();
+        let ms = |n| t0 + std::time::Duration::from_millis(n);
+        let at = Vec2 { x: 10.0, y: 10.0 };
+        let mut c = ClickCounter::default();
+        assert_eq!(c.press(1, at, t0), 1);
+        assert_eq!(c.press(1, Vec2 { x: 12.0, y: 9.0 }, ms(200)), 2);
+        assert_eq!(c.press(1, at, ms(400)), 3);
+        assert_eq!(c.press(1, at, ms(600)), 1);
+        // Too slow, moved away, or on another view: starts over.
+        assert_eq!(c.press(1, at, ms(1500)), 1);
+        assert_eq!(c.press(1, Vec2 { x: 30.0, y: 10.0 }, ms(1600)), 1);
+        assert_eq!(c.press(2, Vec2 { x: 30.0, y: 10.0 }, ms(1700)), 1);
+    }
+
+    #[test]
+    fn pacer_spaces_animation_frames_only_when_saving_power() {
+        use std::time::Duration;
+
+        let t0 = web_time::Instant::now();
+        let ms = |n| t0 + Duration::from_millis(n);
+        let interval = Some(Duration::from_millis(33));
+        let mut p = FramePacer::default();
+        assert_eq!(p.wait_until(t0, interval), None);
+
+        p.frame_at(t0);
+        assert_eq!(p.wait_until(ms(10), None), None);
+        assert_eq!(p.wait_until(ms(10), interval), Some(ms(33)));
+        assert_eq!(p.wait_until(ms(40), interval), None);
+
+        let s = |kind: &str, state: &str| (kind.to_string(), state.to_string());
+        assert_eq!(on_battery_from(&[]), None);
+        assert_eq!(on_battery_from(&[s("Mains", "1")]), Some(false));
+        assert_eq!(
+            on_battery_from(&[s("Mains", "0"), s("Battery", "Discharging")]),
+            Some(true)
+        );
+        assert_eq!(
+            on_battery_from(&[s("USB", "1"), s("Battery", "Discharging")]),
+            Some(false)
+        );
+        assert_eq!(on_battery_from(&[s("Battery", "Full")]), Some(false));
+    }
+
+    #[test]
+    fn keyboard_inset_is_what_covers_more_than_the_nav_bar() {
+        let mut t = InsetsTracker::default();
+        let visible = |bottom: f32| Rect {
+            x: 0.0,
+            y: 48.0,
+            w: 400.0,
+            h: 800.0 - 48.0 - bottom,
+        };
+
+        let i = t.update((400, 800), visible(96.0), 2.0);
+        assert_eq!(i.system_bars.top, 24.0);
+        assert_eq!(i.system_bars.bottom, 48.0);
+        assert_eq!(i.ime.bottom, 0.0);
+
+        let i = t.update((400, 800), visible(600.0), 2.0);
+        assert_eq!(i.system_bars.bottom, 48.0);
+        assert_eq!(i.ime.bottom, 300.0);
+        assert_eq!(i.bottom(), 300.0);
+
+        assert_eq!(t.update((400, 800), visible(96.0), 2.0).ime.bottom, 0.0);
+    }
+}
@@ -0,0 +1,250 @@
+//! # Crash reports
+//!
+//! Once an app calls [`set_crash_dir`], a panic on the UI thread leaves a directory behind
+//! with what the user was looking at and doing:
+//!
+//! - `report.txt` — the panic message and backtrace, scheduler state, the app's metadata
+//!   (see [`set_crash_metadata`]) and the last input events, oldest first.
+//! - `scene.json` — the last composed `Scene`, with the `serde` feature; without it,
+//!   `scene.txt` holds its `Debug` dump instead.
+//! - `semantics.json` — the last frame's semantics tree (`Frame::semantics_to_json`).
+//!
+//! ```rust,no_run
+//! repose_platform::crash::set_crash_dir(std::env::temp_dir().join("my-app-crashes"));
+//! repose_platform::crash::set_crash_metadata(|| format!("version {}", env!("CARGO_PKG_VERSION")));
+//! ```
+//!
+//! The desktop and Android runners install the panic hook (chained to the previous one)
+//! and record frames and input; nothing is recorded until a directory is set. On Android
+//! pick one under the app's internal data path. The web runner has nowhere to write and
+//! keeps logging panics to the console.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt::{Debug, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Once};
+
+use repose_core::{Frame, Scene, Scheduler, SemNode};
+
+/// Input events kept for the report.
+const INPUT_HISTORY: usize = 64;
+
+type Metadata = Box<dyn Fn() -> String + Send + Sync>;
+
+struct Settings {
+    dir: Option<PathBuf>,
+    metadata: Option<Metadata>,
+}
+
+static SETTINGS: Mutex<Settings> = Mutex::new(Settings {
+    dir: None,
+    metadata: None,
+});
+
+/// What the UI thread last composed and received.
+#[derive(Default)]
+struct Recording {
+    scene: Option<Scene>,
+    semantics: Vec<SemNode>,
+    scheduler: String,
+    inputs: VecDeque<String>,
+}
+
+thread_local! {
+    static RECORDING: RefCell<Recording> = RefCell::new(Recording::default());
+}
+
+fn settings() -> std::sync::MutexGuard<'static, Settings> {
+    // A panic while the lock was held must not stop the report of that panic.
+    SETTINGS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Turns crash reports on, writing them under `dir` (created on demand).
+pub fn set_crash_dir(dir: impl Into<PathBuf>) {
+    settings().dir = Some(dir.into());
+}
+
+/// Sets a callback whose text is added to every crash report: app version, open document,
+/// feature flags. It runs inside the panic hook, so keep it simple.
+pub fn set_crash_metadata(f: impl Fn() -> String + Send + Sync + 'static) {
+    settings().metadata = Some(Box::new(f));
+}
+
+fn enabled() -> bool {
+    settings().dir.is_some()
+}
+
+/// Installs the panic hook, once per process.
+pub(crate) fn install() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let dir = settings().dir.clone();
+            if let Some(dir) = dir {
+                match write_report(&dir, &info.to_string()) {
+                    Ok(path) => log::error!("crash report written to {}", path.display()),
+                    Err(e) => log::error!("failed to write crash report: {e}"),
+                }
+            }
+            previous(info);
+        }));
+    });
+}
+
+/// Keeps `frame` and the scheduler's state for the report.
+pub(crate) fn record_frame(frame: &Frame, sched: &Scheduler) {
+    if !enabled() {
+        return;
+    }
+    RECORDING.with(|r| {
+        let mut r = r.borrow_mut();
+        r.scene = Some(frame.scene.clone());
+        r.semantics = frame.semantics_nodes.clone();
+        r.scheduler = format!(
+            "size: {:?}\nfocused: {:?}\nlast frame: {:?}\njanky frames: {}\n",
+            sched.size,
+            sched.focused,
+            sched.jank.last(),
+            sched.jank.janky_frames(),
+        );
+    });
+}
+
+/// Adds `event` to the input history kept for the report, if it is input.
+#[cfg(any(feature = "desktop", feature = "android"))]
+pub(crate) fn record_window_event(event: &winit::event::WindowEvent) {
+    use winit::event::WindowEvent as E;
+    if matches!(
+        event,
+        E::KeyboardInput { .. }
+            | E::Ime(_)
+            | E::CursorMoved { .. }
+            | E::MouseInput { .. }
+            | E::MouseWheel { .. }
+            | E::Touch(_)
+    ) {
+        record_input(event);
+    }
+}
+
+fn record_input(event: &dyn Debug) {
+    if !enabled() {
+        return;
+    }
+    RECORDING.with(|r| {
+        let mut r = r.borrow_mut();
+        if r.inputs.len() == INPUT_HISTORY {
+            r.inputs.pop_front();
+        }
+        r.inputs.push_back(format!("{event:?}"));
+    });
+}
+
+/// Writes a report for `panic` into a new directory under `dir` and returns its path.
+fn write_report(dir: &Path, panic: &str) -> std::io::Result<PathBuf> {
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let path = dir.join(format!("crash-{stamp}-{}", std::process::id()));
+    std::fs::create_dir_all(&path)?;
+
+    let metadata = settings().metadata.as_ref().map(|f| f());
+    let mut report = String::new();
+    let _ = writeln!(report, "{panic}");
+    if let Some(name) = std::thread::current().name() {
+        let _ = writeln!(report, "thread: {name}");
+    }
+    let _ = writeln!(
+        report,
+        "\n## Backtrace\n{}",
+        std::backtrace::Backtrace::force_capture()
+    );
+
+    // The panic may have come from inside a recording call; write what can be read.
+    RECORDING.with(|r| -> std::io::Result<()> {
+        let Ok(r) = r.try_borrow() else {
+            return Ok(());
+        };
+        let _ = writeln!(report, "## Scheduler\n{}", r.scheduler);
+        if let Some(m) = &metadata {
+            let _ = writeln!(report, "## Metadata\n{m}\n");
+        }
+        let _ = writeln!(report, "## Recent input");
+        for e in &r.inputs {
+            let _ = writeln!(report, "{e}");
+        }
+        if let Some(scene) = &r.scene {
+            write_scene(&path, scene)?;
+        }
+        std::fs::write(
+            path.join("semantics.json"),
+            repose_core::frame::semantics_to_json(&r.semantics),
+        )
+    })?;
+    std::fs::write(path.join("report.txt"), report)?;
+    Ok(path)
+}
+
+#[cfg(feature = "serde")]
+fn write_scene(dir: &Path, scene: &Scene) -> std::io::Result<()> {
+    let file = std::fs::File::create(dir.join("scene.json"))?;
+    serde_json::to_writer(std::io::BufWriter::new(file), scene).map_err(std::io::Error::other)
+}
+
+#[cfg(not(feature = "serde"))]
+fn write_scene(dir: &Path, scene: &Scene) -> std::io::Result<()> {
+    std::fs::write(dir.join("scene.txt"), format!("{scene:#?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use repose_core::{Color, Rect, Role};
+
+    #[test]
+    fn report_holds_the_last_frame_and_input() {
+        let dir = std::env::temp_dir().join(format!("repose-crash-test-{}", std::process::id()));
+        set_crash_dir(&dir);
+        set_crash_metadata(|| "doc: notes.txt".into());
+
+        let r = Rect {
+            x: 0.0,
+            y: 0.0,
+            w: 10.0,
+            h: 10.0,
+        };
+        let frame = Frame::new(Scene::new(Color::BLACK))
+            .with_semantics(vec![SemNode::new(7, Role::Button, r).label("Save")]);
+        record_frame(&frame, &Scheduler::new());
+        for n in 0..INPUT_HISTORY + 1 {
+            record_input(&format!("key {n}"));
+        }
+
+        let path = write_report(&dir, "panicked at src/main.rs:1:1:\nboom").unwrap();
+        let report = std::fs::read_to_string(path.join("report.txt")).unwrap();
+        assert!(report.starts_with("panicked at src/main.rs:1:1:\nboom"));
+        assert!(report.contains("doc: notes.txt"));
+        assert!(report.contains("focused: None"));
+        // The oldest event fell out of the history.
+        assert!(!report.contains("\"key 0\""));
+        assert!(report.contains("\"key 64\""));
+        let semantics = std::fs::read_to_string(path.join("semantics.json")).unwrap();
+        assert!(semantics.contains("\"Save\""));
+        #[cfg(feature = "serde")]
+        {
+            let scene = std::fs::read_to_string(path.join("scene.json")).unwrap();
+            let scene: Scene = serde_json::from_str(&scene).unwrap();
+            assert_eq!(scene.clear_color, Color::BLACK);
+        }
+        #[cfg(not(feature = "serde"))]
+        assert!(path.join("scene.txt").exists());
+
+        // Later panics in this process shouldn't write reports.
+        settings().dir = None;
+        settings().metadata = None;
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,9 +1,11 @@
 //! Platform runners
+//!
+//! With the `profile-with-tracy` or `profile-with-puffin` feature, the runners mark every
+//! frame for that profiler and the framework reports zones for composition, Taffy layout,
+//! the scene walk, glyph rasterization and GPU submission.
 use repose_core::locals::dp_to_px;
 use repose_core::*;
-use repose_ui::textfield::{TF_FONT_DP, TF_PADDING_X_DP, index_for_x_bytes, measure_text};
-use std::cell::RefCell;
-use std::rc::Rc;
+use repose_ui::textfield::{TF_FONT_DP, TF_PADDING_X_DP};
 use web_time::Instant;
 
 #[cfg(all(feature = "android", target_os = "android"))]
@@ -14,15 +16,24 @@ pub mod web;
 
 mod common;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod crash;
+
+#[cfg(all(feature = "desktop", not(target_arch = "wasm32")))]
+pub mod print;
+
 /// Compose a single frame with density and text-scale applied, returning Frame.
+///
+/// State in `retained` belonging to views that are no longer in the tree is dropped.
 pub fn compose_frame<F>(
     sched: &mut Scheduler,
     root_fn: &mut F,
     scale: f32,
     size_px_u32: (u32, u32),
     hover_id: Option<u64>,
+    pointer_px: Option<Vec2>,
     pressed_ids: &std::collections::HashSet<u64>,
-    tf_states: &std::collections::HashMap<u64, Rc<RefCell<repose_ui::TextFieldState>>>,
+    retained: &RetainedStateStore,
     focused: Option<u64>,
 ) -> Frame
 where
@@ -30,7 +41,7 @@ where
 {
     set_density_default(Density { scale });
 
-    sched.repose(
+    let frame = sched.repose(
         {
             let scale = scale;
             move |s: &mut Scheduler| with_density(Density { scale }, || (root_fn)(s))
@@ -42,26 +53,22 @@ where
                 let interactions = repose_ui::Interactions {
                     hover: hover_id,
                     pressed: pressed_ids.clone(),
+                    pointer: pointer_px,
                 };
                 with_density(Density { scale }, || {
-                    repose_ui::layout_and_paint(
-                        view,
-                        size_px_u32,
-                        tf_states,
-                        &interactions,
-                        focused,
-                    )
+                    repose_ui::layout_and_paint(view, size_px_u32, retained, &interactions, focused)
                 })
             }
         },
-    )
+    );
+    retained.evict_unseen();
+    frame
 }
 
 /// Helper: ensure caret visibility for a TextFieldState inside a given rect (px).
 pub fn tf_ensure_visible_in_rect(state: &mut repose_ui::TextFieldState, inner_rect: Rect) {
     let font_px = dp_to_px(TF_FONT_DP) * repose_core::locals::text_scale().0;
-    let m = measure_text(&state.text, font_px);
-    let caret_x_px = m.positions.get(state.caret_index()).copied().unwrap_or(0.0);
+    let caret_x_px = state.x_for_index(state.caret_index(), font_px);
     state.ensure_caret_visible(
         caret_x_px,
         inner_rect.w - 2.0 * dp_to_px(TF_PADDING_X_DP),
@@ -69,32 +76,157 @@ pub fn tf_ensure_visible_in_rect(state: &mut repose_ui::TextFieldState, inner_re
     );
 }
 
+/// How the desktop runner opens its window. Sizes are in dp (logical pixels).
+#[cfg(feature = "desktop")]
+#[derive(Clone, Debug)]
+pub struct WindowOptions {
+    title: String,
+    inner_size: Option<(f32, f32)>,
+    min_inner_size: Option<(f32, f32)>,
+    max_inner_size: Option<(f32, f32)>,
+    size_to_content: bool,
+    decorations: bool,
+    app_id: Option<String>,
+    activation_token: Option<String>,
+    continuous_redraw: bool,
+}
+
+#[cfg(feature = "desktop")]
+impl Default for WindowOptions {
+    fn default() -> Self {
+        Self {
+            title: "Repose".into(),
+            inner_size: None,
+            min_inner_size: None,
+            max_inner_size: None,
+            size_to_content: false,
+            decorations: true,
+            app_id: None,
+            activation_token: None,
+            continuous_redraw: true,
+        }
+    }
+}
+
+#[cfg(feature = "desktop")]
+impl WindowOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Initial size; 1280x800 px when unset.
+    pub fn inner_size(mut self, width: f32, height: f32) -> Self {
+        self.inner_size = Some((width, height));
+        self
+    }
+
+    pub fn min_inner_size(mut self, width: f32, height: f32) -> Self {
+        self.min_inner_size = Some((width, height));
+        self
+    }
+
+    pub fn max_inner_size(mut self, width: f32, height: f32) -> Self {
+        self.max_inner_size = Some((width, height));
+        self
+    }
+
+    /// Resizes the window to the natural size of its content before the first frame is
+    /// drawn, within the min and max sizes. Suits dialogs and small utility windows.
+    pub fn size_to_content(mut self, on: bool) -> Self {
+        self.size_to_content = on;
+        self
+    }
+
+    /// Whether the window gets a title bar and borders. On Wayland they're drawn by the
+    /// compositor when it offers to, and by the app otherwise.
+    pub fn decorations(mut self, on: bool) -> Self {
+        self.decorations = on;
+        self
+    }
+
+    /// Linux: the Wayland app_id and X11 `WM_CLASS`, which desktops match against the
+    /// app's `.desktop` file for its icon and taskbar grouping. Use the desktop file's name
+    /// without the extension, e.g. `org.example.Notes`.
+    pub fn app_id(mut self, id: impl Into<String>) -> Self {
+        self.app_id = Some(id.into());
+        self
+    }
+
+    /// Linux: the startup notification token (`XDG_ACTIVATION_TOKEN` on Wayland,
+    /// `DESKTOP_STARTUP_ID` on X11) the window is activated with. By default it's read
+    /// from the environment the launcher set, then removed so child processes don't reuse it.
+    pub fn activation_token(mut self, token: impl Into<String>) -> Self {
+        self.activation_token = Some(token.into());
+        self
+    }
+
+    /// Whether to redraw every frame (the default, needed for animations). When off, the
+    /// window only redraws for input, state changes, `with_frame_nanos` callbacks and the
+    /// caret blink of a focused `TextField`.
+    pub fn continuous_redraw(mut self, on: bool) -> Self {
+        self.continuous_redraw = on;
+        self
+    }
+}
+
+#[cfg(feature = "desktop")]
+thread_local! {
+    static DOCUMENT_EDITED: std::cell::Cell<Option<bool>> = const { std::cell::Cell::new(None) };
+}
+
+/// Marks the window's document as having unsaved changes, shown as the dot in the close
+/// button on macOS. Other platforms have no such indicator and ignore it.
+#[cfg(feature = "desktop")]
+pub fn set_document_edited(edited: bool) {
+    DOCUMENT_EDITED.with(|d| d.set(Some(edited)));
+}
+
 #[cfg(feature = "desktop")]
 pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> anyhow::Result<()> {
-    use std::cell::RefCell;
-    use std::collections::{HashMap, HashSet};
-    use std::rc::Rc;
+    run_desktop_app_with(WindowOptions::default(), root)
+}
+
+#[cfg(feature = "desktop")]
+pub fn run_desktop_app_with(
+    options: WindowOptions,
+    root: impl FnMut(&mut Scheduler) -> View + 'static,
+) -> anyhow::Result<()> {
+    use std::collections::HashSet;
     use std::sync::Arc;
 
     use repose_ui::TextFieldState;
+    use repose_ui::text_diagnostics;
     use winit::application::ApplicationHandler;
     use winit::dpi::{LogicalPosition, LogicalSize, PhysicalSize};
     use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
-    use winit::event_loop::EventLoop;
+    use winit::event_loop::{ControlFlow, EventLoop};
     use winit::keyboard::{KeyCode, PhysicalKey};
     use winit::window::{ImePurpose, Window, WindowAttributes};
 
     struct App {
         // App state
         root: Box<dyn FnMut(&mut Scheduler) -> View>,
+        options: WindowOptions,
         window: Option<Arc<Window>>,
         backend: Option<repose_render_wgpu::WgpuBackend>,
         sched: Scheduler,
         inspector: repose_devtools::Inspector,
         frame_cache: Option<Frame>,
         mouse_pos_px: (f32, f32),
+        mouse_in_window: bool,
         modifiers: Modifiers,
-        textfield_states: HashMap<u64, Rc<RefCell<TextFieldState>>>,
+        key_repeat: common::KeyRepeat,
+        smooth_wheel: common::SmoothWheel,
+        clicks: common::ClickCounter,
+        pacer: common::FramePacer,
+        battery: common::BatteryWatch,
+        caret_blink: common::CaretBlinkTimer,
+        retained: RetainedStateStore,
         ime_preedit: bool,
         hover_id: Option<u64>,
         capture_id: Option<u64>,
@@ -106,17 +238,25 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
     }
 
     impl App {
-        fn new(root: Box<dyn FnMut(&mut Scheduler) -> View>) -> Self {
+        fn new(root: Box<dyn FnMut(&mut Scheduler) -> View>, options: WindowOptions) -> Self {
             Self {
                 root,
+                options,
                 window: None,
                 backend: None,
                 sched: Scheduler::new(),
                 inspector: repose_devtools::Inspector::new(),
                 frame_cache: None,
                 mouse_pos_px: (0.0, 0.0),
+                mouse_in_window: false,
                 modifiers: Modifiers::default(),
-                textfield_states: HashMap::new(),
+                key_repeat: common::KeyRepeat::default(),
+                smooth_wheel: common::SmoothWheel::default(),
+                clicks: common::ClickCounter::default(),
+                pacer: common::FramePacer::default(),
+                battery: common::BatteryWatch::default(),
+                caret_blink: common::CaretBlinkTimer::default(),
+                retained: RetainedStateStore::new(),
                 ime_preedit: false,
                 hover_id: None,
                 capture_id: None,
@@ -143,11 +283,49 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
             }
         }
 
+        // Double click selects the word under the pointer, triple click everything. The
+        // selection then stays put rather than following the drag.
+        fn tf_select_by_clicks(st: &mut TextFieldState, idx: usize, clicks: u32) {
+            match clicks {
+                2 => st.select_word_at(idx),
+                3 => st.select_all(),
+                _ => return,
+            }
+            st.end_drag();
+        }
+
+        // Composes a frame just to measure the content, then asks for a window that fits it.
+        // The platform keeps the request within the min and max sizes.
+        fn size_to_content(&mut self, win: &Window) {
+            let size = win.inner_size();
+            repose_ui::measure_content_size();
+            compose_frame(
+                &mut self.sched,
+                &mut self.root,
+                win.scale_factor() as f32,
+                (size.width, size.height),
+                None,
+                None,
+                &HashSet::new(),
+                &self.retained,
+                None,
+            );
+            if let Some(c) = repose_ui::take_content_size()
+                && c.x >= 1.0
+                && c.y >= 1.0
+            {
+                let _ = win.request_inner_size(PhysicalSize::new(c.x.ceil(), c.y.ceil()));
+            }
+        }
+
         // Ensure caret is visible after edits/moves (all units in px)
         fn tf_ensure_caret_visible(st: &mut TextFieldState) {
             let font_px = dp_to_px(TF_FONT_DP) * repose_core::locals::text_scale().0;
-            let m = measure_text(&st.text, font_px);
-            let caret_x_px = m.positions.get(st.caret_index()).copied().unwrap_or(0.0);
+            if st.multiline {
+                st.ensure_caret_visible_y(font_px);
+                return;
+            }
+            let caret_x_px = st.x_for_index(st.caret_index(), font_px);
             st.ensure_caret_visible(caret_x_px, st.inner_width, dp_to_px(2.0));
         }
 
@@ -178,13 +356,45 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
             self.clipboard = clipawl::Clipboard::new().ok();
             // Create the window once when app resumes.
             if self.window.is_none() {
-                match el.create_window(
-                    WindowAttributes::default()
-                        .with_title("Repose")
-                        .with_inner_size(PhysicalSize::new(1280, 800)),
-                ) {
+                let o = &self.options;
+                let mut attrs = WindowAttributes::default().with_title(o.title.clone());
+                attrs = match o.inner_size {
+                    Some((w, h)) => attrs.with_inner_size(LogicalSize::new(w, h)),
+                    None => attrs.with_inner_size(PhysicalSize::new(1280, 800)),
+                };
+                if let Some((w, h)) = o.min_inner_size {
+                    attrs = attrs.with_min_inner_size(LogicalSize::new(w, h));
+                }
+                if let Some((w, h)) = o.max_inner_size {
+                    attrs = attrs.with_max_inner_size(LogicalSize::new(w, h));
+                }
+                attrs = attrs.with_decorations(o.decorations);
+                #[cfg(target_os = "linux")]
+                {
+                    use winit::platform::startup_notify::{
+                        EventLoopExtStartupNotify, WindowAttributesExtStartupNotify,
+                    };
+                    use winit::platform::wayland::WindowAttributesExtWayland;
+                    if let Some(id) = &o.app_id {
+                        // Sets the X11 WM_CLASS as well.
+                        attrs = attrs.with_name(id, id);
+                    }
+                    let token = o
+                        .activation_token
+                        .clone()
+                        .map(winit::window::ActivationToken::from_raw)
+                        .or_else(|| el.read_token_from_env());
+                    if let Some(token) = token {
+                        attrs = attrs.with_activation_token(token);
+                    }
+                    winit::platform::startup_notify::reset_activation_token_env();
+                }
+                match el.create_window(attrs) {
                     Ok(win) => {
                         let w = Arc::new(win);
+                        if self.options.size_to_content {
+                            self.size_to_content(&w);
+                        }
                         let size = w.inner_size();
                         self.sched.size = (size.width, size.height);
                         // Create WGPU backend
@@ -214,6 +424,7 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
             _id: winit::window::WindowId,
             event: WindowEvent,
         ) {
+            crash::record_window_event(&event);
             match event {
                 WindowEvent::CloseRequested => {
                     el.exit();
@@ -227,19 +438,24 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                         let sf = w.scale_factor() as f32;
                         let dp_w = size.width as f32 / sf;
                         let dp_h = size.height as f32 / sf;
-                        log::info!(
-                            "Resized: fb={}x{} px, scale_factor={}, ~{}x{} dp",
-                            size.width,
-                            size.height,
-                            sf,
-                            dp_w as i32,
-                            dp_h as i32
+                        tracing::debug!(
+                            width_px = size.width,
+                            height_px = size.height,
+                            scale_factor = sf,
+                            width_dp = dp_w as i32,
+                            height_dp = dp_h as i32,
+                            "resized"
                         );
                     }
                     self.request_redraw();
                 }
+                WindowEvent::CursorLeft { .. } => {
+                    self.mouse_in_window = false;
+                    self.request_redraw();
+                }
                 WindowEvent::CursorMoved { position, .. } => {
                     self.mouse_pos_px = (position.x as f32, position.y as f32);
+                    self.mouse_in_window = true;
 
                     // Inspector hover
                     if self.inspector.hud.inspector_enabled
@@ -266,7 +482,22 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                             .find(|n| n.id == cid && n.role == Role::TextField)
                     {
                         let key = self.tf_key_of(cid);
-                        if let Some(state_rc) = self.textfield_states.get(&key) {
+                        let hit_rect = f.hit_regions.iter().find(|h| h.id == cid).map(|h| h.rect);
+                        if let Some(state_rc) = self.retained.get::<TextFieldState>(key)
+                            && state_rc.borrow().multiline
+                            && let Some(field) = hit_rect
+                        {
+                            let mut state = state_rc.borrow_mut();
+                            let pos = Vec2 {
+                                x: self.mouse_pos_px.0,
+                                y: self.mouse_pos_px.1,
+                            };
+                            let (idx, affinity) = common::text_area_index(&state, field, pos);
+                            state.drag_to(idx);
+                            state.affinity = affinity;
+                            App::tf_ensure_caret_visible(&mut state);
+                            self.request_redraw();
+                        } else if let Some(state_rc) = self.retained.get::<TextFieldState>(key) {
                             let mut state = state_rc.borrow_mut();
                             // inner content left edge in px
                             let inner_x_px = f
@@ -279,14 +510,11 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                                 self.mouse_pos_px.0 - inner_x_px + state.scroll_offset;
                             let font_px =
                                 dp_to_px(TF_FONT_DP) * repose_core::locals::text_scale().0;
-                            let idx =
-                                index_for_x_bytes(&state.text, font_px, content_x_px.max(0.0));
+                            let idx = state.index_for_x(font_px, content_x_px.max(0.0));
                             state.drag_to(idx);
 
                             // Scroll caret into view
-                            let m = measure_text(&state.text, font_px);
-                            let caret_x_px =
-                                m.positions.get(state.caret_index()).copied().unwrap_or(0.0);
+                            let caret_x_px = state.x_for_index(state.caret_index(), font_px);
                             if let Some(hit) = f.hit_regions.iter().find(|h| h.id == cid) {
                                 state.ensure_caret_visible(
                                     caret_x_px,
@@ -337,6 +565,15 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                                 };
                                 cb(pe);
                             }
+                            // I-beam over text fields.
+                            if let Some(w) = &self.window {
+                                let over_text = top.is_some_and(|h| h.tf_state_key.is_some());
+                                w.set_cursor(if over_text {
+                                    winit::window::CursorIcon::Text
+                                } else {
+                                    winit::window::CursorIcon::Default
+                                });
+                            }
                             self.hover_id = new_hover;
                         }
 
@@ -365,35 +602,25 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                     }
                 }
                 WindowEvent::MouseWheel { delta, .. } => {
-                    // Convert line deltas (logical) to px; pixel delta is already px
-                    let (dx_px, dy_px) = match delta {
-                        MouseScrollDelta::LineDelta(x, y) => {
-                            let unit_px = dp_to_px(60.0);
-                            (-(x * unit_px), -(y * unit_px))
-                        }
-                        MouseScrollDelta::PixelDelta(lp) => (-(lp.x as f32), -(lp.y as f32)),
+                    let scale = self
+                        .window
+                        .as_ref()
+                        .map_or(1.0, |w| w.scale_factor() as f32);
+                    let d = common::wheel_delta_px(delta, scale);
+                    let pos = Vec2 {
+                        x: self.mouse_pos_px.0,
+                        y: self.mouse_pos_px.1,
                     };
-                    log::debug!("MouseWheel: dx={}, dy={}", dx_px, dy_px);
 
-                    if let Some(f) = &self.frame_cache {
-                        let pos = Vec2 {
-                            x: self.mouse_pos_px.0,
-                            y: self.mouse_pos_px.1,
-                        };
-
-                        for hit in f.hit_regions.iter().rev().filter(|h| h.rect.contains(pos)) {
-                            if let Some(cb) = &hit.on_scroll {
-                                log::debug!("Calling on_scroll for hit region id={}", hit.id);
-                                let before = Vec2 { x: dx_px, y: dy_px };
-                                let leftover = cb(before);
-                                let consumed_x = (before.x - leftover.x).abs() > 0.001;
-                                let consumed_y = (before.y - leftover.y).abs() > 0.001;
-                                if consumed_x || consumed_y {
-                                    self.request_redraw();
-                                    break; // stop after first consumer
-                                }
-                            }
-                        }
+                    if repose_core::locals::platform_settings().scroll.smooth
+                        && matches!(delta, MouseScrollDelta::LineDelta(..))
+                    {
+                        self.smooth_wheel.add(d, pos, Instant::now());
+                        self.request_redraw();
+                    } else if let Some(f) = &self.frame_cache
+                        && common::dispatch_scroll(f, pos, d)
+                    {
+                        self.request_redraw();
                     }
                 }
                 WindowEvent::MouseInput {
@@ -401,6 +628,18 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                     button: MouseButton::Left,
                     ..
                 } => {
+                    // Inspector: clicks pick the node to edit instead of reaching the app
+                    if self.inspector.hud.inspector_enabled
+                        && let Some(f) = &self.frame_cache
+                    {
+                        let pos = Vec2 {
+                            x: self.mouse_pos_px.0,
+                            y: self.mouse_pos_px.1,
+                        };
+                        self.inspector.pick(f, pos);
+                        self.request_redraw();
+                        return;
+                    }
                     let mut need_announce = false;
                     if let Some(f) = &self.frame_cache {
                         let pos = Vec2 {
@@ -409,6 +648,10 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                         };
                         if let Some(hit) = f.hit_regions.iter().rev().find(|h| h.rect.contains(pos))
                         {
+                            if !text_diagnostics::is_menu_item(hit.id) {
+                                text_diagnostics::dismiss_menu();
+                            }
+                            let clicks = self.clicks.press(hit.id, pos, Instant::now());
                             // Capture starts on press
                             self.capture_id = Some(hit.id);
                             // Pressed visual for mouse
@@ -421,11 +664,7 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                                 self.sched.focused = Some(hit.id);
                                 need_announce = true;
                                 let key = self.tf_key_of(hit.id);
-                                self.textfield_states.entry(key).or_insert_with(|| {
-                                    Rc::new(RefCell::new(
-                                        repose_ui::textfield::TextFieldState::new(),
-                                    ))
-                                });
+                                self.retained.get_or_insert_with(key, TextFieldState::new);
                                 if let Some(win) = &self.window {
                                     let sf = win.scale_factor();
                                     win.set_ime_allowed(true);
@@ -465,25 +704,30 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                                 .find(|n| n.id == hit.id && n.role == Role::TextField)
                             {
                                 let key = self.tf_key_of(hit.id);
-                                if let Some(state_rc) = self.textfield_states.get(&key) {
+                                if let Some(state_rc) = self.retained.get::<TextFieldState>(key)
+                                    && state_rc.borrow().multiline
+                                {
+                                    let mut state = state_rc.borrow_mut();
+                                    let (idx, affinity) =
+                                        common::text_area_index(&state, hit.rect, pos);
+                                    state.begin_drag(idx, self.modifiers.shift);
+                                    state.affinity = affinity;
+                                    App::tf_select_by_clicks(&mut state, idx, clicks);
+                                    App::tf_ensure_caret_visible(&mut state);
+                                } else if let Some(state_rc) =
+                                    self.retained.get::<TextFieldState>(key)
+                                {
                                     let mut state = state_rc.borrow_mut();
                                     let inner_x_px = hit.rect.x + dp_to_px(TF_PADDING_X_DP);
                                     let content_x_px =
                                         self.mouse_pos_px.0 - inner_x_px + state.scroll_offset;
                                     let font_px =
                                         dp_to_px(TF_FONT_DP) * repose_core::locals::text_scale().0;
-                                    let idx = index_for_x_bytes(
-                                        &state.text,
-                                        font_px,
-                                        content_x_px.max(0.0),
-                                    );
+                                    let idx = state.index_for_x(font_px, content_x_px.max(0.0));
                                     state.begin_drag(idx, self.modifiers.shift);
-                                    let m = measure_text(&state.text, font_px);
-                                    let caret_x_px = m
-                                        .positions
-                                        .get(state.caret_index())
-                                        .copied()
-                                        .unwrap_or(0.0);
+                                    App::tf_select_by_clicks(&mut state, idx, clicks);
+                                    let caret_x_px =
+                                        state.x_for_index(state.caret_index(), font_px);
                                     state.ensure_caret_visible(
                                         caret_x_px,
                                         hit.rect.w - 2.0 * dp_to_px(TF_PADDING_X_DP),
@@ -497,6 +741,7 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
 
                             self.request_redraw();
                         } else {
+                            text_diagnostics::dismiss_menu();
                             // Click outside: drop focus/IME
                             if self.ime_preedit {
                                 if let Some(win) = &self.window {
@@ -509,6 +754,39 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                         }
                     }
                 }
+                WindowEvent::MouseInput {
+                    state: ElementState::Pressed,
+                    button: MouseButton::Right,
+                    ..
+                } => {
+                    // Right-click on a TextField diagnostic opens its suggestions menu
+                    text_diagnostics::dismiss_menu();
+                    let pos = Vec2 {
+                        x: self.mouse_pos_px.0,
+                        y: self.mouse_pos_px.1,
+                    };
+                    if let Some(f) = &self.frame_cache
+                        && let Some(hit) = f.hit_regions.iter().rev().find(|h| h.rect.contains(pos))
+                        && f.semantics_nodes
+                            .iter()
+                            .any(|n| n.id == hit.id && n.role == Role::TextField)
+                        && let Some(state_rc) =
+                            self.retained.get::<TextFieldState>(self.tf_key_of(hit.id))
+                    {
+                        let state = state_rc.borrow();
+                        let idx = if state.multiline {
+                            common::text_area_index(&state, hit.rect, pos).0
+                        } else {
+                            let inner_x_px = hit.rect.x + dp_to_px(TF_PADDING_X_DP);
+                            let content_x_px = pos.x - inner_x_px + state.scroll_offset;
+                            let font_px =
+                                dp_to_px(TF_FONT_DP) * repose_core::locals::text_scale().0;
+                            state.index_for_x(font_px, content_x_px.max(0.0))
+                        };
+                        text_diagnostics::open_menu(hit.id, &state, idx, pos);
+                    }
+                    self.request_redraw();
+                }
                 WindowEvent::MouseInput {
                     state: ElementState::Released,
                     button: MouseButton::Left,
@@ -545,7 +823,7 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                             .find(|n| n.id == cid && n.role == Role::TextField)
                     {
                         let key = self.tf_key_of(cid);
-                        if let Some(state_rc) = self.textfield_states.get(&key) {
+                        if let Some(state_rc) = self.retained.get::<TextFieldState>(key) {
                             state_rc.borrow_mut().end_drag();
                         }
                     }
@@ -557,15 +835,36 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                     self.modifiers.alt = new_mods.state().alt_key();
                     self.modifiers.meta = new_mods.state().super_key();
                 }
+                WindowEvent::Focused(false) => {
+                    // The release of a held key goes to whichever window has focus now.
+                    self.key_repeat.cancel();
+                }
                 WindowEvent::KeyboardInput {
-                    event: key_event, ..
+                    device_id,
+                    event: key_event,
+                    ..
                 } => {
+                    if !self.key_repeat.accept(device_id, &key_event) {
+                        return;
+                    }
+                    let app_event = common::key_event_from_winit(&key_event, self.modifiers);
+                    if let Some(f) = &self.frame_cache
+                        && common::dispatch_key(f, self.sched.focused, &app_event)
+                    {
+                        self.request_redraw();
+                        return;
+                    }
                     if key_event.state == ElementState::Pressed && !key_event.repeat {
                         match key_event.physical_key {
                             PhysicalKey::Code(KeyCode::BrowserBack)
                             | PhysicalKey::Code(KeyCode::Escape) => {
                                 use repose_navigation::back;
 
+                                if self.sched.layers.dismiss_modal() {
+                                    self.request_redraw();
+                                    return;
+                                }
+
                                 if !back::handle() {
                                     // el.exit();
                                 }
@@ -666,7 +965,7 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                     {
                         let key = self.tf_key_of(focused_id);
 
-                        if let Some(state) = self.textfield_states.get(&key) {
+                        if let Some(state) = self.retained.get::<TextFieldState>(key) {
                             let text = state.borrow().text.clone();
                             on_submit(text);
                             self.request_redraw();
@@ -675,6 +974,17 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                     }
 
                     if key_event.state == ElementState::Pressed {
+                        // Cmd+W closes the window and Cmd+Q quits, which is the same thing
+                        // with one window.
+                        #[cfg(target_os = "macos")]
+                        if self.modifiers.meta
+                            && let PhysicalKey::Code(KeyCode::KeyW | KeyCode::KeyQ) =
+                                key_event.physical_key
+                        {
+                            el.exit();
+                            return;
+                        }
+
                         // Inspector hotkey: Ctrl+Shift+I
                         if self.modifiers.ctrl
                             && self.modifiers.shift
@@ -685,26 +995,182 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                             return;
                         }
 
+                        // Semantics dump (and diff against the previous one): Ctrl+Shift+S
+                        if self.modifiers.ctrl
+                            && self.modifiers.shift
+                            && let PhysicalKey::Code(KeyCode::KeyS) = key_event.physical_key
+                            && let Some(f) = &self.frame_cache
+                        {
+                            tracing::info!("semantics: {}", self.inspector.dump_semantics(f));
+                            return;
+                        }
+
+                        // Overdraw view: Ctrl+Shift+O
+                        if self.modifiers.ctrl
+                            && self.modifiers.shift
+                            && let PhysicalKey::Code(KeyCode::KeyO) = key_event.physical_key
+                        {
+                            self.inspector.toggle_overdraw();
+                            self.request_redraw();
+                            return;
+                        }
+
+                        // Paint cost overlay: Ctrl+Shift+P
+                        if self.modifiers.ctrl
+                            && self.modifiers.shift
+                            && let PhysicalKey::Code(KeyCode::KeyP) = key_event.physical_key
+                        {
+                            self.inspector.toggle_paint_cost();
+                            self.request_redraw();
+                            return;
+                        }
+
+                        // Animation playback: Ctrl+Shift+T cycles the speed (1x, 0.5x, 0.1x),
+                        // Ctrl+Shift+K pauses and Ctrl+Shift+L steps one frame
+                        if self.modifiers.ctrl
+                            && self.modifiers.shift
+                            && let PhysicalKey::Code(
+                                code @ (KeyCode::KeyT | KeyCode::KeyK | KeyCode::KeyL),
+                            ) = key_event.physical_key
+                        {
+                            match code {
+                                KeyCode::KeyT => {
+                                    self.inspector.cycle_animation_speed();
+                                }
+                                KeyCode::KeyK => {
+                                    self.inspector.toggle_animation_pause();
+                                }
+                                _ => self.inspector.step_animations(),
+                            }
+                            self.request_redraw();
+                            return;
+                        }
+
+                        // Accessibility audit: Ctrl+Shift+A toggles, F8 jumps to the next issue
+                        if self.modifiers.ctrl
+                            && self.modifiers.shift
+                            && let PhysicalKey::Code(KeyCode::KeyA) = key_event.physical_key
+                        {
+                            self.inspector.toggle_audit();
+                            self.request_redraw();
+                            return;
+                        }
+                        if self.inspector.audit_enabled
+                            && let PhysicalKey::Code(KeyCode::F8) = key_event.physical_key
+                        {
+                            self.inspector.next_issue();
+                            self.request_redraw();
+                            return;
+                        }
+
+                        // Inspector theme edits: T picks the color, - and = step it
+                        if self.inspector.hud.inspector_enabled
+                            && let PhysicalKey::Code(code) = key_event.physical_key
+                        {
+                            let handled = match code {
+                                KeyCode::KeyT => {
+                                    self.inspector.next_theme_token();
+                                    true
+                                }
+                                KeyCode::Minus => {
+                                    self.inspector.nudge_theme(-8);
+                                    true
+                                }
+                                KeyCode::Equal => {
+                                    self.inspector.nudge_theme(8);
+                                    true
+                                }
+                                _ => false,
+                            };
+                            if handled {
+                                self.request_redraw();
+                                return;
+                            }
+                        }
+
+                        // Inspector live edits on the selected node
+                        if self.inspector.hud.inspector_enabled
+                            && self.inspector.selected().is_some()
+                            && let PhysicalKey::Code(code) = key_event.physical_key
+                        {
+                            use repose_devtools::Nudge;
+                            let handled = match code {
+                                KeyCode::BracketLeft => self.inspector.nudge(Nudge::Padding(-1.0)),
+                                KeyCode::BracketRight => self.inspector.nudge(Nudge::Padding(1.0)),
+                                KeyCode::ArrowLeft => self.inspector.nudge(Nudge::Width(-4.0)),
+                                KeyCode::ArrowRight => self.inspector.nudge(Nudge::Width(4.0)),
+                                KeyCode::ArrowUp => self.inspector.nudge(Nudge::Height(-4.0)),
+                                KeyCode::ArrowDown => self.inspector.nudge(Nudge::Height(4.0)),
+                                KeyCode::Backspace => {
+                                    self.inspector.reset_edits();
+                                    true
+                                }
+                                KeyCode::Escape => {
+                                    self.inspector.deselect();
+                                    true
+                                }
+                                _ => false,
+                            };
+                            if handled {
+                                self.request_redraw();
+                                return;
+                            }
+                        }
+
                         // TextField navigation/edit
                         if let Some(focused_id) = self.sched.focused {
                             let key = self.tf_key_of(focused_id);
-                            if let Some(state_rc) = self.textfield_states.get(&key) {
+                            if let Some(state_rc) = self.retained.get::<TextFieldState>(key) {
                                 let mut state = state_rc.borrow_mut();
+                                if let Some(changed) = common::text_area_key(
+                                    &mut state,
+                                    &key_event.logical_key,
+                                    self.modifiers.shift,
+                                ) {
+                                    if changed {
+                                        let new_text = state.text.clone();
+                                        self.notify_text_change(focused_id, new_text);
+                                    }
+                                    self.request_redraw();
+                                    return;
+                                }
                                 match key_event.physical_key {
                                     PhysicalKey::Code(KeyCode::Backspace) => {
-                                        state.delete_backward();
+                                        if self.modifiers.word() {
+                                            state.delete_word_backward();
+                                        } else {
+                                            state.delete_backward();
+                                        }
                                         let new_text = state.text.clone();
                                         self.notify_text_change(focused_id, new_text);
                                         App::tf_ensure_caret_visible(&mut state);
                                         self.request_redraw();
                                     }
                                     PhysicalKey::Code(KeyCode::Delete) => {
-                                        state.delete_forward();
+                                        if self.modifiers.word() {
+                                            state.delete_word_forward();
+                                        } else {
+                                            state.delete_forward();
+                                        }
                                         let new_text = state.text.clone();
                                         self.notify_text_change(focused_id, new_text);
                                         App::tf_ensure_caret_visible(&mut state);
                                         self.request_redraw();
                                     }
+                                    PhysicalKey::Code(KeyCode::ArrowLeft)
+                                        if self.modifiers.word() =>
+                                    {
+                                        state.move_word(false, self.modifiers.shift);
+                                        App::tf_ensure_caret_visible(&mut state);
+                                        self.request_redraw();
+                                    }
+                                    PhysicalKey::Code(KeyCode::ArrowRight)
+                                        if self.modifiers.word() =>
+                                    {
+                                        state.move_word(true, self.modifiers.shift);
+                                        App::tf_ensure_caret_visible(&mut state);
+                                        self.request_redraw();
+                                    }
                                     PhysicalKey::Code(KeyCode::ArrowLeft) => {
                                         state.move_cursor(-1, self.modifiers.shift);
                                         App::tf_ensure_caret_visible(&mut state);
@@ -716,19 +1182,18 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                                         self.request_redraw();
                                     }
                                     PhysicalKey::Code(KeyCode::Home) => {
-                                        state.selection = 0..0;
+                                        state.move_to_edge(false, self.modifiers.shift);
                                         App::tf_ensure_caret_visible(&mut state);
                                         self.request_redraw();
                                     }
                                     PhysicalKey::Code(KeyCode::End) => {
-                                        {
-                                            let end = state.text.len();
-                                            state.selection = end..end;
-                                        }
+                                        state.move_to_edge(true, self.modifiers.shift);
                                         App::tf_ensure_caret_visible(&mut state);
                                         self.request_redraw();
                                     }
-                                    PhysicalKey::Code(KeyCode::KeyA) if self.modifiers.ctrl => {
+                                    PhysicalKey::Code(KeyCode::KeyA)
+                                        if self.modifiers.command() =>
+                                    {
                                         state.selection = 0..state.text.len();
                                         App::tf_ensure_caret_visible(&mut state);
                                         self.request_redraw();
@@ -736,12 +1201,14 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                                     _ => {}
                                 }
                             }
-                            if self.modifiers.ctrl {
+                            if self.modifiers.command() {
                                 match key_event.physical_key {
                                     PhysicalKey::Code(KeyCode::KeyC) => {
                                         if let Some(fid) = self.sched.focused {
                                             let key = self.tf_key_of(fid);
-                                            if let Some(state) = self.textfield_states.get(&key) {
+                                            if let Some(state) =
+                                                self.retained.get::<TextFieldState>(key)
+                                            {
                                                 let txt = state.borrow().selected_text();
                                                 if !txt.is_empty() {
                                                     let _ = self.copy_to_clipboard(txt);
@@ -754,7 +1221,7 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                                         if let Some(fid) = self.sched.focused {
                                             let key = self.tf_key_of(fid);
                                             if let Some(state_rc) =
-                                                self.textfield_states.get(&key).cloned()
+                                                self.retained.get::<TextFieldState>(key)
                                             {
                                                 // Copy
                                                 let txt = state_rc.borrow().selected_text();
@@ -782,13 +1249,14 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                                         if let Some(fid) = self.sched.focused {
                                             let key = self.tf_key_of(fid);
                                             if let Some(state_rc) =
-                                                self.textfield_states.get(&key).cloned()
+                                                self.retained.get::<TextFieldState>(key)
                                                 && let Some(mut txt) = self.paste_from_clipboard()
                                             {
-                                                // Single-line TextField: strip control/newlines
-                                                txt.retain(|c| {
-                                                    !c.is_control() && c != '\n' && c != '\r'
-                                                });
+                                                // Newlines only survive in a TextArea
+                                                txt = repose_ui::textfield::sanitize_input(
+                                                    &txt,
+                                                    state_rc.borrow().multiline,
+                                                );
                                                 if !txt.is_empty() {
                                                     let mut st = state_rc.borrow_mut();
                                                     st.insert_text(&txt);
@@ -801,6 +1269,26 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                                         }
                                         return;
                                     }
+                                    PhysicalKey::Code(KeyCode::KeyZ | KeyCode::KeyY) => {
+                                        let key = self.tf_key_of(focused_id);
+                                        if let Some(state_rc) =
+                                            self.retained.get::<TextFieldState>(key)
+                                        {
+                                            // Ctrl+Shift+Z or Ctrl+Y (Cmd on macOS) redoes.
+                                            let redo = self.modifiers.shift
+                                                || key_event.physical_key
+                                                    == PhysicalKey::Code(KeyCode::KeyY);
+                                            let mut st = state_rc.borrow_mut();
+                                            let changed = if redo { st.redo() } else { st.undo() };
+                                            if changed {
+                                                let new_text = st.text.clone();
+                                                self.notify_text_change(focused_id, new_text);
+                                                App::tf_ensure_caret_visible(&mut st);
+                                                self.request_redraw();
+                                            }
+                                        }
+                                        return;
+                                    }
                                     _ => {}
                                 }
                             }
@@ -821,7 +1309,7 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                                 && let Some(fid) = self.sched.focused
                             {
                                 let key = self.tf_key_of(fid);
-                                if let Some(state_rc) = self.textfield_states.get(&key) {
+                                if let Some(state_rc) = self.retained.get::<TextFieldState>(key) {
                                     let mut st = state_rc.borrow_mut();
                                     st.insert_text(&text);
                                     self.notify_text_change(fid, text.clone());
@@ -864,7 +1352,7 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                     use winit::event::Ime;
                     if let Some(focused_id) = self.sched.focused {
                         let key = self.tf_key_of(focused_id);
-                        if let Some(state_rc) = self.textfield_states.get(&key) {
+                        if let Some(state_rc) = self.retained.get::<TextFieldState>(key) {
                             let mut state = state_rc.borrow_mut();
                             match ime {
                                 Ime::Enabled => {
@@ -937,9 +1425,20 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                     if let (Some(backend), Some(win)) =
                         (self.backend.as_mut(), self.window.as_ref())
                     {
+                        let _span = tracing::info_span!("frame").entered();
                         let t0 = Instant::now();
+                        self.pacer.frame_at(t0);
                         let scale = win.scale_factor() as f32;
                         let size_px_u32 = self.sched.size;
+
+                        let duration = repose_core::locals::platform_settings()
+                            .scroll
+                            .smooth_duration;
+                        if let Some((d, at)) = self.smooth_wheel.step(t0, duration)
+                            && let Some(f) = &self.frame_cache
+                        {
+                            common::dispatch_scroll(f, at, d);
+                        }
                         let focused = self.sched.focused;
 
                         let frame = compose_frame(
@@ -948,10 +1447,15 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
                             scale,
                             size_px_u32,
                             self.hover_id,
+                            self.mouse_in_window.then_some(Vec2 {
+                                x: self.mouse_pos_px.0,
+                                y: self.mouse_pos_px.1,
+                            }),
                             &self.pressed_ids,
-                            &self.textfield_states,
+                            &self.retained,
                             focused,
                         );
+                        crash::record_frame(&frame, &self.sched);
 
                         let build_layout_ms = (Instant::now() - t0).as_secs_f32() * 1000.0;
 
@@ -969,15 +1473,27 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
 
                         // Render
                         let mut scene = frame.scene.clone();
-                        // Update HUD metrics before overlay draws
+                        // Update HUD metrics before overlay draws (GPU time is from the
+                        // previous frame, as this one hasn't been submitted yet).
                         self.inspector.hud.metrics = Some(repose_devtools::Metrics {
                             build_layout_ms,
                             scene_nodes: scene.nodes.len(),
+                            stats: self.sched.jank.last().unwrap_or(frame.stats),
+                            jank_frames: self.sched.jank.janky_frames(),
+                            jank_window: self.sched.jank.recent().count(),
+                            budget_ms: self.sched.jank.budget_ms,
                         });
-                        self.inspector.frame(&mut scene);
+                        self.inspector.frame(&mut scene, &frame);
+                        backend.set_debug_overdraw(self.inspector.overdraw_enabled);
+                        backend.set_power_saving(repose_core::power_saving());
+                        let t_gpu = Instant::now();
                         backend
                             // .lock()
-                            .frame(&scene, GlyphRasterConfig { px: 18.0 * scale });
+                            .frame(&scene, GlyphRasterConfig { scale });
+                        let mut stats = frame.stats;
+                        stats.gpu_ms = t_gpu.elapsed().as_secs_f32() * 1000.0;
+                        self.sched.jank.record(stats);
+                        profiling::finish_frame!();
                         self.frame_cache = Some(frame);
                     }
                 }
@@ -985,8 +1501,87 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
             }
         }
 
-        fn about_to_wait(&mut self, _el: &winit::event_loop::ActiveEventLoop) {
-            self.request_redraw();
+        fn about_to_wait(&mut self, el: &winit::event_loop::ActiveEventLoop) {
+            if let Some((device_id, event)) = self.key_repeat.due(Instant::now())
+                && let Some(id) = self.window.as_ref().map(|w| w.id())
+            {
+                self.window_event(
+                    el,
+                    id,
+                    WindowEvent::KeyboardInput {
+                        device_id,
+                        event,
+                        is_synthetic: false,
+                    },
+                );
+            }
+            #[cfg(target_os = "macos")]
+            if let Some(edited) = DOCUMENT_EDITED.with(std::cell::Cell::take)
+                && let Some(w) = &self.window
+            {
+                use winit::platform::macos::WindowExtMacOS;
+                w.set_document_edited(edited);
+            }
+            let now = Instant::now();
+            self.battery.poll(now);
+            // Queued snapshots, published diagnostics, bus events or woken tasks are waiting for a
+            // frame, or a smooth wheel scroll is under way.
+            if repose_core::snapshots_pending()
+                || repose_core::diagnostics_pending()
+                || repose_core::events_pending()
+                || repose_core::tasks_pending()
+                || self.smooth_wheel.is_active()
+            {
+                el.set_control_flow(ControlFlow::Wait);
+                self.request_redraw();
+                return;
+            }
+            // Animation frames (continuous redraw, `with_frame_nanos` callbacks) are spaced out
+            // while saving power.
+            let focused = self
+                .sched
+                .focused
+                .and_then(|id| self.retained.get::<TextFieldState>(self.tf_key_of(id)));
+            let wake = self
+                .caret_blink
+                .poll(focused.as_ref().map(|s| s.borrow()).as_deref(), now);
+            let repeat_at = self.key_repeat.deadline();
+            if self.options.continuous_redraw || repose_core::frame_requested() {
+                match self.pacer.wait_until(now, repose_core::frame_interval()) {
+                    // A caret flip doesn't wait for the next paced frame.
+                    Some(_) if wake == common::BlinkWake::Redraw => {
+                        el.set_control_flow(ControlFlow::Wait);
+                        self.request_redraw();
+                    }
+                    Some(at) => {
+                        let at = repeat_at.map_or(at, |r| r.min(at));
+                        let at = match wake {
+                            common::BlinkWake::WaitUntil(b) => b.min(at),
+                            _ => at,
+                        };
+                        el.set_control_flow(ControlFlow::WaitUntil(at))
+                    }
+                    None => {
+                        el.set_control_flow(ControlFlow::Wait);
+                        self.request_redraw();
+                    }
+                }
+                return;
+            }
+            // Otherwise the caret blink and key repeat are the only timers: wake for them while
+            // a TextField is focused or a key is held, and sleep otherwise.
+            match wake {
+                common::BlinkWake::Redraw => {
+                    el.set_control_flow(ControlFlow::Wait);
+                    self.request_redraw();
+                }
+                common::BlinkWake::WaitUntil(at) => {
+                    el.set_control_flow(ControlFlow::WaitUntil(repeat_at.map_or(at, |r| r.min(at))))
+                }
+                common::BlinkWake::Idle => {
+                    el.set_control_flow(repeat_at.map_or(ControlFlow::Wait, ControlFlow::WaitUntil))
+                }
+            }
         }
 
         fn new_events(
@@ -1037,9 +1632,13 @@ pub fn run_desktop_app(root: impl FnMut(&mut Scheduler) -> View + 'static) -> an
     }
 
     let event_loop = EventLoop::new()?;
-    let mut app = App::new(Box::new(root));
+    let mut app = App::new(Box::new(root), options);
     // Install system clock once
     repose_core::animation::set_clock(Box::new(repose_core::animation::SystemClock));
+    common::init_locale_from_os();
+    common::install_snapshot_waker(&event_loop);
+    crash::install();
+    common::start_profiler();
     event_loop.run_app(&mut app)?;
     Ok(())
 }
@@ -1069,13 +1668,13 @@ impl A11yBridge for NoopA11y {
     }
     fn focus_changed(&mut self, node: Option<&repose_core::runtime::SemNode>) {
         if let Some(n) = node {
-            log::info!("A11y focus: {:?} {:?}", n.role, n.label);
+            tracing::info!("A11y focus: {:?} {:?}", n.role, n.label);
         } else {
-            log::info!("A11y focus: None");
+            tracing::info!("A11y focus: None");
         }
     }
     fn announce(&mut self, msg: &str) {
-        log::info!("A11y announce: {msg}");
+        tracing::info!("A11y announce: {msg}");
     }
 }
 
@@ -1088,12 +1687,12 @@ impl A11yBridge for LinuxAtspiStub {
     }
     fn focus_changed(&mut self, node: Option<&repose_core::runtime::SemNode>) {
         if let Some(n) = node {
-            log::info!("AT-SPI stub focus: {:?} {:?}", n.role, n.label);
+            tracing::info!("AT-SPI stub focus: {:?} {:?}", n.role, n.label);
         } else {
-            log::info!("AT-SPI stub focus: None");
+            tracing::info!("AT-SPI stub focus: None");
         }
     }
     fn announce(&mut self, msg: &str) {
-        log::info!("AT-SPI stub announce: {msg}");
+        tracing::info!("AT-SPI stub announce: {msg}");
     }
 }
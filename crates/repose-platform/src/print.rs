@@ -0,0 +1,36 @@
+//! Desktop print hook for PDFs made with `repose_ui::print::print`.
+
+use std::io;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Writes `pdf` to the temp directory as `<name>.pdf` and opens it in the system's PDF
+/// viewer, from which the user prints it. Returns where the file was written.
+pub fn open_for_printing(pdf: &[u8], name: &str) -> io::Result<PathBuf> {
+    let file: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || "-_ ".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let path = std::env::temp_dir().join(format!("{file}.pdf"));
+    std::fs::write(&path, pdf)?;
+
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut c = Command::new("cmd");
+        c.args(["/C", "start", ""]);
+        c
+    };
+    #[cfg(target_os = "macos")]
+    let mut cmd = Command::new("open");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut cmd = Command::new("xdg-open");
+
+    cmd.arg(&path).spawn()?;
+    Ok(path)
+}
@@ -3,7 +3,7 @@ use crate::common as rc;
 use crate::*;
 
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -13,13 +13,14 @@ use wasm_bindgen_futures::spawn_local;
 use winit::application::ApplicationHandler;
 use winit::dpi::PhysicalSize;
 use winit::event::{ElementState, Ime, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent};
-use winit::event_loop::{ActiveEventLoop, EventLoop};
+use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
 use winit::keyboard::{KeyCode, PhysicalKey};
 use winit::platform::web::{EventLoopExtWebSys, WindowAttributesExtWebSys, WindowExtWebSys};
 use winit::window::{ImePurpose, Window};
 
+use repose_core::input::PointerId;
 use repose_ui::TextFieldState;
-use repose_ui::textfield::{TF_FONT_DP, TF_PADDING_X_DP, index_for_x_bytes, measure_text};
+use repose_ui::textfield::{TF_FONT_DP, TF_PADDING_X_DP};
 
 #[wasm_bindgen]
 pub struct WebOptions {
@@ -94,11 +95,14 @@ pub fn run_web_app(
     options: WebOptions,
 ) -> Result<(), JsValue> {
     std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+    rc::start_profiler();
     let _ = console_log::init_with_level(log::Level::Info);
 
     repose_core::animation::set_clock(Box::new(repose_core::animation::SystemClock));
+    rc::init_locale_from_os();
 
     let event_loop = EventLoop::new().map_err(|e| JsValue::from_str(&format!("{e:?}")))?;
+    rc::install_snapshot_waker(&event_loop);
     let app = App::new(Box::new(root), options);
 
     event_loop.spawn_app(app);
@@ -117,22 +121,26 @@ struct App {
 
     // pointer + focus
     mouse_pos_px: (f32, f32),
+    mouse_in_window: bool,
     modifiers: Modifiers,
+    key_repeat: rc::KeyRepeat,
+    smooth_wheel: rc::SmoothWheel,
     hover_id: Option<u64>,
-    capture_id: Option<u64>,
-    pressed_ids: HashSet<u64>,
+    captures: rc::PointerCaptures,
 
-    // touch click-cancel after scroll
-    touch_scrolled: bool,
-    touch_scroll_accum_y_px: f32,
-    prev_touch_px: Option<(f32, f32)>,
+    /// The gesture of each finger that's down.
+    touches: HashMap<u64, rc::TouchGesture>,
 
     // text
     ime_preedit: bool,
-    textfield_states: HashMap<u64, Rc<RefCell<TextFieldState>>>,
+    retained: RetainedStateStore,
 
     // runner-provided root scroll
     root_scroll: Rc<RefCell<rc::RootScrollState>>,
+
+    caret_blink: rc::CaretBlinkTimer,
+    pacer: rc::FramePacer,
+    insets: rc::InsetsTracker,
 }
 
 impl App {
@@ -146,19 +154,23 @@ impl App {
             frame_cache: None,
 
             mouse_pos_px: (0.0, 0.0),
+            mouse_in_window: false,
             modifiers: Modifiers::default(),
+            key_repeat: rc::KeyRepeat::default(),
+            smooth_wheel: rc::SmoothWheel::default(),
             hover_id: None,
-            capture_id: None,
-            pressed_ids: HashSet::new(),
+            captures: rc::PointerCaptures::default(),
 
-            touch_scrolled: false,
-            touch_scroll_accum_y_px: 0.0,
-            prev_touch_px: None,
+            touches: HashMap::new(),
 
             ime_preedit: false,
-            textfield_states: HashMap::new(),
+            retained: RetainedStateStore::new(),
 
             root_scroll: Rc::new(RefCell::new(rc::RootScrollState::default())),
+
+            caret_blink: rc::CaretBlinkTimer::default(),
+            pacer: rc::FramePacer::default(),
+            insets: rc::InsetsTracker::default(),
         }
     }
 
@@ -217,8 +229,11 @@ impl App {
         hit_rect: Rect,
     ) {
         let font_px = dp_to_px(TF_FONT_DP) * repose_core::locals::text_scale().0;
-        let m = measure_text(&state.text, font_px);
-        let caret_x_px = m.positions.get(state.caret_index()).copied().unwrap_or(0.0);
+        if state.multiline {
+            state.ensure_caret_visible_y(font_px);
+            return;
+        }
+        let caret_x_px = state.x_for_index(state.caret_index(), font_px);
         let pad = self.padding_px(window);
         state.ensure_caret_visible(caret_x_px, hit_rect.w - 2.0 * pad, dp_to_px(2.0));
     }
@@ -278,6 +293,23 @@ impl App {
         }
     }
 
+    /// The visual viewport in canvas px. Mobile browsers shrink it, not the window, when the
+    /// on-screen keyboard opens.
+    fn visible_rect_px(&self) -> Option<Rect> {
+        if !self.options.fullscreen {
+            return None;
+        }
+        let w = web_sys::window()?;
+        let vv = w.visual_viewport()?;
+        let dpr = w.device_pixel_ratio();
+        Some(Rect {
+            x: (vv.offset_left() * dpr) as f32,
+            y: (vv.offset_top() * dpr) as f32,
+            w: (vv.width() * dpr) as f32,
+            h: (vv.height() * dpr) as f32,
+        })
+    }
+
     fn sync_size_from_window(&mut self, window: &Window) {
         let s = window.inner_size();
         if (s.width, s.height) != self.sched.size {
@@ -336,7 +368,7 @@ impl ApplicationHandler<()> for App {
                     b.configure_surface(s.width, s.height);
                     *backend_cell.borrow_mut() = Some(b);
                     window_for_async.request_redraw();
-                    log::info!("WGPU backend initialized");
+                    tracing::info!("WGPU backend initialized");
                 }
                 Err(e) => {
                     log::error!("WGPU init failed: {e:?}");
@@ -366,15 +398,36 @@ impl ApplicationHandler<()> for App {
                 self.request_redraw();
             }
 
+            WindowEvent::CursorLeft { .. } => {
+                self.mouse_in_window = false;
+                self.request_redraw();
+            }
             WindowEvent::CursorMoved { position, .. } => {
                 self.mouse_pos_px = (position.x as f32, position.y as f32);
+                self.mouse_in_window = true;
 
                 // TextField drag selection (if captured)
-                if let (Some(f), Some(cid)) = (&self.frame_cache, self.capture_id)
+                if let (Some(f), Some(cid)) =
+                    (&self.frame_cache, self.captures.get(rc::MOUSE_POINTER))
                     && self.is_textfield(cid)
                 {
                     let key = self.tf_key_of(cid);
-                    if let Some(state_rc) = self.textfield_states.get(&key) {
+                    let hit_rect = f.hit_regions.iter().find(|h| h.id == cid).map(|h| h.rect);
+                    if let Some(state_rc) = self.retained.get::<TextFieldState>(key)
+                        && state_rc.borrow().multiline
+                        && let Some(field) = hit_rect
+                    {
+                        let mut state = state_rc.borrow_mut();
+                        let pos = Vec2 {
+                            x: self.mouse_pos_px.0,
+                            y: self.mouse_pos_px.1,
+                        };
+                        let (idx, affinity) = rc::text_area_index(&state, field, pos);
+                        state.drag_to(idx);
+                        state.affinity = affinity;
+                        self.tf_ensure_caret_visible_in_hit(&window, &mut state, field);
+                        self.request_redraw();
+                    } else if let Some(state_rc) = self.retained.get::<TextFieldState>(key) {
                         let mut state = state_rc.borrow_mut();
                         let pad = self.padding_px(&window);
 
@@ -387,7 +440,7 @@ impl ApplicationHandler<()> for App {
 
                         let content_x_px = self.mouse_pos_px.0 - inner_x_px + state.scroll_offset;
                         let font_px = dp_to_px(TF_FONT_DP) * repose_core::locals::text_scale().0;
-                        let idx = index_for_x_bytes(&state.text, font_px, content_x_px.max(0.0));
+                        let idx = state.index_for_x(font_px, content_x_px.max(0.0));
                         state.drag_to(idx);
 
                         if let Some(hit) = f.hit_regions.iter().find(|h| h.id == cid) {
@@ -435,7 +488,7 @@ impl ApplicationHandler<()> for App {
                         self.modifiers,
                     );
 
-                    if let Some(cid) = self.capture_id {
+                    if let Some(cid) = self.captures.get(rc::MOUSE_POINTER) {
                         if let Some(i) = rc::hit_index_by_id(f, cid)
                             && let Some(cb) = &f.hit_regions[i].on_pointer_move
                         {
@@ -450,23 +503,21 @@ impl ApplicationHandler<()> for App {
             }
 
             WindowEvent::MouseWheel { delta, .. } => {
-                let scale = self.scale(&window);
-                let (dx_px, dy_px) = match delta {
-                    MouseScrollDelta::LineDelta(x, y) => {
-                        let unit_px = 60.0 * scale;
-                        (-(x * unit_px), -(y * unit_px))
-                    }
-                    MouseScrollDelta::PixelDelta(p) => (-(p.x as f32), -(p.y as f32)),
+                let d = rc::wheel_delta_px(delta, self.scale(&window));
+                let pos = Vec2 {
+                    x: self.mouse_pos_px.0,
+                    y: self.mouse_pos_px.1,
                 };
 
-                if let Some(f) = &self.frame_cache {
-                    let pos = Vec2 {
-                        x: self.mouse_pos_px.0,
-                        y: self.mouse_pos_px.1,
-                    };
-                    if rc::dispatch_scroll(f, pos, Vec2 { x: dx_px, y: dy_px }) {
-                        self.request_redraw();
-                    }
+                if repose_core::locals::platform_settings().scroll.smooth
+                    && matches!(delta, MouseScrollDelta::LineDelta(..))
+                {
+                    self.smooth_wheel.add(d, pos, Instant::now());
+                    self.request_redraw();
+                } else if let Some(f) = &self.frame_cache
+                    && rc::dispatch_scroll(f, pos, d)
+                {
+                    self.request_redraw();
                 }
             }
 
@@ -485,16 +536,13 @@ impl ApplicationHandler<()> for App {
                         ElementState::Pressed => {
                             if let Some(i) = rc::top_hit_index(f, pos) {
                                 let hit = &f.hit_regions[i];
-                                self.capture_id = Some(hit.id);
-                                self.pressed_ids.insert(hit.id);
+                                self.captures.capture(rc::MOUSE_POINTER, hit.id);
 
                                 if hit.focusable {
                                     self.sched.focused = Some(hit.id);
 
                                     let key = self.tf_key_of(hit.id);
-                                    self.textfield_states.entry(key).or_insert_with(|| {
-                                        Rc::new(RefCell::new(TextFieldState::new()))
-                                    });
+                                    self.retained.get_or_insert_with(key, TextFieldState::new);
 
                                     if self.is_textfield(hit.id) {
                                         window.set_ime_allowed(true);
@@ -515,7 +563,20 @@ impl ApplicationHandler<()> for App {
                                 // TextField begin selection
                                 if self.is_textfield(hit.id) {
                                     let key = self.tf_key_of(hit.id);
-                                    if let Some(state_rc) = self.textfield_states.get(&key) {
+                                    if let Some(state_rc) = self.retained.get::<TextFieldState>(key)
+                                        && state_rc.borrow().multiline
+                                    {
+                                        let mut st = state_rc.borrow_mut();
+                                        let (idx, affinity) =
+                                            rc::text_area_index(&st, hit.rect, pos);
+                                        st.begin_drag(idx, self.modifiers.shift);
+                                        st.affinity = affinity;
+                                        self.tf_ensure_caret_visible_in_hit(
+                                            &window, &mut st, hit.rect,
+                                        );
+                                    } else if let Some(state_rc) =
+                                        self.retained.get::<TextFieldState>(key)
+                                    {
                                         let mut st = state_rc.borrow_mut();
                                         let pad = self.padding_px(&window);
                                         let inner_x_px = hit.rect.x + pad;
@@ -524,11 +585,7 @@ impl ApplicationHandler<()> for App {
                                         let font_px = dp_to_px(TF_FONT_DP)
                                             * repose_core::locals::text_scale().0;
 
-                                        let idx = index_for_x_bytes(
-                                            &st.text,
-                                            font_px,
-                                            content_x_px.max(0.0),
-                                        );
+                                        let idx = st.index_for_x(font_px, content_x_px.max(0.0));
                                         st.begin_drag(idx, self.modifiers.shift);
                                         self.tf_ensure_caret_visible_in_hit(
                                             &window, &mut st, hit.rect,
@@ -543,9 +600,7 @@ impl ApplicationHandler<()> for App {
                         }
 
                         ElementState::Released => {
-                            if let Some(cid) = self.capture_id {
-                                self.pressed_ids.remove(&cid);
-
+                            if let Some(cid) = self.captures.release(rc::MOUSE_POINTER) {
                                 if let Some(i) = rc::hit_index_by_id(f, cid)
                                     && let Some(cb) = &f.hit_regions[i].on_pointer_up
                                 {
@@ -567,12 +622,11 @@ impl ApplicationHandler<()> for App {
 
                                 if self.is_textfield(cid) {
                                     let key = self.tf_key_of(cid);
-                                    if let Some(st) = self.textfield_states.get(&key) {
+                                    if let Some(st) = self.retained.get::<TextFieldState>(key) {
                                         st.borrow_mut().end_drag();
                                     }
                                 }
                             }
-                            self.capture_id = None;
                             self.request_redraw();
                         }
                     }
@@ -589,89 +643,98 @@ impl ApplicationHandler<()> for App {
 
                 match t.phase {
                     TouchPhase::Started => {
-                        self.touch_scrolled = false;
-                        self.touch_scroll_accum_y_px = 0.0;
-
                         if let Some(f) = &self.frame_cache {
                             if let Some(i) = rc::top_hit_index(f, pos) {
                                 let hit = &f.hit_regions[i];
-                                self.capture_id = Some(hit.id);
-                                self.pressed_ids.insert(hit.id);
+                                self.captures.capture(PointerId(t.id), hit.id);
 
                                 if let Some(cb) = &hit.on_pointer_down {
-                                    cb(rc::pe_down_primary(
-                                        repose_core::input::PointerKind::Touch,
-                                        pos,
-                                        self.modifiers,
+                                    cb(rc::pe_finger(
+                                        rc::pe_down_primary(
+                                            repose_core::input::PointerKind::Touch,
+                                            pos,
+                                            self.modifiers,
+                                        ),
+                                        t.id,
                                     ));
                                 }
 
                                 if self.is_textfield(hit.id) {
                                     self.sched.focused = Some(hit.id);
                                     let key = self.tf_key_of(hit.id);
-                                    self.textfield_states.entry(key).or_insert_with(|| {
-                                        Rc::new(RefCell::new(TextFieldState::new()))
-                                    });
+                                    self.retained.get_or_insert_with(key, TextFieldState::new);
                                     window.set_ime_allowed(true);
                                     window.set_ime_purpose(ImePurpose::Normal);
                                 }
                             }
                         }
 
-                        self.prev_touch_px = Some(pos_px);
+                        let captured = self.captures.get(PointerId(t.id)).is_some();
+                        self.touches
+                            .insert(t.id, rc::TouchGesture::new(pos, captured));
                         self.request_redraw();
                     }
 
                     TouchPhase::Moved => {
-                        if let (Some(prev), Some(f)) = (self.prev_touch_px, &self.frame_cache) {
-                            let dy_px = pos_px.1 - prev.1;
-                            if dy_px.abs() > 0.0 {
-                                self.touch_scroll_accum_y_px += dy_px;
-
-                                let consumed =
-                                    rc::dispatch_scroll(f, pos, Vec2 { x: 0.0, y: -dy_px });
-
-                                if consumed
-                                    && self.touch_scroll_accum_y_px.abs()
-                                        > self.touch_slop_px(&window)
-                                {
-                                    self.touch_scrolled = true;
-                                }
-                            }
+                        let keep = repose_core::intercept_disallowed(PointerId(t.id));
+                        let (fingers, slop) = (self.touches.len(), self.touch_slop_px(&window));
+                        if let (Some(g), Some(f)) = (self.touches.get_mut(&t.id), &self.frame_cache)
+                            && g.move_to(f, pos, fingers, slop, keep)
+                            && let Some(cid) = self.captures.release(PointerId(t.id))
+                            && let Some(i) = rc::hit_index_by_id(f, cid)
+                            && let Some(cb) = &f.hit_regions[i].on_pointer_up
+                        {
+                            // A scroll container took the gesture.
+                            cb(rc::pe_finger(
+                                rc::pe_touch(
+                                    repose_core::input::PointerEventKind::Cancel,
+                                    pos,
+                                    self.modifiers,
+                                ),
+                                t.id,
+                            ));
+                        }
 
-                            // still deliver pointer_move to captured widget (if any)
-                            if let Some(cid) = self.capture_id
+                        if let Some(f) = &self.frame_cache {
+                            // still deliver pointer_move to the widget this finger captured
+                            if let Some(cid) = self.captures.get(PointerId(t.id))
                                 && let Some(i) = rc::hit_index_by_id(f, cid)
                                 && let Some(cb) = &f.hit_regions[i].on_pointer_move
                             {
-                                cb(rc::pe_touch(
-                                    repose_core::input::PointerEventKind::Move,
-                                    pos,
-                                    self.modifiers,
+                                cb(rc::pe_finger(
+                                    rc::pe_touch(
+                                        repose_core::input::PointerEventKind::Move,
+                                        pos,
+                                        self.modifiers,
+                                    ),
+                                    t.id,
                                 ));
                             }
                         }
 
-                        self.prev_touch_px = Some(pos_px);
                         self.request_redraw();
                     }
 
                     TouchPhase::Ended | TouchPhase::Cancelled => {
-                        if let (Some(f), Some(cid)) = (&self.frame_cache, self.capture_id) {
+                        let capture = self.captures.release(PointerId(t.id));
+                        if let (Some(f), Some(cid)) = (&self.frame_cache, capture) {
                             if let Some(i) = rc::hit_index_by_id(f, cid) {
                                 let hit = &f.hit_regions[i];
 
                                 if let Some(cb) = &hit.on_pointer_up {
-                                    cb(rc::pe_up_primary(
-                                        repose_core::input::PointerKind::Touch,
-                                        pos,
-                                        self.modifiers,
+                                    cb(rc::pe_finger(
+                                        rc::pe_up_primary(
+                                            repose_core::input::PointerKind::Touch,
+                                            pos,
+                                            self.modifiers,
+                                        ),
+                                        t.id,
                                     ));
                                 }
 
-                                // click only if we didn't scroll-drag
+                                // A scroll that took the gesture released the capture, so
+                                // there's no click after a scroll-drag.
                                 if t.phase == TouchPhase::Ended
-                                    && !self.touch_scrolled
                                     && hit.rect.contains(pos)
                                     && let Some(cb) = &hit.on_click
                                 {
@@ -680,9 +743,8 @@ impl ApplicationHandler<()> for App {
                             }
                         }
 
-                        self.capture_id = None;
-                        self.prev_touch_px = None;
-                        self.pressed_ids.clear();
+                        self.touches.remove(&t.id);
+                        repose_core::end_gesture(PointerId(t.id));
                         self.request_redraw();
                     }
                 }
@@ -695,9 +757,33 @@ impl ApplicationHandler<()> for App {
                 self.modifiers.meta = new_mods.state().super_key();
             }
 
+            WindowEvent::Focused(false) => {
+                // The release of a held key goes to whichever window has focus now.
+                self.key_repeat.cancel();
+            }
             WindowEvent::KeyboardInput {
-                event: key_event, ..
+                device_id,
+                event: key_event,
+                ..
             } => {
+                if !self.key_repeat.accept(device_id, &key_event) {
+                    return;
+                }
+                let app_event = rc::key_event_from_winit(&key_event, self.modifiers);
+                if let Some(f) = &self.frame_cache
+                    && rc::dispatch_key(f, self.sched.focused, &app_event)
+                {
+                    self.request_redraw();
+                    return;
+                }
+                if key_event.state == ElementState::Pressed
+                    && !key_event.repeat
+                    && key_event.physical_key == PhysicalKey::Code(KeyCode::Escape)
+                    && self.sched.layers.dismiss_modal()
+                {
+                    self.request_redraw();
+                    return;
+                }
                 // focus traversal: Tab / Shift+Tab
                 if matches!(key_event.physical_key, PhysicalKey::Code(KeyCode::Tab)) {
                     if key_event.state == ElementState::Pressed && !key_event.repeat {
@@ -748,7 +834,7 @@ impl ApplicationHandler<()> for App {
                             && let Some(on_submit) = &f.hit_regions[i].on_text_submit
                         {
                             let key = self.tf_key_of(focused_id);
-                            if let Some(state) = self.textfield_states.get(&key) {
+                            if let Some(state) = self.retained.get::<TextFieldState>(key) {
                                 on_submit(state.borrow().text.clone());
                                 self.request_redraw();
                                 return;
@@ -761,27 +847,53 @@ impl ApplicationHandler<()> for App {
                 if key_event.state == ElementState::Pressed {
                     if let Some(fid) = self.sched.focused {
                         let key = self.tf_key_of(fid);
-                        if let Some(state_rc) = self.textfield_states.get(&key) {
+                        if let Some(state_rc) = self.retained.get::<TextFieldState>(key) {
                             let mut st = state_rc.borrow_mut();
+                            if let Some(changed) = rc::text_area_key(
+                                &mut st,
+                                &key_event.logical_key,
+                                self.modifiers.shift,
+                            ) {
+                                if changed {
+                                    self.notify_text_change(fid, st.text.clone());
+                                }
+                                self.request_redraw();
+                                return;
+                            }
                             match key_event.physical_key {
                                 PhysicalKey::Code(KeyCode::Backspace) => {
-                                    st.delete_backward();
+                                    if self.modifiers.ctrl {
+                                        st.delete_word_backward();
+                                    } else {
+                                        st.delete_backward();
+                                    }
                                     self.notify_text_change(fid, st.text.clone());
                                 }
                                 PhysicalKey::Code(KeyCode::Delete) => {
-                                    st.delete_forward();
+                                    if self.modifiers.ctrl {
+                                        st.delete_word_forward();
+                                    } else {
+                                        st.delete_forward();
+                                    }
                                     self.notify_text_change(fid, st.text.clone());
                                 }
+                                PhysicalKey::Code(KeyCode::ArrowLeft) if self.modifiers.ctrl => {
+                                    st.move_word(false, self.modifiers.shift)
+                                }
+                                PhysicalKey::Code(KeyCode::ArrowRight) if self.modifiers.ctrl => {
+                                    st.move_word(true, self.modifiers.shift)
+                                }
                                 PhysicalKey::Code(KeyCode::ArrowLeft) => {
                                     st.move_cursor(-1, self.modifiers.shift)
                                 }
                                 PhysicalKey::Code(KeyCode::ArrowRight) => {
                                     st.move_cursor(1, self.modifiers.shift)
                                 }
-                                PhysicalKey::Code(KeyCode::Home) => st.selection = 0..0,
+                                PhysicalKey::Code(KeyCode::Home) => {
+                                    st.move_to_edge(false, self.modifiers.shift)
+                                }
                                 PhysicalKey::Code(KeyCode::End) => {
-                                    let end = st.text.len();
-                                    st.selection = end..end;
+                                    st.move_to_edge(true, self.modifiers.shift)
                                 }
                                 PhysicalKey::Code(KeyCode::KeyA)
                                     if self.modifiers.ctrl || self.modifiers.meta =>
@@ -819,7 +931,7 @@ impl ApplicationHandler<()> for App {
                             && let Some(fid) = self.sched.focused
                         {
                             let key = self.tf_key_of(fid);
-                            if let Some(state_rc) = self.textfield_states.get(&key) {
+                            if let Some(state_rc) = self.retained.get::<TextFieldState>(key) {
                                 let mut st = state_rc.borrow_mut();
                                 st.insert_text(&text);
                                 self.notify_text_change(fid, st.text.clone());
@@ -843,7 +955,7 @@ impl ApplicationHandler<()> for App {
             WindowEvent::Ime(ime) => {
                 if let Some(focused_id) = self.sched.focused {
                     let key = self.tf_key_of(focused_id);
-                    if let Some(state_rc) = self.textfield_states.get(&key) {
+                    if let Some(state_rc) = self.retained.get::<TextFieldState>(key) {
                         let mut state = state_rc.borrow_mut();
                         match ime {
                             Ime::Enabled => self.ime_preedit = false,
@@ -912,10 +1024,24 @@ impl ApplicationHandler<()> for App {
                     return;
                 }
 
+                let _span = tracing::info_span!("frame").entered();
+                self.pacer.frame_at(Instant::now());
                 let scale = self.scale(&window);
                 let size_px_u32 = self.sched.size;
                 let focused = self.sched.focused;
 
+                let duration = repose_core::locals::platform_settings()
+                    .scroll
+                    .smooth_duration;
+                if let Some((d, at)) = self.smooth_wheel.step(Instant::now(), duration)
+                    && let Some(f) = &self.frame_cache
+                {
+                    rc::dispatch_scroll(f, at, d);
+                }
+                if let Some(visible) = self.visible_rect_px() {
+                    set_window_insets_default(self.insets.update(size_px_u32, visible, scale));
+                }
+
                 let auto_root_scroll = self.options.auto_root_scroll;
                 let root_scroll = self.root_scroll.clone();
                 let root_fn = &mut self.root;
@@ -935,18 +1061,29 @@ impl ApplicationHandler<()> for App {
                     scale,
                     size_px_u32,
                     self.hover_id,
-                    &self.pressed_ids,
-                    &self.textfield_states,
+                    self.mouse_in_window.then_some(Vec2 {
+                        x: self.mouse_pos_px.0,
+                        y: self.mouse_pos_px.1,
+                    }),
+                    &self.captures.pressed(),
+                    &self.retained,
                     focused,
                 );
 
+                let t_gpu = Instant::now();
                 if let Some(backend) = self.backend.borrow_mut().as_mut() {
-                    backend.frame(&frame.scene, GlyphRasterConfig { px: 18.0 * scale });
+                    backend.set_power_saving(repose_core::power_saving());
+                    backend.frame(&frame.scene, GlyphRasterConfig { scale });
                 }
+                let mut stats = frame.stats;
+                stats.gpu_ms = t_gpu.elapsed().as_secs_f32() * 1000.0;
+                self.sched.jank.record(stats);
+                profiling::finish_frame!();
 
                 self.frame_cache = Some(frame);
 
-                if self.options.continuous_redraw {
+                // While saving power `about_to_wait` paces the next one.
+                if self.options.continuous_redraw && repose_core::frame_interval().is_none() {
                     window.request_redraw();
                 }
             }
@@ -955,8 +1092,75 @@ impl ApplicationHandler<()> for App {
         }
     }
 
-    fn about_to_wait(&mut self, _el: &ActiveEventLoop) {
+    fn about_to_wait(&mut self, el: &ActiveEventLoop) {
+        if let Some((device_id, event)) = self.key_repeat.due(web_time::Instant::now())
+            && let Some(id) = self.window.as_ref().map(|w| w.id())
+        {
+            self.window_event(
+                el,
+                id,
+                WindowEvent::KeyboardInput {
+                    device_id,
+                    event,
+                    is_synthetic: false,
+                },
+            );
+        }
         // Do not request redraw unconditionally.
         // For animations, should set WebOptions.continuous_redraw = true. (currently true for resize issues)
+        let interval = repose_core::frame_interval();
+        if self.options.continuous_redraw && interval.is_none() {
+            return;
+        }
+        // Queued snapshots, published diagnostics, bus events or woken tasks are waiting for a
+        // frame, or a smooth wheel scroll is under way.
+        if repose_core::snapshots_pending()
+            || repose_core::diagnostics_pending()
+            || repose_core::events_pending()
+            || repose_core::tasks_pending()
+            || self.smooth_wheel.is_active()
+        {
+            el.set_control_flow(ControlFlow::Wait);
+            self.request_redraw();
+            return;
+        }
+        // Animation frames (continuous redraw, `with_frame_nanos` callbacks) are spaced out
+        // while saving power.
+        if self.options.continuous_redraw || repose_core::frame_requested() {
+            match self.pacer.wait_until(web_time::Instant::now(), interval) {
+                None => {
+                    el.set_control_flow(ControlFlow::Wait);
+                    self.request_redraw();
+                }
+                Some(at) => {
+                    let at = self.key_repeat.deadline().map_or(at, |r| r.min(at));
+                    el.set_control_flow(ControlFlow::WaitUntil(at));
+                }
+            }
+            return;
+        }
+        // The caret blink and key repeat are the timers the runner owns: wake for them while
+        // a TextField is focused or a key is held, and sleep otherwise.
+        let focused = self
+            .sched
+            .focused
+            .and_then(|id| self.retained.get::<TextFieldState>(self.tf_key_of(id)));
+        let repeat_at = self.key_repeat.deadline();
+        let wake = self.caret_blink.poll(
+            focused.as_ref().map(|s| s.borrow()).as_deref(),
+            web_time::Instant::now(),
+        );
+        match wake {
+            rc::BlinkWake::Redraw => {
+                el.set_control_flow(ControlFlow::Wait);
+                self.request_redraw();
+            }
+            rc::BlinkWake::WaitUntil(at) => {
+                el.set_control_flow(ControlFlow::WaitUntil(repeat_at.map_or(at, |r| r.min(at))))
+            }
+            rc::BlinkWake::Idle => {
+                el.set_control_flow(repeat_at.map_or(ControlFlow::Wait, ControlFlow::WaitUntil))
+            }
+        }
     }
 }
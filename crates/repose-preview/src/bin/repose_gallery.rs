@@ -0,0 +1,3 @@
+fn main() -> anyhow::Result<()> {
+    repose_preview::run_previews(repose_preview::gallery::widget_previews())
+}
@@ -0,0 +1,301 @@
+//! Widget gallery: one preview per built-in widget, wired to the gallery knobs.
+//!
+//! Every interactive widget respects `knobs().enabled` (callbacks become no-ops, semantics
+//! report `enabled: false`), which makes this a convenient surface for manual a11y checks.
+//! The tests below compose and lay out every entry, so a widget that panics or stops
+//! emitting semantics fails CI.
+
+use repose_core::prelude::*;
+use repose_core::signal;
+use repose_ui::lazy::{LazyColumn, LazyColumnState};
+use repose_ui::scroll::{ScrollArea, ScrollDirection, remember_scroll_area_state};
+use repose_ui::*;
+
+use crate::{Previews, knobs};
+
+/// Dims a widget and marks its semantics disabled when the `enabled` knob is off.
+fn gated(mut v: View) -> View {
+    if !knobs().enabled {
+        v.modifier.alpha = Some(0.38);
+        if let Some(s) = v.semantics.as_mut() {
+            s.enabled = false;
+        }
+    }
+    v
+}
+
+fn label(text: impl Into<String>) -> View {
+    Text(text).size(14.0).color(theme().on_surface)
+}
+
+fn text_preview() -> View {
+    let th = theme();
+    Column(Modifier::new().fill_max_width()).child((
+        Text("Headline").size(24.0).color(th.on_surface),
+        Text("Body text wraps across as many lines as it needs to fit the available width.")
+            .color(th.on_surface),
+        Text("A single line that is far too long to fit and gets an ellipsis at the end")
+            .color(th.on_surface)
+            .single_line()
+            .overflow_ellipsize()
+            .modifier(Modifier::new().width(240.0)),
+    ))
+}
+
+fn button_preview() -> View {
+    let clicks = remember_with_key("gallery:button", || signal(0u32));
+    let en = knobs().enabled;
+    Column(Modifier::new()).child((
+        gated(Button(Text("Click me"), {
+            let clicks = clicks.clone();
+            move || {
+                if en {
+                    clicks.update(|c| *c += 1)
+                }
+            }
+        })),
+        label(format!("Clicked {} times", clicks.get())),
+    ))
+}
+
+fn checkbox_preview() -> View {
+    let checked = remember_with_key("gallery:checkbox", || signal(false));
+    let en = knobs().enabled;
+    Row(Modifier::new().align_items(AlignItems::Center)).child((
+        gated(Checkbox(checked.get(), {
+            let checked = checked.clone();
+            move |v| {
+                if en {
+                    checked.set(v)
+                }
+            }
+        })),
+        label("Accept terms"),
+    ))
+}
+
+fn radio_preview() -> View {
+    let selected = remember_with_key("gallery:radio", || signal(0usize));
+    let en = knobs().enabled;
+    Column(Modifier::new()).child(
+        ["Small", "Medium", "Large"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                Row(Modifier::new()
+                    .key(i as u64)
+                    .align_items(AlignItems::Center))
+                .child((
+                    gated(RadioButton(selected.get() == i, {
+                        let selected = selected.clone();
+                        move || {
+                            if en {
+                                selected.set(i)
+                            }
+                        }
+                    })),
+                    label(*name),
+                ))
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+fn switch_preview() -> View {
+    let on = remember_with_key("gallery:switch", || signal(true));
+    let en = knobs().enabled;
+    Row(Modifier::new().align_items(AlignItems::Center)).child((
+        gated(Switch(on.get(), {
+            let on = on.clone();
+            move |v| {
+                if en {
+                    on.set(v)
+                }
+            }
+        })),
+        label(if on.get() { "On" } else { "Off" }),
+    ))
+}
+
+fn slider_preview() -> View {
+    let value = remember_with_key("gallery:slider", || signal(0.4f32));
+    let range = remember_with_key("gallery:range", || signal((20.0f32, 80.0f32)));
+    let en = knobs().enabled;
+    let (lo, hi) = range.get();
+    Column(Modifier::new().width(280.0)).child((
+        label(format!("Value {:.2}", value.get())),
+        gated(Slider(value.get(), (0.0, 1.0), None, {
+            let value = value.clone();
+            move |v| {
+                if en {
+                    value.set(v)
+                }
+            }
+        })),
+        label(format!("Range {lo:.0} – {hi:.0}")),
+        gated(RangeSlider(lo, hi, (0.0, 100.0), Some(5.0), {
+            let range = range.clone();
+            move |a, b| {
+                if en {
+                    range.set((a, b))
+                }
+            }
+        })),
+    ))
+}
+
+fn progress_preview() -> View {
+    Column(Modifier::new().width(280.0)).child((
+        label("Determinate"),
+        ProgressBar(0.6, (0.0, 1.0)),
+        label("Indeterminate"),
+        LinearProgress(None),
+    ))
+}
+
+fn textfield_preview() -> View {
+    let last = remember_with_key("gallery:textfield", || signal(String::new()));
+    let en = knobs().enabled;
+    Column(Modifier::new().width(280.0)).child((
+        gated(TextField(
+            "Type here",
+            Modifier::new().fill_max_width(),
+            Some({
+                let last = last.clone();
+                move |s: String| {
+                    if en {
+                        last.set(s)
+                    }
+                }
+            }),
+            None::<fn(String)>,
+        )),
+        label(format!("Text: {}", last.get())),
+    ))
+}
+
+fn layout_preview() -> View {
+    let th = theme();
+    let tile = |i: usize| {
+        Box(Modifier::new()
+            .key(i as u64)
+            .size(48.0, 48.0)
+            .background(th.primary)
+            .clip_rounded(6.0))
+    };
+    Column(Modifier::new()).child((
+        label("Row"),
+        Row(Modifier::new()).child((0..4).map(tile).collect::<Vec<_>>()),
+        label("Grid"),
+        Grid(3, Modifier::new(), (0..6).map(tile).collect(), 4.0, 4.0),
+        label("Stack"),
+        Stack(Modifier::new().size(64.0, 64.0)).child((
+            Box(Modifier::new().fill_max_size().background(th.outline)),
+            Box(Modifier::new().size(24.0, 24.0).background(th.primary)),
+        )),
+    ))
+}
+
+fn scroll_preview() -> View {
+    let state = remember_scroll_area_state("gallery:scroll");
+    ScrollArea(
+        ScrollDirection::Vertical,
+        state,
+        Modifier::new().height(240.0).fill_max_width(),
+    )
+    .child(
+        Column(Modifier::new().fill_max_width()).child(
+            (0..40)
+                .map(|i| label(format!("Row {i}")).modifier(Modifier::new().key(i).padding(4.0)))
+                .collect::<Vec<_>>(),
+        ),
+    )
+}
+
+fn lazy_preview() -> View {
+    let state = remember_with_key("gallery:lazy", LazyColumnState::new);
+    LazyColumn(
+        (0..1000).collect::<Vec<u32>>(),
+        32.0,
+        state,
+        Modifier::new().height(240.0).fill_max_width(),
+        |i, _| label(format!("Item {i}")),
+    )
+}
+
+/// Previews for every built-in widget, grouped by category.
+pub fn widget_previews() -> Previews {
+    Previews::new()
+        .add_in("Text", "Text", text_preview)
+        .add_in("Input", "Button", button_preview)
+        .add_in("Input", "Checkbox", checkbox_preview)
+        .add_in("Input", "RadioButton", radio_preview)
+        .add_in("Input", "Switch", switch_preview)
+        .add_in("Input", "Slider", slider_preview)
+        .add_in("Input", "TextField", textfield_preview)
+        .add_in("Feedback", "Progress", progress_preview)
+        .add_in("Layout", "Row / Grid / Stack", layout_preview)
+        .add_in("Layout", "ScrollArea", scroll_preview)
+        .add_in("Layout", "LazyColumn", lazy_preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use repose_core::{RetainedStateStore, SemNode, TextDirection, with_text_direction};
+
+    use super::*;
+    use crate::{Knobs, with_knobs};
+
+    fn render(k: Knobs, p: &crate::Preview) -> (Scene, Vec<SemNode>) {
+        let view = WithTextScale(k.text_scale, || with_knobs(k, || p.build()));
+        let dir = if k.rtl {
+            TextDirection::Rtl
+        } else {
+            TextDirection::Ltr
+        };
+        let (scene, _hits, sems) = with_text_direction(dir, || {
+            layout_and_paint(
+                &view,
+                (800, 600),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            )
+        });
+        (scene, sems)
+    }
+
+    #[test]
+    fn every_widget_preview_lays_out() {
+        for p in widget_previews().iter() {
+            for k in [
+                Knobs::default(),
+                Knobs {
+                    rtl: true,
+                    text_scale: 2.0,
+                    ..Knobs::default()
+                },
+            ] {
+                let (scene, _) = render(k, p);
+                assert!(!scene.nodes.is_empty(), "{} painted nothing", p.name);
+            }
+        }
+    }
+
+    #[test]
+    fn disabled_knob_reaches_semantics() {
+        let disabled = Knobs {
+            enabled: false,
+            ..Knobs::default()
+        };
+        for p in widget_previews().iter() {
+            let (_, sems) = render(disabled, p);
+            for s in sems
+                .iter()
+                .filter(|s| !matches!(s.role, Role::Text | Role::ProgressBar))
+            {
+                assert!(!s.enabled, "{}: {:?} still enabled", p.name, s.role);
+            }
+        }
+    }
+}
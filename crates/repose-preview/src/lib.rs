@@ -0,0 +1,329 @@
+//! # Previews
+//!
+//! A small Storybook-like harness: register closures (or plain `fn() -> View`s via
+//! [`previews!`]) and get a gallery with a sidebar listing them, plus live theme,
+//! density, text-scale, RTL and enabled knobs. The selected preview is recomposed every
+//! frame like any other view, so state changes inside it re-render automatically.
+//! Previews read the current knob values with [`knobs`].
+//!
+//! With the `gallery` feature (on by default), [`gallery::widget_previews`] registers
+//! every built-in widget; `cargo run -p repose-preview --features desktop --bin
+//! repose_gallery` opens it.
+//!
+//! ```rust,ignore
+//! use repose_preview::{Previews, previews};
+//!
+//! fn primary_button() -> View {
+//!     Button(Text("Primary"), || {})
+//! }
+//!
+//! fn main() -> anyhow::Result<()> {
+//!     let previews = previews![primary_button]
+//!         .add_in("Text", "Long paragraph", || Text("Lorem ipsum ...").max_lines(2));
+//!     repose_preview::run_previews(previews)
+//! }
+//! ```
+#![allow(non_snake_case)]
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use repose_core::prelude::*;
+use repose_core::{
+    TextDirection, set_text_direction_default, set_theme_default, signal, with_theme,
+};
+use repose_ui::*;
+
+#[cfg(feature = "gallery")]
+pub mod gallery;
+
+/// Knob values the gallery applies while building the selected preview.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Knobs {
+    pub enabled: bool,
+    pub dark: bool,
+    pub rtl: bool,
+    pub density: f32,
+    pub text_scale: f32,
+}
+
+impl Default for Knobs {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dark: true,
+            rtl: false,
+            density: 1.0,
+            text_scale: 1.0,
+        }
+    }
+}
+
+thread_local! {
+    static KNOBS: Cell<Knobs> = Cell::new(Knobs::default());
+}
+
+/// Current knob values; outside of [`PreviewGallery`] this is `Knobs::default()`.
+pub fn knobs() -> Knobs {
+    KNOBS.with(|k| k.get())
+}
+
+/// Runs `f` with `k` as the current knobs (used by the gallery, and by tests).
+pub fn with_knobs<R>(k: Knobs, f: impl FnOnce() -> R) -> R {
+    let prev = KNOBS.with(|c| c.replace(k));
+    let out = f();
+    KNOBS.with(|c| c.set(prev));
+    out
+}
+
+/// A single named preview.
+#[derive(Clone)]
+pub struct Preview {
+    pub name: String,
+    pub group: Option<String>,
+    build: Rc<dyn Fn() -> View>,
+}
+
+impl Preview {
+    pub fn new(name: impl Into<String>, build: impl Fn() -> View + 'static) -> Self {
+        Self {
+            name: name.into(),
+            group: None,
+            build: Rc::new(build),
+        }
+    }
+
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    pub fn build(&self) -> View {
+        (self.build)()
+    }
+}
+
+/// Ordered collection of previews shown by [`PreviewGallery`].
+#[derive(Clone, Default)]
+pub struct Previews {
+    items: Vec<Preview>,
+}
+
+impl Previews {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(mut self, name: impl Into<String>, build: impl Fn() -> View + 'static) -> Self {
+        self.items.push(Preview::new(name, build));
+        self
+    }
+
+    pub fn add_in(
+        mut self,
+        group: impl Into<String>,
+        name: impl Into<String>,
+        build: impl Fn() -> View + 'static,
+    ) -> Self {
+        self.items.push(Preview::new(name, build).group(group));
+        self
+    }
+
+    pub fn push(&mut self, preview: Preview) {
+        self.items.push(preview);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Preview> {
+        self.items.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+/// Registers plain preview functions, using each function's name as the preview title.
+///
+/// `previews![button, card]` is `Previews::new().add("button", button).add("card", card)`.
+#[macro_export]
+macro_rules! previews {
+    ($($f:path),* $(,)?) => {
+        $crate::Previews::new()$(.add(stringify!($f), $f))*
+    };
+}
+
+/// Light theme used by the gallery's theme toggle.
+pub fn light_theme() -> Theme {
+    Theme {
+        background: Color::from_hex("#FAFAFA"),
+        surface: Color::from_hex("#FFFFFF"),
+        on_surface: Color::from_hex("#222222"),
+        primary: Color::from_hex("#3B82F6"),
+        on_primary: Color::WHITE,
+        outline: Color::from_hex("#DDDDDD"),
+        focus: Color::from_hex("#2563EB"),
+        button_bg: Color::from_hex("#3B82F6"),
+        button_bg_hover: Color::from_hex("#2563EB"),
+        button_bg_pressed: Color::from_hex("#1D4ED8"),
+        scrollbar_track: Color(0, 0, 0, 20),
+        scrollbar_thumb: Color(0, 0, 0, 80),
+        ..Theme::default()
+    }
+}
+
+fn gallery_theme(dark: bool) -> Theme {
+    if dark {
+        Theme::default()
+    } else {
+        light_theme()
+    }
+}
+
+/// Gallery root: sidebar of previews, knobs toolbar, and the selected preview.
+///
+/// Density is a multiplier over the host window's density; text scale is absolute.
+pub fn PreviewGallery(previews: Rc<Previews>) -> View {
+    let selected = remember_with_key("repose_preview:selected", || signal(0usize));
+    // The defaults follow the toggles, so they're only written when one flips (and once
+    // up front), not on every frame.
+    let dark = remember_with_key("repose_preview:dark", || {
+        set_theme_default(gallery_theme(true));
+        signal(true)
+    });
+    let zoom = remember_with_key("repose_preview:density", || signal(1.0f32));
+    let text_scale = remember_with_key("repose_preview:text_scale", || signal(1.0f32));
+    let rtl = remember_with_key("repose_preview:rtl", || {
+        set_text_direction_default(TextDirection::Ltr);
+        signal(false)
+    });
+    let enabled = remember_with_key("repose_preview:enabled", || signal(true));
+
+    let th = gallery_theme(dark.get());
+    let current = selected.get().min(previews.len().saturating_sub(1));
+
+    let sidebar = Column(
+        Modifier::new()
+            .width(220.0)
+            .fill_max_height()
+            .background(th.surface)
+            .border(1.0, th.outline, 0.0)
+            .padding(8.0),
+    )
+    .child(
+        previews
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let title = match &p.group {
+                    Some(g) => format!("{g} / {}", p.name),
+                    None => p.name.clone(),
+                };
+                let fg = if i == current {
+                    th.primary
+                } else {
+                    th.on_surface
+                };
+                Button(Text(title).size(14.0).color(fg).single_line(), {
+                    let selected = selected.clone();
+                    move || selected.set(i)
+                })
+                .modifier(Modifier::new().key(i as u64).fill_max_width().padding(4.0))
+            })
+            .collect::<Vec<_>>(),
+    );
+
+    let toolbar = Row(Modifier::new()
+        .background(th.surface)
+        .border(1.0, th.outline, 0.0)
+        .padding(8.0)
+        .align_items(AlignItems::Center))
+    .child(vec![
+        Text("Dark").size(14.0).color(th.on_surface),
+        Switch(dark.get(), {
+            let dark = dark.clone();
+            move |v| {
+                set_theme_default(gallery_theme(v));
+                dark.set(v)
+            }
+        }),
+        Box(Modifier::new().width(16.0).height(1.0)),
+        Text("RTL").size(14.0).color(th.on_surface),
+        Switch(rtl.get(), {
+            let rtl = rtl.clone();
+            move |v| {
+                set_text_direction_default(if v {
+                    TextDirection::Rtl
+                } else {
+                    TextDirection::Ltr
+                });
+                rtl.set(v)
+            }
+        }),
+        Box(Modifier::new().width(16.0).height(1.0)),
+        Text("Enabled").size(14.0).color(th.on_surface),
+        Switch(enabled.get(), {
+            let enabled = enabled.clone();
+            move |v| enabled.set(v)
+        }),
+        Box(Modifier::new().width(16.0).height(1.0)),
+        Text(format!("Density {:.2}", zoom.get()))
+            .size(14.0)
+            .color(th.on_surface),
+        Slider(zoom.get(), (0.5, 3.0), Some(0.25), {
+            let zoom = zoom.clone();
+            move |v| zoom.set(v)
+        })
+        .modifier(Modifier::new().width(160.0)),
+        Box(Modifier::new().width(16.0).height(1.0)),
+        Text(format!("Text {:.2}", text_scale.get()))
+            .size(14.0)
+            .color(th.on_surface),
+        Slider(text_scale.get(), (0.5, 3.0), Some(0.25), {
+            let text_scale = text_scale.clone();
+            move |v| text_scale.set(v)
+        })
+        .modifier(Modifier::new().width(160.0)),
+    ]);
+
+    let k = Knobs {
+        enabled: enabled.get(),
+        dark: dark.get(),
+        rtl: rtl.get(),
+        density: zoom.get(),
+        text_scale: text_scale.get(),
+    };
+    let content = match previews.iter().nth(current) {
+        Some(p) => WithDensity(density().scale * k.density, || {
+            WithTextScale(k.text_scale, || {
+                with_theme(th, || with_knobs(k, || p.build()))
+            })
+        }),
+        None => Text("No previews registered").color(th.on_surface),
+    };
+
+    Surface(
+        Modifier::new().fill_max_size().background(th.background),
+        Row(Modifier::new().fill_max_size()).child((
+            sidebar,
+            Column(Modifier::new().fill_max_size()).child((
+                toolbar,
+                Box(Modifier::new()
+                    .fill_max_size()
+                    .padding(16.0)
+                    .clip_rounded(0.0))
+                .child(content),
+            )),
+        )),
+    )
+}
+
+/// Opens a desktop window hosting [`PreviewGallery`].
+#[cfg(feature = "desktop")]
+pub fn run_previews(previews: Previews) -> anyhow::Result<()> {
+    let previews = Rc::new(previews);
+    repose_platform::run_desktop_app(move |_s| PreviewGallery(previews.clone()))
+}
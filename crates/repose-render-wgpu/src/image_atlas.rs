@@ -0,0 +1,351 @@
+//! Shared texture for small images (icons, thumbnails), so consecutive small images draw in
+//! one call without switching bind groups. Images up to [`MAX_ATLAS_IMAGE`] px on a side are
+//! packed into shelves; anything larger, or anything that doesn't fit once the atlas is at
+//! its largest, gets a texture of its own.
+//!
+//! Freed slots leave holes in their shelf. When an insert doesn't fit and enough space is
+//! wasted, the live images are repacked into a fresh texture (copied on the GPU) before the
+//! atlas grows.
+
+use std::collections::HashMap;
+
+/// Largest width or height packed into the atlas.
+pub(crate) const MAX_ATLAS_IMAGE: u32 = 256;
+
+const INITIAL_SIZE: u32 = 1024;
+const MAX_SIZE: u32 = 4096;
+/// Space left between images, so linear sampling doesn't bleed.
+const GAP: u32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Slot {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+struct Shelf {
+    y: u32,
+    h: u32,
+    next_x: u32,
+}
+
+/// Where images sit in the atlas; no GPU state.
+pub(crate) struct ShelfPacker {
+    size: u32,
+    shelves: Vec<Shelf>,
+    slots: HashMap<u64, Slot>,
+    /// Area of freed slots not reused yet.
+    wasted: u64,
+}
+
+impl ShelfPacker {
+    pub(crate) fn new(size: u32) -> Self {
+        Self {
+            size,
+            shelves: Vec::new(),
+            slots: HashMap::new(),
+            wasted: 0,
+        }
+    }
+
+    pub(crate) fn size(&self) -> u32 {
+        self.size
+    }
+
+    pub(crate) fn get(&self, id: u64) -> Option<Slot> {
+        self.slots.get(&id).copied()
+    }
+
+    /// Places a `w`×`h` image for `id`; `None` when it doesn't fit.
+    pub(crate) fn alloc(&mut self, id: u64, w: u32, h: u32) -> Option<Slot> {
+        let (gw, gh) = (w + GAP, h + GAP);
+        if gw > self.size || gh > self.size {
+            return None;
+        }
+        // The shortest shelf that takes it, so tall shelves aren't filled with small icons.
+        let shelf = self
+            .shelves
+            .iter_mut()
+            .filter(|s| s.h >= gh && s.next_x + gw <= self.size)
+            .min_by_key(|s| s.h);
+        let shelf = match shelf {
+            Some(s) => s,
+            None => {
+                let y = self.shelves.last().map_or(0, |s| s.y + s.h);
+                if y + gh > self.size {
+                    return None;
+                }
+                self.shelves.push(Shelf {
+                    y,
+                    h: gh,
+                    next_x: 0,
+                });
+                self.shelves.last_mut().unwrap()
+            }
+        };
+        let slot = Slot {
+            x: shelf.next_x,
+            y: shelf.y,
+            w,
+            h,
+        };
+        shelf.next_x += gw;
+        self.slots.insert(id, slot);
+        Some(slot)
+    }
+
+    pub(crate) fn free(&mut self, id: u64) -> Option<Slot> {
+        let slot = self.slots.remove(&id)?;
+        self.wasted += u64::from(slot.w + GAP) * u64::from(slot.h + GAP);
+        Some(slot)
+    }
+
+    /// Whether repacking would free at least `w`×`h`.
+    pub(crate) fn worth_repacking_for(&self, w: u32, h: u32) -> bool {
+        self.wasted >= u64::from(w + GAP) * u64::from(h + GAP)
+    }
+
+    /// Packs the live slots into a fresh `size` layout, tallest first. Returns the new
+    /// packer and each image's old and new slot, or `None` if they don't fit.
+    pub(crate) fn repack(&self, size: u32) -> Option<(ShelfPacker, Vec<(Slot, Slot)>)> {
+        let mut ids: Vec<(&u64, &Slot)> = self.slots.iter().collect();
+        ids.sort_by_key(|(id, s)| (std::cmp::Reverse(s.h), **id));
+        let mut packed = ShelfPacker::new(size);
+        let mut moves = Vec::with_capacity(ids.len());
+        for (&id, &old) in ids {
+            let new = packed.alloc(id, old.w, old.h)?;
+            moves.push((old, new));
+        }
+        Some((packed, moves))
+    }
+}
+
+/// The atlas texture and its layout.
+pub(crate) struct ImageAtlas {
+    pub tex: wgpu::Texture,
+    pub bind: wgpu::BindGroup,
+    sampler: wgpu::Sampler,
+    pub packer: ShelfPacker,
+}
+
+impl ImageAtlas {
+    pub(crate) fn new(device: &wgpu::Device, layout: &wgpu::BindGroupLayout) -> Self {
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("image atlas sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::MipmapFilterMode::Linear,
+            ..Default::default()
+        });
+        let (tex, bind) = Self::create_texture(device, layout, &sampler, INITIAL_SIZE);
+        Self {
+            tex,
+            bind,
+            sampler,
+            packer: ShelfPacker::new(INITIAL_SIZE),
+        }
+    }
+
+    fn create_texture(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        size: u32,
+    ) -> (wgpu::Texture, wgpu::BindGroup) {
+        let tex = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("image atlas"),
+            size: wgpu::Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = tex.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("image atlas bind"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        });
+        (tex, bind)
+    }
+
+    /// Uploads `rgba` (sRGB) for `id`. Returns `false` when it should get its own texture
+    /// instead: too large, or no room even after repacking and growing.
+    pub(crate) fn insert(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        layout: &wgpu::BindGroupLayout,
+        id: u64,
+        rgba: &image::RgbaImage,
+    ) -> bool {
+        let (w, h) = rgba.dimensions();
+        if w > MAX_ATLAS_IMAGE || h > MAX_ATLAS_IMAGE {
+            return false;
+        }
+        let slot = match self.packer.alloc(id, w, h) {
+            Some(s) => s,
+            None => {
+                let size = self.packer.size();
+                let mut fits = false;
+                if self.packer.worth_repacking_for(w, h) {
+                    fits = self.rebuild(device, queue, layout, size);
+                    fits = fits && self.packer.alloc(id, w, h).is_some();
+                }
+                let mut size = size;
+                while !fits && size < MAX_SIZE {
+                    size *= 2;
+                    fits = self.rebuild(device, queue, layout, size)
+                        && self.packer.alloc(id, w, h).is_some();
+                }
+                match self.packer.get(id) {
+                    Some(s) if fits => s,
+                    _ => return false,
+                }
+            }
+        };
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfoBase {
+                texture: &self.tex,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: slot.x,
+                    y: slot.y,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            rgba,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * w),
+                rows_per_image: Some(h),
+            },
+            wgpu::Extent3d {
+                width: w,
+                height: h,
+                depth_or_array_layers: 1,
+            },
+        );
+        true
+    }
+
+    /// Repacks the live images into a new `size` texture, copying them over on the GPU.
+    fn rebuild(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        layout: &wgpu::BindGroupLayout,
+        size: u32,
+    ) -> bool {
+        let Some((packer, moves)) = self.packer.repack(size) else {
+            return false;
+        };
+        let (tex, bind) = Self::create_texture(device, layout, &self.sampler, size);
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("image atlas repack"),
+        });
+        for (old, new) in moves {
+            encoder.copy_texture_to_texture(
+                wgpu::TexelCopyTextureInfoBase {
+                    texture: &self.tex,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d {
+                        x: old.x,
+                        y: old.y,
+                        z: 0,
+                    },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                wgpu::TexelCopyTextureInfoBase {
+                    texture: &tex,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d {
+                        x: new.x,
+                        y: new.y,
+                        z: 0,
+                    },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                wgpu::Extent3d {
+                    width: old.w,
+                    height: old.h,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+        log::debug!("image atlas repacked into {size}x{size}");
+        self.tex = tex;
+        self.bind = bind;
+        self.packer = packer;
+        true
+    }
+
+    /// Maps `uv` (`[u0, v1, u1, v0]` within the image, as the color pipeline takes it) into
+    /// the atlas, inset by half a texel so neighbours don't bleed in.
+    pub(crate) fn uv(&self, id: u64, uv: [f32; 4]) -> Option<[f32; 4]> {
+        let s = self.packer.get(id)?;
+        let size = self.packer.size() as f32;
+        let (x0, y0) = (s.x as f32 + 0.5, s.y as f32 + 0.5);
+        let (w, h) = ((s.w as f32 - 1.0).max(0.0), (s.h as f32 - 1.0).max(0.0));
+        Some([
+            (x0 + uv[0] * w) / size,
+            (y0 + uv[1] * h) / size,
+            (x0 + uv[2] * w) / size,
+            (y0 + uv[3] * h) / size,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn freed_space_is_reclaimed_by_repacking() {
+        let mut p = ShelfPacker::new(64);
+        // Two rows of 15×15 (16 with the gap), four per row.
+        for id in 0..8 {
+            assert!(p.alloc(id, 15, 15).is_some());
+        }
+        assert_eq!(p.alloc(100, 7, 7).map(|s| s.y), Some(32));
+        assert_eq!(p.alloc(101, 40, 40), None);
+        for id in [0, 1, 2, 3, 4, 5] {
+            p.free(id);
+        }
+        assert!(p.worth_repacking_for(31, 31));
+        assert!(!p.worth_repacking_for(63, 63));
+
+        let (mut packed, moves) = p.repack(64).unwrap();
+        assert_eq!(moves.len(), p.slots.len());
+        // What's left shares the first shelf now, making room for a big one.
+        assert!(packed.alloc(101, 40, 40).is_some());
+        for id in [6, 7, 100] {
+            let s = packed.get(id).unwrap();
+            assert_eq!(s.y, 0);
+        }
+    }
+}
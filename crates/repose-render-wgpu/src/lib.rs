@@ -6,7 +6,11 @@ use repose_core::{Brush, GlyphRasterConfig, RenderBackend, Scene, SceneNode, Tra
 use std::panic::{AssertUnwindSafe, catch_unwind};
 use wgpu::Instance;
 
+mod image_atlas;
+mod overdraw;
+
 static ROT_WARN_ONCE: Once = Once::new();
+static STACK_WARN_ONCE: Once = Once::new();
 
 #[derive(Clone)]
 struct UploadRing {
@@ -72,6 +76,7 @@ pub struct WgpuBackend {
     border_pipeline: wgpu::RenderPipeline,
     ellipse_pipeline: wgpu::RenderPipeline,
     ellipse_border_pipeline: wgpu::RenderPipeline,
+    shape_pipeline: wgpu::RenderPipeline,
     text_pipeline_mask: wgpu::RenderPipeline,
     text_pipeline_color: wgpu::RenderPipeline,
     text_bind_layout: wgpu::BindGroupLayout,
@@ -85,16 +90,35 @@ pub struct WgpuBackend {
     ring_border: UploadRing,
     ring_ellipse: UploadRing,
     ring_ellipse_border: UploadRing,
+    ring_shape: UploadRing,
     ring_glyph_mask: UploadRing,
     ring_glyph_color: UploadRing,
 
     next_image_handle: u64,
     images: std::collections::HashMap<u64, ImageTex>,
+    /// Small images, drawn together without switching textures.
+    image_atlas: image_atlas::ImageAtlas,
+
+    /// Tint frames by how many times each pixel is written.
+    debug_overdraw: bool,
+    /// Present mode used when not saving power (`Fifo` is used when saving it).
+    fast_present_mode: wgpu::PresentMode,
+
+    /// Shaped text drawn last frame, by interned text id, pixel size and weight.
+    text_runs: HashMap<(u64, u32, u16), TextRun>,
+    /// `repose_text::font_generation()` the runs were shaped under.
+    font_generation: u64,
+}
+
+struct TextRun {
+    glyphs: std::sync::Arc<[repose_text::ShapedGlyph]>,
+    /// Grapheme byte offsets, computed the first time the run is drawn with letter spacing.
+    graphemes: Option<Vec<usize>>,
 }
 
 struct ImageTex {
-    view: wgpu::TextureView,
-    bind: wgpu::BindGroup,
+    /// The image's own texture; `None` when it lives in the image atlas.
+    bind: Option<wgpu::BindGroup>,
     w: u32,
     h: u32,
 }
@@ -184,6 +208,18 @@ struct EllipseBorderInstance {
     color: [f32; 4],
 }
 
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ShapeInstance {
+    // bounding rect in NDC
+    xywh: [f32; 4],
+    // shape parameters in framebuffer px; see shape.wgsl
+    a: [f32; 4],
+    b: [f32; 4],
+    // rgba (linear)
+    color: [f32; 4],
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
 struct GlyphInstance {
@@ -661,6 +697,67 @@ impl WgpuBackend {
             cache: None,
         });
 
+        // Pipelines: lines and arcs
+        let shape_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("shape.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("shaders/shape.wgsl"))),
+        });
+        let shape_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("shape pipeline layout"),
+            bind_group_layouts: &[],
+            immediate_size: 0,
+        });
+        let shape_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("shape pipeline"),
+            layout: Some(&shape_layout),
+            vertex: wgpu::VertexState {
+                module: &shape_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<ShapeInstance>() as u64,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &[
+                        wgpu::VertexAttribute {
+                            shader_location: 0,
+                            offset: 0,
+                            format: wgpu::VertexFormat::Float32x4,
+                        },
+                        wgpu::VertexAttribute {
+                            shader_location: 1,
+                            offset: 16,
+                            format: wgpu::VertexFormat::Float32x4,
+                        },
+                        wgpu::VertexAttribute {
+                            shader_location: 2,
+                            offset: 32,
+                            format: wgpu::VertexFormat::Float32x4,
+                        },
+                        wgpu::VertexAttribute {
+                            shader_location: 3,
+                            offset: 48,
+                            format: wgpu::VertexFormat::Float32x4,
+                        },
+                    ],
+                }],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shape_shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview_mask: None,
+            cache: None,
+        });
+
         // Atlases
         let atlas_mask = Self::init_atlas_mask(&device)?;
         let atlas_color = Self::init_atlas_color(&device)?;
@@ -670,9 +767,11 @@ impl WgpuBackend {
         let ring_border = UploadRing::new(&device, "ring border", 1 << 20);
         let ring_ellipse = UploadRing::new(&device, "ring ellipse", 1 << 20);
         let ring_ellipse_border = UploadRing::new(&device, "ring ellipse border", 1 << 20);
+        let ring_shape = UploadRing::new(&device, "ring shape", 1 << 20);
         let ring_glyph_mask = UploadRing::new(&device, "ring glyph mask", 1 << 20);
         let ring_glyph_color = UploadRing::new(&device, "ring glyph color", 1 << 20);
 
+        let image_atlas = image_atlas::ImageAtlas::new(&device, &text_bind_layout);
         Ok(Self {
             surface,
             device,
@@ -685,19 +784,48 @@ impl WgpuBackend {
             text_bind_layout,
             ellipse_pipeline,
             ellipse_border_pipeline,
+            shape_pipeline,
             atlas_mask,
             atlas_color,
             ring_rect,
             ring_border,
             ring_ellipse,
             ring_ellipse_border,
+            ring_shape,
             ring_glyph_color,
             ring_glyph_mask,
             next_image_handle: 1,
             images: HashMap::new(),
+            image_atlas,
+            debug_overdraw: false,
+            fast_present_mode: present_mode,
+            text_runs: HashMap::new(),
+            font_generation: repose_text::font_generation(),
         })
     }
 
+    /// Overdraw debug view: frames are tinted by how many times each pixel is written
+    /// (blue once, green twice, red three times or more).
+    pub fn set_debug_overdraw(&mut self, on: bool) {
+        self.debug_overdraw = on;
+    }
+
+    /// While saving power, present with vsync (`Fifo`) rather than the lower-latency mode
+    /// picked at startup. Reconfigures the surface only when the mode changes.
+    pub fn set_power_saving(&mut self, on: bool) {
+        let mode = if on {
+            wgpu::PresentMode::Fifo
+        } else {
+            self.fast_present_mode
+        };
+        if self.config.present_mode != mode {
+            self.config.present_mode = mode;
+            if self.config.width > 0 && self.config.height > 0 {
+                self.surface.configure(&self.device, &self.config);
+            }
+        }
+    }
+
     /// Native/blocking convenience.
     #[cfg(not(target_arch = "wasm32"))]
     pub fn new(window: Arc<winit::window::Window>) -> anyhow::Result<Self> {
@@ -710,17 +838,45 @@ impl WgpuBackend {
         anyhow::bail!("Use WgpuBackend::new_async(window).await on wasm32")
     }
 
+    /// Decodes `data` and uploads it, returning the handle for `SceneNode::Image`. Small
+    /// sRGB images share a texture atlas; others get a texture of their own.
     pub fn register_image_from_bytes(&mut self, data: &[u8], srgb: bool) -> u64 {
         // Decode via image crate
         let img = image::load_from_memory(data).expect("decode image");
         let rgba = img.to_rgba8();
         let (w, h) = rgba.dimensions();
-        // Texture format
+        let handle = self.next_image_handle;
+        self.next_image_handle += 1;
+        if srgb
+            && self.image_atlas.insert(
+                &self.device,
+                &self.queue,
+                &self.text_bind_layout,
+                handle,
+                &rgba,
+            )
+        {
+            self.images.insert(handle, ImageTex { bind: None, w, h });
+            return handle;
+        }
         let format = if srgb {
             wgpu::TextureFormat::Rgba8UnormSrgb
         } else {
             wgpu::TextureFormat::Rgba8Unorm
         };
+        self.upload_image(handle, w, h, &rgba, format);
+        handle
+    }
+
+    /// Puts `rgba` in a texture of its own under `handle`, replacing what was there.
+    fn upload_image(
+        &mut self,
+        handle: u64,
+        w: u32,
+        h: u32,
+        rgba: &[u8],
+        format: wgpu::TextureFormat,
+    ) {
         let tex = self.device.create_texture(&wgpu::TextureDescriptor {
             label: Some("user image"),
             size: wgpu::Extent3d {
@@ -742,7 +898,7 @@ impl WgpuBackend {
                 origin: wgpu::Origin3d::ZERO,
                 aspect: wgpu::TextureAspect::All,
             },
-            &rgba,
+            rgba,
             wgpu::TexelCopyBufferLayout {
                 offset: 0,
                 bytes_per_row: Some(4 * w),
@@ -769,10 +925,24 @@ impl WgpuBackend {
                 },
             ],
         });
-        let handle = self.next_image_handle;
-        self.next_image_handle += 1;
-        self.images.insert(handle, ImageTex { view, bind, w, h });
-        handle
+        self.images.insert(
+            handle,
+            ImageTex {
+                bind: Some(bind),
+                w,
+                h,
+            },
+        );
+    }
+
+    /// Frees an image registered with [`register_image_from_bytes`](Self::register_image_from_bytes).
+    /// Its atlas space is reclaimed the next time the atlas repacks.
+    pub fn unregister_image(&mut self, handle: u64) {
+        if let Some(tex) = self.images.remove(&handle)
+            && tex.bind.is_none()
+        {
+            self.image_atlas.packer.free(handle);
+        }
     }
 
     fn init_atlas_mask(device: &wgpu::Device) -> anyhow::Result<AtlasA8> {
@@ -893,6 +1063,7 @@ impl WgpuBackend {
             return Some(*info);
         }
 
+        profiling::scope!("glyph raster");
         let gb = repose_text::rasterize(key, px as f32)?;
         if gb.w == 0 || gb.h == 0 || gb.data.is_empty() {
             return None; //Whitespace, but doesn't get inserted?
@@ -961,6 +1132,7 @@ impl WgpuBackend {
         if let Some(info) = self.atlas_color.map.get(&keyp) {
             return Some(*info);
         }
+        profiling::scope!("glyph raster");
         let gb = repose_text::rasterize(key, px as f32)?;
         if !matches!(gb.content, cosmic_text::SwashContent::Color) {
             return None;
@@ -1154,10 +1326,30 @@ impl RenderBackend for WgpuBackend {
         self.surface.configure(&self.device, &self.config);
     }
 
-    fn frame(&mut self, scene: &Scene, _glyph_cfg: GlyphRasterConfig) {
+    fn frame(&mut self, scene: &Scene, glyph_cfg: GlyphRasterConfig) {
+        for (handle, pixmap) in repose_core::take_pixmap_updates() {
+            match pixmap {
+                Some(p) => self.upload_image(
+                    handle,
+                    p.width,
+                    p.height,
+                    &p.rgba,
+                    wgpu::TextureFormat::Rgba8UnormSrgb,
+                ),
+                None => self.unregister_image(handle),
+            }
+        }
         if self.config.width == 0 || self.config.height == 0 {
             return;
         }
+        let _span = tracing::info_span!("render", nodes = scene.nodes.len()).entered();
+        let tinted;
+        let scene = if self.debug_overdraw {
+            tinted = overdraw::overdraw_scene(scene, self.config.width, self.config.height);
+            &tinted
+        } else {
+            scene
+        };
         let frame = loop {
             match self.surface.get_current_texture() {
                 Ok(f) => break f,
@@ -1212,6 +1404,12 @@ impl RenderBackend for WgpuBackend {
             let sy = to_ndc_scalar(w, fb_h);
             sx.min(sy)
         }
+        fn warn_unbalanced_stacks() {
+            STACK_WARN_ONCE.call_once(|| {
+        log::warn!("Scene has unmatched PushClip/PopClip or PushTransform/PopTransform; ignoring the extras.");
+    });
+        }
+
         fn to_scissor(r: &repose_core::Rect, fb_w: u32, fb_h: u32) -> (u32, u32, u32, u32) {
             // Clamp origin inside framebuffer
             let mut x = r.x.floor() as i64;
@@ -1239,9 +1437,11 @@ impl RenderBackend for WgpuBackend {
             Border { off: u64, cnt: u32 },
             Ellipse { off: u64, cnt: u32 },
             EllipseBorder { off: u64, cnt: u32 },
+            Shape { off: u64, cnt: u32 },
             GlyphsMask { off: u64, cnt: u32 },
             GlyphsColor { off: u64, cnt: u32 },
             Image { off: u64, cnt: u32, handle: u64 },
+            AtlasImages { off: u64, cnt: u32 },
             PushTransform(Transform),
             PopTransform,
         }
@@ -1251,8 +1451,10 @@ impl RenderBackend for WgpuBackend {
             borders: Vec<BorderInstance>,
             ellipses: Vec<EllipseInstance>,
             e_borders: Vec<EllipseBorderInstance>,
+            shapes: Vec<ShapeInstance>,
             masks: Vec<GlyphInstance>,
             colors: Vec<GlyphInstance>,
+            images: Vec<GlyphInstance>,
         }
         impl Batch {
             fn new() -> Self {
@@ -1261,8 +1463,10 @@ impl RenderBackend for WgpuBackend {
                     borders: vec![],
                     ellipses: vec![],
                     e_borders: vec![],
+                    shapes: vec![],
                     masks: vec![],
                     colors: vec![],
+                    images: vec![],
                 }
             }
 
@@ -1275,6 +1479,7 @@ impl RenderBackend for WgpuBackend {
                     &mut UploadRing,
                     &mut UploadRing,
                     &mut UploadRing,
+                    &mut UploadRing,
                 ),
                 device: &wgpu::Device,
                 queue: &wgpu::Queue,
@@ -1285,6 +1490,7 @@ impl RenderBackend for WgpuBackend {
                     ring_border,
                     ring_ellipse,
                     ring_ellipse_border,
+                    ring_shape,
                     ring_mask,
                     ring_color,
                 ) = rings;
@@ -1333,6 +1539,17 @@ impl RenderBackend for WgpuBackend {
                     });
                     self.e_borders.clear();
                 }
+                if !self.shapes.is_empty() {
+                    let bytes = bytemuck::cast_slice(&self.shapes);
+                    ring_shape.grow_to_fit(device, bytes.len() as u64);
+                    let (off, wrote) = ring_shape.alloc_write(queue, bytes);
+                    debug_assert_eq!(wrote as usize, bytes.len());
+                    cmds.push(Cmd::Shape {
+                        off,
+                        cnt: self.shapes.len() as u32,
+                    });
+                    self.shapes.clear();
+                }
                 if !self.masks.is_empty() {
                     let bytes = bytemuck::cast_slice(&self.masks);
                     ring_mask.grow_to_fit(device, bytes.len() as u64);
@@ -1355,6 +1572,17 @@ impl RenderBackend for WgpuBackend {
                     });
                     self.colors.clear();
                 }
+                if !self.images.is_empty() {
+                    let bytes = bytemuck::cast_slice(&self.images);
+                    ring_color.grow_to_fit(device, bytes.len() as u64);
+                    let (off, wrote) = ring_color.alloc_write(queue, bytes);
+                    debug_assert_eq!(wrote as usize, bytes.len());
+                    cmds.push(Cmd::AtlasImages {
+                        off,
+                        cnt: self.images.len() as u32,
+                    });
+                    self.images.clear();
+                }
             }
         }
         // per frame
@@ -1362,11 +1590,23 @@ impl RenderBackend for WgpuBackend {
         self.ring_border.reset();
         self.ring_ellipse.reset();
         self.ring_ellipse_border.reset();
+        self.ring_shape.reset();
         self.ring_glyph_mask.reset();
         self.ring_glyph_color.reset();
         let mut batch = Batch::new();
 
         let mut transform_stack: Vec<Transform> = vec![Transform::identity()];
+        // Clips pushed and not popped yet; stray pops are dropped rather than popping the
+        // root scissor.
+        let mut clip_depth = 0usize;
+        // Runs shaped before a font was added or the fallbacks changed may be tofu.
+        let font_generation = repose_text::font_generation();
+        if font_generation != self.font_generation {
+            self.font_generation = font_generation;
+            self.text_runs.clear();
+        }
+        // Runs not drawn again this frame are dropped with it.
+        let mut last_runs = std::mem::take(&mut self.text_runs);
 
         for node in &scene.nodes {
             let t_identity = Transform::identity();
@@ -1450,30 +1690,104 @@ impl RenderBackend for WgpuBackend {
                         color: color.to_linear(),
                     });
                 }
+                SceneNode::Line {
+                    from,
+                    to,
+                    width,
+                    color,
+                } => {
+                    let a = current_transform.apply_to_point(*from);
+                    let b = current_transform.apply_to_point(*to);
+                    let hw = 0.5 * width * current_transform.scale_x;
+                    // One px of slack around the shape for antialiasing.
+                    let pad = hw + 1.0;
+                    let (x0, y0) = (a.x.min(b.x) - pad, a.y.min(b.y) - pad);
+                    let (x1, y1) = (a.x.max(b.x) + pad, a.y.max(b.y) + pad);
+                    batch.shapes.push(ShapeInstance {
+                        xywh: to_ndc(x0, y0, x1 - x0, y1 - y0, fb_w, fb_h),
+                        a: [a.x, a.y, b.x, b.y],
+                        b: [hw, 0.0, 0.0, 0.0],
+                        color: color.to_linear(),
+                    });
+                }
+                SceneNode::Arc {
+                    center,
+                    radius,
+                    inner_radius,
+                    start,
+                    sweep,
+                    color,
+                } => {
+                    let c = current_transform.apply_to_point(*center);
+                    let r = radius * current_transform.scale_x;
+                    let inner = inner_radius * current_transform.scale_x;
+                    let (start, sweep) = if *sweep < 0.0 {
+                        (start + sweep, -sweep)
+                    } else {
+                        (*start, *sweep)
+                    };
+                    let pad = r + 1.0;
+                    batch.shapes.push(ShapeInstance {
+                        xywh: to_ndc(c.x - pad, c.y - pad, 2.0 * pad, 2.0 * pad, fb_w, fb_h),
+                        a: [c.x, c.y, r, inner],
+                        b: [start, sweep, 0.0, 1.0],
+                        color: color.to_linear(),
+                    });
+                }
                 SceneNode::Text {
                     rect,
                     text,
                     color,
                     size,
+                    letter_spacing,
+                    weight,
                 } => {
-                    let px = (*size).clamp(8.0, 96.0);
-                    let shaped = repose_text::shape_line(text, px);
+                    let px = (*size).clamp(8.0, 96.0 * glyph_cfg.scale.max(1.0));
+                    let run_key = (text.id(), px.to_bits(), weight.0);
+                    let mut run = last_runs
+                        .remove(&run_key)
+                        .or_else(|| self.text_runs.remove(&run_key))
+                        .unwrap_or_else(|| TextRun {
+                            glyphs: repose_text::shape_line_weighted(text, px, weight.0),
+                            graphemes: None,
+                        });
 
                     let transformed_rect = current_transform.apply_to_rect(*rect);
 
-                    for sg in shaped {
+                    // Letter spacing shifts each glyph by the number of graphemes before it.
+                    if *letter_spacing != 0.0 && run.graphemes.is_none() {
+                        run.graphemes =
+                            Some(repose_text::metrics_for_textfield(text, px).byte_offsets);
+                    }
+                    let graphemes = run.graphemes.as_ref().filter(|_| *letter_spacing != 0.0);
+                    let spacing_before = |start: usize| -> f32 {
+                        graphemes.map_or(0.0, |b| {
+                            let i = match b.binary_search(&start) {
+                                Ok(i) => i,
+                                Err(i) => i.saturating_sub(1),
+                            };
+                            *letter_spacing * i as f32
+                        })
+                    };
+
+                    // Glyph offsets are whole pixels, so snapping the pen to the pixel grid
+                    // maps every glyph texel onto one device pixel, keeping text sharp at
+                    // fractional scale factors.
+                    let top = transformed_rect.y.round();
+                    for sg in run.glyphs.iter() {
+                        let sx = (transformed_rect.x + spacing_before(sg.start)).round() + sg.x;
                         // Try color first; if not color, try mask
                         if let Some(info) = self.upload_glyph_color(sg.key, px as u32) {
-                            let x = transformed_rect.x + sg.x + sg.bearing_x;
-                            let y = transformed_rect.y + sg.y - sg.bearing_y;
+                            let x = sx + sg.bearing_x;
+                            let y = top + sg.y - sg.bearing_y;
                             batch.colors.push(GlyphInstance {
                                 xywh: to_ndc(x, y, info.w, info.h, fb_w, fb_h),
                                 uv: [info.u0, info.v1, info.u1, info.v0],
                                 color: [1.0, 1.0, 1.0, 1.0], // do not tint color glyphs
                             });
                         } else if let Some(info) = self.upload_glyph_mask(sg.key, px as u32) {
-                            let x = transformed_rect.x + sg.x + sg.bearing_x;
-                            let y = transformed_rect.y + sg.y - sg.bearing_y;
+                            let x = sx + sg.bearing_x;
+                            let y = top + sg.y - sg.bearing_y;
                             batch.masks.push(GlyphInstance {
                                 xywh: to_ndc(x, y, info.w, info.h, fb_w, fb_h),
                                 uv: [info.u0, info.v1, info.u1, info.v0],
@@ -1481,6 +1795,7 @@ impl RenderBackend for WgpuBackend {
                             });
                         }
                     }
+                    self.text_runs.insert(run_key, run);
                 }
                 SceneNode::Image {
                     rect,
@@ -1494,6 +1809,7 @@ impl RenderBackend for WgpuBackend {
                         log::warn!("Image handle {} not found", handle);
                         continue;
                     };
+                    let in_atlas = tex.bind.is_none();
                     let src_w = tex.w as f32;
                     let src_h = tex.h as f32;
                     let dst_w = rect.w.max(0.0);
@@ -1543,6 +1859,16 @@ impl RenderBackend for WgpuBackend {
                             (to_ndc(x, rect.y, w, h, fb_w, fb_h), [0.0, 1.0, 1.0, 0.0])
                         }
                     };
+                    if in_atlas {
+                        if let Some(uv) = self.image_atlas.uv(*handle, uv_rect) {
+                            batch.images.push(GlyphInstance {
+                                xywh: xywh_ndc,
+                                uv,
+                                color: tint.to_linear(),
+                            });
+                        }
+                        continue;
+                    }
                     let inst = GlyphInstance {
                         xywh: xywh_ndc,
                         uv: uv_rect,
@@ -1558,6 +1884,7 @@ impl RenderBackend for WgpuBackend {
                             &mut self.ring_border,
                             &mut self.ring_ellipse,
                             &mut self.ring_ellipse_border,
+                            &mut self.ring_shape,
                             &mut self.ring_glyph_mask,
                             &mut self.ring_glyph_color,
                         ),
@@ -1578,6 +1905,7 @@ impl RenderBackend for WgpuBackend {
                             &mut self.ring_border,
                             &mut self.ring_ellipse,
                             &mut self.ring_ellipse_border,
+                            &mut self.ring_shape,
                             &mut self.ring_glyph_mask,
                             &mut self.ring_glyph_color,
                         ),
@@ -1589,14 +1917,21 @@ impl RenderBackend for WgpuBackend {
                     let current_transform = transform_stack.last().unwrap_or(&t_identity);
                     let transformed = current_transform.apply_to_rect(*rect);
                     cmds.push(Cmd::SetClipPush(transformed));
+                    clip_depth += 1;
                 }
                 SceneNode::PopClip => {
+                    if clip_depth == 0 {
+                        warn_unbalanced_stacks();
+                        continue;
+                    }
+                    clip_depth -= 1;
                     batch.flush(
                         (
                             &mut self.ring_rect,
                             &mut self.ring_border,
                             &mut self.ring_ellipse,
                             &mut self.ring_ellipse_border,
+                            &mut self.ring_shape,
                             &mut self.ring_glyph_mask,
                             &mut self.ring_glyph_color,
                         ),
@@ -1618,10 +1953,19 @@ impl RenderBackend for WgpuBackend {
                     transform_stack.push(combined);
                 }
                 SceneNode::PopTransform => {
-                    transform_stack.pop();
+                    if transform_stack.len() > 1 {
+                        transform_stack.pop();
+                    } else {
+                        warn_unbalanced_stacks();
+                    }
                 }
             }
         }
+        // Whatever is left open ends with this frame; the next one starts from the root
+        // scissor and identity transform.
+        if clip_depth > 0 || transform_stack.len() > 1 {
+            warn_unbalanced_stacks();
+        }
 
         batch.flush(
             (
@@ -1629,6 +1973,7 @@ impl RenderBackend for WgpuBackend {
                 &mut self.ring_border,
                 &mut self.ring_ellipse,
                 &mut self.ring_ellipse_border,
+                &mut self.ring_shape,
                 &mut self.ring_glyph_mask,
                 &mut self.ring_glyph_color,
             ),
@@ -1737,9 +2082,9 @@ impl RenderBackend for WgpuBackend {
                         handle,
                     } => {
                         // Use the same color text pipeline; bind the per-image texture
-                        if let Some(tex) = self.images.get(&handle) {
+                        if let Some(bind) = self.images.get(&handle).and_then(|t| t.bind.as_ref()) {
                             rpass.set_pipeline(&self.text_pipeline_color);
-                            rpass.set_bind_group(0, &tex.bind, &[]);
+                            rpass.set_bind_group(0, bind, &[]);
                             let bytes = (n as u64) * std::mem::size_of::<GlyphInstance>() as u64;
                             rpass.set_vertex_buffer(
                                 0,
@@ -1750,6 +2095,16 @@ impl RenderBackend for WgpuBackend {
                             log::warn!("Image handle {} not found; skipping draw", handle);
                         }
                     }
+                    Cmd::AtlasImages { off, cnt: n } => {
+                        rpass.set_pipeline(&self.text_pipeline_color);
+                        rpass.set_bind_group(0, &self.image_atlas.bind, &[]);
+                        let bytes = (n as u64) * std::mem::size_of::<GlyphInstance>() as u64;
+                        rpass.set_vertex_buffer(
+                            0,
+                            self.ring_glyph_color.buf.slice(off..off + bytes),
+                        );
+                        rpass.draw(0..6, 0..n);
+                    }
                     Cmd::Ellipse { off, cnt: n } => {
                         rpass.set_pipeline(&self.ellipse_pipeline);
                         let bytes = (n as u64) * std::mem::size_of::<EllipseInstance>() as u64;
@@ -1766,12 +2121,19 @@ impl RenderBackend for WgpuBackend {
                         );
                         rpass.draw(0..6, 0..n);
                     }
+                    Cmd::Shape { off, cnt: n } => {
+                        rpass.set_pipeline(&self.shape_pipeline);
+                        let bytes = (n as u64) * std::mem::size_of::<ShapeInstance>() as u64;
+                        rpass.set_vertex_buffer(0, self.ring_shape.buf.slice(off..off + bytes));
+                        rpass.draw(0..6, 0..n);
+                    }
                     Cmd::PushTransform(_transform) => {}
                     Cmd::PopTransform => {}
                 }
             }
         }
 
+        profiling::scope!("gpu submit");
         self.queue.submit(std::iter::once(encoder.finish()));
         if let Err(e) = catch_unwind(AssertUnwindSafe(|| frame.present())) {
             log::warn!("frame.present panicked: {:?}", e);
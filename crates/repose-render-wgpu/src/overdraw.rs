@@ -0,0 +1,292 @@
+//! Overdraw debug view: how many times each pixel is written in a frame, sampled at the
+//! centre of every `CELL`-sized square. Fills, images and border strokes count as writes;
+//! text doesn't (its glyphs cover little of their box). Counts respect clips and the
+//! translate/scale part of transforms, like the renderer itself.
+
+use repose_core::{Color, Rect, Scene, SceneNode, Transform, Vec2};
+
+/// Sampling grid in px.
+const CELL: u32 = 4;
+
+/// Tint for a pixel written `n` times: none for 0, blue, green, then red for 3 or more.
+fn tint(n: u8) -> Option<Color> {
+    match n {
+        0 => None,
+        1 => Some(Color(40, 90, 255, 110)),
+        2 => Some(Color(40, 200, 80, 120)),
+        _ => Some(Color(255, 50, 50, 140)),
+    }
+}
+
+fn intersect(a: Rect, b: Rect) -> Rect {
+    let x0 = a.x.max(b.x);
+    let y0 = a.y.max(b.y);
+    let x1 = (a.x + a.w).min(b.x + b.w);
+    let y1 = (a.y + a.h).min(b.y + b.h);
+    Rect {
+        x: x0,
+        y: y0,
+        w: (x1 - x0).max(0.0),
+        h: (y1 - y0).max(0.0),
+    }
+}
+
+/// Whether `p` is inside the rounded rect `r`.
+fn in_rounded(r: Rect, radius: f32, p: Vec2) -> bool {
+    if !r.contains(p) {
+        return false;
+    }
+    let rad = radius.min(r.w * 0.5).min(r.h * 0.5).max(0.0);
+    let cx = p.x.clamp(r.x + rad, r.x + r.w - rad);
+    let cy = p.y.clamp(r.y + rad, r.y + r.h - rad);
+    (p.x - cx).powi(2) + (p.y - cy).powi(2) <= rad * rad
+}
+
+/// Which pixels of `shape` a node writes, in the node's transformed space.
+enum Shape {
+    Fill(Rect, f32),
+    Ellipse(Rect),
+    Stroke(Rect, f32, f32),
+    // from, to, half width
+    Segment(Vec2, Vec2, f32),
+    // center, radius, inner radius, start, sweep
+    Sector(Vec2, f32, f32, f32, f32),
+}
+
+impl Shape {
+    fn bounds(&self) -> Rect {
+        match *self {
+            Shape::Fill(r, _) | Shape::Ellipse(r) | Shape::Stroke(r, _, _) => r,
+            Shape::Segment(a, b, hw) => Rect {
+                x: a.x.min(b.x) - hw,
+                y: a.y.min(b.y) - hw,
+                w: (a.x - b.x).abs() + 2.0 * hw,
+                h: (a.y - b.y).abs() + 2.0 * hw,
+            },
+            Shape::Sector(c, r, ..) => Rect {
+                x: c.x - r,
+                y: c.y - r,
+                w: 2.0 * r,
+                h: 2.0 * r,
+            },
+        }
+    }
+
+    fn covers(&self, p: Vec2) -> bool {
+        match *self {
+            Shape::Fill(r, radius) => in_rounded(r, radius, p),
+            Shape::Ellipse(r) => {
+                let (rx, ry) = (r.w * 0.5, r.h * 0.5);
+                if rx <= 0.0 || ry <= 0.0 {
+                    return false;
+                }
+                let dx = (p.x - (r.x + rx)) / rx;
+                let dy = (p.y - (r.y + ry)) / ry;
+                dx * dx + dy * dy <= 1.0
+            }
+            Shape::Stroke(r, width, radius) => {
+                let inner = Rect {
+                    x: r.x + width,
+                    y: r.y + width,
+                    w: r.w - 2.0 * width,
+                    h: r.h - 2.0 * width,
+                };
+                in_rounded(r, radius, p)
+                    && !(inner.w > 0.0
+                        && inner.h > 0.0
+                        && in_rounded(inner, (radius - width).max(0.0), p))
+            }
+            Shape::Segment(a, b, hw) => {
+                let (bx, by) = (b.x - a.x, b.y - a.y);
+                let len2 = (bx * bx + by * by).max(f32::EPSILON);
+                let h = (((p.x - a.x) * bx + (p.y - a.y) * by) / len2).clamp(0.0, 1.0);
+                (p.x - a.x - bx * h).hypot(p.y - a.y - by * h) <= hw
+            }
+            Shape::Sector(c, r, inner, start, sweep) => {
+                let d = (p.x - c.x).hypot(p.y - c.y);
+                let ang = (p.y - c.y).atan2(p.x - c.x);
+                d <= r && d >= inner && (ang - start).rem_euclid(std::f32::consts::TAU) <= sweep
+            }
+        }
+    }
+}
+
+/// Write counts per cell, row-major, `cols` x `rows`.
+fn counts(scene: &Scene, cols: u32, rows: u32) -> Vec<u8> {
+    let mut grid = vec![0u8; (cols * rows) as usize];
+    let screen = Rect {
+        x: 0.0,
+        y: 0.0,
+        w: (cols * CELL) as f32,
+        h: (rows * CELL) as f32,
+    };
+    let mut clips = vec![screen];
+    let mut transforms = vec![Transform::identity()];
+
+    for node in &scene.nodes {
+        let t = *transforms.last().unwrap();
+        let clip = *clips.last().unwrap();
+        let shape = match node {
+            SceneNode::Rect { rect, radius, .. } => {
+                Shape::Fill(t.apply_to_rect(*rect), *radius * t.scale_x)
+            }
+            SceneNode::Image { rect, .. } => Shape::Fill(t.apply_to_rect(*rect), 0.0),
+            SceneNode::Ellipse { rect, .. } => Shape::Ellipse(t.apply_to_rect(*rect)),
+            SceneNode::Border {
+                rect,
+                width,
+                radius,
+                ..
+            } => Shape::Stroke(
+                t.apply_to_rect(*rect),
+                *width * t.scale_x,
+                *radius * t.scale_x,
+            ),
+            SceneNode::EllipseBorder { rect, width, .. } => {
+                let r = t.apply_to_rect(*rect);
+                Shape::Stroke(r, *width, r.w.min(r.h) * 0.5)
+            }
+            SceneNode::Line {
+                from, to, width, ..
+            } => Shape::Segment(
+                t.apply_to_point(*from),
+                t.apply_to_point(*to),
+                *width * t.scale_x * 0.5,
+            ),
+            SceneNode::Arc {
+                center,
+                radius,
+                inner_radius,
+                start,
+                sweep,
+                ..
+            } => {
+                // Negative sweeps run counter-clockwise from `start`.
+                let (start, sweep) = if *sweep < 0.0 {
+                    (start + sweep, -sweep)
+                } else {
+                    (*start, *sweep)
+                };
+                Shape::Sector(
+                    t.apply_to_point(*center),
+                    *radius * t.scale_x,
+                    *inner_radius * t.scale_x,
+                    start,
+                    sweep,
+                )
+            }
+            SceneNode::PushClip { rect, .. } => {
+                clips.push(intersect(clip, t.apply_to_rect(*rect)));
+                continue;
+            }
+            SceneNode::PopClip => {
+                if clips.len() > 1 {
+                    clips.pop();
+                }
+                continue;
+            }
+            SceneNode::PushTransform { transform } => {
+                transforms.push(t.combine(transform));
+                continue;
+            }
+            SceneNode::PopTransform => {
+                if transforms.len() > 1 {
+                    transforms.pop();
+                }
+                continue;
+            }
+            SceneNode::Text { .. } => continue,
+        };
+
+        let area = intersect(shape.bounds(), clip);
+        if area.w <= 0.0 || area.h <= 0.0 {
+            continue;
+        }
+        let c0 = (area.x / CELL as f32).floor().max(0.0) as u32;
+        let r0 = (area.y / CELL as f32).floor().max(0.0) as u32;
+        let c1 = ((area.x + area.w) / CELL as f32).ceil().min(cols as f32) as u32;
+        let r1 = ((area.y + area.h) / CELL as f32).ceil().min(rows as f32) as u32;
+        for row in r0..r1 {
+            for col in c0..c1 {
+                let p = Vec2 {
+                    x: (col * CELL) as f32 + CELL as f32 * 0.5,
+                    y: (row * CELL) as f32 + CELL as f32 * 0.5,
+                };
+                if area.contains(p) && shape.covers(p) {
+                    let n = &mut grid[(row * cols + col) as usize];
+                    *n = n.saturating_add(1);
+                }
+            }
+        }
+    }
+    grid
+}
+
+/// `scene` with its overdraw tinted on top, for a `width` x `height` px target.
+pub(crate) fn overdraw_scene(scene: &Scene, width: u32, height: u32) -> Scene {
+    let cols = width.div_ceil(CELL);
+    let rows = height.div_ceil(CELL);
+    let grid = counts(scene, cols, rows);
+
+    let mut out = scene.clone();
+    // One rect per run of equal counts along a row keeps the node count reasonable.
+    for row in 0..rows {
+        let line = &grid[(row * cols) as usize..((row + 1) * cols) as usize];
+        let mut col = 0;
+        while col < cols {
+            let n = line[col as usize];
+            let start = col;
+            while col < cols && line[col as usize] == n {
+                col += 1;
+            }
+            if let Some(color) = tint(n.min(3)) {
+                out.nodes.push(SceneNode::Rect {
+                    rect: Rect {
+                        x: (start * CELL) as f32,
+                        y: (row * CELL) as f32,
+                        w: ((col - start) * CELL) as f32,
+                        h: CELL as f32,
+                    },
+                    brush: repose_core::Brush::Solid(color),
+                    radius: 0.0,
+                });
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stacked_fills_count_per_pixel() {
+        let fill = |x, y, w, h| SceneNode::Rect {
+            rect: Rect { x, y, w, h },
+            brush: repose_core::Brush::Solid(Color::WHITE),
+            radius: 0.0,
+        };
+        let scene = Scene {
+            clear_color: Color::BLACK,
+            nodes: vec![
+                fill(0.0, 0.0, 16.0, 16.0),
+                fill(0.0, 0.0, 8.0, 16.0),
+                SceneNode::PushClip {
+                    rect: Rect {
+                        x: 0.0,
+                        y: 0.0,
+                        w: 4.0,
+                        h: 4.0,
+                    },
+                    radius: 0.0,
+                },
+                fill(0.0, 0.0, 16.0, 16.0),
+                SceneNode::PopClip,
+            ],
+        };
+        let grid = counts(&scene, 4, 4);
+        assert_eq!(&grid[0..4], &[3, 2, 1, 1]);
+        assert_eq!(&grid[4..8], &[2, 2, 1, 1]);
+    }
+}
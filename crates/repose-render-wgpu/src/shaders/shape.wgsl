@@ -0,0 +1,72 @@
+// Lines (round-capped segments) and arcs (circular sectors / ring segments), shaded by
+// signed distance in framebuffer px. `b.w` picks the shape: 0 = line, 1 = arc.
+//   line: a = (x0, y0, x1, y1), b.x = half width
+//   arc:  a = (cx, cy, radius, inner radius), b.xy = (start, sweep) radians, sweep >= 0
+struct VSOut {
+    @builtin(position) pos: vec4<f32>,
+    @location(0) a: vec4<f32>,
+    @location(1) b: vec4<f32>,
+    @location(2) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(
+    @location(0) xywh: vec4<f32>,
+    @location(1) a: vec4<f32>,
+    @location(2) b: vec4<f32>,
+    @location(3) color: vec4<f32>,
+    @builtin(vertex_index) v: u32
+) -> VSOut {
+    var positions = array<vec2<f32>, 6>(
+        vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(1.0, 1.0),
+        vec2(0.0, 0.0), vec2(1.0, 1.0), vec2(0.0, 1.0)
+    );
+    let p = positions[v];
+
+    var out: VSOut;
+    out.pos = vec4(xywh.xy + p * xywh.zw, 0.0, 1.0);
+    out.a = a;
+    out.b = b;
+    out.color = color;
+    return out;
+}
+
+fn sdf_segment(p: vec2<f32>, a: vec2<f32>, b: vec2<f32>, hw: f32) -> f32 {
+    let pa = p - a;
+    let ba = b - a;
+    let h = clamp(dot(pa, ba) / max(dot(ba, ba), 1e-6), 0.0, 1.0);
+    return length(pa - ba * h) - hw;
+}
+
+fn sdf_arc(p: vec2<f32>, c: vec2<f32>, r: f32, inner: f32, start: f32, sweep: f32) -> f32 {
+    let q = p - c;
+    let d = length(q);
+    let ring = max(d - r, inner - d);
+    if (sweep >= 6.2831) {
+        return ring;
+    }
+    // Signed distances to the two edge lines, positive on the inside of the sector.
+    let end = start + sweep;
+    let s1 = dot(q, vec2(-sin(start), cos(start)));
+    let s2 = dot(q, vec2(sin(end), -cos(end)));
+    var edges: f32;
+    if (sweep <= 3.14159265) {
+        edges = -min(s1, s2);
+    } else {
+        edges = -max(s1, s2);
+    }
+    return max(ring, edges);
+}
+
+@fragment
+fn fs_main(in: VSOut) -> @location(0) vec4<f32> {
+    let p = in.pos.xy;
+    var d: f32;
+    if (in.b.w < 0.5) {
+        d = sdf_segment(p, in.a.xy, in.a.zw, in.b.x);
+    } else {
+        d = sdf_arc(p, in.a.xy, in.a.z, in.a.w, in.b.x, in.b.y);
+    }
+    let alpha = clamp(0.5 - d, 0.0, 1.0);
+    return vec4(in.color.rgb, in.color.a * alpha);
+}
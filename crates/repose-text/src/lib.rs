@@ -1,20 +1,27 @@
 use ahash::{AHashMap, AHasher};
 use cosmic_text::{
-    Attrs, Buffer, CacheKey, FontSystem, Metrics, Shaping, SwashCache, SwashContent,
+    Attrs, Buffer, CacheKey, Fallback, Family, FontSystem, Metrics, PlatformFallback, Shaping,
+    SwashCache, SwashContent,
 };
 use once_cell::sync::OnceCell;
 use std::{
     collections::{HashMap, VecDeque},
     hash::{Hash, Hasher},
-    sync::Mutex,
+    ops::Range,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
 };
 use unicode_segmentation::UnicodeSegmentation;
 
 const WRAP_CACHE_CAP: usize = 1024;
 const ELLIP_CACHE_CAP: usize = 2048;
+const RUN_CACHE_CAP: usize = 1024;
 
-static METRICS_LRU: OnceCell<Mutex<Lru<(u64, u32), TextMetrics>>> = OnceCell::new();
-fn metrics_cache() -> &'static Mutex<Lru<(u64, u32), TextMetrics>> {
+type MetricsCache = Lru<(u64, u32, u16), TextMetrics>;
+static METRICS_LRU: OnceCell<Mutex<MetricsCache>> = OnceCell::new();
+fn metrics_cache() -> &'static Mutex<MetricsCache> {
     METRICS_LRU.get_or_init(|| Mutex::new(Lru::new(4096)))
 }
 
@@ -58,19 +65,37 @@ impl<K: std::hash::Hash + Eq + Clone, V> Lru<K, V> {
         self.order.push_back(k.clone());
         self.map.insert(k, v);
     }
+
+    fn clear(&mut self) {
+        self.map.clear();
+        self.order.clear();
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&K) -> bool) {
+        self.map.retain(|k, _| keep(k));
+        self.order.retain(|k| self.map.contains_key(k));
+    }
 }
 
-static WRAP_LRU: OnceCell<Mutex<Lru<(u64, u32, u32, u16, bool), (Vec<String>, bool)>>> =
-    OnceCell::new();
-static ELLIP_LRU: OnceCell<Mutex<Lru<(u64, u32, u32), String>>> = OnceCell::new();
+type WrapKey = (u64, u32, i32, u32, u16, bool);
+static WRAP_LRU: OnceCell<Mutex<Lru<WrapKey, (Vec<String>, bool)>>> = OnceCell::new();
+static ELLIP_LRU: OnceCell<Mutex<Lru<(u64, u32, i32, u32), String>>> = OnceCell::new();
 
-fn wrap_cache() -> &'static Mutex<Lru<(u64, u32, u32, u16, bool), (Vec<String>, bool)>> {
+fn wrap_cache() -> &'static Mutex<Lru<WrapKey, (Vec<String>, bool)>> {
     WRAP_LRU.get_or_init(|| Mutex::new(Lru::new(WRAP_CACHE_CAP)))
 }
-fn ellip_cache() -> &'static Mutex<Lru<(u64, u32, u32), String>> {
+fn ellip_cache() -> &'static Mutex<Lru<(u64, u32, i32, u32), String>> {
     ELLIP_LRU.get_or_init(|| Mutex::new(Lru::new(ELLIP_CACHE_CAP)))
 }
 
+// Shaped runs by (text hash, px * 100, weight, family). Runs of text the UI stopped showing
+// are dropped by `evict_runs`; the capacity only bounds what's on screen at once.
+type RunCache = Lru<(u64, u32, u16, Family<'static>), Arc<[ShapedGlyph]>>;
+static RUN_LRU: OnceCell<Mutex<RunCache>> = OnceCell::new();
+fn run_cache() -> &'static Mutex<RunCache> {
+    RUN_LRU.get_or_init(|| Mutex::new(Lru::new(RUN_CACHE_CAP)))
+}
+
 fn fast_hash(s: &str) -> u64 {
     use std::hash::{Hash, Hasher};
     let mut h = AHasher::default();
@@ -83,6 +108,8 @@ pub struct GlyphKey(pub u64);
 
 pub struct ShapedGlyph {
     pub key: GlyphKey,
+    /// Pen position in whole pixels; the bitmap carries the sub-pixel remainder, so a glyph
+    /// drawn at a whole-pixel origin plus `x`/`y` lands exactly on the device pixel grid.
     pub x: f32,
     pub y: f32,
     pub w: f32,
@@ -90,6 +117,8 @@ pub struct ShapedGlyph {
     pub bearing_x: f32,
     pub bearing_y: f32,
     pub advance: f32,
+    /// Byte offset in the source text of the cluster this glyph belongs to.
+    pub start: usize,
 }
 
 pub struct GlyphBitmap {
@@ -114,6 +143,33 @@ impl Engine {
     }
 }
 
+/// The platform's fallback lists with the app's chain tried ahead of its common fonts.
+struct ChainFallback {
+    common: Vec<&'static str>,
+}
+
+impl ChainFallback {
+    fn new(chain: &[&'static str]) -> Self {
+        let mut common = chain.to_vec();
+        common.extend_from_slice(PlatformFallback.common_fallback());
+        Self { common }
+    }
+}
+
+impl Fallback for ChainFallback {
+    fn common_fallback(&self) -> &[&'static str] {
+        &self.common
+    }
+
+    fn forbidden_fallback(&self) -> &[&'static str] {
+        PlatformFallback.forbidden_fallback()
+    }
+
+    fn script_fallback(&self, script: unicode_script::Script, locale: &str) -> &[&'static str] {
+        PlatformFallback.script_fallback(script, locale)
+    }
+}
+
 static ENGINE: OnceCell<Mutex<Engine>> = OnceCell::new();
 
 fn engine() -> &'static Mutex<Engine> {
@@ -148,6 +204,62 @@ fn engine() -> &'static Mutex<Engine> {
     })
 }
 
+/// Registers a font (TTF/OTF/TTC bytes) for shaping. Glyphs missing from the default family
+/// are looked up in [`set_font_fallbacks`]'s chain, then the platform's lists, then every
+/// registered font, so bundling a CJK or Arabic font is enough to stop it rendering as boxes.
+pub fn load_font_data(data: Vec<u8>) {
+    engine().lock().unwrap().fs.db_mut().load_font_data(data);
+    clear_caches();
+}
+
+/// Registers every font file under `dir`. Desktop targets load the system's fonts on
+/// startup; Android only has the bundled ones until `load_fonts_dir("/system/fonts")`.
+pub fn load_fonts_dir(dir: impl AsRef<std::path::Path>) {
+    engine().lock().unwrap().fs.db_mut().load_fonts_dir(dir);
+    clear_caches();
+}
+
+/// Sets the families tried, in order, for characters the default family has no glyph for,
+/// e.g. `["Noto Sans CJK JP", "Noto Naskh Arabic", "Noto Color Emoji"]`. They're preferred
+/// over the platform's common fallbacks but not its per-script picks.
+pub fn set_font_fallbacks<I, S>(families: I)
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    // cosmic-text wants 'static names; a chain is set a handful of times per process at most.
+    let chain: Vec<&'static str> = families
+        .into_iter()
+        .map(|f| &*Box::leak(f.into().into_boxed_str()))
+        .collect();
+    {
+        let mut eng = engine().lock().unwrap();
+        let locale = eng.fs.locale().to_string();
+        let db = eng.fs.db().clone();
+        eng.fs =
+            FontSystem::new_with_locale_and_db_and_fallback(locale, db, ChainFallback::new(&chain));
+    }
+    clear_caches();
+}
+
+static FONT_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Bumped whenever the font set changes. Renderers that keep shaped runs of their own
+/// should drop them when it moves, or text keeps the glyphs of the old fonts.
+pub fn font_generation() -> u64 {
+    FONT_GENERATION.load(Ordering::Acquire)
+}
+
+/// Drops everything shaped with the previous font set, so text is reshaped against the new one.
+fn clear_caches() {
+    run_cache().lock().unwrap().clear();
+    metrics_cache().lock().unwrap().clear();
+    wrap_cache().lock().unwrap().clear();
+    ellip_cache().lock().unwrap().clear();
+    ellipsis_width_cache().lock().unwrap().clear();
+    FONT_GENERATION.fetch_add(1, Ordering::AcqRel);
+}
+
 // Utility: stable u64 key from a CacheKey using its Hash impl
 fn key_from_cachekey(k: &CacheKey) -> GlyphKey {
     let mut h = AHasher::default();
@@ -155,8 +267,49 @@ fn key_from_cachekey(k: &CacheKey) -> GlyphKey {
     GlyphKey(h.finish())
 }
 
-// Shape a single-line string (no wrapping). Returns positioned glyphs relative to baseline y=0.
-pub fn shape_line(text: &str, px: f32) -> Vec<ShapedGlyph> {
+/// CSS-style weight of regular text.
+pub const NORMAL_WEIGHT: u16 = 400;
+
+/// Family text is shaped in; the font database maps it to the bundled or system sans-serif.
+const FAMILY: Family<'static> = Family::SansSerif;
+
+/// Shapes a single-line string (no wrapping) into glyphs positioned relative to baseline
+/// y=0. Runs are cached by content, size, weight and family, so text drawn again is not
+/// reshaped until [`evict_runs`] drops it.
+pub fn shape_line(text: &str, px: f32) -> Arc<[ShapedGlyph]> {
+    shape_line_weighted(text, px, NORMAL_WEIGHT)
+}
+
+/// [`shape_line`] at a font weight (100–900; 700 is bold).
+pub fn shape_line_weighted(text: &str, px: f32, weight: u16) -> Arc<[ShapedGlyph]> {
+    let key = (fast_hash(text), (px * 100.0) as u32, weight, FAMILY);
+    if let Some(run) = run_cache().lock().unwrap().get(&key).cloned() {
+        return run;
+    }
+    let run: Arc<[ShapedGlyph]> = shape_line_uncached(text, px, weight).into();
+    run_cache().lock().unwrap().put(key, run.clone());
+    run
+}
+
+/// Drops the cached runs of `texts` at every size and weight, for text that's no longer
+/// shown (e.g. what `repose_core::take_swept_text` reports after a frame).
+pub fn evict_runs<'a>(texts: impl IntoIterator<Item = &'a str>) {
+    let gone: ahash::AHashSet<u64> = texts.into_iter().map(fast_hash).collect();
+    if !gone.is_empty() {
+        run_cache()
+            .lock()
+            .unwrap()
+            .retain(|(hash, ..)| !gone.contains(hash));
+    }
+}
+
+fn attrs(weight: u16) -> Attrs<'static> {
+    Attrs::new()
+        .family(FAMILY)
+        .weight(cosmic_text::Weight(weight))
+}
+
+fn shape_line_uncached(text: &str, px: f32, weight: u16) -> Vec<ShapedGlyph> {
     let mut eng = engine().lock().unwrap();
 
     // Construct a temporary buffer each call; FontSystem and caches are retained globally
@@ -165,7 +318,7 @@ pub fn shape_line(text: &str, px: f32) -> Vec<ShapedGlyph> {
         // Borrow with FS for ergonomic setters (no FS arg)
         let mut b = buf.borrow_with(&mut eng.fs);
         b.set_size(None, None);
-        b.set_text(text, &Attrs::new(), Shaping::Advanced, None);
+        b.set_text(text, &attrs(weight), Shaping::Advanced, None);
         b.shape_until_scroll(true);
     }
 
@@ -192,13 +345,14 @@ pub fn shape_line(text: &str, px: f32) -> Vec<ShapedGlyph> {
 
             out.push(ShapedGlyph {
                 key,
-                x: g.x + g.x_offset, // visual x
-                y: run.line_y,       // baseline y
+                x: phys.x as f32, // visual x
+                y: phys.y as f32, // baseline y
                 w,
                 h,
                 bearing_x: left,
                 bearing_y: top,
                 advance: g.w,
+                start: g.start,
             });
         }
     }
@@ -230,7 +384,12 @@ pub struct TextMetrics {
 
 /// Computes caret mapping using shaping (no wrapping).
 pub fn metrics_for_textfield(text: &str, px: f32) -> TextMetrics {
-    let key = (fast_hash(text), (px * 100.0) as u32);
+    metrics_weighted(text, px, NORMAL_WEIGHT)
+}
+
+/// [`metrics_for_textfield`] at a font weight.
+pub fn metrics_weighted(text: &str, px: f32, weight: u16) -> TextMetrics {
+    let key = (fast_hash(text), (px * 100.0) as u32, weight);
     if let Some(m) = metrics_cache().lock().unwrap().get(&key).cloned() {
         return m;
     }
@@ -239,7 +398,7 @@ pub fn metrics_for_textfield(text: &str, px: f32) -> TextMetrics {
     {
         let mut b = buf.borrow_with(&mut eng.fs);
         b.set_size(None, None);
-        b.set_text(text, &Attrs::new(), Shaping::Advanced, None);
+        b.set_text(text, &attrs(weight), Shaping::Advanced, None);
         b.shape_until_scroll(true);
     }
     let mut edges: Vec<(usize, f32)> = Vec::new();
@@ -279,6 +438,30 @@ pub fn metrics_for_textfield(text: &str, px: f32) -> TextMetrics {
     m
 }
 
+/// [`metrics_for_textfield`] with `letter_spacing` px added after every grapheme.
+pub fn metrics_spaced(text: &str, px: f32, letter_spacing: f32) -> TextMetrics {
+    let mut m = metrics_for_textfield(text, px);
+    if letter_spacing != 0.0 {
+        for (i, p) in m.positions.iter_mut().enumerate() {
+            *p += letter_spacing * i as f32;
+        }
+    }
+    m
+}
+
+/// Shaped width (px) of `text` laid out without wrapping: its widest `\n`-separated line.
+pub fn measure_width(text: &str, px: f32, letter_spacing: f32) -> f32 {
+    text.split('\n')
+        .map(|line| {
+            metrics_spaced(line, px, letter_spacing)
+                .positions
+                .last()
+                .copied()
+                .unwrap_or(0.0)
+        })
+        .fold(0.0, f32::max)
+}
+
 fn width_between(edges: &[(usize, f32)], start_b: usize, end_b: usize) -> f32 {
     let x0 = lookup_right(edges, start_b);
     let x1 = lookup_right(edges, end_b);
@@ -306,6 +489,18 @@ pub fn wrap_lines(
     max_width: f32,
     max_lines: Option<usize>,
     soft_wrap: bool,
+) -> (Vec<String>, bool) {
+    wrap_lines_spaced(text, px, 0.0, max_width, max_lines, soft_wrap)
+}
+
+/// [`wrap_lines`] for text drawn with `letter_spacing` px after every grapheme.
+pub fn wrap_lines_spaced(
+    text: &str,
+    px: f32,
+    letter_spacing: f32,
+    max_width: f32,
+    max_lines: Option<usize>,
+    soft_wrap: bool,
 ) -> (Vec<String>, bool) {
     if text.is_empty() || max_width <= 0.0 {
         return (vec![String::new()], false);
@@ -324,6 +519,7 @@ pub fn wrap_lines(
     let key = (
         fast_hash(text),
         (px * 100.0) as u32,
+        (letter_spacing * 100.0) as i32,
         (max_width * 100.0) as u32,
         max_lines_key,
         soft_wrap,
@@ -333,7 +529,7 @@ pub fn wrap_lines(
     }
 
     // Shape once and reuse positions/byte mapping.
-    let m = metrics_for_textfield(text, px);
+    let m = metrics_spaced(text, px, letter_spacing);
     // Fast path: fits
     if let Some(&last) = m.positions.last()
         && last <= max_width + 0.5
@@ -435,27 +631,146 @@ pub fn wrap_lines(
     res
 }
 
+/// A stretch of rich text shaped at its own size and weight, for [`wrap_runs`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct StyledRun {
+    pub range: Range<usize>,
+    pub px: f32,
+    pub weight: u16,
+}
+
+/// [`wrap_lines_spaced`] for rich text: each run is measured at its own size and weight, and
+/// lines break wherever they fit regardless of runs. `runs` must cover `text` in order. Every
+/// `\n` ends a line without being part of it. Returns each line's byte range, trailing
+/// whitespace excluded, with its width.
+pub fn wrap_runs(
+    text: &str,
+    runs: &[StyledRun],
+    letter_spacing: f32,
+    max_width: f32,
+) -> Vec<(Range<usize>, f32)> {
+    // Grapheme boundaries with the pen x at each; a `\n` doesn't move the pen.
+    let mut edges: Vec<(usize, f32)> = vec![(0, 0.0)];
+    let mut x = 0.0;
+    for run in runs {
+        let mut at = run.range.start;
+        for piece in text[run.range.clone()].split_inclusive('\n') {
+            let body = piece.strip_suffix('\n').unwrap_or(piece);
+            let m = metrics_weighted(body, run.px, run.weight);
+            let x_at = |b: usize| m.positions[m.byte_offsets.partition_point(|&o| o <= b) - 1];
+            for (g, s) in body.grapheme_indices(true) {
+                x += x_at(g + s.len()) - x_at(g) + letter_spacing;
+                edges.push((at + g + s.len(), x));
+            }
+            if body.len() < piece.len() {
+                edges.push((at + piece.len(), x));
+            }
+            at += piece.len();
+        }
+    }
+    let x_of = |b: usize| edges[edges.partition_point(|e| e.0 <= b) - 1].1;
+    let line = |start: usize, end: usize| {
+        let end = start + text[start..end].trim_end().len();
+        (start..end, x_of(end) - x_of(start))
+    };
+    let breaks: Vec<usize> = text.split_word_bound_indices().map(|(i, _)| i).collect();
+
+    let mut lines = Vec::new();
+    let mut start = 0;
+    // The line started at a soft break, so its leading whitespace is dropped.
+    let mut soft = false;
+    let mut brk = None;
+    for pair in edges.windows(2) {
+        let ((b, _), (e, right)) = (pair[0], pair[1]);
+        let g = &text[b..e];
+        if g.ends_with('\n') {
+            lines.push(line(start, b));
+            (start, soft, brk) = (e, false, None);
+            continue;
+        }
+        if b == start && soft && g.trim().is_empty() {
+            start = e;
+            continue;
+        }
+        if b > start && breaks.binary_search(&b).is_ok() {
+            brk = Some(b);
+        }
+        if b > start && right - x_of(start) > max_width + 0.5 {
+            let cut = brk.unwrap_or(b);
+            lines.push(line(start, cut));
+            (start, soft, brk) = (cut, true, None);
+            if start == b && g.trim().is_empty() {
+                start = e;
+            }
+        }
+    }
+    lines.push(line(start, text.len()));
+    lines
+}
+
+/// Distance (px) from the top of a [`shape_line`] line to its baseline.
+pub fn baseline(px: f32) -> f32 {
+    shape_line(" ", px).first().map_or(px, |g| g.y)
+}
+
+/// Words for caret movement: UAX #29 word segments that contain a letter or digit, as byte
+/// ranges. Spaces and punctuation between them aren't words.
+pub fn word_ranges(text: &str) -> impl DoubleEndedIterator<Item = Range<usize>> + '_ {
+    text.split_word_bound_indices()
+        .filter(|(_, w)| w.chars().any(char::is_alphanumeric))
+        .map(|(i, w)| i..i + w.len())
+}
+
+/// Start of the word before `byte`, or of the one it's inside; 0 if there is none.
+pub fn prev_word_start(text: &str, byte: usize) -> usize {
+    word_ranges(text)
+        .rev()
+        .find(|w| w.start < byte)
+        .map_or(0, |w| w.start)
+}
+
+/// End of the word after `byte`, or of the one it's inside; `text.len()` if there is none.
+pub fn next_word_end(text: &str, byte: usize) -> usize {
+    word_ranges(text)
+        .find(|w| w.end > byte)
+        .map_or(text.len(), |w| w.end)
+}
+
+/// The UAX #29 segment (a word, a run of spaces, a punctuation mark) around `byte`. At
+/// the very end of the text, the last segment.
+pub fn word_segment_at(text: &str, byte: usize) -> Option<Range<usize>> {
+    text.split_word_bound_indices()
+        .map(|(i, w)| i..i + w.len())
+        .find(|r| r.contains(&byte) || r.end == byte && byte == text.len())
+}
+
 /// Return a string truncated to fit max_width at the given px size, appending '…' if truncated.
 pub fn ellipsize_line(text: &str, px: f32, max_width: f32) -> String {
+    ellipsize_line_spaced(text, px, 0.0, max_width)
+}
+
+/// [`ellipsize_line`] for text drawn with `letter_spacing` px after every grapheme.
+pub fn ellipsize_line_spaced(text: &str, px: f32, letter_spacing: f32, max_width: f32) -> String {
     if text.is_empty() || max_width <= 0.0 {
         return String::new();
     }
     let key = (
         fast_hash(text),
         (px * 100.0) as u32,
+        (letter_spacing * 100.0) as i32,
         (max_width * 100.0) as u32,
     );
     if let Some(s) = ellip_cache().lock().unwrap().get(&key).cloned() {
         return s;
     }
-    let m = metrics_for_textfield(text, px);
+    let m = metrics_spaced(text, px, letter_spacing);
     if let Some(&last) = m.positions.last()
         && last <= max_width + 0.5
     {
         return text.to_string();
     }
     let el = "…";
-    let e_w = ellipsis_width(px);
+    let e_w = ellipsis_width(px) + letter_spacing;
     if e_w >= max_width {
         return String::new();
     }
@@ -484,9 +799,13 @@ pub fn ellipsize_line(text: &str, px: f32, max_width: f32) -> String {
     s
 }
 
-fn ellipsis_width(px: f32) -> f32 {
+fn ellipsis_width_cache() -> &'static Mutex<Lru<u32, f32>> {
     static ELLIP_W_LRU: OnceCell<Mutex<Lru<u32, f32>>> = OnceCell::new();
-    let cache = ELLIP_W_LRU.get_or_init(|| Mutex::new(Lru::new(64)));
+    ELLIP_W_LRU.get_or_init(|| Mutex::new(Lru::new(64)))
+}
+
+fn ellipsis_width(px: f32) -> f32 {
+    let cache = ellipsis_width_cache();
     let key = (px * 100.0) as u32;
     if let Some(w) = cache.lock().unwrap().get(&key).copied() {
         return w;
@@ -499,3 +818,148 @@ fn ellipsis_width(px: f32) -> f32 {
     cache.lock().unwrap().put(key, w);
     w
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Loading a font clears the run cache under tests that hold on to runs.
+    static FONTS: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn shaped_runs_are_reused_until_evicted() {
+        let _fonts = FONTS.lock().unwrap_or_else(|e| e.into_inner());
+        let text = "run cache test";
+        let first = shape_line(text, 14.0);
+        assert!(Arc::ptr_eq(&first, &shape_line(text, 14.0)));
+        // Size and weight are part of the key.
+        assert!(!Arc::ptr_eq(&first, &shape_line(text, 16.0)));
+        assert!(!Arc::ptr_eq(&first, &shape_line_weighted(text, 14.0, 700)));
+
+        evict_runs(["something else"]);
+        assert!(Arc::ptr_eq(&first, &shape_line(text, 14.0)));
+        evict_runs([text]);
+        assert!(!Arc::ptr_eq(&first, &shape_line(text, 14.0)));
+    }
+
+    #[test]
+    fn lru_drops_the_least_recently_used() {
+        let mut lru = Lru::new(2);
+        lru.put(1, "a");
+        lru.put(2, "b");
+        lru.get(&1);
+        lru.put(3, "c");
+        assert_eq!(lru.get(&2), None);
+        assert_eq!(lru.get(&1), Some(&"a"));
+
+        lru.retain(|k| *k != 1);
+        assert_eq!(lru.get(&1), None);
+        assert_eq!(lru.order, [3]);
+    }
+
+    #[test]
+    fn loading_a_font_reshapes_text_it_covers() {
+        let _fonts = FONTS.lock().unwrap_or_else(|e| e.into_inner());
+        // Last private-use code point; no bundled or system font should cover it.
+        let text = "\u{10FFFD}";
+        let generation = font_generation();
+        let before = shape_line(text, 20.0);
+
+        load_font_data(one_glyph_font(0x10FFFD));
+        assert!(font_generation() > generation);
+        let after = shape_line(text, 20.0);
+        assert!(!Arc::ptr_eq(&before, &after));
+        assert_eq!(after.len(), 1);
+        // The test font's only glyph is one em wide.
+        assert_eq!(after[0].advance, 20.0);
+        assert!(before.first().map(|g| g.key) != Some(after[0].key));
+    }
+
+    /// A TrueType font whose only non-notdef glyph is an empty, one em wide `ch`.
+    fn one_glyph_font(ch: u32) -> Vec<u8> {
+        fn be(out: &mut Vec<u8>, vals: &[(u32, usize)]) {
+            for &(v, n) in vals {
+                out.extend_from_slice(&v.to_be_bytes()[4 - n..]);
+            }
+        }
+        let mut head = Vec::new();
+        be(
+            &mut head,
+            &[
+                (0x10000, 4),
+                (0x10000, 4),
+                (0, 4),
+                (0x5F0F3CF5, 4),
+                (0, 2),
+                (1000, 2),
+            ],
+        );
+        head.extend_from_slice(&[0; 16 + 8 + 2]); // dates, bbox, macStyle
+        be(&mut head, &[(8, 2), (2, 2), (0, 2), (0, 2)]);
+        let mut hhea = Vec::new();
+        be(
+            &mut hhea,
+            &[(0x10000, 4), (800, 2), (0xFF38, 2), (0, 2), (1000, 2)],
+        );
+        hhea.extend_from_slice(&[0; 6]);
+        be(&mut hhea, &[(1, 2)]);
+        hhea.extend_from_slice(&[0; 14]);
+        be(&mut hhea, &[(2, 2)]);
+        let mut maxp = Vec::new();
+        be(&mut maxp, &[(0x10000, 4), (2, 2)]);
+        maxp.extend_from_slice(&[0; 26]);
+        let mut hmtx = Vec::new();
+        be(&mut hmtx, &[(1000, 2), (0, 2), (1000, 2), (0, 2)]);
+        let loca = vec![0; 6];
+        let glyf = vec![0; 4];
+        let mut cmap = Vec::new();
+        be(&mut cmap, &[(0, 2), (1, 2), (3, 2), (10, 2), (12, 4)]);
+        be(&mut cmap, &[(12, 2), (0, 2), (28, 4), (0, 4), (1, 4)]);
+        be(&mut cmap, &[(ch, 4), (ch, 4), (1, 4)]);
+        let family: Vec<u8> = "ReposeTest"
+            .encode_utf16()
+            .flat_map(u16::to_be_bytes)
+            .collect();
+        let mut name = Vec::new();
+        be(&mut name, &[(0, 2), (2, 2), (30, 2)]);
+        for id in [1, 6] {
+            be(&mut name, &[(3, 2), (1, 2), (0x409, 2), (id, 2)]);
+            be(&mut name, &[(family.len() as u32, 2), (0, 2)]);
+        }
+        name.extend_from_slice(&family);
+        let mut post = Vec::new();
+        be(&mut post, &[(0x30000, 4)]);
+        post.extend_from_slice(&[0; 28]);
+
+        let tables: [(&[u8; 4], Vec<u8>); 9] = [
+            (b"cmap", cmap),
+            (b"glyf", glyf),
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"hmtx", hmtx),
+            (b"loca", loca),
+            (b"maxp", maxp),
+            (b"name", name),
+            (b"post", post),
+        ];
+        let mut font = Vec::new();
+        be(
+            &mut font,
+            &[(0x10000, 4), (9, 2), (128, 2), (3, 2), (16, 2)],
+        );
+        let mut offset = 12 + 16 * tables.len();
+        for (tag, data) in &tables {
+            font.extend_from_slice(*tag);
+            be(
+                &mut font,
+                &[(0, 4), (offset as u32, 4), (data.len() as u32, 4)],
+            );
+            offset += data.len().next_multiple_of(4);
+        }
+        for (_, data) in &tables {
+            font.extend_from_slice(data);
+            font.resize(font.len().next_multiple_of(4), 0);
+        }
+        font
+    }
+}
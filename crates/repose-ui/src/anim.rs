@@ -1,7 +1,10 @@
-use repose_core::Color;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use repose_core::{Color, Rect, Vec2, ViewId};
 use repose_core::{
-    animation::{AnimatedValue, AnimationSpec},
-    remember_state_with_key,
+    animation::{AnimatedOffset, AnimatedValue, AnimationSpec},
+    remember_state_with_key, remember_with_key,
 };
 
 /// Animate f32 from an explicit initial value to a target.
@@ -56,3 +59,212 @@ pub fn animate_color_from(
 pub fn animate_color(key: impl Into<String>, target: Color, spec: AnimationSpec) -> Color {
     animate_color_from(key, target, target, spec)
 }
+
+/// Animate a dp offset toward `target`, for use with `Modifier::offset_animated`.
+/// Starts at the target on first mount, like [`animate_f32`].
+pub fn animate_offset(key: impl Into<String>, target: Vec2, spec: AnimationSpec) -> AnimatedOffset {
+    let key = key.into();
+    let state = remember_with_key(format!("anim:offset:{key}"), || {
+        AnimatedOffset::new(target, spec)
+    });
+    state.animate_to(target);
+    (*state).clone()
+}
+
+/// Paint-time size animation behind `Modifier::animate_content_size`, keyed by view.
+struct ContentSize {
+    spec: AnimationSpec,
+    anim: Option<AnimatedValue<Vec2>>,
+    /// Minimum layout size (px) held while shrinking.
+    hold: Option<Vec2>,
+    /// Layout pass that last built this view.
+    pass: u64,
+}
+
+/// Layout passes an unbuilt view survives before its state is dropped. A frame can run
+/// several passes (one per window layer), each building a different tree.
+const CONTENT_SIZE_GRACE_PASSES: u64 = 8;
+
+/// Layout passes an unpainted control keeps its transition. Dropping it only means the
+/// control shows up settled next time, so this just has to outlast the passes of the
+/// other window layers in a few frames.
+const TOGGLE_KEEP_PASSES: u64 = 32;
+
+/// Paint-time on/off transition of a two-state control, keyed by view.
+struct Toggle {
+    anim: AnimatedValue<f32>,
+    pass: u64,
+}
+
+/// Paint-time state of a shared element key: the view painting it and, while it moves in
+/// from where another view painted it, that box (px) and the progress.
+struct Shared {
+    id: ViewId,
+    pass: u64,
+    /// Where it was painted last (px), mid-transition included.
+    painted: Rect,
+    from: Option<(Rect, AnimatedValue<f32>)>,
+}
+
+thread_local! {
+    static CONTENT_SIZES: RefCell<HashMap<ViewId, ContentSize>> = RefCell::new(HashMap::new());
+    static TOGGLES: RefCell<HashMap<ViewId, Toggle>> = RefCell::new(HashMap::new());
+    static SHARED: RefCell<HashMap<u64, Shared>> = RefCell::new(HashMap::new());
+    static PASS: Cell<u64> = const { Cell::new(0) };
+}
+
+pub(crate) fn begin_content_size_frame() {
+    let pass = PASS.with(|p| {
+        p.set(p.get() + 1);
+        p.get()
+    });
+    TOGGLES.with(|m| {
+        m.borrow_mut()
+            .retain(|_, t| pass - t.pass <= TOGGLE_KEEP_PASSES)
+    });
+    SHARED.with(|m| {
+        m.borrow_mut()
+            .retain(|_, s| pass - s.pass <= CONTENT_SIZE_GRACE_PASSES)
+    });
+}
+
+/// How far (0 = off, 1 = on) control `id` is through its switch to `on`. A control starts
+/// settled; flipping it animates with `spec` and keeps frames coming until it lands.
+pub(crate) fn toggle_progress(id: ViewId, on: bool, spec: AnimationSpec) -> f32 {
+    let target = if on { 1.0 } else { 0.0 };
+    let pass = PASS.with(|p| p.get());
+    TOGGLES.with(|m| {
+        let mut m = m.borrow_mut();
+        let t = m.entry(id).or_insert_with(|| Toggle {
+            anim: AnimatedValue::new(target, spec),
+            pass,
+        });
+        t.pass = pass;
+        if *t.anim.target() != target {
+            t.anim.set_target(target);
+        }
+        if t.anim.update() {
+            repose_core::with_frame_nanos(|_| {});
+        }
+        *t.anim.get()
+    })
+}
+
+/// Where (px) view `id`, laid out at `rect`, paints shared element `key`. When another view
+/// painted the key in an earlier pass, `id` takes it over and moves from that view's box to
+/// its own with `spec`, keeping frames coming until it lands.
+pub(crate) fn shared_element_bounds(key: u64, id: ViewId, rect: Rect, spec: AnimationSpec) -> Rect {
+    let pass = PASS.with(|p| p.get());
+    SHARED.with(|m| {
+        let mut m = m.borrow_mut();
+        let s = m.entry(key).or_insert(Shared {
+            id,
+            pass,
+            painted: rect,
+            from: None,
+        });
+        if s.id != id {
+            // Two views with the key in one pass (e.g. both sides of a crossfade): the
+            // first keeps it and the other paints in place.
+            if s.pass == pass {
+                return rect;
+            }
+            let mut t = AnimatedValue::new(0.0, spec);
+            t.set_target(1.0);
+            s.from = Some((s.painted, t));
+            s.id = id;
+        }
+        s.pass = pass;
+        s.painted = match &mut s.from {
+            Some((from, t)) => {
+                let running = t.update();
+                let p = *t.get();
+                let at = Rect {
+                    x: from.x + (rect.x - from.x) * p,
+                    y: from.y + (rect.y - from.y) * p,
+                    w: from.w + (rect.w - from.w) * p,
+                    h: from.h + (rect.h - from.h) * p,
+                };
+                if running {
+                    repose_core::with_frame_nanos(|_| {});
+                } else {
+                    s.from = None;
+                }
+                at
+            }
+            None => rect,
+        };
+        s.painted
+    })
+}
+
+/// Where control `id` is in its current transition, as of the last [`toggle_progress`].
+pub(crate) fn toggle_current(id: ViewId) -> Option<f32> {
+    TOGGLES.with(|m| m.borrow().get(&id).map(|t| *t.anim.get()))
+}
+
+/// Registers `id` for this frame and returns the min size (px) layout must keep, if any.
+pub(crate) fn content_size_hold(id: ViewId, spec: AnimationSpec) -> Option<Vec2> {
+    let pass = PASS.with(|p| p.get());
+    CONTENT_SIZES.with(|m| {
+        let mut m = m.borrow_mut();
+        let c = m.entry(id).or_insert(ContentSize {
+            spec,
+            anim: None,
+            hold: None,
+            pass,
+        });
+        c.pass = pass;
+        c.spec = spec;
+        if let Some(a) = c.anim.as_mut()
+            && !a.update()
+        {
+            c.hold = None;
+        }
+        c.hold
+    })
+}
+
+/// Feeds laid-out sizes (px) back after layout, dropping views that haven't been built for a
+/// while. Returns views that just started shrinking and need their old size held.
+pub(crate) fn observe_content_sizes(
+    size_of: impl Fn(ViewId) -> Option<Vec2>,
+) -> Vec<(ViewId, Vec2)> {
+    CONTENT_SIZES.with(|m| {
+        let mut m = m.borrow_mut();
+        let pass = PASS.with(|p| p.get());
+        m.retain(|_, c| pass - c.pass <= CONTENT_SIZE_GRACE_PASSES);
+        let mut held = Vec::new();
+        for (&id, c) in m.iter_mut().filter(|(_, c)| c.pass == pass) {
+            let Some(size) = size_of(id) else { continue };
+            let Some(a) = c.anim.as_mut() else {
+                c.anim = Some(AnimatedValue::new(size, c.spec));
+                continue;
+            };
+            let prev = *a.target();
+            if c.hold.is_some() || (prev.x - size.x).abs() + (prev.y - size.y).abs() < 0.5 {
+                continue;
+            }
+            a.set_target(size);
+            if size.x < prev.x || size.y < prev.y {
+                let hold = Vec2 {
+                    x: prev.x.max(size.x),
+                    y: prev.y.max(size.y),
+                };
+                c.hold = Some(hold);
+                held.push((id, hold));
+            }
+        }
+        held
+    })
+}
+
+/// Current animated size (px) to clip `id` to.
+pub(crate) fn content_size_current(id: ViewId) -> Option<Vec2> {
+    CONTENT_SIZES.with(|m| {
+        m.borrow()
+            .get(&id)
+            .and_then(|c| c.anim.as_ref())
+            .map(|a| *a.get())
+    })
+}
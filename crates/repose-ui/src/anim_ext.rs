@@ -80,3 +80,10 @@ pub fn AnimatedContent(key: String, transition: Option<Transition>, content: Vie
         _ => content,
     }
 }
+
+/// `content` as the shared element `key`: when the previous frame painted that key
+/// somewhere else, e.g. as a thumbnail on the screen just navigated away from, `content`
+/// animates from there into its own box. See [`Modifier::shared_element`].
+pub fn SharedElement(key: u64, content: View) -> View {
+    Box(Modifier::new().shared_element(key)).child(content)
+}
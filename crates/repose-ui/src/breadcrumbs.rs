@@ -0,0 +1,125 @@
+//! # Breadcrumbs
+//!
+//! A trail of links from the root to the current location. When the trail is wider than
+//! the row was last laid out, the middle collapses into a `…` between the root and as many
+//! of the trailing items as fit; clicking `…` goes to the deepest hidden item. A new row
+//! width asks for another frame, so the trail catches up without waiting for input.
+
+use repose_core::*;
+
+use crate::{Box, Row, Text, TextStyle, ViewExt};
+
+const FONT_DP: f32 = 14.0;
+const PAD_X_DP: f32 = 4.0;
+const SEPARATOR: &str = "›";
+const ELLIPSIS: &str = "…";
+
+/// One slot of a laid-out trail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Crumb {
+    Item(usize),
+    /// Stands for the hidden items; holds the deepest of them.
+    Collapsed(usize),
+}
+
+/// Which crumbs fit in `available` px given each item's width, the separator's and the
+/// ellipsis'. The first and last items always stay. A non-positive `available` (not laid
+/// out yet) shows everything.
+pub fn visible_crumbs(widths: &[f32], separator: f32, ellipsis: f32, available: f32) -> Vec<Crumb> {
+    let n = widths.len();
+    let total = widths.iter().sum::<f32>() + separator * n.saturating_sub(1) as f32;
+    if n <= 2 || available <= 0.0 || total <= available {
+        return (0..n).map(Crumb::Item).collect();
+    }
+
+    let mut used = widths[0] + widths[n - 1] + ellipsis + 2.0 * separator;
+    let mut first_tail = n - 1;
+    while first_tail > 1 && used + widths[first_tail - 1] + separator <= available {
+        first_tail -= 1;
+        used += widths[first_tail] + separator;
+    }
+
+    let mut out = vec![Crumb::Item(0)];
+    if first_tail > 1 {
+        out.push(Crumb::Collapsed(first_tail - 1));
+    }
+    out.extend((first_tail..n).map(Crumb::Item));
+    out
+}
+
+/// `items` from the root to the current location; `on_click` gets the index of the item
+/// clicked. The last item is the current location and isn't clickable.
+pub fn Breadcrumbs(items: Vec<String>, on_click: impl Fn(usize) + 'static) -> View {
+    let width_px = remember(|| signal(0.0f32));
+    let on_click = std::rc::Rc::new(on_click);
+
+    let font_px = locals::dp_to_px(FONT_DP) * locals::text_scale().0;
+    let pad_px = 2.0 * locals::dp_to_px(PAD_X_DP);
+    let text_w = |s: &str| {
+        repose_text::metrics_for_textfield(s, font_px)
+            .positions
+            .last()
+            .copied()
+            .unwrap_or(0.0)
+    };
+    let widths: Vec<f32> = items.iter().map(|s| text_w(s) + pad_px).collect();
+    let crumbs = visible_crumbs(
+        &widths,
+        text_w(SEPARATOR) + pad_px,
+        text_w(ELLIPSIS) + pad_px,
+        width_px.get(),
+    );
+
+    let th = locals::theme();
+    let last = items.len().saturating_sub(1);
+    let mut children = Vec::with_capacity(crumbs.len() * 2);
+    for (i, crumb) in crumbs.into_iter().enumerate() {
+        if i > 0 {
+            children.push(crumb_box(
+                Text(SEPARATOR).size(FONT_DP).color(th.outline),
+                Modifier::new(),
+            ));
+        }
+        let (label, target) = match crumb {
+            Crumb::Item(ix) => (items[ix].as_str(), ix),
+            Crumb::Collapsed(ix) => (ELLIPSIS, ix),
+        };
+        if crumb == Crumb::Item(last) {
+            children.push(crumb_box(
+                Text(label).size(FONT_DP).color(th.on_surface).single_line(),
+                Modifier::new(),
+            ));
+            continue;
+        }
+        let on_click = on_click.clone();
+        let description = match crumb {
+            Crumb::Item(_) => label.to_string(),
+            Crumb::Collapsed(_) => items[target].clone(),
+        };
+        children.push(crumb_box(
+            Text(label).size(FONT_DP).color(th.primary).single_line(),
+            Modifier::new()
+                .clickable()
+                .on_pointer_down(move |_| on_click(target))
+                .content_description(description),
+        ));
+    }
+
+    let width = width_px.clone();
+    Row(Modifier::new().fill_max_width().on_size(move |s| {
+        if width.get() != s.width {
+            width.set(s.width);
+            with_frame_nanos(|_| {});
+        }
+    }))
+    .with_children(children)
+}
+
+fn crumb_box(text: View, modifier: Modifier) -> View {
+    Box(modifier.padding_values(PaddingValues {
+        left: PAD_X_DP,
+        right: PAD_X_DP,
+        ..Default::default()
+    }))
+    .child(text)
+}
@@ -0,0 +1,197 @@
+//! # Dialogs
+//!
+//! [`Dialog`] shows its content in a card centered over a dimmed window, as a modal window
+//! layer: nothing underneath takes pointer input, Tab cycles through the dialog's own
+//! focusable views (focus goes back to where it was when the dialog closes), and clicking
+//! the scrim or pressing Escape calls `on_dismiss`.
+//!
+//! [`Sheet`] is the same kind of layer with its content in a full-width panel along the
+//! bottom edge of the window instead.
+//!
+//! Like other layers the dialog is declarative: it is shown for as long as the composable
+//! that calls [`Dialog`] keeps calling it.
+//!
+//! ```rust
+//! use std::cell::Cell;
+//! use std::rc::Rc;
+//!
+//! use repose_core::*;
+//! use repose_ui::{Box, Text, dialog::Dialog};
+//!
+//! fn DiscardDialog(open: Rc<Cell<bool>>) -> View {
+//!     if !open.get() {
+//!         return Box(Modifier::new());
+//!     }
+//!     Dialog(Text("Discard changes?"), move || open.set(false))
+//! }
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use repose_core::*;
+
+use crate::{Box, Column, ViewExt};
+
+/// Above popups such as dropdowns.
+const DIALOG_Z: i32 = 200;
+const MAX_W_DP: f32 = 560.0;
+const PAD_DP: f32 = 24.0;
+const RADIUS_DP: f32 = 16.0;
+const SCRIM: Color = Color(0, 0, 0, 0x88);
+
+static NEXT_DIALOG: AtomicU64 = AtomicU64::new(1);
+
+/// Shows `content` as a modal dialog for this frame. Returns an empty view, so it can sit
+/// anywhere in the tree.
+pub fn Dialog(content: View, on_dismiss: impl Fn() + 'static) -> View {
+    let th = locals::theme();
+
+    // `clickable` so clicks on the card don't reach the backdrop and dismiss it.
+    let card = Box(Modifier::new()
+        .max_width(MAX_W_DP)
+        .background(th.surface)
+        .clip_rounded(RADIUS_DP)
+        .padding(PAD_DP)
+        .clickable())
+    .child(content);
+    modal(
+        on_dismiss,
+        Box(Modifier::new()
+            .fill_max_size()
+            .align_items(AlignItems::Center)
+            .justify_content(JustifyContent::Center))
+        .child(card),
+    )
+}
+
+/// Shows `content` as a modal bottom sheet for this frame: a full-width panel along the
+/// bottom of the window. Returns an empty view, like [`Dialog`].
+pub fn Sheet(content: View, on_dismiss: impl Fn() + 'static) -> View {
+    let th = locals::theme();
+
+    let panel = Column(
+        Modifier::new()
+            .background(th.surface)
+            .clip_rounded(RADIUS_DP)
+            .padding(PAD_DP)
+            .clickable(),
+    )
+    .child(content);
+    modal(
+        on_dismiss,
+        Column(
+            Modifier::new()
+                .fill_max_size()
+                .justify_content(JustifyContent::FlexEnd),
+        )
+        .child(panel),
+    )
+}
+
+fn modal(on_dismiss: impl Fn() + 'static, layer: View) -> View {
+    let key = remember(|| format!("dialog-{}", NEXT_DIALOG.fetch_add(1, Ordering::Relaxed)));
+    show_layer(
+        key.as_str(),
+        LayerOptions::default()
+            .z(DIALOG_Z)
+            .modal()
+            .scrim(SCRIM)
+            .on_dismiss(on_dismiss),
+        layer,
+    );
+    Box(Modifier::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Button, Column, Interactions, Text, layout_and_paint};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn dialog_dims_the_window_and_takes_focus_until_dismissed() {
+        let open = Rc::new(Cell::new(false));
+        let mut sched = Scheduler::new();
+        let layout = |v: &View, size: (u32, u32)| {
+            layout_and_paint(
+                v,
+                size,
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            )
+        };
+        let build = |open: Rc<Cell<bool>>| {
+            move |_: &mut Scheduler| {
+                let dialog = if open.get() {
+                    let o = open.clone();
+                    Dialog(Button(Text("OK"), || {}), move || o.set(false))
+                } else {
+                    Box(Modifier::new())
+                };
+                Column(Modifier::new()).child((Button(Text("Open"), || {}), dialog))
+            }
+        };
+
+        let frame = sched.repose(build(open.clone()), layout);
+        let page_button = frame.focus_chain[0];
+        sched.focused = Some(page_button);
+
+        // Opening it moves focus into the dialog.
+        open.set(true);
+        let frame = sched.repose(build(open.clone()), layout);
+        assert_eq!(frame.focus_chain.len(), 1);
+        assert_ne!(frame.focus_chain[0], page_button);
+        assert_eq!(sched.focused, Some(frame.focus_chain[0]));
+        assert!(frame.scene.nodes.iter().any(|n| matches!(
+            n,
+            SceneNode::Rect { brush: Brush::Solid(c), .. } if *c == SCRIM
+        )));
+
+        // Escape dismisses it, and focus goes back.
+        assert!(sched.layers.dismiss_modal());
+        assert!(!open.get());
+        sched.repose(build(open.clone()), layout);
+        assert!(!sched.layers.dismiss_modal());
+        assert_eq!(sched.focused, Some(page_button));
+    }
+
+    #[test]
+    fn sheet_spans_the_bottom_of_the_window() {
+        let mut sched = Scheduler::new();
+        let frame = sched.repose(
+            |_| {
+                let sheet = Sheet(Box(Modifier::new().fill_max_width().height(50.0)), || {});
+                Column(Modifier::new()).child((Text("Page"), sheet))
+            },
+            |v, size| {
+                layout_and_paint(
+                    v,
+                    size,
+                    &RetainedStateStore::new(),
+                    &Interactions::default(),
+                    None,
+                )
+            },
+        );
+        let surface = locals::theme().surface;
+        let panel = frame
+            .scene
+            .nodes
+            .iter()
+            .find_map(|n| match n {
+                SceneNode::Rect {
+                    rect,
+                    brush: Brush::Solid(c),
+                    ..
+                } if *c == surface => Some(*rect),
+                _ => None,
+            })
+            .expect("sheet panel");
+        let (w, h) = (sched.size.0 as f32, sched.size.1 as f32);
+        assert_eq!((panel.x, panel.w), (0.0, w));
+        assert!((panel.y + panel.h - h).abs() < 0.5);
+        assert!(panel.h < h / 2.0);
+    }
+}
@@ -0,0 +1,433 @@
+//! # Dropdowns
+//!
+//! [`DropdownMenu`] opens a list of items under a trigger view; [`Select`] is a dropdown
+//! whose trigger shows the chosen option. The list is a modal window layer (see
+//! `repose_core::show_layer`), so it paints above the rest of the window, isn't clipped by
+//! the scroll container the trigger sits in, and a click anywhere outside it closes it.
+//!
+//! While the list is open the trigger keeps focus and takes the keys: Up and Down move the
+//! highlight, Home and End jump to the ends, Enter or Space picks, Escape and Tab close.
+//! Typing jumps to the next item that starts with what was typed in the last
+//! [`TYPEAHEAD_TIMEOUT`].
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use repose_core::*;
+use web_time::{Duration, Instant};
+
+use crate::scroll::{ScrollArea, ScrollAreaState, ScrollDirection};
+use crate::{Box, Column, Row, Text, TextStyle, ViewExt};
+
+/// How long typed characters keep adding up to one typeahead query.
+pub const TYPEAHEAD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+const ITEM_H_DP: f32 = 36.0;
+const LIST_MAX_H_DP: f32 = 280.0;
+const LIST_PAD_DP: f32 = 4.0;
+const POPUP_GAP_DP: f32 = 4.0;
+const TRIGGER_H_DP: f32 = 40.0;
+/// Above app layers that don't pick a `z`.
+const POPUP_Z: i32 = 100;
+
+static NEXT_DROPDOWN: AtomicU64 = AtomicU64::new(1);
+
+pub struct DropdownState {
+    layer_key: String,
+    open: Cell<bool>,
+    highlighted: Cell<usize>,
+    // Where the trigger was laid out, in window px.
+    anchor: Cell<Rect>,
+    typed: RefCell<String>,
+    typed_at: Cell<Option<Instant>>,
+    scroll: Rc<ScrollAreaState>,
+}
+
+impl Default for DropdownState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DropdownState {
+    pub fn new() -> Self {
+        Self {
+            layer_key: format!("dropdown-{}", NEXT_DROPDOWN.fetch_add(1, Ordering::Relaxed)),
+            open: Cell::new(false),
+            highlighted: Cell::new(0),
+            anchor: Cell::new(Rect::default()),
+            typed: RefCell::new(String::new()),
+            typed_at: Cell::new(None),
+            scroll: Rc::new(ScrollAreaState::new()),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open.get()
+    }
+
+    /// Opens the list with `highlighted` under the keyboard cursor.
+    pub fn open(&self, highlighted: usize) {
+        self.open.set(true);
+        self.highlighted.set(highlighted);
+        self.typed.borrow_mut().clear();
+        self.typed_at.set(None);
+        self.scroll.scroll_to(Vec2::default());
+        self.scroll_to_highlight();
+    }
+
+    pub fn close(&self) {
+        self.open.set(false);
+    }
+
+    /// The item under the keyboard cursor.
+    pub fn highlighted(&self) -> usize {
+        self.highlighted.get()
+    }
+
+    fn highlight(&self, i: usize) {
+        self.highlighted.set(i);
+        self.scroll_to_highlight();
+    }
+
+    // Keeps the highlighted row inside the list's viewport.
+    fn scroll_to_highlight(&self) {
+        let item = locals::dp_to_px(ITEM_H_DP);
+        // Not laid out yet: the tallest the list gets.
+        let view = match self.scroll.viewport().y {
+            h if h > 0.0 => h,
+            _ => locals::dp_to_px(LIST_MAX_H_DP - 2.0 * LIST_PAD_DP),
+        };
+        let top = self.highlighted.get() as f32 * item;
+        let off = self.scroll.offset().y;
+        if top < off {
+            self.scroll.scroll_to(Vec2 { x: 0.0, y: top });
+        } else if top + item > off + view {
+            self.scroll.scroll_to(Vec2 {
+                x: 0.0,
+                y: top + item - view,
+            });
+        }
+    }
+
+    /// Adds `c` to the typeahead query and returns the item it now selects, if any: the
+    /// first item from the highlighted one on (the one after it, for a new query) whose
+    /// label starts with the query, ignoring case.
+    pub fn typeahead(&self, c: char, labels: &[String], now: Instant) -> Option<usize> {
+        let mut typed = self.typed.borrow_mut();
+        if self
+            .typed_at
+            .get()
+            .is_none_or(|at| now.saturating_duration_since(at) > TYPEAHEAD_TIMEOUT)
+        {
+            typed.clear();
+        }
+        self.typed_at.set(Some(now));
+        typed.extend(c.to_lowercase());
+
+        let n = labels.len();
+        let from = self.highlighted.get() + usize::from(typed.chars().count() == 1);
+        (0..n)
+            .map(|k| (from + k) % n)
+            .find(|&i| labels[i].to_lowercase().starts_with(typed.as_str()))
+    }
+
+    /// Handles a key for a list of `labels`; `pick` gets the chosen index. Returns whether
+    /// the key was used.
+    fn key(
+        &self,
+        e: &KeyEvent,
+        labels: &[String],
+        current: Option<usize>,
+        pick: &dyn Fn(usize),
+    ) -> bool {
+        if !e.is_pressed() || labels.is_empty() {
+            return false;
+        }
+        let last = labels.len() - 1;
+        if !self.is_open() {
+            return match e.key {
+                input::Key::ArrowDown
+                | input::Key::ArrowUp
+                | input::Key::Enter
+                | input::Key::Space => {
+                    self.open(current.unwrap_or(0).min(last));
+                    true
+                }
+                _ => false,
+            };
+        }
+        let h = self.highlighted.get().min(last);
+        match e.key {
+            input::Key::ArrowDown => self.highlight((h + 1).min(last)),
+            input::Key::ArrowUp => self.highlight(h.saturating_sub(1)),
+            input::Key::Home => self.highlight(0),
+            input::Key::End => self.highlight(last),
+            input::Key::Enter | input::Key::Space => {
+                self.close();
+                pick(h);
+            }
+            input::Key::Escape => self.close(),
+            input::Key::Tab => {
+                // Closes, and lets focus move on.
+                self.close();
+                return false;
+            }
+            input::Key::Character(c) if !e.modifiers.ctrl && !e.modifiers.meta => {
+                if let Some(i) = self.typeahead(c, labels, Instant::now()) {
+                    self.highlight(i);
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+pub fn remember_dropdown_state(key: impl Into<String>) -> Rc<DropdownState> {
+    repose_core::remember_with_key(key.into(), DropdownState::new)
+}
+
+/// `trigger` opens a list of `items` when clicked (or on Enter, Space or an arrow key
+/// while focused); `on_item` gets the index of the item picked.
+pub fn DropdownMenu(
+    state: Rc<DropdownState>,
+    trigger: View,
+    items: Vec<String>,
+    on_item: impl Fn(usize) + 'static,
+) -> View {
+    dropdown(
+        state,
+        trigger,
+        items,
+        None,
+        Rc::new(on_item),
+        Modifier::new(),
+    )
+}
+
+/// A field showing `options[selected]` (or `placeholder` with nothing selected) that opens
+/// the list of options; `on_select` gets the index picked.
+pub fn Select(
+    state: Rc<DropdownState>,
+    options: Vec<String>,
+    selected: Option<usize>,
+    placeholder: impl Into<String>,
+    on_select: impl Fn(usize) + 'static,
+) -> View {
+    let th = locals::theme();
+    let (label, color) = match selected.and_then(|i| options.get(i)) {
+        Some(s) => (s.clone(), th.on_surface),
+        None => (placeholder.into(), th.outline),
+    };
+    let border = if state.is_open() {
+        th.focus
+    } else {
+        th.outline
+    };
+    let trigger = Row(Modifier::new()
+        .fill_max_width()
+        .height(TRIGGER_H_DP)
+        .background(th.surface)
+        .border(1.0, border, 6.0)
+        .padding_values(PaddingValues {
+            left: 12.0,
+            right: 8.0,
+            ..Default::default()
+        })
+        .align_items(AlignItems::Center))
+    .child((
+        Box(Modifier::new().flex_grow(1.0)).child(Text(label.clone()).color(color).single_line()),
+        Text("▾").color(th.on_surface),
+    ));
+    dropdown(
+        state,
+        trigger,
+        options,
+        selected,
+        Rc::new(on_select),
+        Modifier::new().fill_max_width().content_description(label),
+    )
+}
+
+fn dropdown(
+    state: Rc<DropdownState>,
+    trigger: View,
+    items: Vec<String>,
+    current: Option<usize>,
+    pick: Rc<dyn Fn(usize)>,
+    modifier: Modifier,
+) -> View {
+    let items = Rc::new(items);
+    if state.is_open() && !items.is_empty() {
+        show_list(&state, &items, current, &pick);
+    }
+
+    let on_down = {
+        let st = state.clone();
+        let n = items.len();
+        move |_| {
+            if !st.is_open() && n > 0 {
+                st.open(current.unwrap_or(0).min(n - 1));
+            }
+        }
+    };
+    let on_key = {
+        let st = state.clone();
+        let items = items.clone();
+        move |e: KeyEvent| st.key(&e, &items, current, &*pick)
+    };
+    let on_placed = {
+        let st = state.clone();
+        move |r: Rect| st.anchor.set(r)
+    };
+    Box(modifier
+        .clickable()
+        .on_pointer_down(on_down)
+        .on_key_event(on_key)
+        .on_placed(on_placed))
+    .child(trigger)
+}
+
+fn show_list(
+    state: &Rc<DropdownState>,
+    items: &Rc<Vec<String>>,
+    current: Option<usize>,
+    pick: &Rc<dyn Fn(usize)>,
+) {
+    let th = locals::theme();
+    let highlighted = state.highlighted();
+    let rows: Vec<View> = items
+        .iter()
+        .enumerate()
+        .map(|(i, label)| {
+            let bg = if i == highlighted {
+                th.primary.with_alpha(48)
+            } else {
+                Color::TRANSPARENT
+            };
+            let fg = if Some(i) == current {
+                th.primary
+            } else {
+                th.on_surface
+            };
+            let on_enter = {
+                let st = state.clone();
+                move |_| st.highlighted.set(i)
+            };
+            let on_down = {
+                let st = state.clone();
+                let pick = pick.clone();
+                move |_| {
+                    st.close();
+                    pick(i);
+                }
+            };
+            Box(Modifier::new()
+                .fill_max_width()
+                .height(ITEM_H_DP)
+                .background(bg)
+                .padding_values(PaddingValues {
+                    left: 12.0,
+                    right: 12.0,
+                    ..Default::default()
+                })
+                .align_items(AlignItems::Center)
+                .clickable()
+                .on_pointer_enter(on_enter)
+                .on_pointer_down(on_down)
+                .content_description(label.clone()))
+            .child(Text(label.clone()).color(fg).single_line())
+        })
+        .collect();
+
+    let px_per_dp = locals::dp_to_px(1.0);
+    let anchor = state.anchor.get();
+    let list_h = (items.len() as f32 * ITEM_H_DP + 2.0 * LIST_PAD_DP).min(LIST_MAX_H_DP);
+    let list = Box(Modifier::new()
+        .absolute()
+        .offset_left(anchor.x / px_per_dp)
+        .offset_top((anchor.y + anchor.h) / px_per_dp + POPUP_GAP_DP)
+        .min_width(anchor.w / px_per_dp)
+        .height(list_h)
+        .background(th.surface)
+        .border(1.0, th.outline, 8.0)
+        .clip_rounded(8.0)
+        .padding(LIST_PAD_DP))
+    .child(
+        ScrollArea(
+            ScrollDirection::Vertical,
+            state.scroll.clone(),
+            Modifier::new().fill_max_size(),
+        )
+        .child(Column(Modifier::new().fill_max_width()).with_children(rows)),
+    );
+
+    let st = state.clone();
+    show_layer(
+        state.layer_key.clone(),
+        LayerOptions::default()
+            .z(POPUP_Z)
+            .modal()
+            .on_dismiss(move || st.close()),
+        list,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(k: input::Key) -> KeyEvent {
+        KeyEvent {
+            key: k,
+            location: input::KeyLocation::Standard,
+            state: input::KeyState::Pressed,
+            modifiers: input::Modifiers::default(),
+            is_repeat: false,
+            text: None,
+        }
+    }
+
+    #[test]
+    fn keys_open_move_and_pick_with_typeahead() {
+        let labels: Vec<String> = ["Apple", "Banana", "Blueberry", "Cherry"]
+            .map(String::from)
+            .to_vec();
+        let picked = Rc::new(Cell::new(None));
+        let p = picked.clone();
+        let pick = move |i: usize| p.set(Some(i));
+        let st = DropdownState::new();
+
+        assert!(!st.key(&key(input::Key::Escape), &labels, Some(1), &pick));
+        assert!(st.key(&key(input::Key::ArrowDown), &labels, Some(1), &pick));
+        assert!(st.is_open());
+        assert_eq!(st.highlighted(), 1);
+
+        st.key(&key(input::Key::End), &labels, Some(1), &pick);
+        assert_eq!(st.highlighted(), 3);
+        st.key(&key(input::Key::ArrowDown), &labels, Some(1), &pick);
+        assert_eq!(st.highlighted(), 3);
+
+        // "b" goes to the next B, a second "b" (a new query) to the one after it.
+        let t0 = Instant::now();
+        assert_eq!(st.typeahead('b', &labels, t0), Some(1));
+        st.highlighted.set(1);
+        assert_eq!(st.typeahead('l', &labels, t0), Some(2));
+        st.highlighted.set(2);
+        let later = t0 + TYPEAHEAD_TIMEOUT + Duration::from_millis(1);
+        assert_eq!(st.typeahead('B', &labels, later), Some(1));
+        assert_eq!(st.typeahead('z', &labels, later), None);
+
+        st.highlighted.set(2);
+        assert!(st.key(&key(input::Key::Enter), &labels, Some(1), &pick));
+        assert!(!st.is_open());
+        assert_eq!(picked.get(), Some(2));
+
+        // Tab closes but isn't consumed, so focus still moves.
+        st.open(0);
+        assert!(!st.key(&key(input::Key::Tab), &labels, Some(1), &pick));
+        assert!(!st.is_open());
+    }
+}
@@ -59,7 +59,8 @@ impl GestureDetector {
 
                 // Check for double tap
                 if let Some(last) = self.last_tap
-                    && (Instant::now() - last) < Duration::from_millis(300)
+                    && (Instant::now() - last)
+                        < repose_core::locals::platform_settings().double_click_interval
                 {
                     if let Some(cb) = &self.on_double_tap {
                         cb(event.position);
@@ -104,6 +105,10 @@ impl GestureDetector {
                 self.press_start = None;
                 self.drag_start = None;
             }
+            PointerEventKind::Cancel => {
+                self.press_start = None;
+                self.drag_start = None;
+            }
             PointerEventKind::Move => {
                 if let Some(start) = self.drag_start
                     && let Some(cb) = &self.on_drag
@@ -1,11 +1,48 @@
+use repose_core::animation::AnimatedValue;
 use repose_core::*;
 use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use web_time::Instant;
 
+static NEXT_LIST: AtomicU64 = AtomicU64::new(1);
+
+/// Where a lazy list is scrolled to in item terms: the first visible item and how far into
+/// it the viewport starts. With item keys ([`LazyColumnKeyed`]) it follows that item when
+/// items are added or removed above it.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LazyListAnchor {
+    pub index: usize,
+    pub key: Option<u64>,
+    pub offset_px: f32,
+}
+
+/// Scroll state of a [`LazyColumn`], [`LazyRow`] or [`LazyGrid`]. Keep it across recompositions (`remember_with_key`, or
+/// `EntryScope::remember_lazy_list_state` in a navigation entry) to keep the position; the
+/// list re-derives its offset from the [`anchor`](Self::anchor) whenever the items change
+/// under it instead of jumping.
 pub struct LazyColumnState {
-    scroll_offset: Signal<f32>,   // px
-    viewport_height: Signal<f32>, // px
+    scroll_offset: Signal<f32>, // px
+    viewport: Signal<f32>,      // px, along the scroll axis
+
+    // Laid-out content height (px), which exceeds the `items × item height` estimate while
+    // items are taller than that, e.g. an open `ExpandableSection`.
+    measured_content: RefCell<f32>,
+    anchor: RefCell<Option<LazyListAnchor>>,
+    // The offset moved by input or `set_offset` since the last build, so the anchor follows
+    // it; otherwise the offset follows the anchor.
+    moved: RefCell<bool>,
+    // Laid-out item sizes (px, along the scroll axis) by key, for the lists built from a
+    // `LazyListScope`; a `LazyGrid` keeps one per row, under its first item's key.
+    heights: RefCell<HashMap<u64, f32>>,
+    // Keys of the items that have had a `keyed` group.
+    built: RefCell<HashSet<u64>>,
+    // Salts item keys into `keyed` groups, so two lists can use the same keys.
+    id: u64,
+
+    // The running `animate_scroll_to`, advanced by `tick`.
+    scroll_anim: RefCell<Option<AnimatedValue<f32>>>,
 
     // physics
     vel_px_s: RefCell<f32>, // px/sec
@@ -25,7 +62,14 @@ impl LazyColumnState {
         let now = Instant::now();
         Self {
             scroll_offset: signal(0.0),
-            viewport_height: signal(600.0),
+            viewport: signal(600.0),
+            measured_content: RefCell::new(0.0),
+            anchor: RefCell::new(None),
+            moved: RefCell::new(false),
+            heights: RefCell::new(HashMap::new()),
+            built: RefCell::new(HashSet::new()),
+            id: NEXT_LIST.fetch_add(1, Ordering::Relaxed),
+            scroll_anim: RefCell::new(None),
             vel_px_s: RefCell::new(0.0),
             last_t: RefCell::new(now),
             last_input_t: RefCell::new(now),
@@ -33,16 +77,120 @@ impl LazyColumnState {
         }
     }
 
+    /// A state that opens at `anchor`, e.g. one saved from [`anchor`](Self::anchor) earlier.
+    pub fn with_anchor(anchor: LazyListAnchor) -> Self {
+        let st = Self::new();
+        *st.anchor.borrow_mut() = Some(anchor);
+        st
+    }
+
+    /// The position as of the last build.
+    pub fn anchor(&self) -> LazyListAnchor {
+        self.anchor.borrow().unwrap_or_default()
+    }
+
     pub fn set_offset(&self, off: f32, content_height: f32) {
-        let vh = self.viewport_height.get();
+        self.clamp_offset(off, content_height);
+        *self.moved.borrow_mut() = true;
+    }
+
+    /// Current offset (px) along the scroll axis.
+    pub fn offset(&self) -> f32 {
+        self.scroll_offset.get()
+    }
+
+    /// Largest offset (px), as of the last layout.
+    pub fn max_offset(&self) -> f32 {
+        (*self.measured_content.borrow() - self.viewport.get()).max(0.0)
+    }
+
+    /// Whether the offset is moving on its own: a fling or an `animate_scroll_to`.
+    pub fn is_scrolling(&self) -> bool {
+        *self.animating.borrow() || self.scroll_anim.borrow().is_some()
+    }
+
+    /// Jumps so that item `index` starts the viewport (or as close as the end allows), from
+    /// the next build on.
+    pub fn scroll_to_item(&self, index: usize) {
+        self.scroll_anim.borrow_mut().take();
+        *self.animating.borrow_mut() = false;
+        *self.anchor.borrow_mut() = Some(LazyListAnchor {
+            index,
+            key: None,
+            offset_px: 0.0,
+        });
+        *self.moved.borrow_mut() = false;
+    }
+
+    /// Animates to offset `off` (px) with the default spec, from the next build on.
+    pub fn animate_scroll_to(&self, off: f32) {
+        self.animate_scroll_to_with(off, AnimationSpec::default());
+    }
+    pub fn animate_scroll_to_with(&self, off: f32, spec: AnimationSpec) {
+        let mut a = AnimatedValue::new(self.scroll_offset.get(), spec);
+        a.set_target(off);
+        *self.animating.borrow_mut() = false;
+        *self.scroll_anim.borrow_mut() = Some(a);
+        with_frame_nanos(|_| {});
+    }
+
+    /// Keeps the offset in range after a layout change without moving the anchor, so a list
+    /// that is briefly short (loading, mid-transition) doesn't forget where it was.
+    fn clamp_offset(&self, off: f32, content_height: f32) {
+        let vh = self.viewport.get();
         let max_off = (content_height - vh).max(0.0);
         self.scroll_offset.set(off.clamp(0.0, max_off));
     }
 
+    /// Scrollable content height (px) given the estimate from item count and height.
+    fn content_height(&self, estimate: f32) -> f32 {
+        estimate.max(*self.measured_content.borrow())
+    }
+
+    /// Reconciles the offset and the anchor before a build over `n` items, `total` px tall,
+    /// where item `i` starts at `top_of(i)` and `index_at(y)` is the item covering `y`.
+    fn sync_anchor(
+        &self,
+        n: usize,
+        total: f32,
+        top_of: impl Fn(usize) -> f32,
+        index_at: impl Fn(f32) -> usize,
+        key_of: impl Fn(usize) -> Option<u64>,
+    ) {
+        if n == 0 {
+            return;
+        }
+        let off = self.scroll_offset.get();
+        let mut anchor = self.anchor.borrow_mut();
+        match *anchor {
+            Some(a) if !self.moved.replace(false) => {
+                let index = a
+                    .key
+                    .and_then(|k| (0..n).find(|&i| key_of(i) == Some(k)))
+                    .unwrap_or(a.index.min(n - 1));
+                let max_off = (total - self.viewport.get()).max(0.0);
+                let want = (top_of(index) + a.offset_px).clamp(0.0, max_off);
+                if (want - off).abs() > 0.5 {
+                    self.scroll_offset.set(want);
+                }
+                *anchor = Some(LazyListAnchor { index, ..a });
+            }
+            _ => {
+                let index = index_at(off).min(n - 1);
+                *anchor = Some(LazyListAnchor {
+                    index,
+                    key: key_of(index),
+                    offset_px: off - top_of(index),
+                });
+            }
+        }
+    }
+
     /// Consume delta in px. Returns leftover in px (for nested scroll).
     pub fn scroll_immediate(&self, delta_px: f32, content_height_px: f32) -> f32 {
+        self.scroll_anim.borrow_mut().take();
         let before = self.scroll_offset.get();
-        let viewport = self.viewport_height.get();
+        let viewport = self.viewport.get();
         let max_offset = (content_height_px - viewport).max(0.0);
 
         let new_offset = (before + delta_px).clamp(0.0, max_offset);
@@ -50,6 +198,9 @@ impl LazyColumnState {
 
         let consumed = new_offset - before;
         let leftover = delta_px - consumed;
+        if consumed != 0.0 {
+            *self.moved.borrow_mut() = true;
+        }
 
         // estimate velocity (px/sec) from input cadence
         let now = Instant::now();
@@ -64,8 +215,21 @@ impl LazyColumnState {
         leftover
     }
 
-    /// Advance inertia one tick; returns true if animating.
+    /// Advance inertia (or `animate_scroll_to`) one tick; returns true if animating.
     pub fn tick(&self, content_height_px: f32) -> bool {
+        {
+            let mut anim = self.scroll_anim.borrow_mut();
+            if let Some(a) = anim.as_mut() {
+                let running = a.update();
+                self.set_offset(*a.get(), content_height_px);
+                if running {
+                    with_frame_nanos(|_| {});
+                } else {
+                    *anim = None;
+                }
+                return running;
+            }
+        }
         if !*self.animating.borrow() {
             return false;
         }
@@ -86,11 +250,12 @@ impl LazyColumnState {
         }
 
         let before = self.scroll_offset.get();
-        let viewport = self.viewport_height.get();
+        let viewport = self.viewport.get();
         let max_offset = (content_height_px - viewport).max(0.0);
 
         let new_off = (before + vel0 * dt).clamp(0.0, max_offset);
         self.scroll_offset.set(new_off);
+        *self.moved.borrow_mut() = true;
 
         // Stop quickly at bounds
         if (new_off - before).abs() < 0.01 && (before <= 0.0 || before >= max_offset) {
@@ -108,7 +273,8 @@ impl LazyColumnState {
     }
 }
 
-/// Virtualized list - only renders visible items
+/// Virtualized list of items `item_height_dp` tall; only the visible ones are built.
+/// [`LazyColumnWith`] takes items of varying height.
 #[allow(non_snake_case)]
 pub fn LazyColumn<T, F>(
     items: Vec<T>,
@@ -117,6 +283,46 @@ pub fn LazyColumn<T, F>(
     modifier: Modifier,
     item_builder: F,
 ) -> View
+where
+    T: Clone + 'static,
+    F: Fn(T, usize) -> View + 'static,
+{
+    lazy_column(items, None, item_height_dp, state, modifier, item_builder)
+}
+
+/// [`LazyColumn`] whose items have stable keys: when items are inserted or removed above
+/// the first visible one, the list stays on that item instead of on its old index.
+#[allow(non_snake_case)]
+pub fn LazyColumnKeyed<T, F>(
+    items: Vec<T>,
+    key: impl Fn(&T) -> u64,
+    item_height_dp: f32,
+    state: Rc<LazyColumnState>,
+    modifier: Modifier,
+    item_builder: F,
+) -> View
+where
+    T: Clone + 'static,
+    F: Fn(T, usize) -> View + 'static,
+{
+    lazy_column(
+        items,
+        Some(&key),
+        item_height_dp,
+        state,
+        modifier,
+        item_builder,
+    )
+}
+
+fn lazy_column<T, F>(
+    items: Vec<T>,
+    key: Option<&dyn Fn(&T) -> u64>,
+    item_height_dp: f32,
+    state: Rc<LazyColumnState>,
+    modifier: Modifier,
+    item_builder: F,
+) -> View
 where
     T: Clone + 'static,
     F: Fn(T, usize) -> View + 'static,
@@ -125,12 +331,22 @@ where
     let item_h_px = dp_to_px(item_height_dp);
     let content_height_px = items.len() as f32 * item_h_px;
 
+    if item_h_px > 0.0 {
+        state.sync_anchor(
+            items.len(),
+            content_height_px,
+            |i| i as f32 * item_h_px,
+            |y| (y / item_h_px).floor().max(0.0) as usize,
+            |i| key.map(|k| k(&items[i])),
+        );
+    }
+
     // Signals are px (fed by ScrollV)
     let scroll_offset_px = state.scroll_offset.get();
-    let viewport_height_px = state.viewport_height.get();
+    let viewport_height_px = state.viewport.get();
 
     // Advance physics in px
-    state.tick(content_height_px);
+    state.tick(state.content_height(content_height_px));
 
     // Visible range (px)
     let first_visible = (scroll_offset_px / item_h_px).floor().max(0.0) as usize;
@@ -162,54 +378,500 @@ where
         ));
     }
 
+    lazy_scroll(
+        state,
+        Axis::Vertical,
+        content_height_px,
+        modifier,
+        children,
+        None,
+    )
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Axis {
+    Vertical,
+    Horizontal,
+}
+
+// The scroll container around a lazy list's `children`, driving `state` along `axis`.
+// `pinned` is laid over them, positioned in content coordinates.
+#[allow(deprecated)]
+fn lazy_scroll(
+    state: Rc<LazyColumnState>,
+    axis: Axis,
+    content_px: f32,
+    modifier: Modifier,
+    children: Vec<View>,
+    pinned: Option<View>,
+) -> View {
     // Scroll callbacks (px)
     let on_scroll = {
         let st = state.clone();
         Rc::new(move |d: repose_core::Vec2| -> repose_core::Vec2 {
-            let leftover_y_px = st.scroll_immediate(d.y, content_height_px);
-            repose_core::Vec2 {
-                x: d.x,
-                y: leftover_y_px,
+            let content = st.content_height(content_px);
+            match axis {
+                Axis::Vertical => repose_core::Vec2 {
+                    x: d.x,
+                    y: st.scroll_immediate(d.y, content),
+                },
+                Axis::Horizontal => {
+                    // Wheel-only mice report dy; treat it as horizontal and consume it.
+                    let wheel = d.x.abs() <= 0.001;
+                    let left = st.scroll_immediate(if wheel { d.y } else { d.x }, content);
+                    repose_core::Vec2 {
+                        x: left,
+                        y: if wheel { 0.0 } else { d.y },
+                    }
+                }
             }
         })
     };
 
     let set_viewport = {
         let st = state.clone();
-        Rc::new(move |h_px: f32| st.viewport_height.set(h_px.max(0.0)))
+        Rc::new(move |px: f32| st.viewport.set(px.max(0.0)))
     };
 
     let get_scroll = {
         let st = state.clone();
-        Rc::new(move || -> f32 { st.scroll_offset.get() })
+        move || -> f32 { st.scroll_offset.get() }
     };
 
     let set_scroll = {
         let st = state.clone();
-        Rc::new(move |off_px: f32| st.set_offset(off_px, content_height_px))
+        move |off_px: f32| st.set_offset(off_px, st.content_height(content_px))
     };
 
-    let measured_h_px = {
+    let set_content = {
         let st = state.clone();
-        Rc::new(move |h_px: f32| {
-            st.set_offset(st.scroll_offset.get(), h_px);
+        Rc::new(move |px: f32| {
+            *st.measured_content.borrow_mut() = px;
+            st.clamp_offset(st.scroll_offset.get(), px);
         })
     };
 
-    let content = crate::Column(Modifier::new()).with_children(children);
-
-    repose_core::View::new(
-        0,
-        repose_core::ViewKind::ScrollV {
-            on_scroll: Some(on_scroll),
-            set_viewport_height: Some(set_viewport),
-            set_content_height: Some(Rc::new(move |h| measured_h_px(h))),
-            get_scroll_offset: Some(get_scroll),
-            set_scroll_offset: Some(set_scroll),
-        },
+    let mut content = vec![match axis {
+        Axis::Vertical => crate::Column(Modifier::new()).with_children(children),
+        Axis::Horizontal => crate::Row(Modifier::new()).with_children(children),
+    }];
+    content.extend(pinned);
+    match axis {
+        Axis::Vertical => repose_core::View::new(
+            0,
+            repose_core::ViewKind::ScrollV {
+                on_scroll: Some(on_scroll),
+                set_viewport_height: Some(set_viewport),
+                set_content_height: Some(set_content),
+                get_scroll_offset: Some(Rc::new(get_scroll)),
+                set_scroll_offset: Some(Rc::new(set_scroll)),
+            },
+        )
+        .modifier(modifier)
+        .with_children(content),
+        Axis::Horizontal => repose_core::View::new(
+            0,
+            repose_core::ViewKind::ScrollXY {
+                on_scroll: Some(on_scroll),
+                set_viewport_width: Some(set_viewport),
+                set_viewport_height: None,
+                set_content_width: Some(set_content),
+                set_content_height: None,
+                get_scroll_offset_xy: Some(Rc::new(move || (get_scroll(), 0.0))),
+                set_scroll_offset_xy: Some(Rc::new(move |x, _| set_scroll(x))),
+            },
+        )
+        .modifier(modifier)
+        .with_children(content),
+    }
+}
+
+// Builds the lines of a virtualized list (its items, or a grid's rows) that are near the
+// viewport, with spacers standing in for the rest. Line `i` has key `keys[i]`, is built by
+// `build(i)`, and counts as `estimate_px` long along `axis` until it has been laid out.
+// The lines at `sticky` (ascending) are sticky headers.
+fn virtualize(
+    state: Rc<LazyColumnState>,
+    axis: Axis,
+    estimate_px: f32,
+    keys: &[u64],
+    sticky: &[usize],
+    build: impl Fn(usize) -> View,
+    modifier: Modifier,
+) -> View {
+    let n = keys.len();
+
+    // Where each line starts (px), and the end of the last one.
+    let tops: Vec<f32> = {
+        let heights = state.heights.borrow();
+        let mut y = 0.0;
+        std::iter::once(0.0)
+            .chain(keys.iter().map(|k| {
+                y += heights.get(k).copied().unwrap_or(estimate_px);
+                y
+            }))
+            .collect()
+    };
+    let total = tops[n];
+    let index_at = |y: f32| tops.partition_point(|t| *t <= y).saturating_sub(1);
+
+    state.sync_anchor(n, total, |i| tops[i], index_at, |i| Some(keys[i]));
+    state.tick(state.content_height(total));
+
+    let scroll_offset_px = state.scroll_offset.get();
+    let viewport_px = state.viewport.get();
+    let buffer = 2usize;
+    let first = index_at(scroll_offset_px).min(n).saturating_sub(buffer);
+    let last = (index_at(scroll_offset_px + viewport_px) + 1 + buffer).min(n);
+
+    // The header of the section at the top of the viewport, if it has scrolled under the
+    // top, and where it is pinned (px): at the top, or above it as the next header arrives.
+    let stuck = {
+        let j = sticky.partition_point(|&h| tops[h] <= scroll_offset_px);
+        j.checked_sub(1).map(|j| {
+            let h = sticky[j];
+            let pin = match sticky.get(j + 1) {
+                Some(&next) => scroll_offset_px.min(tops[next] - (tops[h + 1] - tops[h])),
+                None => scroll_offset_px,
+            };
+            (h, pin)
+        })
+    };
+
+    // A row lays its children out across the viewport width; they must not shrink to fit it.
+    let line = |m: Modifier| match axis {
+        Axis::Vertical => m,
+        Axis::Horizontal => m.flex_shrink(0.0),
+    };
+    let px_per_dp = dp_to_px(1.0);
+    let spacer = |px: f32| {
+        crate::Box(line(match axis {
+            Axis::Vertical => Modifier::new().size(1.0, px / px_per_dp),
+            Axis::Horizontal => Modifier::new().size(px / px_per_dp, 1.0),
+        }))
+    };
+    let mut children = Vec::with_capacity(last.saturating_sub(first) + 2);
+    if first > 0 {
+        children.push(spacer(tops[first]));
+    }
+    let measured = |key: u64| {
+        let st = state.clone();
+        Modifier::new()
+            .key(item_group(state.id, key))
+            .on_size(move |size| {
+                let len = match axis {
+                    Axis::Vertical => size.height,
+                    Axis::Horizontal => size.width,
+                };
+                st.heights.borrow_mut().insert(key, len);
+            })
+    };
+    for (i, &key) in keys.iter().enumerate().take(last).skip(first) {
+        if stuck.is_some_and(|(h, _)| h == i) {
+            // Drawn pinned instead; this keeps its place.
+            children.push(spacer(tops[i + 1] - tops[i]));
+            continue;
+        }
+        children.push(crate::Box(line(measured(key))).with_children(vec![build(i)]));
+    }
+    if last < n {
+        children.push(spacer(total - tops[last]));
+    }
+    // Laid over the list, so it paints (and takes input) above the items it covers; the
+    // scroll container clips it to the viewport.
+    let pinned = stuck.map(|(h, pin)| {
+        let m = measured(keys[h]).absolute();
+        let m = match axis {
+            Axis::Vertical => m
+                .offset_top(pin / px_per_dp)
+                .offset_left(0.0)
+                .offset_right(0.0),
+            Axis::Horizontal => m
+                .offset_left(pin / px_per_dp)
+                .offset_top(0.0)
+                .offset_bottom(0.0),
+        };
+        crate::Box(m).with_children(vec![build(h)])
+    });
+
+    if state.heights.borrow().len() > n {
+        let live: HashSet<u64> = keys.iter().copied().collect();
+        state.heights.borrow_mut().retain(|k, _| live.contains(k));
+    }
+
+    lazy_scroll(state, axis, total, modifier, children, pinned)
+}
+
+type ItemBuilder = Rc<dyn Fn(u64) -> View>;
+
+/// The items of a [`LazyColumnWith`] or [`LazyRow`], declared in order. Declaring is cheap:
+/// an item's builder only runs while it is near the viewport.
+#[derive(Default)]
+pub struct LazyListScope {
+    keys: Vec<u64>,
+    // Index of the first item of each `items` call, with its builder.
+    builders: Vec<(usize, ItemBuilder)>,
+    // Indices of the sticky headers.
+    sticky: Vec<usize>,
+}
+
+impl LazyListScope {
+    /// One item, e.g. a section header. `key` must be unique in the list.
+    pub fn item(&mut self, key: u64, content: impl Fn() -> View + 'static) {
+        self.items([key], move |_| content());
+    }
+
+    /// A section header that sticks to the start of the viewport while the items after it
+    /// scroll under it, until the next header pushes it out. Give it an opaque background.
+    pub fn sticky_header(&mut self, key: u64, content: impl Fn() -> View + 'static) {
+        self.sticky.push(self.keys.len());
+        self.item(key, content);
+    }
+
+    /// An item for each key, built by `content(key)`. Keys must be unique in the list; the
+    /// list's position and each item's remembered state follow them.
+    pub fn items(
+        &mut self,
+        keys: impl IntoIterator<Item = u64>,
+        content: impl Fn(u64) -> View + 'static,
+    ) {
+        self.builders.push((self.keys.len(), Rc::new(content)));
+        self.keys.extend(keys);
+    }
+
+    // Item `i`, in its `keyed` group of `state`'s list.
+    fn build(&self, state: &LazyColumnState, i: usize) -> View {
+        let key = self.keys[i];
+        state.built.borrow_mut().insert(key);
+        let b = self.builders.partition_point(|(first, _)| *first <= i) - 1;
+        keyed(item_group(state.id, key), || (self.builders[b].1)(key))
+    }
+
+    // Drops the remembered state of items that have left the list.
+    fn forget_removed(&self, state: &LazyColumnState) {
+        if state.built.borrow().len() > self.keys.len() {
+            let live: HashSet<u64> = self.keys.iter().copied().collect();
+            state.built.borrow_mut().retain(|k| {
+                let keep = live.contains(k);
+                if !keep {
+                    forget_keyed(item_group(state.id, *k));
+                }
+                keep
+            });
+        }
+    }
+}
+
+/// Virtualized list of keyed items that may differ in height.
+///
+/// Items are laid out at `estimated_item_height_dp` until they have been on screen once;
+/// after that their measured height is used, so the scrollbar settles as the list is
+/// explored. Only items near the viewport are built. Remembered state inside an item is
+/// kept by key, so it survives the item scrolling out of view and back, and the items
+/// around it being inserted or removed.
+///
+/// ```rust
+/// use repose_core::*;
+/// use repose_ui::lazy::{LazyColumnState, LazyColumnWith};
+/// use repose_ui::Text;
+/// use std::rc::Rc;
+///
+/// fn inbox(ids: Rc<Vec<u64>>, state: Rc<LazyColumnState>) -> View {
+///     LazyColumnWith(state, 56.0, Modifier::new().fill_max_size(), |list| {
+///         list.sticky_header(u64::MAX, || Text("Inbox"));
+///         list.items(ids.iter().copied(), |id| Text(format!("Message {id}")));
+///     })
+/// }
+/// ```
+#[allow(non_snake_case)]
+pub fn LazyColumnWith(
+    state: Rc<LazyColumnState>,
+    estimated_item_height_dp: f32,
+    modifier: Modifier,
+    content: impl FnOnce(&mut LazyListScope),
+) -> View {
+    lazy_list(
+        state,
+        Axis::Vertical,
+        estimated_item_height_dp,
+        modifier,
+        content,
+    )
+}
+
+/// [`LazyColumnWith`] laid out left to right and scrolled horizontally, e.g. a carousel.
+/// Items are `estimated_item_width_dp` wide until measured; a mouse wheel without a
+/// horizontal axis scrolls it too.
+#[allow(non_snake_case)]
+pub fn LazyRow(
+    state: Rc<LazyColumnState>,
+    estimated_item_width_dp: f32,
+    modifier: Modifier,
+    content: impl FnOnce(&mut LazyListScope),
+) -> View {
+    lazy_list(
+        state,
+        Axis::Horizontal,
+        estimated_item_width_dp,
+        modifier,
+        content,
     )
-    .modifier(modifier)
-    .with_children(vec![content])
+}
+
+fn lazy_list(
+    state: Rc<LazyColumnState>,
+    axis: Axis,
+    estimate_dp: f32,
+    modifier: Modifier,
+    content: impl FnOnce(&mut LazyListScope),
+) -> View {
+    let mut list = LazyListScope::default();
+    content(&mut list);
+    let view = virtualize(
+        state.clone(),
+        axis,
+        dp_to_px(estimate_dp),
+        &list.keys,
+        &list.sticky,
+        |i| list.build(&state, i),
+        modifier,
+    );
+    list.forget_removed(&state);
+    view
+}
+
+/// How many columns a [`LazyGrid`] item takes; clamped to the grid's column count.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GridItemSpan(pub usize);
+
+impl GridItemSpan {
+    /// A whole row, e.g. for a section header.
+    pub const FULL_LINE: Self = Self(usize::MAX);
+}
+
+impl Default for GridItemSpan {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+/// The items of a [`LazyGrid`], declared in order.
+#[derive(Default)]
+pub struct LazyGridScope {
+    list: LazyListScope,
+    spans: Vec<GridItemSpan>,
+}
+
+impl LazyGridScope {
+    /// One item `span` columns wide. `key` must be unique in the grid.
+    pub fn item(&mut self, key: u64, span: GridItemSpan, content: impl Fn() -> View + 'static) {
+        self.items_spanned([key], move |_| span, move |_| content());
+    }
+
+    /// A one-column item for each key, built by `content(key)`. Keys must be unique in the
+    /// grid; each item's remembered state follows its key.
+    pub fn items(
+        &mut self,
+        keys: impl IntoIterator<Item = u64>,
+        content: impl Fn(u64) -> View + 'static,
+    ) {
+        self.items_spanned(keys, |_| GridItemSpan::default(), content);
+    }
+
+    /// An item for each key, `span(key)` columns wide and built by `content(key)`.
+    pub fn items_spanned(
+        &mut self,
+        keys: impl IntoIterator<Item = u64>,
+        span: impl Fn(u64) -> GridItemSpan,
+        content: impl Fn(u64) -> View + 'static,
+    ) {
+        let first = self.list.keys.len();
+        self.list.items(keys, content);
+        self.spans
+            .extend(self.list.keys[first..].iter().map(|&k| span(k)));
+    }
+}
+
+/// Virtualized vertical grid of keyed items in `columns` equal columns, e.g. a photo
+/// gallery.
+///
+/// Items fill each row left to right; one that doesn't fit in what is left of a row (see
+/// [`GridItemSpan`]) starts the next. Rows are virtualized like [`LazyColumnWith`] items:
+/// one counts as `estimated_row_height_dp` tall until it has been laid out, and only rows
+/// near the viewport are built.
+///
+/// ```rust
+/// use repose_core::*;
+/// use repose_ui::lazy::{GridItemSpan, LazyColumnState, LazyGrid};
+/// use repose_ui::{Box, Text};
+/// use std::rc::Rc;
+///
+/// fn gallery(photos: Rc<Vec<u64>>, state: Rc<LazyColumnState>) -> View {
+///     LazyGrid(state, 3, 120.0, Modifier::new().fill_max_size(), |grid| {
+///         grid.item(u64::MAX, GridItemSpan::FULL_LINE, || Text("Recent"));
+///         grid.items(photos.iter().copied(), |_| {
+///             Box(Modifier::new().aspect_ratio(1.0))
+///         });
+///     })
+/// }
+/// ```
+#[allow(non_snake_case)]
+pub fn LazyGrid(
+    state: Rc<LazyColumnState>,
+    columns: usize,
+    estimated_row_height_dp: f32,
+    modifier: Modifier,
+    content: impl FnOnce(&mut LazyGridScope),
+) -> View {
+    let mut grid = LazyGridScope::default();
+    content(&mut grid);
+    let columns = columns.max(1);
+    let span_of = |i: usize| grid.spans[i].0.clamp(1, columns);
+
+    // The first item of each row, then the item count.
+    let mut starts = Vec::new();
+    let mut used = columns;
+    for i in 0..grid.spans.len() {
+        if used + span_of(i) > columns {
+            starts.push(i);
+            used = 0;
+        }
+        used += span_of(i);
+    }
+    let row_keys: Vec<u64> = starts.iter().map(|&i| grid.list.keys[i]).collect();
+    starts.push(grid.spans.len());
+
+    let build_row = |r: usize| {
+        let mut cells: Vec<View> = (starts[r]..starts[r + 1])
+            .map(|i| {
+                // A column, so the item is stretched to the cell's width.
+                crate::Column(Modifier::new().weight(span_of(i) as f32))
+                    .with_children(vec![grid.list.build(&state, i)])
+            })
+            .collect();
+        let used: usize = (starts[r]..starts[r + 1]).map(span_of).sum();
+        if used < columns {
+            // Keeps the cells of a short row at column width.
+            cells.push(crate::Box(Modifier::new().weight((columns - used) as f32)));
+        }
+        crate::Row(Modifier::new().fill_max_width()).with_children(cells)
+    };
+    let view = virtualize(
+        state.clone(),
+        Axis::Vertical,
+        dp_to_px(estimated_row_height_dp),
+        &row_keys,
+        &[],
+        build_row,
+        modifier,
+    );
+    grid.list.forget_removed(&state);
+    view
+}
+
+// The `keyed` group (and view key) of item `key` in list `list`.
+fn item_group(list: u64, key: u64) -> u64 {
+    list.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ key
 }
 
 /// List without virtualization (for small lists)
@@ -44,11 +44,16 @@
 //! - Size hints: `size`, `width`, `height`, `min_size`, `max_size`,
 //!   `fill_max_size`, `fill_max_width`, `fill_max_height`.
 //! - Box model: `padding`, `padding_values`.
-//! - Visuals: `background`, `background_brush`, `border`, `clip_rounded`, `alpha`, `transform`.
+//! - Visuals: `background`, `background_brush`, `border`, `clip_rounded`, `alpha`, `transform`,
+//!   `graphics_layer` (paint-only, never relayouts).
+//!   These and `padding` apply in call order: `background(c).padding(8.0)` fills the whole
+//!   node, `padding(8.0).background(c)` only the inset box. Calling one again replaces it.
 //! - Flex / grid: `flex_grow`, `flex_shrink`, `flex_basis`, `align_self`,
 //!   `justify_content`, `align_items`, `grid`, `grid_span`.
-//! - Positioning: `absolute()`, `offset(..)` for overlay / Stack / FABs.
-//! - Interaction: `clickable()`, pointer callbacks, `on_scroll`, `semantics`.
+//! - Positioning: `absolute()`, `offset(..)`, `z_index` for overlay / Stack / FABs.
+//! - Interaction: `clickable()`, `toggleable()`, pointer callbacks, `on_key_event`, `on_scroll`, `semantics`.
+//! - Animation: `animate_content_size` (paint-time clip), `animate_expand` (layout follows),
+//!   `shared_element` (moves in from where the same key was painted last frame).
 //! - Custom paint: `painter` (used by `repose-canvas`).
 //!
 //! Example:
@@ -60,10 +65,10 @@
 //! fn CardExample() -> View {
 //!     Surface(
 //!         Modifier::new()
-//!             .padding(16.0)
 //!             .background(Color::from_hex("#1E1E1E"))
 //!             .border(1.0, Color::from_hex("#333333"), 8.0)
-//!             .clip_rounded(8.0),
+//!             .clip_rounded(8.0)
+//!             .padding(16.0),
 //!         Text("Hello, Repose!"),
 //!     )
 //! }
@@ -81,7 +86,7 @@
 //! pub fn layout_and_paint(
 //!     root: &View,
 //!     size_px: (u32, u32),
-//!     textfield_states: &HashMap<u64, Rc<RefCell<TextFieldState>>>,
+//!     retained: &RetainedStateStore,
 //!     interactions: &Interactions,
 //!     focused: Option<u64>,
 //! ) -> (Scene, Vec<HitRegion>, Vec<SemNode>);
@@ -104,10 +109,18 @@
 
 pub mod anim;
 pub mod anim_ext;
+pub mod breadcrumbs;
+pub mod dialog;
+pub mod dropdown;
 pub mod gestures;
 pub mod lazy;
+pub mod minimap;
 pub mod navigation;
+pub mod pagination;
+pub mod print;
 pub mod scroll;
+pub mod search;
+pub mod walk_cache;
 
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
@@ -116,17 +129,32 @@ use std::{cell::RefCell, cmp::Ordering};
 use repose_core::*;
 use taffy::style::FlexDirection;
 use taffy::{Overflow, Point};
+use web_time::Instant;
 
+pub mod text_diagnostics;
+pub mod text_selection;
 pub mod textfield;
-pub use textfield::{TextField, TextFieldState};
-
-use crate::textfield::{TF_FONT_DP, TF_PADDING_X_DP, byte_to_char_index, measure_text};
+pub mod tooltip;
+pub mod zoom;
+pub use textfield::{TextArea, TextField, TextFieldExt, TextFieldState};
+
+use crate::textfield::{
+    TA_DEFAULT_LINES, TF_FONT_DP, TF_PADDING_X_DP, TF_PADDING_Y_DP, byte_to_char_index,
+    measure_text,
+};
+use repose_core::animation::Interpolate;
 use repose_core::locals;
+use unicode_segmentation::UnicodeSegmentation;
+
+// Link hit ids in a `RichText` are `text_id ^ (LINK_SALT + n)`.
+const LINK_SALT: u64 = 0x6c69_6e6b_0000_0000;
 
 #[derive(Default)]
 pub struct Interactions {
     pub hover: Option<u64>,
     pub pressed: HashSet<u64>,
+    /// Mouse position in px while it's over the window; shows overflow tooltips.
+    pub pointer: Option<Vec2>,
 }
 
 pub fn Surface(modifier: Modifier, child: View) -> View {
@@ -151,7 +179,14 @@ pub fn Stack(modifier: Modifier) -> View {
     View::new(0, ViewKind::Stack).modifier(modifier)
 }
 
-#[deprecated = "Use ScollArea instead"]
+/// The subtree `build` returns, built again only when `key` changes; see
+/// [`repose_core::memo`].
+pub fn Memo<K: PartialEq + 'static>(key: K, build: impl FnOnce() -> View) -> View {
+    repose_core::memo(key, build)
+}
+
+#[deprecated = "use scroll::ScrollArea instead"]
+#[allow(deprecated)]
 pub fn Scroll(modifier: Modifier) -> View {
     View::new(
         0,
@@ -166,6 +201,17 @@ pub fn Scroll(modifier: Modifier) -> View {
     .modifier(modifier)
 }
 
+/// Text with styled runs and links, from an [`AnnotatedText`]. Styles the spans leave unset
+/// come from the usual `Text` modifiers (`.color()`, `.size()`…); lines wrap across spans.
+pub fn RichText(text: impl Into<AnnotatedText>) -> View {
+    let AnnotatedText { text, spans } = text.into();
+    let mut v = Text(text);
+    if let ViewKind::Text { spans: s, .. } = &mut v.kind {
+        *s = spans;
+    }
+    v
+}
+
 pub fn Text(text: impl Into<String>) -> View {
     View::new(
         0,
@@ -173,13 +219,37 @@ pub fn Text(text: impl Into<String>) -> View {
             text: text.into(),
             color: Color::WHITE,
             font_size: 16.0, // dp (converted to px in layout/paint)
+            line_height: None,
+            letter_spacing: 0.0,
+            paragraph_spacing: 0.0,
             soft_wrap: true,
             max_lines: None,
             overflow: TextOverflow::Visible,
+            align: TextAlign::Start,
+            spans: Vec::new(),
         },
     )
 }
 
+/// Builds `content` at a different dp→px density than the host window (e.g. device previews,
+/// zoomed canvases). The override applies both while composing and during layout/paint.
+pub fn WithDensity(scale: f32, content: impl FnOnce() -> View) -> View {
+    let density = Density {
+        scale: scale.max(0.0),
+    };
+    let mut v = with_density(density, content);
+    v.modifier.density = Some(density);
+    v
+}
+
+/// Builds `content` with a different user text scale than the host window.
+pub fn WithTextScale(scale: f32, content: impl FnOnce() -> View) -> View {
+    let ts = TextScale(scale.max(0.0));
+    let mut v = with_text_scale(ts, content);
+    v.modifier.text_scale = Some(ts);
+    v
+}
+
 pub fn Spacer() -> View {
     Box(Modifier::new().flex_grow(1.0))
 }
@@ -343,6 +413,25 @@ pub fn ProgressBar(value: f32, range: (f32, f32)) -> View {
     })
 }
 
+/// A header that opens and closes the `content` below it. `expanded` is the state; clicking
+/// the header, or Enter/Space while it is focused, calls `on_toggle` with the new value. The
+/// content's height animates (see [`Modifier::animate_expand`]), and a list or scroll area
+/// around the section follows it frame by frame. The content is built even while closed.
+pub fn ExpandableSection(
+    header: View,
+    expanded: bool,
+    on_toggle: impl Fn(bool) + 'static,
+    content: View,
+) -> View {
+    Column(Modifier::new().fill_max_width()).child(vec![
+        Row(Modifier::new()
+            .fill_max_width()
+            .toggleable(Role::Button, expanded, move || on_toggle(!expanded)))
+        .child(header),
+        Column(Modifier::new().fill_max_width().animate_expand(expanded)).child(content),
+    ])
+}
+
 pub fn Image(modifier: Modifier, handle: ImageHandle) -> View {
     View::new(
         0,
@@ -355,6 +444,11 @@ pub fn Image(modifier: Modifier, handle: ImageHandle) -> View {
     .modifier(modifier)
 }
 
+/// A view drawn by a third-party [`Widget`](repose_core::Widget).
+pub fn Custom(modifier: Modifier, widget: impl repose_core::Widget + 'static) -> View {
+    View::new(0, ViewKind::Custom(Rc::new(widget))).modifier(modifier)
+}
+
 pub trait ImageExt {
     fn image_tint(self, c: Color) -> View;
     fn image_fit(self, fit: ImageFit) -> View;
@@ -374,6 +468,7 @@ impl ImageExt for View {
     }
 }
 
+#[allow(deprecated)]
 fn flex_dir_for(kind: &ViewKind) -> Option<FlexDirection> {
     match kind {
         ViewKind::Row => {
@@ -444,14 +539,172 @@ impl_into_children_tuple!(0 A, 1 B, 2 C, 3 D, 4 E, 5 F);
 impl_into_children_tuple!(0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G);
 impl_into_children_tuple!(0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H);
 
+/// Runs `f` with the subtree locals (`Density`, `TextScale`) a modifier overrides, if any.
+fn with_modifier_locals<R>(m: &Modifier, f: impl FnOnce() -> R) -> R {
+    match (m.density, m.text_scale) {
+        (None, None) => f(),
+        (Some(d), None) => with_density(d, f),
+        (None, Some(ts)) => with_text_scale(ts, f),
+        (Some(d), Some(ts)) => with_density(d, || with_text_scale(ts, f)),
+    }
+}
+
+/// Why a subtree (or the whole frame) couldn't be laid out.
+#[derive(Debug)]
+pub enum LayoutError {
+    /// A modifier or widget carried a NaN/infinite dimension.
+    NonFinite { view: ViewId, field: &'static str },
+    /// Taffy rejected a tree operation.
+    Taffy(taffy::TaffyError),
+}
+
+impl std::fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayoutError::NonFinite { view, field } => {
+                write!(f, "non-finite `{field}` on view {view:#x}")
+            }
+            LayoutError::Taffy(e) => write!(f, "taffy: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+impl From<taffy::TaffyError> for LayoutError {
+    fn from(e: taffy::TaffyError) -> Self {
+        LayoutError::Taffy(e)
+    }
+}
+
+/// First non-finite dimension on a modifier, if any.
+fn non_finite_field(m: &Modifier) -> Option<&'static str> {
+    let size = m.size.map(|s| (s.width, s.height));
+    let pv = m
+        .total_padding()
+        .map(|p| (p.left, p.right, p.top, p.bottom));
+    let fields = [
+        ("size.width", size.map(|s| s.0)),
+        ("size.height", size.map(|s| s.1)),
+        ("width", m.width),
+        ("height", m.height),
+        ("min_width", m.min_width),
+        ("min_height", m.min_height),
+        ("max_width", m.max_width),
+        ("max_height", m.max_height),
+        ("padding.left", pv.map(|p| p.0)),
+        ("padding.right", pv.map(|p| p.1)),
+        ("padding.top", pv.map(|p| p.2)),
+        ("padding.bottom", pv.map(|p| p.3)),
+        ("flex_grow", m.flex_grow),
+        ("flex_shrink", m.flex_shrink),
+        ("flex_basis", m.flex_basis),
+        ("offset_left", m.offset_left),
+        ("offset_right", m.offset_right),
+        ("offset_top", m.offset_top),
+        ("offset_bottom", m.offset_bottom),
+        ("margin_left", m.margin_left),
+        ("margin_right", m.margin_right),
+        ("margin_top", m.margin_top),
+        ("margin_bottom", m.margin_bottom),
+        ("aspect_ratio", m.aspect_ratio),
+    ];
+    fields
+        .into_iter()
+        .find(|(_, v)| v.is_some_and(|v| !v.is_finite()))
+        .map(|(name, _)| name)
+}
+
 /// Layout and paint with TextField state injection (Taffy 0.9 API)
+///
+/// Every view in `root` is marked live in `retained`; the runner evicts the state of views
+/// that weren't once the frame is done.
+///
+/// Subtrees that fail to lay out are replaced by an error placeholder; if the frame as a
+/// whole can't be laid out, the returned scene only carries an error message. Use
+/// [`try_layout_and_paint`] to handle the latter case yourself.
 pub fn layout_and_paint(
     root: &View,
     size_px_u32: (u32, u32),
-    textfield_states: &HashMap<u64, Rc<RefCell<TextFieldState>>>,
+    retained: &RetainedStateStore,
     interactions: &Interactions,
     focused: Option<u64>,
 ) -> (Scene, Vec<HitRegion>, Vec<SemNode>) {
+    try_layout_and_paint(root, size_px_u32, retained, interactions, focused).unwrap_or_else(|e| {
+        log::error!("layout_and_paint: {e}");
+        let scene = Scene {
+            clear_color: locals::theme().background,
+            nodes: vec![SceneNode::Text {
+                rect: crate::Rect {
+                    x: dp_to_px(8.0),
+                    y: dp_to_px(8.0),
+                    w: size_px_u32.0 as f32,
+                    h: dp_to_px(20.0),
+                },
+                text: format!("Layout failed: {e}").into(),
+                color: Color::from_hex("#FF5555"),
+                size: dp_to_px(14.0),
+                letter_spacing: 0.0,
+                weight: FontWeight::NORMAL,
+            }],
+        };
+        (scene, vec![], vec![])
+    })
+}
+
+thread_local! {
+    /// Whether the next pass measures the root's natural size, and what it measured.
+    static CONTENT_SIZE: std::cell::Cell<(bool, Option<Vec2>)> =
+        const { std::cell::Cell::new((false, None)) };
+}
+
+/// Asks the next layout pass to also measure how big the root wants to be with no size
+/// imposed on it (max-content in both axes), for [`take_content_size`]. Runners use this
+/// to size a window to its content.
+pub fn measure_content_size() {
+    CONTENT_SIZE.set((true, None));
+}
+
+/// The root's natural size (px) measured on behalf of [`measure_content_size`], once.
+pub fn take_content_size() -> Option<Vec2> {
+    let (pending, size) = CONTENT_SIZE.get();
+    CONTENT_SIZE.set((pending, None));
+    size
+}
+
+/// Like [`layout_and_paint`], but reports a frame-level layout failure instead of
+/// painting an error message.
+pub fn try_layout_and_paint(
+    root: &View,
+    size_px_u32: (u32, u32),
+    retained: &RetainedStateStore,
+    interactions: &Interactions,
+    focused: Option<u64>,
+) -> Result<(Scene, Vec<HitRegion>, Vec<SemNode>), LayoutError> {
+    paint_frame(root, size_px_u32, retained, interactions, focused, true)
+}
+
+/// The frame behind [`try_layout_and_paint`]. With `cull` off, the scene keeps what lies
+/// outside the window, for callers such as `print` that show more than the window holds.
+pub(crate) fn paint_frame(
+    root: &View,
+    size_px_u32: (u32, u32),
+    retained: &RetainedStateStore,
+    interactions: &Interactions,
+    focused: Option<u64>,
+    cull: bool,
+) -> Result<(Scene, Vec<HitRegion>, Vec<SemNode>), LayoutError> {
+    let t_measure = Instant::now();
+    let measure_span = tracing::info_span!("layout").entered();
+    anim::begin_content_size_frame();
+    tooltip::begin_pass();
+    walk_cache::begin_pass();
+    minimap::begin_pass();
+    text_selection::begin_pass();
+    text_diagnostics::begin_pass();
+    // Text the last frame dropped won't be drawn again soon; don't keep its glyph runs.
+    repose_text::evict_runs(repose_core::take_swept_text().iter().map(|t| &**t));
+
     // Unit helpers
     // dp -> px using current Density
     let px = |dp_val: f32| dp_to_px(dp_val);
@@ -477,7 +730,7 @@ pub fn layout_and_paint(
         splitmix64(a ^ splitmix64(b))
     }
 
-    fn assign_ids(mut v: View, parent: u64, index: u64) -> View {
+    fn assign_ids(mut v: View, parent: u64, index: u64, retained: &RetainedStateStore) -> View {
         // Prefer explicit keys; otherwise fall back to structural position.
         let salt = v.modifier.key.unwrap_or(index);
 
@@ -486,18 +739,28 @@ pub fn layout_and_paint(
 
         // Avoid 0 as a sentinel.
         v.id = if raw == 0 { 1 } else { raw };
+        v.modifier = repose_core::apply_live_edits(v.id, v.modifier);
+
+        // Still in composition, so keep whatever state the view has.
+        retained.mark_live(v.id);
+        if let ViewKind::TextField { state_key, .. } | ViewKind::TextArea { state_key, .. } =
+            &v.kind
+            && *state_key != 0
+        {
+            retained.mark_live(*state_key);
+        }
 
         v.children = v
             .children
             .into_iter()
             .enumerate()
-            .map(|(i, c)| assign_ids(c, v.id, i as u64))
+            .map(|(i, c)| assign_ids(c, v.id, i as u64, retained))
             .collect();
 
         v
     }
 
-    let root = assign_ids(root.clone(), 0, 0);
+    let root = assign_ids(root.clone(), 0, 0, retained);
 
     // Build Taffy tree (with per-node contexts for measurement)
     use taffy::prelude::*;
@@ -506,14 +769,16 @@ pub fn layout_and_paint(
         Text {
             text: String,
             font_dp: f32, // logical size (dp)
+            line_height_dp: Option<f32>,
+            letter_spacing_dp: f32,
+            paragraph_spacing_dp: f32,
             soft_wrap: bool,
             max_lines: Option<usize>,
             overflow: TextOverflow,
-        },
-        Button {
-            label: String,
+            spans: Vec<TextSpan>,
         },
         TextField,
+        TextArea,
         Container,
         ScrollContainer,
         Checkbox,
@@ -522,6 +787,11 @@ pub fn layout_and_paint(
         Slider,
         Range,
         Progress,
+        Custom(Rc<dyn repose_core::Widget>),
+        /// A subtree whose layout couldn't be built; painted as a placeholder.
+        Error {
+            message: String,
+        },
     }
 
     let mut taffy: TaffyTree<NodeCtx> = TaffyTree::new();
@@ -530,12 +800,32 @@ pub fn layout_and_paint(
     #[derive(Clone)]
     struct TextLayout {
         lines: Vec<String>,
+        /// Top of each line relative to the content box (paragraph spacing included).
+        line_ys: Vec<f32>,
         size_px: f32,
         line_h_px: f32,
+        letter_spacing_px: f32,
+        /// Lines were dropped or ellipsized to fit.
+        truncated: bool,
+        /// Which of `lines` end a paragraph (aren't justified).
+        para_ends: Vec<bool>,
+        /// Lines of text with spans; `lines` holds the same text.
+        rich: Vec<RichLine>,
+    }
+    #[derive(Clone)]
+    struct RichLine {
+        range: std::ops::Range<usize>,
+        /// Top relative to the content box.
+        y: f32,
+        w: f32,
+        h: f32,
+        /// Baseline below the top.
+        baseline: f32,
     }
     use std::collections::HashMap as StdHashMap;
     let mut text_cache: StdHashMap<taffy::NodeId, TextLayout> = StdHashMap::new();
 
+    #[allow(deprecated)]
     fn style_from_modifier(m: &Modifier, kind: &ViewKind, px: &dyn Fn(f32) -> f32) -> taffy::Style {
         use taffy::prelude::*;
 
@@ -598,6 +888,7 @@ pub fn layout_and_paint(
                 | ViewKind::Text { .. }
                 | ViewKind::Button { .. }
                 | ViewKind::TextField { .. }
+                | ViewKind::TextArea { .. }
                 | ViewKind::Image { .. }
                 | ViewKind::Checkbox { .. }
                 | ViewKind::RadioButton { .. }
@@ -607,6 +898,7 @@ pub fn layout_and_paint(
                 | ViewKind::ProgressBar { .. }
                 | ViewKind::ScrollV { .. }
                 | ViewKind::ScrollXY { .. }
+                | ViewKind::Custom(_)
         ) {
             s.flex_shrink = 1.0;
         }
@@ -685,22 +977,14 @@ pub fn layout_and_paint(
             };
         }
 
-        // Padding
-        if let Some(pv_dp) = m.padding_values {
+        // Padding (chained paddings add up)
+        if let Some(pv_dp) = m.total_padding() {
             s.padding = Rect {
                 left: length(px(pv_dp.left)),
                 right: length(px(pv_dp.right)),
                 top: length(px(pv_dp.top)),
                 bottom: length(px(pv_dp.bottom)),
             };
-        } else if let Some(p_dp) = m.padding {
-            let v = length(px(p_dp));
-            s.padding = Rect {
-                left: v,
-                right: v,
-                top: v,
-                bottom: v,
-            };
         }
 
         // Explicit size (highest priority)
@@ -776,7 +1060,7 @@ pub fn layout_and_paint(
             }
 
             // Default padding unless user provided padding
-            if m.padding.is_none() && m.padding_values.is_none() {
+            if m.total_padding().is_none() {
                 let ph = px(14.0);
                 let pv = px(10.0);
                 s.padding = Rect {
@@ -818,17 +1102,96 @@ pub fn layout_and_paint(
         s
     }
 
+    // Nodes under a `WithDensity`/`WithTextScale` subtree, with the locals to re-enter when
+    // measuring them (measure runs after the build pass, outside of those frames).
+    let mut scoped_locals: HashMap<taffy::NodeId, (Density, TextScale)> = HashMap::new();
+
     fn build_node(
         v: &View,
         t: &mut TaffyTree<NodeCtx>,
         nodes_map: &mut HashMap<ViewId, taffy::NodeId>,
-    ) -> taffy::NodeId {
+        scoped_locals: &mut HashMap<taffy::NodeId, (Density, TextScale)>,
+        in_scope: bool,
+    ) -> Result<taffy::NodeId, LayoutError> {
+        let in_scope = in_scope || v.modifier.density.is_some() || v.modifier.text_scale.is_some();
+        with_modifier_locals(&v.modifier, || {
+            let node = build_node_inner(v, t, nodes_map, scoped_locals, in_scope)?;
+            if in_scope {
+                scoped_locals.insert(node, (locals::density(), locals::text_scale()));
+            }
+            Ok(node)
+        })
+    }
+
+    /// Builds `v`, substituting an error placeholder leaf if its subtree fails.
+    fn build_child(
+        v: &View,
+        t: &mut TaffyTree<NodeCtx>,
+        nodes_map: &mut HashMap<ViewId, taffy::NodeId>,
+        scoped_locals: &mut HashMap<taffy::NodeId, (Density, TextScale)>,
+        in_scope: bool,
+    ) -> Result<taffy::NodeId, LayoutError> {
+        match build_node(v, t, nodes_map, scoped_locals, in_scope) {
+            Ok(n) => Ok(n),
+            Err(e) => {
+                log::error!("layout: {e}; painting a placeholder instead");
+                let style = Style {
+                    size: taffy::geometry::Size {
+                        width: length(dp_to_px(48.0)),
+                        height: length(dp_to_px(24.0)),
+                    },
+                    ..Default::default()
+                };
+                let n = t.new_leaf_with_context(
+                    style,
+                    NodeCtx::Error {
+                        message: e.to_string(),
+                    },
+                )?;
+                nodes_map.insert(v.id, n);
+                Ok(n)
+            }
+        }
+    }
+
+    #[allow(deprecated)]
+    fn build_node_inner(
+        v: &View,
+        t: &mut TaffyTree<NodeCtx>,
+        nodes_map: &mut HashMap<ViewId, taffy::NodeId>,
+        scoped_locals: &mut HashMap<taffy::NodeId, (Density, TextScale)>,
+        in_scope: bool,
+    ) -> Result<taffy::NodeId, LayoutError> {
+        if let Some(field) = non_finite_field(&v.modifier) {
+            return Err(LayoutError::NonFinite { view: v.id, field });
+        }
+        if let ViewKind::Text { font_size, .. } = &v.kind
+            && !font_size.is_finite()
+        {
+            return Err(LayoutError::NonFinite {
+                view: v.id,
+                field: "font_size",
+            });
+        }
+
         // We'll inject px() at call-site (need locals access); this function
         // is called from a scope that has the helper closure.
         let px_helper = |dp_val: f32| dp_to_px(dp_val);
 
         let mut style = style_from_modifier(&v.modifier, &v.kind, &px_helper);
 
+        // A shrinking `animate_content_size` keeps its old size until the animation settles.
+        for el in v.modifier.elements().iter() {
+            if let ModifierElement::AnimateContentSize(spec) = el
+                && let Some(hold) = anim::content_size_hold(v.id, *spec)
+            {
+                style.min_size = taffy::geometry::Size {
+                    width: length(hold.x),
+                    height: length(hold.y),
+                };
+            }
+        }
+
         if v.modifier.grid_col_span.is_some() || v.modifier.grid_row_span.is_some() {
             use taffy::prelude::{GridPlacement, Line};
 
@@ -845,87 +1208,78 @@ pub fn layout_and_paint(
             };
         }
 
-        let children: Vec<_> = v
+        let children = v
             .children
             .iter()
-            .map(|c| build_node(c, t, nodes_map))
-            .collect();
+            .map(|c| build_child(c, t, nodes_map, scoped_locals, in_scope))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let node = match &v.kind {
             ViewKind::Text {
                 text,
                 font_size: font_dp,
+                line_height,
+                letter_spacing,
+                paragraph_spacing,
                 soft_wrap,
                 max_lines,
                 overflow,
+                spans,
                 ..
-            } => t
-                .new_leaf_with_context(
-                    style,
-                    NodeCtx::Text {
-                        text: text.clone(),
-                        font_dp: *font_dp,
-                        soft_wrap: *soft_wrap,
-                        max_lines: *max_lines,
-                        overflow: *overflow,
-                    },
-                )
-                .unwrap(),
+            } => t.new_leaf_with_context(
+                style,
+                NodeCtx::Text {
+                    text: text.clone(),
+                    font_dp: *font_dp,
+                    line_height_dp: *line_height,
+                    letter_spacing_dp: *letter_spacing,
+                    paragraph_spacing_dp: *paragraph_spacing,
+                    soft_wrap: *soft_wrap,
+                    max_lines: *max_lines,
+                    overflow: *overflow,
+                    spans: spans.clone(),
+                },
+            )?,
             ViewKind::Button { .. } => {
-                let children: Vec<_> = v
-                    .children
-                    .iter()
-                    .map(|c| build_node(c, t, nodes_map))
-                    .collect();
-                let n = t.new_with_children(style, &children).unwrap();
+                let n = t.new_with_children(style, &children)?;
                 t.set_node_context(n, Some(NodeCtx::Container)).ok();
                 n
             }
-            ViewKind::TextField { .. } => {
-                t.new_leaf_with_context(style, NodeCtx::TextField).unwrap()
-            }
-            ViewKind::Image { .. } => t.new_leaf_with_context(style, NodeCtx::Container).unwrap(),
-            ViewKind::Checkbox { .. } => t
-                .new_leaf_with_context(style, NodeCtx::Checkbox {})
-                .unwrap(),
-            ViewKind::RadioButton { .. } => {
-                t.new_leaf_with_context(style, NodeCtx::Radio {}).unwrap()
-            }
-            ViewKind::Switch { .. } => t.new_leaf_with_context(style, NodeCtx::Switch {}).unwrap(),
-            ViewKind::Slider { .. } => t.new_leaf_with_context(style, NodeCtx::Slider).unwrap(),
-            ViewKind::RangeSlider { .. } => t.new_leaf_with_context(style, NodeCtx::Range).unwrap(),
-            ViewKind::ProgressBar { .. } => {
-                t.new_leaf_with_context(style, NodeCtx::Progress).unwrap()
+            ViewKind::TextField { .. } => t.new_leaf_with_context(style, NodeCtx::TextField)?,
+            ViewKind::TextArea { .. } => t.new_leaf_with_context(style, NodeCtx::TextArea)?,
+            ViewKind::Image { .. } => t.new_leaf_with_context(style, NodeCtx::Container)?,
+            ViewKind::Checkbox { .. } => t.new_leaf_with_context(style, NodeCtx::Checkbox {})?,
+            ViewKind::RadioButton { .. } => t.new_leaf_with_context(style, NodeCtx::Radio {})?,
+            ViewKind::Switch { .. } => t.new_leaf_with_context(style, NodeCtx::Switch {})?,
+            ViewKind::Slider { .. } => t.new_leaf_with_context(style, NodeCtx::Slider)?,
+            ViewKind::RangeSlider { .. } => t.new_leaf_with_context(style, NodeCtx::Range)?,
+            ViewKind::ProgressBar { .. } => t.new_leaf_with_context(style, NodeCtx::Progress)?,
+            ViewKind::Custom(w) if children.is_empty() => {
+                t.new_leaf_with_context(style, NodeCtx::Custom(w.clone()))?
             }
             ViewKind::ScrollV { .. } => {
-                let children: Vec<_> = v
-                    .children
-                    .iter()
-                    .map(|c| build_node(c, t, nodes_map))
-                    .collect();
-
-                let n = t.new_with_children(style, &children).unwrap();
+                let n = t.new_with_children(style, &children)?;
                 t.set_node_context(n, Some(NodeCtx::ScrollContainer)).ok();
                 n
             }
             _ => {
-                let n = t.new_with_children(style, &children).unwrap();
+                let n = t.new_with_children(style, &children)?;
                 t.set_node_context(n, Some(NodeCtx::Container)).ok();
                 n
             }
         };
 
         nodes_map.insert(v.id, node);
-        node
+        Ok(node)
     }
 
-    let root_node = build_node(&root, &mut taffy, &mut nodes_map);
+    let root_node = build_child(&root, &mut taffy, &mut nodes_map, &mut scoped_locals, false)?;
 
     {
-        let mut rs = taffy.style(root_node).unwrap().clone();
+        let mut rs = taffy.style(root_node)?.clone();
         rs.size.width = length(size_px_u32.0 as f32);
         rs.size.height = length(size_px_u32.1 as f32);
-        taffy.set_style(root_node, rs).unwrap();
+        taffy.set_style(root_node, rs)?;
     }
 
     let available = taffy::geometry::Size {
@@ -934,133 +1288,334 @@ pub fn layout_and_paint(
     };
 
     // Measure function for intrinsic content
-    taffy
-        .compute_layout_with_measure(root_node, available, |known, avail, node, ctx, _style| {
-            match ctx {
-                Some(NodeCtx::Text {
-                    text,
-                    font_dp,
-                    soft_wrap,
-                    max_lines,
-                    overflow,
-                }) => {
-                    let size_px_val = font_px(*font_dp);
-                    let line_h_px_val = size_px_val * 1.3;
-
-                    // Rough guess (used only as fallback)
-                    let approx_w_px = text.len() as f32 * size_px_val * 0.6;
-
-                    let target_w_px = match avail.width {
-                        AvailableSpace::Definite(w) if w > 0.5 => w,
-                        _ => known.width.unwrap_or(approx_w_px),
-                    };
-
-                    let mut wrap_w_px = if *soft_wrap || matches!(overflow, TextOverflow::Ellipsis)
-                    {
-                        target_w_px
-                    } else {
-                        known.width.unwrap_or(approx_w_px)
+    let mut measure = |known: taffy::geometry::Size<Option<f32>>,
+                       avail: taffy::geometry::Size<AvailableSpace>,
+                       node: taffy::NodeId,
+                       ctx: Option<&mut NodeCtx>| {
+        match ctx {
+            Some(NodeCtx::Text {
+                text,
+                font_dp,
+                line_height_dp,
+                letter_spacing_dp,
+                paragraph_spacing_dp,
+                soft_wrap,
+                max_lines,
+                overflow,
+                spans,
+            }) => {
+                let size_px_val = font_px(*font_dp);
+                let line_h_px_val = line_height_dp.map(font_px).unwrap_or(size_px_val * 1.3);
+                let ls_px = font_px(*letter_spacing_dp);
+                let para_px = font_px(*paragraph_spacing_dp);
+
+                if !spans.is_empty() {
+                    let runs: Vec<repose_text::StyledRun> = span_runs(text, spans)
+                        .map(|(range, span)| repose_text::StyledRun {
+                            range,
+                            px: span
+                                .and_then(|s| s.style.font_size)
+                                .map_or(size_px_val, font_px),
+                            weight: span.and_then(|s| s.style.weight).unwrap_or_default().0,
+                        })
+                        .collect();
+                    let max_w = match avail.width {
+                        AvailableSpace::Definite(w) if *soft_wrap && w > 0.5 => w,
+                        _ => known.width.unwrap_or(f32::INFINITY),
                     };
-
-                    if wrap_w_px <= 0.5 && !text.is_empty() {
-                        wrap_w_px = approx_w_px.max(1.0);
-                    }
-
-                    // Build lines (wraps by def.)
-                    let mut lines_vec: Vec<String>;
-                    let mut truncated = false;
-
-                    if *soft_wrap {
-                        let (ls, trunc) =
-                            repose_text::wrap_lines(text, size_px_val, wrap_w_px, *max_lines, true);
-                        lines_vec = ls;
-                        truncated = trunc;
-                        if matches!(overflow, TextOverflow::Ellipsis)
-                            && truncated
-                            && !lines_vec.is_empty()
-                        {
-                            let last = lines_vec.len() - 1;
-                            lines_vec[last] = repose_text::ellipsize_line(
-                                &lines_vec[last],
-                                size_px_val,
-                                wrap_w_px,
-                            );
-                        }
-                    } else if matches!(overflow, TextOverflow::Ellipsis) {
-                        if approx_w_px > wrap_w_px + 0.5 {
-                            lines_vec =
-                                vec![repose_text::ellipsize_line(text, size_px_val, wrap_w_px)];
-                        } else {
-                            lines_vec = vec![text.clone()];
+                    let wrapped = repose_text::wrap_runs(text, &runs, ls_px, max_w);
+                    let truncated = max_lines.is_some_and(|m| wrapped.len() > m);
+
+                    // Each line is as tall as its largest run, scaled like the text's own.
+                    let ratio = line_h_px_val / size_px_val.max(1.0);
+                    let mut rich = Vec::new();
+                    let mut y = 0.0;
+                    let mut width: f32 = 0.0;
+                    for (range, w) in wrapped.into_iter().take(max_lines.unwrap_or(usize::MAX)) {
+                        if range.start > 0 && text.as_bytes()[range.start - 1] == b'\n' {
+                            y += para_px;
                         }
-                    } else {
-                        lines_vec = vec![text.clone()];
+                        let sizes = runs
+                            .iter()
+                            .filter(|r| r.range.start < range.end && r.range.end > range.start)
+                            .map(|r| r.px);
+                        let max_px = sizes.clone().fold(0.0, f32::max);
+                        let max_px = if max_px > 0.0 { max_px } else { size_px_val };
+                        let baseline = sizes
+                            .map(repose_text::baseline)
+                            .fold(repose_text::baseline(max_px), f32::max);
+                        rich.push(RichLine {
+                            range,
+                            y,
+                            w,
+                            h: max_px * ratio,
+                            baseline,
+                        });
+                        y += max_px * ratio;
+                        width = width.max(w);
                     }
-
-                    // Cache for paint (much better perf.)
+                    let width = if max_w.is_finite() && *soft_wrap {
+                        max_w
+                    } else {
+                        known.width.unwrap_or(width)
+                    };
                     text_cache.insert(
                         node,
                         TextLayout {
-                            lines: lines_vec.clone(),
+                            lines: rich.iter().map(|l| text[l.range.clone()].into()).collect(),
+                            line_ys: rich.iter().map(|l| l.y).collect(),
                             size_px: size_px_val,
                             line_h_px: line_h_px_val,
+                            letter_spacing_px: ls_px,
+                            truncated,
+                            // Rich lines aren't justified.
+                            para_ends: vec![true; rich.len()],
+                            rich,
                         },
                     );
+                    return taffy::geometry::Size { width, height: y };
+                }
+
+                // Shaped width on one line (per paragraph)
+                let line_w_px = repose_text::measure_width(text, size_px_val, ls_px);
+
+                let target_w_px = match avail.width {
+                    AvailableSpace::Definite(w) if w > 0.5 => w,
+                    _ => known.width.unwrap_or(line_w_px),
+                };
+
+                let mut wrap_w_px = if *soft_wrap || matches!(overflow, TextOverflow::Ellipsis) {
+                    target_w_px
+                } else {
+                    known.width.unwrap_or(line_w_px)
+                };
 
-                    // Height = no. of measured lines
-                    let line_count = lines_vec.len().max(1);
-                    taffy::geometry::Size {
-                        width: wrap_w_px,
-                        height: line_h_px_val * line_count as f32,
+                if wrap_w_px <= 0.5 && !text.is_empty() {
+                    wrap_w_px = line_w_px.max(1.0);
+                }
+
+                // Build lines (wraps by def.); each `\n` starts a new paragraph.
+                let mut lines_vec: Vec<String> = Vec::new();
+                let mut line_ys: Vec<f32> = Vec::new();
+                let mut para_ends: Vec<bool> = Vec::new();
+                let mut truncated = false;
+
+                if *soft_wrap {
+                    for (pi, para) in text.split('\n').enumerate() {
+                        let budget = max_lines.map(|m| m.saturating_sub(lines_vec.len()));
+                        if budget == Some(0) {
+                            truncated = true;
+                            break;
+                        }
+                        let (ls, trunc) = repose_text::wrap_lines_spaced(
+                            para,
+                            size_px_val,
+                            ls_px,
+                            wrap_w_px,
+                            budget,
+                            true,
+                        );
+                        let n = ls.len();
+                        for (i, ln) in ls.into_iter().enumerate() {
+                            line_ys
+                                .push(lines_vec.len() as f32 * line_h_px_val + pi as f32 * para_px);
+                            lines_vec.push(ln);
+                            para_ends.push(i + 1 == n);
+                        }
+                        if trunc {
+                            truncated = true;
+                            break;
+                        }
+                    }
+                    if matches!(overflow, TextOverflow::Ellipsis)
+                        && truncated
+                        && !lines_vec.is_empty()
+                    {
+                        let last = lines_vec.len() - 1;
+                        lines_vec[last] = repose_text::ellipsize_line_spaced(
+                            &lines_vec[last],
+                            size_px_val,
+                            ls_px,
+                            wrap_w_px,
+                        );
                     }
+                } else if matches!(overflow, TextOverflow::Ellipsis) && line_w_px > wrap_w_px + 0.5
+                {
+                    lines_vec = vec![repose_text::ellipsize_line_spaced(
+                        text,
+                        size_px_val,
+                        ls_px,
+                        wrap_w_px,
+                    )];
+                    truncated = lines_vec[0] != *text;
+                } else {
+                    lines_vec = vec![text.clone()];
                 }
-                Some(NodeCtx::Button { label }) => taffy::geometry::Size {
-                    width: (label.len() as f32 * font_px(16.0) * 0.6) + px(24.0),
-                    height: px(36.0),
-                },
-                Some(NodeCtx::TextField) => taffy::geometry::Size {
-                    width: known.width.unwrap_or(px(120.0)),
-                    height: px(36.0),
-                },
-                Some(NodeCtx::Checkbox) => taffy::geometry::Size {
-                    width: known.width.unwrap_or(px(24.0)),
-                    height: px(24.0),
-                },
-                Some(NodeCtx::Radio) => taffy::geometry::Size {
-                    width: known.width.unwrap_or(px(18.0)),
-                    height: px(18.0),
-                },
-                Some(NodeCtx::Switch) => taffy::geometry::Size {
-                    width: known.width.unwrap_or(px(46.0)),
-                    height: px(28.0),
-                },
-                Some(NodeCtx::Slider) => taffy::geometry::Size {
-                    width: known.width.unwrap_or(px(200.0)),
-                    height: px(28.0),
-                },
-                Some(NodeCtx::Range) => taffy::geometry::Size {
-                    width: known.width.unwrap_or(px(220.0)),
-                    height: px(28.0),
-                },
-                Some(NodeCtx::Progress) => taffy::geometry::Size {
-                    width: known.width.unwrap_or(px(200.0)),
-                    height: px(12.0),
-                },
-                Some(NodeCtx::ScrollContainer) | Some(NodeCtx::Container) | None => {
-                    taffy::geometry::Size::ZERO
+                if line_ys.len() != lines_vec.len() {
+                    line_ys = vec![0.0];
+                }
+
+                // Height = bottom of the last measured line
+                let height = line_ys.last().map_or(line_h_px_val, |y| y + line_h_px_val);
+
+                // Cache for paint (much better perf.)
+                text_cache.insert(
+                    node,
+                    TextLayout {
+                        lines: lines_vec,
+                        line_ys,
+                        size_px: size_px_val,
+                        line_h_px: line_h_px_val,
+                        letter_spacing_px: ls_px,
+                        truncated,
+                        para_ends,
+                        rich: Vec::new(),
+                    },
+                );
+
+                taffy::geometry::Size {
+                    width: wrap_w_px,
+                    height,
+                }
+            }
+            Some(NodeCtx::TextField) => taffy::geometry::Size {
+                width: known.width.unwrap_or(px(120.0)),
+                height: px(36.0),
+            },
+            Some(NodeCtx::TextArea) => taffy::geometry::Size {
+                width: known.width.unwrap_or(px(240.0)),
+                height: known.height.unwrap_or(
+                    TA_DEFAULT_LINES as f32 * font_px(TF_FONT_DP) * 1.3 + 2.0 * px(TF_PADDING_Y_DP),
+                ),
+            },
+            Some(NodeCtx::Checkbox) => taffy::geometry::Size {
+                width: known.width.unwrap_or(px(24.0)),
+                height: px(24.0),
+            },
+            Some(NodeCtx::Radio) => taffy::geometry::Size {
+                width: known.width.unwrap_or(px(18.0)),
+                height: px(18.0),
+            },
+            Some(NodeCtx::Switch) => taffy::geometry::Size {
+                width: known.width.unwrap_or(px(46.0)),
+                height: px(28.0),
+            },
+            Some(NodeCtx::Slider) => taffy::geometry::Size {
+                width: known.width.unwrap_or(px(200.0)),
+                height: px(28.0),
+            },
+            Some(NodeCtx::Range) => taffy::geometry::Size {
+                width: known.width.unwrap_or(px(220.0)),
+                height: px(28.0),
+            },
+            Some(NodeCtx::Progress) => taffy::geometry::Size {
+                width: known.width.unwrap_or(px(200.0)),
+                height: px(12.0),
+            },
+            Some(NodeCtx::Custom(w)) => {
+                let max = |a: AvailableSpace| match a {
+                    AvailableSpace::Definite(v) => Some(v),
+                    _ => None,
+                };
+                let s = w.measure(repose_core::Constraints {
+                    known_width: known.width,
+                    known_height: known.height,
+                    max_width: max(avail.width),
+                    max_height: max(avail.height),
+                });
+                taffy::geometry::Size {
+                    width: s.width.max(0.0),
+                    height: s.height.max(0.0),
                 }
             }
+            Some(NodeCtx::ScrollContainer)
+            | Some(NodeCtx::Container)
+            | Some(NodeCtx::Error { .. })
+            | None => taffy::geometry::Size::ZERO,
+        }
+    };
+    let mut measure_scoped =
+        |known, avail, node, ctx: Option<&mut NodeCtx>, _style: &Style| match scoped_locals
+            .get(&node)
+        {
+            Some(&(d, ts)) => with_density(d, || {
+                with_text_scale(ts, || measure(known, avail, node, ctx))
+            }),
+            None => measure(known, avail, node, ctx),
+        };
+    if CONTENT_SIZE.get().0 {
+        let sized = taffy.style(root_node)?.clone();
+        let mut natural = sized.clone();
+        natural.size = taffy::geometry::Size::auto();
+        taffy.set_style(root_node, natural)?;
+        taffy.compute_layout_with_measure(
+            root_node,
+            taffy::geometry::Size::MAX_CONTENT,
+            &mut measure_scoped,
+        )?;
+        let l = taffy.layout(root_node)?;
+        let size = Vec2 {
+            x: l.size.width,
+            y: l.size.height,
+        };
+        CONTENT_SIZE.set((false, Some(size)));
+        taffy.set_style(root_node, sized)?;
+    }
+    {
+        profiling::scope!("taffy layout");
+        taffy.compute_layout_with_measure(root_node, available, &mut measure_scoped)?;
+    }
+
+    // Content-size animations that just started shrinking hold their old size, and
+    // `animate_expand` nodes part way open take a fraction of the height they just measured;
+    // those are the only cases where this frame needs a second layout pass.
+    let held = anim::observe_content_sizes(|id| {
+        let l = taffy.layout(*nodes_map.get(&id)?).ok()?;
+        Some(Vec2 {
+            x: l.size.width,
+            y: l.size.height,
         })
-        .unwrap();
+    });
+    let mut expanding = Vec::new();
+    collect_expanding(&root, &mut expanding);
+    expanding.retain(|(id, p)| *p < 1.0 && nodes_map.contains_key(id));
+    if !held.is_empty() || !expanding.is_empty() {
+        for (id, hold) in held {
+            let node = nodes_map[&id];
+            let mut st = taffy.style(node)?.clone();
+            st.min_size = taffy::geometry::Size {
+                width: length(hold.x),
+                height: length(hold.y),
+            };
+            taffy.set_style(node, st)?;
+        }
+        for (id, p) in expanding {
+            let node = nodes_map[&id];
+            let natural = taffy.layout(node)?.size.height;
+            let mut st = taffy.style(node)?.clone();
+            st.size.height = length(natural * p.max(0.0));
+            st.min_size.height = length(0.0);
+            st.overflow.y = taffy::Overflow::Clip;
+            taffy.set_style(node, st)?;
+        }
+        profiling::scope!("taffy layout");
+        taffy.compute_layout_with_measure(root_node, available, &mut measure_scoped)?;
+    }
+    drop(measure_span);
+    record_phase(
+        FramePhase::Measure,
+        t_measure.elapsed().as_secs_f32() * 1000.0,
+    );
 
     fn layout_of(node: taffy::NodeId, t: &TaffyTree<impl Clone>) -> repose_core::Rect {
-        let l = t.layout(node).unwrap();
-        repose_core::Rect {
-            x: l.location.x,
-            y: l.location.y,
-            w: l.size.width,
-            h: l.size.height,
-        }
+        t.layout(node)
+            .map(|l| repose_core::Rect {
+                x: l.location.x,
+                y: l.location.y,
+                w: l.size.width,
+                h: l.size.height,
+            })
+            .unwrap_or_default()
     }
 
     fn add_offset(mut r: repose_core::Rect, off: (f32, f32)) -> repose_core::Rect {
@@ -1069,6 +1624,129 @@ pub fn layout_and_paint(
         r
     }
 
+    // Approximates a blurred drop shadow with a few expanding translucent rects
+    fn push_shadow(scene: &mut Scene, r: repose_core::Rect, radius: f32, sh: Shadow, alpha: f32) {
+        const STEPS: usize = 4;
+        let blur = dp_to_px(sh.blur).max(0.0);
+        let base = repose_core::Rect {
+            x: r.x + dp_to_px(sh.offset.x),
+            y: r.y + dp_to_px(sh.offset.y),
+            ..r
+        };
+        let color = mul_alpha_color(sh.color, alpha / STEPS as f32);
+        for i in (0..STEPS).rev() {
+            let spread = blur * (i as f32 + 1.0) / STEPS as f32;
+            scene.nodes.push(SceneNode::Rect {
+                rect: repose_core::Rect {
+                    x: base.x - spread,
+                    y: base.y - spread,
+                    w: base.w + 2.0 * spread,
+                    h: base.h + 2.0 * spread,
+                },
+                brush: Brush::Solid(color),
+                radius: radius + spread,
+            });
+        }
+    }
+
+    const STATE_LAYER_DP: f32 = 40.0;
+
+    // Hover/press halo behind a control's indicator, centred on `c`; pressed doubles the
+    // theme's state-layer opacity
+    fn push_state_layer(
+        scene: &mut Scene,
+        c: (f32, f32),
+        color: Color,
+        hovered: bool,
+        pressed: bool,
+        alpha: f32,
+    ) {
+        if !hovered && !pressed {
+            return;
+        }
+        let opacity = locals::theme().state_layer_opacity * if pressed { 2.0 } else { 1.0 };
+        let d = dp_to_px(STATE_LAYER_DP);
+        scene.nodes.push(SceneNode::Ellipse {
+            rect: repose_core::Rect {
+                x: c.0 - d * 0.5,
+                y: c.1 - d * 0.5,
+                w: d,
+                h: d,
+            },
+            brush: Brush::Solid(mul_alpha_color(color, alpha * opacity.clamp(0.0, 1.0))),
+        });
+    }
+
+    // Siblings sorted by `z_index` for painting; ties keep tree order
+    fn paint_order(children: &[View]) -> Vec<&View> {
+        let mut order: Vec<&View> = children.iter().collect();
+        if order
+            .windows(2)
+            .any(|w| w[0].modifier.z_index != w[1].modifier.z_index)
+        {
+            order.sort_by(|a, b| a.modifier.z_index.total_cmp(&b.modifier.z_index));
+        }
+        order
+    }
+
+    // Shrinks a px rect by a dp inset
+    fn inset_rect(r: repose_core::Rect, p: PaddingValues) -> repose_core::Rect {
+        let (l, t) = (dp_to_px(p.left), dp_to_px(p.top));
+        repose_core::Rect {
+            x: r.x + l,
+            y: r.y + t,
+            w: (r.w - l - dp_to_px(p.right)).max(0.0),
+            h: (r.h - t - dp_to_px(p.bottom)).max(0.0),
+        }
+    }
+
+    // How far across its box a line `slack` px narrower than the box is placed.
+    fn align_shift(align: TextAlign, slack: f32) -> f32 {
+        let rtl = locals::text_direction() == locals::TextDirection::Rtl;
+        match (align, rtl) {
+            (TextAlign::Center, _) => slack * 0.5,
+            (TextAlign::End, false) | (TextAlign::Start | TextAlign::Justify, true) => slack,
+            _ => 0.0,
+        }
+    }
+
+    // Role, state and handler of a view that toggles: anything `Modifier::toggleable`, plus
+    // the built-in `Checkbox`, `RadioButton` and `Switch`. A modifier wins over the kind.
+    fn toggle_of(v: &View) -> Option<(Role, ToggleableState, Option<Callback>)> {
+        if let Some(t) = &v.modifier.toggleable {
+            return Some((t.role, t.state, Some(t.on_toggle.clone())));
+        }
+        let flip = |checked: bool, cb: &Option<Rc<dyn Fn(bool)>>| {
+            cb.clone()
+                .map(|cb| Rc::new(move || cb(!checked)) as Callback)
+        };
+        match &v.kind {
+            ViewKind::Checkbox { checked, on_change } => {
+                Some((Role::Checkbox, (*checked).into(), flip(*checked, on_change)))
+            }
+            ViewKind::Switch { checked, on_change } => {
+                Some((Role::Switch, (*checked).into(), flip(*checked, on_change)))
+            }
+            ViewKind::RadioButton {
+                selected,
+                on_select,
+            } => Some((Role::RadioButton, (*selected).into(), on_select.clone())),
+            _ => None,
+        }
+    }
+
+    // Grows a control's hit rect around its centre to the `MinTouchTarget` (visual unchanged)
+    fn touch_target(r: repose_core::Rect) -> repose_core::Rect {
+        let min = dp_to_px(locals::min_touch_target().0);
+        let (w, h) = (r.w.max(min), r.h.max(min));
+        repose_core::Rect {
+            x: r.x - (w - r.w) * 0.5,
+            y: r.y - (h - r.h) * 0.5,
+            w,
+            h,
+        }
+    }
+
     // Rect intersection helper for hit clipping
     fn intersect(a: repose_core::Rect, b: repose_core::Rect) -> Option<repose_core::Rect> {
         let x0 = a.x.max(b.x);
@@ -1106,6 +1784,14 @@ pub fn layout_and_paint(
             _ => v.clamp(min, max),
         }
     }
+    /// Fraction digits for a slider's value label: enough to show one step.
+    fn slider_decimals(min: f32, max: f32, step: Option<f32>) -> usize {
+        match step {
+            Some(s) if s > 0.0 => (-s.log10()).ceil().clamp(0.0, 4.0) as usize,
+            _ if (max - min).abs() >= 10.0 => 0,
+            _ => 2,
+        }
+    }
 
     /// Multiply alpha into a Color
     fn mul_alpha_color(c: Color, a: f32) -> Color {
@@ -1236,6 +1922,7 @@ pub fn layout_and_paint(
                 on_pointer_up: Some(on_pu),
                 on_pointer_enter: None,
                 on_pointer_leave: None,
+                on_key_event: None,
                 z_index: z + 1000.0,
                 on_text_change: None,
                 on_text_submit: None,
@@ -1345,6 +2032,7 @@ pub fn layout_and_paint(
                 on_pointer_up: Some(on_pu),
                 on_pointer_enter: None,
                 on_pointer_leave: None,
+                on_key_event: None,
                 z_index: z + 1000.0,
                 on_text_change: None,
                 on_text_submit: None,
@@ -1360,6 +2048,7 @@ pub fn layout_and_paint(
     let mut hits: Vec<HitRegion> = vec![];
     let mut sems: Vec<SemNode> = vec![];
 
+    #[allow(clippy::too_many_arguments)]
     fn walk(
         v: &View,
         t: &TaffyTree<NodeCtx>,
@@ -1367,7 +2056,7 @@ pub fn layout_and_paint(
         scene: &mut Scene,
         hits: &mut Vec<HitRegion>,
         sems: &mut Vec<SemNode>,
-        textfield_states: &HashMap<u64, Rc<RefCell<TextFieldState>>>,
+        retained: &RetainedStateStore,
         interactions: &Interactions,
         focused: Option<u64>,
         parent_offset_px: (f32, f32),
@@ -1375,66 +2064,278 @@ pub fn layout_and_paint(
         text_cache: &StdHashMap<taffy::NodeId, TextLayout>,
         font_px: &dyn Fn(f32) -> f32,
     ) {
-        let local = layout_of(nodes[&v.id], t);
-        let rect = add_offset(local, parent_offset_px);
-
-        // Convert padding from dp to px for content rect
-        let content_rect = {
-            if let Some(pv_dp) = v.modifier.padding_values {
-                crate::Rect {
-                    x: rect.x + dp_to_px(pv_dp.left),
-                    y: rect.y + dp_to_px(pv_dp.top),
-                    w: (rect.w - dp_to_px(pv_dp.left) - dp_to_px(pv_dp.right)).max(0.0),
-                    h: (rect.h - dp_to_px(pv_dp.top) - dp_to_px(pv_dp.bottom)).max(0.0),
-                }
-            } else if let Some(p_dp) = v.modifier.padding {
-                let p_px = dp_to_px(p_dp);
-                crate::Rect {
-                    x: rect.x + p_px,
-                    y: rect.y + p_px,
-                    w: (rect.w - 2.0 * p_px).max(0.0),
-                    h: (rect.h - 2.0 * p_px).max(0.0),
-                }
-            } else {
-                rect
-            }
+        #[cfg(feature = "trace-widgets")]
+        let _span = tracing::trace_span!("view", id = v.id, kind = ?v.kind).entered();
+        let first_sem = sems.len();
+        let first_hit = hits.len();
+        #[cfg(debug_assertions)]
+        STACK_CHECK.with(|c| c.borrow_mut().enter(v.id, &scene.nodes));
+        let track_cost = repose_core::paint_cost_tracking();
+        let (first_node, first_cost, depth) = if track_cost {
+            PAINT_COSTS.with(|c| {
+                let (costs, depth) = &mut *c.borrow_mut();
+                *depth += 1;
+                (scene.nodes.len(), costs.len(), *depth - 1)
+            })
+        } else {
+            (0, 0, 0)
         };
-
-        let pad_dx = content_rect.x - rect.x;
-        let pad_dy = content_rect.y - rect.y;
-
-        let base_px = (parent_offset_px.0 + local.x, parent_offset_px.1 + local.y);
-
-        let is_hovered = interactions.hover == Some(v.id);
-        let is_pressed = interactions.pressed.contains(&v.id);
-        let is_focused = focused == Some(v.id);
-
-        // Background
-        if let Some(bg_brush) = v.modifier.background {
-            scene.nodes.push(SceneNode::Rect {
-                rect,
-                brush: mul_alpha_brush(bg_brush, alpha_accum),
-                radius: v.modifier.clip_rounded.map(dp_to_px).unwrap_or(0.0),
-            });
-        }
-
-        // Border
-        if let Some(b) = &v.modifier.border {
+        let layers = with_modifier_locals(&v.modifier, || {
+            walk_inner(
+                v,
+                t,
+                nodes,
+                scene,
+                hits,
+                sems,
+                retained,
+                interactions,
+                focused,
+                parent_offset_px,
+                alpha_accum,
+                text_cache,
+                font_px,
+            )
+        });
+        // Close the modifier chain's clips/transforms innermost first.
+        scene.nodes.extend(layers.into_iter().rev());
+        // Invisible subtrees and click-through overlays don't catch the pointer.
+        let alpha = v
+            .modifier
+            .elements()
+            .iter()
+            .fold(alpha_accum, |a, el| match el {
+                ModifierElement::Alpha(x) => a * x,
+                ModifierElement::GraphicsLayer(g) => a * g.alpha,
+                _ => a,
+            });
+        if v.modifier.hit_test_disabled || alpha <= 0.0 {
+            hits.truncate(first_hit);
+        }
+        // A view (e.g. a custom painter) left something open or closed its parent's.
+        #[cfg(debug_assertions)]
+        STACK_CHECK.with(|c| c.borrow_mut().leave(&scene.nodes));
+
+        if let Some(desc) = &v.modifier.content_description {
+            // The description replaces the visible text inside, so readers don't say both.
+            let inside = sems.split_off(first_sem);
+            sems.extend(
+                inside
+                    .into_iter()
+                    .filter(|s| s.id == v.id || s.role != Role::Text),
+            );
+            match sems[first_sem..].iter_mut().find(|s| s.id == v.id) {
+                Some(s) => s.label = Some(desc.clone()),
+                None => {
+                    let rect = add_offset(layout_of(nodes[&v.id], t), parent_offset_px);
+                    sems.insert(
+                        first_sem,
+                        SemNode {
+                            id: v.id,
+                            role: v.semantics.as_ref().map_or(Role::Container, |s| s.role),
+                            label: Some(desc.clone()),
+                            rect,
+                            focused: focused == Some(v.id),
+                            enabled: v.semantics.as_ref().is_none_or(|s| s.enabled),
+                            truncated: false,
+                            toggle: None,
+                        },
+                    );
+                }
+            }
+        }
+
+        if track_cost {
+            let emitted = &scene.nodes[first_node..];
+            let subtree_nodes = emitted.len() as u32;
+            let subtree_fill_px: f32 = emitted.iter().map(SceneNode::coverage_px).sum();
+            let rect = add_offset(layout_of(nodes[&v.id], t), parent_offset_px);
+            PAINT_COSTS.with(|c| {
+                let (costs, d) = &mut *c.borrow_mut();
+                *d -= 1;
+                let (child_nodes, child_fill) = costs[first_cost..]
+                    .iter()
+                    .filter(|c| c.depth == depth + 1)
+                    .fold((0, 0.0), |(n, f), c| {
+                        (n + c.subtree_nodes, f + c.subtree_fill_px)
+                    });
+                costs.push(repose_core::PaintCost {
+                    id: v.id,
+                    depth,
+                    rect,
+                    nodes: subtree_nodes - child_nodes,
+                    fill_px: (subtree_fill_px - child_fill).max(0.0),
+                    subtree_nodes,
+                    subtree_fill_px,
+                });
+            });
+        }
+    }
+
+    /// Paints one node and its children; returns the pops for any clip/transform layers
+    /// its modifier chain opened.
+    #[allow(deprecated)]
+    fn walk_inner(
+        v: &View,
+        t: &TaffyTree<NodeCtx>,
+        nodes: &HashMap<ViewId, taffy::NodeId>,
+        scene: &mut Scene,
+        hits: &mut Vec<HitRegion>,
+        sems: &mut Vec<SemNode>,
+        retained: &RetainedStateStore,
+        interactions: &Interactions,
+        focused: Option<u64>,
+        parent_offset_px: (f32, f32),
+        alpha_accum: f32,
+        text_cache: &StdHashMap<taffy::NodeId, TextLayout>,
+        font_px: &dyn Fn(f32) -> f32,
+    ) -> Vec<SceneNode> {
+        let local = layout_of(nodes[&v.id], t);
+        let rect = add_offset(local, parent_offset_px);
+        if let Some(f) = &v.modifier.on_size {
+            f(repose_core::Size {
+                width: rect.w,
+                height: rect.h,
+            });
+        }
+        if let Some(f) = &v.modifier.on_placed {
+            f(rect);
+        }
+        if let Some(tip) = &v.modifier.tooltip {
+            tooltip::consider(interactions.pointer, v.id, tip, rect);
+        }
+
+        // Subtree that failed to build: paint a placeholder and skip its children.
+        if let Some(NodeCtx::Error { message }) = t.get_node_context(nodes[&v.id]) {
+            scene.nodes.push(SceneNode::Rect {
+                rect,
+                brush: Brush::Solid(Color(220, 40, 40, 48)),
+                radius: 0.0,
+            });
             scene.nodes.push(SceneNode::Border {
                 rect,
-                color: mul_alpha_color(b.color, alpha_accum),
-                width: dp_to_px(b.width),
-                radius: dp_to_px(b.radius.max(v.modifier.clip_rounded.unwrap_or(0.0))),
+                color: Color(220, 40, 40, 255),
+                width: dp_to_px(1.0),
+                radius: 0.0,
             });
+            sems.push(SemNode {
+                id: v.id,
+                role: Role::Text,
+                label: Some(format!("Layout error: {message}")),
+                rect,
+                focused: false,
+                enabled: false,
+                truncated: false,
+                toggle: None,
+            });
+            return Vec::new();
         }
 
-        // Transform and alpha
-        let this_alpha = v.modifier.alpha.unwrap_or(1.0);
-        let alpha_accum = (alpha_accum * this_alpha).clamp(0.0, 1.0);
-
-        if let Some(tf) = v.modifier.transform {
-            scene.nodes.push(SceneNode::PushTransform { transform: tf });
+        // Widgets can be marked disabled via their `Semantics`; reflect that in the a11y tree.
+        let sem_enabled = v.semantics.as_ref().is_none_or(|s| s.enabled);
+
+        // Modifier chain, in builder order. Paddings inset whatever comes after them; clips
+        // and transforms stay open over the content and children until `walk` pops them.
+        let shape_radius = v.modifier.clip_rounded.unwrap_or(0.0);
+        let mut inset = PaddingValues::default();
+        let mut layers = Vec::new();
+        let mut alpha_accum = alpha_accum;
+        for el in v.modifier.elements().iter() {
+            match el {
+                ModifierElement::Padding(p) => {
+                    inset.left += p.left;
+                    inset.right += p.right;
+                    inset.top += p.top;
+                    inset.bottom += p.bottom;
+                }
+                ModifierElement::Background(brush) => {
+                    scene.nodes.push(SceneNode::Rect {
+                        rect: inset_rect(rect, inset),
+                        brush: mul_alpha_brush(*brush, alpha_accum),
+                        radius: dp_to_px(shape_radius),
+                    });
+                }
+                ModifierElement::Border(b) => {
+                    scene.nodes.push(SceneNode::Border {
+                        rect: inset_rect(rect, inset),
+                        color: mul_alpha_color(b.color, alpha_accum),
+                        width: dp_to_px(b.width),
+                        radius: dp_to_px(b.radius.max(shape_radius)),
+                    });
+                }
+                ModifierElement::Clip(radius) => {
+                    scene.nodes.push(SceneNode::PushClip {
+                        rect: inset_rect(rect, inset),
+                        radius: dp_to_px(*radius),
+                    });
+                    layers.push(SceneNode::PopClip);
+                }
+                ModifierElement::Alpha(a) => {
+                    alpha_accum = (alpha_accum * a).clamp(0.0, 1.0);
+                }
+                ModifierElement::Transform(tf) => {
+                    scene
+                        .nodes
+                        .push(SceneNode::PushTransform { transform: *tf });
+                    layers.push(SceneNode::PopTransform);
+                }
+                ModifierElement::AnimateContentSize(_) => {
+                    if let Some(size) = anim::content_size_current(v.id) {
+                        scene.nodes.push(SceneNode::PushClip {
+                            rect: repose_core::Rect {
+                                w: size.x,
+                                h: size.y,
+                                ..rect
+                            },
+                            radius: dp_to_px(shape_radius),
+                        });
+                        layers.push(SceneNode::PopClip);
+                    }
+                }
+                ModifierElement::SharedElement(key, spec) => {
+                    let at = inset_rect(rect, inset);
+                    let to = anim::shared_element_bounds(*key, v.id, at, *spec);
+                    if to != at {
+                        let px_per_dp = dp_to_px(1.0);
+                        let layer = GraphicsLayer {
+                            scale_x: if at.w > 0.0 { to.w / at.w } else { 1.0 },
+                            scale_y: if at.h > 0.0 { to.h / at.h } else { 1.0 },
+                            translation: Vec2 {
+                                x: (to.x - at.x) / px_per_dp,
+                                y: (to.y - at.y) / px_per_dp,
+                            },
+                            transform_origin: TransformOrigin::TOP_LEFT,
+                            ..GraphicsLayer::default()
+                        };
+                        scene.nodes.push(SceneNode::PushTransform {
+                            transform: layer.transform(at),
+                        });
+                        layers.push(SceneNode::PopTransform);
+                    }
+                }
+                ModifierElement::GraphicsLayer(g) => {
+                    let at = inset_rect(rect, inset);
+                    alpha_accum = (alpha_accum * g.alpha).clamp(0.0, 1.0);
+                    scene.nodes.push(SceneNode::PushTransform {
+                        transform: g.transform(at),
+                    });
+                    layers.push(SceneNode::PopTransform);
+                    if let Some(sh) = g.shadow {
+                        push_shadow(scene, at, dp_to_px(shape_radius), sh, alpha_accum);
+                    }
+                }
+            }
         }
+        let content_rect = inset_rect(rect, inset);
+
+        let pad_dx = content_rect.x - rect.x;
+        let pad_dy = content_rect.y - rect.y;
+
+        let base_px = (parent_offset_px.0 + local.x, parent_offset_px.1 + local.y);
+
+        let is_hovered = interactions.hover == Some(v.id);
+        let is_pressed = interactions.pressed.contains(&v.id);
+        let is_focused = focused == Some(v.id);
 
         // Custom painter (Canvas)
         if let Some(p) = &v.modifier.painter {
@@ -1445,20 +2346,65 @@ pub fn layout_and_paint(
             || v.modifier.on_pointer_move.is_some()
             || v.modifier.on_pointer_up.is_some()
             || v.modifier.on_pointer_enter.is_some()
-            || v.modifier.on_pointer_leave.is_some();
+            || v.modifier.on_pointer_leave.is_some()
+            || v.modifier.on_scroll.is_some();
+        let has_keys = v.modifier.on_key_event.is_some();
 
-        if has_pointer || v.modifier.click {
+        let toggle = toggle_of(v);
+        if let Some((role, state, on_toggle)) = &toggle {
+            hits.push(HitRegion {
+                id: v.id,
+                rect: touch_target(rect),
+                on_click: on_toggle.clone(),
+                on_scroll: None,
+                focusable: true,
+                on_pointer_down: v.modifier.on_pointer_down.clone(),
+                on_pointer_move: v.modifier.on_pointer_move.clone(),
+                on_pointer_up: v.modifier.on_pointer_up.clone(),
+                on_pointer_enter: v.modifier.on_pointer_enter.clone(),
+                on_pointer_leave: v.modifier.on_pointer_leave.clone(),
+                on_key_event: v.modifier.on_key_event.clone(),
+                z_index: v.modifier.z_index,
+                on_text_change: None,
+                on_text_submit: None,
+                tf_state_key: None,
+            });
+            sems.push(SemNode {
+                id: v.id,
+                role: *role,
+                label: None,
+                rect,
+                focused: is_focused,
+                enabled: sem_enabled,
+                truncated: false,
+                toggle: Some(*state),
+            });
+            // The built-in toggles draw their state layer around the box, dot or knob.
+            if v.modifier.toggleable.is_some() && (is_hovered || is_pressed) {
+                let th = locals::theme();
+                let opacity = th.state_layer_opacity * if is_pressed { 2.0 } else { 1.0 };
+                scene.nodes.push(SceneNode::Rect {
+                    rect,
+                    brush: Brush::Solid(mul_alpha_color(
+                        th.on_surface,
+                        alpha_accum * opacity.clamp(0.0, 1.0),
+                    )),
+                    radius: dp_to_px(shape_radius),
+                });
+            }
+        } else if has_pointer || has_keys || v.modifier.click {
             hits.push(HitRegion {
                 id: v.id,
                 rect,
-                on_click: None,  // unless ViewKind provides one
-                on_scroll: None, // provided by ScrollV case
-                focusable: false,
+                on_click: None, // unless ViewKind provides one
+                on_scroll: v.modifier.on_scroll.clone(),
+                focusable: has_keys,
                 on_pointer_down: v.modifier.on_pointer_down.clone(),
                 on_pointer_move: v.modifier.on_pointer_move.clone(),
                 on_pointer_up: v.modifier.on_pointer_up.clone(),
                 on_pointer_enter: v.modifier.on_pointer_enter.clone(),
                 on_pointer_leave: v.modifier.on_pointer_leave.clone(),
+                on_key_event: v.modifier.on_key_event.clone(),
                 z_index: v.modifier.z_index,
                 on_text_change: None,
                 on_text_submit: None,
@@ -1467,25 +2413,165 @@ pub fn layout_and_paint(
         }
 
         match &v.kind {
+            ViewKind::Text {
+                text,
+                color,
+                overflow,
+                align,
+                spans,
+                ..
+            } if !spans.is_empty()
+                && text_cache
+                    .get(&nodes[&v.id])
+                    .is_some_and(|tl| !tl.rich.is_empty()) =>
+            {
+                let tl = &text_cache[&nodes[&v.id]];
+                let need_clip = *overflow != TextOverflow::Visible
+                    && tl
+                        .rich
+                        .last()
+                        .is_some_and(|l| l.y + l.h > content_rect.h + 0.5);
+                if need_clip {
+                    scene.nodes.push(SceneNode::PushClip {
+                        rect: content_rect,
+                        radius: 0.0,
+                    });
+                }
+                let link_color = locals::theme().primary;
+                let mut piece_n = 0u64;
+                for line in &tl.rich {
+                    let top = content_rect.y + line.y;
+                    let mut x =
+                        content_rect.x + align_shift(*align, (content_rect.w - line.w).max(0.0));
+                    for (range, span) in span_runs(text, spans) {
+                        let (a, b) = (
+                            range.start.max(line.range.start),
+                            range.end.min(line.range.end),
+                        );
+                        if a >= b {
+                            continue;
+                        }
+                        let piece = &text[a..b];
+                        let style = span.map(|s| s.style).unwrap_or_default();
+                        let link = span.and_then(|s| s.on_click.clone());
+                        let px = style.font_size.map_or(tl.size_px, font_px);
+                        let weight = style.weight.unwrap_or_default();
+                        let m = repose_text::metrics_weighted(piece, px, weight.0);
+                        let w = m.positions.last().copied().unwrap_or(0.0)
+                            + tl.letter_spacing_px * piece.graphemes(true).count() as f32;
+                        let c = match (style.color, &link) {
+                            (Some(c), _) => c,
+                            (None, Some(_)) => link_color,
+                            (None, None) => *color,
+                        };
+                        let piece_rect = crate::Rect {
+                            x,
+                            y: top + line.baseline - repose_text::baseline(px),
+                            w,
+                            h: line.h,
+                        };
+                        scene.nodes.push(SceneNode::Text {
+                            rect: piece_rect,
+                            text: piece.into(),
+                            color: mul_alpha_color(c, alpha_accum),
+                            size: px,
+                            letter_spacing: tl.letter_spacing_px,
+                            weight,
+                        });
+                        if style.underline {
+                            let thickness = (px / 14.0).max(1.0);
+                            scene.nodes.push(SceneNode::Rect {
+                                rect: crate::Rect {
+                                    x,
+                                    y: top + line.baseline + thickness,
+                                    w,
+                                    h: thickness,
+                                },
+                                brush: Brush::Solid(mul_alpha_color(c, alpha_accum)),
+                                radius: 0.0,
+                            });
+                        }
+                        if let Some(on_click) = link {
+                            // Each piece of a link gets its own id, as a wrapped link has two.
+                            piece_n += 1;
+                            let id = v.id ^ (LINK_SALT + piece_n);
+                            let hit_rect = crate::Rect {
+                                y: top,
+                                ..piece_rect
+                            };
+                            hits.push(HitRegion {
+                                id,
+                                rect: hit_rect,
+                                on_click: Some(on_click),
+                                on_scroll: None,
+                                focusable: false,
+                                on_pointer_down: None,
+                                on_pointer_move: None,
+                                on_pointer_up: None,
+                                on_pointer_enter: None,
+                                on_pointer_leave: None,
+                                on_key_event: None,
+                                z_index: v.modifier.z_index,
+                                on_text_change: None,
+                                on_text_submit: None,
+                                tf_state_key: None,
+                            });
+                            sems.push(SemNode {
+                                id,
+                                role: Role::Button,
+                                label: Some(piece.to_string()),
+                                rect: hit_rect,
+                                focused: false,
+                                enabled: sem_enabled,
+                                truncated: false,
+                                toggle: None,
+                            });
+                        }
+                        x += w;
+                    }
+                }
+                if need_clip {
+                    scene.nodes.push(SceneNode::PopClip);
+                }
+                sems.push(SemNode {
+                    id: v.id,
+                    role: Role::Text,
+                    label: Some(text.clone()),
+                    rect,
+                    focused: is_focused,
+                    enabled: sem_enabled,
+                    truncated: tl.truncated,
+                    toggle: None,
+                });
+            }
             ViewKind::Text {
                 text,
                 color,
                 font_size: font_dp,
+                line_height,
+                letter_spacing,
                 soft_wrap,
                 max_lines,
                 overflow,
+                align,
+                ..
             } => {
                 let nid = nodes[&v.id];
                 let tl = text_cache.get(&nid);
 
-                let (size_px_val, line_h_px_val, mut lines): (f32, f32, Vec<String>) =
-                    if let Some(tl) = tl {
-                        (tl.size_px, tl.line_h_px, tl.lines.clone())
-                    } else {
-                        // Fallback
-                        let sz_px = font_px(*font_dp);
-                        (sz_px, sz_px * 1.3, vec![text.clone()])
-                    };
+                let (size_px_val, line_h_px_val, ls_px) = if let Some(tl) = tl {
+                    (tl.size_px, tl.line_h_px, tl.letter_spacing_px)
+                } else {
+                    // Fallback
+                    let sz_px = font_px(*font_dp);
+                    let line_h = line_height.map(font_px).unwrap_or(sz_px * 1.3);
+                    (sz_px, line_h, font_px(*letter_spacing))
+                };
+                let (mut lines, mut line_ys) = match tl {
+                    Some(tl) => (tl.lines.clone(), tl.line_ys.clone()),
+                    None => (vec![text.clone()], vec![0.0]),
+                };
+                let mut truncated = tl.is_some_and(|tl| tl.truncated);
 
                 // Work within the content box
                 let mut draw_box = content_rect;
@@ -1502,32 +2588,43 @@ pub fn layout_and_paint(
                 }
 
                 // Calculate total text height
-                let total_text_height = lines.len() as f32 * line_h_px_val;
+                let total_text_height = line_ys.last().map_or(0.0, |y| y + line_h_px_val);
 
                 let need_v_clip =
                     total_text_height > max_h_px + 0.5 && *overflow != TextOverflow::Visible;
 
                 let max_visual_lines = if max_h_px > 0.5 && need_v_clip {
-                    (max_h_px / line_h_px_val).floor().max(1.0) as usize
+                    line_ys
+                        .iter()
+                        .take_while(|y| **y + line_h_px_val <= max_h_px + 0.5)
+                        .count()
+                        .max(1)
                 } else {
                     lines.len()
                 };
 
                 if lines.len() > max_visual_lines {
                     lines.truncate(max_visual_lines);
+                    line_ys.truncate(max_visual_lines);
+                    truncated = true;
                     if *overflow == TextOverflow::Ellipsis && max_w_px > 0.5 && !lines.is_empty() {
                         // Ellipsize the last visible line
                         if let Some(last) = lines.last_mut() {
-                            *last = repose_text::ellipsize_line(last, size_px_val, max_w_px);
+                            *last = repose_text::ellipsize_line_spaced(
+                                last,
+                                size_px_val,
+                                ls_px,
+                                max_w_px,
+                            );
                         }
                     }
                 }
 
-                let approx_w_px = (text.len() as f32) * size_px_val * 0.6;
+                let line_w_px = repose_text::measure_width(text, size_px_val, ls_px);
                 let need_h_clip = match overflow {
                     TextOverflow::Visible => false,
                     TextOverflow::Ellipsis => false, // Ellipsis handled above
-                    TextOverflow::Clip => approx_w_px > max_w_px + 0.5 || need_v_clip,
+                    TextOverflow::Clip => line_w_px > max_w_px + 0.5 || need_v_clip,
                 };
 
                 let need_clip = need_h_clip || need_v_clip;
@@ -1542,36 +2639,84 @@ pub fn layout_and_paint(
                 // Horizontal ellipsis for non-wrapped text
                 if !*soft_wrap
                     && matches!(overflow, TextOverflow::Ellipsis)
-                    && approx_w_px > max_w_px + 0.5
+                    && line_w_px > max_w_px + 0.5
                 {
-                    lines = vec![repose_text::ellipsize_line(text, size_px_val, max_w_px)];
+                    lines = vec![repose_text::ellipsize_line_spaced(
+                        text,
+                        size_px_val,
+                        ls_px,
+                        max_w_px,
+                    )];
+                    line_ys = vec![0.0];
+                    truncated |= lines[0] != *text;
                 }
 
-                for (i, ln) in lines.iter().enumerate() {
-                    scene.nodes.push(SceneNode::Text {
-                        rect: crate::Rect {
-                            x: content_rect.x,
-                            y: content_rect.y + i as f32 * line_h_px_val,
-                            w: content_rect.w,
-                            h: line_h_px_val,
-                        },
-                        text: ln.clone(),
-                        color: mul_alpha_color(*color, alpha_accum),
-                        size: size_px_val,
-                    });
+                let para_ends = tl.map_or(&[][..], |tl| &tl.para_ends[..]);
+                let aligned = *align != TextAlign::Start
+                    || locals::text_direction() == locals::TextDirection::Rtl;
+                for (i, (ln, y)) in lines.iter().zip(&line_ys).enumerate() {
+                    let mut piece = |x: f32, s: &str| {
+                        scene.nodes.push(SceneNode::Text {
+                            rect: crate::Rect {
+                                x,
+                                y: content_rect.y + y,
+                                w: content_rect.w,
+                                h: line_h_px_val,
+                            },
+                            text: s.into(),
+                            color: mul_alpha_color(*color, alpha_accum),
+                            size: size_px_val,
+                            letter_spacing: ls_px,
+                            weight: FontWeight::NORMAL,
+                        });
+                    };
+                    if !aligned {
+                        piece(content_rect.x, ln);
+                        continue;
+                    }
+                    let ends_para = i + 1 == lines.len() || para_ends.get(i).is_none_or(|e| *e);
+                    let words: Vec<&str> = ln.split(' ').filter(|w| !w.is_empty()).collect();
+                    if *align == TextAlign::Justify && !ends_para && words.len() > 1 {
+                        let widths: Vec<f32> = words
+                            .iter()
+                            .map(|w| repose_text::measure_width(w, size_px_val, ls_px))
+                            .collect();
+                        let gap = (content_rect.w - widths.iter().sum::<f32>())
+                            / (words.len() - 1) as f32;
+                        let mut x = content_rect.x;
+                        for (w, ww) in words.iter().zip(&widths) {
+                            piece(x, w);
+                            x += ww + gap;
+                        }
+                    } else {
+                        let lw = repose_text::measure_width(ln, size_px_val, ls_px);
+                        piece(
+                            content_rect.x + align_shift(*align, (content_rect.w - lw).max(0.0)),
+                            ln,
+                        );
+                    }
                 }
 
                 if need_clip {
                     scene.nodes.push(SceneNode::PopClip);
                 }
 
+                if truncated
+                    && *overflow == TextOverflow::Ellipsis
+                    && !v.modifier.no_overflow_tooltip
+                {
+                    tooltip::consider(interactions.pointer, v.id, text, content_rect);
+                }
+
                 sems.push(SemNode {
                     id: v.id,
                     role: Role::Text,
                     label: Some(text.clone()),
                     rect,
                     focused: is_focused,
-                    enabled: true,
+                    enabled: sem_enabled,
+                    truncated,
+                    toggle: None,
                 });
             }
 
@@ -1596,7 +2741,7 @@ pub fn layout_and_paint(
                 if v.modifier.click || on_click.is_some() {
                     hits.push(HitRegion {
                         id: v.id,
-                        rect,
+                        rect: touch_target(rect),
                         on_click: on_click.clone(),
                         on_scroll: None,
                         focusable: true,
@@ -1605,6 +2750,7 @@ pub fn layout_and_paint(
                         on_pointer_up: v.modifier.on_pointer_up.clone(),
                         on_pointer_enter: v.modifier.on_pointer_enter.clone(),
                         on_pointer_leave: v.modifier.on_pointer_leave.clone(),
+                        on_key_event: v.modifier.on_key_event.clone(),
                         z_index: v.modifier.z_index,
                         on_text_change: None,
                         on_text_submit: None,
@@ -1618,7 +2764,9 @@ pub fn layout_and_paint(
                     label: None,
                     rect,
                     focused: is_focused,
-                    enabled: true,
+                    enabled: sem_enabled,
+                    truncated: false,
+                    toggle: None,
                 });
 
                 if is_focused {
@@ -1648,7 +2796,10 @@ pub fn layout_and_paint(
                 hint,
                 on_change,
                 on_submit,
-                ..
+                diagnostics,
+                visual_transformation,
+                input_filter,
+                max_length,
             } => {
                 // Persistent key for platform-managed state
                 let tf_key = if *state_key != 0 { *state_key } else { v.id };
@@ -1664,6 +2815,7 @@ pub fn layout_and_paint(
                     on_pointer_up: None,
                     on_pointer_enter: None,
                     on_pointer_leave: None,
+                    on_key_event: v.modifier.on_key_event.clone(),
                     z_index: v.modifier.z_index,
                     on_text_change: on_change.clone(),
                     on_text_submit: on_submit.clone(),
@@ -1696,22 +2848,48 @@ pub fn layout_and_paint(
                     });
                 }
 
-                if let Some(state_rc) = textfield_states
-                    .get(&tf_key)
-                    .or_else(|| textfield_states.get(&v.id))
+                if let Some(state_rc) = retained
+                    .get::<TextFieldState>(tf_key)
+                    .or_else(|| retained.get::<TextFieldState>(v.id))
                 // fallback for older platforms
                 {
-                    state_rc.borrow_mut().set_inner_width(inner.w);
+                    if let Some(text) = textfield::take_replacement(tf_key) {
+                        let mut st = state_rc.borrow_mut();
+                        st.composition = None;
+                        let all = 0..st.text.len();
+                        st.replace_range(all, &text);
+                    }
+                    {
+                        let mut st = state_rc.borrow_mut();
+                        st.set_inner_width(inner.w);
+                        st.visual_transformation = visual_transformation.clone();
+                        st.constraints = textfield::InputConstraints {
+                            filter: input_filter.clone(),
+                            max_length: *max_length,
+                        };
+                    }
+                    if let Some(provider) = diagnostics {
+                        let mut st = state_rc.borrow_mut();
+                        let TextFieldState {
+                            text, diagnostics, ..
+                        } = &mut *st;
+                        diagnostics.sync(text, provider.as_ref());
+                    }
 
                     let state = state_rc.borrow();
-                    let text_val = &state.text;
+                    let shown = state.display();
+                    let text_val = &shown.text;
                     let font_px_val = font_px(TF_FONT_DP);
                     let m = measure_text(text_val, font_px_val);
+                    // Bytes of the text to glyph boundaries of what's painted
+                    let glyph_at =
+                        |byte| byte_to_char_index(&m, shown.mapping.to_transformed(byte));
 
                     // Selection highlight
                     if state.selection.start != state.selection.end {
-                        let i0 = byte_to_char_index(&m, state.selection.start);
-                        let i1 = byte_to_char_index(&m, state.selection.end);
+                        let sel = state.ordered_selection();
+                        let i0 = glyph_at(sel.start);
+                        let i1 = glyph_at(sel.end);
                         let sx_px =
                             m.positions.get(i0).copied().unwrap_or(0.0) - state.scroll_offset;
                         let ex_px =
@@ -1738,8 +2916,8 @@ pub fn layout_and_paint(
                         && range.start < range.end
                         && !text_val.is_empty()
                     {
-                        let i0 = byte_to_char_index(&m, range.start);
-                        let i1 = byte_to_char_index(&m, range.end);
+                        let i0 = glyph_at(range.start);
+                        let i1 = glyph_at(range.end);
                         let sx_px =
                             m.positions.get(i0).copied().unwrap_or(0.0) - state.scroll_offset;
                         let ex_px =
@@ -1775,17 +2953,37 @@ pub fn layout_and_paint(
                             h: inner.h,
                         },
                         text: if text_val.is_empty() {
-                            hint.clone()
+                            hint.as_str().into()
                         } else {
-                            text_val.clone()
+                            text_val.as_str().into()
                         },
                         color: text_color,
                         size: font_px_val,
+                        letter_spacing: 0.0,
+                        weight: FontWeight::NORMAL,
                     });
 
+                    // Diagnostics squiggles
+                    if !text_val.is_empty() {
+                        for issue in &state.diagnostics.issues {
+                            let x_of = |byte| {
+                                let i = glyph_at(byte);
+                                inner.x + m.positions.get(i).copied().unwrap_or(0.0)
+                                    - state.scroll_offset
+                            };
+                            text_diagnostics::push_squiggle(
+                                scene,
+                                x_of(issue.range.start),
+                                x_of(issue.range.end),
+                                inner.y + inner.h - dp_to_px(2.0),
+                                mul_alpha_color(issue.severity.color(), alpha_accum),
+                            );
+                        }
+                    }
+
                     // Caret (blink)
                     if state.selection.start == state.selection.end && state.caret_visible() {
-                        let i = byte_to_char_index(&m, state.selection.end);
+                        let i = glyph_at(state.selection.end);
                         let cx_px =
                             m.positions.get(i).copied().unwrap_or(0.0) - state.scroll_offset;
                         let caret_x_px = inner.x + cx_px.max(0.0);
@@ -1803,13 +3001,20 @@ pub fn layout_and_paint(
                     // end inner clip
                     scene.nodes.push(SceneNode::PopClip);
 
+                    if is_focused && state.touch.handles {
+                        text_selection::offer(v.id, state_rc.clone(), rect, inner, font_px_val);
+                    }
+                    text_diagnostics::offer(v.id, &state_rc, on_change);
+
                     sems.push(SemNode {
                         id: v.id,
                         role: Role::TextField,
                         label: Some(text_val.clone()),
                         rect,
                         focused: is_focused,
-                        enabled: true,
+                        enabled: sem_enabled,
+                        truncated: false,
+                        toggle: None,
                     });
                 } else {
                     let font_px_val = font_px(TF_FONT_DP);
@@ -1822,9 +3027,11 @@ pub fn layout_and_paint(
                             w: inner.w,
                             h: inner.h,
                         },
-                        text: hint.clone(),
+                        text: hint.as_str().into(),
                         color: mul_alpha_color(Color::from_hex("#666666"), alpha_accum),
                         size: font_px_val,
+                        letter_spacing: 0.0,
+                        weight: FontWeight::NORMAL,
                     });
                     scene.nodes.push(SceneNode::PopClip);
 
@@ -1834,73 +3041,279 @@ pub fn layout_and_paint(
                         label: Some(hint.clone()),
                         rect,
                         focused: is_focused,
-                        enabled: true,
+                        enabled: sem_enabled,
+                        truncated: false,
+                        toggle: None,
                     });
                 }
             }
-            ViewKind::ScrollV {
-                on_scroll,
-                set_viewport_height,
-                set_content_height,
-                get_scroll_offset,
-                set_scroll_offset,
+            ViewKind::TextArea {
+                state_key,
+                hint,
+                on_change,
+                diagnostics,
+                input_filter,
+                max_length,
             } => {
-                // Keep hit region as outer rect so scroll works even on padding
+                let tf_key = if *state_key != 0 { *state_key } else { v.id };
                 hits.push(HitRegion {
                     id: v.id,
-                    rect, // outer
+                    rect,
                     on_click: None,
-                    on_scroll: on_scroll.clone(),
-                    focusable: false,
-                    on_pointer_down: v.modifier.on_pointer_down.clone(),
-                    on_pointer_move: v.modifier.on_pointer_move.clone(),
-                    on_pointer_up: v.modifier.on_pointer_up.clone(),
-                    on_pointer_enter: v.modifier.on_pointer_enter.clone(),
-                    on_pointer_leave: v.modifier.on_pointer_leave.clone(),
+                    on_scroll: None,
+                    focusable: true,
+                    on_pointer_down: None,
+                    on_pointer_move: None,
+                    on_pointer_up: None,
+                    on_pointer_enter: None,
+                    on_pointer_leave: None,
+                    on_key_event: v.modifier.on_key_event.clone(),
                     z_index: v.modifier.z_index,
-                    on_text_change: None,
+                    on_text_change: on_change.clone(),
                     on_text_submit: None,
-                    tf_state_key: None,
+                    tf_state_key: Some(tf_key),
                 });
 
-                // Use the inner content box (after padding) as the true viewport
-                let vp = content_rect; // already computed above with padding converted to px
-
-                if let Some(set_vh) = set_viewport_height {
-                    set_vh(vp.h.max(0.0));
+                let (pad_x, pad_y) = (dp_to_px(TF_PADDING_X_DP), dp_to_px(TF_PADDING_Y_DP));
+                let inner = repose_core::Rect {
+                    x: rect.x + pad_x,
+                    y: rect.y + pad_y,
+                    w: (rect.w - 2.0 * pad_x).max(0.0),
+                    h: (rect.h - 2.0 * pad_y).max(0.0),
+                };
+                if is_focused {
+                    scene.nodes.push(SceneNode::Border {
+                        rect,
+                        color: mul_alpha_color(locals::theme().focus, alpha_accum),
+                        width: dp_to_px(2.0),
+                        radius: v
+                            .modifier
+                            .clip_rounded
+                            .map(dp_to_px)
+                            .unwrap_or(dp_to_px(6.0)),
+                    });
                 }
+                scene.nodes.push(SceneNode::PushClip {
+                    rect: inner,
+                    radius: 0.0,
+                });
 
-                // True content height (use subtree extents per child)
-                fn subtree_extents(node: taffy::NodeId, t: &TaffyTree<NodeCtx>) -> (f32, f32) {
-                    let l = t.layout(node).unwrap();
-                    let mut w = l.size.width;
-                    let mut h = l.size.height;
-                    if let Ok(children) = t.children(node) {
-                        for &ch in children.iter() {
-                            let cl = t.layout(ch).unwrap();
-                            let (cw, chh) = subtree_extents(ch, t);
-                            w = w.max(cl.location.x + cw);
-                            h = h.max(cl.location.y + chh);
-                        }
+                let font_px_val = font_px(TF_FONT_DP);
+                let state_rc = retained.get::<TextFieldState>(tf_key);
+                if let Some(state_rc) = &state_rc {
+                    if let Some(text) = textfield::take_replacement(tf_key) {
+                        let mut st = state_rc.borrow_mut();
+                        st.composition = None;
+                        let all = 0..st.text.len();
+                        st.replace_range(all, &text);
+                    }
+                    let mut st = state_rc.borrow_mut();
+                    st.multiline = true;
+                    st.constraints = textfield::InputConstraints {
+                        filter: input_filter.clone(),
+                        max_length: *max_length,
+                    };
+                    st.set_inner_width(inner.w);
+                    st.inner_height = inner.h;
+                    if let Some(provider) = diagnostics {
+                        let TextFieldState {
+                            text, diagnostics, ..
+                        } = &mut *st;
+                        diagnostics.sync(text, provider.as_ref());
                     }
-                    (w, h)
-                }
-                let mut content_h_px = 0.0f32;
-                for c in &v.children {
-                    let nid = nodes[&c.id];
-                    let l = t.layout(nid).unwrap();
-                    let (_cw, chh) = subtree_extents(nid, t);
-                    content_h_px = content_h_px.max(l.location.y + chh);
-                }
-                if let Some(set_ch) = set_content_height {
-                    set_ch(content_h_px);
                 }
+                let state = state_rc.as_ref().map(|s| s.borrow());
+                let text_val = state.as_ref().map_or("", |s| s.text.as_str());
 
-                // Clip to the inner viewport
-                scene.nodes.push(SceneNode::PushClip {
-                    rect: vp,
-                    radius: 0.0, // inner clip; keep simple (outer border already drawn if any)
-                });
+                if text_val.is_empty() {
+                    scene.nodes.push(SceneNode::Text {
+                        rect: repose_core::Rect {
+                            h: font_px_val * 1.3,
+                            ..inner
+                        },
+                        text: hint.as_str().into(),
+                        color: mul_alpha_color(Color::from_hex("#666666"), alpha_accum),
+                        size: font_px_val,
+                        letter_spacing: 0.0,
+                        weight: FontWeight::NORMAL,
+                    });
+                }
+                if let Some(state) = &state {
+                    let w = state.wrapped(font_px_val);
+                    let lh = w.line_height();
+                    let top = inner.y - state.scroll_offset_y;
+                    let place = |r: repose_core::Rect| repose_core::Rect {
+                        x: inner.x + r.x,
+                        y: top + r.y,
+                        ..r
+                    };
+
+                    let sel = state.selection.start.min(state.selection.end)
+                        ..state.selection.start.max(state.selection.end);
+                    for r in w.selection_rects(sel.clone()) {
+                        scene.nodes.push(SceneNode::Rect {
+                            rect: place(r),
+                            brush: Brush::Solid(mul_alpha_color(
+                                Color::from_hex("#3B7BFF55"),
+                                alpha_accum,
+                            )),
+                            radius: 0.0,
+                        });
+                    }
+                    if let Some(range) = &state.composition {
+                        for r in w.selection_rects(range.clone()) {
+                            let r = place(r);
+                            scene.nodes.push(SceneNode::Rect {
+                                rect: repose_core::Rect {
+                                    y: r.y + r.h - dp_to_px(2.0),
+                                    h: dp_to_px(2.0),
+                                    ..r
+                                },
+                                brush: Brush::Solid(mul_alpha_color(
+                                    locals::theme().focus,
+                                    alpha_accum,
+                                )),
+                                radius: 0.0,
+                            });
+                        }
+                    }
+
+                    // Only the lines inside the field.
+                    let first = (state.scroll_offset_y / lh).floor().max(0.0) as usize;
+                    let last = ((state.scroll_offset_y + inner.h) / lh).ceil() as usize;
+                    for (i, line) in w.lines.iter().enumerate().take(last + 1).skip(first) {
+                        if line.is_empty() {
+                            continue;
+                        }
+                        scene.nodes.push(SceneNode::Text {
+                            rect: repose_core::Rect {
+                                x: inner.x,
+                                y: top + i as f32 * lh,
+                                w: inner.w,
+                                h: lh,
+                            },
+                            text: text_val[line.clone()].into(),
+                            color: mul_alpha_color(locals::theme().on_surface, alpha_accum),
+                            size: font_px_val,
+                            letter_spacing: 0.0,
+                            weight: FontWeight::NORMAL,
+                        });
+                    }
+
+                    for issue in &state.diagnostics.issues {
+                        for r in w.selection_rects(issue.range.clone()) {
+                            let r = place(r);
+                            text_diagnostics::push_squiggle(
+                                scene,
+                                r.x,
+                                r.x + r.w,
+                                r.y + r.h - dp_to_px(2.0),
+                                mul_alpha_color(issue.severity.color(), alpha_accum),
+                            );
+                        }
+                    }
+
+                    if is_focused && sel.is_empty() && state.caret_visible() {
+                        let at = w.caret_position(state.selection.end, state.affinity);
+                        scene.nodes.push(SceneNode::Rect {
+                            rect: repose_core::Rect {
+                                x: inner.x + at.x,
+                                y: top + at.y,
+                                w: dp_to_px(1.0),
+                                h: lh,
+                            },
+                            brush: Brush::Solid(mul_alpha_color(
+                                locals::theme().on_surface,
+                                alpha_accum,
+                            )),
+                            radius: 0.0,
+                        });
+                    }
+                }
+                scene.nodes.push(SceneNode::PopClip);
+                if let Some(state_rc) = &state_rc {
+                    text_diagnostics::offer(v.id, state_rc, on_change);
+                }
+
+                sems.push(SemNode {
+                    id: v.id,
+                    role: Role::TextField,
+                    label: Some(if text_val.is_empty() {
+                        hint.clone()
+                    } else {
+                        text_val.to_string()
+                    }),
+                    rect,
+                    focused: is_focused,
+                    enabled: sem_enabled,
+                    truncated: false,
+                    toggle: None,
+                });
+            }
+            ViewKind::ScrollV {
+                on_scroll,
+                set_viewport_height,
+                set_content_height,
+                get_scroll_offset,
+                set_scroll_offset,
+            } => {
+                // Keep hit region as outer rect so scroll works even on padding
+                hits.push(HitRegion {
+                    id: v.id,
+                    rect, // outer
+                    on_click: None,
+                    on_scroll: on_scroll.clone(),
+                    focusable: false,
+                    on_pointer_down: v.modifier.on_pointer_down.clone(),
+                    on_pointer_move: v.modifier.on_pointer_move.clone(),
+                    on_pointer_up: v.modifier.on_pointer_up.clone(),
+                    on_pointer_enter: v.modifier.on_pointer_enter.clone(),
+                    on_pointer_leave: v.modifier.on_pointer_leave.clone(),
+                    on_key_event: v.modifier.on_key_event.clone(),
+                    z_index: v.modifier.z_index,
+                    on_text_change: None,
+                    on_text_submit: None,
+                    tf_state_key: None,
+                });
+
+                // Use the inner content box (after padding) as the true viewport
+                let vp = content_rect; // already computed above with padding converted to px
+
+                if let Some(set_vh) = set_viewport_height {
+                    set_vh(vp.h.max(0.0));
+                }
+
+                // True content height (use subtree extents per child)
+                fn subtree_extents(node: taffy::NodeId, t: &TaffyTree<NodeCtx>) -> (f32, f32) {
+                    let l = layout_of(node, t);
+                    let mut w = l.w;
+                    let mut h = l.h;
+                    if let Ok(children) = t.children(node) {
+                        for &ch in children.iter() {
+                            let cl = layout_of(ch, t);
+                            let (cw, chh) = subtree_extents(ch, t);
+                            w = w.max(cl.x + cw);
+                            h = h.max(cl.y + chh);
+                        }
+                    }
+                    (w, h)
+                }
+                let mut content_h_px = 0.0f32;
+                for c in &v.children {
+                    let nid = nodes[&c.id];
+                    let l = layout_of(nid, t);
+                    let (_cw, chh) = subtree_extents(nid, t);
+                    content_h_px = content_h_px.max(l.y + chh);
+                }
+                if let Some(set_ch) = set_content_height {
+                    set_ch(content_h_px);
+                }
+
+                // Clip to the inner viewport
+                scene.nodes.push(SceneNode::PushClip {
+                    rect: vp,
+                    radius: 0.0, // inner clip; keep simple (outer border already drawn if any)
+                });
 
                 // Walk children
                 let hit_start = hits.len();
@@ -1910,22 +3323,40 @@ pub fn layout_and_paint(
                     0.0
                 };
                 let child_offset_px = (base_px.0 + pad_dx, base_px.1 + pad_dy - scroll_offset_px);
-                for c in &v.children {
-                    walk(
-                        c,
-                        t,
-                        nodes,
-                        scene,
-                        hits,
-                        sems,
-                        textfield_states,
+                let paint_children =
+                    |scene: &mut Scene, hits: &mut Vec<HitRegion>, sems: &mut Vec<SemNode>| {
+                        for c in paint_order(&v.children) {
+                            walk(
+                                c,
+                                t,
+                                nodes,
+                                scene,
+                                hits,
+                                sems,
+                                retained,
+                                interactions,
+                                focused,
+                                child_offset_px,
+                                alpha_accum,
+                                text_cache,
+                                font_px,
+                            );
+                        }
+                    };
+                match v.modifier.walk_cache {
+                    Some(key) => walk_cache::paint(
+                        key,
+                        walk_cache::Placement {
+                            viewport: vp,
+                            origin: child_offset_px,
+                            alpha: alpha_accum,
+                        },
                         interactions,
                         focused,
-                        child_offset_px,
-                        alpha_accum,
-                        text_cache,
-                        font_px,
-                    );
+                        (scene, hits, sems),
+                        paint_children,
+                    ),
+                    None => paint_children(scene, hits, sems),
                 }
 
                 // Clip descendant hit regions to the viewport
@@ -1953,7 +3384,7 @@ pub fn layout_and_paint(
                 );
 
                 scene.nodes.push(SceneNode::PopClip);
-                return;
+                return layers;
             }
             ViewKind::ScrollXY {
                 on_scroll,
@@ -1975,13 +3406,23 @@ pub fn layout_and_paint(
                     on_pointer_up: v.modifier.on_pointer_up.clone(),
                     on_pointer_enter: v.modifier.on_pointer_enter.clone(),
                     on_pointer_leave: v.modifier.on_pointer_leave.clone(),
+                    on_key_event: v.modifier.on_key_event.clone(),
                     z_index: v.modifier.z_index,
                     on_text_change: None,
                     on_text_submit: None,
                     tf_state_key: None,
                 });
 
-                let vp = content_rect;
+                // The minimap gutter comes off the right of the viewport.
+                let gutter_w = v
+                    .modifier
+                    .minimap
+                    .map(|w| dp_to_px(w).min(content_rect.w * 0.5))
+                    .unwrap_or(0.0);
+                let vp = repose_core::Rect {
+                    w: content_rect.w - gutter_w,
+                    ..content_rect
+                };
 
                 if let Some(set_w) = set_viewport_width {
                     set_w(vp.w.max(0.0));
@@ -1991,15 +3432,15 @@ pub fn layout_and_paint(
                 }
 
                 fn subtree_extents(node: taffy::NodeId, t: &TaffyTree<NodeCtx>) -> (f32, f32) {
-                    let l = t.layout(node).unwrap();
-                    let mut w = l.size.width;
-                    let mut h = l.size.height;
+                    let l = layout_of(node, t);
+                    let mut w = l.w;
+                    let mut h = l.h;
                     if let Ok(children) = t.children(node) {
                         for &ch in children.iter() {
-                            let cl = t.layout(ch).unwrap();
+                            let cl = layout_of(ch, t);
                             let (cw, chh) = subtree_extents(ch, t);
-                            w = w.max(cl.location.x + cw);
-                            h = h.max(cl.location.y + chh);
+                            w = w.max(cl.x + cw);
+                            h = h.max(cl.y + chh);
                         }
                     }
                     (w, h)
@@ -2008,10 +3449,10 @@ pub fn layout_and_paint(
                 let mut content_h_px = 0.0f32;
                 for c in &v.children {
                     let nid = nodes[&c.id];
-                    let l = t.layout(nid).unwrap();
+                    let l = layout_of(nid, t);
                     let (cw, chh) = subtree_extents(nid, t);
-                    content_w_px = content_w_px.max(l.location.x + cw);
-                    content_h_px = content_h_px.max(l.location.y + chh);
+                    content_w_px = content_w_px.max(l.x + cw);
+                    content_h_px = content_h_px.max(l.y + chh);
                 }
                 if let Some(set_cw) = set_content_width {
                     set_cw(content_w_px);
@@ -2026,29 +3467,49 @@ pub fn layout_and_paint(
                 });
 
                 let hit_start = hits.len();
+                let node_start = scene.nodes.len();
                 let (ox_px, oy_px) = if let Some(get) = get_scroll_offset_xy {
                     get()
                 } else {
                     (0.0, 0.0)
                 };
                 let child_offset_px = (base_px.0 + pad_dx - ox_px, base_px.1 + pad_dy - oy_px);
-                for c in &v.children {
-                    walk(
-                        c,
-                        t,
-                        nodes,
-                        scene,
-                        hits,
-                        sems,
-                        textfield_states,
+                let paint_children =
+                    |scene: &mut Scene, hits: &mut Vec<HitRegion>, sems: &mut Vec<SemNode>| {
+                        for c in paint_order(&v.children) {
+                            walk(
+                                c,
+                                t,
+                                nodes,
+                                scene,
+                                hits,
+                                sems,
+                                retained,
+                                interactions,
+                                focused,
+                                child_offset_px,
+                                alpha_accum,
+                                text_cache,
+                                font_px,
+                            );
+                        }
+                    };
+                match v.modifier.walk_cache {
+                    Some(key) => walk_cache::paint(
+                        key,
+                        walk_cache::Placement {
+                            viewport: vp,
+                            origin: child_offset_px,
+                            alpha: alpha_accum,
+                        },
                         interactions,
                         focused,
-                        child_offset_px,
-                        alpha_accum,
-                        text_cache,
-                        font_px,
-                    );
+                        (scene, hits, sems),
+                        paint_children,
+                    ),
+                    None => paint_children(scene, hits, sems),
                 }
+                let node_end = scene.nodes.len();
                 // Clip descendant hits to viewport
                 let mut i = hit_start;
                 while i < hits.len() {
@@ -2067,17 +3528,19 @@ pub fn layout_and_paint(
                     });
 
                 // Scrollbars against inner viewport
-                push_scrollbar_v(
-                    scene,
-                    hits,
-                    interactions,
-                    v.id,
-                    vp,
-                    content_h_px,
-                    oy_px,
-                    v.modifier.z_index,
-                    set_scroll_y,
-                );
+                if gutter_w <= 0.0 {
+                    push_scrollbar_v(
+                        scene,
+                        hits,
+                        interactions,
+                        v.id,
+                        vp,
+                        content_h_px,
+                        oy_px,
+                        v.modifier.z_index,
+                        set_scroll_y,
+                    );
+                }
                 push_scrollbar_h(
                     scene,
                     hits,
@@ -2092,14 +3555,79 @@ pub fn layout_and_paint(
                 );
 
                 scene.nodes.push(SceneNode::PopClip);
-                return;
+
+                if gutter_w > 0.0 {
+                    let map = minimap::Minimap::new(
+                        repose_core::Rect {
+                            x: vp.x + vp.w,
+                            w: gutter_w,
+                            ..vp
+                        },
+                        Vec2 {
+                            x: child_offset_px.0,
+                            y: child_offset_px.1,
+                        },
+                        Vec2 {
+                            x: content_w_px,
+                            y: content_h_px,
+                        },
+                        Vec2 { x: vp.w, y: vp.h },
+                        Vec2 { x: ox_px, y: oy_px },
+                    );
+                    map.paint(v.id, scene, node_start..node_end);
+
+                    if let Some(set_xy) = set_scroll_offset_xy {
+                        let map_id: u64 = v.id ^ 0x8000_0021;
+                        let go_to: Rc<dyn Fn(repose_core::input::PointerEvent)> = {
+                            let set_xy = set_xy.clone();
+                            Rc::new(move |pe| {
+                                let off = map.offset_at(pe.position);
+                                set_xy(off.x, off.y)
+                            })
+                        };
+                        hits.push(HitRegion {
+                            id: map_id,
+                            rect: map.gutter(),
+                            on_click: None,
+                            on_scroll: on_scroll.clone(),
+                            focusable: false,
+                            on_pointer_down: Some(go_to.clone()),
+                            on_pointer_move: interactions
+                                .pressed
+                                .contains(&map_id)
+                                .then_some(go_to),
+                            on_pointer_up: Some(Rc::new(|_| {})),
+                            on_pointer_enter: None,
+                            on_pointer_leave: None,
+                            on_key_event: None,
+                            z_index: v.modifier.z_index + 1000.0,
+                            on_text_change: None,
+                            on_text_submit: None,
+                            tf_state_key: None,
+                        });
+                    }
+                }
+                return layers;
             }
-            ViewKind::Checkbox { checked, on_change } => {
+            ViewKind::Checkbox { checked, .. } => {
                 let theme = locals::theme();
+                let p = anim::toggle_progress(v.id, *checked, theme.motion.short);
                 // Box at left (20x20 centered vertically)
                 let box_size_px = dp_to_px(18.0);
                 let bx = rect.x;
                 let by = rect.y + (rect.h - box_size_px) * 0.5;
+                push_state_layer(
+                    scene,
+                    (bx + box_size_px * 0.5, by + box_size_px * 0.5),
+                    if *checked {
+                        theme.primary
+                    } else {
+                        theme.on_surface
+                    },
+                    is_hovered,
+                    is_pressed,
+                    alpha_accum,
+                );
                 // box bg/border
                 scene.nodes.push(SceneNode::Rect {
                     rect: repose_core::Rect {
@@ -2108,11 +3636,10 @@ pub fn layout_and_paint(
                         w: box_size_px,
                         h: box_size_px,
                     },
-                    brush: Brush::Solid(if *checked {
-                        mul_alpha_color(theme.primary, alpha_accum)
-                    } else {
-                        mul_alpha_color(theme.surface, alpha_accum)
-                    }),
+                    brush: Brush::Solid(mul_alpha_color(
+                        theme.surface.interpolate(&theme.primary, p),
+                        alpha_accum,
+                    )),
                     radius: dp_to_px(3.0),
                 });
                 scene.nodes.push(SceneNode::Border {
@@ -2126,8 +3653,17 @@ pub fn layout_and_paint(
                     width: dp_to_px(1.0),
                     radius: dp_to_px(3.0),
                 });
-                // checkmark
-                if *checked {
+                // checkmark, drawn in left to right as the box fills
+                if p > 0.0 {
+                    scene.nodes.push(SceneNode::PushClip {
+                        rect: repose_core::Rect {
+                            x: bx,
+                            y: by,
+                            w: box_size_px * p,
+                            h: box_size_px,
+                        },
+                        radius: 0.0,
+                    });
                     scene.nodes.push(SceneNode::Text {
                         rect: repose_core::Rect {
                             x: bx + dp_to_px(3.0),
@@ -2135,63 +3671,34 @@ pub fn layout_and_paint(
                             w: rect.w - (box_size_px + dp_to_px(8.0)),
                             h: font_px(16.0),
                         },
-                        text: "✓".to_string(),
+                        text: "✓".into(),
                         color: mul_alpha_color(theme.on_primary, alpha_accum),
                         size: font_px(16.0),
+                        letter_spacing: 0.0,
+                        weight: FontWeight::NORMAL,
                     });
-                }
-                // Hit + semantics + focus ring
-                let toggled = !*checked;
-                let on_click = on_change.as_ref().map(|cb| {
-                    let cb = cb.clone();
-                    Rc::new(move || cb(toggled)) as Rc<dyn Fn()>
-                });
-                hits.push(HitRegion {
-                    id: v.id,
-                    rect,
-                    on_click,
-                    on_scroll: None,
-                    focusable: true,
-                    on_pointer_down: v.modifier.on_pointer_down.clone(),
-                    on_pointer_move: v.modifier.on_pointer_move.clone(),
-                    on_pointer_up: v.modifier.on_pointer_up.clone(),
-                    on_pointer_enter: v.modifier.on_pointer_enter.clone(),
-                    on_pointer_leave: v.modifier.on_pointer_leave.clone(),
-                    z_index: v.modifier.z_index,
-                    on_text_change: None,
-                    on_text_submit: None,
-                    tf_state_key: None,
-                });
-                sems.push(SemNode {
-                    id: v.id,
-                    role: Role::Checkbox,
-                    label: None,
-                    rect,
-                    focused: is_focused,
-                    enabled: true,
-                });
-                if is_focused {
-                    scene.nodes.push(SceneNode::Border {
-                        rect,
-                        color: mul_alpha_color(locals::theme().focus, alpha_accum),
-                        width: dp_to_px(2.0),
-                        radius: v
-                            .modifier
-                            .clip_rounded
-                            .map(dp_to_px)
-                            .unwrap_or(dp_to_px(6.0)),
-                    });
+                    scene.nodes.push(SceneNode::PopClip);
                 }
             }
 
-            ViewKind::RadioButton {
-                selected,
-                on_select,
-            } => {
+            ViewKind::RadioButton { selected, .. } => {
                 let theme = locals::theme();
+                let p = anim::toggle_progress(v.id, *selected, theme.motion.short);
                 let d_px = dp_to_px(18.0);
                 let cx = rect.x;
                 let cy = rect.y + (rect.h - d_px) * 0.5;
+                push_state_layer(
+                    scene,
+                    (cx + d_px * 0.5, cy + d_px * 0.5),
+                    if *selected {
+                        theme.primary
+                    } else {
+                        theme.on_surface
+                    },
+                    is_hovered,
+                    is_pressed,
+                    alpha_accum,
+                );
 
                 // outer circle (rounded rect as circle)
                 scene.nodes.push(SceneNode::Border {
@@ -2205,59 +3712,23 @@ pub fn layout_and_paint(
                     width: dp_to_px(1.5),
                     radius: d_px * 0.5,
                 });
-                // inner dot if selected
-                if *selected {
+                // inner dot, grown from the centre as it gets selected
+                if p > 0.0 {
+                    let dot = (d_px - dp_to_px(8.0)) * p;
                     scene.nodes.push(SceneNode::Rect {
                         rect: repose_core::Rect {
-                            x: cx + dp_to_px(4.0),
-                            y: cy + dp_to_px(4.0),
-                            w: d_px - dp_to_px(8.0),
-                            h: d_px - dp_to_px(8.0),
+                            x: cx + (d_px - dot) * 0.5,
+                            y: cy + (d_px - dot) * 0.5,
+                            w: dot,
+                            h: dot,
                         },
                         brush: Brush::Solid(mul_alpha_color(theme.primary, alpha_accum)),
-                        radius: (d_px - dp_to_px(8.0)) * 0.5,
-                    });
-                }
-
-                hits.push(HitRegion {
-                    id: v.id,
-                    rect,
-                    on_click: on_select.clone(),
-                    on_scroll: None,
-                    focusable: true,
-                    on_pointer_down: v.modifier.on_pointer_down.clone(),
-                    on_pointer_move: v.modifier.on_pointer_move.clone(),
-                    on_pointer_up: v.modifier.on_pointer_up.clone(),
-                    on_pointer_enter: v.modifier.on_pointer_enter.clone(),
-                    on_pointer_leave: v.modifier.on_pointer_leave.clone(),
-                    z_index: v.modifier.z_index,
-                    on_text_change: None,
-                    on_text_submit: None,
-                    tf_state_key: None,
-                });
-                sems.push(SemNode {
-                    id: v.id,
-                    role: Role::RadioButton,
-                    label: None,
-                    rect,
-                    focused: is_focused,
-                    enabled: true,
-                });
-                if is_focused {
-                    scene.nodes.push(SceneNode::Border {
-                        rect,
-                        color: mul_alpha_color(locals::theme().focus, alpha_accum),
-                        width: dp_to_px(2.0),
-                        radius: v
-                            .modifier
-                            .clip_rounded
-                            .map(dp_to_px)
-                            .unwrap_or(dp_to_px(6.0)),
+                        radius: dot * 0.5,
                     });
                 }
             }
 
-            ViewKind::Switch { checked, on_change } => {
+            ViewKind::Switch { checked, .. } => {
                 let theme = locals::theme();
                 // track 46x26, knob 22x22
                 let track_w_px = dp_to_px(46.0);
@@ -2267,6 +3738,7 @@ pub fn layout_and_paint(
                 let knob_px = dp_to_px(22.0);
                 let on_col = theme.primary;
                 let off_col = Color::from_hex("#333333");
+                let p = anim::toggle_progress(v.id, *checked, theme.motion.medium);
 
                 // track
                 scene.nodes.push(SceneNode::Rect {
@@ -2276,20 +3748,24 @@ pub fn layout_and_paint(
                         w: track_w_px,
                         h: track_h_px,
                     },
-                    brush: Brush::Solid(if *checked {
-                        mul_alpha_color(on_col, alpha_accum)
-                    } else {
-                        mul_alpha_color(off_col, alpha_accum)
-                    }),
+                    brush: Brush::Solid(mul_alpha_color(
+                        off_col.interpolate(&on_col, p),
+                        alpha_accum,
+                    )),
                     radius: track_h_px * 0.5,
                 });
                 // knob position
-                let kx = if *checked {
-                    tx + track_w_px - knob_px - dp_to_px(2.0)
-                } else {
-                    tx + dp_to_px(2.0)
-                };
+                let kx = (tx + dp_to_px(2.0))
+                    .interpolate(&(tx + track_w_px - knob_px - dp_to_px(2.0)), p);
                 let ky = ty + (track_h_px - knob_px) * 0.5;
+                push_state_layer(
+                    scene,
+                    (kx + knob_px * 0.5, ky + knob_px * 0.5),
+                    if *checked { on_col } else { theme.on_surface },
+                    is_hovered,
+                    is_pressed,
+                    alpha_accum,
+                );
                 scene.nodes.push(SceneNode::Rect {
                     rect: repose_core::Rect {
                         x: kx,
@@ -2311,48 +3787,6 @@ pub fn layout_and_paint(
                     width: dp_to_px(1.0),
                     radius: knob_px * 0.5,
                 });
-
-                let toggled = !*checked;
-                let on_click = on_change.as_ref().map(|cb| {
-                    let cb = cb.clone();
-                    Rc::new(move || cb(toggled)) as Rc<dyn Fn()>
-                });
-                hits.push(HitRegion {
-                    id: v.id,
-                    rect,
-                    on_click,
-                    on_scroll: None,
-                    focusable: true,
-                    on_pointer_down: v.modifier.on_pointer_down.clone(),
-                    on_pointer_move: v.modifier.on_pointer_move.clone(),
-                    on_pointer_up: v.modifier.on_pointer_up.clone(),
-                    on_pointer_enter: v.modifier.on_pointer_enter.clone(),
-                    on_pointer_leave: v.modifier.on_pointer_leave.clone(),
-                    z_index: v.modifier.z_index,
-                    on_text_change: None,
-                    on_text_submit: None,
-                    tf_state_key: None,
-                });
-                sems.push(SemNode {
-                    id: v.id,
-                    role: Role::Switch,
-                    label: None,
-                    rect,
-                    focused: is_focused,
-                    enabled: true,
-                });
-                if is_focused {
-                    scene.nodes.push(SceneNode::Border {
-                        rect,
-                        color: mul_alpha_color(locals::theme().focus, alpha_accum),
-                        width: dp_to_px(2.0),
-                        radius: v
-                            .modifier
-                            .clip_rounded
-                            .map(dp_to_px)
-                            .unwrap_or(dp_to_px(6.0)),
-                    });
-                }
             }
             ViewKind::Slider {
                 value,
@@ -2386,6 +3820,14 @@ pub fn layout_and_paint(
                 // Knob position
                 let t = clamp01(norm(*value, *min, *max));
                 let kx = track_x + t * track_w_px;
+                push_state_layer(
+                    scene,
+                    (kx, cy),
+                    theme.primary,
+                    is_hovered,
+                    is_pressed,
+                    alpha_accum,
+                );
                 scene.nodes.push(SceneNode::Rect {
                     rect: repose_core::Rect {
                         x: kx - knob_d_px * 0.5,
@@ -2408,6 +3850,23 @@ pub fn layout_and_paint(
                     radius: knob_d_px * 0.5,
                 });
 
+                // Value label
+                let label_px = font_px(14.0);
+                let label_left = track_x + track_w_px + knob_d_px * 0.5 + dp_to_px(8.0);
+                scene.nodes.push(SceneNode::Text {
+                    rect: repose_core::Rect {
+                        x: label_left,
+                        y: cy - label_px * 0.65,
+                        w: (rect.x + rect.w - label_left).max(0.0),
+                        h: label_px * 1.3,
+                    },
+                    text: format_number(*value as f64, slider_decimals(*min, *max, *step)).into(),
+                    color: mul_alpha_color(theme.on_surface, alpha_accum),
+                    size: label_px,
+                    letter_spacing: 0.0,
+                    weight: FontWeight::NORMAL,
+                });
+
                 // Interactions
                 let on_change_cb: Option<Rc<dyn Fn(f32)>> = on_change.as_ref().cloned();
                 let minv = *min;
@@ -2471,7 +3930,7 @@ pub fn layout_and_paint(
                 // Register move handler only while pressed so hover doesn't change value
                 hits.push(HitRegion {
                     id: v.id,
-                    rect,
+                    rect: touch_target(rect),
                     on_click: None,
                     on_scroll: Some(on_scroll),
                     focusable: true,
@@ -2480,6 +3939,7 @@ pub fn layout_and_paint(
                     on_pointer_up: Some(on_pu),
                     on_pointer_enter: v.modifier.on_pointer_enter.clone(),
                     on_pointer_leave: v.modifier.on_pointer_leave.clone(),
+                    on_key_event: v.modifier.on_key_event.clone(),
                     z_index: v.modifier.z_index,
                     on_text_change: None,
                     on_text_submit: None,
@@ -2492,7 +3952,9 @@ pub fn layout_and_paint(
                     label: None,
                     rect,
                     focused: is_focused,
-                    enabled: true,
+                    enabled: sem_enabled,
+                    truncated: false,
+                    toggle: None,
                 });
                 if is_focused {
                     scene.nodes.push(SceneNode::Border {
@@ -2556,6 +4018,14 @@ pub fn layout_and_paint(
 
                 // Knobs
                 for &kx in &[k0x, k1x] {
+                    push_state_layer(
+                        scene,
+                        (kx, cy),
+                        theme.primary,
+                        is_hovered,
+                        is_pressed,
+                        alpha_accum,
+                    );
                     scene.nodes.push(SceneNode::Rect {
                         rect: repose_core::Rect {
                             x: kx - knob_d_px * 0.5,
@@ -2653,7 +4123,7 @@ pub fn layout_and_paint(
 
                 hits.push(HitRegion {
                     id: v.id,
-                    rect,
+                    rect: touch_target(rect),
                     on_click: None,
                     on_scroll: None,
                     focusable: true,
@@ -2662,6 +4132,7 @@ pub fn layout_and_paint(
                     on_pointer_up: Some(on_pu),
                     on_pointer_enter: v.modifier.on_pointer_enter.clone(),
                     on_pointer_leave: v.modifier.on_pointer_leave.clone(),
+                    on_key_event: v.modifier.on_key_event.clone(),
                     z_index: v.modifier.z_index,
                     on_text_change: None,
                     on_text_submit: None,
@@ -2673,7 +4144,9 @@ pub fn layout_and_paint(
                     label: None,
                     rect,
                     focused: is_focused,
-                    enabled: true,
+                    enabled: sem_enabled,
+                    truncated: false,
+                    toggle: None,
                 });
                 if is_focused {
                     scene.nodes.push(SceneNode::Border {
@@ -2732,9 +4205,11 @@ pub fn layout_and_paint(
                         w: rect.w - (label_w_split_px + gap_px),
                         h: font_px(16.0),
                     },
-                    text: format!("{:.0}%", t * 100.0),
+                    text: format_percent(t as f64, 0).into(),
                     color: mul_alpha_color(theme.on_surface, alpha_accum),
                     size: font_px(16.0),
+                    letter_spacing: 0.0,
+                    weight: FontWeight::NORMAL,
                 });
 
                 sems.push(SemNode {
@@ -2743,13 +4218,70 @@ pub fn layout_and_paint(
                     label: None,
                     rect,
                     focused: is_focused,
-                    enabled: true,
+                    enabled: sem_enabled,
+                    truncated: false,
+                    toggle: None,
                 });
             }
+            ViewKind::Custom(w) => {
+                let cx = repose_core::PaintCtx {
+                    id: v.id,
+                    rect,
+                    alpha: alpha_accum,
+                    focused: is_focused,
+                    enabled: sem_enabled,
+                };
+                w.paint(&cx, scene);
+                hits.extend(w.hit_regions(&cx));
+                let sem = match &v.semantics {
+                    Some(s) => Some(SemNode {
+                        id: v.id,
+                        role: s.role,
+                        label: s.label.clone(),
+                        rect,
+                        focused: is_focused,
+                        enabled: s.enabled,
+                        truncated: false,
+                        toggle: None,
+                    }),
+                    None => w.semantics(&cx),
+                };
+                sems.extend(sem);
+            }
             _ => {}
         }
 
-        for c in &v.children {
+        if toggle.is_some() && is_focused {
+            scene.nodes.push(SceneNode::Border {
+                rect,
+                color: mul_alpha_color(locals::theme().focus, alpha_accum),
+                width: dp_to_px(2.0),
+                radius: v
+                    .modifier
+                    .clip_rounded
+                    .map(dp_to_px)
+                    .unwrap_or(dp_to_px(6.0)),
+            });
+        }
+
+        // A closed `animate_expand` skips its children; one part way open clips them, hit
+        // regions included.
+        let expand = v
+            .modifier
+            .animate_expand
+            .and_then(|_| anim::toggle_current(v.id))
+            .filter(|p| *p < 1.0);
+        if expand.is_some_and(|p| p <= 0.0) {
+            return layers;
+        }
+        let first_hit = hits.len();
+        if expand.is_some() {
+            scene.nodes.push(SceneNode::PushClip { rect, radius: 0.0 });
+        }
+        let first_node = scene.nodes.len();
+        let first_sem = sems.len();
+
+        for c in paint_order(&v.children) {
             walk(
                 c,
                 t,
@@ -2757,7 +4289,7 @@ pub fn layout_and_paint(
                 scene,
                 hits,
                 sems,
-                textfield_states,
+                retained,
                 interactions,
                 focused,
                 base_px,
@@ -2767,34 +4299,235 @@ pub fn layout_and_paint(
             );
         }
 
-        if v.modifier.transform.is_some() {
-            scene.nodes.push(SceneNode::PopTransform);
+        if let Some(f) = &v.modifier.on_content_bounds
+            && let Some(b) = zoom::painted_bounds(&scene.nodes[first_node..])
+        {
+            f(repose_core::Rect {
+                x: b.x - rect.x,
+                y: b.y - rect.y,
+                ..b
+            });
         }
+        // Pan/zoom the children, and their hit regions within this node's bounds.
+        if let Some(tf) = v.modifier.content_transform {
+            let map = zoom::ContentMap::new(
+                Vec2 {
+                    x: rect.x,
+                    y: rect.y,
+                },
+                tf,
+            );
+            for n in &mut scene.nodes[first_node..] {
+                map.node(n);
+            }
+            for s in &mut sems[first_sem..] {
+                s.rect = map.rect(s.rect);
+            }
+            let mut i = first_hit;
+            while i < hits.len() {
+                if let Some(r) = intersect(map.rect(hits[i].rect), rect) {
+                    map.hit(&mut hits[i]);
+                    hits[i].rect = r;
+                    i += 1;
+                } else {
+                    hits.remove(i);
+                }
+            }
+        }
+
+        if expand.is_some() {
+            scene.nodes.push(SceneNode::PopClip);
+            let mut i = first_hit;
+            while i < hits.len() {
+                if let Some(r) = intersect(hits[i].rect, rect) {
+                    hits[i].rect = r;
+                    i += 1;
+                } else {
+                    hits.remove(i);
+                }
+            }
+        }
+
+        layers
     }
 
     let font_px = |dp_font: f32| dp_to_px(dp_font) * locals::text_scale().0;
 
+    let t_paint = Instant::now();
+    let paint_span = tracing::info_span!("paint").entered();
+
     // Start with zero offset
-    walk(
-        &root,
-        &taffy,
-        &nodes_map,
-        &mut scene,
-        &mut hits,
-        &mut sems,
-        textfield_states,
-        interactions,
-        focused,
-        (0.0, 0.0),
-        1.0,
-        &text_cache,
-        &font_px,
-    );
+    {
+        profiling::scope!("scene walk");
+        #[cfg(debug_assertions)]
+        STACK_CHECK.with(|c| *c.borrow_mut() = StackCheck::default());
+        walk(
+            &root,
+            &taffy,
+            &nodes_map,
+            &mut scene,
+            &mut hits,
+            &mut sems,
+            retained,
+            interactions,
+            focused,
+            (0.0, 0.0),
+            1.0,
+            &text_cache,
+            &font_px,
+        );
+    }
+    resolve_labelled_by(&root, &mut sems);
+    if repose_core::paint_cost_tracking() {
+        let costs = PAINT_COSTS.with(|c| std::mem::take(&mut c.borrow_mut().0));
+        repose_core::record_paint_costs(costs);
+    }
+    let window_px = (size_px_u32.0 as f32, size_px_u32.1 as f32);
+    text_selection::paint(root.modifier.key, &hits, window_px);
+    text_diagnostics::paint_menu(root.modifier.key, &hits, window_px);
+    tooltip::paint(window_px);
+    if cull {
+        scene.cull(repose_core::Rect {
+            x: 0.0,
+            y: 0.0,
+            w: window_px.0,
+            h: window_px.1,
+        });
+    }
 
     // Ensure visual order: low z_index first. Topmost will be found by iter().rev().
     hits.sort_by(|a, b| a.z_index.partial_cmp(&b.z_index).unwrap_or(Ordering::Equal));
+    drop(paint_span);
+    record_phase(FramePhase::Paint, t_paint.elapsed().as_secs_f32() * 1000.0);
+
+    Ok((scene, hits, sems))
+}
+
+thread_local! {
+    /// Paint costs of the walk in progress, and the current depth in it.
+    static PAINT_COSTS: RefCell<(Vec<repose_core::PaintCost>, u32)> =
+        const { RefCell::new((Vec::new(), 0)) };
+}
+
+#[cfg(debug_assertions)]
+thread_local! {
+    static STACK_CHECK: RefCell<StackCheck> = RefCell::new(StackCheck::default());
+}
+
+/// Checks, in debug builds, that each view closes every clip and transform it opens and
+/// none of its parent's. Open counts are carried through the walk, so every scene node is
+/// looked at once however deep the tree is.
+#[cfg(debug_assertions)]
+#[derive(Default)]
+struct StackCheck {
+    /// Nodes already counted.
+    seen: usize,
+    clips: usize,
+    transforms: usize,
+    /// Views being painted, with the clips and transforms open when each started.
+    open: Vec<(ViewId, usize, usize)>,
+}
+
+#[cfg(debug_assertions)]
+impl StackCheck {
+    fn enter(&mut self, id: ViewId, nodes: &[SceneNode]) {
+        // Nodes so far belong to the view painting this one.
+        self.count(nodes);
+        self.open.push((id, self.clips, self.transforms));
+    }
+
+    fn leave(&mut self, nodes: &[SceneNode]) {
+        self.count(nodes);
+        if let Some((id, clips, transforms)) = self.open.pop()
+            && (self.clips, self.transforms) != (clips, transforms)
+        {
+            let e = repose_core::frame::StackError::Unclosed {
+                clips: self.clips - clips,
+                transforms: self.transforms - transforms,
+            };
+            panic!("view {id} painted unbalanced clips/transforms: {e}");
+        }
+    }
+
+    fn count(&mut self, nodes: &[SceneNode]) {
+        let (id, floor_clips, floor_transforms) = self.open.last().copied().unwrap_or_default();
+        for (index, node) in nodes.iter().enumerate().skip(self.seen) {
+            let (depth, floor, clip) = match node {
+                SceneNode::PushClip { .. } => {
+                    self.clips += 1;
+                    continue;
+                }
+                SceneNode::PushTransform { .. } => {
+                    self.transforms += 1;
+                    continue;
+                }
+                SceneNode::PopClip => (&mut self.clips, floor_clips, true),
+                SceneNode::PopTransform => (&mut self.transforms, floor_transforms, false),
+                _ => continue,
+            };
+            if *depth == floor {
+                let e = repose_core::frame::StackError::UnmatchedPop { index, clip };
+                panic!("view {id} painted unbalanced clips/transforms: {e}");
+            }
+            *depth -= 1;
+        }
+        self.seen = nodes.len();
+    }
+}
+
+/// `animate_expand` nodes in `v` and how far open each is this frame.
+fn collect_expanding(v: &View, out: &mut Vec<(ViewId, f32)>) {
+    if let Some((open, spec)) = v.modifier.animate_expand {
+        out.push((v.id, anim::toggle_progress(v.id, open, spec)));
+    }
+    for c in &v.children {
+        collect_expanding(c, out);
+    }
+}
 
-    (scene, hits, sems)
+/// Applies `Modifier::labelled_by`: each such node takes the labels found inside the view
+/// with that `label_id`, joined with spaces. An explicit `content_description` still wins.
+fn resolve_labelled_by(root: &View, sems: &mut [SemNode]) {
+    fn index<'a>(v: &'a View, keyed: &mut HashMap<u64, &'a View>, wanted: &mut Vec<(ViewId, u64)>) {
+        if let Some(k) = v.modifier.label_id {
+            keyed.entry(k).or_insert(v);
+        }
+        if let (None, Some(k)) = (&v.modifier.content_description, v.modifier.labelled_by) {
+            wanted.push((v.id, k));
+        }
+        for c in &v.children {
+            index(c, keyed, wanted);
+        }
+    }
+    fn subtree_ids(v: &View, out: &mut HashSet<ViewId>) {
+        out.insert(v.id);
+        for c in &v.children {
+            subtree_ids(c, out);
+        }
+    }
+
+    let mut keyed = HashMap::new();
+    let mut wanted = Vec::new();
+    index(root, &mut keyed, &mut wanted);
+    for (id, key) in wanted {
+        let Some(src) = keyed.get(&key) else {
+            continue;
+        };
+        let mut ids = HashSet::new();
+        subtree_ids(src, &mut ids);
+        let label = sems
+            .iter()
+            .filter(|s| ids.contains(&s.id))
+            .filter_map(|s| s.label.as_deref())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if label.is_empty() {
+            continue;
+        }
+        for s in sems.iter_mut().filter(|s| s.id == id) {
+            s.label = Some(label.clone());
+        }
+    }
 }
 
 /// Method styling
@@ -2806,6 +4539,13 @@ pub trait TextStyle {
     fn overflow_ellipsize(self) -> View;
     fn overflow_clip(self) -> View;
     fn overflow_visible(self) -> View;
+    /// Baseline-to-baseline distance in dp (default `1.3 *` font size).
+    fn line_height(self, dp: f32) -> View;
+    /// Extra dp after every grapheme; negative values tighten.
+    fn letter_spacing(self, dp: f32) -> View;
+    /// Extra dp between paragraphs (lines separated by `\n`).
+    fn paragraph_spacing(self, dp: f32) -> View;
+    fn text_align(self, align: TextAlign) -> View;
 }
 impl TextStyle for View {
     fn color(mut self, c: Color) -> View {
@@ -2869,4 +4609,1978 @@ impl TextStyle for View {
         }
         self
     }
+    fn line_height(mut self, dp: f32) -> View {
+        if let ViewKind::Text { line_height, .. } = &mut self.kind {
+            *line_height = Some(dp);
+        }
+        self
+    }
+    fn letter_spacing(mut self, dp: f32) -> View {
+        if let ViewKind::Text { letter_spacing, .. } = &mut self.kind {
+            *letter_spacing = dp;
+        }
+        self
+    }
+    fn paragraph_spacing(mut self, dp: f32) -> View {
+        if let ViewKind::Text {
+            paragraph_spacing, ..
+        } = &mut self.kind
+        {
+            *paragraph_spacing = dp;
+        }
+        self
+    }
+    fn text_align(mut self, a: TextAlign) -> View {
+        if let ViewKind::Text { align, .. } = &mut self.kind {
+            *align = a;
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ellipsized_text_reports_truncation_and_shows_tooltip() {
+        use repose_core::animation::{TestClock, set_clock, swap_clock};
+        use web_time::Duration;
+
+        let full = "A label that is far too long for its box";
+        let root = Row(Modifier::new()).child(
+            Text(full)
+                .single_line()
+                .overflow_ellipsize()
+                .modifier(Modifier::new().width(60.0)),
+        );
+        let interactions = Interactions {
+            pointer: Some(Vec2 { x: 10.0, y: 8.0 }),
+            ..Default::default()
+        };
+        let t0 = Instant::now();
+        let _clock = swap_clock(Box::new(TestClock { t: t0 }));
+        let mut sched = Scheduler::new();
+        sched.size = (400, 200);
+        let mut frame = |ms: u64| {
+            set_clock(Box::new(TestClock {
+                t: t0 + Duration::from_millis(ms),
+            }));
+            sched.repose(
+                |_| root.clone(),
+                |v, size| {
+                    layout_and_paint(v, size, &RetainedStateStore::new(), &interactions, None)
+                },
+            )
+        };
+        let shows_full = |frame: &Frame| {
+            frame
+                .scene
+                .nodes
+                .iter()
+                .any(|n| matches!(n, SceneNode::Text { text, .. } if text == full))
+        };
+
+        let f = frame(0);
+        let sem = f
+            .semantics_nodes
+            .iter()
+            .find(|s| s.role == Role::Text)
+            .unwrap();
+        assert!(sem.truncated);
+        assert_eq!(sem.label.as_deref(), Some(full));
+        assert!(!shows_full(&f), "tooltip waits for the hover delay");
+        assert!(frame_requested(), "and asks for the frames that end it");
+
+        // Keep hovering past the delay.
+        assert!(!shows_full(&frame(250)));
+        let delay = tooltip::TOOLTIP_DELAY.as_millis() as u64;
+        assert!(shows_full(&frame(delay)));
+        assert!(!frame_requested());
+    }
+
+    #[test]
+    fn tooltip_modifier_shows_after_delay_and_flips_above_near_the_bottom() {
+        use repose_core::animation::{TestClock, set_clock, swap_clock};
+        use web_time::Duration;
+
+        // A 40px tall button hugging the bottom of a 100px window.
+        let root = Column(
+            Modifier::new()
+                .fill_max_size()
+                .justify_content(JustifyContent::FlexEnd),
+        )
+        .child(Box(Modifier::new().size(80.0, 40.0).tooltip("Save draft")));
+        let interactions = Interactions {
+            pointer: Some(Vec2 { x: 10.0, y: 80.0 }),
+            ..Default::default()
+        };
+        let t0 = Instant::now();
+        let _clock = swap_clock(Box::new(TestClock { t: t0 }));
+        let mut sched = Scheduler::new();
+        sched.size = (300, 100);
+        let mut frame = |ms: u64| {
+            set_clock(Box::new(TestClock {
+                t: t0 + Duration::from_millis(ms),
+            }));
+            sched
+                .repose(
+                    |_| root.clone(),
+                    |v, size| {
+                        layout_and_paint(v, size, &RetainedStateStore::new(), &interactions, None)
+                    },
+                )
+                .scene
+        };
+        let tip_top = |scene: &Scene| {
+            scene.nodes.iter().find_map(|n| match n {
+                SceneNode::Text { text, rect, .. } if text == "Save draft" => Some(rect.y),
+                _ => None,
+            })
+        };
+
+        assert_eq!(tip_top(&frame(0)), None);
+        let delay = tooltip::TOOLTIP_DELAY.as_millis() as u64;
+        let top = tip_top(&frame(delay)).expect("tooltip after the delay");
+        assert!(top < 60.0, "no room below, so it sits above the button");
+    }
+
+    #[test]
+    fn touch_selection_handles_and_toolbar_sit_in_a_layer() {
+        use crate::textfield::{EditAction, TextFieldExt};
+
+        let retained = RetainedStateStore::new();
+        let mut st = TextFieldState::new();
+        st.text = "hello world".into();
+        st.selection = 0..5;
+        st.touch.handles = true;
+        st.touch.toolbar = true;
+        retained.insert(42, st);
+        let root = Column(Modifier::new().padding(80.0)).child(
+            TextField("", Modifier::new(), None::<fn(String)>, None::<fn(String)>).state_key(42),
+        );
+        let (_, hits, _) =
+            layout_and_paint(&root, (400, 400), &retained, &Interactions::default(), None);
+        let field = hits.iter().find(|h| h.tf_state_key == Some(42)).unwrap().id;
+
+        let mut sched = Scheduler::new();
+        sched.size = (400, 400);
+        let frame = sched.repose(
+            |_| root.clone(),
+            |v, size| layout_and_paint(v, size, &retained, &Interactions::default(), Some(field)),
+        );
+        let keys: Vec<&str> = sched
+            .layers
+            .layers()
+            .iter()
+            .map(|l| l.key.as_str())
+            .collect();
+        assert_eq!(keys, ["text_selection"]);
+
+        // Two handles, then Cut, Copy, Paste and Select all.
+        let overlay: Vec<&HitRegion> = frame
+            .hit_regions
+            .iter()
+            .filter(|h| text_selection::is_overlay_of(h.id, field))
+            .collect();
+        assert_eq!(overlay.len(), 6);
+        assert!(overlay[..2].iter().all(|h| h.on_pointer_down.is_some()));
+        (overlay[3].on_click.as_ref().unwrap())();
+        assert_eq!(
+            text_selection::take_edit_actions(),
+            [(field, EditAction::Copy)]
+        );
+    }
+
+    #[test]
+    fn diagnostics_squiggle_and_suggestions_menu() {
+        use crate::textfield::TextFieldExt;
+        use repose_core::input::*;
+        use std::ops::Range;
+
+        struct Teh;
+        impl TextDiagnostics for Teh {
+            fn check(&self, text: &str, range: Range<usize>, sink: DiagnosticsSink) {
+                let issues = text[range.clone()]
+                    .match_indices("teh")
+                    .map(|(i, w)| TextIssue {
+                        range: range.start + i..range.start + i + w.len(),
+                        severity: Severity::Error,
+                        message: "Unknown word".into(),
+                        suggestions: vec!["the".into()],
+                    })
+                    .collect();
+                sink.publish(issues);
+            }
+        }
+
+        let retained = RetainedStateStore::new();
+        let mut st = TextFieldState::new();
+        st.insert_text("teh cat");
+        retained.insert(42, st);
+        let changed = Rc::new(RefCell::new(None));
+        let c = changed.clone();
+        let root = Column(Modifier::new()).child(
+            TextField(
+                "",
+                Modifier::new(),
+                Some(move |t: String| *c.borrow_mut() = Some(t)),
+                None::<fn(String)>,
+            )
+            .state_key(42)
+            .text_diagnostics(Teh),
+        );
+        let mut sched = Scheduler::new();
+        sched.size = (400, 400);
+        let mut frame = || {
+            sched.repose(
+                |_| root.clone(),
+                |v, size| layout_and_paint(v, size, &retained, &Interactions::default(), None),
+            )
+        };
+
+        frame();
+        let f = frame(); // the results land on the next frame
+        let segments = f
+            .scene
+            .nodes
+            .iter()
+            .filter(|n| matches!(n, SceneNode::Line { .. }))
+            .count();
+        // A zigzag under "teh": a segment every 2dp, not a rect every pixel.
+        let teh_w = crate::textfield::measure_text("teh", dp_to_px(16.0))
+            .positions
+            .last()
+            .copied()
+            .unwrap();
+        assert_eq!(segments, (teh_w / dp_to_px(2.0)).ceil() as usize);
+
+        let field = f
+            .hit_regions
+            .iter()
+            .find(|h| h.tf_state_key == Some(42))
+            .unwrap()
+            .id;
+        let state = retained.get::<TextFieldState>(42).unwrap();
+        let at = Vec2 { x: 10.0, y: 40.0 };
+        assert!(text_diagnostics::open_menu(field, &state.borrow(), 1, at));
+        let f = frame();
+        let pick = f
+            .hit_regions
+            .iter()
+            .rev()
+            .find(|h| text_diagnostics::is_menu_item(h.id) && h.on_pointer_down.is_some())
+            .expect("a suggestion row");
+        (pick.on_pointer_down.as_ref().unwrap())(PointerEvent {
+            id: PointerId(0),
+            kind: PointerKind::Mouse,
+            event: PointerEventKind::Down(PointerButton::Primary),
+            position: at,
+            pressure: 1.0,
+            modifiers: Modifiers::default(),
+        });
+        assert_eq!(changed.borrow().as_deref(), Some("the cat"));
+        assert!(!text_diagnostics::menu_open());
+        frame();
+        assert!(sched.layers.is_empty());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "unbalanced clips/transforms: 1 PushClip")]
+    fn painter_that_leaves_a_clip_open_is_caught_in_debug() {
+        let r = Rect {
+            x: 0.0,
+            y: 0.0,
+            w: 10.0,
+            h: 10.0,
+        };
+        let root = Column(Modifier::new()).child(Box(Modifier::new().size(10.0, 10.0).painter(
+            move |scene: &mut Scene, _| {
+                scene.nodes.push(SceneNode::PushClip {
+                    rect: r,
+                    radius: 0.0,
+                })
+            },
+        )));
+        layout_and_paint(
+            &root,
+            (100, 100),
+            &RetainedStateStore::new(),
+            &Interactions::default(),
+            None,
+        );
+    }
+
+    #[test]
+    fn text_area_paints_visible_lines_and_marks_state_multiline() {
+        let root = Column(Modifier::new()).child(
+            TextArea("Notes", Modifier::new().width(200.0), None::<fn(String)>).state_key(9),
+        );
+        let retained = RetainedStateStore::new();
+        let st = retained.insert(9, TextFieldState::new());
+        st.borrow_mut().insert_text(
+            &(1..=8)
+                .map(|i| format!("line {i}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+        let (scene, _, _) =
+            layout_and_paint(&root, (400, 400), &retained, &Interactions::default(), None);
+        let lines: Vec<String> = scene
+            .nodes
+            .iter()
+            .filter_map(|n| match n {
+                SceneNode::Text { text, .. } => Some(text.to_string()),
+                _ => None,
+            })
+            .collect();
+        // Four lines tall by default; the one partly showing below is painted too.
+        assert_eq!(lines.first().map(String::as_str), Some("line 1"));
+        assert!(lines.len() < 8, "{lines:?}");
+        assert!(st.borrow().multiline);
+    }
+
+    #[test]
+    fn text_spacing_offsets_lines() {
+        let line_tops = |t: View| {
+            let root = Column(Modifier::new()).child(t);
+            let (scene, _hits, _sems) = layout_and_paint(
+                &root,
+                (400, 400),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            );
+            scene
+                .nodes
+                .iter()
+                .filter_map(|n| match n {
+                    SceneNode::Text {
+                        rect,
+                        letter_spacing,
+                        ..
+                    } => Some((rect.y, *letter_spacing)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let plain = line_tops(Text("one\ntwo").size(10.0));
+        assert_eq!(plain.len(), 2);
+        assert!((plain[1].0 - plain[0].0 - 13.0).abs() < 0.01);
+
+        let spaced = line_tops(
+            Text("one\ntwo")
+                .size(10.0)
+                .line_height(20.0)
+                .paragraph_spacing(5.0)
+                .letter_spacing(2.0),
+        );
+        assert!((spaced[1].0 - spaced[0].0 - 25.0).abs() < 0.01);
+        assert!(spaced.iter().all(|(_, ls)| (*ls - 2.0).abs() < 0.01));
+    }
+
+    #[test]
+    fn scroll_area_state_tracks_each_direction() {
+        use crate::scroll::{ScrollArea, ScrollAreaState, ScrollDirection};
+
+        let tiles = |n: usize| {
+            (0..n)
+                .map(|i| {
+                    Box(Modifier::new()
+                        .key(i as u64)
+                        .size(50.0, 50.0)
+                        .flex_shrink(0.0))
+                })
+                .collect::<Vec<_>>()
+        };
+        // The root is stretched to the window, so keep the area one level down.
+        let paint = |v: &View| {
+            layout_and_paint(
+                &Column(Modifier::new()).child(v.clone()),
+                (400, 400),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            )
+        };
+
+        let state = Rc::new(ScrollAreaState::new());
+        let v = ScrollArea(
+            ScrollDirection::Vertical,
+            state.clone(),
+            Modifier::new().size(100.0, 100.0),
+        )
+        .child(Column(Modifier::new()).child(tiles(10)));
+        let (_, hits, _) = paint(&v);
+        let max = state.max_offset();
+        assert!(max.x == 0.0 && max.y > 0.0);
+
+        // Wheel input goes through the container's hit region; the overshoot is handed back.
+        let on_scroll = hits.iter().find_map(|h| h.on_scroll.clone()).unwrap();
+        let left = on_scroll(Vec2 {
+            x: 3.0,
+            y: max.y + 50.0,
+        });
+        assert_eq!(left, Vec2 { x: 3.0, y: 50.0 });
+        assert_eq!(state.offset(), max);
+
+        // Horizontal areas take a plain wheel's dy as sideways scroll.
+        let state = Rc::new(ScrollAreaState::new());
+        let v = ScrollArea(
+            ScrollDirection::Horizontal,
+            state.clone(),
+            Modifier::new().size(100.0, 100.0),
+        )
+        .child(Row(Modifier::new()).child(tiles(4)));
+        let (_, hits, _) = paint(&v);
+        let on_scroll = hits.iter().find_map(|h| h.on_scroll.clone()).unwrap();
+        assert_eq!(on_scroll(Vec2 { x: 0.0, y: 30.0 }), Vec2::default());
+        assert_eq!(state.offset(), Vec2 { x: 30.0, y: 0.0 });
+    }
+
+    #[test]
+    fn small_controls_get_min_touch_target() {
+        let root = Column(Modifier::new().padding(40.0))
+            .child(Checkbox(false, |_| {}).modifier(Modifier::new().size(20.0, 20.0)));
+        let hit_rect = || {
+            let (_, hits, _) = layout_and_paint(
+                &root,
+                (400, 400),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            );
+            hits.iter().find(|h| h.focusable).map(|h| h.rect).unwrap()
+        };
+
+        let r = hit_rect();
+        assert_eq!((r.x, r.y, r.w, r.h), (26.0, 26.0, 48.0, 48.0));
+        let r = with_min_touch_target(MinTouchTarget(0.0), hit_rect);
+        assert_eq!((r.x, r.y, r.w, r.h), (40.0, 40.0, 20.0, 20.0));
+    }
+
+    #[test]
+    fn toggles_show_state_layer_on_hover_and_press() {
+        let root = Column(Modifier::new()).child(Checkbox(true, |_| {}));
+        let halo = |interactions: &Interactions| {
+            let (scene, _, _) = layout_and_paint(
+                &root,
+                (400, 400),
+                &RetainedStateStore::new(),
+                interactions,
+                None,
+            );
+            scene.nodes.iter().find_map(|n| match n {
+                SceneNode::Ellipse {
+                    brush: Brush::Solid(c),
+                    ..
+                } => Some(c.3),
+                _ => None,
+            })
+        };
+        let (_, hits, _) = layout_and_paint(
+            &root,
+            (400, 400),
+            &RetainedStateStore::new(),
+            &Interactions::default(),
+            None,
+        );
+        let id = hits.iter().find(|h| h.focusable).unwrap().id;
+
+        assert_eq!(halo(&Interactions::default()), None);
+        let hover = halo(&Interactions {
+            hover: Some(id),
+            ..Default::default()
+        })
+        .unwrap();
+        let pressed = halo(&Interactions {
+            pressed: [id].into_iter().collect(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(hover > 0 && pressed > hover);
+    }
+
+    #[test]
+    fn switch_knob_animates_instead_of_snapping() {
+        let knob_x = |on: bool| {
+            let root = Column(Modifier::new()).child(Switch(on, |_| {}));
+            let (scene, _, _) = layout_and_paint(
+                &root,
+                (400, 400),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            );
+            scene
+                .nodes
+                .iter()
+                .find_map(|n| match n {
+                    SceneNode::Rect {
+                        rect,
+                        brush: Brush::Solid(c),
+                        ..
+                    } if *c == Color::from_hex("#EEEEEE") => Some(rect.x),
+                    _ => None,
+                })
+                .unwrap()
+        };
+
+        let off = knob_x(false);
+        assert_eq!(knob_x(false), off);
+        let moving = knob_x(true);
+        let travel = dp_to_px(46.0 - 22.0 - 4.0);
+        assert!(moving >= off && moving < off + travel * 0.5);
+    }
+
+    #[test]
+    fn content_description_and_labelled_by_name_nodes() {
+        let root = Column(Modifier::new()).child(vec![
+            Button(Text("×"), || {}).modifier(Modifier::new().content_description("Close")),
+            Box(Modifier::new().size(24.0, 24.0).content_description("Logo")),
+            // Keys are only unique among siblings, so they don't name anything.
+            Text("Name").modifier(Modifier::new().key(7)),
+            Text("Email").modifier(Modifier::new().label_id(7)),
+            TextField(
+                "you@example.com",
+                Modifier::new().labelled_by(7),
+                None::<fn(String)>,
+                None::<fn(String)>,
+            ),
+        ]);
+        let (_, _, sems) = layout_and_paint(
+            &root,
+            (400, 400),
+            &RetainedStateStore::new(),
+            &Interactions::default(),
+            None,
+        );
+        let label_of = |role: Role| {
+            sems.iter()
+                .filter(|s| s.role == role)
+                .filter_map(|s| s.label.as_deref())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(label_of(Role::Button), ["Close"]);
+        assert_eq!(label_of(Role::Container), ["Logo"]);
+        assert_eq!(label_of(Role::TextField), ["Email"]);
+        // "×" is replaced by the button's description rather than read after it.
+        assert_eq!(label_of(Role::Text), ["Name", "Email"]);
+    }
+
+    #[test]
+    fn live_edits_apply_on_next_layout() {
+        let root = Column(Modifier::new()).child(Button(Text("Go"), || {}));
+        let button = || {
+            let (_, hits, _) = layout_and_paint(
+                &root,
+                (400, 400),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            );
+            hits.into_iter().find(|h| h.focusable).unwrap()
+        };
+        let before = button();
+        repose_core::set_inspected(Some(before.id));
+        repose_core::set_modifier_override(
+            before.id,
+            repose_core::ModifierOverride {
+                width: Some(before.rect.w + 50.0),
+                ..Default::default()
+            },
+        );
+
+        let after = button();
+        assert_eq!(after.rect.w, before.rect.w + 50.0);
+        let (id, values) = repose_core::inspected().unwrap();
+        assert_eq!((id, values.width), (before.id, Some(before.rect.w + 50.0)));
+
+        repose_core::clear_modifier_overrides();
+        assert_eq!(button().rect.w, before.rect.w);
+    }
+
+    #[test]
+    fn paint_costs_attribute_nodes_to_views() {
+        repose_core::set_paint_cost_tracking(true);
+        let root = Column(Modifier::new()).child(vec![
+            Box(Modifier::new().size(10.0, 10.0).background(Color::WHITE)),
+            Box(Modifier::new()
+                .size(20.0, 20.0)
+                .background(Color::WHITE)
+                .border(1.0, Color::BLACK, 0.0)),
+        ]);
+        let (scene, _, _) = layout_and_paint(
+            &root,
+            (400, 400),
+            &RetainedStateStore::new(),
+            &Interactions::default(),
+            None,
+        );
+        let costs = repose_core::paint_costs();
+        repose_core::set_paint_cost_tracking(false);
+
+        let root_cost = costs.iter().find(|c| c.depth == 0).unwrap();
+        assert_eq!(root_cost.subtree_nodes as usize, scene.nodes.len());
+        let mut leaves: Vec<_> = costs.iter().filter(|c| c.depth == 1).collect();
+        leaves.sort_by(|a, b| a.fill_px.total_cmp(&b.fill_px));
+        assert_eq!(leaves.iter().map(|c| c.nodes).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(leaves[0].fill_px, 100.0);
+    }
+
+    #[test]
+    fn wrapped_text_selection_and_affinity() {
+        use crate::textfield::{CaretAffinity, WrappedText, measure_text};
+
+        let font = 16.0;
+        let word = measure_text("alpha ", font)
+            .positions
+            .last()
+            .copied()
+            .unwrap();
+        // Room for one word per line, then a hard break.
+        let w = WrappedText::new("alpha beta\nbeta", font, word + 2.0);
+        assert_eq!(w.lines, vec![0..6, 6..10, 11..15]);
+
+        // Byte 6 is both the end of line 0 and the start of line 1.
+        assert_eq!(w.line_of(6, CaretAffinity::Downstream), 1);
+        assert_eq!(w.line_of(6, CaretAffinity::Upstream), 0);
+        assert_eq!(w.caret_position(6, CaretAffinity::Downstream).x, 0.0);
+
+        let rects = w.selection_rects(3..13);
+        assert_eq!(rects.len(), 3);
+        assert_eq!(rects[1].y, w.line_height());
+
+        // Past the end of the wrapped first line stays on it.
+        assert_eq!(w.index_for_point(500.0, 1.0), (6, CaretAffinity::Upstream));
+        assert_eq!(
+            w.index_for_point(0.0, w.line_height() * 2.5),
+            (11, CaretAffinity::Downstream)
+        );
+    }
+
+    #[test]
+    fn lazy_column_with_builds_only_visible_items_and_keeps_their_state_by_key() {
+        use crate::lazy::{LazyColumnState, LazyColumnWith};
+        use std::cell::Cell;
+
+        let state = Rc::new(LazyColumnState::new());
+        let built = Rc::new(RefCell::new(Vec::new()));
+        // Items are 40dp tall; the list guesses 20dp. Each counts its own builds.
+        let frame = |keys: Vec<u64>| {
+            built.borrow_mut().clear();
+            let built = built.clone();
+            let root = Column(Modifier::new()).child(LazyColumnWith(
+                state.clone(),
+                20.0,
+                Modifier::new().height(200.0),
+                move |list| {
+                    list.items(keys, move |k| {
+                        let builds = remember(|| Cell::new(0));
+                        builds.set(builds.get() + 1);
+                        built.borrow_mut().push((k, builds.get()));
+                        Box(Modifier::new().height(40.0))
+                    })
+                },
+            ));
+            layout_and_paint(
+                &root,
+                (400, 400),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            );
+        };
+
+        // The first frame doesn't know the viewport height yet either.
+        frame((0..10_000).collect());
+        let first = built.borrow().len();
+        assert!(first < 40, "built {first} of 10000");
+        // Now measured: 200dp holds five items, plus a few either side.
+        frame((0..10_000).collect());
+        assert!(built.borrow().len() <= 8, "{:?}", built.borrow());
+
+        // Items inserted on top: the list stays on item 0, and its state went along.
+        frame((100_000..100_003).chain(0..10_000).collect());
+        assert_eq!((state.anchor().index, state.anchor().key), (3, Some(0)));
+        assert_eq!(built.borrow().iter().find(|(k, _)| *k == 0), Some(&(0, 3)));
+    }
+
+    #[test]
+    fn lazy_grid_spans_columns_and_lazy_row_builds_only_visible_items() {
+        use crate::lazy::{GridItemSpan, LazyColumnState, LazyGrid, LazyRow};
+
+        let widths = Rc::new(RefCell::new(HashMap::new()));
+        let frame = |root: &View| {
+            layout_and_paint(
+                root,
+                (400, 400),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            );
+        };
+
+        // 3 columns over 300dp: a full-width header, then item 4 takes two columns.
+        let grid_state = Rc::new(LazyColumnState::new());
+        let grid = || {
+            let widths = widths.clone();
+            Column(Modifier::new()).child(LazyGrid(
+                grid_state.clone(),
+                3,
+                50.0,
+                Modifier::new().width(300.0).height(200.0),
+                move |grid| {
+                    let cell = move |widths: Rc<RefCell<HashMap<u64, f32>>>, k: u64| {
+                        Box(Modifier::new().height(50.0).on_size(move |s| {
+                            widths.borrow_mut().insert(k, s.width);
+                        }))
+                    };
+                    let w = widths.clone();
+                    grid.item(u64::MAX, GridItemSpan::FULL_LINE, move || {
+                        cell(w.clone(), u64::MAX)
+                    });
+                    grid.items_spanned(
+                        0..10_000,
+                        |k| GridItemSpan(if k == 4 { 2 } else { 1 }),
+                        move |k| cell(widths.clone(), k),
+                    );
+                },
+            ))
+        };
+        // The first frame builds for a default viewport; the second knows it is 200dp.
+        frame(&grid());
+        widths.borrow_mut().clear();
+        frame(&grid());
+        let w = widths.borrow();
+        assert!((w[&u64::MAX] - 300.0).abs() < 1.0, "{w:?}");
+        assert!((w[&0] - 100.0).abs() < 1.0, "{w:?}");
+        assert!((w[&4] - 200.0).abs() < 1.0, "{w:?}");
+        // Four rows fit, plus two below: nowhere near 10000 items.
+        assert!(w.len() <= 18, "built {}", w.len());
+        drop(w);
+
+        let built = Rc::new(RefCell::new(Vec::new()));
+        let row_state = Rc::new(LazyColumnState::new());
+        let row = || {
+            built.borrow_mut().clear();
+            let built = built.clone();
+            Column(Modifier::new()).child(LazyRow(
+                row_state.clone(),
+                50.0,
+                Modifier::new().width(200.0).height(50.0),
+                move |list| {
+                    list.items(0..10_000, move |k| {
+                        built.borrow_mut().push(k);
+                        Box(Modifier::new().size(50.0, 50.0))
+                    })
+                },
+            ))
+        };
+        frame(&row());
+        frame(&row());
+        assert_eq!(*built.borrow(), (0..7).collect::<Vec<u64>>());
+
+        // Scrolling sideways moves the built window along.
+        row_state.set_offset(1000.0, 500_000.0);
+        frame(&row());
+        assert_eq!(built.borrow().first(), Some(&18));
+    }
+
+    #[test]
+    fn sticky_header_pins_to_the_top_until_the_next_one_pushes_it_out() {
+        use crate::lazy::{LazyColumnState, LazyColumnWith};
+
+        let state = Rc::new(LazyColumnState::new());
+        // Sections of a 30dp white header and ten 40dp items, so headers sit 430dp apart.
+        let frame = || {
+            let root = Column(Modifier::new()).child(LazyColumnWith(
+                state.clone(),
+                40.0,
+                Modifier::new().height(200.0),
+                |list| {
+                    for section in 0..3u64 {
+                        list.sticky_header(1000 + section, || {
+                            Box(Modifier::new()
+                                .fill_max_width()
+                                .height(30.0)
+                                .background(Color::WHITE))
+                        });
+                        list.items(section * 10..section * 10 + 10, |_| {
+                            Box(Modifier::new()
+                                .fill_max_width()
+                                .height(40.0)
+                                .background(Color::BLACK))
+                        });
+                    }
+                },
+            ));
+            let (scene, _hits, _sems) = layout_and_paint(
+                &root,
+                (400, 400),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            );
+            let fills: Vec<(Color, f32)> = scene
+                .nodes
+                .iter()
+                .filter_map(|n| match n {
+                    SceneNode::Rect {
+                        brush: Brush::Solid(c),
+                        rect,
+                        ..
+                    } => Some((*c, rect.y)),
+                    _ => None,
+                })
+                .collect();
+            fills
+        };
+        let headers = |fills: &[(Color, f32)]| -> Vec<f32> {
+            fills
+                .iter()
+                .filter(|(c, _)| *c == Color::WHITE)
+                .map(|(_, y)| *y)
+                .collect()
+        };
+        frame();
+        frame();
+
+        // Scrolled into the first section: its header stays at the top, over the items.
+        state.set_offset(100.0, 1290.0);
+        let fills = frame();
+        assert_eq!(headers(&fills), vec![0.0]);
+        let last_of = |c: Color| fills.iter().rposition(|f| f.0 == c).unwrap();
+        assert!(last_of(Color::WHITE) > last_of(Color::BLACK));
+
+        // The second header arrives 10dp below the top and pushes the first 20dp up.
+        state.set_offset(420.0, 1290.0);
+        let mut ys = headers(&frame());
+        ys.sort_by(f32::total_cmp);
+        assert_eq!(ys, vec![-20.0, 10.0]);
+
+        // Past it, the second header is the one pinned.
+        state.set_offset(500.0, 1290.0);
+        assert_eq!(headers(&frame()), vec![0.0]);
+    }
+
+    #[test]
+    fn scroll_states_animate_and_jump_to_items_from_app_code() {
+        use crate::lazy::{LazyColumn, LazyColumnState};
+        use crate::scroll::{ScrollArea, ScrollAreaState, ScrollDirection};
+        use repose_core::animation::{Easing, TestClock, set_clock, swap_clock};
+        use web_time::Duration;
+
+        let t0 = Instant::now();
+        let _clock = swap_clock(Box::new(TestClock { t: t0 }));
+        let at = |ms: u64| {
+            set_clock(Box::new(TestClock {
+                t: t0 + Duration::from_millis(ms),
+            }))
+        };
+        let spec = AnimationSpec::tween(Duration::from_millis(100), Easing::Linear);
+        let paint = |root: View| {
+            layout_and_paint(
+                &root,
+                (400, 400),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            );
+        };
+
+        // 1000dp of content in a 200dp viewport.
+        at(0);
+        let area = Rc::new(ScrollAreaState::new());
+        let scroll_area = || {
+            Column(Modifier::new()).child(
+                ScrollArea(
+                    ScrollDirection::Vertical,
+                    area.clone(),
+                    Modifier::new().height(200.0),
+                )
+                .child(Column(Modifier::new()).child(Box(Modifier::new().size(10.0, 1000.0)))),
+            )
+        };
+        paint(scroll_area());
+        assert_eq!(area.max_offset().y, 800.0);
+        area.animate_scroll_to_with(Vec2 { x: 0.0, y: 400.0 }, spec);
+        assert!(area.is_scrolling());
+        at(50);
+        paint(scroll_area());
+        assert_eq!(area.offset().y, 200.0);
+        at(200);
+        paint(scroll_area());
+        assert_eq!(area.offset().y, 400.0);
+        assert!(!area.is_scrolling());
+
+        // A list of 40dp items: jump to item 10, then glide back up.
+        let list = Rc::new(LazyColumnState::new());
+        let lazy = || {
+            Column(Modifier::new()).child(LazyColumn(
+                (0..100u64).collect(),
+                40.0,
+                list.clone(),
+                Modifier::new().height(200.0),
+                |_, _| Box(Modifier::new().height(40.0)),
+            ))
+        };
+        paint(lazy());
+        list.scroll_to_item(10);
+        paint(lazy());
+        assert_eq!(list.offset(), 400.0);
+        assert_eq!(list.max_offset(), 3800.0);
+        list.animate_scroll_to_with(100.0, spec);
+        at(250);
+        paint(lazy());
+        assert_eq!(list.offset(), 250.0);
+        at(400);
+        paint(lazy());
+        assert_eq!(list.offset(), 100.0);
+        assert!(!list.is_scrolling());
+    }
+
+    #[test]
+    fn keyed_lazy_column_keeps_its_item_when_items_are_inserted_above() {
+        use crate::lazy::{LazyColumnKeyed, LazyColumnState, LazyListAnchor};
+
+        let state = Rc::new(LazyColumnState::new());
+        let build = |items: Vec<u64>| {
+            LazyColumnKeyed(
+                items,
+                |k| *k,
+                50.0,
+                state.clone(),
+                Modifier::new(),
+                |_, _| Box(Modifier::new().height(50.0)),
+            );
+        };
+        build((0..100).collect());
+        state.set_offset(10.0 * 50.0 + 5.0, 100.0 * 50.0);
+        build((0..100).collect());
+        assert_eq!(
+            state.anchor(),
+            LazyListAnchor {
+                index: 10,
+                key: Some(10),
+                offset_px: 5.0
+            }
+        );
+
+        // A refresh adds three items on top: still on item 10, now at index 13.
+        build((1000..1003).chain(0..100).collect());
+        assert_eq!(state.anchor().index, 13);
+        // A list that is briefly empty doesn't lose the place either.
+        build(vec![]);
+        build((0..100).collect());
+        assert_eq!(state.anchor().index, 10);
+
+        let restored = LazyColumnState::with_anchor(state.anchor());
+        assert_eq!(restored.anchor(), state.anchor());
+    }
+
+    #[test]
+    fn expandable_section_animates_its_layout_height() {
+        use repose_core::animation::{Easing, TestClock, set_clock, swap_clock};
+        use web_time::Duration;
+
+        let t0 = Instant::now();
+        let _clock = swap_clock(Box::new(TestClock { t: t0 }));
+        let at = |ms: u64| {
+            set_clock(Box::new(TestClock {
+                t: t0 + Duration::from_millis(ms),
+            }))
+        };
+        let spec = AnimationSpec::tween(Duration::from_millis(100), Easing::Linear);
+        // Number of hit regions and the bottom edge of the lowest one.
+        let frame = |open: bool| {
+            let mut root = Column(Modifier::new()).child(ExpandableSection(
+                Box(Modifier::new().height(48.0)),
+                open,
+                |_| {},
+                Box(Modifier::new()
+                    .height(40.0)
+                    .clickable()
+                    .on_pointer_down(|_| {})),
+            ));
+            let body = &mut root.children[0].children[1];
+            body.modifier = body.modifier.clone().animate_expand_with(open, spec);
+            let (_, hits, _) = layout_and_paint(
+                &root,
+                (200, 400),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            );
+            let bottom = hits.iter().map(|h| h.rect.y + h.rect.h).fold(0.0, f32::max);
+            (hits.len(), bottom)
+        };
+
+        at(0);
+        let (closed_hits, header_bottom) = frame(false);
+        assert_eq!(closed_hits, 1, "collapsed content is not hit-testable");
+
+        frame(true);
+        at(50);
+        let (hits, bottom) = frame(true);
+        assert_eq!(hits, 2);
+        assert!((bottom - (header_bottom + 20.0)).abs() < 0.5, "{bottom}");
+
+        at(200);
+        let (_, bottom) = frame(true);
+        assert!((bottom - (header_bottom + 40.0)).abs() < 0.5, "{bottom}");
+    }
+
+    #[test]
+    fn expandable_section_toggles_on_click_not_on_press() {
+        let toggled = Rc::new(std::cell::Cell::new(None));
+        let t = toggled.clone();
+        let root = ExpandableSection(
+            Text("Header"),
+            false,
+            move |open| t.set(Some(open)),
+            Text("Body"),
+        );
+        let (_, hits, _) = layout_and_paint(
+            &root,
+            (200, 400),
+            &RetainedStateStore::new(),
+            &Interactions::default(),
+            None,
+        );
+        let header = &hits[0];
+        assert!(header.on_pointer_down.is_none() && header.focusable);
+        (header.on_click.as_ref().unwrap())();
+        assert_eq!(toggled.get(), Some(true));
+    }
+
+    #[test]
+    fn breadcrumbs_collapse_from_the_middle() {
+        use crate::breadcrumbs::{Crumb, visible_crumbs};
+
+        let w = [40.0, 50.0, 60.0, 70.0, 30.0];
+        let all: Vec<_> = (0..5).map(Crumb::Item).collect();
+        assert_eq!(visible_crumbs(&w, 10.0, 20.0, 0.0), all);
+        assert_eq!(visible_crumbs(&w, 10.0, 20.0, 290.0), all);
+        // Root, `…`, and as many trailing items as fit.
+        assert_eq!(
+            visible_crumbs(&w, 10.0, 20.0, 200.0),
+            vec![
+                Crumb::Item(0),
+                Crumb::Collapsed(2),
+                Crumb::Item(3),
+                Crumb::Item(4)
+            ]
+        );
+        assert_eq!(
+            visible_crumbs(&w, 10.0, 20.0, 50.0),
+            vec![Crumb::Item(0), Crumb::Collapsed(3), Crumb::Item(4)]
+        );
+    }
+
+    #[test]
+    fn breadcrumbs_collapse_on_the_frame_after_a_narrow_layout() {
+        use crate::breadcrumbs::Breadcrumbs;
+
+        let items: Vec<String> = ["Home", "Projects", "Repose", "Widgets", "Breadcrumbs"]
+            .map(String::from)
+            .to_vec();
+        let mut sched = Scheduler::new();
+        sched.size = (160, 40);
+        let mut frame = || {
+            let f = sched.repose(
+                |_| Breadcrumbs(items.clone(), |_| {}),
+                |v, size| {
+                    layout_and_paint(
+                        v,
+                        size,
+                        &RetainedStateStore::new(),
+                        &Interactions::default(),
+                        None,
+                    )
+                },
+            );
+            let shows = |s: &str| {
+                f.scene
+                    .nodes
+                    .iter()
+                    .any(|n| matches!(n, SceneNode::Text { text, .. } if text == s))
+            };
+            (shows("…"), frame_requested())
+        };
+
+        // Not measured yet: everything, and a frame to catch up.
+        assert_eq!(frame(), (false, true));
+        assert_eq!(frame(), (true, false));
+        assert_eq!(frame(), (true, false));
+    }
+
+    #[test]
+    fn pagination_keeps_ends_and_neighbours() {
+        use crate::pagination::{PageItem::*, page_items};
+
+        assert_eq!(page_items(0, 0), vec![]);
+        assert_eq!(page_items(0, 3), vec![Page(0), Page(1), Page(2)]);
+        assert_eq!(
+            page_items(9, 20),
+            vec![
+                Page(0),
+                Gap(4),
+                Page(8),
+                Page(9),
+                Page(10),
+                Gap(14),
+                Page(19)
+            ]
+        );
+        // A gap of one page shows that page instead.
+        assert_eq!(
+            page_items(2, 20),
+            vec![Page(0), Page(1), Page(2), Page(3), Gap(11), Page(19)]
+        );
+        assert_eq!(
+            page_items(19, 20),
+            vec![Page(0), Gap(9), Page(18), Page(19)]
+        );
+    }
+
+    #[test]
+    fn walk_cache_replays_scrolled_content_until_the_key_or_hover_changes() {
+        use crate::scroll::{ScrollArea, ScrollAreaState, ScrollDirection};
+        use std::cell::Cell;
+
+        let state = Rc::new(ScrollAreaState::new());
+        let placed = Rc::new(Cell::new(0));
+        let view = |key: u64| {
+            let p = placed.clone();
+            ScrollArea(
+                ScrollDirection::Vertical,
+                state.clone(),
+                Modifier::new().size(200.0, 100.0).walk_cache(key),
+            )
+            .child(
+                Column(Modifier::new().on_placed(move |_| p.set(p.get() + 1))).child(
+                    (0..20)
+                        .map(|i| Button(Text(format!("row {i}")), || {}))
+                        .collect::<Vec<_>>(),
+                ),
+            )
+        };
+        let paint = |v: &View, hover: Option<u64>| {
+            layout_and_paint(
+                v,
+                (200, 100),
+                &RetainedStateStore::new(),
+                &Interactions {
+                    hover,
+                    ..Default::default()
+                },
+                None,
+            )
+        };
+        let row_y = |scene: &Scene, label: &str| {
+            scene.nodes.iter().find_map(|n| match n {
+                SceneNode::Text { rect, text, .. } if *text == label => Some(rect.y),
+                _ => None,
+            })
+        };
+
+        let (scene, hits, _) = paint(&view(1), None);
+        let y0 = row_y(&scene, "row 1").unwrap();
+        assert_eq!(placed.get(), 1);
+
+        // Only scrolled: the content is moved, not painted again.
+        state.scroll_to(Vec2 { x: 0.0, y: 30.0 });
+        let (scene, hits2, _) = paint(&view(1), None);
+        assert_eq!(placed.get(), 1);
+        assert_eq!(row_y(&scene, "row 1"), Some(y0 - 30.0));
+        let button = hits
+            .iter()
+            .find(|h| h.on_click.is_some() && h.rect.y > 40.0)
+            .unwrap();
+        let moved = hits2.iter().find(|h| h.id == button.id).unwrap();
+        assert_eq!(moved.rect.y, button.rect.y - 30.0);
+
+        // Hovering a row inside, or a new key, paints it again.
+        paint(&view(1), Some(moved.id));
+        assert_eq!(placed.get(), 2);
+        paint(&view(2), Some(moved.id));
+        assert_eq!(placed.get(), 3);
+        paint(&view(2), Some(moved.id));
+        assert_eq!(placed.get(), 3);
+    }
+
+    #[test]
+    fn minimap_takes_the_right_edge_and_jumps_on_press() {
+        use crate::scroll::{ScrollArea, ScrollAreaState, ScrollDirection};
+        use repose_core::input::*;
+
+        let state = Rc::new(ScrollAreaState::new());
+        let view = ScrollArea(
+            ScrollDirection::Both,
+            state.clone(),
+            Modifier::new().size(400.0, 300.0).minimap(100.0),
+        )
+        .child(Box(Modifier::new()
+            .size(1000.0, 6000.0)
+            .flex_shrink(0.0)
+            .background(Color::WHITE)));
+        let paint = || {
+            layout_and_paint(
+                &view,
+                (400, 300),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            )
+        };
+        let _ = repose_core::take_pixmap_updates();
+        let (scene, hits, _) = paint();
+        assert_eq!(state.viewport().x, 300.0);
+
+        // The content again at a tenth of its size, drawn from a pixmap.
+        let handle = scene
+            .nodes
+            .iter()
+            .find_map(|n| match n {
+                SceneNode::Image { rect, handle, .. }
+                    if rect.x == 300.0 && (rect.h - 600.0).abs() < 0.01 =>
+                {
+                    Some(*handle)
+                }
+                _ => None,
+            })
+            .expect("minimap image");
+        let updates = repose_core::take_pixmap_updates();
+        let (_, pixmap) = updates
+            .iter()
+            .find(|(h, _)| *h == handle)
+            .expect("pixmap upload");
+        let pixmap = pixmap.as_ref().unwrap();
+        assert_eq!((pixmap.width, pixmap.height), (100, 600));
+        assert_eq!(pixmap.pixel(50, 300), Color::WHITE);
+
+        // Scrolling moves the map without drawing it again.
+        state.scroll_to(Vec2 { x: 0.0, y: 500.0 });
+        paint();
+        assert!(repose_core::take_pixmap_updates().is_empty());
+
+        let gutter = hits
+            .iter()
+            .find(|h| h.rect.x == 300.0 && h.rect.w == 100.0)
+            .expect("gutter hit region");
+        (gutter.on_pointer_down.as_ref().unwrap())(PointerEvent {
+            id: PointerId(0),
+            kind: PointerKind::Mouse,
+            event: PointerEventKind::Down(PointerButton::Primary),
+            position: Vec2 { x: 350.0, y: 150.0 },
+            pressure: 1.0,
+            modifiers: Modifiers::default(),
+        });
+        // The middle of the gutter is the middle of the scroll range.
+        let off = state.offset();
+        assert!((off.x - 350.0).abs() < 0.5, "{off:?}");
+        assert!((off.y - 2850.0).abs() < 0.5, "{off:?}");
+    }
+
+    #[test]
+    fn zoomable_canvas_scales_content_and_maps_pointer_positions_back() {
+        use crate::zoom::{CanvasState, ZoomableCanvas};
+        use repose_core::input::*;
+
+        let state = Rc::new(CanvasState::new());
+        state.set_zoom(2.0);
+        state.set_pan(Vec2 { x: 10.0, y: 0.0 });
+        let got = Rc::new(std::cell::Cell::new(None::<Vec2>));
+        let seen = got.clone();
+        let view = Box(Modifier::new().size(400.0, 300.0)).child(ZoomableCanvas(
+            state.clone(),
+            Box(Modifier::new()
+                .size(50.0, 40.0)
+                .absolute()
+                .offset_left(20.0)
+                .background(Color::WHITE)
+                .on_pointer_down(move |e| seen.set(Some(e.position)))),
+        ));
+        let (scene, hits, _) = layout_and_paint(
+            &view,
+            (400, 300),
+            &RetainedStateStore::new(),
+            &Interactions::default(),
+            None,
+        );
+
+        // Laid out at x 20, drawn at 10 + 2 * 20 and twice the size.
+        let drawn = |r: &Rect| r.x == 50.0 && r.y == 0.0 && r.w == 100.0 && r.h == 80.0;
+        assert!(scene.nodes.iter().any(
+            |n| matches!(n, SceneNode::Rect { rect, brush: Brush::Solid(c), .. } if *c == Color::WHITE && drawn(rect))
+        ));
+        let hit = hits
+            .iter()
+            .find(|h| drawn(&h.rect))
+            .expect("child hit region");
+        (hit.on_pointer_down.as_ref().unwrap())(PointerEvent {
+            id: PointerId(0),
+            kind: PointerKind::Mouse,
+            event: PointerEventKind::Down(PointerButton::Primary),
+            position: Vec2 { x: 100.0, y: 40.0 },
+            pressure: 1.0,
+            modifiers: Modifiers::default(),
+        });
+        assert_eq!(got.get(), Some(Vec2 { x: 45.0, y: 20.0 }));
+
+        // The content is known after the frame, so it can be framed.
+        assert_eq!(
+            state.content_bounds(),
+            Some(Rect {
+                x: 20.0,
+                y: 0.0,
+                w: 50.0,
+                h: 40.0
+            })
+        );
+        state.fit_to_content(0.0);
+        assert_eq!(state.zoom(), 7.5);
+    }
+
+    #[test]
+    fn retained_state_is_dropped_once_its_view_leaves_the_tree() {
+        use crate::textfield::TextFieldExt;
+
+        let retained = RetainedStateStore::new();
+        let frame = |with_field: bool| {
+            let mut root = Column(Modifier::new()).child(Text("title"));
+            if with_field {
+                root = root.child(
+                    TextField(
+                        "name",
+                        Modifier::new(),
+                        None::<fn(String)>,
+                        None::<fn(String)>,
+                    )
+                    .state_key(42),
+                );
+            }
+            layout_and_paint(&root, (400, 200), &retained, &Interactions::default(), None);
+            retained.evict_unseen();
+        };
+
+        frame(true);
+        retained.insert(42, TextFieldState::new());
+        retained.insert(42, 3u32);
+        frame(true);
+        assert!(retained.get::<TextFieldState>(42).is_some());
+        assert_eq!(retained.get::<u32>(42).map(|v| *v.borrow()), Some(3));
+
+        // Gone from the tree: both kinds of state go with it.
+        frame(false);
+        assert!(retained.is_empty());
+    }
+
+    #[test]
+    fn custom_widget_measures_and_paints() {
+        struct Dot;
+        impl repose_core::Widget for Dot {
+            fn measure(&self, _: repose_core::Constraints) -> repose_core::Size {
+                repose_core::Size {
+                    width: 30.0,
+                    height: 20.0,
+                }
+            }
+            fn paint(&self, cx: &repose_core::PaintCtx, scene: &mut Scene) {
+                scene.nodes.push(SceneNode::Ellipse {
+                    rect: cx.rect,
+                    brush: Brush::Solid(Color::WHITE),
+                });
+            }
+            fn hit_regions(&self, cx: &repose_core::PaintCtx) -> Vec<HitRegion> {
+                vec![HitRegion::new(cx.id, cx.rect).on_click(|| {})]
+            }
+            fn semantics(&self, cx: &repose_core::PaintCtx) -> Option<SemNode> {
+                Some(SemNode::new(cx.id, Role::Button, cx.rect).label("dot"))
+            }
+        }
+
+        let root = Row(Modifier::new()).child(vec![
+            Box(Modifier::new().size(10.0, 10.0)),
+            Custom(Modifier::new(), Dot),
+        ]);
+        let (scene, hits, sems) = layout_and_paint(
+            &root,
+            (400, 400),
+            &RetainedStateStore::new(),
+            &Interactions::default(),
+            None,
+        );
+        let rect = scene
+            .nodes
+            .iter()
+            .find_map(|n| match n {
+                SceneNode::Ellipse { rect, .. } => Some(*rect),
+                _ => None,
+            })
+            .expect("widget painted");
+        // Rows stretch the cross axis, so only the measured width survives.
+        assert_eq!((rect.x, rect.w), (10.0, 30.0));
+        assert!(hits.iter().any(|h| h.rect == rect && h.on_click.is_some()));
+        assert!(sems.iter().any(|s| s.label.as_deref() == Some("dot")));
+    }
+
+    #[test]
+    fn non_finite_subtree_becomes_placeholder() {
+        let root = Column(Modifier::new()).child((
+            Text("ok"),
+            Box(Modifier::new().width(f32::NAN)).child(Text("lost")),
+        ));
+        let (scene, _hits, sems) = try_layout_and_paint(
+            &root,
+            (200, 200),
+            &RetainedStateStore::new(),
+            &Interactions::default(),
+            None,
+        )
+        .expect("frame should recover per subtree");
+
+        assert!(sems.iter().any(|s| s.label.as_deref() == Some("ok")));
+        assert!(!sems.iter().any(|s| s.label.as_deref() == Some("lost")));
+        assert!(
+            sems.iter()
+                .any(|s| s.label.as_deref().is_some_and(|l| l.contains("width")))
+        );
+        assert!(
+            scene
+                .nodes
+                .iter()
+                .any(|n| matches!(n, SceneNode::Border { .. }))
+        );
+    }
+
+    #[test]
+    fn modifier_chain_paints_in_order() {
+        let m = Modifier::new()
+            .border(2.0, Color::WHITE, 0.0)
+            .padding(15.0)
+            .background(Color::BLACK)
+            .clip_rounded(4.0)
+            .scale(2.0);
+        let root = Box(Modifier::new().size(100.0, 100.0)).child(Box(m.size(100.0, 100.0)));
+        let (scene, _hits, _sems) = layout_and_paint(
+            &root,
+            (200, 200),
+            &RetainedStateStore::new(),
+            &Interactions::default(),
+            None,
+        );
+
+        let border = scene.nodes.iter().find_map(|n| match n {
+            SceneNode::Border { rect, .. } => Some(*rect),
+            _ => None,
+        });
+        let fill = scene.nodes.iter().find_map(|n| match n {
+            SceneNode::Rect { rect, .. } => Some(*rect),
+            _ => None,
+        });
+        assert_eq!(border.map(|r| r.w), Some(100.0));
+        assert_eq!(fill.map(|r| (r.x, r.w)), Some((15.0, 70.0)));
+
+        let layers: Vec<_> = scene
+            .nodes
+            .iter()
+            .filter(|n| {
+                matches!(
+                    n,
+                    SceneNode::PushClip { .. }
+                        | SceneNode::PushTransform { .. }
+                        | SceneNode::PopClip
+                        | SceneNode::PopTransform
+                )
+            })
+            .collect();
+        assert!(matches!(
+            layers.as_slice(),
+            [
+                SceneNode::PushClip { .. },
+                SceneNode::PushTransform { .. },
+                SceneNode::PopTransform,
+                SceneNode::PopClip
+            ]
+        ));
+    }
+
+    #[test]
+    fn padding_is_ordered_and_repeated_builders_replace() {
+        // Rects painted for `m` on a 100x100 box holding a white child.
+        let fills = |m: Modifier| {
+            let child = Box(Modifier::new().fill_max_size().background(Color::WHITE));
+            let root = Box(Modifier::new().size(100.0, 100.0))
+                .child(Box(m.size(100.0, 100.0)).child(child));
+            let (scene, _hits, _sems) = layout_and_paint(
+                &root,
+                (200, 200),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            );
+            scene
+                .nodes
+                .iter()
+                .filter_map(|n| match n {
+                    SceneNode::Rect {
+                        rect,
+                        brush: Brush::Solid(c),
+                        ..
+                    } => Some((*c, rect.x, rect.w)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let (black, white) = (Color::BLACK, Color::WHITE);
+
+        assert_eq!(
+            fills(Modifier::new().padding(10.0).background(black)),
+            [(black, 10.0, 80.0), (white, 10.0, 80.0)]
+        );
+        assert_eq!(
+            fills(Modifier::new().background(black).padding(10.0)),
+            [(black, 0.0, 100.0), (white, 10.0, 80.0)]
+        );
+        // The later call wins, at its own position.
+        let red = Color::from_hex("#FF0000");
+        assert_eq!(
+            fills(
+                Modifier::new()
+                    .background(red)
+                    .padding(10.0)
+                    .background(black)
+                    .padding(5.0)
+            ),
+            [(black, 0.0, 100.0), (white, 5.0, 90.0)]
+        );
+
+        // Fields written by hand take effect, in their builder's place.
+        let mut m = Modifier::new().padding(10.0).background(black);
+        m.background = Some(Brush::Solid(red));
+        m.padding = Some(20.0);
+        assert_eq!(fills(m), [(red, 20.0, 60.0), (white, 20.0, 60.0)]);
+        let mut m = Modifier::new().alpha(1.0);
+        m.alpha = Some(0.38);
+        assert!(matches!(
+            m.elements().as_ref(),
+            [ModifierElement::Alpha(a)] if *a == 0.38
+        ));
+    }
+
+    #[test]
+    fn unwrapped_text_takes_its_shaped_width() {
+        let marker = Color::from_hex("#FF0000");
+        let x_after = |text: &str| {
+            let root = Row(Modifier::new()).child(vec![
+                Text(text).size(16.0).single_line(),
+                Box(Modifier::new().size(10.0, 10.0).background(marker)),
+            ]);
+            let (scene, _, _) = layout_and_paint(
+                &root,
+                (400, 100),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            );
+            scene
+                .nodes
+                .iter()
+                .find_map(|n| match n {
+                    SceneNode::Rect {
+                        rect,
+                        brush: Brush::Solid(c),
+                        ..
+                    } if *c == marker => Some(rect.x),
+                    _ => None,
+                })
+                .expect("marker painted")
+        };
+        let (narrow, wide) = (x_after("iiii"), x_after("WWWW"));
+        assert!(narrow < wide, "{narrow} vs {wide}");
+        // Layout rounds to whole pixels.
+        assert!((wide - repose_text::measure_width("WWWW", 16.0, 0.0)).abs() <= 1.0);
+    }
+
+    #[test]
+    fn text_align_places_lines_and_justify_fills_all_but_the_last() {
+        let paint = |align: TextAlign, text: &str| {
+            // The root always fills the window, so the 200dp box goes inside it.
+            let root = Column(Modifier::new()).child(vec![
+                Column(Modifier::new().width(200.0))
+                    .child(vec![Text(text).size(16.0).text_align(align)]),
+            ]);
+            let (scene, _, _) = layout_and_paint(
+                &root,
+                (400, 400),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            );
+            scene
+                .nodes
+                .into_iter()
+                .filter_map(|n| match n {
+                    SceneNode::Text { rect, text, .. } => Some((text.to_string(), rect.x, rect.y)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let w = repose_text::measure_width("Hi", 16.0, 0.0);
+        assert_eq!(paint(TextAlign::Start, "Hi")[0].1, 0.0);
+        assert!((paint(TextAlign::Center, "Hi")[0].1 - (200.0 - w) / 2.0).abs() < 0.01);
+        assert!((paint(TextAlign::End, "Hi")[0].1 - (200.0 - w)).abs() < 0.01);
+
+        let words = "the quick brown fox jumps over the lazy dog again and again";
+        let pieces = paint(TextAlign::Justify, words);
+        let first_y = pieces[0].2;
+        let first_line: Vec<_> = pieces.iter().filter(|p| p.2 == first_y).collect();
+        let last = first_line.last().unwrap();
+        let right = last.1 + repose_text::measure_width(&last.0, 16.0, 0.0);
+        assert!((right - 200.0).abs() < 0.5, "{pieces:?}");
+        // The paragraph's last line keeps its natural spacing, in one piece from the start.
+        let last_line = pieces.last().unwrap();
+        assert_eq!(last_line.1, 0.0);
+        assert_eq!(pieces.iter().filter(|p| p.2 == last_line.2).count(), 1);
+    }
+
+    #[test]
+    fn click_through_and_invisible_nodes_get_no_hit_regions() {
+        let area = |side: f32| Modifier::new().size(side, side).clickable();
+        let root = Stack(Modifier::new()).child(vec![
+            Box(area(10.0)),
+            Box(area(20.0).alpha(0.0)),
+            Box(area(30.0).hit_test_disabled()).child(vec![Box(area(40.0))]),
+            Box(area(50.0).alpha(0.5)),
+        ]);
+        let (_, hits, _) = layout_and_paint(
+            &root,
+            (200, 200),
+            &RetainedStateStore::new(),
+            &Interactions::default(),
+            None,
+        );
+        let sides: Vec<f32> = hits.iter().map(|h| h.rect.w).collect();
+        assert_eq!(sides, vec![10.0, 50.0]);
+    }
+
+    #[test]
+    fn toggleable_canvas_gets_the_same_hit_semantics_and_visuals_as_checkbox() {
+        let toggles = Rc::new(RefCell::new(Vec::new()));
+        let custom = {
+            let toggles = toggles.clone();
+            Box(Modifier::new().size(60.0, 20.0).toggleable(
+                Role::Switch,
+                ToggleableState::Indeterminate,
+                move || toggles.borrow_mut().push("custom"),
+            ))
+        };
+        let checkbox = {
+            let toggles = toggles.clone();
+            Checkbox(true, move |on| {
+                toggles
+                    .borrow_mut()
+                    .push(if on { "checked" } else { "unchecked" })
+            })
+        };
+        let root = Row(Modifier::new()).child(vec![custom, checkbox]);
+        let frame = |interactions: &Interactions| {
+            layout_and_paint(
+                &root,
+                (400, 200),
+                &RetainedStateStore::new(),
+                interactions,
+                None,
+            )
+        };
+
+        let (_, hits, sems) = frame(&Interactions::default());
+        let toggle_sems: Vec<(Role, Option<ToggleableState>)> =
+            sems.iter().map(|s| (s.role, s.toggle)).collect();
+        assert_eq!(
+            toggle_sems,
+            vec![
+                (Role::Switch, Some(ToggleableState::Indeterminate)),
+                (Role::Checkbox, Some(ToggleableState::On)),
+            ]
+        );
+        assert_eq!(hits.len(), 2);
+        for h in &hits {
+            assert!(h.focusable);
+            (h.on_click.as_ref().unwrap())();
+        }
+        assert_eq!(*toggles.borrow(), vec!["custom", "unchecked"]);
+
+        // Pressing (pointer, or Space/Enter while focused) shades the custom toggle.
+        let custom_rect = sems[0].rect;
+        let shaded = |scene: &Scene| {
+            scene
+                .nodes
+                .iter()
+                .any(|n| matches!(n, SceneNode::Rect { rect, .. } if *rect == custom_rect))
+        };
+        assert!(!shaded(&frame(&Interactions::default()).0));
+        let pressed = Interactions {
+            pressed: HashSet::from([sems[0].id]),
+            ..Default::default()
+        };
+        assert!(shaded(&frame(&pressed).0));
+    }
+
+    #[test]
+    fn rich_text_wraps_across_spans_and_makes_links_clickable() {
+        let clicked = Rc::new(RefCell::new(false));
+        let text = AnnotatedText::new()
+            .push("Read the ")
+            .push_styled("important", SpanStyle::new().bold())
+            .push(" note and then the ")
+            .push_link("terms of service", {
+                let clicked = clicked.clone();
+                move || *clicked.borrow_mut() = true
+            });
+        let root = Column(Modifier::new().width(160.0)).child(vec![RichText(text).size(16.0)]);
+        let (scene, hits, _) = layout_and_paint(
+            &root,
+            (400, 400),
+            &RetainedStateStore::new(),
+            &Interactions::default(),
+            None,
+        );
+
+        let pieces: Vec<(&str, f32, FontWeight)> = scene
+            .nodes
+            .iter()
+            .filter_map(|n| match n {
+                SceneNode::Text {
+                    text, rect, weight, ..
+                } => Some((text.as_str(), rect.y, *weight)),
+                _ => None,
+            })
+            .collect();
+        assert!(pieces.contains(&("important", pieces[0].1, FontWeight::BOLD)));
+        // 160dp can't hold it all, so it wraps onto more than one line.
+        let tops: HashSet<u32> = pieces.iter().map(|p| p.1 as u32).collect();
+        assert!(tops.len() > 1, "{pieces:?}");
+        assert_eq!(
+            pieces
+                .iter()
+                .map(|p| p.0)
+                .collect::<String>()
+                .replace(' ', ""),
+            "Readtheimportantnoteandthenthetermsofservice"
+        );
+
+        // The link is underlined and each of its pieces clicks through.
+        assert!(
+            scene
+                .nodes
+                .iter()
+                .any(|n| matches!(n, SceneNode::Rect { rect, .. } if rect.h <= 2.0))
+        );
+        let links: Vec<_> = hits.iter().filter(|h| h.on_click.is_some()).collect();
+        assert!(!links.is_empty());
+        (links[0].on_click.as_ref().unwrap())();
+        assert!(*clicked.borrow());
+    }
+
+    #[test]
+    fn content_size_is_measured_once_without_the_window_size() {
+        let root = Column(Modifier::new().padding(10.0)).child(vec![
+            Box(Modifier::new().size(120.0, 40.0)),
+            Box(Modifier::new().size(60.0, 30.0)),
+        ]);
+        let paint = || {
+            layout_and_paint(
+                &root,
+                (800, 600),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            )
+        };
+
+        paint();
+        assert_eq!(take_content_size(), None);
+        measure_content_size();
+        paint();
+        let size = take_content_size().expect("measured");
+        assert_eq!((size.x, size.y), (140.0, 90.0));
+        assert_eq!(take_content_size(), None);
+        // Only the pass that was asked measures.
+        paint();
+        assert_eq!(take_content_size(), None);
+    }
+
+    #[test]
+    fn shrinking_content_size_holds_until_settled() {
+        use repose_core::animation::{Easing, TestClock, set_clock, swap_clock};
+        use web_time::Duration;
+
+        let t0 = Instant::now();
+        let _clock = swap_clock(Box::new(TestClock { t: t0 }));
+        let spec = AnimationSpec::tween(Duration::from_millis(100), Easing::Linear);
+        let frame = |h: f32| {
+            let root = Column(Modifier::new()).child(
+                Box(Modifier::new().animate_content_size_with(spec))
+                    .child(Box(Modifier::new().size(50.0, h))),
+            );
+            let (scene, _hits, _sems) = layout_and_paint(
+                &root,
+                (200, 200),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            );
+            scene
+                .nodes
+                .iter()
+                .find_map(|n| match n {
+                    SceneNode::PushClip { rect, .. } => Some(rect.h),
+                    _ => None,
+                })
+                .unwrap_or(0.0)
+        };
+
+        frame(100.0);
+        // Shrinking starts from the old size and layout keeps it until the animation ends.
+        assert_eq!(frame(40.0), 100.0);
+        set_clock(Box::new(TestClock {
+            t: t0 + Duration::from_millis(200),
+        }));
+        assert_eq!(frame(40.0), 40.0);
+    }
+
+    #[test]
+    fn shared_element_moves_from_its_previous_box_into_the_new_one() {
+        use crate::anim_ext::SharedElement;
+        use repose_core::animation::{Easing, TestClock, set_clock, swap_clock};
+        use web_time::Duration;
+
+        let t0 = Instant::now();
+        let _clock = swap_clock(Box::new(TestClock { t: t0 }));
+        let spec = AnimationSpec::tween(Duration::from_millis(100), Easing::Linear);
+        let at = |ms: u64| {
+            set_clock(Box::new(TestClock {
+                t: t0 + Duration::from_millis(ms),
+            }))
+        };
+        let paint = |root: View| {
+            let (scene, _hits, _sems) = layout_and_paint(
+                &root,
+                (400, 400),
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            );
+            // Where the element's 80px box at (100, 100) ends up on screen.
+            scene.nodes.iter().find_map(|n| match n {
+                SceneNode::PushTransform { transform } => {
+                    let a = transform.apply_to_point(Vec2 { x: 100.0, y: 100.0 });
+                    let b = transform.apply_to_point(Vec2 { x: 180.0, y: 180.0 });
+                    Some((a.x, a.y, b.x - a.x))
+                }
+                _ => None,
+            })
+        };
+        // A 40px thumbnail at the origin, then a screen with the same key 80px wide at (100, 100).
+        let list = || {
+            Column(Modifier::new()).child(
+                Row(Modifier::new()).child(SharedElement(7, Box(Modifier::new().size(40.0, 40.0)))),
+            )
+        };
+        let detail = || {
+            Column(Modifier::new()).child((
+                Box(Modifier::new().size(1.0, 100.0)),
+                Row(Modifier::new()).child((
+                    Box(Modifier::new().size(100.0, 1.0)),
+                    Box(Modifier::new()
+                        .size(80.0, 80.0)
+                        .shared_element_with(7, spec)),
+                )),
+            ))
+        };
+
+        assert_eq!(paint(list()), None);
+        assert_eq!(paint(detail()), Some((0.0, 0.0, 40.0)));
+        at(50);
+        let (x, y, w) = paint(detail()).unwrap();
+        assert!((x - 50.0).abs() < 0.01 && (y - 50.0).abs() < 0.01 && (w - 60.0).abs() < 0.01);
+        at(200);
+        assert_eq!(paint(detail()), None);
+    }
+
+    #[test]
+    fn z_index_reorders_sibling_paint() {
+        let root = Stack(Modifier::new().size(100.0, 100.0)).child((
+            Box(Modifier::new()
+                .size(50.0, 50.0)
+                .background(Color::WHITE)
+                .z_index(1.0)),
+            Box(Modifier::new().size(50.0, 50.0).background(Color::BLACK)),
+            Box(Modifier::new().size(50.0, 50.0).background(Color::BLACK)),
+        ));
+        let (scene, _hits, _sems) = layout_and_paint(
+            &root,
+            (200, 200),
+            &RetainedStateStore::new(),
+            &Interactions::default(),
+            None,
+        );
+        let fills: Vec<_> = scene
+            .nodes
+            .iter()
+            .filter_map(|n| match n {
+                SceneNode::Rect {
+                    brush: Brush::Solid(c),
+                    ..
+                } => Some(*c),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(fills, vec![Color::BLACK, Color::BLACK, Color::WHITE]);
+    }
+
+    #[test]
+    fn density_and_text_scale_overrides_reach_layout_and_paint() {
+        let boxed = |c: Color| Box(Modifier::new().size(50.0, 20.0).background(c));
+        let root = Column(Modifier::new()).child((
+            WithDensity(2.0, || boxed(Color::WHITE)),
+            boxed(Color::BLACK),
+            WithTextScale(1.5, || Text("scaled").size(10.0)),
+            Text("plain").size(10.0),
+        ));
+        let (scene, _hits, _sems) = layout_and_paint(
+            &root,
+            (400, 400),
+            &RetainedStateStore::new(),
+            &Interactions::default(),
+            None,
+        );
+        let fill = |want: Color| {
+            scene.nodes.iter().find_map(|n| match n {
+                SceneNode::Rect {
+                    rect,
+                    brush: Brush::Solid(c),
+                    ..
+                } if *c == want => Some((rect.w, rect.h)),
+                _ => None,
+            })
+        };
+        assert_eq!(fill(Color::WHITE), Some((100.0, 40.0)));
+        assert_eq!(fill(Color::BLACK), Some((50.0, 20.0)));
+
+        let text = |want: &str| {
+            scene.nodes.iter().find_map(|n| match n {
+                SceneNode::Text { text, size, .. } if **text == *want => Some(*size),
+                _ => None,
+            })
+        };
+        assert_eq!(text("scaled"), Some(15.0));
+        assert_eq!(text("plain"), Some(10.0));
+    }
 }
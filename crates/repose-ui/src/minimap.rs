@@ -0,0 +1,585 @@
+//! # Minimap
+//!
+//! An overview gutter for a two-axis [`ScrollArea`](crate::scroll::ScrollArea), for
+//! content much larger than its viewport (code, node graphs). Turn it on with
+//! [`Modifier::minimap`]; the gutter takes the place of the vertical scrollbar.
+//!
+//! The gutter shows the content scaled down, with text drawn as bars, and a rectangle for
+//! the visible part. The scaled content is drawn once into a [`Pixmap`] and shown as an
+//! image; it's drawn again only when what the content painted changes, so scrolling just
+//! moves the image. Pressing or dragging in the gutter centres the viewport on the
+//! pointer. When the scaled content is taller than the gutter, the map scrolls along with
+//! the content.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use repose_core::*;
+
+/// Largest scale the content is drawn at; narrow content doesn't fill the gutter.
+const MAX_SCALE: f32 = 0.2;
+/// Width of a character in a text bar, relative to the font size.
+const CHAR_W: f32 = 0.5;
+/// Longest side of a map's pixmap, in px; taller content is drawn smaller and stretched.
+const MAX_PIXMAP: f32 = 4096.0;
+/// Passes a map can go unpainted before its pixmap is freed.
+const KEEP_PASSES: u64 = 120;
+
+/// What the content painted, in content px, reduced to what the map draws.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Mark {
+    Fill(Rect, Color),
+    Ellipse(Rect, Color),
+    Ring(Rect, f32, Color),
+    Line(Vec2, Vec2, f32, Color),
+    Arc {
+        center: Vec2,
+        radius: f32,
+        inner: f32,
+        start: f32,
+        sweep: f32,
+        color: Color,
+    },
+    Clip(Rect),
+    Unclip,
+}
+
+struct Cached {
+    marks: Vec<Mark>,
+    size: (u32, u32),
+    scale: f32,
+    last_pass: u64,
+}
+
+thread_local! {
+    static MAPS: RefCell<HashMap<ViewId, Cached>> = RefCell::new(HashMap::new());
+    static PASS: Cell<u64> = const { Cell::new(0) };
+}
+
+pub(crate) fn begin_pass() {
+    let pass = PASS.get() + 1;
+    PASS.set(pass);
+    MAPS.with(|m| {
+        m.borrow_mut().retain(|id, map| {
+            let keep = pass - map.last_pass <= KEEP_PASSES;
+            if !keep {
+                remove_pixmap(pixmap_handle(*id));
+            }
+            keep
+        })
+    });
+}
+
+/// Where the content lands in a minimap gutter this frame. All values are px.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Minimap {
+    gutter: Rect,
+    /// Screen position of the content's top-left.
+    origin: Vec2,
+    content: Vec2,
+    viewport: Vec2,
+    offset: Vec2,
+    scale: f32,
+    /// How far the map is scrolled when it doesn't fit the gutter.
+    scroll: f32,
+}
+
+impl Minimap {
+    pub(crate) fn new(
+        gutter: Rect,
+        origin: Vec2,
+        content: Vec2,
+        viewport: Vec2,
+        offset: Vec2,
+    ) -> Self {
+        let scale = if content.x > 0.0 {
+            (gutter.w / content.x).min(MAX_SCALE)
+        } else {
+            MAX_SCALE
+        };
+        let extra = (content.y * scale - gutter.h).max(0.0);
+        let max_y = (content.y - viewport.y).max(0.0);
+        let scroll = if max_y > 0.0 {
+            extra * (offset.y / max_y).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        Self {
+            gutter,
+            origin,
+            content,
+            viewport,
+            offset,
+            scale,
+            scroll,
+        }
+    }
+
+    pub(crate) fn gutter(&self) -> Rect {
+        self.gutter
+    }
+
+    fn map_point(&self, p: Vec2) -> Vec2 {
+        Vec2 {
+            x: self.gutter.x + (p.x - self.origin.x) * self.scale,
+            y: self.gutter.y + (p.y - self.origin.y) * self.scale - self.scroll,
+        }
+    }
+
+    fn map_rect(&self, r: Rect) -> Rect {
+        let p = self.map_point(Vec2 { x: r.x, y: r.y });
+        Rect {
+            x: p.x,
+            y: p.y,
+            w: r.w * self.scale,
+            h: r.h * self.scale,
+        }
+    }
+
+    /// The visible part of the content, in the gutter.
+    pub(crate) fn viewport_rect(&self) -> Rect {
+        Rect {
+            x: self.gutter.x + self.offset.x * self.scale,
+            y: self.gutter.y + self.offset.y * self.scale - self.scroll,
+            w: self.viewport.x * self.scale,
+            h: self.viewport.y * self.scale,
+        }
+    }
+
+    /// The scroll offset that centres the viewport rectangle on `pointer`, accounting for
+    /// the map scrolling along with it.
+    pub(crate) fn offset_at(&self, pointer: Vec2) -> Vec2 {
+        let max = Vec2 {
+            x: (self.content.x - self.viewport.x).max(0.0),
+            y: (self.content.y - self.viewport.y).max(0.0),
+        };
+        let s = self.scale;
+        let x = (pointer.x - self.gutter.x) / s - self.viewport.x / 2.0;
+
+        // The rectangle's top sits at `offset.y * k` in the gutter.
+        let extra = (self.content.y * s - self.gutter.h).max(0.0);
+        let k = if max.y > 0.0 { s - extra / max.y } else { s };
+        let y = if k > 0.0 {
+            (pointer.y - self.gutter.y - self.viewport.y * s / 2.0) / k
+        } else {
+            0.0
+        };
+        Vec2 {
+            x: x.clamp(0.0, max.x),
+            y: y.clamp(0.0, max.y),
+        }
+    }
+
+    /// `nodes` (as painted in the viewport) in content px.
+    fn marks(&self, nodes: &[SceneNode]) -> Vec<Mark> {
+        let at = |p: Vec2| Vec2 {
+            x: p.x - self.origin.x,
+            y: p.y - self.origin.y,
+        };
+        let local = |r: Rect| Rect {
+            x: r.x - self.origin.x,
+            y: r.y - self.origin.y,
+            ..r
+        };
+        let mut out = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            match node {
+                SceneNode::Rect { rect, brush, .. } => {
+                    out.push(Mark::Fill(local(*rect), brush_color(brush)))
+                }
+                SceneNode::Border {
+                    rect, color, width, ..
+                } => {
+                    let r = local(*rect);
+                    let w = width.min(r.w / 2.0).min(r.h / 2.0).max(0.0);
+                    out.extend([
+                        Mark::Fill(Rect { h: w, ..r }, *color),
+                        Mark::Fill(
+                            Rect {
+                                y: r.y + r.h - w,
+                                h: w,
+                                ..r
+                            },
+                            *color,
+                        ),
+                        Mark::Fill(Rect { w, ..r }, *color),
+                        Mark::Fill(
+                            Rect {
+                                x: r.x + r.w - w,
+                                w,
+                                ..r
+                            },
+                            *color,
+                        ),
+                    ]);
+                }
+                SceneNode::Text {
+                    rect,
+                    text,
+                    color,
+                    size,
+                    ..
+                } => {
+                    // A bar where the ink would be: indentation kept, one line high.
+                    let indent = text.chars().take_while(|c| c.is_whitespace()).count();
+                    let len = text.trim().chars().count();
+                    let r = local(*rect);
+                    let h = size * 0.6;
+                    out.push(Mark::Fill(
+                        Rect {
+                            x: r.x + indent as f32 * size * CHAR_W,
+                            y: r.y + (size - h) / 2.0,
+                            w: len as f32 * size * CHAR_W,
+                            h,
+                        },
+                        color.with_alpha((color.3 as f32 * 0.6) as u8),
+                    ));
+                }
+                SceneNode::Ellipse { rect, brush } => {
+                    out.push(Mark::Ellipse(local(*rect), brush_color(brush)))
+                }
+                SceneNode::EllipseBorder { rect, color, width } => {
+                    out.push(Mark::Ring(local(*rect), *width, *color))
+                }
+                SceneNode::Line {
+                    from,
+                    to,
+                    width,
+                    color,
+                } => out.push(Mark::Line(at(*from), at(*to), *width, *color)),
+                SceneNode::Arc {
+                    center,
+                    radius,
+                    inner_radius,
+                    start,
+                    sweep,
+                    color,
+                } => out.push(Mark::Arc {
+                    center: at(*center),
+                    radius: *radius,
+                    inner: *inner_radius,
+                    start: *start,
+                    sweep: *sweep,
+                    color: *color,
+                }),
+                // Images show as a tinted block.
+                SceneNode::Image { rect, tint, .. } => out.push(Mark::Fill(
+                    local(*rect),
+                    tint.with_alpha((tint.3 as f32 * 0.4) as u8),
+                )),
+                SceneNode::PushClip { rect, .. } => out.push(Mark::Clip(local(*rect))),
+                SceneNode::PopClip => out.push(Mark::Unclip),
+                SceneNode::PushTransform { .. } | SceneNode::PopTransform => {}
+            }
+        }
+        out
+    }
+
+    /// Draws the content (the scene nodes in `content`, as painted in the viewport of view
+    /// `id`) scaled into the gutter, then the viewport rectangle. The content's pixmap is
+    /// only drawn again when it painted something different from last time.
+    pub(crate) fn paint(&self, id: ViewId, scene: &mut Scene, content: std::ops::Range<usize>) {
+        let marks = self.marks(&scene.nodes[content]);
+        let scale = self
+            .scale
+            .min(MAX_PIXMAP / self.content.x.max(1.0))
+            .min(MAX_PIXMAP / self.content.y.max(1.0));
+        let size = (
+            (self.content.x * scale).ceil().max(1.0) as u32,
+            (self.content.y * scale).ceil().max(1.0) as u32,
+        );
+        let handle = pixmap_handle(id);
+        MAPS.with(|m| {
+            let mut maps = m.borrow_mut();
+            let pass = PASS.get();
+            match maps.get_mut(&id) {
+                Some(map) if map.size == size && map.scale == scale && map.marks == marks => {
+                    map.last_pass = pass
+                }
+                _ => {
+                    set_pixmap(handle, rasterize(&marks, size, scale));
+                    maps.insert(
+                        id,
+                        Cached {
+                            marks,
+                            size,
+                            scale,
+                            last_pass: pass,
+                        },
+                    );
+                }
+            }
+        });
+
+        let th = locals::theme();
+        scene.nodes.push(SceneNode::PushClip {
+            rect: self.gutter,
+            radius: 0.0,
+        });
+        scene.nodes.push(SceneNode::Rect {
+            rect: self.gutter,
+            brush: Brush::Solid(th.scrollbar_track),
+            radius: 0.0,
+        });
+        scene.nodes.push(SceneNode::Image {
+            rect: self.map_rect(Rect {
+                x: self.origin.x,
+                y: self.origin.y,
+                w: self.content.x,
+                h: self.content.y,
+            }),
+            handle,
+            tint: Color::WHITE,
+            fit: ImageFit::Cover,
+        });
+
+        let vp = self.viewport_rect();
+        scene.nodes.push(SceneNode::Rect {
+            rect: vp,
+            brush: Brush::Solid(th.scrollbar_thumb.with_alpha(60)),
+            radius: 0.0,
+        });
+        scene.nodes.push(SceneNode::Border {
+            rect: vp,
+            color: th.scrollbar_thumb,
+            width: 1.0,
+            radius: 0.0,
+        });
+        scene.nodes.push(SceneNode::PopClip);
+    }
+}
+
+fn brush_color(brush: &Brush) -> Color {
+    match brush {
+        Brush::Solid(c) => *c,
+        Brush::Linear { start_color, .. } => *start_color,
+    }
+}
+
+/// Draws `marks` at `scale` into a `size` pixmap. Anything drawn is at least a pixel across,
+/// so thin lines and small text stay visible.
+fn rasterize(marks: &[Mark], size: (u32, u32), scale: f32) -> Pixmap {
+    let mut p = Pixmap::new(size.0, size.1);
+    let px = |v: f32| if v > 0.0 { (v * scale).max(1.0) } else { 0.0 };
+    let pt = |v: Vec2| Vec2 {
+        x: v.x * scale,
+        y: v.y * scale,
+    };
+    let rect = |r: Rect| Rect {
+        x: r.x * scale,
+        y: r.y * scale,
+        w: px(r.w),
+        h: px(r.h),
+    };
+    let mut clips: Vec<Rect> = Vec::new();
+    for m in marks {
+        let clip = clips.last().copied();
+        match *m {
+            Mark::Fill(r, c) => p.fill_rect(rect(r), c, clip),
+            Mark::Ellipse(r, c) => fill_ellipse(&mut p, rect(r), None, c, clip),
+            Mark::Ring(r, w, c) => fill_ellipse(&mut p, rect(r), Some(px(w)), c, clip),
+            Mark::Line(a, b, w, c) => {
+                let (a, b) = (pt(a), pt(b));
+                let steps = (b.x - a.x).hypot(b.y - a.y).ceil().max(1.0) as u32;
+                for i in 0..=steps {
+                    let t = i as f32 / steps as f32;
+                    let at = Vec2 {
+                        x: a.x + (b.x - a.x) * t,
+                        y: a.y + (b.y - a.y) * t,
+                    };
+                    stamp(&mut p, at, px(w), c, clip);
+                }
+            }
+            Mark::Arc {
+                center,
+                radius,
+                inner,
+                start,
+                sweep,
+                color,
+            } => {
+                let c = pt(center);
+                let mid = (radius + inner) / 2.0 * scale;
+                let steps = (mid * sweep.abs()).ceil().max(1.0) as u32;
+                for i in 0..=steps {
+                    let a = start + sweep * i as f32 / steps as f32;
+                    let at = Vec2 {
+                        x: c.x + mid * a.cos(),
+                        y: c.y + mid * a.sin(),
+                    };
+                    stamp(&mut p, at, px(radius - inner), color, clip);
+                }
+            }
+            Mark::Clip(r) => {
+                let r = Rect {
+                    x: r.x * scale,
+                    y: r.y * scale,
+                    w: r.w * scale,
+                    h: r.h * scale,
+                };
+                clips.push(clip.map_or(r, |c| {
+                    let (x0, y0) = (r.x.max(c.x), r.y.max(c.y));
+                    let (x1, y1) = ((r.x + r.w).min(c.x + c.w), (r.y + r.h).min(c.y + c.h));
+                    Rect {
+                        x: x0,
+                        y: y0,
+                        w: (x1 - x0).max(0.0),
+                        h: (y1 - y0).max(0.0),
+                    }
+                }));
+            }
+            Mark::Unclip => {
+                clips.pop();
+            }
+        }
+    }
+    p
+}
+
+/// A `size` square centred on `at`.
+fn stamp(p: &mut Pixmap, at: Vec2, size: f32, color: Color, clip: Option<Rect>) {
+    let r = Rect {
+        x: at.x - size / 2.0,
+        y: at.y - size / 2.0,
+        w: size,
+        h: size,
+    };
+    p.fill_rect(r, color, clip);
+}
+
+/// Fills the ellipse inscribed in `r` a pixel row at a time, or just a `ring` px wide
+/// band inside its edge.
+fn fill_ellipse(p: &mut Pixmap, r: Rect, ring: Option<f32>, color: Color, clip: Option<Rect>) {
+    let (rx, ry) = (r.w / 2.0, r.h / 2.0);
+    let (cx, cy) = (r.x + rx, r.y + ry);
+    if rx <= 0.0 || ry <= 0.0 {
+        return;
+    }
+    // Half the width of an ellipse with radii (a, b) at `dy` from its centre.
+    let half = |a: f32, b: f32, dy: f32| {
+        let t = dy / b;
+        (t.abs() < 1.0 && a > 0.0 && b > 0.0).then(|| a * (1.0 - t * t).sqrt())
+    };
+    let row = |x0: f32, x1: f32, y: f32| Rect {
+        x: x0,
+        y,
+        w: x1 - x0,
+        h: 1.0,
+    };
+    let (y0, y1) = ((cy - ry).floor() as i64, (cy + ry).ceil() as i64);
+    for y in y0..y1 {
+        let dy = y as f32 + 0.5 - cy;
+        let Some(outer) = half(rx, ry, dy) else {
+            continue;
+        };
+        match ring.and_then(|w| half(rx - w, ry - w, dy)) {
+            Some(inner) => {
+                p.fill_rect(row(cx - outer, cx - inner, y as f32), color, clip);
+                p.fill_rect(row(cx + inner, cx + outer, y as f32), color, clip);
+            }
+            None => p.fill_rect(row(cx - outer, cx + outer, y as f32), color, clip),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gutter() -> Rect {
+        Rect {
+            x: 400.0,
+            y: 0.0,
+            w: 100.0,
+            h: 200.0,
+        }
+    }
+
+    #[test]
+    fn pointer_centres_the_viewport_when_the_map_fits() {
+        // 1000x800 content at scale 0.1 is 100x80: fits the gutter, no map scrolling.
+        let m = Minimap::new(
+            gutter(),
+            Vec2::default(),
+            Vec2 {
+                x: 1000.0,
+                y: 800.0,
+            },
+            Vec2 { x: 400.0, y: 200.0 },
+            Vec2::default(),
+        );
+        let off = m.offset_at(Vec2 { x: 450.0, y: 40.0 });
+        assert!((off.x - 300.0).abs() < 1e-3 && (off.y - 300.0).abs() < 1e-3);
+
+        let m = Minimap::new(gutter(), Vec2::default(), m.content, m.viewport, off);
+        let r = m.viewport_rect();
+        assert!((r.x + r.w / 2.0 - 450.0).abs() < 1e-3);
+        assert!((r.y + r.h / 2.0 - 40.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn thin_content_stays_visible_in_the_pixmap() {
+        let red = Color::from_rgb(255, 0, 0);
+        // Content whose top-left is on screen at (-50, -20), scrolled that far.
+        let m = Minimap::new(
+            gutter(),
+            Vec2 { x: -50.0, y: -20.0 },
+            Vec2 {
+                x: 1000.0,
+                y: 800.0,
+            },
+            Vec2 { x: 400.0, y: 200.0 },
+            Vec2 { x: 50.0, y: 20.0 },
+        );
+        let marks = m.marks(&[SceneNode::Border {
+            rect: Rect {
+                x: 150.0,
+                y: 180.0,
+                w: 200.0,
+                h: 100.0,
+            },
+            color: red,
+            width: 1.0,
+            radius: 4.0,
+        }]);
+        let p = rasterize(&marks, (100, 80), m.scale);
+        // The 1 px outline at content (200, 200) is a pixel wide at a tenth of the size,
+        // and the inside stays empty.
+        assert_eq!(p.pixel(20, 20), red);
+        assert_eq!(p.pixel(40, 25), red);
+        assert_eq!(p.pixel(30, 25), Color::TRANSPARENT);
+    }
+
+    #[test]
+    fn a_tall_map_scrolls_with_the_content() {
+        // 500x10000 content at the 0.2 cap is 100x2000 in a 200 px gutter.
+        let content = Vec2 {
+            x: 500.0,
+            y: 10_000.0,
+        };
+        let viewport = Vec2 { x: 400.0, y: 200.0 };
+        let at = |y| {
+            Minimap::new(
+                gutter(),
+                Vec2::default(),
+                content,
+                viewport,
+                Vec2 { x: 0.0, y },
+            )
+        };
+
+        assert_eq!(at(0.0).viewport_rect().y, 0.0);
+        // At the end the rectangle sits at the bottom of the gutter.
+        let r = at(9_800.0).viewport_rect();
+        assert!((r.y + r.h - 200.0).abs() < 1e-3);
+
+        // Pointing back at where the rectangle's centre is drawn keeps the offset.
+        let m = at(4_900.0);
+        let r = m.viewport_rect();
+        let off = m.offset_at(Vec2 {
+            x: 410.0,
+            y: r.y + r.h / 2.0,
+        });
+        assert!((off.y - 4_900.0).abs() < 0.5);
+    }
+}
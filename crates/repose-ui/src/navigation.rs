@@ -4,12 +4,28 @@ use repose_core::*;
 use std::any::Any;
 use std::cell::RefCell;
 use std::collections::{HashMap, VecDeque};
-use std::rc::Rc;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::rc::{Rc, Weak};
+use std::str::FromStr;
 
+/// Navigation between string routes, with a back stack.
+///
+/// Guards added with [`add_guard`](Self::add_guard) vet every `navigate` and `replace`
+/// before it happens. A screen opened with [`navigate_for_result`](Self::navigate_for_result)
+/// hands a value back to its caller with [`set_result`](Self::set_result). Results travel
+/// as strings in the entries' `args`, so a [`snapshot`](Self::snapshot) taken while a
+/// picker is open restores with the request, or its unread result, intact.
 pub struct NavController {
     stack: RefCell<VecDeque<NavEntry>>,
     pub current: Signal<String>,
     pub transitions: Signal<Option<Transition>>,
+    guards: RefCell<Vec<NavGuard>>,
+    // Runs the guards; `pending` is the navigation waiting on them.
+    tasks: TaskScope,
+    pending: RefCell<Option<TaskHandle>>,
+    me: Weak<NavController>,
 }
 
 pub struct NavEntry {
@@ -18,6 +34,13 @@ pub struct NavEntry {
     pub state: Box<dyn Any>,
 }
 
+/// The restorable part of a [`NavEntry`]: its route and args.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SavedNavEntry {
+    pub route: String,
+    pub args: HashMap<String, String>,
+}
+
 #[derive(Clone)]
 pub enum Transition {
     Push { from: String, to: String },
@@ -25,59 +48,202 @@ pub enum Transition {
     Replace { from: String, to: String },
 }
 
+/// What a route guard decides about a navigation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GuardDecision {
+    Allow,
+    /// Stay where we are.
+    Deny,
+    /// Go to this route instead; the guards see that navigation too.
+    Redirect(String),
+}
+
+type NavGuard = Rc<dyn Fn(&Transition) -> Pin<Box<dyn Future<Output = GuardDecision>>>>;
+
+/// How many redirects a navigation follows before it is dropped as a loop.
+const MAX_REDIRECTS: usize = 8;
+
+// Arg of an entry opened for a result: the result's name.
+const RESULT_FOR: &str = "nav.result_for";
+// Arg prefix of a result waiting in the caller's entry.
+const RESULT_PREFIX: &str = "nav.result.";
+
+/// Names a result a screen can hand back to its caller, and its type; see
+/// [`NavController::navigate_for_result`]. Declare one per kind of result, shared by
+/// both screens.
+pub struct NavResultKey<T> {
+    name: &'static str,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T> NavResultKey<T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for NavResultKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for NavResultKey<T> {}
+
 impl NavController {
     pub fn new(initial: impl Into<String>) -> Rc<Self> {
-        let route = initial.into();
-        Rc::new(Self {
-            stack: RefCell::new({
-                let mut dq = VecDeque::new();
-                dq.push_back(NavEntry {
-                    route: route.clone(),
-                    args: HashMap::new(),
-                    state: Box::new(()),
-                });
-                dq
-            }),
+        Self::restore(vec![SavedNavEntry {
+            route: initial.into(),
+            args: HashMap::new(),
+        }])
+    }
+
+    /// A controller with the back stack from an earlier [`snapshot`](Self::snapshot),
+    /// or `/` if it is empty.
+    pub fn restore(entries: Vec<SavedNavEntry>) -> Rc<Self> {
+        let mut stack: VecDeque<NavEntry> = entries
+            .into_iter()
+            .map(|e| NavEntry {
+                route: e.route,
+                args: e.args,
+                state: Box::new(()),
+            })
+            .collect();
+        if stack.is_empty() {
+            stack.push_back(NavEntry {
+                route: "/".into(),
+                args: HashMap::new(),
+                state: Box::new(()),
+            });
+        }
+        let route = stack.back().map(|e| e.route.clone()).unwrap_or_default();
+        Rc::new_cyclic(|me| Self {
+            stack: RefCell::new(stack),
             current: signal(route),
             transitions: signal(None),
+            guards: RefCell::new(Vec::new()),
+            tasks: TaskScope::new(),
+            pending: RefCell::new(None),
+            me: me.clone(),
         })
     }
 
+    /// The back stack's routes and args, bottom first, for [`restore`](Self::restore).
+    pub fn snapshot(&self) -> Vec<SavedNavEntry> {
+        self.stack
+            .borrow()
+            .iter()
+            .map(|e| SavedNavEntry {
+                route: e.route.clone(),
+                args: e.args.clone(),
+            })
+            .collect()
+    }
+
+    /// Runs `guard` before every later `navigate` and `replace`, in the order guards were
+    /// added; the first that doesn't allow it decides. While any guard is installed,
+    /// navigation happens once the guards have answered, on a later frame; a navigation
+    /// requested meanwhile replaces the one waiting.
+    pub fn add_guard<Fut>(&self, guard: impl Fn(&Transition) -> Fut + 'static)
+    where
+        Fut: Future<Output = GuardDecision> + 'static,
+    {
+        self.guards
+            .borrow_mut()
+            .push(Rc::new(move |t| Box::pin(guard(t))));
+    }
+
+    /// Whether a navigation is waiting on the guards.
+    pub fn is_navigating(&self) -> bool {
+        self.pending
+            .borrow()
+            .as_ref()
+            .is_some_and(TaskHandle::is_active)
+    }
+
     pub fn navigate(&self, route: impl Into<String>) {
-        let route = route.into();
-        let mut stack = self.stack.borrow_mut();
+        let to = route.into();
         let from = self.current.get();
+        self.request(Transition::Push { from, to }, HashMap::new());
+    }
 
-        stack.push_back(NavEntry {
-            route: route.clone(),
-            args: HashMap::new(),
-            state: Box::new(()),
-        });
-
-        self.transitions.set(Some(Transition::Push {
-            from,
-            to: route.clone(),
-        }));
-        self.current.set(route);
+    /// Navigates to `route`, which can then hand a value of type `T` back to this screen
+    /// with [`set_result`](Self::set_result); read it here with
+    /// [`take_result`](Self::take_result) once it is back on top.
+    pub fn navigate_for_result<T>(&self, route: impl Into<String>, key: NavResultKey<T>) {
+        let to = route.into();
+        let from = self.current.get();
+        let args = HashMap::from([(RESULT_FOR.to_string(), key.name.to_string())]);
+        self.request(Transition::Push { from, to }, args);
     }
 
     pub fn replace(&self, route: impl Into<String>) {
-        let route = route.into();
-        let mut stack = self.stack.borrow_mut();
+        let to = route.into();
         let from = self.current.get();
+        self.request(Transition::Replace { from, to }, HashMap::new());
+    }
 
-        if let Some(_last) = stack.pop_back() {
-            // drop last
+    fn request(&self, t: Transition, args: HashMap<String, String>) {
+        if let Some(p) = self.pending.borrow_mut().take() {
+            p.cancel();
+        }
+        let guards = self.guards.borrow().clone();
+        if guards.is_empty() {
+            self.commit(t, args);
+            return;
         }
+        let me = self.me.clone();
+        let task = self.tasks.spawn(async move {
+            let (mut t, mut args) = (t, args);
+            for _ in 0..=MAX_REDIRECTS {
+                let mut decision = GuardDecision::Allow;
+                for g in &guards {
+                    decision = g(&t).await;
+                    if decision != GuardDecision::Allow {
+                        break;
+                    }
+                }
+                match decision {
+                    GuardDecision::Allow => {
+                        if let Some(me) = me.upgrade() {
+                            me.commit(t, args);
+                        }
+                        return;
+                    }
+                    GuardDecision::Deny => return,
+                    GuardDecision::Redirect(route) => {
+                        if let Transition::Push { to, .. } | Transition::Replace { to, .. } = &mut t
+                        {
+                            *to = route;
+                        }
+                        // The result request was for the original destination.
+                        args.clear();
+                    }
+                }
+            }
+            log::warn!("navigation redirected more than {MAX_REDIRECTS} times; dropped");
+        });
+        *self.pending.borrow_mut() = Some(task);
+    }
+
+    fn commit(&self, t: Transition, args: HashMap<String, String>) {
+        let mut stack = self.stack.borrow_mut();
+        let route = match &t {
+            Transition::Push { to, .. } => to.clone(),
+            Transition::Replace { to, .. } => {
+                stack.pop_back();
+                to.clone()
+            }
+            Transition::Pop { .. } => return,
+        };
         stack.push_back(NavEntry {
             route: route.clone(),
-            args: HashMap::new(),
+            args,
             state: Box::new(()),
         });
-        self.transitions.set(Some(Transition::Replace {
-            from,
-            to: route.clone(),
-        }));
+        self.transitions.set(Some(t));
         self.current.set(route);
     }
 
@@ -98,6 +264,34 @@ impl NavController {
         false
     }
 
+    /// Hands `value` to the screen that opened this one with
+    /// [`navigate_for_result`](Self::navigate_for_result) and `key`, for it to take once
+    /// it is back on top; usually followed by [`pop`](Self::pop). Returns `false`, and
+    /// drops `value`, if this screen wasn't opened for that result.
+    pub fn set_result<T: ToString>(&self, key: NavResultKey<T>, value: T) -> bool {
+        let mut stack = self.stack.borrow_mut();
+        let n = stack.len();
+        let asked =
+            n >= 2 && stack[n - 1].args.get(RESULT_FOR).map(String::as_str) == Some(key.name);
+        if asked {
+            stack[n - 2]
+                .args
+                .insert(format!("{RESULT_PREFIX}{}", key.name), value.to_string());
+        }
+        asked
+    }
+
+    /// Takes the result for `key` handed back to the current screen, if one has arrived
+    /// (and parses as `T`).
+    pub fn take_result<T: FromStr>(&self, key: NavResultKey<T>) -> Option<T> {
+        let mut stack = self.stack.borrow_mut();
+        let raw = stack
+            .back_mut()?
+            .args
+            .remove(&format!("{RESULT_PREFIX}{}", key.name))?;
+        raw.parse().ok()
+    }
+
     pub fn take_transition(&self) -> Option<Transition> {
         let t = self.transitions.get();
         self.transitions.set(None);
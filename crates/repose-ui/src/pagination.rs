@@ -0,0 +1,123 @@
+//! # Pagination
+//!
+//! Previous / next arrows around the page numbers. The first and last pages and the
+//! neighbours of the current one are always shown; longer runs in between become `…`
+//! buttons that jump to the middle of the run they hide.
+
+use std::rc::Rc;
+
+use repose_core::*;
+
+use crate::{Box, Row, Text, TextStyle, ViewExt};
+
+const FONT_DP: f32 = 14.0;
+const CELL_DP: f32 = 32.0;
+/// Pages shown on each side of the current one.
+const SIBLINGS: usize = 1;
+
+/// One button of a pagination bar, by 0-based page index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PageItem {
+    Page(usize),
+    /// Stands for a run of hidden pages; holds the page in its middle.
+    Gap(usize),
+}
+
+/// The page buttons for `page` of `count` pages.
+pub fn page_items(page: usize, count: usize) -> Vec<PageItem> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let page = page.min(count - 1);
+    let lo = page.saturating_sub(SIBLINGS);
+    let hi = (page + SIBLINGS).min(count - 1);
+
+    let mut shown = vec![0];
+    shown.extend(lo.max(1)..=hi);
+    if count - 1 > hi {
+        shown.push(count - 1);
+    }
+
+    let mut out = Vec::with_capacity(shown.len() + 2);
+    let mut prev: Option<usize> = None;
+    for p in shown {
+        match prev {
+            // A single hidden page takes no more room than `…`; show it.
+            Some(q) if p == q + 2 => out.push(PageItem::Page(q + 1)),
+            Some(q) if p > q + 2 => out.push(PageItem::Gap((q + p) / 2)),
+            _ => {}
+        }
+        out.push(PageItem::Page(p));
+        prev = Some(p);
+    }
+    out
+}
+
+/// A bar for `count` pages with the 0-based `page` selected; `on_change` gets the page
+/// to go to.
+pub fn Pagination(page: usize, count: usize, on_change: impl Fn(usize) + 'static) -> View {
+    let on_change: Rc<dyn Fn(usize)> = Rc::new(on_change);
+    let last = count.saturating_sub(1);
+    let page = page.min(last);
+
+    let mut children = vec![page_button(
+        "‹",
+        "Previous page".into(),
+        (page > 0).then(|| page - 1),
+        false,
+        &on_change,
+    )];
+    for item in page_items(page, count) {
+        children.push(match item {
+            PageItem::Page(p) => page_button(
+                &(p + 1).to_string(),
+                format!("Page {}", p + 1),
+                (p != page).then_some(p),
+                p == page,
+                &on_change,
+            ),
+            PageItem::Gap(p) => {
+                page_button("…", format!("Page {}", p + 1), Some(p), false, &on_change)
+            }
+        });
+    }
+    children.push(page_button(
+        "›",
+        "Next page".into(),
+        (page < last).then(|| page + 1),
+        false,
+        &on_change,
+    ));
+
+    Row(Modifier::new()).with_children(children)
+}
+
+fn page_button(
+    label: &str,
+    description: String,
+    target: Option<usize>,
+    current: bool,
+    on_change: &Rc<dyn Fn(usize)>,
+) -> View {
+    let th = locals::theme();
+    let (bg, fg) = if current {
+        (th.primary, th.on_primary)
+    } else if target.is_some() {
+        (Color::TRANSPARENT, th.on_surface)
+    } else {
+        (Color::TRANSPARENT, th.outline)
+    };
+
+    let mut m = Modifier::new()
+        .min_size(CELL_DP, CELL_DP)
+        .background(bg)
+        .clip_rounded(CELL_DP / 2.0)
+        .align_items(AlignItems::Center)
+        .justify_content(JustifyContent::Center)
+        .content_description(description);
+    if let Some(p) = target {
+        let on_change = on_change.clone();
+        m = m.clickable().on_pointer_down(move |_| on_change(p));
+    }
+    Box(m).child(Text(label).size(FONT_DP).color(fg).single_line())
+}
@@ -0,0 +1,747 @@
+//! # Printing
+//!
+//! [`print`] lays a view out on pages and returns a PDF. Shapes become vector paths and
+//! text stays text, in the standard Helvetica font stretched to the width the layout gave
+//! it, so pages are sharp at any zoom and their text can be selected and searched.
+//!
+//! The view is laid out at the page's content width, as tall as it wants to be, then cut
+//! into pages; a break moves up rather than cut through a line of text. Images are left
+//! out, gradients are filled with their middle colour, and the window background isn't
+//! painted, so the paper stays white.
+//!
+//! On desktop, `repose_platform::print::open_for_printing` hands the PDF to the system
+//! viewer, whose print dialog takes it from there.
+
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::rc::Rc;
+
+use repose_core::*;
+
+use crate::{Box, Column, Interactions, ViewExt, paint_frame, zoom};
+
+/// Paper and how the view is placed on it. Lengths are points (1/72 inch); the view is
+/// laid out with one px per point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PageConfig {
+    pub width: f32,
+    pub height: f32,
+    /// Blank space on every side.
+    pub margin: f32,
+    /// Points per dp. The default, 0.75, is 96 dp to the inch, as on a desktop screen.
+    pub dp: f32,
+}
+
+impl PageConfig {
+    pub const A4: Self = Self {
+        width: 595.0,
+        height: 842.0,
+        margin: 36.0,
+        dp: 0.75,
+    };
+    pub const LETTER: Self = Self {
+        width: 612.0,
+        height: 792.0,
+        margin: 36.0,
+        dp: 0.75,
+    };
+
+    pub fn landscape(self) -> Self {
+        Self {
+            width: self.height,
+            height: self.width,
+            ..self
+        }
+    }
+
+    pub fn margin(self, margin: f32) -> Self {
+        Self { margin, ..self }
+    }
+
+    /// The area inside the margins.
+    pub fn content_size(&self) -> (f32, f32) {
+        (
+            (self.width - 2.0 * self.margin).max(1.0),
+            (self.height - 2.0 * self.margin).max(1.0),
+        )
+    }
+}
+
+impl Default for PageConfig {
+    fn default() -> Self {
+        Self::A4
+    }
+}
+
+/// `view` as a PDF, on as many pages as it needs.
+pub fn print(view: &View, page: &PageConfig) -> Vec<u8> {
+    let (w, h) = page.content_size();
+    let height = Rc::new(Cell::new(0.0f32));
+    let measured = height.clone();
+    let mut content = Column(
+        Modifier::new()
+            .fill_max_width()
+            .flex_shrink(0.0)
+            .on_size(move |s| measured.set(s.height)),
+    )
+    .child(view.clone());
+    content.modifier.density = Some(Density { scale: page.dp });
+    let root = Box(Modifier::new().fill_max_size()).child(content);
+
+    // The window is one page tall, so the frame isn't culled to it; each page is culled to
+    // its own slice below instead.
+    let scene = match paint_frame(
+        &root,
+        (w.ceil() as u32, h.ceil() as u32),
+        &RetainedStateStore::new(),
+        &Interactions::default(),
+        None,
+        false,
+    ) {
+        Ok((scene, _, _)) => scene,
+        Err(e) => {
+            log::error!("print: {e}");
+            Scene::default()
+        }
+    };
+
+    let mut pdf = Pdf::new(*page);
+    for (top, bottom) in page_breaks(&scene.nodes, height.get(), h) {
+        let mut slice = scene.clone();
+        slice.cull(Rect {
+            x: 0.0,
+            y: top,
+            w,
+            h: bottom - top,
+        });
+        pdf.page(&slice.nodes, top, bottom);
+    }
+    pdf.finish()
+}
+
+/// Splits `0..total` into pages at most `page_h` tall. A break that would cut a line of
+/// text moves up to the top of that line, unless that loses more than half the page.
+pub(crate) fn page_breaks(nodes: &[SceneNode], total: f32, page_h: f32) -> Vec<(f32, f32)> {
+    let lines: Vec<Rect> = nodes
+        .iter()
+        .filter_map(|n| match n {
+            SceneNode::Text { rect, .. } => Some(*rect),
+            _ => None,
+        })
+        .collect();
+
+    let mut pages = Vec::new();
+    let mut top = 0.0;
+    while total - top > page_h + 0.5 {
+        let mut at = top + page_h;
+        while let Some(y) = lines
+            .iter()
+            .filter(|r| r.y < at && r.y + r.h > at)
+            .map(|r| r.y)
+            .reduce(f32::min)
+        {
+            if y <= top + page_h / 2.0 {
+                break;
+            }
+            at = y;
+        }
+        pages.push((top, at));
+        top = at;
+    }
+    pages.push((top, top + page_h));
+    pages
+}
+
+/// Helvetica advance widths for ' ' to '~', per 1000 units of font size.
+const HELVETICA: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278,
+    278, // ' '..'/'
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584,
+    556, // '0'..'?'
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722,
+    778, // '@'..'O'
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469,
+    556, // 'P'..'_'
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556,
+    556, // '`'..'o'
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584, // 'p'..'~'
+];
+
+/// `c` in the WinAnsi encoding the PDF's font uses; `?` when it has no such character.
+fn win_ansi(c: char) -> u8 {
+    match c {
+        ' '..='~' | '\u{a0}'..='\u{ff}' => c as u32 as u8,
+        '€' => 0x80,
+        '…' => 0x85,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        _ => b'?',
+    }
+}
+
+fn helvetica_width(b: u8) -> f32 {
+    f32::from(match b {
+        32..=126 => HELVETICA[usize::from(b - 32)],
+        0x85 | 0x97 => 1000,
+        0x95 => 350,
+        0x91 | 0x92 => 222,
+        0x93 | 0x94 => 333,
+        _ => 556,
+    })
+}
+
+/// A number as short as PDF allows.
+fn num(v: f32) -> String {
+    let s = format!("{v:.3}");
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" { "0".into() } else { s.into() }
+}
+
+fn brush_color(b: &Brush) -> Color {
+    match b {
+        Brush::Solid(c) => *c,
+        Brush::Linear {
+            start_color: a,
+            end_color: b,
+            ..
+        } => {
+            let mid = |x: u8, y: u8| ((u16::from(x) + u16::from(y)) / 2) as u8;
+            Color(mid(a.0, b.0), mid(a.1, b.1), mid(a.2, b.2), mid(a.3, b.3))
+        }
+    }
+}
+
+/// One page's content stream.
+struct Canvas {
+    ops: String,
+    /// Alpha values used, each a graphics state `/A<alpha>` in the page's resources.
+    alphas: BTreeSet<u8>,
+}
+
+impl Canvas {
+    fn op(&mut self, s: &str) {
+        self.ops.push_str(s);
+        self.ops.push('\n');
+    }
+
+    fn color(&mut self, c: Color, stroke: bool) {
+        self.alphas.insert(c.3);
+        let [r, g, b] = [c.0, c.1, c.2].map(|v| num(f32::from(v) / 255.0));
+        let op = if stroke { "RG" } else { "rg" };
+        let _ = writeln!(self.ops, "/A{} gs {r} {g} {b} {op}", c.3);
+    }
+
+    fn rect_path(&mut self, r: Rect, radius: f32) {
+        let radius = radius.min(r.w / 2.0).min(r.h / 2.0).max(0.0);
+        if radius <= 0.0 {
+            let _ = writeln!(
+                self.ops,
+                "{} {} {} {} re",
+                num(r.x),
+                num(r.y),
+                num(r.w),
+                num(r.h)
+            );
+            return;
+        }
+        let (x0, y0, x1, y1) = (r.x, r.y, r.x + r.w, r.y + r.h);
+        let k = radius * (1.0 - 0.552_284_8);
+        let mut p = |s: String| self.op(&s);
+        p(format!("{} {} m", num(x0 + radius), num(y0)));
+        p(format!("{} {} l", num(x1 - radius), num(y0)));
+        p(format!(
+            "{} {} {} {} {} {} c",
+            num(x1 - k),
+            num(y0),
+            num(x1),
+            num(y0 + k),
+            num(x1),
+            num(y0 + radius)
+        ));
+        p(format!("{} {} l", num(x1), num(y1 - radius)));
+        p(format!(
+            "{} {} {} {} {} {} c",
+            num(x1),
+            num(y1 - k),
+            num(x1 - k),
+            num(y1),
+            num(x1 - radius),
+            num(y1)
+        ));
+        p(format!("{} {} l", num(x0 + radius), num(y1)));
+        p(format!(
+            "{} {} {} {} {} {} c",
+            num(x0 + k),
+            num(y1),
+            num(x0),
+            num(y1 - k),
+            num(x0),
+            num(y1 - radius)
+        ));
+        p(format!("{} {} l", num(x0), num(y0 + radius)));
+        p(format!(
+            "{} {} {} {} {} {} c",
+            num(x0),
+            num(y0 + k),
+            num(x0 + k),
+            num(y0),
+            num(x0 + radius),
+            num(y0)
+        ));
+        p("h".into());
+    }
+
+    fn ellipse_path(&mut self, r: Rect) {
+        let (cx, cy) = (r.x + r.w / 2.0, r.y + r.h / 2.0);
+        let (rx, ry) = (r.w / 2.0, r.h / 2.0);
+        let (kx, ky) = (rx * 0.552_284_8, ry * 0.552_284_8);
+        let _ = writeln!(self.ops, "{} {} m", num(cx + rx), num(cy));
+        for (a, b, c) in [
+            ((cx + rx, cy + ky), (cx + kx, cy + ry), (cx, cy + ry)),
+            ((cx - kx, cy + ry), (cx - rx, cy + ky), (cx - rx, cy)),
+            ((cx - rx, cy - ky), (cx - kx, cy - ry), (cx, cy - ry)),
+            ((cx + kx, cy - ry), (cx + rx, cy - ky), (cx + rx, cy)),
+        ] {
+            let _ = writeln!(
+                self.ops,
+                "{} {} {} {} {} {} c",
+                num(a.0),
+                num(a.1),
+                num(b.0),
+                num(b.1),
+                num(c.0),
+                num(c.1)
+            );
+        }
+        self.op("h");
+    }
+
+    /// Continues the path along a circular arc from `start` to `end` (radians, clockwise
+    /// with y down) in Bézier pieces of at most a quarter turn.
+    fn arc_to(&mut self, c: Vec2, r: f32, start: f32, end: f32) {
+        let n = ((end - start).abs() / std::f32::consts::FRAC_PI_2)
+            .ceil()
+            .max(1.0);
+        let step = (end - start) / n;
+        let k = 4.0 / 3.0 * (step / 4.0).tan() * r;
+        for i in 0..n as usize {
+            let a0 = start + step * i as f32;
+            let a1 = a0 + step;
+            let (s0, c0, s1, c1) = (a0.sin(), a0.cos(), a1.sin(), a1.cos());
+            let _ = writeln!(
+                self.ops,
+                "{} {} {} {} {} {} c",
+                num(c.x + r * c0 - k * s0),
+                num(c.y + r * s0 + k * c0),
+                num(c.x + r * c1 + k * s1),
+                num(c.y + r * s1 - k * c1),
+                num(c.x + r * c1),
+                num(c.y + r * s1)
+            );
+        }
+    }
+
+    fn text(&mut self, rect: Rect, text: &str, color: Color, size: f32, spacing: f32) {
+        let bytes: Vec<u8> = text.chars().map(win_ansi).collect();
+        if bytes.is_empty() || size <= 0.0 {
+            return;
+        }
+        // Stretch Helvetica to the width the text has on screen, so layout holds.
+        let natural = bytes
+            .iter()
+            .map(|b| helvetica_width(*b) * size / 1000.0 + spacing)
+            .sum::<f32>();
+        let laid_out = repose_text::metrics_spaced(text, size, spacing)
+            .positions
+            .last()
+            .copied()
+            .unwrap_or(0.0);
+        let stretch = if natural > 0.0 && laid_out > 0.0 {
+            (100.0 * laid_out / natural).clamp(50.0, 200.0)
+        } else {
+            100.0
+        };
+        let baseline = repose_text::shape_line(text, size)
+            .first()
+            .map_or(size * 0.95, |g| g.y);
+
+        let mut s = String::with_capacity(bytes.len() + 2);
+        s.push('(');
+        for b in bytes {
+            match b {
+                b'(' | b')' | b'\\' => {
+                    s.push('\\');
+                    s.push(b as char);
+                }
+                32..=126 => s.push(b as char),
+                _ => {
+                    let _ = write!(s, "\\{b:03o}");
+                }
+            }
+        }
+        s.push(')');
+
+        self.color(color, false);
+        let _ = writeln!(
+            self.ops,
+            "BT /F1 {} Tf {} Tz {} Tc 1 0 0 -1 {} {} Tm {s} Tj ET",
+            num(size),
+            num(stretch),
+            num(spacing),
+            num(rect.x),
+            num(rect.y + baseline)
+        );
+    }
+
+    fn node(&mut self, node: &SceneNode) {
+        match node {
+            SceneNode::Rect {
+                rect,
+                brush,
+                radius,
+            } => {
+                let c = brush_color(brush);
+                if c.3 > 0 {
+                    self.color(c, false);
+                    self.rect_path(*rect, *radius);
+                    self.op("f");
+                }
+            }
+            SceneNode::Border {
+                rect,
+                color,
+                width,
+                radius,
+            } => {
+                if color.3 > 0 && *width > 0.0 {
+                    self.color(*color, true);
+                    let _ = writeln!(self.ops, "{} w", num(*width));
+                    let h = width / 2.0;
+                    let inner = Rect {
+                        x: rect.x + h,
+                        y: rect.y + h,
+                        w: (rect.w - width).max(0.0),
+                        h: (rect.h - width).max(0.0),
+                    };
+                    self.rect_path(inner, radius - h);
+                    self.op("S");
+                }
+            }
+            SceneNode::Text {
+                rect,
+                text,
+                color,
+                size,
+                letter_spacing,
+                ..
+            } => {
+                if color.3 > 0 {
+                    self.text(*rect, text, *color, *size, *letter_spacing);
+                }
+            }
+            SceneNode::Ellipse { rect, brush } => {
+                let c = brush_color(brush);
+                if c.3 > 0 {
+                    self.color(c, false);
+                    self.ellipse_path(*rect);
+                    self.op("f");
+                }
+            }
+            SceneNode::EllipseBorder { rect, color, width } => {
+                if color.3 > 0 && *width > 0.0 {
+                    self.color(*color, true);
+                    let _ = writeln!(self.ops, "{} w", num(*width));
+                    let h = width / 2.0;
+                    self.ellipse_path(Rect {
+                        x: rect.x + h,
+                        y: rect.y + h,
+                        w: (rect.w - width).max(0.0),
+                        h: (rect.h - width).max(0.0),
+                    });
+                    self.op("S");
+                }
+            }
+            SceneNode::Line {
+                from,
+                to,
+                width,
+                color,
+            } => {
+                if color.3 > 0 && *width > 0.0 {
+                    self.color(*color, true);
+                    let _ = writeln!(
+                        self.ops,
+                        "{} w {} {} m {} {} l S",
+                        num(*width),
+                        num(from.x),
+                        num(from.y),
+                        num(to.x),
+                        num(to.y)
+                    );
+                }
+            }
+            SceneNode::Arc {
+                center,
+                radius,
+                inner_radius,
+                start,
+                sweep,
+                color,
+            } => {
+                if color.3 == 0 || *sweep == 0.0 || *radius <= 0.0 {
+                    return;
+                }
+                let sweep = sweep.clamp(-std::f32::consts::TAU, std::f32::consts::TAU);
+                let end = start + sweep;
+                let at = |r: f32, a: f32| (center.x + r * a.cos(), center.y + r * a.sin());
+                self.color(*color, false);
+                let (x, y) = at(*radius, *start);
+                if *inner_radius > 0.0 {
+                    let _ = writeln!(self.ops, "{} {} m", num(x), num(y));
+                    self.arc_to(*center, *radius, *start, end);
+                    let (x, y) = at(*inner_radius, end);
+                    let _ = writeln!(self.ops, "{} {} l", num(x), num(y));
+                    self.arc_to(*center, *inner_radius, end, *start);
+                } else {
+                    let _ = writeln!(
+                        self.ops,
+                        "{} {} m {} {} l",
+                        num(center.x),
+                        num(center.y),
+                        num(x),
+                        num(y)
+                    );
+                    self.arc_to(*center, *radius, *start, end);
+                }
+                self.op("h f");
+            }
+            SceneNode::PushClip { rect, radius } => {
+                self.op("q");
+                self.rect_path(*rect, *radius);
+                self.op("W n");
+            }
+            SceneNode::PushTransform { transform: t } => {
+                let (sin, cos) = t.rotate.sin_cos();
+                let _ = writeln!(
+                    self.ops,
+                    "q {} {} {} {} {} {} cm",
+                    num(t.scale_x * cos),
+                    num(t.scale_x * sin),
+                    num(-t.scale_y * sin),
+                    num(t.scale_y * cos),
+                    num(t.translate_x),
+                    num(t.translate_y)
+                );
+            }
+            SceneNode::PopClip | SceneNode::PopTransform => self.op("Q"),
+            SceneNode::Image { .. } => {}
+        }
+    }
+}
+
+/// Objects of a PDF being written; object `n` is `objects[n - 1]`.
+struct Pdf {
+    page: PageConfig,
+    objects: Vec<Vec<u8>>,
+    pages: Vec<usize>,
+}
+
+const CATALOG: usize = 1;
+const PAGES: usize = 2;
+const FONT: usize = 3;
+
+impl Pdf {
+    fn new(page: PageConfig) -> Self {
+        let font = b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica \
+                     /Encoding /WinAnsiEncoding >>"
+            .to_vec();
+        Self {
+            page,
+            // The catalog and page tree are filled in by `finish`.
+            objects: vec![Vec::new(), Vec::new(), font],
+            pages: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, body: Vec<u8>) -> usize {
+        self.objects.push(body);
+        self.objects.len()
+    }
+
+    /// Adds a page showing the scene between `top` and `bottom`.
+    fn page(&mut self, nodes: &[SceneNode], top: f32, bottom: f32) {
+        let PageConfig {
+            width,
+            height,
+            margin,
+            ..
+        } = self.page;
+        let mut cv = Canvas {
+            ops: String::new(),
+            alphas: BTreeSet::new(),
+        };
+        // Flip to y-down, move the slice to the top margin and clip to it.
+        let _ = writeln!(
+            cv.ops,
+            "1 0 0 -1 0 {} cm 1 0 0 1 {} {} cm 1 J",
+            num(height),
+            num(margin),
+            num(margin - top)
+        );
+        let _ = writeln!(
+            cv.ops,
+            "0 {} {} {} re W n",
+            num(top),
+            num(width - 2.0 * margin),
+            num(bottom - top)
+        );
+
+        // Skip what lies entirely on other pages; inside a transform, bounds aren't known.
+        let mut transforms = 0;
+        for n in nodes {
+            match n {
+                SceneNode::PushTransform { .. } => transforms += 1,
+                SceneNode::PopTransform => transforms -= 1,
+                _ => {}
+            }
+            if transforms == 0
+                && let Some(b) = zoom::painted_bounds(std::slice::from_ref(n))
+                && (b.y >= bottom || b.y + b.h <= top)
+            {
+                continue;
+            }
+            cv.node(n);
+        }
+
+        let stream = format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            cv.ops.len(),
+            cv.ops
+        );
+        let contents = self.add(stream.into_bytes());
+
+        let mut states = String::new();
+        for a in &cv.alphas {
+            let v = num(f32::from(*a) / 255.0);
+            let _ = write!(states, "/A{a} << /ca {v} /CA {v} >> ");
+        }
+        let page = format!(
+            "<< /Type /Page /Parent {PAGES} 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /Font << /F1 {FONT} 0 R >> /ExtGState << {states}>> >> \
+             /Contents {contents} 0 R >>",
+            num(width),
+            num(height)
+        );
+        let id = self.add(page.into_bytes());
+        self.pages.push(id);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let kids: Vec<String> = self.pages.iter().map(|p| format!("{p} 0 R")).collect();
+        self.objects[CATALOG - 1] = format!("<< /Type /Catalog /Pages {PAGES} 0 R >>").into();
+        self.objects[PAGES - 1] = format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            kids.len()
+        )
+        .into();
+
+        let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(self.objects.len());
+        for (i, body) in self.objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend(format!("{} 0 obj\n", i + 1).bytes());
+            out.extend(body);
+            out.extend(b"\nendobj\n");
+        }
+        let xref = out.len();
+        out.extend(format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1).bytes());
+        for off in offsets {
+            out.extend(format!("{off:010} 00000 n \n").bytes());
+        }
+        out.extend(
+            format!(
+                "trailer\n<< /Size {} /Root {CATALOG} 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+                self.objects.len() + 1
+            )
+            .bytes(),
+        );
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Text, TextStyle};
+
+    fn pdf_text(bytes: &[u8]) -> String {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+
+    #[test]
+    fn a_short_view_prints_on_one_page_with_its_text() {
+        let view = Column(Modifier::new()).child(vec![
+            Text("Invoice (draft)").size(20.0),
+            Box(Modifier::new().size(100.0, 40.0).background(Color::BLACK)),
+        ]);
+        let pdf = pdf_text(&print(&view, &PageConfig::A4));
+        assert!(pdf.starts_with("%PDF-1.4"));
+        assert!(pdf.trim_end().ends_with("%%EOF"));
+        assert!(pdf.contains("/Count 1"));
+        assert!(pdf.contains("(Invoice \\(draft\\)) Tj"));
+        // The box, 100 x 40 dp at 0.75 pt per dp.
+        assert!(pdf.contains(" 75 30 re"));
+    }
+
+    #[test]
+    fn breaks_move_up_to_keep_lines_whole() {
+        let line = |y: f32| SceneNode::Text {
+            rect: Rect {
+                x: 0.0,
+                y,
+                w: 50.0,
+                h: 20.0,
+            },
+            text: "x".into(),
+            color: Color::BLACK,
+            size: 14.0,
+            letter_spacing: 0.0,
+            weight: FontWeight::NORMAL,
+        };
+        let nodes: Vec<SceneNode> = (0..25).map(|i| line(i as f32 * 20.0 + 5.0)).collect();
+        // 505 px of lines on 100 px pages: each break lands on a line's top.
+        let pages = page_breaks(&nodes, 505.0, 100.0);
+        assert_eq!(pages[0], (0.0, 85.0));
+        assert_eq!(pages[1], (85.0, 185.0));
+        assert_eq!(pages.len(), 6);
+        assert!(pages.windows(2).all(|w| w[0].1 == w[1].0));
+
+        // Nothing to avoid, or nothing at all: whole pages.
+        assert_eq!(page_breaks(&[], 250.0, 100.0).len(), 3);
+        assert_eq!(page_breaks(&[], 0.0, 100.0), vec![(0.0, 100.0)]);
+    }
+
+    #[test]
+    fn every_page_of_a_long_view_carries_its_own_rows() {
+        let rows: Vec<View> = (0..200).map(|i| Text(format!("Row {i}"))).collect();
+        let pdf = pdf_text(&print(
+            &Column(Modifier::new()).child(rows),
+            &PageConfig::A4,
+        ));
+        let pages: Vec<&str> = pdf.split(">>\nstream\n").skip(1).collect();
+        assert!(pages.len() > 3);
+        for i in 0..200 {
+            assert_eq!(pdf.matches(&format!("(Row {i}) Tj")).count(), 1, "row {i}");
+        }
+        let last = pages[pages.len() - 1];
+        assert!(last.contains("(Row 199) Tj"));
+        assert!(!last.contains("(Row 0) Tj"));
+    }
+}
@@ -2,11 +2,30 @@
 //!
 //! Repose separates visual scroll containers from scroll state.
 //!
+//! [`ScrollArea`] is the scroll container: pick a [`ScrollDirection`], hand it a
+//! [`ScrollAreaState`] (usually from [`remember_scroll_area_state`]) and give it its content
+//! with `.child(..)`. It lowers to the `ScrollV` / `ScrollXY` view kinds, which are the wire
+//! format `layout_and_paint` understands. The older per-axis helpers ([`VerticalScrollArea`],
+//! [`HorizontalScrollArea`], [`ScrollAreaXY`]) still build those too but are deprecated.
+//!
+//! ```rust,no_run
+//! use repose_core::*;
+//! use repose_ui::scroll::*;
+//! use repose_ui::*;
+//!
+//! fn log_view(lines: &[String]) -> View {
+//!     let state = remember_scroll_area_state("log");
+//!     ScrollArea(ScrollDirection::Vertical, state, Modifier::new().fill_max_size())
+//!         .child(Column(Modifier::new()).child(lines.iter().map(Text).collect::<Vec<_>>()))
+//! }
+//! ```
+//!
 //! This file implements inertial scroll states.
 //!
 //! Velocities are expressed in px/sec and integrated with dt,
 //! so behavior is frame-rate independent.
 
+use repose_core::animation::AnimatedValue;
 use repose_core::*;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -414,8 +433,219 @@ pub fn remember_scroll_state_xy(key: impl Into<String>) -> Rc<ScrollStateXY> {
     repose_core::remember_with_key(key.into(), ScrollStateXY::new)
 }
 
-/// Scroll container with inertia, like verticalScroll.
-pub fn ScrollArea(modifier: Modifier, state: Rc<ScrollState>, content: View) -> View {
+/// Which axes a [`ScrollArea`] scrolls.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScrollDirection {
+    #[default]
+    Vertical,
+    /// Horizontal only; a plain mouse wheel (no horizontal delta) scrolls sideways.
+    Horizontal,
+    Both,
+}
+
+/// Scroll position and fling physics for a [`ScrollArea`], whichever direction it scrolls.
+///
+/// App code can read it and drive it: jump with [`scroll_to`](Self::scroll_to), or glide
+/// with [`animate_scroll_to`](Self::animate_scroll_to), which runs on the animation clock
+/// and gives way to the next scroll input.
+#[derive(Default)]
+pub struct ScrollAreaState {
+    xy: ScrollStateXY,
+    // The running `animate_scroll_to`, advanced whenever the container reads the offset.
+    anim: RefCell<Option<AnimatedValue<Vec2>>>,
+}
+
+impl ScrollAreaState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current offset (px) from the content's top-left.
+    pub fn offset(&self) -> Vec2 {
+        let (x, y) = self.xy.get();
+        Vec2 { x, y }
+    }
+
+    /// Jumps to `offset`, clamped to the scrollable range.
+    pub fn scroll_to(&self, offset: Vec2) {
+        self.anim.borrow_mut().take();
+        self.xy.set_offset_xy(offset.x, offset.y);
+    }
+
+    /// Scrolls by `delta` as if it came from input (starting a fling); returns the part
+    /// that didn't fit.
+    pub fn scroll_by(&self, delta: Vec2) -> Vec2 {
+        self.anim.borrow_mut().take();
+        self.xy.scroll_immediate(delta)
+    }
+
+    /// Animates to `offset` (clamped as it goes) with the default spec.
+    pub fn animate_scroll_to(&self, offset: Vec2) {
+        self.animate_scroll_to_with(offset, AnimationSpec::default());
+    }
+    pub fn animate_scroll_to_with(&self, offset: Vec2, spec: AnimationSpec) {
+        let mut a = AnimatedValue::new(self.offset(), spec);
+        a.set_target(offset);
+        *self.xy.animating.borrow_mut() = false;
+        *self.anim.borrow_mut() = Some(a);
+        repose_core::with_frame_nanos(|_| {});
+    }
+
+    /// Whether the offset is moving on its own: a fling or an `animate_scroll_to`.
+    pub fn is_scrolling(&self) -> bool {
+        *self.xy.animating.borrow() || self.anim.borrow().is_some()
+    }
+
+    // Advances a fling or `animate_scroll_to` to this frame.
+    fn advance(&self) {
+        let mut anim = self.anim.borrow_mut();
+        let Some(a) = anim.as_mut() else {
+            self.xy.tick();
+            return;
+        };
+        let running = a.update();
+        let to = *a.get();
+        self.xy.set_offset_xy(to.x, to.y);
+        if running {
+            repose_core::with_frame_nanos(|_| {});
+        } else {
+            *anim = None;
+        }
+    }
+
+    /// Size of the visible area (px) as of the last frame.
+    pub fn viewport(&self) -> Vec2 {
+        Vec2 {
+            x: self.xy.vp_w.get(),
+            y: self.xy.vp_h.get(),
+        }
+    }
+
+    /// Size of the content (px) as of the last frame.
+    pub fn content_size(&self) -> Vec2 {
+        Vec2 {
+            x: self.xy.c_w.get(),
+            y: self.xy.c_h.get(),
+        }
+    }
+
+    /// Largest offset on each axis.
+    pub fn max_offset(&self) -> Vec2 {
+        let (vp, c) = (self.viewport(), self.content_size());
+        Vec2 {
+            x: (c.x - vp.x).max(0.0),
+            y: (c.y - vp.y).max(0.0),
+        }
+    }
+}
+
+pub fn remember_scroll_area_state(key: impl Into<String>) -> Rc<ScrollAreaState> {
+    repose_core::remember_with_key(key.into(), ScrollAreaState::new)
+}
+
+/// Scroll container with inertia. Give it content with `.child(..)`.
+#[allow(deprecated)]
+pub fn ScrollArea(
+    direction: ScrollDirection,
+    state: Rc<ScrollAreaState>,
+    modifier: Modifier,
+) -> View {
+    let get_xy = {
+        let st = state.clone();
+        Rc::new(move || {
+            st.advance();
+            st.xy.get()
+        })
+    };
+    let set_xy = {
+        let st = state.clone();
+        Rc::new(move |x: f32, y: f32| st.scroll_to(Vec2 { x, y }))
+    };
+    let set_vw = {
+        let st = state.clone();
+        Rc::new(move |w: f32| st.xy.set_viewport(w, st.xy.vp_h.get()))
+    };
+    let set_vh = {
+        let st = state.clone();
+        Rc::new(move |h: f32| st.xy.set_viewport(st.xy.vp_w.get(), h))
+    };
+    let set_cw = {
+        let st = state.clone();
+        Rc::new(move |w: f32| st.xy.set_content(w, st.xy.c_h.get()))
+    };
+    let set_ch = {
+        let st = state.clone();
+        Rc::new(move |h: f32| st.xy.set_content(st.xy.c_w.get(), h))
+    };
+
+    let kind = match direction {
+        ScrollDirection::Vertical => {
+            let on_scroll = {
+                let st = state.clone();
+                Rc::new(move |d: Vec2| -> Vec2 {
+                    let left = st.scroll_by(Vec2 { x: 0.0, y: d.y });
+                    Vec2 { x: d.x, y: left.y }
+                })
+            };
+            ViewKind::ScrollV {
+                on_scroll: Some(on_scroll),
+                set_viewport_height: Some(set_vh),
+                set_content_height: Some(set_ch),
+                get_scroll_offset: Some(Rc::new(move || get_xy().1)),
+                set_scroll_offset: Some(Rc::new(move |y| {
+                    let (x, _) = state.xy.get();
+                    set_xy(x, y)
+                })),
+            }
+        }
+        ScrollDirection::Horizontal => {
+            let on_scroll = {
+                let st = state.clone();
+                Rc::new(move |d: Vec2| -> Vec2 {
+                    // Wheel-only mice report dy; treat it as horizontal and consume it.
+                    let wheel = d.x.abs() <= 0.001;
+                    let dx = if wheel { d.y } else { d.x };
+                    let left = st.scroll_by(Vec2 { x: dx, y: 0.0 });
+                    Vec2 {
+                        x: left.x,
+                        y: if wheel { 0.0 } else { d.y },
+                    }
+                })
+            };
+            ViewKind::ScrollXY {
+                on_scroll: Some(on_scroll),
+                set_viewport_width: Some(set_vw),
+                set_viewport_height: None,
+                set_content_width: Some(set_cw),
+                set_content_height: None,
+                get_scroll_offset_xy: Some(get_xy),
+                set_scroll_offset_xy: Some(set_xy),
+            }
+        }
+        ScrollDirection::Both => {
+            let on_scroll = {
+                let st = state.clone();
+                Rc::new(move |d: Vec2| -> Vec2 { st.scroll_by(d) })
+            };
+            ViewKind::ScrollXY {
+                on_scroll: Some(on_scroll),
+                set_viewport_width: Some(set_vw),
+                set_viewport_height: Some(set_vh),
+                set_content_width: Some(set_cw),
+                set_content_height: Some(set_ch),
+                get_scroll_offset_xy: Some(get_xy),
+                set_scroll_offset_xy: Some(set_xy),
+            }
+        }
+    };
+    View::new(0, kind).modifier(modifier)
+}
+
+/// Scroll container with inertia, like verticalScroll. This was `ScrollArea` before that
+/// name went to the direction-aware container.
+#[deprecated = "use ScrollArea(ScrollDirection::Vertical, state, modifier).child(content)"]
+#[allow(deprecated)]
+pub fn VerticalScrollArea(modifier: Modifier, state: Rc<ScrollState>, content: View) -> View {
     let st_clone = state.clone();
     let on_scroll = {
         Rc::new(move |d: Vec2| -> Vec2 {
@@ -458,6 +688,8 @@ pub fn ScrollArea(modifier: Modifier, state: Rc<ScrollState>, content: View) ->
     .with_children(vec![content])
 }
 
+#[deprecated = "use ScrollArea(ScrollDirection::Horizontal, state, modifier).child(content)"]
+#[allow(deprecated)]
 pub fn HorizontalScrollArea(
     modifier: Modifier,
     state: Rc<HorizontalScrollState>,
@@ -511,6 +743,8 @@ pub fn HorizontalScrollArea(
     .with_children(vec![content])
 }
 
+#[deprecated = "use ScrollArea(ScrollDirection::Both, state, modifier).child(content)"]
+#[allow(deprecated)]
 pub fn ScrollAreaXY(modifier: Modifier, state: Rc<ScrollStateXY>, content: View) -> View {
     let on_scroll = {
         let st = state.clone();
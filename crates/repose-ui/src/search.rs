@@ -0,0 +1,382 @@
+//! # Search bar
+//!
+//! [`SearchBar`] is a `TextField` with a search glyph and a clear button, above a results
+//! popup anchored under it. [`SearchBarState`] holds the query, a debounced copy of it to
+//! run searches on, and the recently submitted queries; remember it with
+//! [`remember_search_bar_state`], which also keeps the history in the saved-value registry
+//! (`repose_core::saved`) under the state's key, so it outlives the view and, once the app
+//! persists the registry, the process.
+//!
+//! Typing opens the popup. Escape or Back closes it and puts back the text from before it
+//! opened; Enter records the query in the history. With an empty query the popup lists the
+//! history. The popup is a window layer (see `repose_core::show_layer`), so scroll
+//! containers and clips around the bar don't cut it off.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use repose_core::*;
+use web_time::{Duration, Instant};
+
+use crate::textfield::{TextFieldExt, replace_text};
+use crate::{Box, Column, Row, Text, TextField, TextStyle, ViewExt};
+
+/// How long the query has to stay unchanged before [`SearchBarState::debounced_query`]
+/// picks it up.
+pub const SEARCH_DEBOUNCE: Duration = Duration::from_millis(300);
+/// Submitted queries kept in the history, most recent first.
+pub const SEARCH_HISTORY_LEN: usize = 10;
+
+const BAR_H_DP: f32 = 48.0;
+const POPUP_GAP_DP: f32 = 4.0;
+/// Above app layers that don't pick a `z`, like dropdowns.
+const POPUP_Z: i32 = 100;
+
+// Salt for the text field state keys, so they don't collide with view ids.
+const FIELD_SALT: u64 = 0x7365_6172_6368_0000;
+static NEXT_FIELD: AtomicU64 = AtomicU64::new(1);
+
+pub struct SearchBarState {
+    field_key: u64,
+    layer_key: String,
+    // Saved-value key the history is kept under.
+    history_key: Option<String>,
+    // Where the bar was laid out, in window px.
+    anchor: Cell<Rect>,
+    debounce: Duration,
+    query: RefCell<String>,
+    settled: RefCell<String>,
+    edited_at: Cell<Option<Instant>>,
+    open: Cell<bool>,
+    // The query when the popup opened; Escape goes back to it.
+    before_open: RefCell<String>,
+    history: RefCell<Vec<String>>,
+}
+
+impl Default for SearchBarState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SearchBarState {
+    pub fn new() -> Self {
+        let n = NEXT_FIELD.fetch_add(1, Ordering::Relaxed);
+        Self {
+            field_key: FIELD_SALT ^ n,
+            layer_key: format!("search-{n}"),
+            history_key: None,
+            anchor: Cell::new(Rect::default()),
+            debounce: SEARCH_DEBOUNCE,
+            query: RefCell::new(String::new()),
+            settled: RefCell::new(String::new()),
+            edited_at: Cell::new(None),
+            open: Cell::new(false),
+            before_open: RefCell::new(String::new()),
+            history: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn with_debounce(mut self, d: Duration) -> Self {
+        self.debounce = d;
+        self
+    }
+
+    /// Keeps the history in the saved-value registry under `key`, starting from what is
+    /// already stored there.
+    pub fn with_saved_history(mut self, key: impl Into<String>) -> Self {
+        let key = key.into();
+        if let Some(saved) = saved_value(&key) {
+            let h = saved.lines().map(str::to_string).collect();
+            *self.history.get_mut() = h;
+        }
+        self.history_key = Some(key);
+        self
+    }
+
+    /// The text as typed.
+    pub fn query(&self) -> String {
+        self.query.borrow().clone()
+    }
+
+    /// The query once typing has paused for the debounce interval; what to search for.
+    /// Until then it asks for frames, so the results show up without further input.
+    pub fn debounced_query(&self) -> String {
+        if let Some(at) = self.edited_at.get() {
+            if Instant::now().saturating_duration_since(at) >= self.debounce {
+                self.edited_at.set(None);
+                *self.settled.borrow_mut() = self.query();
+            } else {
+                with_frame_nanos(|_| {});
+            }
+        }
+        self.settled.borrow().clone()
+    }
+
+    /// Replaces the query (and the field's text) without going through the debounce.
+    pub fn set_query(&self, q: impl Into<String>) {
+        let q = q.into();
+        replace_text(self.field_key, q.clone());
+        *self.settled.borrow_mut() = q.clone();
+        *self.query.borrow_mut() = q;
+        self.edited_at.set(None);
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open.get()
+    }
+
+    /// Recent submitted queries, most recent first.
+    pub fn history(&self) -> Vec<String> {
+        self.history.borrow().clone()
+    }
+
+    pub fn set_history(&self, history: Vec<String>) {
+        let mut h = history;
+        h.truncate(SEARCH_HISTORY_LEN);
+        *self.history.borrow_mut() = h;
+        self.save_history();
+    }
+
+    fn save_history(&self) {
+        if let Some(key) = &self.history_key {
+            save_value(key.clone(), self.history.borrow().join("\n"));
+        }
+    }
+
+    /// Takes in a keystroke's worth of text from the field.
+    pub fn edit(&self, text: String) {
+        if !self.open.get() {
+            *self.before_open.borrow_mut() = self.query();
+            self.open.set(true);
+        }
+        *self.query.borrow_mut() = text;
+        self.edited_at.set(Some(Instant::now()));
+    }
+
+    /// Runs the current query right away, records it in the history and closes the popup.
+    pub fn submit(&self) {
+        let q = self.query();
+        *self.settled.borrow_mut() = q.clone();
+        self.edited_at.set(None);
+        self.open.set(false);
+
+        // One line per entry in the saved history.
+        let q = q.replace('\n', " ");
+        let q = q.trim();
+        if !q.is_empty() {
+            {
+                let mut h = self.history.borrow_mut();
+                h.retain(|x| x != q);
+                h.insert(0, q.to_string());
+                h.truncate(SEARCH_HISTORY_LEN);
+            }
+            self.save_history();
+        }
+    }
+
+    /// Closes the popup and restores the query from before it opened. Returns whether there
+    /// was a popup to close.
+    pub fn cancel(&self) -> bool {
+        if !self.open.replace(false) {
+            return false;
+        }
+        let before = self.before_open.borrow().clone();
+        self.set_query(before);
+        true
+    }
+
+    /// Empties the query, leaving the popup open on the history.
+    pub fn clear(&self) {
+        if !self.open.get() {
+            *self.before_open.borrow_mut() = self.query();
+            self.open.set(true);
+        }
+        self.set_query("");
+    }
+}
+
+/// A [`SearchBarState`] remembered under `key`, with its history saved under
+/// `search.history.<key>`.
+pub fn remember_search_bar_state(key: impl Into<String>) -> Rc<SearchBarState> {
+    let key = key.into();
+    let history_key = format!("search.history.{key}");
+    repose_core::remember_with_key(key, || {
+        SearchBarState::new().with_saved_history(history_key)
+    })
+}
+
+/// A search field; while its popup is open, `results` builds the popup's content from the
+/// debounced query. Clicking a recent query submits it.
+pub fn SearchBar(
+    state: Rc<SearchBarState>,
+    hint: impl Into<String>,
+    results: impl FnOnce(&str) -> View,
+) -> View {
+    let th = locals::theme();
+    let query = state.query();
+
+    let field = {
+        let on_key = state.clone();
+        let on_change = state.clone();
+        let on_submit = state.clone();
+        TextField(
+            hint,
+            Modifier::new().flex_grow(1.0).on_key_event(move |e| {
+                e.is_pressed() && e.key == input::Key::Escape && on_key.cancel()
+            }),
+            Some(move |t: String| on_change.edit(t)),
+            Some(move |_: String| on_submit.submit()),
+        )
+        .state_key(state.field_key)
+    };
+
+    let clear = if query.is_empty() {
+        Box(Modifier::new())
+    } else {
+        let st = state.clone();
+        Box(Modifier::new()
+            .padding(8.0)
+            .clickable()
+            .on_pointer_down(move |_| st.clear())
+            .content_description("Clear search"))
+        .child(Text("✕").size(14.0).color(th.on_surface))
+    };
+
+    let on_placed = {
+        let st = state.clone();
+        move |r: Rect| st.anchor.set(r)
+    };
+    let bar = Row(Modifier::new()
+        .fill_max_width()
+        .height(BAR_H_DP)
+        .on_placed(on_placed)
+        .background(th.surface)
+        .border(1.0, th.outline, BAR_H_DP / 2.0)
+        .clip_rounded(BAR_H_DP / 2.0)
+        .padding_values(PaddingValues {
+            left: 12.0,
+            right: 4.0,
+            ..Default::default()
+        })
+        .align_items(AlignItems::Center))
+    .child((Text("⌕").size(20.0).color(th.on_surface), field, clear));
+
+    let content = if !state.is_open() {
+        None
+    } else if query.is_empty() {
+        let recent = state.history();
+        (!recent.is_empty()).then(|| {
+            Column(Modifier::new().fill_max_width()).with_children(
+                recent
+                    .into_iter()
+                    .map(|q| {
+                        let st = state.clone();
+                        let label = q.clone();
+                        Box(Modifier::new()
+                            .fill_max_width()
+                            .padding(8.0)
+                            .clickable()
+                            .on_pointer_down(move |_| {
+                                st.set_query(q.clone());
+                                st.submit();
+                            }))
+                        .child(Text(label).color(th.on_surface).single_line())
+                    })
+                    .collect(),
+            )
+        })
+    } else {
+        Some(results(&state.debounced_query()))
+    };
+
+    if let Some(c) = content {
+        let px_per_dp = locals::dp_to_px(1.0);
+        let anchor = state.anchor.get();
+        let popup = Box(Modifier::new()
+            .absolute()
+            .offset_left(anchor.x / px_per_dp)
+            .offset_top((anchor.y + anchor.h) / px_per_dp + POPUP_GAP_DP)
+            .width(anchor.w / px_per_dp)
+            .background(th.surface)
+            .border(1.0, th.outline, 8.0)
+            .clip_rounded(8.0)
+            .padding(4.0))
+        .child(c);
+        // Not modal, so the field keeps taking clicks and keys; Back still closes it.
+        let st = state.clone();
+        show_layer(
+            state.layer_key.clone(),
+            LayerOptions::default().z(POPUP_Z).on_dismiss(move || {
+                st.cancel();
+            }),
+            popup,
+        );
+    }
+
+    Box(Modifier::new().fill_max_width()).child(bar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Column, Interactions, TextFieldState, layout_and_paint};
+
+    #[test]
+    fn escape_restores_the_query_from_before_the_popup_opened() {
+        let st = Rc::new(SearchBarState::new().with_debounce(Duration::ZERO));
+        st.edit("ru".into());
+        assert!(st.is_open());
+        assert_eq!(st.debounced_query(), "ru");
+        st.submit();
+        assert!(!st.is_open());
+        assert_eq!(st.history(), vec!["ru".to_string()]);
+
+        st.edit("rust".into());
+        let fields = RetainedStateStore::new();
+        let field = fields.insert(st.field_key, TextFieldState::new());
+        field.borrow_mut().insert_text("rust");
+
+        assert!(st.cancel());
+        assert!(!st.cancel());
+        assert_eq!(st.query(), "ru");
+        let view = SearchBar(st.clone(), "Search", |q| Text(q));
+        layout_and_paint(&view, (300, 200), &fields, &Interactions::default(), None);
+        assert_eq!(field.borrow().text, "ru");
+    }
+
+    #[test]
+    fn history_is_saved_and_back_closes_the_popup_layer() {
+        let first = SearchBarState::new().with_saved_history("search.test");
+        first.edit("taffy".into());
+        first.submit();
+        let st = Rc::new(SearchBarState::new().with_saved_history("search.test"));
+        assert_eq!(st.history(), vec!["taffy".to_string()]);
+
+        st.edit("ta".into());
+        let mut sched = Scheduler::new();
+        let view = {
+            let st = st.clone();
+            move |_: &mut Scheduler| {
+                Column(Modifier::new()).child(SearchBar(st.clone(), "Search", |q| Text(q)))
+            }
+        };
+        sched.repose(view, |v, size| {
+            layout_and_paint(
+                v,
+                size,
+                &RetainedStateStore::new(),
+                &Interactions::default(),
+                None,
+            )
+        });
+        assert_eq!(sched.layers.layers().len(), 1);
+        // Still debouncing, so another frame is on its way.
+        assert!(frame_requested());
+
+        assert!(sched.layers.dismiss_modal());
+        assert!(!st.is_open());
+        assert_eq!(st.query(), "");
+    }
+}
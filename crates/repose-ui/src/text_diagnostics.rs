@@ -0,0 +1,258 @@
+//! # Diagnostics underlines and suggestions menu
+//!
+//! A `TextField` with a `TextDiagnostics` provider syncs it every frame it's painted (see
+//! `FieldDiagnostics::sync`) and draws a squiggle under each issue. Right-clicking an issue
+//! opens a menu with its message and suggestions, shown in a window layer (see
+//! `repose_core::show_layer`); picking one replaces the issue's text and fires the field's
+//! `on_change`.
+//!
+//! Results published from another thread show up on the field's next frame. Idle runners
+//! keep repainting a focused field for its caret blink, so they don't wait long.
+
+use std::cell::RefCell;
+use std::ops::Range;
+use std::rc::Rc;
+
+use repose_core::{
+    Color, HitRegion, JustifyContent, LayerOptions, Modifier, PaddingValues, Scene, SceneNode,
+    Vec2, layer_root_key, locals, show_layer,
+};
+
+use crate::textfield::{TextFieldState, measure_text};
+use crate::{Box, Column, Text, TextStyle, ViewExt};
+
+const WAVE_DP: f32 = 4.0;
+const AMPLITUDE_DP: f32 = 1.0;
+const MENU_FONT_DP: f32 = 14.0;
+const ITEM_H_DP: f32 = 32.0;
+const MENU_PAD_X_DP: f32 = 12.0;
+const MENU_MIN_W_DP: f32 = 120.0;
+const MAX_ITEMS: usize = 64;
+
+const LAYER_KEY: &str = "text_diagnostics.menu";
+/// Above popups and dialogs, below tooltips.
+const MENU_Z: i32 = 900;
+
+struct Menu {
+    field_id: u64,
+    range: Range<usize>,
+    at: Vec2,
+}
+
+struct Offer {
+    field_id: u64,
+    state: Rc<RefCell<TextFieldState>>,
+    on_change: Option<Rc<dyn Fn(String)>>,
+}
+
+thread_local! {
+    static MENU: RefCell<Option<Menu>> = const { RefCell::new(None) };
+    static OFFER: RefCell<Option<Offer>> = const { RefCell::new(None) };
+    /// Hit regions the menu layer's pass painted.
+    static ITEMS: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Draws a zigzag underline from `x0` to `x1` (px) centred on `y`, one line segment per
+/// half wave.
+pub(crate) fn push_squiggle(scene: &mut Scene, x0: f32, x1: f32, y: f32, color: Color) {
+    let px = locals::dp_to_px;
+    let (half, amp) = (px(WAVE_DP) * 0.5, px(AMPLITUDE_DP));
+    let width = px(1.0).max(1.0);
+    let mut from = Vec2 { x: x0, y: y - amp };
+    let mut down = true;
+    while from.x < x1 {
+        let x = (from.x + half).min(x1);
+        let dy = 2.0 * amp * (x - from.x) / half;
+        let to = Vec2 {
+            x,
+            y: if down { from.y + dy } else { from.y - dy },
+        };
+        scene.nodes.push(SceneNode::Line {
+            from,
+            to,
+            width,
+            color,
+        });
+        from = to;
+        down = !down;
+    }
+}
+
+/// Opens the suggestions menu for the issue under byte `idx` of the field, anchored at `at`
+/// (px). Returns `false` if there's no issue there.
+pub fn open_menu(field_id: u64, state: &TextFieldState, idx: usize, at: Vec2) -> bool {
+    let Some(issue) = state.diagnostics.issue_at(idx) else {
+        return false;
+    };
+    let range = issue.range.clone();
+    MENU.with(|m| {
+        *m.borrow_mut() = Some(Menu {
+            field_id,
+            range,
+            at,
+        })
+    });
+    true
+}
+
+pub fn dismiss_menu() {
+    MENU.with(|m| m.borrow_mut().take());
+}
+
+pub fn menu_open() -> bool {
+    MENU.with(|m| m.borrow().is_some())
+}
+
+/// Whether `hit_id` is part of the open menu; runners don't dismiss the menu for those.
+pub fn is_menu_item(hit_id: u64) -> bool {
+    menu_open() && ITEMS.with(|i| i.borrow().contains(&hit_id))
+}
+
+pub(crate) fn begin_pass() {
+    OFFER.with(|o| o.borrow_mut().take());
+}
+
+/// Called by every painted field; only the one the menu belongs to is kept.
+pub(crate) fn offer(
+    field_id: u64,
+    state: &Rc<RefCell<TextFieldState>>,
+    on_change: &Option<Rc<dyn Fn(String)>>,
+) {
+    if MENU.with(|m| m.borrow().as_ref().is_some_and(|m| m.field_id == field_id)) {
+        OFFER.with(|o| {
+            *o.borrow_mut() = Some(Offer {
+                field_id,
+                state: state.clone(),
+                on_change: on_change.clone(),
+            })
+        });
+    }
+}
+
+/// Shows the open menu in its layer. A menu whose issue went away closes. On the pass that
+/// lays out that layer (`root_key`), notes the hit regions it painted instead.
+pub(crate) fn paint_menu(root_key: Option<u64>, hits: &[HitRegion], window_px: (f32, f32)) {
+    if root_key == Some(layer_root_key(LAYER_KEY)) {
+        ITEMS.with(|i| *i.borrow_mut() = hits.iter().map(|h| h.id).collect());
+        return;
+    }
+    let Some(offer) = OFFER.with(|o| o.borrow_mut().take()) else {
+        return;
+    };
+    let Some((range, at)) = MENU.with(|m| {
+        m.borrow()
+            .as_ref()
+            .filter(|m| m.field_id == offer.field_id)
+            .map(|m| (m.range.clone(), m.at))
+    }) else {
+        return;
+    };
+    let Some(issue) = offer
+        .state
+        .borrow()
+        .diagnostics
+        .issues
+        .iter()
+        .find(|i| i.range == range)
+        .cloned()
+    else {
+        dismiss_menu();
+        return;
+    };
+
+    let px = locals::dp_to_px;
+    let th = locals::theme();
+    let font_px = px(MENU_FONT_DP) * locals::text_scale().0;
+    let (item_h, pad) = (px(ITEM_H_DP), px(MENU_PAD_X_DP));
+    let text_w = |s: &str| {
+        measure_text(s, font_px)
+            .positions
+            .last()
+            .copied()
+            .unwrap_or(0.0)
+    };
+
+    let mut rows: Vec<(Option<String>, String)> = vec![(None, issue.message.clone())];
+    if issue.suggestions.is_empty() {
+        rows.push((None, "No suggestions".to_string()));
+    }
+    rows.extend(
+        issue
+            .suggestions
+            .iter()
+            .map(|s| (Some(s.clone()), s.clone())),
+    );
+    rows.truncate(MAX_ITEMS);
+
+    let w = rows
+        .iter()
+        .map(|(_, label)| text_w(label) + 2.0 * pad)
+        .fold(px(MENU_MIN_W_DP), f32::max)
+        .min(window_px.0);
+    let h = rows.len() as f32 * item_h;
+    let x = at.x.min(window_px.0 - w).max(0.0);
+    let y = if at.y + h <= window_px.1 {
+        at.y
+    } else {
+        (at.y - h).max(0.0)
+    };
+
+    let items = rows
+        .into_iter()
+        .map(|(suggestion, label)| {
+            let color = if suggestion.is_some() {
+                th.on_surface
+            } else {
+                issue.severity.color()
+            };
+            let mut m = Modifier::new()
+                .fill_max_width()
+                .height(ITEM_H_DP)
+                .justify_content(JustifyContent::Center)
+                .padding_values(PaddingValues {
+                    left: MENU_PAD_X_DP,
+                    right: MENU_PAD_X_DP,
+                    top: 0.0,
+                    bottom: 0.0,
+                });
+            if let Some(replacement) = suggestion {
+                let state = offer.state.clone();
+                let on_change = offer.on_change.clone();
+                let range = range.clone();
+                m = m.clickable().on_pointer_down(move |_| {
+                    let text = {
+                        let mut st = state.borrow_mut();
+                        st.replace_range(range.clone(), &replacement);
+                        st.text.clone()
+                    };
+                    dismiss_menu();
+                    if let Some(cb) = &on_change {
+                        cb(text);
+                    }
+                });
+            }
+            Column(m).child(Text(label).size(MENU_FONT_DP).single_line().color(color))
+        })
+        .collect();
+
+    let dp = px(1.0);
+    let menu = Column(
+        Modifier::new()
+            .absolute()
+            .offset_left(x / dp)
+            .offset_top(y / dp)
+            .size(w / dp, h / dp)
+            .background(th.surface)
+            .border(1.0, th.outline, 6.0)
+            .clip_rounded(6.0)
+            // Swallow wheel and clicks on the menu's non-item rows so they don't reach the
+            // UI below.
+            .on_scroll(|_| Vec2::default()),
+    )
+    .with_children(items);
+    show_layer(
+        LAYER_KEY,
+        LayerOptions::default().z(MENU_Z).on_dismiss(dismiss_menu),
+        Box(Modifier::new().fill_max_size()).child(menu),
+    );
+}
@@ -0,0 +1,322 @@
+//! # Touch selection overlay
+//!
+//! When the focused `TextField`'s [`TouchSelection`] is active, its paint offers the field
+//! here and `layout_and_paint` shows, in a window layer above the whole frame (see
+//! `repose_core::show_layer`):
+//!
+//! - draggable handles under the selection ends (one under the caret when collapsed),
+//! - a magnifier loupe over the text while a handle is dragged,
+//! - a floating Cut/Copy/Paste/Select-All toolbar above the selection.
+//!
+//! Handle drags edit the `TextFieldState` directly. Toolbar taps need the clipboard, which
+//! only the platform runner has, so they're queued; runners drain them with
+//! [`take_edit_actions`] after dispatching a click and apply them with
+//! `TextFieldState::apply_edit`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use repose_core::input::PointerEvent;
+use repose_core::{
+    Color, HitRegion, LayerOptions, Modifier, PaddingValues, Rect, Vec2, View, layer_root_key,
+    locals, show_layer,
+};
+
+use crate::textfield::{
+    EditAction, SelectionHandle, TextFieldState, byte_to_char_index, measure_text,
+};
+use crate::{Box, Button, Row, Text, TextStyle, ViewExt};
+
+const HANDLE_DP: f32 = 20.0;
+/// Handles are small; their hit regions get a full touch target.
+const HANDLE_TARGET_DP: f32 = 44.0;
+const LOUPE_W_DP: f32 = 96.0;
+const LOUPE_ZOOM: f32 = 1.25;
+const TOOLBAR_FONT_DP: f32 = 14.0;
+const TOOLBAR_PAD_X_DP: f32 = 12.0;
+const TOOLBAR_H_DP: f32 = 40.0;
+const GAP_DP: f32 = 8.0;
+
+const LAYER_KEY: &str = "text_selection";
+/// Above popups and dialogs, below tooltips.
+const SELECTION_Z: i32 = 900;
+
+struct Offer {
+    field_id: u64,
+    state: Rc<RefCell<TextFieldState>>,
+    field: Rect,
+    inner: Rect,
+    font_px: f32,
+}
+
+thread_local! {
+    static OFFER: RefCell<Option<Offer>> = const { RefCell::new(None) };
+    static ACTIONS: RefCell<Vec<(u64, EditAction)>> = const { RefCell::new(Vec::new()) };
+    /// The field the overlay layer was last shown for, and the hit regions that layer's
+    /// pass painted.
+    static SHOWN: RefCell<(u64, Vec<u64>)> = const { RefCell::new((0, Vec::new())) };
+}
+
+pub(crate) fn begin_pass() {
+    OFFER.with(|o| o.borrow_mut().take());
+}
+
+/// Called by the focused TextField whose touch selection UI is showing.
+pub(crate) fn offer(
+    field_id: u64,
+    state: Rc<RefCell<TextFieldState>>,
+    field: Rect,
+    inner: Rect,
+    font_px: f32,
+) {
+    OFFER.with(|o| {
+        *o.borrow_mut() = Some(Offer {
+            field_id,
+            state,
+            field,
+            inner,
+            font_px,
+        })
+    });
+}
+
+/// Toolbar actions tapped since the last call, as `(field hit id, action)`.
+pub fn take_edit_actions() -> Vec<(u64, EditAction)> {
+    ACTIONS.with(|a| std::mem::take(&mut *a.borrow_mut()))
+}
+
+/// Whether `hit_id` is one of the handles or toolbar buttons drawn for `field_id`. Runners use
+/// this to keep the selection UI up while it's being touched.
+pub fn is_overlay_of(hit_id: u64, field_id: u64) -> bool {
+    SHOWN.with(|s| {
+        let s = s.borrow();
+        s.0 == field_id && s.1.contains(&hit_id)
+    })
+}
+
+/// Shows this pass's handles, magnifier and toolbar in the selection layer. On the pass that
+/// lays out that layer (`root_key`), notes the hit regions it painted instead.
+pub(crate) fn paint(root_key: Option<u64>, hits: &[HitRegion], window_px: (f32, f32)) {
+    if root_key == Some(layer_root_key(LAYER_KEY)) {
+        SHOWN.with(|s| s.borrow_mut().1 = hits.iter().map(|h| h.id).collect());
+        return;
+    }
+    let Some(offer) = OFFER.with(|o| o.borrow_mut().take()) else {
+        return;
+    };
+    let st = offer.state.borrow();
+    if !st.touch.handles {
+        return;
+    }
+    let shown = st.display();
+    let m = measure_text(&shown.text, offer.font_px);
+    // Caret x (window px) for a byte index.
+    let x_of = |byte: usize| {
+        let i = byte_to_char_index(&m, shown.mapping.to_transformed(byte));
+        offer.inner.x + m.positions[i.min(m.positions.len() - 1)] - st.scroll_offset
+    };
+
+    let mut overlay = Vec::new();
+    let collapsed = st.selection.start == st.selection.end;
+    let mut handles = vec![(SelectionHandle::End, st.selection.end)];
+    if !collapsed {
+        handles.insert(0, (SelectionHandle::Start, st.selection.start));
+    }
+    for (handle, byte) in handles {
+        let cx = x_of(byte);
+        if cx < offer.inner.x - 0.5 || cx > offer.inner.x + offer.inner.w + 0.5 {
+            continue; // scrolled out of view
+        }
+        overlay.push(handle_view(&offer, handle, cx));
+    }
+
+    if let Some(byte) = st.touch.dragging {
+        overlay.push(magnifier(&offer, &st, x_of(byte), window_px));
+    } else if st.touch.toolbar {
+        let anchor_x = (x_of(st.selection.start) + x_of(st.selection.end)) * 0.5;
+        let mut actions = Vec::new();
+        if !collapsed {
+            actions.extend([(EditAction::Cut, "Cut"), (EditAction::Copy, "Copy")]);
+        }
+        actions.push((EditAction::Paste, "Paste"));
+        if st.selection != (0..st.text.len()) {
+            actions.push((EditAction::SelectAll, "Select all"));
+        }
+        overlay.push(toolbar(&offer, &actions, anchor_x, window_px));
+    }
+
+    SHOWN.with(|s| s.borrow_mut().0 = offer.field_id);
+    show_layer(
+        LAYER_KEY,
+        LayerOptions::default().z(SELECTION_Z),
+        Box(Modifier::new().fill_max_size()).with_children(overlay),
+    );
+}
+
+/// `Modifier::absolute()` placed at `rect` (window px).
+fn placed(rect: Rect) -> Modifier {
+    let dp = locals::dp_to_px(1.0);
+    Modifier::new()
+        .absolute()
+        .offset_left(rect.x / dp)
+        .offset_top(rect.y / dp)
+        .size(rect.w / dp, rect.h / dp)
+}
+
+/// A stem from the text bottom into a round knob, in a full-size touch target.
+fn handle_view(offer: &Offer, handle: SelectionHandle, cx: f32) -> View {
+    let px = locals::dp_to_px;
+    let th = locals::theme();
+    let (d, t) = (HANDLE_DP, HANDLE_TARGET_DP);
+    let top = offer.inner.y + offer.inner.h;
+    let knob_cy = top + px(d * 0.75);
+    let target = Rect {
+        x: cx - px(t) * 0.5,
+        y: knob_cy - px(t) * 0.5,
+        w: px(t),
+        h: px(t),
+    };
+
+    let (inner, font_px) = (offer.inner, offer.font_px);
+    let down_st = offer.state.clone();
+    let move_st = offer.state.clone();
+    let up_st = offer.state.clone();
+    Box(placed(target)
+        .on_pointer_down(move |pe: PointerEvent| {
+            down_st
+                .borrow_mut()
+                .begin_handle_drag(handle, cx - pe.position.x);
+        })
+        .on_pointer_move(move |pe: PointerEvent| {
+            let mut st = move_st.borrow_mut();
+            let x = pe.position.x + st.touch.grab_dx - inner.x + st.scroll_offset;
+            let idx = st.index_for_x(font_px, x.max(0.0));
+            st.drag_handle_to(idx);
+            let caret_x = st.x_for_index(idx, font_px);
+            st.ensure_caret_visible(caret_x, inner.w, locals::dp_to_px(2.0));
+        })
+        .on_pointer_up(move |_| up_st.borrow_mut().end_handle_drag())
+        // Swallow touch scrolling that starts on a handle.
+        .on_scroll(|_| Vec2::default()))
+    .child(Box(Modifier::new()
+        .absolute()
+        .offset_left(t * 0.5 - 1.0)
+        .offset_top(t * 0.5 - d * 0.75)
+        .size(2.0, d * 0.5)
+        .background(th.primary)))
+    .child(Box(Modifier::new()
+        .absolute()
+        .offset_left((t - d) * 0.5)
+        .offset_top((t - d) * 0.5)
+        .size(d, d)
+        .background(th.primary)
+        .clip_rounded(d * 0.5)))
+}
+
+fn magnifier(offer: &Offer, st: &TextFieldState, focus_x: f32, window_px: (f32, f32)) -> View {
+    let px = locals::dp_to_px;
+    let th = locals::theme();
+    let dp = px(1.0);
+    let zoom_px = offer.font_px * LOUPE_ZOOM;
+    let w = px(LOUPE_W_DP);
+    let h = offer.inner.h * LOUPE_ZOOM + px(8.0);
+    let x = (focus_x - w * 0.5).clamp(0.0, (window_px.0 - w).max(0.0));
+    let above = offer.field.y - px(GAP_DP) - h;
+    let y = if above >= 0.0 {
+        above
+    } else {
+        offer.field.y + offer.field.h + px(HANDLE_DP * 1.5)
+    };
+
+    // Line the magnified text up so the point under the handle sits at the loupe's centre.
+    let content_x = (focus_x - offer.inner.x + st.scroll_offset) * LOUPE_ZOOM;
+    let focus_in_loupe = focus_x - x;
+    let text = Text(st.display().text.as_str())
+        .size(zoom_px / (dp * locals::text_scale().0))
+        .single_line()
+        .overflow_visible()
+        .color(th.on_surface)
+        .modifier(
+            Modifier::new()
+                .absolute()
+                .offset_left((focus_in_loupe - content_x) / dp)
+                .offset_top((h - offer.inner.h * LOUPE_ZOOM) * 0.5 / dp)
+                .height(offer.inner.h * LOUPE_ZOOM / dp),
+        );
+    Box(placed(Rect { x, y, w, h })
+        .background(th.surface)
+        .border(1.0, th.outline, 8.0)
+        .clip_rounded(8.0))
+    .child(text)
+    .child(Box(Modifier::new()
+        .absolute()
+        .offset_left(focus_in_loupe / dp - 1.0)
+        .offset_top(4.0)
+        .size(2.0, h / dp - 8.0)
+        .background(th.primary)))
+}
+
+fn toolbar(
+    offer: &Offer,
+    actions: &[(EditAction, &str)],
+    anchor_x: f32,
+    window_px: (f32, f32),
+) -> View {
+    let px = locals::dp_to_px;
+    let th = locals::theme();
+    let font_px = px(TOOLBAR_FONT_DP) * locals::text_scale().0;
+    let pad = px(TOOLBAR_PAD_X_DP);
+    let h = px(TOOLBAR_H_DP);
+    let widths: Vec<f32> = actions
+        .iter()
+        .map(|(_, label)| {
+            measure_text(label, font_px)
+                .positions
+                .last()
+                .copied()
+                .unwrap_or(0.0)
+                + 2.0 * pad
+        })
+        .collect();
+    let w: f32 = widths.iter().sum();
+    let gap = px(GAP_DP);
+    let x = (anchor_x - w * 0.5).clamp(gap, (window_px.0 - w - gap).max(gap));
+    // Above the field, or below the handles when the field is at the top of the window.
+    let above = offer.field.y - gap - h;
+    let y = if above >= 0.0 {
+        above
+    } else {
+        offer.inner.y + offer.inner.h + px(HANDLE_DP * 1.25) + gap
+    };
+
+    let field_id = offer.field_id;
+    let buttons = actions
+        .iter()
+        .zip(&widths)
+        .map(|(&(action, label), bw)| {
+            Button(
+                Text(label)
+                    .size(TOOLBAR_FONT_DP)
+                    .single_line()
+                    .color(th.on_surface),
+                move || ACTIONS.with(|a| a.borrow_mut().push((field_id, action))),
+            )
+            .modifier(
+                Modifier::new()
+                    .size(bw / px(1.0), TOOLBAR_H_DP)
+                    .background(Color::TRANSPARENT)
+                    .padding_values(PaddingValues {
+                        left: TOOLBAR_PAD_X_DP,
+                        right: TOOLBAR_PAD_X_DP,
+                        top: 0.0,
+                        bottom: 0.0,
+                    }),
+            )
+        })
+        .collect();
+    Row(placed(Rect { x, y, w, h })
+        .background(th.surface)
+        .border(1.0, th.outline, 8.0)
+        .clip_rounded(8.0))
+    .with_children(buttons)
+}
@@ -13,6 +13,15 @@
 //!     pub drag_anchor: Option<usize>,   // selection start for drag
 //!     pub blink_start: Instant,         // caret blink timer
 //!     pub inner_width: f32,             // px, content box width
+//!     pub touch: TouchSelection,        // handles/toolbar shown by touch
+//!     pub diagnostics: FieldDiagnostics, // spellcheck/lint issues
+//!     pub multiline: bool,              // painted as a TextArea
+//!     pub scroll_offset_y: f32,         // px, top edge of visible text (TextArea)
+//!     pub inner_height: f32,            // px, content box height (TextArea)
+//!     pub affinity: CaretAffinity,      // caret side at soft wraps (TextArea)
+//!     pub history: EditHistory,         // undo/redo
+//!     pub visual_transformation: Option<Rc<dyn VisualTransformation>>, // painted form
+//!     pub constraints: InputConstraints, // input filter and max length
 //! }
 //! ```
 //!
@@ -24,6 +33,26 @@
 //!   `cancel_composition` integrate with platform IME events.
 //! - Horizontal scrolling: `scroll_offset` plus `ensure_caret_visible` keep
 //!   the caret within the visible inner rect.
+//! - Wrapped text: [`WrappedText`] maps byte indices to visual lines for text that
+//!   wraps, with [`CaretAffinity`] picking a side at soft wraps.
+//! - Touch selection: `touch_release` shows draggable handles (and, on long
+//!   press, the Cut/Copy/Paste toolbar); see [`crate::text_selection`].
+//! - Diagnostics: with a `TextDiagnostics` provider attached
+//!   (`TextFieldExt::text_diagnostics`), `diagnostics` tracks the reported issues
+//!   across edits; see [`crate::text_diagnostics`].
+//! - Undo/redo: every edit records the text it replaced in `history`; consecutive
+//!   typing (up to a space) and consecutive deletes undo as one step, as does an IME
+//!   composition once committed. See [`TextFieldState::undo`].
+//! - Input constraints: `constraints` (from `TextFieldExt::filter` and `max_length`)
+//!   is checked by `insert_text`, paste and `commit_composition`; input the filter
+//!   rejects is dropped and input past the length limit is cut short.
+//! - Visual transformations: a field given one (`TextFieldExt::visual_transformation`)
+//!   paints its text transformed, e.g. masked. Paint copies it into the state, and
+//!   `x_for_index` / `index_for_x` map between bytes of the text and painted positions.
+//! - Multiple lines: [`TextArea`] shares this state. Its paint sets `multiline`, which
+//!   tells runners to keep newlines and hand Enter, Up/Down, PageUp/PageDown and Home/End
+//!   to [`TextFieldState::multiline_key`]; `scroll_offset_y` plus
+//!   `ensure_caret_visible_y` scroll it vertically.
 //!
 //! Platform runners (`repose-platform`) keep a `HashMap<u64, Rc<RefCell<TextFieldState>>>`
 //! indexed by a stable `tf_state_key`. During layout/paint, this map is passed
@@ -33,21 +62,36 @@
 //! - Composition underline
 //! - Text (value or hint)
 //! - Caret (with blink)
+//! - Touch handles, magnifier and toolbar (focused field only)
+//! - Squiggly underlines for diagnostics
 //!
 //! And exposes `on_text_change` / `on_text_submit` callbacks via `HitRegion`
 //! so your app can react to edits.
 
 use repose_core::*;
+use std::collections::VecDeque;
 use std::ops::Range;
 use web_time::Duration;
 use web_time::Instant;
 
 use unicode_segmentation::UnicodeSegmentation;
 
+/// Half a blink cycle: how long the caret stays shown (or hidden).
+pub const CARET_BLINK_PERIOD: Duration = Duration::from_millis(500);
+
+/// How long a touch must be held on a field before release selects a word.
+pub const LONG_PRESS: Duration = Duration::from_millis(500);
+
 /// Logical font size for TextField in dp (converted to px at measure/paint time).
 pub const TF_FONT_DP: f32 = 16.0;
 /// Horizontal padding inside the TextField in dp.
 pub const TF_PADDING_X_DP: f32 = 8.0;
+/// Vertical padding inside the TextField (and TextArea) in dp.
+pub const TF_PADDING_Y_DP: f32 = 8.0;
+/// Visible lines of a TextArea that isn't given a height.
+pub const TA_DEFAULT_LINES: usize = 4;
+/// Undo steps a field keeps; older ones are dropped.
+pub const UNDO_LIMIT: usize = 100;
 
 pub struct TextMetrics {
     /// positions[i] = advance up to the i-th grapheme (len == graphemes + 1)
@@ -72,20 +116,159 @@ pub fn byte_to_char_index(m: &TextMetrics, byte: usize) -> usize {
     }
 }
 
-/// Given an x position (px), return the nearest grapheme boundary byte index.
+/// Given an x position (px), return the nearest grapheme boundary byte index: a click on
+/// the left half of a glyph lands before it, on the right half (midpoint included) after.
 pub fn index_for_x_bytes(text: &str, font_px: f32, x_px: f32) -> usize {
     let m = measure_text(text, font_px);
+    // Boundaries whose glyph to the left is at least half covered by x.
+    let i = m
+        .positions
+        .windows(2)
+        .take_while(|w| x_px >= (w[0] + w[1]) * 0.5)
+        .count();
+    m.byte_offsets.get(i).copied().unwrap_or(text.len())
+}
+
+/// Which line a caret sits on when its byte index is a soft wrap, where the end of one
+/// visual line and the start of the next are the same index.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CaretAffinity {
+    /// End of the earlier line, e.g. after End or a click past a line's last glyph.
+    Upstream,
+    /// Start of the later line; what typing and most moves produce.
+    #[default]
+    Downstream,
+}
+
+/// Visual lines of soft-wrapped (and `\n`-separated) text, for mapping between byte
+/// indices and positions: caret placement, selection highlights and pointer hit-testing.
+/// Positions are px relative to the top-left of the text, one `font_px * 1.3` row per line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WrappedText {
+    text: String,
+    font_px: f32,
+    /// Byte range of each visual line. A soft-wrapped line keeps the spaces it broke after;
+    /// a hard line ends before its `\n`.
+    pub lines: Vec<Range<usize>>,
+}
+
+impl WrappedText {
+    pub fn new(text: &str, font_px: f32, max_width: f32) -> Self {
+        let mut lines = Vec::new();
+        let mut para_start = 0;
+        for para in text.split('\n') {
+            let (wrapped, _) = repose_text::wrap_lines(para, font_px, max_width, None, true);
+            // `wrap_lines` trims what it breaks at; find each line again in the paragraph.
+            let mut start = 0;
+            for (i, line) in wrapped.iter().enumerate() {
+                let end = if i + 1 == wrapped.len() {
+                    para.len()
+                } else {
+                    let mut e = start + line.len();
+                    while let Some(c) = para[e..].chars().next().filter(|c| c.is_whitespace()) {
+                        e += c.len_utf8();
+                    }
+                    e
+                };
+                lines.push(para_start + start..para_start + end);
+                start = end;
+            }
+            para_start += para.len() + 1;
+        }
+        Self {
+            text: text.to_string(),
+            font_px,
+            lines,
+        }
+    }
+
+    pub fn line_height(&self) -> f32 {
+        self.font_px * 1.3
+    }
+
+    /// Whether line `i` ends in a soft wrap (rather than `\n` or the end of the text).
+    fn wraps_after(&self, i: usize) -> bool {
+        self.lines
+            .get(i + 1)
+            .is_some_and(|next| next.start == self.lines[i].end)
+    }
+
+    /// The visual line holding the caret at `byte`.
+    pub fn line_of(&self, byte: usize, affinity: CaretAffinity) -> usize {
+        let i = self
+            .lines
+            .partition_point(|l| l.start <= byte)
+            .saturating_sub(1);
+        if affinity == CaretAffinity::Upstream
+            && i > 0
+            && byte == self.lines[i].start
+            && self.wraps_after(i - 1)
+        {
+            i - 1
+        } else {
+            i
+        }
+    }
 
-    let mut best_i = 0usize;
-    let mut best_d = f32::INFINITY;
-    for i in 0..m.positions.len() {
-        let d = (m.positions[i] - x_px).abs();
-        if d < best_d {
-            best_d = d;
-            best_i = i;
+    /// x of `byte` within line `i`, which must contain it.
+    fn x_in_line(&self, i: usize, byte: usize) -> f32 {
+        let r = &self.lines[i];
+        let m = measure_text(&self.text[r.clone()], self.font_px);
+        let idx = byte_to_char_index(&m, byte.clamp(r.start, r.end) - r.start);
+        m.positions[idx.min(m.positions.len() - 1)]
+    }
+
+    /// Top of the caret at `byte`; it is `line_height` tall.
+    pub fn caret_position(&self, byte: usize, affinity: CaretAffinity) -> Vec2 {
+        let i = self.line_of(byte, affinity);
+        Vec2 {
+            x: self.x_in_line(i, byte),
+            y: i as f32 * self.line_height(),
         }
     }
-    m.byte_offsets[best_i]
+
+    /// One highlight rect per visual line `selection` touches. Lines the selection runs past
+    /// the end of through a `\n` get a little extra width so the break reads as selected.
+    pub fn selection_rects(&self, selection: Range<usize>) -> Vec<Rect> {
+        let lh = self.line_height();
+        let mut out = Vec::new();
+        if selection.start >= selection.end {
+            return out;
+        }
+        for (i, r) in self.lines.iter().enumerate() {
+            if r.end < selection.start || r.start >= selection.end {
+                continue;
+            }
+            let x0 = self.x_in_line(i, selection.start.max(r.start));
+            let mut x1 = self.x_in_line(i, selection.end.min(r.end));
+            if selection.end > r.end && !self.wraps_after(i) {
+                x1 += self.font_px * 0.25;
+            }
+            if x1 > x0 {
+                out.push(Rect {
+                    x: x0,
+                    y: i as f32 * lh,
+                    w: x1 - x0,
+                    h: lh,
+                });
+            }
+        }
+        out
+    }
+
+    /// The caret position nearest `(x, y)`. Points past the end of a wrapped line put the
+    /// caret at that line's end (upstream) instead of the start of the next one.
+    pub fn index_for_point(&self, x: f32, y: f32) -> (usize, CaretAffinity) {
+        let i = ((y / self.line_height()).floor().max(0.0) as usize).min(self.lines.len() - 1);
+        let r = &self.lines[i];
+        let byte = r.start + index_for_x_bytes(&self.text[r.clone()], self.font_px, x.max(0.0));
+        let affinity = if byte == r.end && self.wraps_after(i) {
+            CaretAffinity::Upstream
+        } else {
+            CaretAffinity::Downstream
+        };
+        (byte, affinity)
+    }
 }
 
 /// find prev/next grapheme boundaries around a byte index
@@ -109,6 +292,224 @@ fn next_grapheme_boundary(text: &str, byte: usize) -> usize {
     text.len()
 }
 
+/// Which end of the selection a touch handle controls. A collapsed selection shows a
+/// single `End` handle under the caret.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectionHandle {
+    Start,
+    End,
+}
+
+/// Actions offered by the touch selection toolbar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditAction {
+    Cut,
+    Copy,
+    Paste,
+    SelectAll,
+}
+
+/// Touch selection UI for one field. Set by touch gestures, cleared by typing.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TouchSelection {
+    /// Selection handles are shown.
+    pub handles: bool,
+    /// The Cut/Copy/Paste/Select-All toolbar is shown.
+    pub toolbar: bool,
+    /// Byte index under the handle being dragged; the magnifier follows it.
+    pub dragging: Option<usize>,
+    /// Handle centre minus finger x at grab time (px), so the handle doesn't jump.
+    pub grab_dx: f32,
+}
+
+/// What input may put into a field; see [`TextFieldExt::filter`] and
+/// [`TextFieldExt::max_length`]. Text set through [`replace_text`] isn't checked.
+#[derive(Clone, Default)]
+pub struct InputConstraints {
+    /// Accepts or rejects the whole text an input would produce.
+    pub filter: Option<TextFilter>,
+    /// Most characters (`char`s) the text may have; longer input is cut to fit.
+    pub max_length: Option<usize>,
+}
+
+impl std::fmt::Debug for InputConstraints {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InputConstraints")
+            .field("filter", &self.filter.as_ref().map(|_| "<filter>"))
+            .field("max_length", &self.max_length)
+            .finish()
+    }
+}
+
+/// A field's diagnostics: the issues for its current text and the check in flight.
+#[derive(Clone, Debug, Default)]
+pub struct FieldDiagnostics {
+    /// Issues in the current text, ordered by position.
+    pub issues: Vec<TextIssue>,
+    /// Text as of the last check; issues are moved along as it's edited.
+    checked: String,
+    revision: u64,
+    /// Range of the check in flight (in `checked`).
+    pending: Option<Range<usize>>,
+    slot: DiagnosticsSlot,
+}
+
+impl FieldDiagnostics {
+    /// Takes in published results, then hands whatever changed since the last check to
+    /// `provider`. Called every frame the field is painted.
+    pub fn sync(&mut self, text: &str, provider: &dyn TextDiagnostics) {
+        if let Some((rev, issues)) = self.slot.take()
+            && rev == self.revision
+            && let Some(range) = self.pending.take()
+        {
+            let checked = &self.checked;
+            self.issues.retain(|i| !overlaps(&i.range, &range));
+            self.issues.extend(issues.into_iter().filter(|i| {
+                i.range.start < i.range.end
+                    && i.range.end <= checked.len()
+                    && checked.is_char_boundary(i.range.start)
+                    && checked.is_char_boundary(i.range.end)
+            }));
+            self.issues.sort_by_key(|i| i.range.start);
+        }
+        if text == self.checked && self.revision > 0 {
+            return;
+        }
+
+        // The edit is whatever lies between the common prefix and suffix.
+        let old = self.checked.as_str();
+        let prefix = old
+            .char_indices()
+            .zip(text.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(old.len().min(text.len()), |((i, _), _)| i);
+        let suffix = old[prefix..]
+            .chars()
+            .rev()
+            .zip(text[prefix..].chars().rev())
+            .take_while(|(a, b)| a == b)
+            .map(|(a, _)| a.len_utf8())
+            .sum::<usize>();
+        let (old_end, new_end) = (old.len() - suffix, text.len() - suffix);
+        let shift = |pos: usize| pos + new_end - old_end;
+
+        self.issues.retain_mut(|i| {
+            if i.range.end <= prefix {
+                true
+            } else if i.range.start >= old_end {
+                i.range = shift(i.range.start)..shift(i.range.end);
+                true
+            } else {
+                false
+            }
+        });
+
+        let mut range = word_bounds(text, prefix..new_end);
+        if let Some(p) = self.pending.take() {
+            let start = if p.start <= prefix {
+                p.start
+            } else {
+                shift(p.start.max(old_end))
+            };
+            let end = if p.end <= prefix {
+                p.end
+            } else {
+                shift(p.end.max(old_end))
+            };
+            range = range.start.min(start)..range.end.max(end);
+        }
+        self.revision += 1;
+        self.pending = Some(range.clone());
+        self.checked = text.to_string();
+        provider.check(text, range, self.slot.sink(self.revision));
+    }
+
+    /// The issue under byte `idx` (inclusive of its end, so clicking just after a word works).
+    pub fn issue_at(&self, idx: usize) -> Option<&TextIssue> {
+        self.issues
+            .iter()
+            .find(|i| i.range.start <= idx && idx <= i.range.end)
+    }
+}
+
+fn overlaps(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+/// Widens `r` to whole words where its ends fall inside one, so a checker never sees half
+/// of a word.
+fn word_bounds(text: &str, r: Range<usize>) -> Range<usize> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '\'' || c == '_';
+    let edited = &text[r.clone()];
+    let (back, fwd) = (
+        edited.chars().next().is_none_or(is_word),
+        edited.chars().next_back().is_none_or(is_word),
+    );
+    if !back && !fwd {
+        return r;
+    }
+    let start = if !back {
+        r.start
+    } else {
+        text[..r.start]
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| is_word(*c))
+            .last()
+            .map_or(r.start, |(i, _)| i)
+    };
+    let end = if !fwd {
+        r.end
+    } else {
+        r.end
+            + text[r.end..]
+                .chars()
+                .take_while(|c| is_word(*c))
+                .map(char::len_utf8)
+                .sum::<usize>()
+    };
+    start..end
+}
+
+/// A field's undo and redo stacks.
+#[derive(Clone, Debug, Default)]
+pub struct EditHistory {
+    undo: VecDeque<Snapshot>,
+    redo: Vec<Snapshot>,
+    /// The edit group in progress and where it left the caret; a matching edit that
+    /// starts there joins it instead of becoming its own step.
+    group: Option<(EditKind, usize)>,
+}
+
+impl EditHistory {
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Snapshot {
+    text: String,
+    selection: Range<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EditKind {
+    Typing,
+    Deleting,
+    Composing,
+    /// Pastes, cuts and replacements: always a step of their own.
+    Other,
+}
+
 #[derive(Clone, Debug)]
 pub struct TextFieldState {
     pub text: String,
@@ -118,6 +519,21 @@ pub struct TextFieldState {
     pub drag_anchor: Option<usize>,        // byte index where drag began
     pub blink_start: Instant,              // caret blink timer
     pub inner_width: f32,                  // px
+    pub touch: TouchSelection,
+    pub diagnostics: FieldDiagnostics,
+    /// Set while the field is painted as a [`TextArea`].
+    pub multiline: bool,
+    pub scroll_offset_y: f32, // px
+    pub inner_height: f32,    // px
+    pub affinity: CaretAffinity,
+    /// Caret byte and the x (px) Up/Down aim for from it, so moving through a short line
+    /// doesn't lose the column.
+    goal_x: Option<(usize, f32)>,
+    pub history: EditHistory,
+    /// The field's [`VisualTransformation`], set as it's painted.
+    pub visual_transformation: Option<std::rc::Rc<dyn VisualTransformation>>,
+    /// The field's filter and length limit, set as it's painted.
+    pub constraints: InputConstraints,
 }
 
 impl Default for TextFieldState {
@@ -136,42 +552,189 @@ impl TextFieldState {
             drag_anchor: None,
             blink_start: Instant::now(),
             inner_width: 0.0,
+            touch: TouchSelection::default(),
+            diagnostics: FieldDiagnostics::default(),
+            multiline: false,
+            scroll_offset_y: 0.0,
+            inner_height: 0.0,
+            affinity: CaretAffinity::Downstream,
+            goal_x: None,
+            history: EditHistory::default(),
+            visual_transformation: None,
+            constraints: InputConstraints::default(),
         }
     }
 
+    /// Runs the edit `f` as one undo step, or as part of the current group when it's the
+    /// same `kind` of edit continuing from where the last one left the caret.
+    fn edit(&mut self, kind: EditKind, f: impl FnOnce(&mut Self)) {
+        let caret = self.selection.clone();
+        let joins = match self.history.group {
+            Some((EditKind::Composing, _)) => kind == EditKind::Composing,
+            Some((k, at)) => k == kind && kind != EditKind::Other && caret == (at..at),
+            None => false,
+        };
+        if !joins {
+            if self.history.undo.len() == UNDO_LIMIT {
+                self.history.undo.pop_front();
+            }
+            self.history.undo.push_back(Snapshot {
+                text: self.text.clone(),
+                selection: caret,
+            });
+        }
+        f(self);
+        // Nothing changed (e.g. Backspace at the start, a cancelled composition).
+        if self
+            .history
+            .undo
+            .back()
+            .is_some_and(|s| s.text == self.text)
+        {
+            self.history.undo.pop_back();
+            self.history.group = None;
+            return;
+        }
+        self.history.redo.clear();
+        self.history.group = Some((kind, self.selection.end));
+    }
+
+    /// Restores the text and selection from before the last edit step. Returns whether
+    /// there was one.
+    pub fn undo(&mut self) -> bool {
+        let Some(prev) = self.history.undo.pop_back() else {
+            return false;
+        };
+        let cur = self.swap_snapshot(prev);
+        self.history.redo.push(cur);
+        true
+    }
+
+    /// Reapplies the last undone step. Any new edit clears what there was to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(next) = self.history.redo.pop() else {
+            return false;
+        };
+        let cur = self.swap_snapshot(next);
+        self.history.undo.push_back(cur);
+        true
+    }
+
+    fn swap_snapshot(&mut self, to: Snapshot) -> Snapshot {
+        let cur = Snapshot {
+            text: std::mem::replace(&mut self.text, to.text),
+            selection: std::mem::replace(&mut self.selection, to.selection),
+        };
+        let len = self.text.len();
+        self.selection = self.selection.start.min(len)..self.selection.end.min(len);
+        self.composition = None;
+        self.history.group = None;
+        self.goal_x = None;
+        self.touch = TouchSelection::default();
+        self.reset_caret_blink();
+        cur
+    }
+
+    /// What of `text` may replace `range` under [`InputConstraints`]: all of it, as much as
+    /// fits the length limit, or nothing (`None`) when the filter rejects the result.
+    fn constrain<'a>(
+        &self,
+        range: Range<usize>,
+        text: &'a str,
+    ) -> Option<std::borrow::Cow<'a, str>> {
+        let mut text = std::borrow::Cow::Borrowed(text);
+        if text.is_empty() {
+            return Some(text);
+        }
+        if let Some(max) = self.constraints.max_length {
+            let kept = self.text.chars().count() - self.text[range.clone()].chars().count();
+            let mut room = max.saturating_sub(kept);
+            let fits = text
+                .grapheme_indices(true)
+                .take_while(|(_, g)| {
+                    let n = g.chars().count();
+                    let fits = n <= room;
+                    room = room.saturating_sub(n);
+                    fits
+                })
+                .last()
+                .map_or(0, |(i, g)| i + g.len());
+            if fits == 0 {
+                return None;
+            }
+            if fits < text.len() {
+                text = std::borrow::Cow::Owned(text[..fits].to_string());
+            }
+        }
+        if let Some(filter) = &self.constraints.filter {
+            let mut after = self.text.clone();
+            after.replace_range(range, &text);
+            if !filter(&after) {
+                return None;
+            }
+        }
+        Some(text)
+    }
+
+    /// Types or pastes `text` over the selection, as far as the field's
+    /// [`InputConstraints`] allow.
     pub fn insert_text(&mut self, text: &str) {
-        let start = self.selection.start.min(self.text.len());
-        let end = self.selection.end.min(self.text.len());
+        let Some(text) = self.constrain(self.ordered_selection(), text) else {
+            return;
+        };
+        let text = text.as_ref();
+        let typing = self.selection.is_empty() && text.graphemes(true).count() == 1;
+        let kind = if typing {
+            EditKind::Typing
+        } else {
+            EditKind::Other
+        };
+        self.edit(kind, |s| s.replace_selection(text));
+        // A space or line break ends the word, and with it the typing step.
+        if typing && text.trim().is_empty() {
+            self.history.group = None;
+        }
+    }
+
+    fn replace_selection(&mut self, text: &str) {
+        let Range { start, end } = self.ordered_selection();
 
         self.text.replace_range(start..end, text);
         let new_pos = start + text.len();
         self.selection = new_pos..new_pos;
+        self.touch = TouchSelection::default();
         self.reset_caret_blink();
     }
 
     pub fn delete_backward(&mut self) {
         if self.selection.start == self.selection.end {
-            let pos = self.selection.start.min(self.text.len());
-            if pos > 0 {
-                let prev = prev_grapheme_boundary(&self.text, pos);
-                self.text.replace_range(prev..pos, "");
-                self.selection = prev..prev;
-            }
+            self.edit(EditKind::Deleting, |s| {
+                let pos = s.selection.start.min(s.text.len());
+                if pos > 0 {
+                    let prev = prev_grapheme_boundary(&s.text, pos);
+                    s.text.replace_range(prev..pos, "");
+                    s.selection = prev..prev;
+                    s.touch = TouchSelection::default();
+                }
+            });
         } else {
-            self.insert_text("");
+            self.edit(EditKind::Other, |s| s.replace_selection(""));
         }
         self.reset_caret_blink();
     }
 
     pub fn delete_forward(&mut self) {
         if self.selection.start == self.selection.end {
-            let pos = self.selection.start.min(self.text.len());
-            if pos < self.text.len() {
-                let next = next_grapheme_boundary(&self.text, pos);
-                self.text.replace_range(pos..next, "");
-            }
+            self.edit(EditKind::Deleting, |s| {
+                let pos = s.selection.start.min(s.text.len());
+                if pos < s.text.len() {
+                    let next = next_grapheme_boundary(&s.text, pos);
+                    s.text.replace_range(pos..next, "");
+                    s.touch = TouchSelection::default();
+                }
+            });
         } else {
-            self.insert_text("");
+            self.edit(EditKind::Other, |s| s.replace_selection(""));
         }
         self.reset_caret_blink();
     }
@@ -196,14 +759,70 @@ impl TextFieldState {
     }
 
     pub fn selected_text(&self) -> String {
-        if self.selection.start == self.selection.end {
-            String::new()
+        self.text[self.ordered_selection()].to_string()
+    }
+
+    /// The selection as a forward range inside the text. `selection` itself runs from the
+    /// anchor to the caret, so it's backwards after extending it to the left.
+    pub fn ordered_selection(&self) -> Range<usize> {
+        let (a, b) = (
+            self.selection.start.min(self.text.len()),
+            self.selection.end.min(self.text.len()),
+        );
+        a.min(b)..a.max(b)
+    }
+
+    /// Moves the caret to the start of the previous word, or the end of the next one
+    /// (Ctrl+Left/Right).
+    pub fn move_word(&mut self, forward: bool, extend_selection: bool) {
+        let caret = self.selection.end.min(self.text.len());
+        let pos = if forward {
+            repose_text::next_word_end(&self.text, caret)
+        } else {
+            repose_text::prev_word_start(&self.text, caret)
+        };
+        self.move_caret_to(pos, extend_selection);
+    }
+
+    /// Moves the caret to the start or end of the text (Home/End in a single-line field).
+    pub fn move_to_edge(&mut self, end: bool, extend_selection: bool) {
+        let pos = if end { self.text.len() } else { 0 };
+        self.move_caret_to(pos, extend_selection);
+    }
+
+    fn move_caret_to(&mut self, pos: usize, extend_selection: bool) {
+        if extend_selection {
+            self.selection.end = pos;
         } else {
-            self.text[self.selection.clone()].to_string()
+            self.selection = pos..pos;
+        }
+        self.goal_x = None;
+        self.reset_caret_blink();
+    }
+
+    /// Deletes back to the start of the previous word (Ctrl+Backspace), or the selection.
+    pub fn delete_word_backward(&mut self) {
+        if self.selection.start == self.selection.end {
+            let caret = self.selection.end.min(self.text.len());
+            self.selection = repose_text::prev_word_start(&self.text, caret)..caret;
         }
+        self.edit(EditKind::Deleting, |s| s.replace_selection(""));
+    }
+
+    /// Deletes up to the end of the next word (Ctrl+Delete), or the selection.
+    pub fn delete_word_forward(&mut self) {
+        if self.selection.start == self.selection.end {
+            let caret = self.selection.end.min(self.text.len());
+            self.selection = caret..repose_text::next_word_end(&self.text, caret);
+        }
+        self.edit(EditKind::Deleting, |s| s.replace_selection(""));
     }
 
     pub fn set_composition(&mut self, text: String, cursor: Option<(usize, usize)>) {
+        self.edit(EditKind::Composing, |s| s.compose(text, cursor));
+    }
+
+    fn compose(&mut self, text: String, cursor: Option<(usize, usize)>) {
         if text.is_empty() {
             if let Some(range) = self.composition.take() {
                 let s = clamp_to_char_boundary(&self.text, range.start.min(self.text.len()));
@@ -233,6 +852,7 @@ impl TextFieldState {
         }
 
         self.composition = Some(anchor_start..(anchor_start + text.len()));
+        self.touch = TouchSelection::default();
 
         if let Some((c0, c1)) = cursor {
             let b0 = char_to_byte(&text, c0);
@@ -246,7 +866,32 @@ impl TextFieldState {
         self.reset_caret_blink();
     }
 
+    /// Replaces the preedit with `text`, or inserts it at the caret when nothing is being
+    /// composed. A committed composition is one undo step.
     pub fn commit_composition(&mut self, text: String) {
+        let len = self.text.len();
+        let range = match &self.composition {
+            Some(r) => {
+                let (a, b) = (r.start.min(len), r.end.min(len));
+                a.min(b)..a.max(b)
+            }
+            None => self.selection.end.min(len)..self.selection.end.min(len),
+        };
+        // What the constraints turn away still ends the composition.
+        let text = self
+            .constrain(range, &text)
+            .map_or_else(String::new, |t| t.into_owned());
+        if self.composition.is_some() {
+            self.edit(EditKind::Composing, |s| s.commit(text));
+            self.history.group = None;
+        } else if text.graphemes(true).count() == 1 && self.selection.is_empty() {
+            self.edit(EditKind::Typing, |s| s.commit(text));
+        } else {
+            self.edit(EditKind::Other, |s| s.commit(text));
+        }
+    }
+
+    fn commit(&mut self, text: String) {
         if let Some(r) = self.composition.take() {
             let s = clamp_to_char_boundary(&self.text, r.start.min(self.text.len()));
             let e = clamp_to_char_boundary(&self.text, r.end.min(self.text.len()));
@@ -259,22 +904,31 @@ impl TextFieldState {
             let new_pos = pos + text.len();
             self.selection = new_pos..new_pos;
         }
+        self.touch = TouchSelection::default();
         self.reset_caret_blink();
     }
 
     pub fn cancel_composition(&mut self) {
-        if let Some(r) = self.composition.take() {
-            let s = clamp_to_char_boundary(&self.text, r.start.min(self.text.len()));
-            let e = clamp_to_char_boundary(&self.text, r.end.min(self.text.len()));
-            if s <= e {
-                self.text.replace_range(s..e, "");
-                self.selection = s..s;
+        self.edit(EditKind::Composing, |st| {
+            if let Some(r) = st.composition.take() {
+                let s = clamp_to_char_boundary(&st.text, r.start.min(st.text.len()));
+                let e = clamp_to_char_boundary(&st.text, r.end.min(st.text.len()));
+                if s <= e {
+                    st.text.replace_range(s..e, "");
+                    st.selection = s..s;
+                }
             }
-        }
+        });
         self.reset_caret_blink();
     }
 
     pub fn delete_surrounding(&mut self, before_bytes: usize, after_bytes: usize) {
+        self.edit(EditKind::Deleting, |s| {
+            s.delete_around_caret(before_bytes, after_bytes)
+        });
+    }
+
+    fn delete_around_caret(&mut self, before_bytes: usize, after_bytes: usize) {
         if self.selection.start != self.selection.end {
             let start = self.selection.start.min(self.text.len());
             let end = self.selection.end.min(self.text.len());
@@ -297,23 +951,27 @@ impl TextFieldState {
         self.reset_caret_blink();
     }
 
+    /// Starts a mouse selection at `idx_byte`. With `extend` (Shift+click) the selection
+    /// keeps its anchor and the caret moves to the click instead.
     pub fn begin_drag(&mut self, idx_byte: usize, extend: bool) {
         let idx = idx_byte.min(self.text.len());
-        if extend {
-            let anchor = self.selection.start;
-            self.selection = anchor.min(idx)..anchor.max(idx);
-            self.drag_anchor = Some(anchor);
+        let anchor = if extend {
+            self.selection.start.min(self.text.len())
         } else {
-            self.selection = idx..idx;
-            self.drag_anchor = Some(idx);
-        }
+            idx
+        };
+        self.selection = anchor..idx;
+        self.drag_anchor = Some(anchor);
+        self.goal_x = None;
         self.reset_caret_blink();
     }
 
+    /// Moves the caret end of a mouse selection; it runs backwards when dragged left of
+    /// the anchor, so a later Shift+click still extends from where the drag began.
     pub fn drag_to(&mut self, idx_byte: usize) {
         if let Some(anchor) = self.drag_anchor {
             let i = idx_byte.min(self.text.len());
-            self.selection = anchor.min(i)..anchor.max(i);
+            self.selection = anchor..i;
         }
         self.reset_caret_blink();
     }
@@ -325,6 +983,114 @@ impl TextFieldState {
         self.selection.end
     }
 
+    /// Replaces `range` (bytes) with `text`, leaving the caret after it.
+    pub fn replace_range(&mut self, range: Range<usize>, text: &str) {
+        let end = clamp_to_char_boundary(&self.text, range.end.min(self.text.len()));
+        let start = clamp_to_char_boundary(&self.text, range.start.min(end));
+        self.selection = start..end;
+        self.edit(EditKind::Other, |s| s.replace_selection(text));
+    }
+
+    pub fn select_all(&mut self) {
+        self.selection = 0..self.text.len();
+        self.reset_caret_blink();
+    }
+
+    /// Selects the word around `idx_byte` (or the whitespace run, if that's what's there).
+    pub fn select_word_at(&mut self, idx_byte: usize) {
+        let idx = idx_byte.min(self.text.len());
+        if let Some(r) = repose_text::word_segment_at(&self.text, idx) {
+            self.selection = r;
+        }
+        self.reset_caret_blink();
+    }
+
+    /// Finishes a touch on the field. `before` is the selection when the finger went down:
+    /// a long press selects the word under the caret and opens the toolbar, a tap on the
+    /// caret that's already showing its handle toggles the toolbar, and any other tap just
+    /// shows the caret handle.
+    pub fn touch_release(&mut self, before: Range<usize>, long_press: bool) {
+        if long_press {
+            self.select_word_at(self.selection.end);
+            self.touch.toolbar = true;
+        } else {
+            let same_caret = before == self.selection && before.is_empty();
+            self.touch.toolbar = self.touch.handles && same_caret && !self.touch.toolbar;
+        }
+        self.touch.handles = true;
+        self.touch.dragging = None;
+    }
+
+    /// Starts dragging a selection handle; the opposite end stays put.
+    pub fn begin_handle_drag(&mut self, handle: SelectionHandle, grab_dx: f32) {
+        let collapsed = self.selection.start == self.selection.end;
+        let (anchor, idx) = match handle {
+            SelectionHandle::Start => (self.selection.end, self.selection.start),
+            SelectionHandle::End => (self.selection.start, self.selection.end),
+        };
+        self.drag_anchor = (!collapsed).then_some(anchor);
+        self.touch.dragging = Some(idx);
+        self.touch.toolbar = false;
+        self.touch.grab_dx = grab_dx;
+        self.reset_caret_blink();
+    }
+
+    /// Moves the dragged handle to `idx_byte`. Handles may cross; the selection stays ordered.
+    pub fn drag_handle_to(&mut self, idx_byte: usize) {
+        if self.touch.dragging.is_none() {
+            return;
+        }
+        let i = idx_byte.min(self.text.len());
+        match self.drag_anchor {
+            // Keep at least one grapheme selected so a range drag doesn't turn into a caret.
+            Some(anchor) if i == anchor => {}
+            Some(_) => {
+                self.drag_to(i);
+                self.selection = self.ordered_selection();
+            }
+            None => self.selection = i..i,
+        }
+        self.touch.dragging = Some(i);
+        self.reset_caret_blink();
+    }
+
+    pub fn end_handle_drag(&mut self) {
+        self.drag_anchor = None;
+        self.touch.dragging = None;
+        self.touch.toolbar = self.selection.start != self.selection.end;
+    }
+
+    /// Applies a toolbar action. `clipboard` is read by `Paste`; the returned text (from `Cut`
+    /// or `Copy`) should be put on the clipboard.
+    pub fn apply_edit(&mut self, action: EditAction, clipboard: Option<&str>) -> Option<String> {
+        match action {
+            EditAction::Cut => {
+                let cut = self.selected_text();
+                self.insert_text("");
+                (!cut.is_empty()).then_some(cut)
+            }
+            EditAction::Copy => {
+                let copied = self.selected_text();
+                self.touch.toolbar = false;
+                (!copied.is_empty()).then_some(copied)
+            }
+            EditAction::Paste => {
+                if let Some(text) = clipboard
+                    && let Some(text) = self.constrain(self.ordered_selection(), text)
+                {
+                    self.edit(EditKind::Other, |s| s.replace_selection(&text));
+                }
+                None
+            }
+            EditAction::SelectAll => {
+                self.select_all();
+                self.touch.handles = true;
+                self.touch.toolbar = true;
+                None
+            }
+        }
+    }
+
     /// Keep caret visible inside inner content width (px).
     /// `inset_px` is a small padding (px) to avoid hugging edges.
     pub fn ensure_caret_visible(&mut self, caret_x_px: f32, inner_width_px: f32, inset_px: f32) {
@@ -342,13 +1108,191 @@ impl TextFieldState {
         self.blink_start = Instant::now();
     }
     pub fn caret_visible(&self) -> bool {
-        const PERIOD: Duration = Duration::from_millis(500);
-        ((Instant::now() - self.blink_start).as_millis() / PERIOD.as_millis()).is_multiple_of(2)
+        ((Instant::now() - self.blink_start).as_millis() / CARET_BLINK_PERIOD.as_millis())
+            .is_multiple_of(2)
+    }
+    /// When the caret next flips between shown and hidden, counted from the last edit or caret
+    /// move. Runners that don't redraw continuously wake up at this instant to repaint it.
+    pub fn next_caret_toggle(&self, now: Instant) -> Instant {
+        let period = CARET_BLINK_PERIOD.as_millis();
+        let elapsed = now.saturating_duration_since(self.blink_start).as_millis();
+        let phases = (elapsed / period + 1) as u32;
+        self.blink_start + CARET_BLINK_PERIOD * phases
     }
 
     pub fn set_inner_width(&mut self, w_px: f32) {
         self.inner_width = w_px.max(0.0);
     }
+
+    /// The text as it's painted, with the mapping back to `text`.
+    pub fn display(&self) -> TransformedText {
+        match &self.visual_transformation {
+            Some(t) => t.filter(&self.text),
+            None => TransformedText::identity(&self.text),
+        }
+    }
+
+    /// Painted x (px, from the start of the text) of byte `idx_byte` of a single-line field.
+    pub fn x_for_index(&self, idx_byte: usize, font_px: f32) -> f32 {
+        let shown = self.display();
+        let m = measure_text(&shown.text, font_px);
+        let i = byte_to_char_index(&m, shown.mapping.to_transformed(idx_byte));
+        m.positions.get(i).copied().unwrap_or(0.0)
+    }
+
+    /// Byte of `text` a pointer at painted x (px) lands on; see [`index_for_x_bytes`].
+    pub fn index_for_x(&self, font_px: f32, x_px: f32) -> usize {
+        let shown = self.display();
+        let i = index_for_x_bytes(&shown.text, font_px, x_px);
+        shown.mapping.to_original(i).min(self.text.len())
+    }
+
+    /// The text's visual lines at the field's current width.
+    pub fn wrapped(&self, font_px: f32) -> WrappedText {
+        WrappedText::new(&self.text, font_px, self.inner_width.max(1.0))
+    }
+
+    /// Moves the caret `delta` visual lines down (up when negative), keeping its column.
+    pub fn move_lines(&mut self, delta: isize, extend_selection: bool, font_px: f32) {
+        let w = self.wrapped(font_px);
+        let caret = self.selection.end.min(self.text.len());
+        let at = w.caret_position(caret, self.affinity);
+        let x = match self.goal_x {
+            Some((b, x)) if b == caret => x,
+            _ => at.x,
+        };
+        let line = w.line_of(caret, self.affinity) as isize;
+        let target = (line + delta).clamp(0, w.lines.len() as isize - 1);
+        let (pos, affinity) = if target == line {
+            // Past the first or last line: go to its start or end.
+            let r = &w.lines[line as usize];
+            if delta < 0 {
+                (r.start, CaretAffinity::Downstream)
+            } else {
+                (r.end, CaretAffinity::Upstream)
+            }
+        } else {
+            w.index_for_point(x, (target as f32 + 0.5) * w.line_height())
+        };
+        if extend_selection {
+            self.selection.end = pos;
+        } else {
+            self.selection = pos..pos;
+        }
+        self.affinity = affinity;
+        self.goal_x = Some((pos, x));
+        self.reset_caret_blink();
+    }
+
+    /// Moves the caret to the start (or end) of its visual line.
+    pub fn move_to_line_edge(&mut self, end: bool, extend_selection: bool, font_px: f32) {
+        let w = self.wrapped(font_px);
+        let caret = self.selection.end.min(self.text.len());
+        let r = &w.lines[w.line_of(caret, self.affinity)];
+        let pos = if end { r.end } else { r.start };
+        if extend_selection {
+            self.selection.end = pos;
+        } else {
+            self.selection = pos..pos;
+        }
+        self.affinity = if end {
+            CaretAffinity::Upstream
+        } else {
+            CaretAffinity::Downstream
+        };
+        self.reset_caret_blink();
+    }
+
+    /// Scrolls vertically so the caret's line is inside the visible inner height.
+    pub fn ensure_caret_visible_y(&mut self, font_px: f32) {
+        let w = self.wrapped(font_px);
+        let lh = w.line_height();
+        let top = w
+            .caret_position(self.selection.end.min(self.text.len()), self.affinity)
+            .y;
+        let view_h = self.inner_height.max(lh);
+        if top < self.scroll_offset_y {
+            self.scroll_offset_y = top;
+        } else if top + lh > self.scroll_offset_y + view_h {
+            self.scroll_offset_y = top + lh - view_h;
+        }
+        let content_h = w.lines.len() as f32 * lh;
+        self.scroll_offset_y = self
+            .scroll_offset_y
+            .min((content_h - view_h).max(0.0))
+            .max(0.0);
+    }
+
+    /// The caret position under `(x, y)`, px relative to the top-left of the inner rect
+    /// (scrolling is accounted for).
+    pub fn index_for_point(&self, x: f32, y: f32, font_px: f32) -> (usize, CaretAffinity) {
+        if self.multiline {
+            self.wrapped(font_px)
+                .index_for_point(x, y + self.scroll_offset_y)
+        } else {
+            let idx = index_for_x_bytes(&self.text, font_px, (x + self.scroll_offset).max(0.0));
+            (idx, CaretAffinity::Downstream)
+        }
+    }
+
+    /// Keys a multi-line field handles differently from a single-line one: Enter inserts a
+    /// line break, Up/Down and PageUp/PageDown move between visual lines, Home/End go to the
+    /// ends of the caret's line. Returns `None` for other keys (and for single-line fields),
+    /// otherwise whether the text changed. Scrolls the caret into view.
+    pub fn multiline_key(&mut self, key: &Key, shift: bool, font_px: f32) -> Option<bool> {
+        if !self.multiline {
+            return None;
+        }
+        let page = ((self.inner_height / (font_px * 1.3)).floor() as isize - 1).max(1);
+        let changed = match key {
+            Key::Enter => {
+                self.insert_text("\n");
+                self.affinity = CaretAffinity::Downstream;
+                true
+            }
+            Key::ArrowUp => {
+                self.move_lines(-1, shift, font_px);
+                false
+            }
+            Key::ArrowDown => {
+                self.move_lines(1, shift, font_px);
+                false
+            }
+            Key::PageUp => {
+                self.move_lines(-page, shift, font_px);
+                false
+            }
+            Key::PageDown => {
+                self.move_lines(page, shift, font_px);
+                false
+            }
+            Key::Home => {
+                self.move_to_line_edge(false, shift, font_px);
+                false
+            }
+            Key::End => {
+                self.move_to_line_edge(true, shift, font_px);
+                false
+            }
+            _ => return None,
+        };
+        self.ensure_caret_visible_y(font_px);
+        Some(changed)
+    }
+}
+
+/// What pasted or typed text keeps: control characters go, and so do line breaks unless
+/// the field is `multiline` (where `\r\n` and `\r` become `\n`).
+pub fn sanitize_input(text: &str, multiline: bool) -> String {
+    if multiline {
+        text.replace("\r\n", "\n")
+            .replace('\r', "\n")
+            .chars()
+            .filter(|c| *c == '\n' || !c.is_control())
+            .collect()
+    } else {
+        text.chars().filter(|c| !c.is_control()).collect()
+    }
 }
 
 // Platform-managed view: hint only.
@@ -365,6 +1309,10 @@ pub fn TextField(
             hint: hint.into(),
             on_change: on_change.map(|f| std::rc::Rc::new(f) as _),
             on_submit: on_submit.map(|f| std::rc::Rc::new(f) as _),
+            diagnostics: None,
+            visual_transformation: None,
+            input_filter: None,
+            max_length: None,
         },
     )
     .modifier(modifier)
@@ -376,10 +1324,413 @@ pub fn TextField(
     })
 }
 
+/// A multi-line text field: wraps to its width, scrolls vertically, and takes Enter as a
+/// line break rather than a submit. Without a height it shows [`TA_DEFAULT_LINES`] lines.
+/// State is held by the runner like [`TextField`]'s, and [`TextFieldExt`] applies to it too.
+pub fn TextArea(
+    hint: impl Into<String>,
+    modifier: repose_core::Modifier,
+    on_change: Option<impl Fn(String) + 'static>,
+) -> repose_core::View {
+    repose_core::View::new(
+        0,
+        repose_core::ViewKind::TextArea {
+            state_key: 0,
+            hint: hint.into(),
+            on_change: on_change.map(|f| std::rc::Rc::new(f) as _),
+            diagnostics: None,
+            input_filter: None,
+            max_length: None,
+        },
+    )
+    .modifier(modifier)
+    .semantics(repose_core::Semantics {
+        role: repose_core::Role::TextField,
+        label: None,
+        focused: false,
+        enabled: true,
+    })
+}
+
+thread_local! {
+    static REPLACEMENTS: std::cell::RefCell<std::collections::HashMap<u64, String>> =
+        std::cell::RefCell::new(std::collections::HashMap::new());
+}
+
+/// Replaces the whole text of the field whose state is keyed `key` (see
+/// [`TextFieldExt::state_key`]), e.g. to clear a search box. It's applied at the field's
+/// next layout once the runner holds state for it; `on_change` isn't called.
+pub fn replace_text(key: u64, text: impl Into<String>) {
+    REPLACEMENTS.with(|r| r.borrow_mut().insert(key, text.into()));
+}
+
+pub(crate) fn take_replacement(key: u64) -> Option<String> {
+    REPLACEMENTS.with(|r| r.borrow_mut().remove(&key))
+}
+
+pub trait TextFieldExt {
+    /// Underlines the issues `provider` reports and offers its suggestions on right-click.
+    fn text_diagnostics(self, provider: impl TextDiagnostics + 'static) -> View;
+    /// Keys the platform-held state (text, caret, selection) by `key` rather than by the
+    /// field's place in the tree, so the app can address it with [`replace_text`].
+    fn state_key(self, key: u64) -> View;
+    /// Paints the text through `transformation`, e.g. [`PasswordVisualTransformation`]
+    /// to mask it. Editing, the caret and pointer hits still work on the real text.
+    /// Only applies to [`TextField`].
+    fn visual_transformation(self, transformation: impl VisualTransformation + 'static) -> View;
+    /// Only lets typing, pasting and IME commits through when `accept` passes the text
+    /// they'd leave, e.g. `|t| t.chars().all(|c| c.is_ascii_digit())`. Deleting isn't
+    /// checked.
+    fn filter(self, accept: impl Fn(&str) -> bool + 'static) -> View;
+    /// Caps the text at `n` characters: input that doesn't fit is cut short.
+    fn max_length(self, n: usize) -> View;
+}
+impl TextFieldExt for View {
+    fn filter(mut self, accept: impl Fn(&str) -> bool + 'static) -> View {
+        if let ViewKind::TextField { input_filter, .. } | ViewKind::TextArea { input_filter, .. } =
+            &mut self.kind
+        {
+            *input_filter = Some(std::rc::Rc::new(accept));
+        }
+        self
+    }
+    fn max_length(mut self, n: usize) -> View {
+        if let ViewKind::TextField { max_length, .. } | ViewKind::TextArea { max_length, .. } =
+            &mut self.kind
+        {
+            *max_length = Some(n);
+        }
+        self
+    }
+    fn visual_transformation(
+        mut self,
+        transformation: impl VisualTransformation + 'static,
+    ) -> View {
+        if let ViewKind::TextField {
+            visual_transformation,
+            ..
+        } = &mut self.kind
+        {
+            *visual_transformation = Some(std::rc::Rc::new(transformation));
+        }
+        self
+    }
+    fn state_key(mut self, key: u64) -> View {
+        if let ViewKind::TextField { state_key, .. } | ViewKind::TextArea { state_key, .. } =
+            &mut self.kind
+        {
+            *state_key = key;
+        }
+        self
+    }
+    fn text_diagnostics(mut self, provider: impl TextDiagnostics + 'static) -> View {
+        if let ViewKind::TextField { diagnostics, .. } | ViewKind::TextArea { diagnostics, .. } =
+            &mut self.kind
+        {
+            *diagnostics = Some(std::rc::Rc::new(provider));
+        }
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_undo_groups_typing_by_word_and_redo_is_cleared_by_edits() {
+        let mut st = TextFieldState::new();
+        for c in ["h", "i", " ", "y", "o", "u"] {
+            st.insert_text(c);
+        }
+        st.delete_backward();
+        st.delete_backward();
+        assert_eq!(st.text, "hi y");
+
+        assert!(st.undo());
+        assert_eq!((st.text.as_str(), st.selection.clone()), ("hi you", 6..6));
+        assert!(st.undo());
+        assert_eq!(st.text, "hi ");
+        assert!(st.redo());
+        assert_eq!(st.text, "hi you");
+
+        // A committed composition is one step; editing drops the redo.
+        st.set_composition("n".into(), None);
+        st.set_composition("ni".into(), None);
+        st.commit_composition("你".into());
+        assert_eq!(st.text, "hi you你");
+        assert!(!st.history.can_redo());
+        assert!(st.undo());
+        assert_eq!(st.text, "hi you");
+
+        // Edits that change nothing aren't steps.
+        st.selection = 0..0;
+        st.delete_backward();
+        st.set_composition("x".into(), None);
+        st.cancel_composition();
+        assert!(st.undo());
+        assert_eq!(st.text, "hi ");
+        assert!(st.undo());
+        assert_eq!(st.text, "");
+        assert!(!st.undo());
+    }
+
+    #[test]
+    fn test_word_moves_and_deletes_and_shift_home_selects_back() {
+        let mut st = TextFieldState::new();
+        st.insert_text("foo, bar baz");
+        st.move_word(false, false);
+        assert_eq!(st.selection, 9..9);
+        st.move_word(false, true);
+        assert_eq!(st.selected_text(), "bar ");
+        st.move_word(true, false);
+        assert_eq!(st.selection, 8..8);
+
+        // Shift+Home from here selects backwards to the start.
+        st.move_to_edge(false, true);
+        assert_eq!((st.selection.start, st.selection.end), (8, 0));
+        assert_eq!(st.ordered_selection(), 0..8);
+        st.insert_text("x");
+        assert_eq!(st.text, "x baz");
+
+        st.move_to_edge(true, false);
+        st.delete_word_backward();
+        assert_eq!(st.text, "x ");
+        st.move_to_edge(false, false);
+        st.delete_word_forward();
+        assert_eq!((st.text.as_str(), st.selection.clone()), (" ", 0..0));
+        assert!(st.undo());
+        assert_eq!(st.text, "x ");
+    }
+
+    #[test]
+    fn test_word_moves_keep_contractions_and_numbers_whole() {
+        let mut st = TextFieldState::new();
+        st.insert_text("can't stop 3.14!");
+        st.move_to_edge(false, false);
+        st.move_word(true, false);
+        assert_eq!(st.selection, 5..5);
+        st.move_to_edge(true, false);
+        st.move_word(false, true);
+        assert_eq!(st.selected_text(), "3.14!");
+        st.select_word_at(12);
+        assert_eq!(st.selected_text(), "3.14");
+    }
+
+    #[test]
+    fn test_filter_and_max_length_hold_for_typing_paste_and_ime_commits() {
+        let mut st = TextFieldState::new();
+        st.constraints = InputConstraints {
+            filter: Some(std::rc::Rc::new(|t: &str| {
+                t.chars().all(|c| c.is_ascii_digit())
+            })),
+            max_length: Some(4),
+        };
+        st.insert_text("1");
+        st.insert_text("x");
+        assert_eq!(st.text, "1");
+        // Pastes are cut to fit, and a rejected one changes nothing.
+        st.apply_edit(EditAction::Paste, Some("23456"));
+        assert_eq!(st.text, "1234");
+        st.insert_text("5");
+        assert_eq!(st.text, "1234");
+        st.select_all();
+        st.apply_edit(EditAction::Paste, Some("9a"));
+        assert_eq!(st.text, "1234");
+
+        // Replacing a selection frees its room.
+        st.selection = 2..4;
+        st.insert_text("789");
+        assert_eq!(st.text, "1278");
+
+        // A rejected IME commit still drops the preedit.
+        st.selection = 2..4;
+        st.delete_backward();
+        st.set_composition("ab".into(), None);
+        st.commit_composition("ab".into());
+        assert_eq!((st.text.as_str(), st.composition.clone()), ("12", None));
+        st.set_composition("5".into(), None);
+        st.commit_composition("567".into());
+        assert_eq!(st.text, "1256");
+    }
+
+    #[test]
+    fn test_password_mask_maps_carets_and_clicks_through_to_the_text() {
+        let font = 16.0;
+        let mut st = TextFieldState::new();
+        st.insert_text("aé!");
+        st.visual_transformation = Some(std::rc::Rc::new(PasswordVisualTransformation::default()));
+        let shown = st.display();
+        assert_eq!(shown.text, "•••");
+
+        // Every character paints as one dot, whatever its width or byte length.
+        let dots = measure_text("••", font).positions.last().copied().unwrap();
+        assert_eq!(st.x_for_index(3, font), dots);
+        let all = measure_text("•••", font).positions.last().copied().unwrap();
+        assert_eq!(st.x_for_index(st.text.len(), font), all);
+        // A click past the second dot lands after "é", on a char boundary of the text.
+        assert_eq!(st.index_for_x(font, dots + 0.5), 3);
+        assert_eq!(st.index_for_x(font, 1000.0), st.text.len());
+    }
+
+    #[test]
+    fn test_clicks_round_at_glyph_midpoints_and_shift_click_keeps_the_anchor() {
+        let font = 16.0;
+        let m = measure_text("abc", font);
+        let x_at = |b: usize| m.positions[m.byte_offsets.iter().rposition(|&o| o == b).unwrap()];
+        let mid = (x_at(1) + x_at(2)) * 0.5;
+        assert_eq!(index_for_x_bytes("abc", font, mid - 0.5), 1);
+        assert_eq!(index_for_x_bytes("abc", font, mid), 2);
+        assert_eq!(index_for_x_bytes("abc", font, 1000.0), 3);
+        assert_eq!(index_for_x_bytes("", font, 10.0), 0);
+
+        let mut st = TextFieldState::new();
+        st.insert_text("hello world");
+        // Drag from 6 back to 2, then Shift+click at 9: extends from where the drag began.
+        st.begin_drag(6, false);
+        st.drag_to(2);
+        assert_eq!(st.selected_text(), "llo ");
+        st.end_drag();
+        st.begin_drag(9, true);
+        assert_eq!((st.selection.start, st.selection.end), (6, 9));
+        assert_eq!(st.caret_index(), 9);
+    }
+
+    #[test]
+    fn test_next_caret_toggle_restarts_on_edit() {
+        let mut st = TextFieldState::new();
+        let t0 = st.blink_start;
+        assert_eq!(st.next_caret_toggle(t0), t0 + CARET_BLINK_PERIOD);
+        let later = t0 + CARET_BLINK_PERIOD * 3 + Duration::from_millis(10);
+        assert_eq!(st.next_caret_toggle(later), t0 + CARET_BLINK_PERIOD * 4);
+
+        st.insert_text("a");
+        assert!(st.blink_start >= t0);
+        assert_eq!(
+            st.next_caret_toggle(st.blink_start),
+            st.blink_start + CARET_BLINK_PERIOD
+        );
+    }
+
+    #[test]
+    fn test_touch_selection_handles_and_toolbar() {
+        let mut st = TextFieldState::new();
+        st.insert_text("hello brave world");
+
+        // Long press inside "brave" selects it and opens the toolbar.
+        st.begin_drag(8, false);
+        st.touch_release(8..8, true);
+        assert_eq!(st.selected_text(), "brave");
+        assert!(st.touch.handles && st.touch.toolbar);
+
+        // Dragging the end handle over the start keeps the selection ordered.
+        st.begin_handle_drag(SelectionHandle::End, 0.0);
+        assert!(!st.touch.toolbar);
+        st.drag_handle_to(0);
+        assert_eq!(st.selection, 0..6);
+        assert_eq!(st.touch.dragging, Some(0));
+        st.end_handle_drag();
+        assert!(st.touch.toolbar && st.touch.dragging.is_none());
+
+        assert_eq!(
+            st.apply_edit(EditAction::Cut, None).as_deref(),
+            Some("hello ")
+        );
+        assert_eq!(st.text, "brave world");
+        assert_eq!(st.touch, TouchSelection::default());
+
+        st.apply_edit(EditAction::Paste, Some("a "));
+        assert_eq!(st.text, "a brave world");
+        st.apply_edit(EditAction::SelectAll, None);
+        assert_eq!(st.selection, 0..st.text.len());
+    }
+
+    #[test]
+    fn test_diagnostics_follow_edits() {
+        use std::cell::RefCell;
+
+        // Flags "teh", remembering which ranges it was asked about.
+        #[derive(Default)]
+        struct Teh(RefCell<Vec<Range<usize>>>);
+        impl TextDiagnostics for Teh {
+            fn check(&self, text: &str, range: Range<usize>, sink: DiagnosticsSink) {
+                self.0.borrow_mut().push(range.clone());
+                let issues = text[range.clone()]
+                    .match_indices("teh")
+                    .map(|(i, w)| TextIssue {
+                        range: range.start + i..range.start + i + w.len(),
+                        severity: Severity::Error,
+                        message: "Unknown word".into(),
+                        suggestions: vec!["the".into()],
+                    })
+                    .collect();
+                sink.publish(issues);
+            }
+        }
+
+        let teh = Teh::default();
+        let mut st = TextFieldState::new();
+        st.insert_text("teh cat");
+        st.diagnostics.sync(&st.text, &teh);
+        assert_eq!(teh.0.borrow().last(), Some(&(0..7)));
+        st.diagnostics.sync(&st.text, &teh); // results land on the next frame
+        assert_eq!(st.diagnostics.issues[0].range, 0..3);
+
+        // Typing in front moves the issue along; only the edited word is rechecked.
+        st.selection = 0..0;
+        st.insert_text("so ");
+        st.diagnostics.sync(&st.text, &teh);
+        assert_eq!(teh.0.borrow().last(), Some(&(0..3)));
+        assert_eq!(
+            st.diagnostics.issue_at(4).map(|i| i.range.clone()),
+            Some(3..6)
+        );
+
+        let issue = st.diagnostics.issue_at(4).unwrap().clone();
+        st.replace_range(issue.range, &issue.suggestions[0]);
+        assert_eq!(st.text, "so the cat");
+        st.diagnostics.sync(&st.text, &teh);
+        st.diagnostics.sync(&st.text, &teh);
+        assert!(st.diagnostics.issues.is_empty());
+    }
+
+    #[test]
+    fn test_text_area_keys_move_by_line_and_scroll() {
+        let font = 16.0;
+        let lh = font * 1.3;
+        let mut st = TextFieldState::new();
+        st.insert_text("one\ntwo\nthree\nfour");
+        assert_eq!(st.multiline_key(&Key::Enter, false, font), None);
+
+        st.multiline = true;
+        st.set_inner_width(1000.0);
+        st.inner_height = 2.0 * lh;
+        assert_eq!(st.multiline_key(&Key::Home, false, font), Some(false));
+        assert_eq!(st.selection, 14..14);
+        assert_eq!(st.scroll_offset_y, 2.0 * lh);
+        st.multiline_key(&Key::ArrowUp, false, font);
+        st.multiline_key(&Key::ArrowUp, true, font);
+        assert_eq!((st.selection.start, st.selection.end), (8, 4));
+        assert_eq!(
+            st.scroll_offset_y, lh,
+            "scrolled up to keep the caret's line in view"
+        );
+
+        st.selection = 4..4;
+        assert_eq!(st.multiline_key(&Key::Enter, false, font), Some(true));
+        assert_eq!(st.text, "one\n\ntwo\nthree\nfour");
+        st.multiline_key(&Key::PageDown, false, font);
+        st.multiline_key(&Key::End, false, font);
+        assert_eq!(
+            st.selection,
+            14..14,
+            "end of \"three\", a page (one line) down"
+        );
+
+        assert_eq!(sanitize_input("a\r\nb\tc", true), "a\nbc");
+        assert_eq!(sanitize_input("a\r\nb", false), "ab");
+    }
+
     #[test]
     fn test_index_for_x_bytes_grapheme() {
         let t = "A👍🏽B";
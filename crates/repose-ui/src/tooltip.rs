@@ -0,0 +1,202 @@
+//! # Tooltips
+//!
+//! Views with `Modifier::tooltip(text)`, and ellipsized `Text` (with its full string), offer
+//! a tooltip while they're painted under the pointer (`Interactions::pointer`); once the
+//! pointer has rested on the same view for [`TOOLTIP_DELAY`], `layout_and_paint` shows the
+//! innermost offer in a window layer (see `repose_core::show_layer`). The delay is measured
+//! on the frame clock, and frames are requested while it runs. Opt out of overflow tooltips
+//! per node with `Modifier::no_overflow_tooltip()`.
+//!
+//! Offering doesn't add a hit region, so a truncated label inside a `Button` still routes
+//! clicks to the button.
+
+use std::cell::{Cell, RefCell};
+use std::time::Duration;
+
+use repose_core::{
+    LayerOptions, Modifier, Rect, Vec2, View, frame_time_nanos, locals, show_layer,
+    with_frame_nanos,
+};
+
+use crate::{Box, Column, Text, TextStyle, ViewExt};
+
+/// How long the pointer has to rest on a view before its tooltip appears.
+pub const TOOLTIP_DELAY: Duration = Duration::from_millis(500);
+
+const FONT_DP: f32 = 12.0;
+const PAD_X_DP: f32 = 6.0;
+const PAD_Y_DP: f32 = 4.0;
+const GAP_DP: f32 = 4.0;
+/// Above popups and dialogs.
+const TOOLTIP_Z: i32 = 1000;
+
+#[derive(Clone)]
+pub(crate) struct Offer {
+    id: u64,
+    text: String,
+    anchor: Rect,
+}
+
+thread_local! {
+    static OFFER: RefCell<Option<Offer>> = const { RefCell::new(None) };
+    /// `(view id, dwell start, last seen)` in frame nanos, per offered view (each layer's
+    /// pass offers its own). A view not offered for a whole frame starts over.
+    static DWELL: RefCell<Vec<(u64, u64, u64)>> = const { RefCell::new(Vec::new()) };
+    /// Frame time of the last pass.
+    static FRAME: Cell<u64> = const { Cell::new(0) };
+    /// Every tooltip view painted while recording, under the pointer or not; see
+    /// [`record`].
+    static RECORDING: RefCell<Vec<Vec<Offer>>> = const { RefCell::new(Vec::new()) };
+}
+
+pub(crate) fn begin_pass() {
+    OFFER.with(|o| o.borrow_mut().take());
+    let now = frame_time_nanos();
+    let last = FRAME.replace(now);
+    if now != last {
+        DWELL.with(|d| d.borrow_mut().retain(|&(_, _, seen)| seen >= last));
+    }
+}
+
+/// Called for every painted tooltip view; offers it when it's under `pointer`.
+pub(crate) fn consider(pointer: Option<Vec2>, id: u64, text: &str, anchor: Rect) {
+    RECORDING.with(|r| {
+        if let Some(rec) = r.borrow_mut().last_mut() {
+            rec.push(Offer {
+                id,
+                text: text.to_string(),
+                anchor,
+            });
+        }
+    });
+    if pointer_in(pointer, anchor) {
+        offer(id, text, anchor);
+    }
+}
+
+/// Runs `paint` and returns the tooltip views it painted, so a cached layer can
+/// [`replay`] them on frames it isn't painted again.
+pub(crate) fn record(paint: impl FnOnce()) -> Vec<Offer> {
+    RECORDING.with(|r| r.borrow_mut().push(Vec::new()));
+    paint();
+    let rec = RECORDING.with(|r| r.borrow_mut().pop().unwrap_or_default());
+    // An enclosing recording sees them too.
+    RECORDING.with(|r| {
+        if let Some(outer) = r.borrow_mut().last_mut() {
+            outer.extend(rec.iter().cloned());
+        }
+    });
+    rec
+}
+
+/// Considers recorded tooltip views again, moved by `(dx, dy)`.
+pub(crate) fn replay(recorded: &[Offer], pointer: Option<Vec2>, dx: f32, dy: f32) {
+    for o in recorded {
+        let anchor = Rect {
+            x: o.anchor.x + dx,
+            y: o.anchor.y + dy,
+            ..o.anchor
+        };
+        consider(pointer, o.id, &o.text, anchor);
+    }
+}
+
+/// Later offers (painted on top) win.
+fn offer(id: u64, text: &str, anchor: Rect) {
+    OFFER.with(|o| {
+        *o.borrow_mut() = Some(Offer {
+            id,
+            text: text.to_string(),
+            anchor,
+        })
+    });
+}
+
+/// Shows the tooltip for this pass's offer once its dwell has elapsed.
+pub(crate) fn paint(window_px: (f32, f32)) {
+    let Some(offer) = OFFER.with(|o| o.borrow_mut().take()) else {
+        return;
+    };
+    let now = frame_time_nanos();
+    let since = DWELL.with(|d| {
+        let mut d = d.borrow_mut();
+        let since = d
+            .iter()
+            .find(|&&(id, ..)| id == offer.id)
+            .map_or(now, |&(_, since, _)| since);
+        d.retain(|&(id, ..)| id != offer.id);
+        d.push((offer.id, since, now));
+        since
+    });
+    if now - since < TOOLTIP_DELAY.as_nanos() as u64 {
+        // Nothing else may be animating, so ask for the frames that end the dwell.
+        with_frame_nanos(|_| {});
+        return;
+    }
+
+    let th = locals::theme();
+    let px = locals::dp_to_px;
+    let size_px = px(FONT_DP) * locals::text_scale().0;
+    let line_h = size_px * 1.3;
+    let (pad_x, pad_y, gap) = (px(PAD_X_DP), px(PAD_Y_DP), px(GAP_DP));
+
+    let max_text_w = (window_px.0 - 2.0 * (gap + pad_x)).max(size_px);
+    let (lines, _) = repose_text::wrap_lines(&offer.text, size_px, max_text_w, None, true);
+    let text_w = lines
+        .iter()
+        .map(|l| {
+            repose_text::metrics_for_textfield(l, size_px)
+                .positions
+                .last()
+                .copied()
+                .unwrap_or(0.0)
+        })
+        .fold(0.0f32, f32::max);
+
+    let w = text_w + 2.0 * pad_x;
+    let h = lines.len().max(1) as f32 * line_h + 2.0 * pad_y;
+    let x = offer.anchor.x.min(window_px.0 - w - gap).max(gap);
+    // Below the text, or above it when there's no room.
+    let below = offer.anchor.y + offer.anchor.h + gap;
+    let y = if below + h <= window_px.1 {
+        below
+    } else {
+        (offer.anchor.y - gap - h).max(gap)
+    };
+
+    let dp = px(1.0);
+    let tip = Box(Modifier::new()
+        .absolute()
+        .offset_left(x / dp)
+        .offset_top(y / dp)
+        .size(w / dp, h / dp)
+        .background(th.surface)
+        .border(1.0, th.outline, 4.0)
+        .clip_rounded(4.0)
+        .padding_values(repose_core::PaddingValues {
+            left: PAD_X_DP,
+            right: PAD_X_DP,
+            top: PAD_Y_DP,
+            bottom: PAD_Y_DP,
+        }))
+    .child(
+        Column(Modifier::new()).with_children(
+            lines
+                .into_iter()
+                .map(|ln| {
+                    Text(ln)
+                        .size(FONT_DP)
+                        .single_line()
+                        .overflow_visible()
+                        .color(th.on_surface)
+                })
+                .collect::<Vec<View>>(),
+        ),
+    );
+    show_layer("tooltip", LayerOptions::default().z(TOOLTIP_Z), tip);
+}
+
+/// Whether `pos` (px) is inside `rect`; `None` (no mouse) never is.
+fn pointer_in(pos: Option<Vec2>, rect: Rect) -> bool {
+    pos.is_some_and(|p| rect.contains(p))
+}
@@ -0,0 +1,193 @@
+//! # Scroll walk cache
+//!
+//! A scroll container with `Modifier::walk_cache(key)` keeps what walking its children
+//! produced (scene nodes, hit regions, semantics and tooltip views). On later passes with the
+//! same key, viewport size, density, text scale, theme and opacity, that output is shifted to
+//! the new scroll offset instead of walking the children again. This saves the CPU walk only:
+//! the replayed nodes are batched and drawn by the renderer like any others.
+//!
+//! The children are walked again when something inside is focused or pressed, when the
+//! hovered view inside changes, while a text diagnostics menu is open, and while paint
+//! costs are tracked. Content that pushes transforms isn't kept, as moving it would mean
+//! rewriting them.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+
+use repose_core::{HitRegion, Rect, Scene, SceneNode, SemNode, Theme, locals};
+
+use crate::{Interactions, text_diagnostics, tooltip};
+
+/// Passes a cached walk can go unused before it's dropped.
+const KEEP_PASSES: u64 = 120;
+
+/// What the cached output depends on besides the key.
+#[derive(Clone, Copy, PartialEq)]
+struct Inputs {
+    viewport: (f32, f32),
+    px_per_dp: f32,
+    text_scale: f32,
+    theme: Theme,
+    alpha: f32,
+}
+
+struct Walk {
+    inputs: Inputs,
+    /// Where the children were placed (px) when captured.
+    origin: (f32, f32),
+    nodes: Vec<SceneNode>,
+    hits: Vec<HitRegion>,
+    sems: Vec<SemNode>,
+    tooltips: Vec<tooltip::Offer>,
+    ids: HashSet<u64>,
+    /// The hovered view when captured, if it was inside.
+    hover: Option<u64>,
+    last_pass: u64,
+}
+
+impl Walk {
+    fn still_good(
+        &self,
+        inputs: &Inputs,
+        interactions: &Interactions,
+        focused: Option<u64>,
+    ) -> bool {
+        self.inputs == *inputs
+            && focused.is_none_or(|f| !self.ids.contains(&f))
+            && !interactions.pressed.iter().any(|p| self.ids.contains(p))
+            && interactions.hover.filter(|h| self.ids.contains(h)) == self.hover
+    }
+}
+
+thread_local! {
+    static WALKS: RefCell<HashMap<u64, Walk>> = RefCell::new(HashMap::new());
+    static PASS: Cell<u64> = const { Cell::new(0) };
+}
+
+pub(crate) fn begin_pass() {
+    let pass = PASS.get() + 1;
+    PASS.set(pass);
+    WALKS.with(|l| {
+        l.borrow_mut()
+            .retain(|_, walk| pass - walk.last_pass <= KEEP_PASSES)
+    });
+}
+
+/// Where a cached container's children go this pass.
+pub(crate) struct Placement {
+    pub viewport: Rect,
+    /// Offset (px) the children are placed at, scroll included.
+    pub origin: (f32, f32),
+    pub alpha: f32,
+}
+
+/// Paints a cached scroll container's children: replays the walk kept for `key` when it's
+/// still good, otherwise runs `paint` and keeps what it adds.
+pub(crate) fn paint(
+    key: u64,
+    at: Placement,
+    interactions: &Interactions,
+    focused: Option<u64>,
+    out: (&mut Scene, &mut Vec<HitRegion>, &mut Vec<SemNode>),
+    paint: impl FnOnce(&mut Scene, &mut Vec<HitRegion>, &mut Vec<SemNode>),
+) {
+    let (scene, hits, sems) = out;
+    let inputs = Inputs {
+        viewport: (at.viewport.w, at.viewport.h),
+        px_per_dp: locals::dp_to_px(1.0),
+        text_scale: locals::text_scale().0,
+        theme: locals::theme(),
+        alpha: at.alpha,
+    };
+    let pass = PASS.get();
+    let cacheable = !repose_core::paint_cost_tracking() && !text_diagnostics::menu_open();
+
+    let replayed = cacheable
+        && WALKS.with(|l| {
+            let mut walks = l.borrow_mut();
+            let Some(walk) = walks.get_mut(&key) else {
+                return false;
+            };
+            if !walk.still_good(&inputs, interactions, focused) {
+                return false;
+            }
+            let (dx, dy) = (at.origin.0 - walk.origin.0, at.origin.1 - walk.origin.1);
+            scene.nodes.extend(walk.nodes.iter().map(|n| {
+                let mut n = n.clone();
+                shift_node(&mut n, dx, dy);
+                n
+            }));
+            hits.extend(walk.hits.iter().map(|h| HitRegion {
+                rect: shift(h.rect, dx, dy),
+                ..h.clone()
+            }));
+            sems.extend(walk.sems.iter().map(|s| SemNode {
+                rect: shift(s.rect, dx, dy),
+                ..s.clone()
+            }));
+            tooltip::replay(&walk.tooltips, interactions.pointer, dx, dy);
+            walk.last_pass = pass;
+            true
+        });
+    if replayed {
+        return;
+    }
+
+    let (first_node, first_hit, first_sem) = (scene.nodes.len(), hits.len(), sems.len());
+    let tooltips = tooltip::record(|| paint(scene, hits, sems));
+    let nodes = &scene.nodes[first_node..];
+    if !cacheable
+        || nodes
+            .iter()
+            .any(|n| matches!(n, SceneNode::PushTransform { .. }))
+    {
+        WALKS.with(|l| l.borrow_mut().remove(&key));
+        return;
+    }
+    let ids: HashSet<u64> = hits[first_hit..].iter().map(|h| h.id).collect();
+    let walk = Walk {
+        inputs,
+        origin: at.origin,
+        nodes: nodes.to_vec(),
+        hits: hits[first_hit..].to_vec(),
+        sems: sems[first_sem..].to_vec(),
+        tooltips,
+        hover: interactions.hover.filter(|h| ids.contains(h)),
+        ids,
+        last_pass: pass,
+    };
+    WALKS.with(|l| l.borrow_mut().insert(key, walk));
+}
+
+fn shift(r: Rect, dx: f32, dy: f32) -> Rect {
+    Rect {
+        x: r.x + dx,
+        y: r.y + dy,
+        ..r
+    }
+}
+
+fn shift_node(node: &mut SceneNode, dx: f32, dy: f32) {
+    match node {
+        SceneNode::Rect { rect, .. }
+        | SceneNode::Border { rect, .. }
+        | SceneNode::Text { rect, .. }
+        | SceneNode::Ellipse { rect, .. }
+        | SceneNode::EllipseBorder { rect, .. }
+        | SceneNode::Image { rect, .. }
+        | SceneNode::PushClip { rect, .. } => *rect = shift(*rect, dx, dy),
+        SceneNode::Line { from, to, .. } => {
+            for p in [from, to] {
+                p.x += dx;
+                p.y += dy;
+            }
+        }
+        SceneNode::Arc { center, .. } => {
+            center.x += dx;
+            center.y += dy;
+        }
+        // Never kept.
+        SceneNode::PushTransform { .. } => {}
+        SceneNode::PopClip | SceneNode::PopTransform => {}
+    }
+}
@@ -0,0 +1,563 @@
+//! # Zoomable canvas
+//!
+//! [`ZoomableCanvas`] pans and zooms its content: dragging pans, the mouse wheel zooms
+//! about the cursor and two fingers pinch. The content is laid out once at its natural
+//! size; the pan and zoom are applied at paint time, to its scene and its hit regions, so
+//! text is drawn at the zoomed size and handlers inside the content see pointer positions
+//! in content coordinates. Node editors and map views build on this.
+//!
+//! Positions are px. Content coordinates are where the content was laid out, relative to
+//! the canvas' top-left; view coordinates are where that ends up on screen, relative to
+//! the same corner. [`CanvasState`] converts between the two and frames the content.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use repose_core::input::{PointerEvent, PointerId};
+use repose_core::*;
+
+use crate::{Box, ViewExt};
+
+/// Wheel travel, in px, that halves or doubles the zoom.
+const WHEEL_PX_PER_DOUBLING: f32 = 500.0;
+
+/// Pan and zoom of a [`ZoomableCanvas`].
+pub struct CanvasState {
+    /// Where the content's origin lands in the canvas.
+    pan: Cell<Vec2>,
+    zoom: Cell<f32>,
+    min_zoom: f32,
+    max_zoom: f32,
+    /// The canvas on screen, as last painted.
+    viewport: Cell<Rect>,
+    /// What the content painted, in content coordinates, as last painted.
+    content: Cell<Option<Rect>>,
+}
+
+impl Default for CanvasState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CanvasState {
+    /// No pan, zoom 1, zoomable from 0.1 to 10.
+    pub fn new() -> Self {
+        Self::with_zoom_range(0.1, 10.0)
+    }
+
+    pub fn with_zoom_range(min: f32, max: f32) -> Self {
+        let min = min.max(f32::EPSILON);
+        Self {
+            pan: Cell::new(Vec2::default()),
+            zoom: Cell::new(1.0f32.clamp(min, max.max(min))),
+            min_zoom: min,
+            max_zoom: max.max(min),
+            viewport: Cell::new(Rect::default()),
+            content: Cell::new(None),
+        }
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom.get()
+    }
+
+    /// Clamped to the zoom range; the content's origin stays put.
+    pub fn set_zoom(&self, zoom: f32) {
+        self.zoom.set(zoom.clamp(self.min_zoom, self.max_zoom));
+    }
+
+    pub fn pan(&self) -> Vec2 {
+        self.pan.get()
+    }
+
+    pub fn set_pan(&self, pan: Vec2) {
+        self.pan.set(pan);
+    }
+
+    pub fn pan_by(&self, delta: Vec2) {
+        let p = self.pan.get();
+        self.pan.set(Vec2 {
+            x: p.x + delta.x,
+            y: p.y + delta.y,
+        });
+    }
+
+    /// Multiplies the zoom by `factor` (within the zoom range), keeping the content under
+    /// `anchor` (view coordinates) where it is.
+    pub fn zoom_at(&self, factor: f32, anchor: Vec2) {
+        let old = self.zoom.get();
+        self.set_zoom(old * factor);
+        let k = self.zoom.get() / old;
+        let p = self.pan.get();
+        self.pan.set(Vec2 {
+            x: anchor.x - (anchor.x - p.x) * k,
+            y: anchor.y - (anchor.y - p.y) * k,
+        });
+    }
+
+    /// The pan and zoom as a transform from content to view coordinates.
+    pub fn transform(&self) -> Transform {
+        let (p, z) = (self.pan.get(), self.zoom.get());
+        Transform {
+            translate_x: p.x,
+            translate_y: p.y,
+            scale_x: z,
+            scale_y: z,
+            rotate: 0.0,
+        }
+    }
+
+    /// A point in view coordinates, in content coordinates.
+    pub fn to_content(&self, view: Vec2) -> Vec2 {
+        let (p, z) = (self.pan.get(), self.zoom.get());
+        Vec2 {
+            x: (view.x - p.x) / z,
+            y: (view.y - p.y) / z,
+        }
+    }
+
+    /// A point in content coordinates, in view coordinates.
+    pub fn to_view(&self, content: Vec2) -> Vec2 {
+        self.transform().apply_to_point(content)
+    }
+
+    /// The canvas' size as last painted; zero before the first frame.
+    pub fn viewport(&self) -> Size {
+        let r = self.viewport.get();
+        Size {
+            width: r.w,
+            height: r.h,
+        }
+    }
+
+    /// What the content painted, in content coordinates; `None` before the first frame or
+    /// when it painted nothing.
+    pub fn content_bounds(&self) -> Option<Rect> {
+        self.content.get()
+    }
+
+    /// Zooms (within the zoom range) and pans so `rect`, in content coordinates, fits the
+    /// viewport with `padding` px to spare on each side, centred.
+    pub fn fit(&self, rect: Rect, padding: f32) {
+        let vp = self.viewport();
+        let room_w = (vp.width - 2.0 * padding).max(1.0);
+        let room_h = (vp.height - 2.0 * padding).max(1.0);
+        if rect.w > 0.0 && rect.h > 0.0 {
+            self.set_zoom((room_w / rect.w).min(room_h / rect.h));
+        }
+        let z = self.zoom.get();
+        self.pan.set(Vec2 {
+            x: vp.width / 2.0 - (rect.x + rect.w / 2.0) * z,
+            y: vp.height / 2.0 - (rect.y + rect.h / 2.0) * z,
+        });
+    }
+
+    /// [`fit`](Self::fit)s the content's bounds; does nothing until they are known.
+    pub fn fit_to_content(&self, padding: f32) {
+        if let Some(r) = self.content.get() {
+            self.fit(r, padding);
+        }
+    }
+}
+
+pub fn remember_canvas_state(key: impl Into<String>) -> Rc<CanvasState> {
+    repose_core::remember_with_key(key.into(), CanvasState::new)
+}
+
+/// Shows `content` panned and zoomed by `state`, clipped to the canvas, which fills the
+/// space it is given.
+///
+/// Content that handles the pointer itself (a draggable node, a button) takes presses
+/// before the canvas does; dragging anywhere else pans.
+pub fn ZoomableCanvas(state: Rc<CanvasState>, content: View) -> View {
+    // Pointers down on the canvas, in view coordinates.
+    let pointers = remember(|| RefCell::new(Vec::<(PointerId, Vec2)>::new()));
+    // Where the mouse last was, for zooming with the wheel.
+    let hover = remember(|| Cell::new(None::<Vec2>));
+
+    let local = {
+        let state = state.clone();
+        move |p: Vec2| {
+            let vp = state.viewport.get();
+            Vec2 {
+                x: p.x - vp.x,
+                y: p.y - vp.y,
+            }
+        }
+    };
+
+    let on_down = {
+        let (pointers, local) = (pointers.clone(), local.clone());
+        move |e: PointerEvent| {
+            // Panning goes every way, so a scroll container around the canvas mustn't take
+            // the drag once it heads along its axis.
+            request_disallow_intercept(e.id);
+            let mut ps = pointers.borrow_mut();
+            ps.retain(|(id, _)| *id != e.id);
+            ps.push((e.id, local(e.position)));
+        }
+    };
+    let on_move = {
+        let (state, pointers, hover) = (state.clone(), pointers.clone(), hover.clone());
+        move |e: PointerEvent| {
+            let at = local(e.position);
+            hover.set(Some(at));
+            let mut ps = pointers.borrow_mut();
+            let Some(i) = ps.iter().position(|(id, _)| *id == e.id) else {
+                return;
+            };
+            // Pan with the pointers' centre; with two or more, zoom with their spread.
+            let (mid0, spread0) = centre_and_spread(&ps);
+            ps[i].1 = at;
+            let (mid1, spread1) = centre_and_spread(&ps);
+            state.pan_by(Vec2 {
+                x: mid1.x - mid0.x,
+                y: mid1.y - mid0.y,
+            });
+            if ps.len() > 1 && spread0 > 0.0 {
+                state.zoom_at(spread1 / spread0, mid1);
+            }
+        }
+    };
+    // The remaining fingers carry on panning from where they are.
+    let on_up = {
+        let pointers = pointers.clone();
+        move |e: PointerEvent| pointers.borrow_mut().retain(|(id, _)| *id != e.id)
+    };
+    let on_scroll = {
+        let state = state.clone();
+        move |delta: Vec2| {
+            // Only the wheel zooms; while pointers are down they pan.
+            if pointers.borrow().is_empty()
+                && let Some(at) = hover.get()
+            {
+                state.zoom_at((-delta.y / WHEEL_PX_PER_DOUBLING).exp2(), at);
+            }
+            Vec2::default()
+        }
+    };
+    let painter = {
+        let state = state.clone();
+        move |_: &mut Scene, rect: Rect| state.viewport.set(rect)
+    };
+    let bounds = {
+        let state = state.clone();
+        move |r: Rect| state.content.set(Some(r))
+    };
+
+    Box(Modifier::new()
+        .fill_max_size()
+        .clip_rounded(0.0)
+        .painter(painter)
+        .on_pointer_down(on_down)
+        .on_pointer_move(on_move)
+        .on_pointer_up(on_up)
+        .on_scroll(on_scroll)
+        .content_transform(state.transform())
+        .on_content_bounds(bounds))
+    .child(content)
+}
+
+fn centre_and_spread(pointers: &[(PointerId, Vec2)]) -> (Vec2, f32) {
+    if pointers.is_empty() {
+        return (Vec2::default(), 0.0);
+    }
+    let n = pointers.len() as f32;
+    let (sx, sy) = pointers
+        .iter()
+        .fold((0.0, 0.0), |(x, y), (_, p)| (x + p.x, y + p.y));
+    let mid = Vec2 {
+        x: sx / n,
+        y: sy / n,
+    };
+    let spread = pointers
+        .iter()
+        .map(|(_, p)| (p.x - mid.x).hypot(p.y - mid.y))
+        .sum::<f32>()
+        / n;
+    (mid, spread)
+}
+
+/// A node's `content_transform`, applied to what its children painted. `p' = p * scale +
+/// offset` for screen points `p`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ContentMap {
+    scale: Vec2,
+    offset: Vec2,
+}
+
+impl ContentMap {
+    /// For children laid out with their origin at `origin` on screen.
+    pub(crate) fn new(origin: Vec2, t: Transform) -> Self {
+        Self {
+            scale: Vec2 {
+                x: t.scale_x,
+                y: t.scale_y,
+            },
+            offset: Vec2 {
+                x: origin.x + t.translate_x - origin.x * t.scale_x,
+                y: origin.y + t.translate_y - origin.y * t.scale_y,
+            },
+        }
+    }
+
+    fn point(&self, p: Vec2) -> Vec2 {
+        Vec2 {
+            x: p.x * self.scale.x + self.offset.x,
+            y: p.y * self.scale.y + self.offset.y,
+        }
+    }
+
+    fn unpoint(&self, p: Vec2) -> Vec2 {
+        Vec2 {
+            x: (p.x - self.offset.x) / self.scale.x,
+            y: (p.y - self.offset.y) / self.scale.y,
+        }
+    }
+
+    pub(crate) fn rect(&self, r: Rect) -> Rect {
+        let p = self.point(Vec2 { x: r.x, y: r.y });
+        Rect {
+            x: p.x,
+            y: p.y,
+            w: r.w * self.scale.x,
+            h: r.h * self.scale.y,
+        }
+    }
+
+    /// Scale for lengths that aren't along an axis: radii, stroke widths.
+    fn len(&self, l: f32) -> f32 {
+        l * self.scale.x.min(self.scale.y)
+    }
+
+    pub(crate) fn node(&self, node: &mut SceneNode) {
+        match node {
+            SceneNode::Rect { rect, radius, .. } | SceneNode::PushClip { rect, radius } => {
+                *rect = self.rect(*rect);
+                *radius = self.len(*radius);
+            }
+            SceneNode::Border {
+                rect,
+                width,
+                radius,
+                ..
+            } => {
+                *rect = self.rect(*rect);
+                *width = self.len(*width);
+                *radius = self.len(*radius);
+            }
+            SceneNode::Text {
+                rect,
+                size,
+                letter_spacing,
+                ..
+            } => {
+                *rect = self.rect(*rect);
+                *size *= self.scale.y;
+                *letter_spacing *= self.scale.x;
+            }
+            SceneNode::Ellipse { rect, .. } | SceneNode::Image { rect, .. } => {
+                *rect = self.rect(*rect);
+            }
+            SceneNode::EllipseBorder { rect, width, .. } => {
+                *rect = self.rect(*rect);
+                *width = self.len(*width);
+            }
+            SceneNode::Line {
+                from, to, width, ..
+            } => {
+                *from = self.point(*from);
+                *to = self.point(*to);
+                *width = self.len(*width);
+            }
+            SceneNode::Arc {
+                center,
+                radius,
+                inner_radius,
+                ..
+            } => {
+                *center = self.point(*center);
+                *radius = self.len(*radius);
+                *inner_radius = self.len(*inner_radius);
+            }
+            // A transform inside the content now acts on mapped points: `T'(M(p))` has to
+            // land on `M(T(p))`, which for `T(p) = L(p) + t` needs `t' = M(t) - L(offset)`.
+            SceneNode::PushTransform { transform } => {
+                let t = Vec2 {
+                    x: transform.translate_x * self.scale.x,
+                    y: transform.translate_y * self.scale.y,
+                };
+                let linear = Transform {
+                    translate_x: 0.0,
+                    translate_y: 0.0,
+                    ..*transform
+                }
+                .apply_to_point(self.offset);
+                transform.translate_x = t.x + self.offset.x - linear.x;
+                transform.translate_y = t.y + self.offset.y - linear.y;
+            }
+            SceneNode::PopClip | SceneNode::PopTransform => {}
+        }
+    }
+
+    /// Makes `hit`'s handlers see positions (and scroll deltas) as if nothing were zoomed.
+    /// Leaves the rect alone.
+    pub(crate) fn hit(&self, hit: &mut HitRegion) {
+        let m = *self;
+        let wrap = |f: &mut Option<Rc<dyn Fn(PointerEvent)>>| {
+            if let Some(cb) = f.take() {
+                *f = Some(Rc::new(move |mut e: PointerEvent| {
+                    e.position = m.unpoint(e.position);
+                    cb(e)
+                }));
+            }
+        };
+        wrap(&mut hit.on_pointer_down);
+        wrap(&mut hit.on_pointer_move);
+        wrap(&mut hit.on_pointer_up);
+        wrap(&mut hit.on_pointer_enter);
+        wrap(&mut hit.on_pointer_leave);
+        if let Some(cb) = hit.on_scroll.take() {
+            hit.on_scroll = Some(Rc::new(move |d: Vec2| {
+                let left = cb(Vec2 {
+                    x: d.x / m.scale.x,
+                    y: d.y / m.scale.y,
+                });
+                Vec2 {
+                    x: left.x * m.scale.x,
+                    y: left.y * m.scale.y,
+                }
+            }));
+        }
+    }
+}
+
+/// The area `nodes` paint, ignoring clips and transforms.
+pub(crate) fn painted_bounds(nodes: &[SceneNode]) -> Option<Rect> {
+    nodes
+        .iter()
+        .filter_map(|n| match n {
+            SceneNode::Rect { rect, .. }
+            | SceneNode::Border { rect, .. }
+            | SceneNode::Text { rect, .. }
+            | SceneNode::Ellipse { rect, .. }
+            | SceneNode::EllipseBorder { rect, .. }
+            | SceneNode::Image { rect, .. } => Some(*rect),
+            SceneNode::Line {
+                from, to, width, ..
+            } => {
+                let h = width / 2.0;
+                let (x, y) = (from.x.min(to.x) - h, from.y.min(to.y) - h);
+                Some(Rect {
+                    x,
+                    y,
+                    w: from.x.max(to.x) + h - x,
+                    h: from.y.max(to.y) + h - y,
+                })
+            }
+            SceneNode::Arc { center, radius, .. } => Some(Rect {
+                x: center.x - radius,
+                y: center.y - radius,
+                w: 2.0 * radius,
+                h: 2.0 * radius,
+            }),
+            SceneNode::PushClip { .. }
+            | SceneNode::PopClip
+            | SceneNode::PushTransform { .. }
+            | SceneNode::PopTransform => None,
+        })
+        .reduce(|a, b| {
+            let (x, y) = (a.x.min(b.x), a.y.min(b.y));
+            Rect {
+                x,
+                y,
+                w: (a.x + a.w).max(b.x + b.w) - x,
+                h: (a.y + a.h).max(b.y + b.h) - y,
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(w: f32, h: f32) -> CanvasState {
+        let s = CanvasState::new();
+        s.viewport.set(Rect {
+            x: 0.0,
+            y: 0.0,
+            w,
+            h,
+        });
+        s
+    }
+
+    #[test]
+    fn zooming_at_a_point_keeps_it_in_place() {
+        let s = state(400.0, 300.0);
+        s.set_pan(Vec2 { x: 30.0, y: -10.0 });
+        let anchor = Vec2 { x: 120.0, y: 80.0 };
+        let under = s.to_content(anchor);
+
+        s.zoom_at(2.5, anchor);
+        assert_eq!(s.zoom(), 2.5);
+        let back = s.to_view(under);
+        assert!((back.x - anchor.x).abs() < 1e-3 && (back.y - anchor.y).abs() < 1e-3);
+
+        // Clamped to the range, still about the anchor.
+        s.zoom_at(100.0, anchor);
+        assert_eq!(s.zoom(), 10.0);
+        let back = s.to_view(under);
+        assert!((back.x - anchor.x).abs() < 1e-2 && (back.y - anchor.y).abs() < 1e-2);
+    }
+
+    #[test]
+    fn fit_centres_the_rect_at_the_largest_zoom_that_shows_it() {
+        let s = state(400.0, 300.0);
+        s.fit(
+            Rect {
+                x: 100.0,
+                y: 100.0,
+                w: 180.0,
+                h: 50.0,
+            },
+            20.0,
+        );
+        // Width-bound: 360 px of room for 180 px of content.
+        assert_eq!(s.zoom(), 2.0);
+        let c = s.to_view(Vec2 { x: 190.0, y: 125.0 });
+        assert!((c.x - 200.0).abs() < 1e-3 && (c.y - 150.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn inner_transforms_compose_with_the_content_map() {
+        let m = ContentMap::new(
+            Vec2 { x: 50.0, y: 20.0 },
+            Transform {
+                translate_x: 5.0,
+                translate_y: 7.0,
+                scale_x: 3.0,
+                scale_y: 3.0,
+                rotate: 0.0,
+            },
+        );
+        let inner = Transform {
+            translate_x: 11.0,
+            translate_y: -4.0,
+            scale_x: 0.5,
+            scale_y: 0.5,
+            rotate: 0.3,
+        };
+        let mut node = SceneNode::PushTransform { transform: inner };
+        m.node(&mut node);
+        let SceneNode::PushTransform { transform: mapped } = node else {
+            unreachable!()
+        };
+
+        let p = Vec2 { x: 70.0, y: 45.0 };
+        let want = m.point(inner.apply_to_point(p));
+        let got = mapped.apply_to_point(m.point(p));
+        assert!((want.x - got.x).abs() < 1e-3 && (want.y - got.y).abs() < 1e-3);
+    }
+}
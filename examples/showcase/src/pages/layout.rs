@@ -13,10 +13,10 @@ pub fn screen() -> View {
                 (0..6)
                     .map(|i| {
                         Box(Modifier::new()
-                            .padding(8.0)
                             .background(theme().surface)
                             .border(1.0, theme().outline, 10.0)
-                            .clip_rounded(10.0))
+                            .clip_rounded(10.0)
+                            .padding(8.0))
                         .child(
                             Text(format!("Item {}", i + 1)).modifier(Modifier::new().padding(12.0)),
                         )
@@ -34,9 +34,9 @@ pub fn screen() -> View {
             let th = theme();
             let done_tint = Color(th.primary.0, th.primary.1, th.primary.2, 48);
             Row(Modifier::new()
-                .padding(12.0)
                 .background(if it.done { done_tint } else { th.surface })
-                .border(1.0, th.outline, 0.0))
+                .border(1.0, th.outline, 0.0)
+                .padding(12.0))
             .child((
                 Text(if it.done { "✓" } else { "○" }).modifier(Modifier::new().padding(8.0)),
                 Text(it.title).modifier(Modifier::new().padding(4.0)),
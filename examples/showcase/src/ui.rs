@@ -57,9 +57,9 @@ pub fn TopBar(
     let th = theme();
 
     Row(Modifier::new()
-        .padding(12.0)
         .background(th.surface)
-        .border(1.0, th.outline, 0.0))
+        .border(1.0, th.outline, 0.0)
+        .padding(12.0))
     .child((
         Text("Repose Showcase").size(18.0).color(th.on_surface),
         Spacer(),
@@ -138,9 +138,9 @@ fn NavItem(route: Route, selected: bool, on_click: impl Fn() + 'static) -> View
         Modifier::new()
             .key(route.id()) // stable identity for nav items
             .fill_max_width()
-            .padding(6.0)
             .background(bg)
-            .clip_rounded(8.0),
+            .clip_rounded(8.0)
+            .padding(6.0),
     )
 }
 